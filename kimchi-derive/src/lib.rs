@@ -0,0 +1,107 @@
+//! Derive macros for the kimchi proof system.
+//!
+//! These macros are re-exported by the `kimchi` crate, see `kimchi::circuits::public_input`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Index};
+
+/// Derives `kimchi::circuits::public_input::PublicInput` for a struct,
+/// by laying out its fields one after the other, in declaration order.
+///
+/// Every field must itself implement `PublicInput`, for example
+/// a native field element, a foreign field element, a point or an array of those.
+#[proc_macro_derive(PublicInput)]
+pub fn derive_public_input(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_public_input(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_public_input(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "PublicInput can only be derived for structs",
+            ))
+        }
+    };
+
+    let krate = quote!(::kimchi::circuits::public_input);
+    let field = format_ident!("__F");
+
+    // the implementation is generic over the native field,
+    // and requires every field of the struct to be a public input over it
+    let mut generics = input.generics.clone();
+    generics.params.push(parse_quote!(#field));
+    {
+        let where_clause = generics.make_where_clause();
+        for f in fields {
+            let ty = &f.ty;
+            where_clause
+                .predicates
+                .push(parse_quote!(#ty: #krate::PublicInput<#field>));
+        }
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let name = &input.ident;
+
+    let members: Vec<TokenStream2> = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| match &f.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = Index::from(i);
+                quote!(#index)
+            }
+        })
+        .collect();
+    let sizes: Vec<TokenStream2> = fields
+        .iter()
+        .map(|f| {
+            let ty = &f.ty;
+            quote!(<#ty as #krate::PublicInput<#field>>::SIZE)
+        })
+        .collect();
+    let reads = fields.iter().enumerate().map(|(i, f)| {
+        let ty = &f.ty;
+        let offset = &sizes[..i];
+        quote!(<#ty as #krate::PublicInput<#field>>::read_public_input(&public[0 #(+ #offset)*..])?)
+    });
+    let writes = fields.iter().zip(&members).map(|(f, member)| {
+        let ty = &f.ty;
+        quote!(<#ty as #krate::PublicInput<#field>>::write_public_input(&self.#member, public);)
+    });
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics #krate::PublicInput<#field> for #name #ty_generics #where_clause {
+            const SIZE: usize = 0 #(+ #sizes)*;
+
+            #[allow(unused_variables)]
+            fn write_public_input(&self, public: &mut ::std::vec::Vec<#field>) {
+                #(#writes)*
+            }
+
+            fn read_public_input(
+                public: &[#field],
+            ) -> ::core::result::Result<Self, #krate::PublicInputError> {
+                if public.len() < <Self as #krate::PublicInput<#field>>::SIZE {
+                    return ::core::result::Result::Err(#krate::PublicInputError::TooShort {
+                        expected: <Self as #krate::PublicInput<#field>>::SIZE,
+                        got: public.len(),
+                    });
+                }
+                ::core::result::Result::Ok(Self {
+                    #(#members: #reads,)*
+                })
+            }
+        }
+    })
+}
@@ -0,0 +1,22 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, SamplingMode};
+use kimchi::bench::ForeignFieldExprCtx;
+
+pub fn bench_expr_cse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Foreign field constraints");
+    group.sample_size(10).sampling_mode(SamplingMode::Flat); // for slow benchmarks
+
+    for srs_size_log2 in [10, 14] {
+        let ctx = ForeignFieldExprCtx::new(srs_size_log2);
+        group.bench_function(
+            format!("separate evaluations ({} rows)", ctx.num_rows()),
+            |b| b.iter(|| black_box(ctx.evaluate_separately())),
+        );
+        group.bench_function(
+            format!("combined evaluations ({} rows)", ctx.num_rows()),
+            |b| b.iter(|| black_box(ctx.evaluate_combined())),
+        );
+    }
+}
+
+criterion_group!(benches, bench_expr_cse);
+criterion_main!(benches);
@@ -19,6 +19,13 @@
 //! For this to work, we use the type [Alphas] to register ranges of powers of alpha,
 //! for the various [ArgumentType]s.
 //!
+//! The allocation is part of the protocol, as external verifiers must combine the constraints
+//! with the same powers of alpha, and it is kept stable across releases:
+//! the gates share the first powers of alpha (as many as the gate with the most constraints),
+//! followed by the permutation argument and then by the lookup argument, if any.
+//! It can be inspected with [Alphas::exponents], and with
+//! [crate::linearization::gate_alpha_exponents] for the constraints of each gate.
+//!
 
 use crate::circuits::{argument::ArgumentType, gate::GateType};
 use ark_ff::Field;
@@ -112,6 +119,25 @@ impl<F: Field> Alphas<F> {
         }
     }
 
+    /// Returns the number of powers of alpha registered, that is the exponent following the last one used.
+    pub fn num_powers(&self) -> u32 {
+        self.next_power
+    }
+
+    /// Returns the exponents of the powers of alpha used by each registered [ArgumentType],
+    /// ordered by their first exponent.
+    /// The gates are all registered as `ArgumentType::Gate(GateType::Zero)`,
+    /// a gate with `k` constraints using the first `k` exponents of their range.
+    pub fn exponents(&self) -> Vec<(ArgumentType, Range<u32>)> {
+        let mut exponents: Vec<_> = self
+            .mapping
+            .iter()
+            .map(|(ty, (start, powers))| (*ty, *start..start + powers))
+            .collect();
+        exponents.sort_by_key(|(_, range)| range.start);
+        exponents
+    }
+
     /// Instantiates the ranges with an actual field element `alpha`.
     /// Once you call this function, you cannot register new constraints via [Self::register].
     pub fn instantiate(&mut self, alpha: F) {
@@ -171,8 +197,12 @@ impl<T> Display for Alphas<T> {
         for arg in [
             ArgumentType::Gate(GateType::Zero),
             ArgumentType::Permutation,
-            //            ArgumentType::Lookup,
+            ArgumentType::Lookup,
         ] {
+            // the lookup argument is optional
+            if arg == ArgumentType::Lookup && !self.mapping.contains_key(&arg) {
+                continue;
+            }
             let name = if matches!(arg, ArgumentType::Gate(_)) {
                 "gates".to_string()
             } else {
@@ -311,11 +341,82 @@ mod tests {
         assert_eq!(alphas.next(), Some(8.into()));
     }
 
+    #[test]
+    fn exponents_of_registered_arguments() {
+        let mut alphas = Alphas::<Fp>::default();
+        alphas.register(ArgumentType::Gate(GateType::Poseidon), 4);
+        alphas.register(ArgumentType::Permutation, 3);
+        alphas.register(ArgumentType::Lookup, 2);
+
+        assert_eq!(alphas.num_powers(), 9);
+        assert_eq!(
+            alphas.exponents(),
+            vec![
+                (ArgumentType::Gate(GateType::Zero), 0..4),
+                (ArgumentType::Permutation, 4..7),
+                (ArgumentType::Lookup, 7..9),
+            ]
+        );
+    }
+
+    // the allocation of the powers of alpha is part of the protocol, it must not change across releases
+
+    #[test]
+    fn stable_alpha_allocation() {
+        let (_, powers_of_alpha) = constraints_expr::<Fp>(None, true, &[]);
+        let exponents = powers_of_alpha.exponents();
+        assert_eq!(exponents[0], (ArgumentType::Gate(GateType::Zero), 0..21));
+        assert_eq!(exponents[1], (ArgumentType::Permutation, 21..24));
+        assert_eq!(exponents[2].0, ArgumentType::Lookup);
+        assert_eq!(exponents[2].1.start, 24);
+        assert_eq!(exponents.len(), 3);
+
+        use GateType::*;
+        assert_eq!(
+            gate_alpha_exponents::<Fp>(None),
+            vec![
+                (Generic, 0..2),
+                (Poseidon, 0..15),
+                (CompleteAdd, 0..7),
+                (VarBaseMul, 0..21),
+                (EndoMul, 0..11),
+                (EndoMulScalar, 0..11),
+                (RangeCheck0, 0..10),
+                (RangeCheck1, 0..21),
+                (ForeignFieldAdd, 0..4),
+                (ForeignFieldMul, 0..9),
+                (Xor16, 0..3),
+                (Rot64, 0..11),
+                (AddU64, 0..3),
+                (Select, 0..3),
+            ]
+        );
+
+        // the optional gates are only listed when enabled
+        let gates = vec![CircuitGate::<Fp>::zero(Wire::for_row(0)); 2];
+        let index = new_index_for_test::<Vesta>(gates, 0);
+        let gate_types: Vec<_> = gate_alpha_exponents::<Fp>(Some(&index.cs.feature_flags))
+            .into_iter()
+            .map(|(gate_type, _)| gate_type)
+            .collect();
+        assert_eq!(
+            gate_types,
+            vec![
+                Generic,
+                Poseidon,
+                CompleteAdd,
+                VarBaseMul,
+                EndoMul,
+                EndoMulScalar
+            ]
+        );
+    }
+
     // useful for the spec
 
     use crate::{
         circuits::{gate::CircuitGate, wires::Wire},
-        linearization::expr_linearization,
+        linearization::{constraints_expr, expr_linearization, gate_alpha_exponents},
         prover_index::testing::new_index_for_test,
     };
 
@@ -324,7 +425,7 @@ mod tests {
         let gates = vec![CircuitGate::<Fp>::zero(Wire::for_row(0)); 2];
         let index = new_index_for_test::<Vesta>(gates, 0);
         let (_linearization, powers_of_alpha) =
-            expr_linearization::<Fp>(Some(&index.cs.feature_flags), true);
+            expr_linearization::<Fp>(Some(&index.cs.feature_flags), true, &[]);
         // make sure this is present in the specification
         let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
         let spec_path = Path::new(&manifest_dir)
@@ -1,20 +1,31 @@
-use std::array;
+use std::{array, collections::HashMap};
 
+use ark_ff::Zero;
+use ark_poly::{univariate::DensePolynomial, Evaluations, Radix2EvaluationDomain as D};
 use groupmap::{BWParameters, GroupMap};
-use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_curves::pasta::{Fp, Fq, Vesta, VestaParameters};
 use mina_poseidon::{
     constants::PlonkSpongeConstantsKimchi,
     sponge::{DefaultFqSponge, DefaultFrSponge},
 };
-use o1_utils::math;
+use o1_utils::{math, FieldHelpers};
 use poly_commitment::commitment::CommitmentCurve;
 
 use crate::{
     circuits::{
-        gate::CircuitGate,
-        polynomials::generic::GenericGateSpec,
+        argument::Argument,
+        expr::{l0_1, Cache, Constants, Environment, E},
+        gate::{CircuitGate, GateType},
+        polynomial::WitnessOverDomains,
+        polynomials::{
+            foreign_field_add::{circuitgates::ForeignFieldAdd, witness::FFOps},
+            foreign_field_mul::circuitgates::ForeignFieldMul,
+            generic::GenericGateSpec,
+            range_check::circuitgates::{RangeCheck0, RangeCheck1},
+        },
         wires::{Wire, COLUMNS},
     },
+    curve::KimchiCurve,
     proof::ProverProof,
     prover_index::{testing::new_index_for_test, ProverIndex},
     verifier::{batch_verify, Context},
@@ -110,6 +121,138 @@ impl BenchmarkCtx {
     }
 }
 
+/// A context to benchmark the evaluation of the constraints of the foreign field gates
+/// over the domain of a circuit, as the prover computes the quotient polynomial,
+/// with or without the extraction of their common subexpressions.
+pub struct ForeignFieldExprCtx {
+    index: ProverIndex<Vesta>,
+    evaluations: WitnessOverDomains<Fp>,
+    constraints: Vec<E<Fp>>,
+}
+
+impl ForeignFieldExprCtx {
+    /// This will create a context for a circuit of foreign field multiplications, additions and range checks
+    /// filling a domain of size `2^srs_size_log2`.
+    pub fn new(srs_size_log2: u32) -> Self {
+        let foreign_field_modulus = Fq::modulus_biguint();
+
+        // create the circuit, repeating a multiplication, an addition and a multi-range-check
+        let num_rows = (1 << srs_size_log2) - 10;
+        let mut gates = vec![];
+        while gates.len() + 8 <= num_rows {
+            let (_, mut mul) =
+                CircuitGate::create_foreign_field_mul(gates.len(), &foreign_field_modulus);
+            gates.append(&mut mul);
+            let (_, mut add) =
+                CircuitGate::create_single_ffadd(gates.len(), FFOps::Add, &foreign_field_modulus);
+            gates.append(&mut add);
+            let (_, mut range_check) = CircuitGate::create_multi_range_check(gates.len());
+            gates.append(&mut range_check);
+        }
+        let index = new_index_for_test(gates, 0);
+
+        // the values of the witness do not matter for the benchmark
+        let witness: [_; COLUMNS] = array::from_fn(|_| DensePolynomial::zero());
+        let evaluations = index.cs.evaluate(&witness, &DensePolynomial::zero());
+
+        let mut cache = Cache::default();
+        let alphas = &index.powers_of_alpha;
+        let constraints = vec![
+            ForeignFieldMul::combined_constraints(alphas, &mut cache),
+            ForeignFieldAdd::combined_constraints(alphas, &mut cache),
+            RangeCheck0::combined_constraints(alphas, &mut cache),
+            RangeCheck1::combined_constraints(alphas, &mut cache),
+        ];
+
+        ForeignFieldExprCtx {
+            index,
+            evaluations,
+            constraints,
+        }
+    }
+
+    fn env(&self) -> Environment<'_, Fp> {
+        let column_evaluations = &self.index.column_evaluations;
+        let mut index = HashMap::new();
+        index.insert(
+            GateType::ForeignFieldMul,
+            column_evaluations
+                .foreign_field_mul_selector8
+                .as_ref()
+                .unwrap(),
+        );
+        index.insert(
+            GateType::ForeignFieldAdd,
+            column_evaluations
+                .foreign_field_add_selector8
+                .as_ref()
+                .unwrap(),
+        );
+        index.insert(
+            GateType::RangeCheck0,
+            column_evaluations.range_check0_selector8.as_ref().unwrap(),
+        );
+        index.insert(
+            GateType::RangeCheck1,
+            column_evaluations.range_check1_selector8.as_ref().unwrap(),
+        );
+
+        Environment {
+            constants: Constants {
+                alpha: Fp::from(2u64),
+                beta: Fp::from(3u64),
+                gamma: Fp::from(5u64),
+                joint_combiner: None,
+                endo_coefficient: self.index.cs.endo,
+                mds: &Vesta::sponge_params().mds,
+            },
+            witness: &self.evaluations.d8.this.w,
+            coefficient: &column_evaluations.coefficients8,
+            vanishes_on_last_4_rows: &self.index.cs.precomputations().vanishes_on_last_4_rows,
+            z: &self.evaluations.d8.this.z,
+            l0_1: l0_1(self.index.cs.domain.d1),
+            domain: self.index.cs.domain,
+            index,
+            custom_index: vec![],
+            lookup: None,
+        }
+    }
+
+    /// The number of rows of the circuit
+    pub fn num_rows(&self) -> usize {
+        self.index.cs.gates.len()
+    }
+
+    /// Evaluates the constraints of each gate separately
+    pub fn evaluate_separately(&self) -> Vec<Evaluations<Fp, D<Fp>>> {
+        let env = self.env();
+        self.constraints
+            .iter()
+            .map(|constraint| constraint.evaluations(&env))
+            .collect()
+    }
+
+    /// Evaluates the constraints of the gates combined by evaluation domain,
+    /// with their common subexpressions extracted, as the prover does
+    pub fn evaluate_combined(&self) -> Vec<Evaluations<Fp, D<Fp>>> {
+        let env = self.env();
+        let d1_size = self.index.cs.domain.d1.size;
+        let (constraints4, constraints8): (Vec<_>, Vec<_>) = self
+            .constraints
+            .iter()
+            .cloned()
+            .partition(|constraint| constraint.degree(d1_size) <= 4 * d1_size);
+        [constraints4, constraints8]
+            .into_iter()
+            .filter(|constraints| !constraints.is_empty())
+            .map(|constraints| {
+                let combined = constraints.into_iter().fold(E::zero(), |acc, c| acc + c);
+                combined.extract_common_subexpressions().evaluations(&env)
+            })
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Instant;
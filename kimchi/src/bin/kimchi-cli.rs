@@ -0,0 +1,41 @@
+//! Proves circuits from files and verifies the proofs, see [kimchi::cli] for the formats of the files.
+
+use kimchi::cli;
+use std::{env, path::Path, process};
+
+const USAGE: &str = "Usage:
+    kimchi-cli witness <CIRCUIT> <ASSIGNMENTS> <WITNESS>
+    kimchi-cli prove <CIRCUIT> <WITNESS> <SRS> <PROOF>
+    kimchi-cli verify <CIRCUIT> <SRS> <PROOF>
+    kimchi-cli tables <TABLES>";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let res = match args.as_slice() {
+        ["witness", circuit, assignments, witness] => cli::witness(
+            Path::new(circuit),
+            Path::new(assignments),
+            Path::new(witness),
+        ),
+        ["prove", circuit, witness, srs, proof] => cli::prove(
+            Path::new(circuit),
+            Path::new(witness),
+            Path::new(srs),
+            Path::new(proof),
+        ),
+        ["verify", circuit, srs, proof] => {
+            cli::verify_proof(Path::new(circuit), Path::new(srs), Path::new(proof))
+                .map(|()| println!("the proof is valid"))
+        }
+        ["tables", tables] => cli::tables(Path::new(tables)),
+        _ => {
+            eprintln!("{USAGE}");
+            process::exit(2);
+        }
+    };
+    if let Err(e) = res {
+        eprintln!("error: {e}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,113 @@
+//! This module implements the configuration of the blinding of the columns
+//! of the permutation and lookup arguments, as [`Blinding`].
+//!
+//! # Zero-knowledge
+//!
+//! Each column committed by the prover is opened at the two evaluation points $\zeta$ and $\zeta \omega$.
+//! A blinded column hides its values with random rows at the end of the domain, which the constraints
+//! do not cover, and with a hiding commitment:
+//!
+//! - the permutation aggregation polynomial $z$ has 2 random rows, $z(g^{n-2})$ and $z(g^{n-1})$;
+//! - the lookup columns, the sorted columns (or the multiplicities with logUp),
+//!   the lookup aggregation polynomial and the runtime table column,
+//!   have [`ZK_ROWS`] random rows.
+//!
+//! As there are at least as many random rows as evaluation points, the evaluations of a blinded column
+//! are uniformly distributed and independent of its other values, and its commitment is uniformly distributed.
+//! The proof then reveals nothing on the witness through the lookup columns:
+//! the values and indices of the lookups, and the contents of the runtime tables, stay secret,
+//! as the witness columns (which are always blinded).
+//!
+//! The blinding of a family of columns can be disabled in the [`ProverIndex`](crate::prover_index::ProverIndex),
+//! when its values are public (for instance to prove a public computation reproducibly):
+//! its random rows are then set to zero and its commitments are not hiding.
+//! Warning: the proofs are then not zero-knowledge, as the commitments are a deterministic
+//! function of the columns, and their evaluations reveal linear relations between the values of the columns.
+
+use crate::circuits::lookup::constraints::{zk_patch, ZK_ROWS};
+use ark_ff::{FftField, Zero};
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain as D};
+use poly_commitment::{
+    commitment::{BlindedCommitment, CommitmentCurve, PolyComm},
+    srs::SRS,
+};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+/// The blinding of the columns of the permutation and lookup arguments (see the module)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Blinding {
+    /// Whether the permutation aggregation polynomial is blinded
+    pub permutation: bool,
+    /// Whether the lookup columns (sorted columns or multiplicities, aggregation and runtime table) are blinded
+    pub lookup: bool,
+}
+
+impl Blinding {
+    /// The blinding of all the columns, for zero-knowledge proofs
+    pub const fn zero_knowledge() -> Self {
+        Blinding {
+            permutation: true,
+            lookup: true,
+        }
+    }
+
+    /// No blinding of the columns of the permutation and lookup arguments,
+    /// whose proofs are then not zero-knowledge
+    pub const fn none() -> Self {
+        Blinding {
+            permutation: false,
+            lookup: false,
+        }
+    }
+}
+
+impl Default for Blinding {
+    fn default() -> Self {
+        Self::zero_knowledge()
+    }
+}
+
+// Hides a commitment when its column is blinded, or returns it with zero blinders
+pub(crate) fn mask<G: CommitmentCurve>(
+    srs: &SRS<G>,
+    comm: PolyComm<G>,
+    blinded: bool,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> BlindedCommitment<G> {
+    if blinded {
+        srs.mask(comm, rng)
+    } else {
+        BlindedCommitment {
+            blinders: comm.map(|_| G::ScalarField::zero()),
+            commitment: comm,
+        }
+    }
+}
+
+// Pads the evaluations of a lookup column with zeroes up to the size of the domain,
+// with random zero-knowledge rows when the column is blinded (see [zk_patch])
+pub(crate) fn patch<F: FftField>(
+    mut evals: Vec<F>,
+    d: D<F>,
+    blinded: bool,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Evaluations<F, D<F>> {
+    if blinded {
+        return zk_patch(evals, d, rng);
+    }
+    let n = d.size();
+    assert!(evals.len() <= n - ZK_ROWS);
+    evals.resize(n, F::zero());
+    Evaluations::<F, D<F>>::from_vec_and_domain(evals, d)
+}
+
+// Sets the random zero-knowledge rows of a lookup column to zero, when the column is not blinded
+pub(crate) fn unpatch<F: FftField>(evals: &mut Evaluations<F, D<F>>, blinded: bool) {
+    if !blinded {
+        let n = evals.evals.len();
+        for e in &mut evals.evals[n - ZK_ROWS..] {
+            *e = F::zero();
+        }
+    }
+}
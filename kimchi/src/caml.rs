@@ -0,0 +1,56 @@
+//! This module is the stable API of the OCaml bindings: the types exchanged with OCaml, per version.
+//!
+//! The bindings import the types of a version module, such as [v1], rather than the `caml` modules
+//! next to the internal types, which change with them.
+//! The types of a version keep their shapes (checked by the compatibility tests of the version):
+//! a change of the fields of a type, or of the constructors of an enum such as [GateType],
+//! comes with a new version module, while the previous versions keep converting from and to
+//! the internal types, until the bindings have moved to the new version.
+//!
+//! Note: OCaml identifies the constructors of [GateType] by their position,
+//! so the new gate types must be added after the gate types of the latest version.
+
+use crate::circuits::gate::GateType;
+
+/// The latest version of the API of the OCaml bindings
+pub const CAML_API_VERSION: u32 = 1;
+
+/// The first version of the API of the OCaml bindings
+pub mod v1 {
+    use super::GateType;
+
+    pub use crate::{
+        circuits::{
+            gate::caml::CamlCircuitGate, scalars::caml::CamlRandomOracles, wires::caml::CamlWire,
+        },
+        oracles::caml::{create_caml_oracles, CamlOracles},
+        proof::caml::{CamlLookupEvaluations, CamlProofEvaluations, CamlRecursionChallenge},
+        prover::caml::{CamlLookupCommitments, CamlProverCommitments, CamlProverProof},
+    };
+
+    /// The gate types of the version, in the order of their constructors in OCaml
+    pub const GATE_TYPES: [GateType; 22] = [
+        GateType::Zero,
+        GateType::Generic,
+        GateType::Poseidon,
+        GateType::CompleteAdd,
+        GateType::VarBaseMul,
+        GateType::EndoMul,
+        GateType::EndoMulScalar,
+        GateType::Lookup,
+        GateType::CairoClaim,
+        GateType::CairoInstruction,
+        GateType::CairoFlags,
+        GateType::CairoTransition,
+        GateType::RangeCheck0,
+        GateType::RangeCheck1,
+        GateType::ForeignFieldAdd,
+        GateType::ForeignFieldMul,
+        GateType::Xor16,
+        GateType::Rot64,
+        GateType::VectorLookup,
+        GateType::RangeCheckU8,
+        GateType::RangeCheckU16,
+        GateType::AddU64,
+    ];
+}
@@ -0,0 +1,413 @@
+//! This module implements the [CircuitBuilder], to write circuits against variables
+//! instead of absolute rows and columns.
+//!
+//! A [Var] is a handle to a value of the circuit. The builder lays out each operation on variables
+//! in new rows (the generic operations two per generic gate), and records the cells holding each variable:
+//! the copy constraints between all the cells of a variable are generated when the circuit is built,
+//! so that the gates of an operation never refer to the rows of the other operations.
+//! The existing gadgets are called with [CircuitBuilder::gadget], given the cells of their inputs and outputs
+//! relative to their first row.
+//!
+//! As the ECDSA layout, the builder creates the gates and, when the values of the inputs are known,
+//! the witness of the circuit, so that the same code describes both:
+//!
+//! ```
+//! use kimchi::circuits::{builder::CircuitBuilder, polynomials::rot::RotMode};
+//! use mina_curves::pasta::Fp;
+//!
+//! // the rotation of the XOR of two public words, plus the first word
+//! fn circuit(values: Option<(u64, u64)>) -> CircuitBuilder<Fp> {
+//!     let mut builder = CircuitBuilder::new(values.is_some());
+//!     let left = builder.public_input(values.map(|(left, _)| left.into()));
+//!     let right = builder.public_input(values.map(|(_, right)| right.into()));
+//!     let xor = builder.xor(left, right, 64);
+//!     let rotated = builder.rot64(xor, 7, RotMode::Left);
+//!     builder.add(rotated, left);
+//!     builder
+//! }
+//!
+//! let (gates, _) = circuit(None).build();
+//! let (same_gates, witness) = circuit(Some((0b1100, 0b1010))).build();
+//! assert_eq!(gates.len(), same_gates.len());
+//! assert_eq!(witness.unwrap()[0].len(), gates.len());
+//! ```
+//!
+//! Note: the public inputs are the first rows of the circuit, and are declared before the other variables.
+
+use crate::circuits::{
+    gate::{CircuitGate, Connect, GateType},
+    polynomial::COLUMNS,
+    polynomials::{
+        generic::{GenericGateSpec, DOUBLE_GENERIC_COEFFS, GENERIC_COEFFS, GENERIC_REGISTERS},
+        rot::{self, RotMode},
+        xor,
+    },
+    sub_circuit::SubCircuit,
+    wires::Wire,
+};
+use ark_ff::{PrimeField, SquareRootField};
+use std::array;
+
+/// A cell of the circuit, as a `(row, column)` pair
+pub type Cell = (usize, usize);
+
+/// A variable of a circuit, a handle to the cells constrained to its value
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Var(usize);
+
+/// The builder of a circuit and of its witness, see the module
+pub struct CircuitBuilder<F: PrimeField> {
+    gates: Vec<CircuitGate<F>>,
+    witness: Option<[Vec<F>; COLUMNS]>,
+    public: usize,
+    // the cells of each variable, whose first cell holds its value
+    cells: Vec<Vec<Cell>>,
+    // the union-find parents of the variables, merged by the equality assertions
+    parents: Vec<usize>,
+    // the variables of the constants
+    constants: Vec<(F, Var)>,
+    // the row of the generic gate whose second half is free
+    half: Option<usize>,
+    // the free cell of the generic gate of the last private input
+    free: Option<Cell>,
+}
+
+impl<F: PrimeField + SquareRootField> CircuitBuilder<F> {
+    /// Creates a builder of a circuit, and of its witness if `with_witness`
+    pub fn new(with_witness: bool) -> Self {
+        Self {
+            gates: vec![],
+            witness: with_witness.then(|| array::from_fn(|_| vec![])),
+            public: 0,
+            cells: vec![],
+            parents: vec![],
+            constants: vec![],
+            half: None,
+            free: None,
+        }
+    }
+
+    /// Returns the number of public inputs
+    pub fn public(&self) -> usize {
+        self.public
+    }
+
+    /// Returns the number of rows of the circuit
+    pub fn num_rows(&self) -> usize {
+        self.gates.len()
+    }
+
+    /// Returns the value of a variable when the witness is laid out
+    pub fn value(&self, var: Var) -> Option<F> {
+        let (row, col) = self.cells[var.0][0];
+        self.witness.as_ref().map(|witness| witness[col][row])
+    }
+
+    // Appends rows of gates, and their witness when it is laid out
+    fn push(&mut self, gates: Vec<CircuitGate<F>>, witness: impl FnOnce(&mut [Vec<F>; COLUMNS])) {
+        if let Some(builder_witness) = self.witness.as_mut() {
+            witness(builder_witness);
+            assert_eq!(
+                builder_witness[0].len(),
+                self.gates.len() + gates.len(),
+                "a witness row per gate"
+            );
+        }
+        self.gates.extend(gates);
+    }
+
+    fn set(&mut self, (row, col): Cell, value: Option<F>) {
+        if let (Some(witness), Some(value)) = (self.witness.as_mut(), value) {
+            witness[col][row] = value;
+        }
+    }
+
+    fn find(&self, var: Var) -> usize {
+        let mut root = var.0;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        root
+    }
+
+    // Creates a variable whose value is in a cell
+    fn new_var(&mut self, cell: Cell) -> Var {
+        self.cells.push(vec![cell]);
+        self.parents.push(self.parents.len());
+        Var(self.parents.len() - 1)
+    }
+
+    // Uses a variable in a cell, which is copied from the others
+    fn use_var(&mut self, var: Var, cell: Cell) {
+        let value = self.value(var);
+        self.set(cell, value);
+        self.cells[var.0].push(cell);
+    }
+
+    /// Declares a public input, in a new row
+    ///
+    /// # Panics
+    ///
+    /// Will panic if a private input or an operation was laid out before.
+    pub fn public_input(&mut self, value: Option<F>) -> Var {
+        assert_eq!(
+            self.gates.len(),
+            self.public,
+            "The public inputs are declared before the other variables"
+        );
+        let row = self.gates.len();
+        let gate =
+            CircuitGate::create_generic_gadget(Wire::for_row(row), GenericGateSpec::Pub, None);
+        self.push(vec![gate], |witness| {
+            for col in witness.iter_mut() {
+                col.push(F::zero());
+            }
+        });
+        self.public += 1;
+        let var = self.new_var((row, 0));
+        self.set((row, 0), value);
+        var
+    }
+
+    /// Declares a private input, in a cell of a generic gate without constraint
+    pub fn private_input(&mut self, value: Option<F>) -> Var {
+        let cell = match self.free.take() {
+            Some(cell) => cell,
+            None => {
+                let [left, right, _] = self.generic_half([F::zero(); GENERIC_COEFFS]);
+                self.free = Some(right);
+                left
+            }
+        };
+        let var = self.new_var(cell);
+        self.set(cell, value);
+        var
+    }
+
+    /// Returns the variable of a constant, which is shared by all its uses
+    pub fn constant(&mut self, value: F) -> Var {
+        if let Some((_, var)) = self.constants.iter().find(|(c, _)| *c == value) {
+            return *var;
+        }
+        // l - c = 0
+        let [cell, _, _] = self.generic_half([F::one(), F::zero(), F::zero(), F::zero(), -value]);
+        let var = self.new_var(cell);
+        self.set(cell, Some(value));
+        self.constants.push((value, var));
+        var
+    }
+
+    // Lays out half of a generic gate, and returns its cells
+    fn generic_half(&mut self, coeffs: [F; GENERIC_COEFFS]) -> [Cell; 3] {
+        let (row, half) = match self.half.take() {
+            Some(row) => (row, 1),
+            None => {
+                let row = self.gates.len();
+                let gate = CircuitGate::new(
+                    GateType::Generic,
+                    Wire::for_row(row),
+                    vec![F::zero(); DOUBLE_GENERIC_COEFFS],
+                );
+                self.push(vec![gate], |witness| {
+                    for col in witness.iter_mut() {
+                        col.push(F::zero());
+                    }
+                });
+                self.half = Some(row);
+                (row, 0)
+            }
+        };
+        self.gates[row].coeffs[half * GENERIC_COEFFS..(half + 1) * GENERIC_COEFFS]
+            .copy_from_slice(&coeffs);
+        array::from_fn(|i| (row, half * GENERIC_REGISTERS + i))
+    }
+
+    /// Lays out the generic operation `l a + r b + o out + m a b + c = 0` of coefficients `[l, r, o, m, c]`,
+    /// and returns its output
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the coefficient `o` of the output is zero.
+    pub fn generic(&mut self, coeffs: [F; GENERIC_COEFFS], left: Var, right: Var) -> Var {
+        let [l, r, o, m, c] = coeffs;
+        assert!(!o.is_zero(), "The output has a coefficient");
+        let output = self
+            .value(left)
+            .zip(self.value(right))
+            .map(|(a, b)| -(l * a + r * b + m * a * b + c) / o);
+        let [left_cell, right_cell, output_cell] = self.generic_half(coeffs);
+        self.use_var(left, left_cell);
+        self.use_var(right, right_cell);
+        let var = self.new_var(output_cell);
+        self.set(output_cell, output);
+        var
+    }
+
+    /// Returns the sum of two variables
+    pub fn add(&mut self, left: Var, right: Var) -> Var {
+        let (zero, one) = (F::zero(), F::one());
+        self.generic([one, one, -one, zero, zero], left, right)
+    }
+
+    /// Returns the difference of two variables
+    pub fn sub(&mut self, left: Var, right: Var) -> Var {
+        let (zero, one) = (F::zero(), F::one());
+        self.generic([one, -one, -one, zero, zero], left, right)
+    }
+
+    /// Returns the product of two variables
+    pub fn mul(&mut self, left: Var, right: Var) -> Var {
+        let (zero, one) = (F::zero(), F::one());
+        self.generic([zero, zero, -one, one, zero], left, right)
+    }
+
+    /// Constrains a variable to be a boolean
+    pub fn assert_boolean(&mut self, var: Var) {
+        let (zero, one) = (F::zero(), F::one());
+        // b * b - b = 0
+        let [left, right, _] = self.generic_half([-one, zero, zero, one, zero]);
+        self.use_var(var, left);
+        self.use_var(var, right);
+    }
+
+    /// Constrains two variables to be equal, with the copy constraints of their cells
+    pub fn assert_equal(&mut self, left: Var, right: Var) {
+        let (left, right) = (self.find(left), self.find(right));
+        if left != right {
+            self.parents[right] = left;
+        }
+    }
+
+    /// Lays out a gadget in new rows, and returns the variables of its outputs
+    /// Input:
+    /// - create  : creates the gates of the gadget, from its first row
+    /// - witness : extends the full witness with the rows of the gadget, from the values of its inputs
+    /// - inputs  : the variables copied to the cells of the gadget, relative to its first row
+    /// - outputs : the cells of the outputs of the gadget, relative to its first row
+    /// Warning:
+    /// - the cells of the inputs should not be wired by the gadget itself
+    pub fn gadget(
+        &mut self,
+        create: impl FnOnce(usize) -> Vec<CircuitGate<F>>,
+        witness: impl FnOnce(&mut [Vec<F>; COLUMNS], &[F]),
+        inputs: &[(Var, Cell)],
+        outputs: &[Cell],
+    ) -> Vec<Var> {
+        let start = self.gates.len();
+        let values: Option<Vec<F>> = inputs.iter().map(|(var, _)| self.value(*var)).collect();
+        let gates = create(start);
+        self.push(gates, |builder_witness| {
+            let values = values.expect("the inputs are known when the witness is laid out");
+            witness(builder_witness, &values);
+        });
+        for (var, (row, col)) in inputs {
+            self.use_var(*var, (start + row, *col));
+        }
+        outputs
+            .iter()
+            .map(|(row, col)| self.new_var((start + row, *col)))
+            .collect()
+    }
+
+    /// Lays out an instance of a sub-circuit in new rows, and returns the variables of its outputs
+    /// (see [Self::gadget])
+    /// Input:
+    /// - sub_circuit : the sub-circuit
+    /// - witness     : extends the full witness with the rows of the sub-circuit, from its input values
+    /// - inputs      : the variables copied to the named inputs of the sub-circuit
+    /// - outputs     : the names of the outputs of the sub-circuit
+    /// Panics if a name is not the name of an input or of an output of the sub-circuit.
+    pub fn sub_circuit(
+        &mut self,
+        sub_circuit: &SubCircuit<F>,
+        witness: impl FnOnce(&mut [Vec<F>; COLUMNS], &[F]),
+        inputs: &[(Var, &str)],
+        outputs: &[&str],
+    ) -> Vec<Var> {
+        let inputs: Vec<(Var, Cell)> = inputs
+            .iter()
+            .map(|(var, name)| match sub_circuit.input(name) {
+                Some(cell) => (*var, cell),
+                None => panic!("{name} is not an input of the sub-circuit"),
+            })
+            .collect();
+        let outputs: Vec<Cell> = outputs
+            .iter()
+            .map(|name| match sub_circuit.output(name) {
+                Some(cell) => cell,
+                None => panic!("{name} is not an output of the sub-circuit"),
+            })
+            .collect();
+        self.gadget(|row| sub_circuit.create(row).1, witness, &inputs, &outputs)
+    }
+
+    /// Returns the XOR of two variables of `bits` length, with the gates of [CircuitGate::create_xor_gadget]
+    /// Panics if the values of the variables are longer than `bits` when the witness is laid out
+    pub fn xor(&mut self, left: Var, right: Var, bits: usize) -> Var {
+        let zero = self.constant(F::zero());
+        let zero_row = xor::num_xors(bits);
+        let outputs = self.gadget(
+            |row| CircuitGate::create_xor_gadget(row, bits).1,
+            |witness, values| xor::extend_xor_witness(witness, values[0], values[1], bits),
+            &[
+                (left, (0, 0)),
+                (right, (0, 1)),
+                (zero, (zero_row, 0)),
+                (zero, (zero_row, 1)),
+                (zero, (zero_row, 2)),
+            ],
+            &[(0, 2)],
+        );
+        outputs[0]
+    }
+
+    /// Returns the rotation of a 64-bit variable, with the range check of the variable
+    /// (see [CircuitGate::extend_rot_from_cell])
+    /// Panics if the value of the variable is longer than 64 bits when the witness is laid out
+    pub fn rot64(&mut self, word: Var, rot: u32, side: RotMode) -> Var {
+        let zero = self.constant(F::zero());
+        let cell = self.cells[word.0][0];
+        let outputs = self.gadget(
+            |row| {
+                let (word_row, mut gates) = CircuitGate::create_rot(row, rot, side);
+                gates.push(CircuitGate::new(
+                    GateType::RangeCheck0,
+                    Wire::for_row(word_row),
+                    vec![F::zero()],
+                ));
+                gates
+            },
+            |witness, values| rot::extend_rot_from_cell(witness, cell, values[0], rot, side),
+            &[
+                (word, (0, 0)),
+                (word, (2, 0)),
+                // the 2 most significant limbs of shifted and of the word are zero
+                (zero, (1, 1)),
+                (zero, (1, 2)),
+                (zero, (2, 1)),
+                (zero, (2, 2)),
+            ],
+            &[(0, 1)],
+        );
+        outputs[0]
+    }
+
+    /// Returns the gates of the circuit, with the copy constraints of the variables, and its witness
+    /// if it is laid out
+    pub fn build(mut self) -> (Vec<CircuitGate<F>>, Option<[Vec<F>; COLUMNS]>) {
+        let mut classes: Vec<Vec<Cell>> = vec![vec![]; self.cells.len()];
+        for var in 0..self.cells.len() {
+            let root = self.find(Var(var));
+            let cells = std::mem::take(&mut self.cells[var]);
+            classes[root].extend(cells);
+        }
+        for cells in classes {
+            if let Some((first, others)) = cells.split_first() {
+                for cell in others {
+                    self.gates.connect_cell_pair(*first, *cell);
+                }
+            }
+        }
+        (self.gates, self.witness)
+    }
+}
@@ -0,0 +1,135 @@
+//! This module implements [CompiledCircuit], a serializable description of a circuit:
+//! its gates, their wiring and coefficients, and the configuration of its constraint system.
+//!
+//! A circuit can thus be compiled once, stored, and loaded later (for example by another process
+//! or a language binding) to create its constraint system, its witness, and its proofs.
+//!
+//! The serialization is versioned with [CIRCUIT_FORMAT_VERSION]: the bytes start with [CIRCUIT_MAGIC]
+//! and the version (as 4 little-endian bytes), followed by the circuit in the MessagePack format,
+//! with named fields so that the fields added by later versions are optional.
+
+use crate::{
+    circuits::{
+        constraints::{
+            default_quotient_chunks, Builder, ConstraintSystem, DEFAULT_QUOTIENT_CHUNKS,
+        },
+        gate::CircuitGate,
+        lookup::{lookups::LookupArgument, runtime_tables::RuntimeTableCfg, tables::LookupTable},
+        wires::Wire,
+    },
+    domain_separation::DomainSeparator,
+    error::{CircuitFormatError, SetupError},
+};
+use ark_ff::{PrimeField, SquareRootField};
+use serde::{Deserialize, Serialize};
+
+/// The bytes starting a serialized circuit
+pub const CIRCUIT_MAGIC: &[u8; 4] = b"kmcc";
+
+/// The version of the serialization of the circuits created by this build
+pub const CIRCUIT_FORMAT_VERSION: u32 = 1;
+
+/// A circuit, with the configuration of its constraint system (see [Builder])
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "F: ark_serialize::CanonicalSerialize + ark_serialize::CanonicalDeserialize")]
+pub struct CompiledCircuit<F: PrimeField> {
+    /// the gates, with their wiring and coefficients
+    pub gates: Vec<CircuitGate<F>>,
+    /// number of public inputs
+    pub public: usize,
+    /// cells of the public outputs, see [Builder::public_outputs]
+    #[serde(default)]
+    pub public_outputs: Vec<Wire>,
+    /// number of previous evaluation challenges, for recursive proving
+    #[serde(default)]
+    pub prev_challenges: usize,
+    /// lookup tables
+    #[serde(default)]
+    pub lookup_tables: Vec<LookupTable<F>>,
+    /// configuration of the runtime tables
+    #[serde(default)]
+    pub runtime_tables: Option<Vec<RuntimeTableCfg<F>>>,
+    /// argument used to prove the lookups
+    #[serde(default)]
+    pub lookup_argument: LookupArgument,
+    /// optional application-specific domain separator
+    #[serde(default)]
+    pub domain_separator: Option<DomainSeparator>,
+    /// number of chunks of the quotient polynomial
+    #[serde(default = "default_quotient_chunks")]
+    pub quotient_chunks: usize,
+}
+
+impl<F: PrimeField + SquareRootField> CompiledCircuit<F> {
+    /// Creates the circuit of the gates, with the default configuration of [ConstraintSystem::create]
+    pub fn new(gates: Vec<CircuitGate<F>>) -> Self {
+        Self {
+            gates,
+            public: 0,
+            public_outputs: vec![],
+            prev_challenges: 0,
+            lookup_tables: vec![],
+            runtime_tables: None,
+            lookup_argument: LookupArgument::default(),
+            domain_separator: None,
+            quotient_chunks: DEFAULT_QUOTIENT_CHUNKS,
+        }
+    }
+
+    /// Returns the builder of the constraint system of the circuit,
+    /// which can be configured further (for example with shared precomputations)
+    pub fn builder(&self) -> Builder<F> {
+        let builder = ConstraintSystem::create(self.gates.clone())
+            .public(self.public)
+            .public_outputs(&self.public_outputs)
+            .prev_challenges(self.prev_challenges)
+            .lookup(self.lookup_tables.clone())
+            .runtime(self.runtime_tables.clone())
+            .lookup_argument(self.lookup_argument)
+            .quotient_chunks(self.quotient_chunks);
+        match &self.domain_separator {
+            Some(domain_separator) => builder.domain_separator(domain_separator.clone()),
+            None => builder,
+        }
+    }
+
+    /// Builds the constraint system of the circuit.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the circuit is invalid, see [Builder::build].
+    pub fn build(&self) -> Result<ConstraintSystem<F>, SetupError> {
+        self.builder().build()
+    }
+
+    /// Serializes the circuit, with the current [CIRCUIT_FORMAT_VERSION].
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the encoding of the circuit fails, which does not happen for valid field elements.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = CIRCUIT_MAGIC.to_vec();
+        bytes.extend(CIRCUIT_FORMAT_VERSION.to_le_bytes());
+        bytes.extend(rmp_serde::to_vec_named(self).expect("failed to encode the circuit"));
+        bytes
+    }
+
+    /// Deserializes a circuit serialized by [Self::to_bytes].
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the bytes are not a serialized circuit, if their version is not supported
+    /// by this build, or if the circuit cannot be decoded.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CircuitFormatError> {
+        let header = CIRCUIT_MAGIC.len() + 4;
+        if bytes.len() < header || bytes[..CIRCUIT_MAGIC.len()] != CIRCUIT_MAGIC[..] {
+            return Err(CircuitFormatError::NotACircuit);
+        }
+        let version = u32::from_le_bytes(bytes[CIRCUIT_MAGIC.len()..header].try_into().unwrap());
+        if version == 0 || version > CIRCUIT_FORMAT_VERSION {
+            return Err(CircuitFormatError::UnsupportedVersion(version));
+        }
+        rmp_serde::from_slice(&bytes[header..])
+            .map_err(|e| CircuitFormatError::Decoding(e.to_string()))
+    }
+}
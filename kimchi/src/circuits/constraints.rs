@@ -2,16 +2,31 @@
 use super::lookup::runtime_tables::RuntimeTableCfg;
 use crate::{
     circuits::{
+        builder::Cell,
+        custom_gate::CustomGate,
         domain_constant_evaluation::DomainConstantEvaluations,
         domains::EvaluationDomains,
-        gate::{CircuitGate, GateType},
-        lookup::{index::LookupConstraintSystem, lookups::LookupFeatures, tables::LookupTable},
+        gate::{check_wiring, CircuitGate, Connect, GateType, VerifyPrecomputations},
+        lookup::{
+            index::{self as lookup_index, LookupConstraintSystem},
+            lookups::{LookupArgument, LookupFeatures},
+            tables::LookupTable,
+        },
+        optimizer::Optimization,
+        packing::Packing,
         polynomial::{WitnessEvals, WitnessOverDomains, WitnessShifts},
-        polynomials::permutation::{Shifts, ZK_ROWS},
+        polynomials::{
+            generic::GENERIC_COEFFS,
+            permutation::{Shifts, ZK_ROWS},
+        },
+        scheduler::Schedule,
+        stats::CircuitStats,
         wires::*,
     },
     curve::KimchiCurve,
+    domain_separation::DomainSeparator,
     error::SetupError,
+    fft::{self, CpuFft, FftBackend},
     prover_index::ProverIndex,
 };
 use ark_ff::{PrimeField, SquareRootField, Zero};
@@ -21,15 +36,30 @@ use ark_poly::{
 };
 use o1_utils::ExtendedEvaluations;
 use once_cell::sync::OnceCell;
+use rayon::prelude::*;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::serde_as;
 use std::array;
+use std::collections::BTreeMap;
+use std::ops::Range;
 use std::sync::Arc;
 
 //
 // ConstraintSystem
 //
 
+/// The default number of chunks of the quotient polynomial,
+/// which bounds the degree of the constraints to `8n` for a domain of size `n`
+pub const DEFAULT_QUOTIENT_CHUNKS: usize = PERMUTS;
+
+/// The maximum number of chunks of the quotient polynomial,
+/// for constraints of degree up to `16n` evaluated over the domain `d16`
+pub const MAX_QUOTIENT_CHUNKS: usize = 15;
+
+pub(crate) fn default_quotient_chunks() -> usize {
+    DEFAULT_QUOTIENT_CHUNKS
+}
+
 /// Flags for optional features in the constraint system
 #[derive(Copy, Clone, Serialize, Deserialize, Debug)]
 pub struct FeatureFlags {
@@ -45,6 +75,12 @@ pub struct FeatureFlags {
     pub xor: bool,
     /// ROT gate
     pub rot: bool,
+    /// 64-bit modular addition gate
+    #[serde(default)]
+    pub add_u64: bool,
+    /// Conditional selection gate
+    #[serde(default)]
+    pub select: bool,
     /// Lookup features
     pub lookup_features: LookupFeatures,
 }
@@ -130,6 +166,21 @@ pub struct ColumnEvaluations<F: PrimeField> {
     /// Rot gate selector over domain d8
     #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
     pub rot_selector8: Option<E<F, D<F>>>,
+
+    /// 64-bit modular addition gate selector over domain d8
+    #[serde(default)]
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub add_u64_selector8: Option<E<F, D<F>>>,
+
+    /// Conditional selection gate selector over domain d8
+    #[serde(default)]
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub select_selector8: Option<E<F, D<F>>>,
+
+    /// selectors of the custom gates over domain d8
+    #[serde(default)]
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub custom_selectors8: Vec<E<F, D<F>>>,
 }
 
 #[serde_as]
@@ -137,8 +188,12 @@ pub struct ColumnEvaluations<F: PrimeField> {
 pub struct ConstraintSystem<F: PrimeField> {
     // Basics
     // ------
-    /// number of public inputs
+    /// number of public inputs, including the public outputs
     pub public: usize,
+    /// number of public outputs, the last public inputs,
+    /// whose rows are connected to the output cells of the circuit
+    #[serde(default)]
+    pub public_outputs: usize,
     /// number of previous evaluation challenges, for recursive proving
     pub prev_challenges: usize,
     /// evaluation domains
@@ -170,6 +225,22 @@ pub struct ConstraintSystem<F: PrimeField> {
 
     /// Disable gates checks (for testing; only enables with development builds)
     pub disable_gates_checks: bool,
+
+    /// optional application-specific domain separator
+    #[serde(default)]
+    pub domain_separator: Option<DomainSeparator>,
+
+    /// number of chunks of the quotient polynomial,
+    /// the constraints have degree at most `(quotient_chunks + 1) * n`
+    #[serde(default = "default_quotient_chunks")]
+    pub quotient_chunks: usize,
+
+    /// custom gates, see [crate::circuits::custom_gate] (they are not serialized)
+    #[serde(skip)]
+    pub custom_gates: Vec<CustomGate<F>>,
+    /// index of the custom gate of each row of type [GateType::Custom]
+    #[serde(skip)]
+    pub custom_rows: BTreeMap<usize, usize>,
 }
 
 /// Represents an error found when verifying a witness with a gate
@@ -183,14 +254,26 @@ pub enum GateError {
     Custom { row: usize, err: String },
 }
 
+#[derive(Clone)]
 pub struct Builder<F: PrimeField> {
     gates: Vec<CircuitGate<F>>,
     public: usize,
+    public_outputs: usize,
     prev_challenges: usize,
     lookup_tables: Vec<LookupTable<F>>,
     runtime_tables: Option<Vec<RuntimeTableCfg<F>>>,
+    lookup_argument: LookupArgument,
     precomputations: Option<Arc<DomainConstantEvaluations<F>>>,
     disable_gates_checks: bool,
+    domain_separator: Option<DomainSeparator>,
+    layout_seed: u64,
+    quotient_chunks: usize,
+    lookup_precomputations: Option<LookupConstraintSystem<F>>,
+    custom_gates: Vec<CustomGate<F>>,
+    custom_rows: BTreeMap<usize, usize>,
+    domain_size: Option<usize>,
+    lookup_table_rows: Option<Range<usize>>,
+    copies: Vec<(Cell, Cell)>,
 }
 
 /// Create selector polynomial for a circuit gate
@@ -224,16 +307,46 @@ pub fn selector_polynomial<F: PrimeField>(
     }
 }
 
+/// Create selector polynomial for a custom gate, from the rows of the custom gates
+pub fn custom_selector_polynomial<F: PrimeField>(
+    selector: usize,
+    custom_rows: &BTreeMap<usize, usize>,
+    domain: &EvaluationDomains<F>,
+    target_domain: &D<F>,
+    disable_gates_checks: bool,
+) -> E<F, D<F>> {
+    if cfg!(debug_assertions) && disable_gates_checks {
+        DP::<F>::zero().evaluate_over_domain_by_ref(*target_domain)
+    } else {
+        let mut evals = vec![F::zero(); domain.d1.size()];
+        for (row, _) in custom_rows.iter().filter(|(_, gate)| **gate == selector) {
+            evals[*row] = F::one();
+        }
+        E::<F, D<F>>::from_vec_and_domain(evals, domain.d1)
+            .interpolate()
+            .evaluate_over_domain_by_ref(*target_domain)
+    }
+}
+
 impl<F: PrimeField> ConstraintSystem<F> {
     /// Initializes the [ConstraintSystem<F>] on input `gates` and `fr_sponge_params`.
     /// Returns a [Builder<F>]
     /// It also defaults to the following values of the builder:
     /// - `public: 0`
+    /// - `public_outputs: 0`
     /// - `prev_challenges: 0`
     /// - `lookup_tables: vec![]`,
     /// - `runtime_tables: None`,
+    /// - `lookup_argument: LookupArgument::Plookup`,
     /// - `precomputations: None`,
     /// - `disable_gates_checks: false`,
+    /// - `domain_separator: None`,
+    /// - `quotient_chunks: DEFAULT_QUOTIENT_CHUNKS`,
+    /// - `lookup_precomputations: None`,
+    /// - `custom_gates: vec![]`,
+    /// - `domain_size: None`,
+    /// - `lookup_table_rows: None`,
+    /// - `copies: vec![]`,
     ///
     /// How to use it:
     /// 1. Create your instance of your builder for the constraint system using `crate(gates, sponge params)`
@@ -243,14 +356,30 @@ impl<F: PrimeField> ConstraintSystem<F> {
         Builder {
             gates,
             public: 0,
+            public_outputs: 0,
             prev_challenges: 0,
             lookup_tables: vec![],
             runtime_tables: None,
+            lookup_argument: LookupArgument::default(),
             precomputations: None,
             disable_gates_checks: false,
+            domain_separator: None,
+            layout_seed: 0,
+            quotient_chunks: DEFAULT_QUOTIENT_CHUNKS,
+            lookup_precomputations: None,
+            custom_gates: vec![],
+            custom_rows: BTreeMap::new(),
+            domain_size: None,
+            lookup_table_rows: None,
+            copies: vec![],
         }
     }
 
+    /// The bound on the degree of the constraints, relative to the size `n` of the domain
+    pub fn max_constraint_degree(&self) -> usize {
+        self.quotient_chunks + 1
+    }
+
     pub fn precomputations(&self) -> &Arc<DomainConstantEvaluations<F>> {
         self.precomputations
             .get_or_init(|| Arc::new(DomainConstantEvaluations::create(self.domain).unwrap()))
@@ -261,6 +390,27 @@ impl<F: PrimeField> ConstraintSystem<F> {
             .set(precomputations)
             .expect("Precomputation has been set before");
     }
+
+    /// Returns the values of the public outputs in a witness, the last public inputs given to the verifier.
+    /// Each value is read from a cell connected to its public row,
+    /// so that the rows of the public outputs do not need to be filled in the witness.
+    pub fn public_output_values(&self, witness: &[Vec<F>; COLUMNS]) -> Vec<F> {
+        (self.public - self.public_outputs..self.public)
+            .map(|row| {
+                let Wire { row, col } = self.gates[row].wires[0];
+                witness[col][row]
+            })
+            .collect()
+    }
+
+    /// Fills the rows of the public outputs of a witness with their values,
+    /// see [Self::public_output_values].
+    pub fn fill_public_outputs(&self, witness: &mut [Vec<F>; COLUMNS]) {
+        let values = self.public_output_values(witness);
+        for (row, value) in (self.public - self.public_outputs..self.public).zip(values) {
+            witness[0][row] = value;
+        }
+    }
 }
 
 impl<F: PrimeField + SquareRootField, G: KimchiCurve<ScalarField = F>> ProverIndex<G> {
@@ -268,61 +418,93 @@ impl<F: PrimeField + SquareRootField, G: KimchiCurve<ScalarField = F>> ProverInd
     /// assignments (witness) against the constraints
     ///     witness: wire assignment witness
     ///     RETURN: verification status
+    /// The rows are verified in parallel, with the structures of the verification of the gates
+    /// created once (see [VerifyPrecomputations]), and the error of the first invalid row is returned.
     pub fn verify(&self, witness: &[Vec<F>; COLUMNS], public: &[F]) -> Result<(), GateError> {
-        // pad the witness
+        let witness = self.pad_witness(witness);
+        let precomputations = VerifyPrecomputations::create(&self.cs.gates);
+
+        (0..self.cs.gates.len())
+            .into_par_iter()
+            .filter_map(|row| {
+                self.verify_row(row, &witness, public, &precomputations)
+                    .err()
+            })
+            .find_first(|_| true)
+            .map_or(Ok(()), Err)
+    }
+
+    /// Pads the witness with zeros to the size of the domain, see [Self::verify_row]
+    pub(crate) fn pad_witness(&self, witness: &[Vec<F>; COLUMNS]) -> [Vec<F>; COLUMNS] {
         let pad = vec![F::zero(); self.cs.domain.d1.size() - witness[0].len()];
-        let witness: [Vec<F>; COLUMNS] = array::from_fn(|i| {
+        array::from_fn(|i| {
             let mut w = witness[i].to_vec();
             w.extend_from_slice(&pad);
             w
-        });
-
-        // check each rows' wiring
-        for (row, gate) in self.cs.gates.iter().enumerate() {
-            // check if wires are connected
-            for col in 0..PERMUTS {
-                let wire = gate.wires[col];
-
-                if wire.col >= PERMUTS {
-                    return Err(GateError::Custom {
-                        row,
-                        err: format!("a wire can only be connected to the first {PERMUTS} columns"),
-                    });
-                }
+        })
+    }
 
-                if witness[col][row] != witness[wire.col][wire.row] {
-                    return Err(GateError::DisconnectedWires(
-                        Wire { col, row },
-                        Wire {
-                            col: wire.col,
-                            row: wire.row,
-                        },
-                    ));
-                }
+    /// This function verifies the wiring and the constraints of a single row of a witness,
+    /// padded to the size of the domain, with the precomputations of the gates of the circuit
+    pub(crate) fn verify_row(
+        &self,
+        row: usize,
+        witness: &[Vec<F>; COLUMNS],
+        public: &[F],
+        precomputations: &VerifyPrecomputations<F>,
+    ) -> Result<(), GateError> {
+        let gate = &self.cs.gates[row];
+
+        // check if wires are connected
+        for col in 0..PERMUTS {
+            let wire = gate.wires[col];
+
+            if wire.col >= PERMUTS {
+                return Err(GateError::Custom {
+                    row,
+                    err: format!("a wire can only be connected to the first {PERMUTS} columns"),
+                });
             }
 
-            // for public gates, only the left wire is toggled
-            if row < self.cs.public && gate.coeffs[0] != F::one() {
-                return Err(GateError::IncorrectPublic(row));
+            if witness[col][row] != witness[wire.col][wire.row] {
+                return Err(GateError::DisconnectedWires(
+                    Wire { col, row },
+                    Wire {
+                        col: wire.col,
+                        row: wire.row,
+                    },
+                ));
             }
+        }
 
-            // check the gate's satisfiability
-            gate.verify::<G>(row, &witness, self, public)
-                .map_err(|err| GateError::Custom { row, err })?;
+        // for public gates, only the left wire is toggled
+        if row < self.cs.public && gate.coeffs[0] != F::one() {
+            return Err(GateError::IncorrectPublic(row));
         }
 
-        // all good!
-        Ok(())
+        // check the gate's satisfiability
+        gate.verify_with::<G>(row, witness, self, public, precomputations)
+            .map_err(|err| GateError::Custom { row, err })
     }
 }
 
 impl<F: PrimeField + SquareRootField> ConstraintSystem<F> {
     /// evaluate witness polynomials over domains
     pub fn evaluate(&self, w: &[DP<F>; COLUMNS], z: &DP<F>) -> WitnessOverDomains<F> {
+        self.evaluate_with(&CpuFft, w, z)
+    }
+
+    /// evaluate witness polynomials over domains, with a backend computing the FFTs (see [fft])
+    pub fn evaluate_with(
+        &self,
+        backend: &dyn FftBackend<F>,
+        w: &[DP<F>; COLUMNS],
+        z: &DP<F>,
+    ) -> WitnessOverDomains<F> {
         // compute shifted witness polynomials
         let w8: [E<F, D<F>>; COLUMNS] =
-            array::from_fn(|i| w[i].evaluate_over_domain_by_ref(self.domain.d8));
-        let z8 = z.evaluate_over_domain_by_ref(self.domain.d8);
+            array::from_fn(|i| fft::evaluate_over_domain(backend, &w[i], self.domain.d8));
+        let z8 = fft::evaluate_over_domain(backend, z, self.domain.d8);
 
         let w4: [E<F, D<F>>; COLUMNS] = array::from_fn(|i| {
             E::<F, D<F>>::from_vec_and_domain(
@@ -565,6 +747,46 @@ impl<F: PrimeField + SquareRootField> ConstraintSystem<F> {
             }
         };
 
+        let add_u64_selector8 = {
+            if !self.feature_flags.add_u64 {
+                None
+            } else {
+                Some(selector_polynomial(
+                    GateType::AddU64,
+                    &self.gates,
+                    &self.domain,
+                    &self.domain.d8,
+                    self.disable_gates_checks,
+                ))
+            }
+        };
+
+        let select_selector8 = {
+            if !self.feature_flags.select {
+                None
+            } else {
+                Some(selector_polynomial(
+                    GateType::Select,
+                    &self.gates,
+                    &self.domain,
+                    &self.domain.d8,
+                    self.disable_gates_checks,
+                ))
+            }
+        };
+
+        let custom_selectors8 = (0..self.custom_gates.len())
+            .map(|selector| {
+                custom_selector_polynomial(
+                    selector,
+                    &self.custom_rows,
+                    &self.domain,
+                    &self.domain.d8,
+                    self.disable_gates_checks,
+                )
+            })
+            .collect();
+
         // TODO: This doesn't need to be degree 8 but that would require some changes in expr
         let coefficients8 = array::from_fn(|i| {
             evaluated_column_coefficients.coefficients[i]
@@ -586,7 +808,74 @@ impl<F: PrimeField + SquareRootField> ConstraintSystem<F> {
             foreign_field_mul_selector8,
             xor_selector8,
             rot_selector8,
+            add_u64_selector8,
+            select_selector8,
+            custom_selectors8,
+        }
+    }
+}
+
+// The number of entries of the lookup tables of a circuit: the entries of its tables,
+// of its runtime tables, and of the built-in tables used by its gates
+fn num_lookups<F: PrimeField>(
+    lookup_tables: &[LookupTable<F>],
+    runtime_tables: &Option<Vec<RuntimeTableCfg<F>>>,
+    lookup_features: &LookupFeatures,
+) -> usize {
+    let mut num_lookups: usize = lookup_tables
+        .iter()
+        .map(
+            |LookupTable { data, id: _ }| {
+                if data.is_empty() {
+                    0
+                } else {
+                    data[0].len()
+                }
+            },
+        )
+        .sum();
+    for runtime_table in runtime_tables.iter() {
+        num_lookups += runtime_table.len();
+    }
+    let LookupFeatures { patterns, .. } = lookup_features;
+    for pattern in patterns.into_iter() {
+        if let Some(gate_table) = pattern.table() {
+            num_lookups += gate_table.table_size();
+        }
+    }
+    num_lookups
+}
+
+// The size of the domain of a circuit with `num_gates` gates and `num_lookups` table entries,
+// see the step 2 of [Builder::build]
+fn domain_size(
+    num_gates: usize,
+    num_lookups: usize,
+    lookup_table_rows: &Option<Range<usize>>,
+    domain_size: Option<usize>,
+) -> Result<usize, SetupError> {
+    let table_rows_end = match lookup_table_rows {
+        Some(rows) if rows.start > rows.end => {
+            return Err(SetupError::ConstraintSystem(format!(
+                "the rows {rows:?} reserved for the lookup tables are not a range"
+            )));
+        }
+        Some(rows) => rows.end,
+        None => num_lookups,
+    };
+    let domain_size_lower_bound = std::cmp::max(num_gates, table_rows_end + 2) + ZK_ROWS as usize;
+    match domain_size {
+        None => Ok(domain_size_lower_bound.next_power_of_two()),
+        Some(domain_size) if !domain_size.is_power_of_two() => Err(SetupError::ConstraintSystem(
+            format!("the reserved domain size {domain_size} is not a power of two"),
+        )),
+        Some(domain_size) if domain_size < domain_size_lower_bound => {
+            Err(SetupError::ConstraintSystem(format!(
+                "the circuit needs {domain_size_lower_bound} rows for its {num_gates} gates \
+                 and its lookup tables, more than the reserved domain size {domain_size}"
+            )))
         }
+        Some(domain_size) => Ok(domain_size),
     }
 }
 
@@ -598,6 +887,44 @@ impl<F: PrimeField + SquareRootField> Builder<F> {
         self
     }
 
+    /// Set up the public outputs: the cells of the circuit whose values are returned to the verifier.
+    /// If not invoked, there is no public output by default.
+    ///
+    /// Each output takes the next row following the public inputs, connected to its output cell,
+    /// which must be a public gate (see [CircuitGate::create_generic_gadget]).
+    /// The values of the outputs are the last public inputs given to the verifier,
+    /// and are read from the witness with [ConstraintSystem::public_output_values].
+    ///
+    /// This must be invoked after [Self::public], and before [Self::pack_generic_gates],
+    /// with cells of the original circuit.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if an output row is not a public gate, or if an output cell is not a wired cell of the circuit.
+    pub fn public_outputs(mut self, outputs: &[Wire]) -> Self {
+        for (i, output) in outputs.iter().enumerate() {
+            let row = self.public + self.public_outputs + i;
+            let is_public = self.gates.get(row).map_or(false, |gate| {
+                gate.typ == GateType::Generic
+                    && gate.coeffs.len() >= GENERIC_COEFFS
+                    && gate.coeffs[0] == F::one()
+                    && gate.coeffs[1..GENERIC_COEFFS].iter().all(F::is_zero)
+            });
+            assert!(
+                is_public,
+                "the public output row {row} is not a public gate"
+            );
+            assert!(
+                output.row < self.gates.len() && output.col < PERMUTS,
+                "the public output cell {output:?} is not a wired cell of the circuit"
+            );
+            self.gates
+                .connect_cell_pair((row, 0), (output.row, output.col));
+        }
+        self.public_outputs += outputs.len();
+        self
+    }
+
     /// Set up the number of previous challenges, used for recusive proving.
     /// If not invoked, it equals `0` by default.
     pub fn prev_challenges(mut self, prev_challenges: usize) -> Self {
@@ -608,9 +935,12 @@ impl<F: PrimeField + SquareRootField> Builder<F> {
     /// Set up the lookup tables.
     /// If not invoked, it is `vec![]` by default.
     ///
-    /// **Warning:** you have to make sure that the IDs of the lookup tables,
-    /// are unique and  not colliding with IDs of built-in lookup tables
-    /// (see [crate::circuits::lookup::tables]).
+    /// The tables can have any number of columns, and are referenced by their ID,
+    /// for example with the gadget of [crate::circuits::lookup::gadget].
+    ///
+    /// **Warning:** the IDs of the lookup tables must be unique and not collide with the IDs
+    /// of the built-in lookup tables used by the gates of the circuit (see [crate::circuits::lookup::tables]),
+    /// otherwise [Self::build] fails.
     pub fn lookup(mut self, lookup_tables: Vec<LookupTable<F>>) -> Self {
         self.lookup_tables = lookup_tables;
         self
@@ -627,6 +957,169 @@ impl<F: PrimeField + SquareRootField> Builder<F> {
         self
     }
 
+    /// Set up the argument used to prove the lookups, see [LookupArgument].
+    /// If not invoked, it is `LookupArgument::Plookup` by default.
+    ///
+    /// The gates and the lookup tables do not depend on the argument.
+    pub fn lookup_argument(mut self, lookup_argument: LookupArgument) -> Self {
+        self.lookup_argument = lookup_argument;
+        self
+    }
+
+    /// Declare copy constraints between cells of the circuit, as pairs of `(row, column)` cells,
+    /// instead of wiring the gates with [Connect::connect_cell_pair] when they are created.
+    /// The copies can be declared in any order, and add up over several invocations.
+    /// They are resolved into the wires of the gates, merging the cycles of their cells
+    /// (see [Connect::connect_cell_pairs]), by [Self::build], and before the layout of the circuit
+    /// is changed by [Self::schedule], [Self::pack_generic_gates] or [Self::optimize]:
+    /// their cells are the ones of the circuit when they are declared.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if a cell is not a wired cell of the circuit.
+    pub fn copies(mut self, copies: &[(Cell, Cell)]) -> Self {
+        for cell in copies.iter().flat_map(|(cell1, cell2)| [cell1, cell2]) {
+            assert!(
+                cell.0 < self.gates.len() && cell.1 < PERMUTS,
+                "the cell {cell:?} is not a wired cell of the circuit"
+            );
+        }
+        self.copies.extend_from_slice(copies);
+        self
+    }
+
+    // Resolves the declared copies into the wires of the gates
+    fn resolve_copies(&mut self) {
+        let copies = std::mem::take(&mut self.copies);
+        self.gates.connect_cell_pairs(&copies);
+    }
+
+    /// Set up the seed of the layout of the circuit, see [Packing::create_with_seed].
+    /// If not invoked, it is `0` by default, which keeps the gates in order.
+    pub fn layout_seed(mut self, layout_seed: u64) -> Self {
+        self.layout_seed = layout_seed;
+        self
+    }
+
+    /// Reorder the independent blocks of gates of the circuit, given by their first rows,
+    /// to reduce the rows of its optimization, see [Schedule].
+    /// Since the public input rows are kept, this must be invoked after [Self::public]
+    /// and [Self::public_outputs], and before [Self::pack_generic_gates] or [Self::optimize].
+    ///
+    /// Returns the schedule, which lays out the witness of the original circuit
+    /// in the scheduled circuit with [Schedule::schedule_witness].
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the first rows of the blocks are not increasing rows of the circuit,
+    /// after the public input rows.
+    pub fn schedule(mut self, blocks: &[usize]) -> (Self, Schedule) {
+        self.resolve_copies();
+        let (gates, schedule) =
+            Schedule::create(&self.gates, self.public + self.public_outputs, blocks);
+        self.custom_rows = self.moved_custom_rows(|row| Some(schedule.cell(Wire { row, col: 0 })));
+        self.gates = gates;
+        (self, schedule)
+    }
+
+    /// Pack the single generic gates of the circuit two per row, see [Packing].
+    /// Since the public input rows are not packed, this must be invoked after [Self::public],
+    /// [Self::public_outputs], and [Self::layout_seed].
+    ///
+    /// Returns the packing, which lays out the witness of the original circuit
+    /// in the packed circuit with [Packing::pack_witness].
+    pub fn pack_generic_gates(mut self) -> (Self, Packing) {
+        self.resolve_copies();
+        let (gates, packing) = Packing::create_with_seed(
+            &self.gates,
+            self.public + self.public_outputs,
+            self.layout_seed,
+        );
+        self.custom_rows = self.moved_custom_rows(|row| packing.cell(Wire { row, col: 0 }));
+        self.gates = gates;
+        (self, packing)
+    }
+
+    /// Optimize the circuit: remove its dead `Zero` gates and its copy gates,
+    /// and pack its single generic gates two per row, see [Optimization].
+    /// Since the public input rows are kept, this must be invoked after [Self::public],
+    /// [Self::public_outputs], and [Self::layout_seed], and instead of [Self::pack_generic_gates].
+    ///
+    /// Returns the optimization, which lays out the witness of the original circuit
+    /// in the optimized circuit with [Optimization::optimize_witness].
+    pub fn optimize(mut self) -> (Self, Optimization) {
+        self.resolve_copies();
+        let (gates, optimization) = Optimization::create_with_seed(
+            &self.gates,
+            self.public + self.public_outputs,
+            self.layout_seed,
+        );
+        self.custom_rows = self.moved_custom_rows(|row| optimization.cell(Wire { row, col: 0 }));
+        self.gates = gates;
+        (self, optimization)
+    }
+
+    // The rows of the custom gates in a new layout of the gates, keeping the rows of type `Custom`
+    // (the rows outside of the circuit are kept, and rejected by `build`)
+    fn moved_custom_rows(&self, cell: impl Fn(usize) -> Option<Wire>) -> BTreeMap<usize, usize> {
+        self.custom_rows
+            .iter()
+            .map(|(row, selector)| match self.gates.get(*row) {
+                Some(gate) if gate.typ == GateType::Custom => {
+                    let cell = cell(*row).expect("the layout keeps the rows of the custom gates");
+                    (cell.row, *selector)
+                }
+                _ => (*row, *selector),
+            })
+            .collect()
+    }
+
+    /// Register a custom gate, used by the given rows of the circuit, of type [GateType::Custom]
+    /// (see [crate::circuits::custom_gate]).
+    /// If not invoked, the circuit has no custom gate by default.
+    ///
+    /// The rows are the ones of the current gates of the builder,
+    /// and follow them if they are packed or optimized later.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if a row is already used by another custom gate.
+    pub fn custom_gate(mut self, gate: CustomGate<F>, rows: &[usize]) -> Self {
+        let selector = self.custom_gates.len();
+        for row in rows {
+            assert!(
+                self.custom_rows.insert(*row, selector).is_none(),
+                "the row {row} is already used by a custom gate"
+            );
+        }
+        self.custom_gates.push(gate);
+        self
+    }
+
+    /// Reserve a domain of `domain_size` rows for the circuit,
+    /// instead of the smallest domain fitting the gates and the lookup tables.
+    /// If not invoked, it is `None` by default.
+    ///
+    /// The reserved domain is not grown: [Self::build] fails if its size is not a power of two,
+    /// or if the gates or the lookup tables do not fit in it.
+    pub fn domain_size(mut self, domain_size: usize) -> Self {
+        self.domain_size = Some(domain_size);
+        self
+    }
+
+    /// Reserve the rows `rows` of the domain for the concatenated lookup table
+    /// (the fixed tables, followed by the runtime tables), instead of its first rows.
+    /// The other rows of the table hold the dummy entry.
+    /// If not invoked, it is `None` by default, and the table starts at the first row.
+    ///
+    /// The domain is large enough for the reserved rows, and [Self::build] fails if the tables
+    /// do not fit in them, or if they collide with the last rows of the domain,
+    /// used by the lookup argument and for zero-knowledge.
+    pub fn lookup_table_rows(mut self, rows: Range<usize>) -> Self {
+        self.lookup_table_rows = Some(rows);
+        self
+    }
+
     /// Set up the shared precomputations.
     /// If not invoked, it is `None` by default.
     pub fn shared_precomputations(
@@ -643,53 +1136,166 @@ impl<F: PrimeField + SquareRootField> Builder<F> {
         self
     }
 
+    /// Set up an application-specific domain separator,
+    /// so that proofs for this circuit are bound to the domain.
+    /// If not invoked, it is `None` by default.
+    pub fn domain_separator(mut self, domain_separator: DomainSeparator) -> Self {
+        self.domain_separator = Some(domain_separator);
+        self
+    }
+
+    /// Set up the number of chunks of the quotient polynomial committed by the proofs,
+    /// from [DEFAULT_QUOTIENT_CHUNKS] to [MAX_QUOTIENT_CHUNKS].
+    /// If not invoked, it is [DEFAULT_QUOTIENT_CHUNKS] by default.
+    ///
+    /// The constraints of the gates can then have a degree up to `(quotient_chunks + 1) * n`,
+    /// the constraints of degree above `8n` being evaluated over the domain `d16` by the prover.
+    pub fn quotient_chunks(mut self, quotient_chunks: usize) -> Self {
+        self.quotient_chunks = quotient_chunks;
+        self
+    }
+
+    /// Set up a precomputed lookup constraint system, for example the one of a deserialized prover index
+    /// of the same circuit, to skip the computation of the lookup table polynomials.
+    /// It is only used if the digest of its inputs matches the ones of the circuit being built
+    /// (see [lookup_index::inputs_digest]), otherwise the lookup constraint system is computed again.
+    /// If not invoked, it is `None` by default.
+    pub fn lookup_precomputations(
+        mut self,
+        lookup_precomputations: LookupConstraintSystem<F>,
+    ) -> Self {
+        self.lookup_precomputations = Some(lookup_precomputations);
+        self
+    }
+
+    /// Returns the statistics of the rows the circuit would use once built, see [CircuitStats],
+    /// without building it: neither the selector polynomials, nor the precomputations of its domain
+    /// are computed, so that the size of a large circuit can be checked quickly while designing it.
+    /// The statistics are the ones of [ConstraintSystem::stats] on the built circuit.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the reserved domain size is not a power of two large enough for the circuit,
+    /// or if the rows reserved for the lookup tables are not a range, as [Self::build] does.
+    pub fn stats(&self) -> Result<CircuitStats, SetupError> {
+        let lookup_features =
+            LookupFeatures::from_gates(&self.gates, self.runtime_tables.is_some());
+        let num_lookups = num_lookups(&self.lookup_tables, &self.runtime_tables, &lookup_features);
+        let domain_size = domain_size(
+            self.gates.len(),
+            num_lookups,
+            &self.lookup_table_rows,
+            self.domain_size,
+        )?;
+        let runtime_table_entries = self
+            .runtime_tables
+            .iter()
+            .flatten()
+            .map(RuntimeTableCfg::len)
+            .sum();
+        // the declared copies only change the wired cells
+        let resolved: Vec<CircuitGate<F>>;
+        let gates = if self.copies.is_empty() {
+            &self.gates
+        } else {
+            let mut gates = self.gates.clone();
+            gates.connect_cell_pairs(&self.copies);
+            resolved = gates;
+            &resolved
+        };
+        Ok(CircuitStats::create(
+            gates,
+            self.public + self.public_outputs,
+            domain_size,
+            runtime_table_entries,
+        ))
+    }
+
     /// Build the [ConstraintSystem] from a [Builder].
     pub fn build(self) -> Result<ConstraintSystem<F>, SetupError> {
+        self.build_after(None)
+    }
+
+    // Builds the constraint system, reusing the precomputations of the domain of a previous
+    // constraint system of the circuit if the domain has the same size
+    fn build_after(
+        mut self,
+        previous: Option<&ConstraintSystem<F>>,
+    ) -> Result<ConstraintSystem<F>, SetupError> {
+        self.resolve_copies();
         let mut gates = self.gates;
         let lookup_tables = self.lookup_tables;
         let runtime_tables = self.runtime_tables;
 
         //~ 1. If the circuit is less than 2 gates, abort.
+        //~    Check that the wires of the gates are a permutation of their wired cells,
+        //~    that is, that each wire points to a wired cell of the circuit,
+        //~    and that no two wires point to the same cell.
         // for some reason we need more than 1 gate for the circuit to work, see TODO below
         assert!(gates.len() > 1);
+        check_wiring(&gates).map_err(SetupError::Wiring)?;
 
-        let lookup_features = LookupFeatures::from_gates(&gates, runtime_tables.is_some());
+        let mut lookup_features = LookupFeatures::from_gates(&gates, runtime_tables.is_some());
+        lookup_features.argument = self.lookup_argument;
 
-        let num_lookups = {
-            let mut num_lookups: usize = lookup_tables
-                .iter()
-                .map(
-                    |LookupTable { data, id: _ }| {
-                        if data.is_empty() {
-                            0
-                        } else {
-                            data[0].len()
-                        }
-                    },
-                )
-                .sum();
-            for runtime_table in runtime_tables.iter() {
-                num_lookups += runtime_table.len();
-            }
-            let LookupFeatures { patterns, .. } = &lookup_features;
-            for pattern in patterns.into_iter() {
-                if let Some(gate_table) = pattern.table() {
-                    num_lookups += gate_table.table_size();
-                }
-            }
-            num_lookups
-        };
+        let num_lookups = num_lookups(&lookup_tables, &runtime_tables, &lookup_features);
 
         //~ 2. Create a domain for the circuit. That is,
         //~    compute the smallest subgroup of the field that
-        //~    has order greater or equal to `n + ZK_ROWS` elements.
-        let domain_size_lower_bound =
-            std::cmp::max(gates.len(), num_lookups + 1) + ZK_ROWS as usize;
-        let domain = EvaluationDomains::<F>::create(domain_size_lower_bound)?;
+        //~    has order greater or equal to `n + ZK_ROWS` elements,
+        //~    where `n` is the largest of the number of gates and of the number of rows needed by the lookup tables.
+        //~    The concatenated lookup table needs a row for each entry of the tables,
+        //~    one for the dummy entry, and one to assert the final product of the lookup aggregation.
+        //~    The circuit does not need to be padded by the user to make room for the tables.
+        //~    If rows are reserved for the tables, they are needed up to the last reserved row.
+        //~    If the domain size is given, check that it is a power of two at least `n + ZK_ROWS`,
+        //~    and use it instead.
+        let domain_size = domain_size(
+            gates.len(),
+            num_lookups,
+            &self.lookup_table_rows,
+            self.domain_size,
+        )?;
+        let domain = EvaluationDomains::<F>::create(domain_size)?;
 
         assert!(domain.d1.size > ZK_ROWS);
 
-        //~ 3. Pad the circuit: add zero gates to reach the domain size.
+        //~ 3. If the quotient polynomial has more chunks than the default `PERMUTS`,
+        //~    check that there are at most 15 of them, and that the field has a domain of size $16n$
+        //~    to evaluate the constraints of degree above $8n$.
+        if !(DEFAULT_QUOTIENT_CHUNKS..=MAX_QUOTIENT_CHUNKS).contains(&self.quotient_chunks) {
+            return Err(SetupError::ConstraintSystem(format!(
+                "the quotient polynomial must have from {DEFAULT_QUOTIENT_CHUNKS} to {MAX_QUOTIENT_CHUNKS} chunks, not {}",
+                self.quotient_chunks
+            )));
+        }
+        if self.quotient_chunks > DEFAULT_QUOTIENT_CHUNKS && domain.d16().is_none() {
+            return Err(SetupError::DomainCreation(
+                "construction of domain d16 did not work as intended",
+            ));
+        }
+
+        // the rows of type `Custom` are the rows of the custom gates,
+        // whose constraints fit the bound on the degree of the constraints
+        for (row, gate) in gates.iter().enumerate() {
+            if (gate.typ == GateType::Custom) != self.custom_rows.contains_key(&row) {
+                return Err(SetupError::ConstraintSystem(format!(
+                    "the row {row} of type {:?} does not match the rows of the custom gates",
+                    gate.typ
+                )));
+            }
+        }
+        if let Some(row) = self.custom_rows.keys().find(|row| **row >= gates.len()) {
+            return Err(SetupError::ConstraintSystem(format!(
+                "the custom gate row {row} is not a row of the circuit"
+            )));
+        }
+        for gate in &self.custom_gates {
+            gate.check_degree(domain.d1.size, (self.quotient_chunks + 1) as u64)
+                .map_err(SetupError::ConstraintSystem)?;
+        }
+
+        //~ 4. Pad the circuit: add zero gates to reach the domain size.
         let d1_size = domain.d1.size();
         let mut padding = (gates.len()..d1_size)
             .map(|i| {
@@ -709,6 +1315,8 @@ impl<F: PrimeField + SquareRootField> Builder<F> {
             foreign_field_mul: false,
             xor: false,
             rot: false,
+            add_u64: false,
+            select: false,
         };
 
         for gate in &gates {
@@ -719,21 +1327,50 @@ impl<F: PrimeField + SquareRootField> Builder<F> {
                 GateType::ForeignFieldMul => feature_flags.foreign_field_mul = true,
                 GateType::Xor16 => feature_flags.xor = true,
                 GateType::Rot64 => feature_flags.rot = true,
+                GateType::AddU64 => feature_flags.add_u64 = true,
+                GateType::Select => feature_flags.select = true,
                 _ => (),
             }
         }
 
-        //~ 4. sample the `PERMUTS` shifts.
-        let shifts = Shifts::new(&domain.d1);
+        let previous = previous.filter(|previous| previous.domain.d1.size == domain.d1.size);
+
+        //~ 5. sample the `PERMUTS` shifts.
+        let (shift, sid) = match previous {
+            Some(previous) => (previous.shift, previous.sid.clone()),
+            None => {
+                let shifts = Shifts::new(&domain.d1);
+                (shifts.shifts, shifts.map[0].clone())
+            }
+        };
 
         //
         // Lookup
         // ------
-        let lookup_constraint_system =
-            LookupConstraintSystem::create(&gates, lookup_tables, runtime_tables, &domain)
-                .map_err(|e| SetupError::ConstraintSystem(e.to_string()))?;
-
-        let sid = shifts.map[0].clone();
+        let lookup_constraint_system = match self.lookup_precomputations {
+            Some(lcs)
+                if lcs.inputs_digest
+                    == lookup_index::inputs_digest(
+                        &gates,
+                        &lookup_tables,
+                        runtime_tables.as_deref(),
+                        self.lookup_argument,
+                        self.lookup_table_rows.as_ref(),
+                        &domain,
+                    ) =>
+            {
+                Some(lcs)
+            }
+            _ => LookupConstraintSystem::create(
+                &gates,
+                lookup_tables,
+                runtime_tables,
+                self.lookup_argument,
+                self.lookup_table_rows,
+                &domain,
+            )
+            .map_err(|e| SetupError::ConstraintSystem(e.to_string()))?,
+        };
 
         // TODO: remove endo as a field
         let endo = F::zero();
@@ -742,24 +1379,32 @@ impl<F: PrimeField + SquareRootField> Builder<F> {
 
         let constraints = ConstraintSystem {
             domain,
-            public: self.public,
+            public: self.public + self.public_outputs,
+            public_outputs: self.public_outputs,
             prev_challenges: self.prev_challenges,
             sid,
             gates,
-            shift: shifts.shifts,
+            shift,
             endo,
             //fr_sponge_params: self.sponge_params,
             lookup_constraint_system,
             feature_flags,
             precomputations: domain_constant_evaluation,
             disable_gates_checks: self.disable_gates_checks,
+            domain_separator: self.domain_separator,
+            quotient_chunks: self.quotient_chunks,
+            custom_gates: self.custom_gates,
+            custom_rows: self.custom_rows,
         };
 
-        match self.precomputations {
-            Some(t) => {
+        match (self.precomputations, previous) {
+            (Some(t), _) => {
                 constraints.set_precomputations(t);
             }
-            None => {
+            (None, Some(previous)) => {
+                constraints.set_precomputations(previous.precomputations().clone());
+            }
+            (None, None) => {
                 constraints.precomputations();
             }
         }
@@ -767,6 +1412,93 @@ impl<F: PrimeField + SquareRootField> Builder<F> {
     }
 }
 
+/// A constraint system which can be extended with gates after it is built,
+/// for example by the tools developing a circuit interactively.
+///
+/// Appending gates builds the constraint system again with the configuration of its [Builder],
+/// keeping the shifts and the precomputations of the domain (see [DomainConstantEvaluations])
+/// as long as the circuit fits in the domain. When the circuit outgrows the domain,
+/// they are computed again for the larger domain.
+pub struct IncrementalConstraintSystem<F: PrimeField> {
+    // the configuration of the constraint system, without its gates
+    builder: Builder<F>,
+    // the number of gates of the circuit, before the padding of the domain
+    num_gates: usize,
+    cs: ConstraintSystem<F>,
+}
+
+impl<F: PrimeField + SquareRootField> IncrementalConstraintSystem<F> {
+    /// Builds the constraint system of a builder, see [Builder::build]
+    ///
+    /// # Errors
+    ///
+    /// Will give error as [Builder::build].
+    pub fn new(builder: Builder<F>) -> Result<Self, SetupError> {
+        let num_gates = builder.gates.len();
+        // the precomputations given to the builder are only valid for its gates
+        let config = Builder {
+            gates: vec![],
+            precomputations: None,
+            lookup_precomputations: None,
+            copies: vec![],
+            ..builder.clone()
+        };
+        let cs = builder.build()?;
+        Ok(Self {
+            builder: config,
+            num_gates,
+            cs,
+        })
+    }
+
+    /// Returns the constraint system of the gates appended so far
+    pub fn constraint_system(&self) -> &ConstraintSystem<F> {
+        &self.cs
+    }
+
+    /// Returns the number of gates of the circuit, without the padding of the domain,
+    /// which is the row of the next appended gate
+    pub fn num_gates(&self) -> usize {
+        self.num_gates
+    }
+
+    /// Appends gates to the circuit, laid out from the row [Self::num_gates],
+    /// connects the pairs of cells of `copies` (see [Connect::connect_cell_pairs]),
+    /// and builds the constraint system again
+    ///
+    /// # Errors
+    ///
+    /// Will give error as [Builder::build], for example if the wires of the gates are not
+    /// a permutation of their cells, or if a gate is a custom gate (see [Builder::custom_gate]).
+    /// The constraint system is then unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if a cell of `copies` is not a wired cell of the circuit.
+    pub fn append(
+        &mut self,
+        gates: Vec<CircuitGate<F>>,
+        copies: &[(Cell, Cell)],
+    ) -> Result<(), SetupError> {
+        let mut all_gates = self.cs.gates[..self.num_gates].to_vec();
+        all_gates.extend(gates);
+        all_gates.connect_cell_pairs(copies);
+        let num_gates = all_gates.len();
+        self.cs = Builder {
+            gates: all_gates,
+            ..self.builder.clone()
+        }
+        .build_after(Some(&self.cs))?;
+        self.num_gates = num_gates;
+        Ok(())
+    }
+
+    /// Returns the constraint system, see [Self::constraint_system]
+    pub fn into_constraint_system(self) -> ConstraintSystem<F> {
+        self.cs
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -0,0 +1,259 @@
+//! This module implements [CustomGate], the gates defined outside of kimchi,
+//! to add a gate to a circuit without modifying the kimchi crate.
+//!
+//! A custom gate is described by:
+//! - its constraints, expressions over the witness and coefficient cells of its row and of the next row
+//!   (see [crate::circuits::expr::prologue]),
+//! - the bound of the degree of its constraints, relative to the size of the domain,
+//! - an optional hook verifying the witness of its rows, in addition to its constraints.
+//!
+//! The rows using a custom gate have the type [GateType::Custom], and are given with the gate
+//! to [crate::circuits::constraints::Builder::custom_gate].
+//! Each custom gate has its own selector polynomial, the column [Column::Custom] of its index
+//! in the order of the registrations, which is committed in the verifier index,
+//! so that the constraints of the custom gates are part of the linearization
+//! proven and verified with the constraints of the other gates.
+//! As the other gates, the custom gates are mutually exclusive and share the powers of alpha of the gates.
+//!
+//! The constraints of the custom gates cannot be serialized: they are not serialized
+//! with their constraint system, and must be registered again to build the constraint system of a
+//! deserialized circuit.
+
+use crate::{
+    alphas::Alphas,
+    circuits::{
+        argument::{ArgumentType, DynArgument},
+        constraints::ConstraintSystem,
+        expr::{Cache, Column, Constants, ExprError, E},
+        gate::{CircuitGate, CurrOrNext, GateType},
+        polynomial::COLUMNS,
+    },
+    curve::KimchiCurve,
+    proof::{PointEvaluations, ProofEvaluations},
+};
+use ark_ff::PrimeField;
+use blake2::{Blake2b512, Digest};
+use o1_utils::FieldHelpers;
+use std::array;
+
+/// The verification of the witness of a row of a custom gate, given the row and the witness
+pub type CustomGateVerifier<F> = fn(usize, &[Vec<F>; COLUMNS]) -> Result<(), String>;
+
+/// A gate defined outside of kimchi, see the [module documentation](self)
+#[derive(Clone, Debug)]
+pub struct CustomGate<F: PrimeField> {
+    /// the name of the gate, used in the errors of its verification
+    pub name: String,
+    /// the constraints of the gate
+    pub constraints: Vec<E<F>>,
+    /// the bound of the degree of the constraints, relative to the size of the domain
+    pub degree: u64,
+    /// the verification of the witness of its rows, if any
+    pub verifier: Option<CustomGateVerifier<F>>,
+}
+
+impl<F: PrimeField> CustomGate<F> {
+    /// Creates a custom gate whose constraints have a degree of at most `degree`
+    pub fn new(name: &str, constraints: Vec<E<F>>, degree: u64) -> Self {
+        CustomGate {
+            name: name.to_string(),
+            constraints,
+            degree,
+            verifier: None,
+        }
+    }
+
+    /// Sets the verification of the witness of the rows of the gate,
+    /// run after the evaluation of its constraints (see [CircuitGate::verify])
+    #[must_use]
+    pub fn verifier(mut self, verifier: CustomGateVerifier<F>) -> Self {
+        self.verifier = Some(verifier);
+        self
+    }
+
+    /// The argument of the constraints of the gate, with the selector of index `selector`
+    pub(crate) fn argument(&self, selector: usize) -> CustomArgument<'_, F> {
+        CustomArgument {
+            selector,
+            gate: self,
+        }
+    }
+
+    /// Checks the degree of the constraints of the gate, over a domain of size `d1_size`
+    /// whose constraints have a degree of at most `max_degree` (see [ConstraintSystem::max_constraint_degree]).
+    ///
+    /// # Errors
+    ///
+    /// Will give error if a constraint has a degree above the bound of the gate,
+    /// or if the bound of the gate with its selector is above `max_degree`.
+    pub fn check_degree(&self, d1_size: u64, max_degree: u64) -> Result<(), String> {
+        // the selector of the gate adds one to the degree of its constraints
+        if self.degree + 1 > max_degree {
+            return Err(format!(
+                "the {} gate has a degree bound above {}",
+                self.name,
+                max_degree - 1
+            ));
+        }
+        match self
+            .constraints
+            .iter()
+            .position(|constraint| constraint.degree(d1_size) > self.degree * d1_size)
+        {
+            Some(i) => Err(format!(
+                "the constraint {} of the {} gate has a degree above its bound {}",
+                i + 1,
+                self.name,
+                self.degree
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Evaluates the constraints of the gate on a row of a witness of a constraint system,
+    /// with the constants of the constraints
+    ///
+    /// # Errors
+    ///
+    /// Will give error if a constraint uses a column which is not a witness or coefficient column.
+    pub fn evaluate_constraints(
+        &self,
+        row: usize,
+        witness: &[Vec<F>; COLUMNS],
+        cs: &ConstraintSystem<F>,
+        constants: &Constants<F>,
+    ) -> Result<Vec<F>, ExprError> {
+        // the cells of the rows after the witness, and the coefficients after the gates, are zero
+        let cell = |col: usize, row: usize| witness[col].get(row).copied().unwrap_or_else(F::zero);
+        let coeff = |col: usize, row: usize| {
+            cs.gates
+                .get(row)
+                .and_then(|gate| gate.coeffs.get(col))
+                .copied()
+                .unwrap_or_else(F::zero)
+        };
+        let mut evals = ProofEvaluations::dummy_with_witness_evaluations(
+            array::from_fn(|col| cell(col, row)),
+            array::from_fn(|col| cell(col, row + 1)),
+        );
+        evals.coefficients = array::from_fn(|col| PointEvaluations {
+            zeta: coeff(col, row),
+            zeta_omega: coeff(col, row + 1),
+        });
+
+        // the constraints are evaluated at the element of the domain of the row
+        let pt = cs.domain.d1.group_gen.pow([row as u64]);
+        self.constraints
+            .iter()
+            .map(|constraint| constraint.evaluate_(cs.domain.d1, pt, &evals, constants))
+            .collect()
+    }
+}
+
+/// The constraints of a custom gate, multiplied by the selector of the gate,
+/// to be combined with the constraints of the other gates
+pub(crate) struct CustomArgument<'a, F: PrimeField> {
+    selector: usize,
+    gate: &'a CustomGate<F>,
+}
+
+impl<'a, F: PrimeField> DynArgument<F> for CustomArgument<'a, F> {
+    fn constraints(&self, _cache: &mut Cache) -> Vec<E<F>> {
+        self.gate.constraints.clone()
+    }
+
+    fn combined_constraints(&self, alphas: &Alphas<F>, cache: &mut Cache) -> E<F> {
+        let constraints = self.constraints(cache);
+        let alphas = alphas.get_exponents(
+            self.argument_type(),
+            u32::try_from(constraints.len()).expect("too many constraints in the gate"),
+        );
+        E::cell(Column::Custom(self.selector), CurrOrNext::Curr)
+            * E::combine_constraints(alphas, constraints)
+    }
+
+    fn argument_type(&self) -> ArgumentType {
+        ArgumentType::Gate(GateType::Custom)
+    }
+}
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Verifies the witness of a row of type [GateType::Custom], against the custom gate of the row
+    pub(crate) fn verify_custom_gate<G: KimchiCurve<ScalarField = F>>(
+        &self,
+        row: usize,
+        witness: &[Vec<F>; COLUMNS],
+        cs: &ConstraintSystem<F>,
+    ) -> Result<(), String> {
+        let gate = cs
+            .custom_gate(row)
+            .ok_or_else(|| format!("no custom gate is registered for the row {row}"))?;
+
+        // the challenges are one, as in the verification of the other gates
+        let constants = Constants {
+            alpha: F::one(),
+            beta: F::one(),
+            gamma: F::one(),
+            joint_combiner: Some(F::one()),
+            endo_coefficient: cs.endo,
+            mds: &G::sponge_params().mds,
+        };
+        let results = gate
+            .evaluate_constraints(row, witness, cs, &constants)
+            .map_err(|e| format!("Failed to evaluate the {} constraints: {e}", gate.name))?;
+        if let Some(i) = results.iter().position(|result| !result.is_zero()) {
+            return Err(format!("Invalid {} constraint: {}", gate.name, i + 1));
+        }
+
+        match gate.verifier {
+            Some(verifier) => verifier(row, witness),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<F: PrimeField> ConstraintSystem<F> {
+    /// The custom gate of a row of type [GateType::Custom], see [crate::circuits::constraints::Builder::custom_gate]
+    pub fn custom_gate(&self, row: usize) -> Option<&CustomGate<F>> {
+        self.custom_rows
+            .get(&row)
+            .map(|selector| &self.custom_gates[*selector])
+    }
+
+    /// The Blake2b hash of the degree bounds and of the constraints of the custom gates,
+    /// in the order of their selectors and in reverse Polish notation, or no byte without custom gates.
+    /// It binds the constraints of the custom gates to the fingerprint of the verifier index
+    /// (see [crate::verifier_index::VerifierIndex::fingerprint]), as only their selectors are committed.
+    pub fn custom_constraints_digest(&self) -> Vec<u8> {
+        if self.custom_gates.is_empty() {
+            return vec![];
+        }
+        let mut hasher = Blake2b512::new();
+        for gate in &self.custom_gates {
+            hasher.update(gate.degree.to_le_bytes());
+            hasher.update((gate.constraints.len() as u64).to_le_bytes());
+            for constraint in &gate.constraints {
+                let tokens: Vec<_> = constraint
+                    .to_polish()
+                    .iter()
+                    .map(|token| token.map_literal(|x| x.to_hex()))
+                    .collect();
+                let bytes = rmp_serde::to_vec(&tokens).expect("the tokens are serializable");
+                hasher.update((bytes.len() as u64).to_le_bytes());
+                hasher.update(bytes);
+            }
+        }
+        hasher.finalize().to_vec()
+    }
+}
+
+/// The largest number of constraints of the custom gates
+pub(crate) fn max_constraints<F: PrimeField>(custom_gates: &[CustomGate<F>]) -> u32 {
+    custom_gates
+        .iter()
+        .map(|gate| {
+            u32::try_from(gate.constraints.len()).expect("too many constraints in the gate")
+        })
+        .max()
+        .unwrap_or(0)
+}
@@ -1,5 +1,32 @@
+//! This module implements the evaluation domains of a circuit,
+//! and the conversions of its polynomials between coefficient form and evaluation form over these domains.
+//!
+//! The columns of a circuit (witness, selectors, permutation) are committed in coefficient form,
+//! and are evaluated over the larger domains `d4` and `d8` to compute the constraints by the prover.
+//! The helpers below allow to move a polynomial of a real circuit between these forms, for example:
+//!
+//! ```
+//! use ark_poly::{univariate::DensePolynomial, Polynomial, UVPolynomial};
+//! use kimchi::circuits::domains::{DomainSize, EvaluationDomains};
+//! use mina_curves::pasta::Fp;
+//!
+//! let domains = EvaluationDomains::<Fp>::create(4).unwrap();
+//! let poly = DensePolynomial::from_coefficients_vec(vec![Fp::from(1u8), Fp::from(2u8)]);
+//!
+//! // evaluations over d8, as used internally by the prover
+//! let evals8 = domains.evaluate(&poly, DomainSize::D8);
+//! assert_eq!(evals8.evals.len(), 32);
+//!
+//! // back to the values of the rows of the circuit, and to coefficient form
+//! let rows = domains.convert(&evals8, DomainSize::D1);
+//! assert_eq!(rows.evals[1], poly.evaluate(&domains.d1.group_gen));
+//! assert_eq!(domains.interpolate(rows.evals), poly);
+//! ```
+
 use ark_ff::FftField;
-use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as Domain};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain as Domain,
+};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
@@ -53,11 +80,117 @@ impl<F: FftField> EvaluationDomains<F> {
     }
 }
 
+/// The evaluation domains of a circuit, by their size relative to the size `n` of the circuit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DomainSize {
+    /// The domain `d1`, of size `n`
+    D1,
+    /// The domain `d2`, of size `2n`
+    D2,
+    /// The domain `d4`, of size `4n`
+    D4,
+    /// The domain `d8`, of size `8n`
+    D8,
+}
+
+impl DomainSize {
+    /// The size of the domain, relative to the size of `d1`
+    pub fn factor(self) -> usize {
+        match self {
+            DomainSize::D1 => 1,
+            DomainSize::D2 => 2,
+            DomainSize::D4 => 4,
+            DomainSize::D8 => 8,
+        }
+    }
+}
+
+impl<F: FftField> EvaluationDomains<F> {
+    /// Returns the domain `d16` (of size `16n`), used to evaluate the constraints of degree above `8n`
+    /// when the quotient polynomial has more than [crate::circuits::wires::PERMUTS] chunks.
+    /// The generator of `d8` is the square of its generator.
+    /// Returns `None` if the field has no subgroup of that size.
+    pub fn d16(&self) -> Option<Domain<F>> {
+        Domain::<F>::new(16 * self.d1.size())
+    }
+
+    /// Returns the domain of the given size
+    pub fn get(&self, size: DomainSize) -> Domain<F> {
+        match size {
+            DomainSize::D1 => self.d1,
+            DomainSize::D2 => self.d2,
+            DomainSize::D4 => self.d4,
+            DomainSize::D8 => self.d8,
+        }
+    }
+
+    /// Returns the size of `domain` if it is one of these domains, `None` otherwise
+    pub fn size_of(&self, domain: &Domain<F>) -> Option<DomainSize> {
+        [
+            DomainSize::D1,
+            DomainSize::D2,
+            DomainSize::D4,
+            DomainSize::D8,
+        ]
+        .into_iter()
+        .find(|size| self.get(*size) == *domain)
+    }
+
+    /// Evaluates a polynomial in coefficient form over the domain of the given size
+    pub fn evaluate(
+        &self,
+        poly: &DensePolynomial<F>,
+        size: DomainSize,
+    ) -> Evaluations<F, Domain<F>> {
+        poly.evaluate_over_domain_by_ref(self.get(size))
+    }
+
+    /// Interpolates the values of the rows of a column (that is, evaluations over `d1`)
+    /// into a polynomial in coefficient form.
+    /// Missing rows are padded with zeros.
+    /// Panics if there are more values than rows.
+    pub fn interpolate(&self, mut rows: Vec<F>) -> DensePolynomial<F> {
+        assert!(
+            rows.len() <= self.d1.size(),
+            "there are more values than rows in the domain"
+        );
+        rows.resize(self.d1.size(), F::zero());
+        Evaluations::from_vec_and_domain(rows, self.d1).interpolate()
+    }
+
+    /// Converts evaluations over one of these domains into evaluations over the domain of the given size.
+    /// Going to a smaller domain keeps the evaluations at its points, and going to a larger domain
+    /// evaluates the polynomial of degree less than the size of the original domain.
+    /// Panics if `evals` are not over one of these domains.
+    pub fn convert(
+        &self,
+        evals: &Evaluations<F, Domain<F>>,
+        size: DomainSize,
+    ) -> Evaluations<F, Domain<F>> {
+        let from = self
+            .size_of(&evals.domain())
+            .expect("the evaluations are not over one of the domains of the circuit");
+        let target = self.get(size);
+        if from.factor() >= size.factor() {
+            // the generator of the target domain is a power of the generator of the original domain
+            let step = from.factor() / size.factor();
+            Evaluations::from_vec_and_domain(
+                evals.evals.iter().step_by(step).copied().collect(),
+                target,
+            )
+        } else {
+            evals.interpolate_by_ref().evaluate_over_domain(target)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ark_ff::Field;
+    use ark_ff::{Field, UniformRand};
+    use ark_poly::{Polynomial, UVPolynomial};
     use mina_curves::pasta::Fp;
+    use rand::{rngs::StdRng, SeedableRng};
 
     #[test]
     #[ignore] // TODO(mimoo): wait for fix upstream (https://github.com/arkworks-rs/algebra/pull/307)
@@ -72,4 +205,48 @@ mod tests {
             println!("d1 = {:?}", d.d1.group_gen);
         }
     }
+
+    #[test]
+    fn test_domain_d16() {
+        let domains = EvaluationDomains::<Fp>::create(16).unwrap();
+        let d16 = domains.d16().unwrap();
+        assert_eq!(d16.size(), 16 * domains.d1.size());
+        assert_eq!(d16.group_gen.square(), domains.d8.group_gen);
+    }
+
+    #[test]
+    fn test_domain_conversions() {
+        let rng = &mut StdRng::from_seed([7u8; 32]);
+        let domains = EvaluationDomains::<Fp>::create(16).unwrap();
+        let rows: Vec<Fp> = (0..16).map(|_| Fp::rand(rng)).collect();
+        let poly = domains.interpolate(rows.clone());
+        assert!(poly.degree() < 16);
+
+        let sizes = [
+            DomainSize::D1,
+            DomainSize::D2,
+            DomainSize::D4,
+            DomainSize::D8,
+        ];
+        for from in sizes {
+            let evals = domains.evaluate(&poly, from);
+            assert_eq!(evals.evals.len(), 16 * from.factor());
+            assert_eq!(domains.size_of(&evals.domain()), Some(from));
+            for to in sizes {
+                assert_eq!(domains.convert(&evals, to), domains.evaluate(&poly, to));
+            }
+        }
+        assert_eq!(
+            domains
+                .convert(&domains.evaluate(&poly, DomainSize::D8), DomainSize::D1)
+                .evals,
+            rows
+        );
+
+        // missing rows are padded with zeros
+        let short = domains.interpolate(vec![Fp::from(3u8)]);
+        let evals = domains.evaluate(&short, DomainSize::D1);
+        assert_eq!(evals.evals[0], Fp::from(3u8));
+        assert_eq!(short.evaluate(&domains.d1.group_gen), Fp::from(0u8));
+    }
 }
@@ -23,6 +23,7 @@ use serde::{Deserialize, Serialize};
 use std::ops::{Add, AddAssign, Mul, Neg, Sub};
 use std::{
     collections::{HashMap, HashSet},
+    hash::Hash,
     ops::MulAssign,
 };
 use std::{fmt, iter::FromIterator};
@@ -53,6 +54,7 @@ pub enum ExprError {
 }
 
 /// The collection of constants required to evaluate an `Expr`.
+#[derive(Clone)]
 pub struct Constants<F: 'static> {
     /// The challenge alpha from the PLONK IOP.
     pub alpha: F,
@@ -102,6 +104,8 @@ pub struct Environment<'a, F: FftField> {
     pub z: &'a Evaluations<F, D<F>>,
     /// The index selector polynomials.
     pub index: HashMap<GateType, &'a Evaluations<F, D<F>>>,
+    /// The selector polynomials of the custom gates.
+    pub custom_index: Vec<&'a Evaluations<F, D<F>>>,
     /// The value `prod_{j != 1} (1 - omega^j)`, used for efficiently
     /// computing the evaluations of the unnormalized Lagrange basis polynomials.
     pub l0_1: F,
@@ -132,6 +136,7 @@ impl<'a, F: FftField> Environment<'a, F> {
                 Some(e) => Some(e),
             },
             Permutation(_) => None,
+            Custom(i) => self.custom_index.get(*i).copied(),
         }
     }
 }
@@ -178,17 +183,11 @@ pub enum Column {
     Index(GateType),
     Coefficient(usize),
     Permutation(usize),
+    /// The selector of the custom gate of the given index, see [crate::circuits::custom_gate]
+    Custom(usize),
 }
 
 impl Column {
-    fn domain(&self) -> Domain {
-        match self {
-            Column::Index(GateType::Generic) => Domain::D4,
-            Column::Index(GateType::CompleteAdd) => Domain::D4,
-            _ => Domain::D8,
-        }
-    }
-
     fn latex(&self) -> String {
         match self {
             Column::Witness(i) => format!("w_{{{i}}}"),
@@ -204,6 +203,7 @@ impl Column {
             }
             Column::Coefficient(i) => format!("c_{{{i}}}"),
             Column::Permutation(i) => format!("sigma_{{{i}}}"),
+            Column::Custom(i) => format!("custom_{{{i}}}"),
         }
     }
 
@@ -222,6 +222,7 @@ impl Column {
             }
             Column::Coefficient(i) => format!("c[{i}]"),
             Column::Permutation(i) => format!("sigma_[{i}]"),
+            Column::Custom(i) => format!("custom[{i}]"),
         }
     }
 }
@@ -258,7 +259,7 @@ impl Variable {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 /// An arithmetic expression over
 ///
 /// - the operations *, +, -, ^
@@ -411,7 +412,7 @@ impl Cache {
 }
 
 /// A binary operation
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Op2 {
     Add,
     Mul,
@@ -430,7 +431,7 @@ impl Op2 {
 }
 
 /// The feature flags that can be used to enable or disable parts of constraints.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(
     feature = "ocaml_types",
     derive(ocaml::IntoValue, ocaml::FromValue, ocaml_gen::Enum)
@@ -449,6 +450,10 @@ pub enum FeatureFlag {
     TableWidth(isize), // NB: isize so that we don't need to convert for OCaml :(
     /// Enabled if the number of lookups per row is at least the given number
     LookupsPerRow(isize), // NB: isize so that we don't need to convert for OCaml :(
+    /// 64-bit modular addition gate
+    AddU64,
+    /// Conditional selection gate
+    Select,
 }
 
 impl FeatureFlag {
@@ -467,7 +472,7 @@ impl FeatureFlag {
 /// This represents a PLONK "custom constraint", which enforces that
 /// the corresponding combination of the polynomials corresponding to
 /// the above variables should vanish on the PLONK domain.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Expr<C> {
     Constant(C),
     Cell(Variable),
@@ -591,6 +596,8 @@ impl<C: Zero + One + Neg<Output = C> + PartialEq + Clone> Expr<C> {
                         ForeignFieldMul => features.foreign_field_mul,
                         Xor => features.xor,
                         Rot => features.rot,
+                        AddU64 => features.add_u64,
+                        Select => features.select,
                         LookupTables => {
                             features.lookup_features.patterns != LookupPatterns::default()
                         }
@@ -655,6 +662,37 @@ pub enum PolishToken<F> {
     SkipIfNot(FeatureFlag, usize),
 }
 
+impl<F> PolishToken<F> {
+    /// Apply a function to the literal of the token, if any.
+    pub fn map_literal<G, H: Fn(&F) -> G>(&self, f: H) -> PolishToken<G> {
+        use PolishToken::*;
+        match self {
+            Alpha => Alpha,
+            Beta => Beta,
+            Gamma => Gamma,
+            JointCombiner => JointCombiner,
+            EndoCoefficient => EndoCoefficient,
+            Mds { row, col } => Mds {
+                row: *row,
+                col: *col,
+            },
+            Literal(x) => Literal(f(x)),
+            Cell(v) => Cell(*v),
+            Dup => Dup,
+            Pow(n) => Pow(*n),
+            Add => Add,
+            Mul => Mul,
+            Sub => Sub,
+            VanishesOnLast4Rows => VanishesOnLast4Rows,
+            UnnormalizedLagrangeBasis(i) => UnnormalizedLagrangeBasis(*i),
+            Store => Store,
+            Load(i) => Load(*i),
+            SkipIf(feature, n) => SkipIf(*feature, *n),
+            SkipIfNot(feature, n) => SkipIfNot(*feature, *n),
+        }
+    }
+}
+
 impl Variable {
     fn evaluate<F: Field>(
         &self,
@@ -677,7 +715,7 @@ impl Variable {
                 Index(GateType::Generic) => Ok(evals.generic_selector),
                 Permutation(i) => Ok(evals.s[i]),
                 Coefficient(i) => Ok(evals.coefficients[i]),
-                LookupKindIndex(_) | LookupRuntimeSelector | Index(_) => {
+                LookupKindIndex(_) | LookupRuntimeSelector | Index(_) | Custom(_) => {
                     Err(ExprError::MissingIndexEvaluation(self.col))
                 }
             }
@@ -791,7 +829,8 @@ impl<C> Expr<C> {
         Expr::Constant(c)
     }
 
-    fn degree(&self, d1_size: u64) -> u64 {
+    /// The degree of the polynomial of this expression, for a domain of size `d1_size`
+    pub(crate) fn degree(&self, d1_size: u64) -> u64 {
         use Expr::*;
         match self {
             Double(x) => x.degree(d1_size),
@@ -811,6 +850,107 @@ impl<C> Expr<C> {
     }
 }
 
+impl<C: Clone + Eq + Hash> Expr<C> {
+    /// Extracts the subexpressions appearing several times in this expression
+    /// into [Expr::Cache] nodes, so that they are computed once when the expression is evaluated
+    /// (with [Expr::evaluations]) or compiled (with [Expr::to_polish]).
+    ///
+    /// Combining the constraints of several gates into one expression before the extraction
+    /// shares the subexpressions across the gates, for instance the limb recompositions
+    /// of the foreign field gates.
+    /// The existing [Expr::Cache] nodes are kept, and the new ones are given larger ids.
+    /// An expression with [Expr::IfFeature] nodes is returned unchanged,
+    /// since the values computed in a branch cannot be reused outside of it.
+    pub fn extract_common_subexpressions(&self) -> Self {
+        let mut next_id = 0;
+        if !self.collect_cache_ids(&mut next_id) {
+            return self.clone();
+        }
+        let mut cache = Cache { next_id };
+
+        let mut occurrences = HashMap::new();
+        self.count_occurrences(&mut occurrences);
+        self.extract_repeated(&occurrences, &mut HashMap::new(), &mut cache)
+    }
+
+    // Updates `next_id` past the ids of the cached subexpressions,
+    // and returns false if the expression has feature flags
+    fn collect_cache_ids(&self, next_id: &mut usize) -> bool {
+        use Expr::*;
+        match self {
+            Constant(_) | Cell(_) | VanishesOnLast4Rows | UnnormalizedLagrangeBasis(_) => true,
+            Double(x) | Square(x) | Pow(x, _) => x.collect_cache_ids(next_id),
+            BinOp(_, x, y) => x.collect_cache_ids(next_id) && y.collect_cache_ids(next_id),
+            Cache(id, x) => {
+                *next_id = std::cmp::max(*next_id, id.0 + 1);
+                x.collect_cache_ids(next_id)
+            }
+            IfFeature(..) => false,
+        }
+    }
+
+    // The subexpressions that can be extracted: the operations, but not the leaves or the cached subexpressions
+    fn is_operation(&self) -> bool {
+        use Expr::*;
+        matches!(self, Double(_) | Square(_) | Pow(..) | BinOp(..))
+    }
+
+    // Counts the occurrences of the operations, without recounting the operations inside a repeated one
+    fn count_occurrences<'a>(&'a self, occurrences: &mut HashMap<&'a Expr<C>, usize>) {
+        use Expr::*;
+        if !self.is_operation() {
+            return;
+        }
+        let count = occurrences.entry(self).or_insert(0);
+        *count += 1;
+        if *count > 1 {
+            return;
+        }
+        match self {
+            Double(x) | Square(x) | Pow(x, _) => x.count_occurrences(occurrences),
+            BinOp(_, x, y) => {
+                x.count_occurrences(occurrences);
+                y.count_occurrences(occurrences);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn extract_repeated<'a>(
+        &'a self,
+        occurrences: &HashMap<&'a Expr<C>, usize>,
+        extracted: &mut HashMap<&'a Expr<C>, Expr<C>>,
+        cache: &mut Cache,
+    ) -> Expr<C> {
+        use Expr::*;
+        if !self.is_operation() {
+            return self.clone();
+        }
+        if let Some(e) = extracted.get(self) {
+            return e.clone();
+        }
+        let mut extract =
+            |x: &'a Expr<C>| Box::new(x.extract_repeated(occurrences, extracted, cache));
+        let res = match self {
+            Double(x) => Double(extract(x)),
+            Square(x) => Square(extract(x)),
+            Pow(x, p) => Pow(extract(x), *p),
+            BinOp(op, x, y) => {
+                let x = extract(x);
+                BinOp(op.clone(), x, extract(y))
+            }
+            _ => unreachable!(),
+        };
+        if occurrences[self] > 1 {
+            let res = Cache(cache.next_id(), Box::new(res));
+            extracted.insert(self, res.clone());
+            res
+        } else {
+            res
+        }
+    }
+}
+
 impl<F> fmt::Display for Expr<ConstantExpr<F>>
 where
     F: PrimeField,
@@ -826,6 +966,21 @@ enum Domain {
     D2 = 2,
     D4 = 4,
     D8 = 8,
+    D16 = 16,
+}
+
+impl Domain {
+    /// The domain of evaluations of the given size, relative to the size `d1_size` of `d1`
+    fn of_evals<F: FftField>(evals: &Evaluations<F, D<F>>, d1_size: u64) -> Self {
+        match evals.evals.len() as u64 / d1_size {
+            1 => Domain::D1,
+            2 => Domain::D2,
+            4 => Domain::D4,
+            8 => Domain::D8,
+            16 => Domain::D16,
+            _ => panic!("the evaluations are not over one of the domains of the circuit"),
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -905,6 +1060,7 @@ fn unnormalized_lagrange_evals<F: FftField>(
         Domain::D2 => 2,
         Domain::D4 => 4,
         Domain::D8 => 8,
+        Domain::D16 => 16,
     };
     let res_domain = get_domain(res_domain, env);
 
@@ -1364,6 +1520,10 @@ fn get_domain<F: FftField>(d: Domain, env: &Environment<F>) -> D<F> {
         Domain::D2 => env.domain.d2,
         Domain::D4 => env.domain.d4,
         Domain::D8 => env.domain.d8,
+        Domain::D16 => env
+            .domain
+            .d16()
+            .expect("the field has no domain of size 16n"),
     }
 }
 
@@ -1621,8 +1781,11 @@ impl<F: FftField> Expr<F> {
             Domain::D4
         } else if deg <= 8 * d1_size {
             Domain::D8
+        } else if deg <= 16 * d1_size {
+            // the columns of the environment must then be evaluated over d16
+            Domain::D16
         } else {
-            panic!("constraint had degree {deg} > d8 ({})", 8 * d1_size);
+            panic!("constraint had degree {deg} > d16 ({})", 16 * d1_size);
         };
 
         let mut cache = HashMap::new();
@@ -1731,7 +1894,7 @@ impl<F: FftField> Expr<F> {
                 }
             }
             Expr::VanishesOnLast4Rows => EvalResult::SubEvals {
-                domain: Domain::D8,
+                domain: Domain::of_evals(env.vanishes_on_last_4_rows, env.domain.d1.size),
                 shift: 0,
                 evals: env.vanishes_on_last_4_rows,
             },
@@ -1748,7 +1911,7 @@ impl<F: FftField> Expr<F> {
                     }
                 };
                 EvalResult::SubEvals {
-                    domain: col.domain(),
+                    domain: Domain::of_evals(evals, env.domain.d1.size),
                     shift: row.shift(),
                     evals,
                 }
@@ -2712,7 +2875,8 @@ pub mod constraints {
 
         fn coeff(col: usize, env: Option<&ArgumentData<F>>) -> Self {
             match env {
-                Some(data) => data.coeffs[col],
+                // the coefficients which are not given are zero, as in the coefficient polynomials
+                Some(data) => data.coeffs.get(col).copied().unwrap_or_else(F::zero),
                 None => panic!("Missing coefficients"),
             }
         }
@@ -2821,16 +2985,22 @@ pub mod test {
     use super::*;
     use crate::{
         circuits::{
+            argument::Argument,
             constraints::ConstraintSystem,
             expr::constraints::ExprOps,
             gate::CircuitGate,
-            polynomials::{generic::GenericGateSpec, permutation::ZK_ROWS},
+            polynomials::{
+                foreign_field_add::circuitgates::ForeignFieldAdd,
+                foreign_field_mul::circuitgates::ForeignFieldMul, generic::GenericGateSpec,
+                permutation::ZK_ROWS,
+            },
             wires::Wire,
         },
         curve::KimchiCurve,
         prover_index::ProverIndex,
     };
     use ark_ff::UniformRand;
+    use ark_poly::{Polynomial, UVPolynomial};
     use mina_curves::pasta::{Fp, Pallas, Vesta};
     use poly_commitment::srs::{endos, SRS};
     use rand::{prelude::StdRng, SeedableRng};
@@ -2854,12 +3024,12 @@ pub mod test {
     #[should_panic]
     fn test_degree_tracking() {
         // The selector CompleteAdd has degree n-1 (so can be tracked with n evaluations in the domain d1 of size n).
-        // Raising a polynomial of degree n-1 to the power 8 makes it degree 8*(n-1) (and so it needs `8(n-1) + 1` evaluations).
-        // Since `d8` is of size `8n`, we are still good with that many evaluations to track the new polynomial.
-        // Raising it to the power 9 pushes us out of the domain d8, which will panic.
+        // Raising a polynomial of degree n-1 to the power 16 makes it degree 16*(n-1) (and so it needs `16(n-1) + 1` evaluations).
+        // Since `d16` is of size `16n`, we are still good with that many evaluations to track the new polynomial.
+        // Raising it to the power 17 pushes us out of the domain d16, which will panic.
         let mut expr: E<Fp> = E::zero();
         expr += index(GateType::CompleteAdd);
-        let expr = expr.pow(9);
+        let expr = expr.pow(17);
 
         // create a dummy env
         let one = Fp::from(1u32);
@@ -2905,6 +3075,7 @@ pub mod test {
             l0_1: l0_1(index.cs.domain.d1),
             domain: index.cs.domain,
             index: HashMap::new(),
+            custom_index: vec![],
             lookup: None,
         };
 
@@ -2961,4 +3132,188 @@ pub mod test {
         );
         assert_eq!(test_4::<Fp, Fp>(Fp::from(5u64)), Fp::from(160u64));
     }
+
+    // The constraints of the foreign field addition and multiplication gates, combined
+    fn foreign_field_constraints() -> E<Fp> {
+        let mut cache = Cache::default();
+        let constraints = ForeignFieldAdd::<Fp>::constraints(&mut cache)
+            .into_iter()
+            .chain(ForeignFieldMul::<Fp>::constraints(&mut cache))
+            .collect();
+        E::combine_constraints(0.., constraints)
+    }
+
+    fn count_tokens(toks: &[PolishToken<Fp>], tok: PolishToken<Fp>) -> usize {
+        toks.iter().filter(|t| **t == tok).count()
+    }
+
+    #[test]
+    fn test_extract_common_subexpressions() {
+        // w0 + w1 * w2 appears twice, and w1 * w2 once more on its own
+        let shared = || witness_curr::<Fp>(0) + witness_curr(1) * witness_curr(2);
+        let expr: E<Fp> = shared() * witness_curr(3)
+            + shared() * witness_next(0)
+            + witness_curr(1) * witness_curr(2);
+        let extracted = expr.extract_common_subexpressions();
+
+        let toks = extracted.to_polish();
+        assert_eq!(count_tokens(&toks, PolishToken::Store), 2);
+        assert_eq!(count_tokens(&toks, PolishToken::Load(0)), 1);
+        assert_eq!(count_tokens(&toks, PolishToken::Load(1)), 1);
+        assert!(toks.len() < expr.to_polish().len());
+
+        // the existing cached subexpressions are kept, and the expressions with feature flags are unchanged
+        let mut cache = Cache::default();
+        let cached = cache.cache(witness_curr::<Fp>(4) * witness_curr(5));
+        let expr = expr + cached.clone() * cached;
+        let toks = expr.extract_common_subexpressions().to_polish();
+        assert_eq!(count_tokens(&toks, PolishToken::Store), 3);
+
+        let flagged = Expr::IfFeature(
+            FeatureFlag::ForeignFieldMul,
+            Box::new(expr.clone()),
+            Box::new(Expr::zero()),
+        );
+        assert_eq!(flagged.extract_common_subexpressions(), flagged);
+
+        // the value of the expression is unchanged
+        let rng = &mut StdRng::from_seed([0u8; 32]);
+        let evals = ProofEvaluations::dummy_with_witness_evaluations(
+            array::from_fn(|_| Fp::rand(rng)),
+            array::from_fn(|_| Fp::rand(rng)),
+        );
+        let constants = Constants {
+            alpha: Fp::rand(rng),
+            beta: Fp::rand(rng),
+            gamma: Fp::rand(rng),
+            joint_combiner: None,
+            endo_coefficient: Fp::rand(rng),
+            mds: &Vesta::sponge_params().mds,
+        };
+        let domain = D::new(8).unwrap();
+        let pt = Fp::rand(rng);
+        for expr in [expr, foreign_field_constraints()] {
+            let eval = |e: &E<Fp>| {
+                PolishToken::evaluate(&e.to_polish(), domain, pt, &evals, &constants).unwrap()
+            };
+            assert_eq!(eval(&expr.extract_common_subexpressions()), eval(&expr));
+        }
+    }
+
+    #[test]
+    fn test_extract_common_subexpressions_evaluations() {
+        // the foreign field multiplication reuses its intermediate products across its constraints
+        let expr = foreign_field_constraints();
+        let extracted = expr.extract_common_subexpressions();
+        assert!(
+            count_tokens(&extracted.to_polish(), PolishToken::Store)
+                > count_tokens(&expr.to_polish(), PolishToken::Store)
+        );
+
+        let gates = (0..10)
+            .map(|row| {
+                CircuitGate::create_generic_gadget(
+                    Wire::for_row(row),
+                    GenericGateSpec::Const(1u32.into()),
+                    None,
+                )
+            })
+            .collect();
+        let index = {
+            let constraint_system = ConstraintSystem::fp_for_testing(gates);
+            let mut srs = SRS::<Vesta>::create(constraint_system.domain.d1.size());
+            srs.add_lagrange_basis(constraint_system.domain.d1);
+            let srs = Arc::new(srs);
+
+            let (endo_q, _endo_r) = endos::<Pallas>();
+            ProverIndex::<Vesta>::create(constraint_system, endo_q, srs)
+        };
+
+        let rng = &mut StdRng::from_seed([1u8; 32]);
+        let n = index.cs.domain.d1.size();
+        let witness_cols: [_; COLUMNS] = array::from_fn(|_| DensePolynomial::rand(n - 1, rng));
+        let permutation = DensePolynomial::zero();
+        let domain_evals = index.cs.evaluate(&witness_cols, &permutation);
+
+        let env = Environment {
+            constants: Constants {
+                alpha: Fp::rand(rng),
+                beta: Fp::rand(rng),
+                gamma: Fp::rand(rng),
+                joint_combiner: None,
+                endo_coefficient: index.cs.endo,
+                mds: &Vesta::sponge_params().mds,
+            },
+            witness: &domain_evals.d8.this.w,
+            coefficient: &index.column_evaluations.coefficients8,
+            vanishes_on_last_4_rows: &index.cs.precomputations().vanishes_on_last_4_rows,
+            z: &domain_evals.d8.this.z,
+            l0_1: l0_1(index.cs.domain.d1),
+            domain: index.cs.domain,
+            index: HashMap::new(),
+            custom_index: vec![],
+            lookup: None,
+        };
+
+        assert_eq!(extracted.evaluations(&env), expr.evaluations(&env));
+    }
+
+    #[test]
+    fn test_evaluations_over_d16() {
+        // w0^6 * w1^6 has degree 12(n-1), above the size of d8
+        let expr: E<Fp> = witness_curr(0).pow(6) * witness_curr(1).pow(6);
+
+        let gates = (0..10)
+            .map(|row| {
+                CircuitGate::create_generic_gadget(
+                    Wire::for_row(row),
+                    GenericGateSpec::Const(1u32.into()),
+                    None,
+                )
+            })
+            .collect();
+        let constraint_system = ConstraintSystem::fp_for_testing(gates);
+        let domain = constraint_system.domain;
+        let d16 = domain.d16().unwrap();
+
+        let rng = &mut StdRng::from_seed([2u8; 32]);
+        let n = domain.d1.size();
+        let witness_cols: [_; COLUMNS] = array::from_fn(|_| DensePolynomial::rand(n - 1, rng));
+        let witness16 = array::from_fn(|i| witness_cols[i].evaluate_over_domain_by_ref(d16));
+        let coefficient16 = array::from_fn(|_| DensePolynomial::zero().evaluate_over_domain(d16));
+        let vanishes_on_last_4_rows16 = constraint_system
+            .precomputations()
+            .vanishes_on_last_4_rows
+            .interpolate_by_ref()
+            .evaluate_over_domain(d16);
+        let z16 = DensePolynomial::zero().evaluate_over_domain(d16);
+
+        let env = Environment {
+            constants: Constants {
+                alpha: Fp::rand(rng),
+                beta: Fp::rand(rng),
+                gamma: Fp::rand(rng),
+                joint_combiner: None,
+                endo_coefficient: constraint_system.endo,
+                mds: &Vesta::sponge_params().mds,
+            },
+            witness: &witness16,
+            coefficient: &coefficient16,
+            vanishes_on_last_4_rows: &vanishes_on_last_4_rows16,
+            z: &z16,
+            l0_1: l0_1(domain.d1),
+            domain,
+            index: HashMap::new(),
+            custom_index: vec![],
+            lookup: None,
+        };
+
+        let evals = expr.evaluations(&env);
+        assert_eq!(evals.domain(), d16);
+        for (i, eval) in evals.evals.iter().enumerate() {
+            let (w0, w1) = (witness16[0].evals[i], witness16[1].evals[i]);
+            assert_eq!(*eval, w0.pow([6]) * w1.pow([6]));
+        }
+        assert_eq!(evals.interpolate().degree(), 12 * (n - 1));
+    }
 }
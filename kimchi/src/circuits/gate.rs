@@ -4,13 +4,16 @@ use crate::{
     circuits::{
         argument::{Argument, ArgumentEnv},
         constraints::ConstraintSystem,
+        optimizer::{find, union},
         polynomials::{
-            complete_add, endomul_scalar, endosclmul, foreign_field_add, foreign_field_mul,
-            poseidon, range_check, turshi, varbasemul,
+            add_u64, complete_add, endomul_scalar, endosclmul, foreign_field_add,
+            foreign_field_mul, generic, poseidon, range_check, select, turshi, varbasemul,
         },
         wires::*,
     },
     curve::KimchiCurve,
+    domain_separation::CIRCUIT_DIGEST_PREFIX,
+    error::WiringError,
     prover_index::ProverIndex,
 };
 use ark_ff::{bytes::ToBytes, PrimeField, SquareRootField};
@@ -18,12 +21,15 @@ use num_traits::cast::ToPrimitive;
 use o1_utils::hasher::CryptoDigest;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
-use std::io::{Result as IoResult, Write};
+use std::{
+    collections::HashMap,
+    io::{Result as IoResult, Write},
+};
 use thiserror::Error;
 
 use super::{
     argument::ArgumentWitness,
-    expr,
+    expr::{self, E},
     polynomials::{rot, xor},
 };
 
@@ -112,6 +118,17 @@ pub enum GateType {
     // Gates for Keccak
     Xor16 = 20,
     Rot64 = 21,
+    /// Vector lookup into a multi-column table
+    VectorLookup = 22,
+    /// Range check of packed bytes
+    RangeCheckU8 = 23,
+    RangeCheckU16 = 24,
+    /// Addition of 64-bit words modulo 2^64
+    AddU64 = 25,
+    /// User-defined gate, see [crate::circuits::custom_gate]
+    Custom = 26,
+    /// Conditional selection between words
+    Select = 27,
 }
 
 /// Gate error
@@ -181,6 +198,43 @@ impl<F: PrimeField> ToBytes for CircuitGate<F> {
     }
 }
 
+/// The structures of the verification of the gates of a circuit which do not depend on the rows
+/// of the witness, so that they are created once and shared by the rows, see [CircuitGate::verify_with]
+#[derive(Clone, Debug, Default)]
+pub struct VerifyPrecomputations<F: PrimeField> {
+    // the constraints of the EndoMul gate, if the circuit has one
+    endomul: Option<Vec<E<F>>>,
+    // the constant terms of the linearized constraints of the Cairo gates of the circuit
+    cairo: HashMap<GateType, E<F>>,
+}
+
+impl<F: PrimeField + SquareRootField> VerifyPrecomputations<F> {
+    /// Creates the structures of the verification of the gate types of a circuit
+    pub fn create(gates: &[CircuitGate<F>]) -> Self {
+        let mut precomputations = Self::default();
+        for typ in gates.iter().map(|gate| gate.typ) {
+            match typ {
+                GateType::EndoMul if precomputations.endomul.is_none() => {
+                    precomputations.endomul = Some(endosclmul::EndosclMul::<F>::constraints(
+                        &mut expr::Cache::default(),
+                    ));
+                }
+                GateType::CairoClaim
+                | GateType::CairoInstruction
+                | GateType::CairoFlags
+                | GateType::CairoTransition => {
+                    precomputations
+                        .cairo
+                        .entry(typ)
+                        .or_insert_with(|| CircuitGate::<F>::cairo_linearized_constraint(typ));
+                }
+                _ => (),
+            }
+        }
+        precomputations
+    }
+}
+
 impl<F: PrimeField + SquareRootField> CircuitGate<F> {
     /// this function creates "empty" circuit gate
     pub fn zero(wires: GateWires) -> Self {
@@ -199,6 +253,29 @@ impl<F: PrimeField + SquareRootField> CircuitGate<F> {
         witness: &[Vec<F>; COLUMNS],
         index: &ProverIndex<G>,
         public: &[F],
+    ) -> Result<(), String> {
+        let precomputations = VerifyPrecomputations::create(std::slice::from_ref(self));
+        self.verify_with(row, witness, index, public, &precomputations)
+    }
+
+    /// This function verifies the consistency of the wire
+    /// assignments (witness) against the constraints, with the structures
+    /// of the verification shared by the rows of the circuit
+    ///
+    /// # Errors
+    ///
+    /// Will give error if verify process returns error.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the precomputations were not created with the type of the gate.
+    pub fn verify_with<G: KimchiCurve<ScalarField = F>>(
+        &self,
+        row: usize,
+        witness: &[Vec<F>; COLUMNS],
+        index: &ProverIndex<G>,
+        public: &[F],
+        precomputations: &VerifyPrecomputations<F>,
     ) -> Result<(), String> {
         use GateType::*;
         match self.typ {
@@ -207,12 +284,22 @@ impl<F: PrimeField + SquareRootField> CircuitGate<F> {
             Poseidon => self.verify_poseidon::<G>(row, witness),
             CompleteAdd => self.verify_complete_add(row, witness),
             VarBaseMul => self.verify_vbmul(row, witness),
-            EndoMul => self.verify_endomul::<G>(row, witness, &index.cs),
+            EndoMul => {
+                let constraints = precomputations
+                    .endomul
+                    .as_ref()
+                    .expect("the precomputations have the EndoMul constraints");
+                self.verify_endomul_with::<G>(row, witness, &index.cs, constraints)
+            }
             EndoMulScalar => self.verify_endomul_scalar::<G>(row, witness, &index.cs),
-            // TODO: implement the verification for the lookup gate
-            Lookup => Ok(()),
+            // TODO: implement the verification for the lookup gates
+            Lookup | VectorLookup | RangeCheckU8 | RangeCheckU16 => Ok(()),
             CairoClaim | CairoInstruction | CairoFlags | CairoTransition => {
-                self.verify_cairo_gate::<G>(row, witness, &index.cs)
+                let constraint = precomputations
+                    .cairo
+                    .get(&self.typ)
+                    .expect("the precomputations have the Cairo constraints");
+                self.verify_cairo_gate_with::<G>(row, witness, &index.cs, constraint)
             }
             RangeCheck0 | RangeCheck1 => self
                 .verify_witness::<G>(row, witness, &index.cs, public)
@@ -229,6 +316,13 @@ impl<F: PrimeField + SquareRootField> CircuitGate<F> {
             Rot64 => self
                 .verify_witness::<G>(row, witness, &index.cs, public)
                 .map_err(|e| e.to_string()),
+            AddU64 => self
+                .verify_witness::<G>(row, witness, &index.cs, public)
+                .map_err(|e| e.to_string()),
+            Custom => self.verify_custom_gate::<G>(row, witness, &index.cs),
+            Select => self
+                .verify_witness::<G>(row, witness, &index.cs, public)
+                .map_err(|e| e.to_string()),
         }
     }
 
@@ -253,7 +347,8 @@ impl<F: PrimeField + SquareRootField> CircuitGate<F> {
             mds: &G::sponge_params().mds,
         };
         // Create the argument environment for the constraints over field elements
-        let env = ArgumentEnv::<F, F>::create(argument_witness, self.coeffs.clone(), constants);
+        let env =
+            ArgumentEnv::<F, F>::create(argument_witness, self.coeffs.clone(), constants.clone());
 
         // Check the wiring (i.e. copy constraints) for this gate
         // Note: Gates can operated on row Curr or Curr and Next.
@@ -277,63 +372,87 @@ impl<F: PrimeField + SquareRootField> CircuitGate<F> {
             }
         }
 
-        let mut cache = expr::Cache::default();
-
         // Perform witness verification on each constraint for this gate
         let results = match self.typ {
-            GateType::Zero => {
-                vec![]
-            }
             GateType::Generic => {
                 // TODO: implement the verification for the generic gate
                 vec![]
             }
-            GateType::Poseidon => poseidon::Poseidon::constraint_checks(&env, &mut cache),
-            GateType::CompleteAdd => complete_add::CompleteAdd::constraint_checks(&env, &mut cache),
-            GateType::VarBaseMul => varbasemul::VarbaseMul::constraint_checks(&env, &mut cache),
-            GateType::EndoMul => endosclmul::EndosclMul::constraint_checks(&env, &mut cache),
+            // a custom row without its custom gate, or whose constraints cannot be evaluated,
+            // fails its first constraint
+            GateType::Custom => cs
+                .custom_gate(row)
+                .and_then(|gate| gate.evaluate_constraints(row, witness, cs, &constants).ok())
+                .unwrap_or_else(|| vec![F::one()]),
+            _ => self.evaluate_constraints(&env),
+        };
+
+        // Check for failed constraints
+        for (i, result) in results.iter().enumerate() {
+            if !result.is_zero() {
+                // Pinpoint failed constraint
+                return Err(CircuitGateError::Constraint(self.typ, i + 1));
+            }
+        }
+
+        // TODO: implement generic plookup witness verification
+
+        Ok(())
+    }
+
+    /// Evaluates the constraints of the gate on the witness and with the constants of the environment,
+    /// in the order of their numbers in [CircuitGateError::Constraint].
+    /// The lookup gates have no constraints to evaluate, the constraints of the custom gates
+    /// are evaluated by [crate::circuits::custom_gate::CustomGate::evaluate_constraints],
+    /// and the evaluations of the generic gate do not include the public input.
+    pub fn evaluate_constraints(&self, env: &ArgumentEnv<F, F>) -> Vec<F> {
+        let mut cache = expr::Cache::default();
+
+        match self.typ {
+            GateType::Zero => {
+                vec![]
+            }
+            GateType::Generic => generic::Generic::constraint_checks(env, &mut cache),
+            GateType::Poseidon => poseidon::Poseidon::constraint_checks(env, &mut cache),
+            GateType::CompleteAdd => complete_add::CompleteAdd::constraint_checks(env, &mut cache),
+            GateType::VarBaseMul => varbasemul::VarbaseMul::constraint_checks(env, &mut cache),
+            GateType::EndoMul => endosclmul::EndosclMul::constraint_checks(env, &mut cache),
             GateType::EndoMulScalar => {
-                endomul_scalar::EndomulScalar::constraint_checks(&env, &mut cache)
+                endomul_scalar::EndomulScalar::constraint_checks(env, &mut cache)
             }
-            GateType::Lookup => {
-                // TODO: implement the verification for the lookup gate
+            GateType::Lookup
+            | GateType::VectorLookup
+            | GateType::RangeCheckU8
+            | GateType::RangeCheckU16 => {
+                // TODO: implement the verification for the lookup gates
                 vec![]
             }
-            GateType::CairoClaim => turshi::Claim::constraint_checks(&env, &mut cache),
-            GateType::CairoInstruction => turshi::Instruction::constraint_checks(&env, &mut cache),
-            GateType::CairoFlags => turshi::Flags::constraint_checks(&env, &mut cache),
-            GateType::CairoTransition => turshi::Transition::constraint_checks(&env, &mut cache),
+            GateType::CairoClaim => turshi::Claim::constraint_checks(env, &mut cache),
+            GateType::CairoInstruction => turshi::Instruction::constraint_checks(env, &mut cache),
+            GateType::CairoFlags => turshi::Flags::constraint_checks(env, &mut cache),
+            GateType::CairoTransition => turshi::Transition::constraint_checks(env, &mut cache),
             GateType::RangeCheck0 => {
-                range_check::circuitgates::RangeCheck0::constraint_checks(&env, &mut cache)
+                range_check::circuitgates::RangeCheck0::constraint_checks(env, &mut cache)
             }
             GateType::RangeCheck1 => {
-                range_check::circuitgates::RangeCheck1::constraint_checks(&env, &mut cache)
+                range_check::circuitgates::RangeCheck1::constraint_checks(env, &mut cache)
             }
             GateType::ForeignFieldAdd => {
-                foreign_field_add::circuitgates::ForeignFieldAdd::constraint_checks(
-                    &env, &mut cache,
-                )
+                foreign_field_add::circuitgates::ForeignFieldAdd::constraint_checks(env, &mut cache)
             }
             GateType::ForeignFieldMul => {
-                foreign_field_mul::circuitgates::ForeignFieldMul::constraint_checks(
-                    &env, &mut cache,
-                )
+                foreign_field_mul::circuitgates::ForeignFieldMul::constraint_checks(env, &mut cache)
             }
-            GateType::Xor16 => xor::Xor16::constraint_checks(&env, &mut cache),
-            GateType::Rot64 => rot::Rot64::constraint_checks(&env, &mut cache),
-        };
-
-        // Check for failed constraints
-        for (i, result) in results.iter().enumerate() {
-            if !result.is_zero() {
-                // Pinpoint failed constraint
-                return Err(CircuitGateError::Constraint(self.typ, i + 1));
+            GateType::Xor16 => xor::Xor16::constraint_checks(env, &mut cache),
+            GateType::Rot64 => rot::Rot64::constraint_checks(env, &mut cache),
+            GateType::AddU64 => add_u64::AddU64::constraint_checks(env, &mut cache),
+            GateType::Select => select::Select::constraint_checks(env, &mut cache),
+            GateType::Custom => {
+                // the constraints of the custom gates are registered with the constraint system,
+                // and evaluated by `CustomGate::evaluate_constraints`
+                vec![]
             }
         }
-
-        // TODO: implement generic plookup witness verification
-
-        Ok(())
     }
 
     // Return the part of the witness relevant to this gate at the given row offset
@@ -375,6 +494,16 @@ pub trait Connect {
     ///       of the same permutation then this would split it.
     fn connect_cell_pair(&mut self, cell1: (usize, usize), cell2: (usize, usize));
 
+    /// Connects the pairs of cells of `copies`, merging the cycles of the cells of each pair
+    /// (see [Self::connect_cell_pair]): the cells can already be wired to other cells,
+    /// or to each other, and the copies can be given in any order, for example
+    /// once the gates of a circuit are created. The cycles of the other cells are kept.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if a cell of `copies` is not a wired cell of the circuit.
+    fn connect_cell_pairs(&mut self, copies: &[((usize, usize), (usize, usize))]);
+
     /// Connects a generic gate cell with zeros to a given row for 64bit range check
     fn connect_64bit(&mut self, zero_row: usize, start_row: usize);
 
@@ -393,6 +522,10 @@ pub trait Connect {
         right_rc: Option<usize>,
         out_rc: usize,
     );
+
+    /// Connects the cells of the limbs of two foreign field elements, from their lowest limbs,
+    /// for example the result of a gadget and the input of the next one
+    fn connect_limbs(&mut self, limbs1: [Wire; 3], limbs2: [Wire; 3]);
 }
 
 impl<F: PrimeField> Connect for Vec<CircuitGate<F>> {
@@ -402,6 +535,52 @@ impl<F: PrimeField> Connect for Vec<CircuitGate<F>> {
         self[cell_new.0].wires[cell_new.1] = wire_tmp;
     }
 
+    fn connect_cell_pairs(&mut self, copies: &[((usize, usize), (usize, usize))]) {
+        for cell in copies.iter().flat_map(|(cell1, cell2)| [cell1, cell2]) {
+            assert!(
+                cell.0 < self.len() && cell.1 < PERMUTS,
+                "the cell {cell:?} is not a wired cell of the circuit"
+            );
+        }
+        if copies.is_empty() {
+            return;
+        }
+        // the sets of cells constrained equal, by the wires and by the copies
+        let index = |(row, col): (usize, usize)| row * PERMUTS + col;
+        let mut parents: Vec<usize> = (0..self.len() * PERMUTS).collect();
+        for (row, gate) in self.iter().enumerate() {
+            for (col, wire) in gate.wires.iter().enumerate() {
+                // the wires outside of the circuit are rejected by check_wiring
+                if wire.row < self.len() && wire.col < PERMUTS {
+                    union(&mut parents, index((row, col)), index((wire.row, wire.col)));
+                }
+            }
+        }
+        for (cell1, cell2) in copies {
+            union(&mut parents, index(*cell1), index(*cell2));
+        }
+
+        // the sets of the copied cells are wired again as cycles, in the order of their cells
+        let mut merged = vec![false; parents.len()];
+        for (cell1, _) in copies {
+            merged[find(&mut parents, index(*cell1))] = true;
+        }
+        let mut cycles: Vec<Vec<Wire>> = vec![vec![]; parents.len()];
+        for row in 0..self.len() {
+            for col in 0..PERMUTS {
+                let root = find(&mut parents, index((row, col)));
+                if merged[root] {
+                    cycles[root].push(Wire { row, col });
+                }
+            }
+        }
+        for cycle in cycles.iter().filter(|cycle| !cycle.is_empty()) {
+            for (i, wire) in cycle.iter().enumerate() {
+                self[wire.row].wires[wire.col] = cycle[(i + 1) % cycle.len()];
+            }
+        }
+    }
+
     fn connect_64bit(&mut self, zero_row: usize, start_row: usize) {
         // Connect the 64-bit cells from previous Generic gate with zeros in first 12 bits
         self.connect_cell_pair((start_row, 1), (start_row, 2));
@@ -441,6 +620,50 @@ impl<F: PrimeField> Connect for Vec<CircuitGate<F>> {
         // Copy result_hi -> Next(2)
         self.connect_cell_pair((out_rc + 2, 0), (ffadd_row + 1, 2));
     }
+
+    fn connect_limbs(&mut self, limbs1: [Wire; 3], limbs2: [Wire; 3]) {
+        for (cell1, cell2) in limbs1.into_iter().zip(limbs2) {
+            self.connect_cell_pair((cell1.row, cell1.col), (cell2.row, cell2.col));
+        }
+    }
+}
+
+/// Checks that the wires of the gates of a circuit are a permutation of their wired cells,
+/// so that the cells are split in cycles which close, as expected by the permutation argument
+/// (a circuit wired with [Connect::connect_cell_pair] always is).
+///
+/// # Errors
+///
+/// Will give error if a cell is wired outside of the wired columns of the rows of the circuit,
+/// or if two cells are wired to the same cell.
+pub fn check_wiring<F: PrimeField>(gates: &[CircuitGate<F>]) -> Result<(), WiringError> {
+    // the cell wired to each cell, if any: as there are as many wires as cells,
+    // the wires are a permutation if no cell is the target of two of them
+    let mut sources: Vec<[Option<Wire>; PERMUTS]> = vec![[None; PERMUTS]; gates.len()];
+    for (row, gate) in gates.iter().enumerate() {
+        for (col, wire) in gate.wires.iter().enumerate() {
+            let cell = Wire { row, col };
+            if wire.col >= PERMUTS {
+                return Err(WiringError::ColumnOutOfRange { cell, wire: *wire });
+            }
+            if wire.row >= gates.len() {
+                return Err(WiringError::RowOutOfRange {
+                    cell,
+                    wire: *wire,
+                    rows: gates.len(),
+                });
+            }
+            let source = &mut sources[wire.row][wire.col];
+            if let Some(other) = source {
+                return Err(WiringError::SharedWire {
+                    cells: [*other, cell],
+                    wire: *wire,
+                });
+            }
+            *source = Some(cell);
+        }
+    }
+    Ok(())
 }
 
 /// A circuit is specified as a public input size and a list of [`CircuitGate`].
@@ -464,7 +687,7 @@ where
 }
 
 impl<'a, F: PrimeField> CryptoDigest for Circuit<'a, F> {
-    const PREFIX: &'static [u8; 15] = b"kimchi-circuit0";
+    const PREFIX: &'static [u8; 15] = CIRCUIT_DIGEST_PREFIX;
 }
 
 impl<'a, F> From<&'a ConstraintSystem<F>> for Circuit<'a, F>
@@ -2,8 +2,12 @@ use crate::{
     circuits::{
         expr::{prologue::*, Column, ConstantExpr},
         gate::{CircuitGate, CurrOrNext},
-        lookup::lookups::{
-            JointLookup, JointLookupSpec, JointLookupValue, LocalPosition, LookupInfo,
+        lookup::{
+            logup,
+            lookups::{
+                JointLookup, JointLookupSpec, JointLookupValue, LocalPosition, LookupArgument,
+                LookupInfo,
+            },
         },
         wires::COLUMNS,
     },
@@ -26,7 +30,7 @@ pub const CONSTRAINTS: u32 = 7;
 /// The number of random values to append to columns for zero-knowledge.
 pub const ZK_ROWS: usize = 3;
 
-/// Pad with zeroes and then add 3 random elements in the last two
+/// Pad with zeroes and then add `ZK_ROWS` random elements in the last
 /// rows for zero knowledge.
 ///
 /// # Panics
@@ -362,14 +366,54 @@ impl<F: Zero> LookupConfiguration<F> {
     }
 }
 
-/// Specifies the lookup constraints as expressions.
+/// Specifies the lookup constraints as expressions,
+/// for the lookup argument of the configuration (see [LookupArgument]).
 ///
 /// # Panics
 ///
-/// Will panic if single `element` length is bigger than `max_per_row` length.
+/// Will panic if single `element` length is bigger than `max_per_row` length,
+/// or if feature flags are generated for the logUp argument, which does not support them.
 pub fn constraints<F: FftField>(
     configuration: &LookupConfiguration<F>,
     generate_feature_flags: bool,
+) -> Vec<E<F>> {
+    let mut res = match configuration.lookup_info.features.argument {
+        LookupArgument::Plookup => plookup_constraints(configuration, generate_feature_flags),
+        LookupArgument::LogUp => {
+            assert!(
+                !generate_feature_flags,
+                "The logUp argument does not support feature flags"
+            );
+            logup::constraints(configuration)
+        }
+    };
+
+    // if we are using runtime tables, we add:
+    // $RT(x) (1 - \text{selector}_{RT}(x)) = 0$
+    if configuration.lookup_info.features.uses_runtime_tables {
+        let mut rt_constraints = runtime_tables::constraints();
+        if generate_feature_flags {
+            for term in rt_constraints.iter_mut() {
+                // Dummy value, to appease the borrow checker.
+                let mut boxed_term = Box::new(constant(F::zero()));
+                std::mem::swap(term, &mut *boxed_term);
+                *term = E::IfFeature(
+                    FeatureFlag::RuntimeLookupTables,
+                    boxed_term,
+                    Box::new(E::zero()),
+                )
+            }
+        }
+        res.extend(rt_constraints);
+    }
+
+    res
+}
+
+// Specifies the constraints of the plookup argument as expressions
+fn plookup_constraints<F: FftField>(
+    configuration: &LookupConfiguration<F>,
+    generate_feature_flags: bool,
 ) -> Vec<E<F>> {
     // Something important to keep in mind is that the last 2 rows of
     // all columns will have random values in them to maintain zero-knowledge.
@@ -640,25 +684,6 @@ pub fn constraints<F: FftField>(
     // consistent.
     res.extend((lookup_info.max_per_row..4).map(|_| E::zero()));
 
-    // if we are using runtime tables, we add:
-    // $RT(x) (1 - \text{selector}_{RT}(x)) = 0$
-    if configuration.lookup_info.features.uses_runtime_tables {
-        let mut rt_constraints = runtime_tables::constraints();
-        if generate_feature_flags {
-            for term in rt_constraints.iter_mut() {
-                // Dummy value, to appease the borrow checker.
-                let mut boxed_term = Box::new(constant(F::zero()));
-                std::mem::swap(term, &mut *boxed_term);
-                *term = E::IfFeature(
-                    FeatureFlag::RuntimeLookupTables,
-                    boxed_term,
-                    Box::new(E::zero()),
-                )
-            }
-        }
-        res.extend(rt_constraints);
-    }
-
     res
 }
 
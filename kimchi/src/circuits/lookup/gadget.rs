@@ -0,0 +1,391 @@
+//! This module includes the definition of the fixed-table lookup gadget and its witness code generation.
+//!
+//! The `Lookup` gate reads the ID of the table it looks up into from its first witness column.
+//! This gadget fixes that ID in the circuit, by wiring the first column of each of its `Lookup` rows
+//! to a `Generic` gate constraining it to a constant, so that a custom table registered with
+//! [ConstraintSystem::lookup](crate::circuits::constraints::Builder::lookup) can be referenced by its ID.
+//!
+//! | Row         | `CircuitGate` | Purpose                                               |
+//! | ----------- | ------------- | ----------------------------------------------------- |
+//! | i           | `Generic`     | Constrain the table ID, in column 0                   |
+//! | i+1...i+n   | `Lookup`      | Look up to 3 pairs `(first, second)` per row          |
+//!
+//! In the `Lookup` rows, the pairs are in columns `(1, 2)`, `(3, 4)` and `(5, 6)`.
+//! Each pair is checked to be an entry of the table, which must have at most two columns:
+//! entries of tables with a single column are looked up as `(value, 0)`.
+//!
+//! The vector lookup gadget has the same layout with `VectorLookup` rows, each looking up a single tuple
+//! of up to 6 values in columns `1..=6`, in a table with at most 6 columns
+//! (for example an ALU table of entries `(op, in1, in2, out)`).
+//! Tuples shorter than the table, and entries of tables narrower than 6 columns, are padded with zeros.
+//! All the values are in wired columns, so that they can be copied from (or to) other gates.
+//!
+//! The table ID can also come from the witness, to dispatch the lookups between tables at proving time.
+//! The dynamic lookup gadget has no `Generic` row: the table ID is in column 0 of its first `Lookup` row,
+//! and it must be copied from another cell constraining it.
+//! The selection lookup gadget constrains it to one of two tables, with a boolean selector $s$:
+//!
+//! | Row         | `CircuitGate` | Purpose                                               |
+//! | ----------- | ------------- | ----------------------------------------------------- |
+//! | i           | `Generic`     | $s \cdot s = s$ and $id = id_0 + s \cdot (id_1 - id_0)$ |
+//! | i+1...i+n   | `Lookup`      | Look up to 3 pairs `(first, second)` per row          |
+//!
+//! The selector is in column 0 of the first row, at the position given by [select_lookup_selector].
+//! Note that the prover can use any table of the circuit in a dynamic lookup if its table ID is not constrained.
+
+use crate::circuits::{
+    gate::{CircuitGate, Connect, GateType},
+    polynomial::COLUMNS,
+    polynomials::generic::GenericGateSpec,
+    wires::Wire,
+};
+use ark_ff::PrimeField;
+use o1_utils::field_helpers::i32_to_field;
+use std::array;
+
+/// Number of lookups performed by each `Lookup` row
+pub const LOOKUPS_PER_ROW: usize = 3;
+
+/// Maximum number of values of the tuples looked up by each `VectorLookup` row
+pub const VECTOR_LOOKUP_WIDTH: usize = 6;
+
+/// Returns the number of `Lookup` rows needed for `num_lookups` lookups
+pub fn num_lookup_rows(num_lookups: usize) -> usize {
+    (num_lookups + LOOKUPS_PER_ROW - 1) / LOOKUPS_PER_ROW
+}
+
+/// Returns the position of the cell containing the table ID of a dynamic lookup gadget
+/// starting at row `new_row`, as a `(row, column)` pair.
+pub fn dynamic_lookup_table_id(new_row: usize) -> (usize, usize) {
+    (new_row, 0)
+}
+
+/// Returns the position of the cell containing the selector of a selection lookup gadget
+/// starting at row `new_row`, as a `(row, column)` pair.
+pub fn select_lookup_selector(new_row: usize) -> (usize, usize) {
+    (new_row, 0)
+}
+
+// Checks that a lookup gadget has lookups
+fn check_num_lookups(num_lookups: usize) {
+    assert!(
+        num_lookups > 0,
+        "The lookup gadget needs at least one lookup"
+    );
+}
+
+// Creates the Generic gate fixing the table ID, followed by `num_rows` gates of type `typ` copying it
+fn create_table_lookup<F: PrimeField>(
+    new_row: usize,
+    table_id: i32,
+    typ: GateType,
+    num_rows: usize,
+) -> (usize, Vec<CircuitGate<F>>) {
+    let mut lookup_gates = vec![CircuitGate::create_generic_gadget(
+        Wire::for_row(new_row),
+        GenericGateSpec::Const(i32_to_field(table_id)),
+        None,
+    )];
+    for i in 1..=num_rows {
+        lookup_gates.push(CircuitGate::new(typ, Wire::for_row(new_row + i), vec![]));
+        // copy the table ID (indices are relative to the gadget, wires are not)
+        lookup_gates.connect_cell_pair((i - 1, 0), (i, 0));
+    }
+    (new_row + lookup_gates.len(), lookup_gates)
+}
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Extends a circuit with a gadget performing `num_lookups` lookups into the table of ID `table_id`
+    /// Includes:
+    /// - 1 Generic gate to fix the table ID
+    /// - 1 Lookup gate for every 3 lookups
+    /// Input:
+    /// - gates       : the full circuit
+    /// - table_id    : the ID of the table, which must be registered in the constraint system
+    /// - num_lookups : the number of lookups
+    /// Output:
+    /// - new row index
+    pub fn extend_lookup(gates: &mut Vec<Self>, table_id: i32, num_lookups: usize) -> usize {
+        let (next_row, mut lookup_gates) = Self::create_lookup(gates.len(), table_id, num_lookups);
+        gates.append(&mut lookup_gates);
+        next_row
+    }
+
+    /// Creates a gadget performing `num_lookups` lookups into the table of ID `table_id`,
+    /// see [Self::extend_lookup]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    /// Panics if `num_lookups` is zero.
+    pub fn create_lookup(new_row: usize, table_id: i32, num_lookups: usize) -> (usize, Vec<Self>) {
+        check_num_lookups(num_lookups);
+        create_table_lookup(
+            new_row,
+            table_id,
+            GateType::Lookup,
+            num_lookup_rows(num_lookups),
+        )
+    }
+
+    /// Extends a circuit with a gadget performing `num_lookups` vector lookups
+    /// into the multi-column table of ID `table_id`
+    /// Includes:
+    /// - 1 Generic gate to fix the table ID
+    /// - 1 VectorLookup gate for every lookup
+    /// Input:
+    /// - gates       : the full circuit
+    /// - table_id    : the ID of the table, which must be registered in the constraint system
+    /// - num_lookups : the number of lookups
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the values of the i-th lookup are in columns `1..=6` of row `i + 1` of the gadget
+    pub fn extend_vector_lookup(gates: &mut Vec<Self>, table_id: i32, num_lookups: usize) -> usize {
+        let (next_row, mut lookup_gates) =
+            Self::create_vector_lookup(gates.len(), table_id, num_lookups);
+        gates.append(&mut lookup_gates);
+        next_row
+    }
+
+    /// Creates a gadget performing `num_lookups` vector lookups into the table of ID `table_id`,
+    /// see [Self::extend_vector_lookup]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    /// Panics if `num_lookups` is zero.
+    pub fn create_vector_lookup(
+        new_row: usize,
+        table_id: i32,
+        num_lookups: usize,
+    ) -> (usize, Vec<Self>) {
+        assert!(
+            num_lookups > 0,
+            "The vector lookup gadget needs at least one lookup"
+        );
+        create_table_lookup(new_row, table_id, GateType::VectorLookup, num_lookups)
+    }
+
+    /// Extends a circuit with a gadget performing `num_lookups` lookups into a table whose ID is in the witness
+    /// Includes:
+    /// - 1 Lookup gate for every 3 lookups
+    /// Input:
+    /// - gates       : the full circuit
+    /// - num_lookups : the number of lookups
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the table ID is at the position given by [dynamic_lookup_table_id],
+    ///   and should come from the copy of another cell constraining it
+    pub fn extend_dynamic_lookup(gates: &mut Vec<Self>, num_lookups: usize) -> usize {
+        let (next_row, mut lookup_gates) = Self::create_dynamic_lookup(gates.len(), num_lookups);
+        gates.append(&mut lookup_gates);
+        next_row
+    }
+
+    /// Creates a gadget performing `num_lookups` lookups into a table whose ID is in the witness,
+    /// see [Self::extend_dynamic_lookup]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    /// Panics if `num_lookups` is zero.
+    pub fn create_dynamic_lookup(new_row: usize, num_lookups: usize) -> (usize, Vec<Self>) {
+        check_num_lookups(num_lookups);
+        let mut lookup_gates: Vec<_> = (0..num_lookup_rows(num_lookups))
+            .map(|i| CircuitGate::new(GateType::Lookup, Wire::for_row(new_row + i), vec![]))
+            .collect();
+        // copy the table ID (indices are relative to the gadget, wires are not)
+        for i in 1..lookup_gates.len() {
+            lookup_gates.connect_cell_pair((i - 1, 0), (i, 0));
+        }
+        (new_row + lookup_gates.len(), lookup_gates)
+    }
+
+    /// Extends a circuit with a gadget performing `num_lookups` lookups into one of two tables,
+    /// selected by a boolean in the witness
+    /// Includes:
+    /// - 1 Generic gate to select the table ID
+    /// - 1 Lookup gate for every 3 lookups
+    /// Input:
+    /// - gates       : the full circuit
+    /// - table_ids   : the IDs of the tables selected by the values 0 and 1 of the selector
+    /// - num_lookups : the number of lookups
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the selector is at the position given by [select_lookup_selector]
+    pub fn extend_select_lookup(
+        gates: &mut Vec<Self>,
+        table_ids: [i32; 2],
+        num_lookups: usize,
+    ) -> usize {
+        let (next_row, mut lookup_gates) =
+            Self::create_select_lookup(gates.len(), table_ids, num_lookups);
+        gates.append(&mut lookup_gates);
+        next_row
+    }
+
+    /// Creates a gadget performing `num_lookups` lookups into one of two tables,
+    /// see [Self::extend_select_lookup]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    /// Panics if `num_lookups` is zero.
+    pub fn create_select_lookup(
+        new_row: usize,
+        table_ids: [i32; 2],
+        num_lookups: usize,
+    ) -> (usize, Vec<Self>) {
+        let [id0, id1]: [F; 2] = table_ids.map(i32_to_field);
+        // s * s - s = 0
+        // (id_1 - id_0) * s - id + id_0 = 0
+        let mut coeffs = [F::zero(); 10];
+        coeffs[2] = -F::one();
+        coeffs[3] = F::one();
+        coeffs[5] = id1 - id0;
+        coeffs[7] = -F::one();
+        coeffs[9] = id0;
+        let mut lookup_gates = vec![CircuitGate::create_generic(Wire::for_row(new_row), coeffs)];
+        let (next_row, mut dynamic_gates) = Self::create_dynamic_lookup(new_row + 1, num_lookups);
+        lookup_gates.append(&mut dynamic_gates);
+
+        // copy s and the table ID (indices are relative to the gadget, wires are not)
+        lookup_gates.connect_cell_pair((0, 0), (0, 1));
+        lookup_gates.connect_cell_pair((0, 1), (0, 2));
+        lookup_gates.connect_cell_pair((0, 2), (0, 3));
+        lookup_gates.connect_cell_pair((0, 5), (1, 0));
+
+        (next_row, lookup_gates)
+    }
+}
+
+/// Extends the fixed-table lookup rows to the full witness
+/// Input
+/// - witness: full witness of the circuit
+/// - table_id: the ID of the table
+/// - lookups: the pairs `(first, second)` to look up in the table
+/// Note: unused slots of the last row repeat the last lookup, since a zero pair may not be in the table.
+/// Panics if `lookups` is empty.
+pub fn extend_lookup_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    table_id: i32,
+    lookups: &[(F, F)],
+) {
+    let table_id: F = i32_to_field(table_id);
+    witness[0].push(table_id);
+    for col in witness.iter_mut().skip(1) {
+        col.push(F::zero());
+    }
+    extend_dynamic_lookup_witness(witness, table_id, lookups);
+}
+
+/// Extends the selection lookup rows to the full witness
+/// Input
+/// - witness: full witness of the circuit
+/// - table_ids: the IDs of the tables selected by the values 0 and 1 of the selector
+/// - select: the value of the selector
+/// - lookups: the pairs `(first, second)` to look up in the selected table
+/// Panics if `lookups` is empty.
+pub fn extend_select_lookup_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    table_ids: [i32; 2],
+    select: bool,
+    lookups: &[(F, F)],
+) {
+    let selector = F::from(select);
+    let table_id: F = i32_to_field(table_ids[usize::from(select)]);
+    for (col, cell) in witness.iter_mut().enumerate() {
+        cell.push(match col {
+            0..=3 => selector,
+            5 => table_id,
+            _ => F::zero(),
+        });
+    }
+    extend_dynamic_lookup_witness(witness, table_id, lookups);
+}
+
+/// Extends the dynamic lookup rows to the full witness
+/// Input
+/// - witness: full witness of the circuit
+/// - table_id: the ID of the table, as a field element
+/// - lookups: the pairs `(first, second)` to look up in the table
+/// Note: unused slots of the last row repeat the last lookup, since a zero pair may not be in the table.
+/// Panics if `lookups` is empty.
+pub fn extend_dynamic_lookup_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    table_id: F,
+    lookups: &[(F, F)],
+) {
+    assert!(
+        !lookups.is_empty(),
+        "The lookup gadget needs at least one lookup"
+    );
+    let num_rows = num_lookup_rows(lookups.len());
+    let last = lookups[lookups.len() - 1];
+    let lookups: Vec<_> = lookups
+        .iter()
+        .copied()
+        .chain(std::iter::repeat(last))
+        .take(num_rows * LOOKUPS_PER_ROW)
+        .collect();
+
+    let lookup_witness: [Vec<F>; COLUMNS] = array::from_fn(|col| match col {
+        0 => vec![table_id; num_rows],
+        1..=6 => {
+            let (slot, second) = ((col - 1) / 2, col % 2 == 0);
+            (0..num_rows)
+                .map(|row| {
+                    let (first_value, second_value) = lookups[row * LOOKUPS_PER_ROW + slot];
+                    if second {
+                        second_value
+                    } else {
+                        first_value
+                    }
+                })
+                .collect()
+        }
+        _ => vec![F::zero(); num_rows],
+    });
+    for col in 0..COLUMNS {
+        witness[col].extend(lookup_witness[col].iter());
+    }
+}
+
+/// Extends the vector lookup rows to the full witness
+/// Input
+/// - witness: full witness of the circuit
+/// - table_id: the ID of the table
+/// - lookups: the tuples to look up in the table, of up to 6 values each
+/// Note: tuples shorter than 6 values are padded with zeros, as the entries of narrower tables are.
+/// Panics if `lookups` is empty, or if a tuple has more than 6 values.
+pub fn extend_vector_lookup_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    table_id: i32,
+    lookups: &[Vec<F>],
+) {
+    assert!(
+        !lookups.is_empty(),
+        "The vector lookup gadget needs at least one lookup"
+    );
+    assert!(
+        lookups
+            .iter()
+            .all(|lookup| lookup.len() <= VECTOR_LOOKUP_WIDTH),
+        "The vector lookup gadget looks up tuples of at most 6 values"
+    );
+    let table_id: F = i32_to_field(table_id);
+
+    let lookup_witness: [Vec<F>; COLUMNS] = array::from_fn(|col| match col {
+        0 => vec![table_id; lookups.len() + 1],
+        1..=VECTOR_LOOKUP_WIDTH => std::iter::once(F::zero())
+            .chain(
+                lookups
+                    .iter()
+                    .map(|lookup| lookup.get(col - 1).copied().unwrap_or_else(F::zero)),
+            )
+            .collect(),
+        _ => vec![F::zero(); lookups.len() + 1],
+    });
+    for col in 0..COLUMNS {
+        witness[col].extend(lookup_witness[col].iter());
+    }
+}
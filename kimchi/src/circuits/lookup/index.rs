@@ -4,7 +4,7 @@ use crate::circuits::{
     gate::CircuitGate,
     lookup::{
         constraints::LookupConfiguration,
-        lookups::{LookupInfo, LookupPattern},
+        lookups::{LookupArgument, LookupInfo, LookupPattern},
         tables::LookupTable,
     },
     polynomials::permutation::ZK_ROWS,
@@ -14,11 +14,12 @@ use ark_poly::{
     univariate::DensePolynomial as DP, EvaluationDomain, Evaluations as E,
     Radix2EvaluationDomain as D,
 };
+use blake2::{Blake2b512, Digest};
 use itertools::repeat_n;
-use o1_utils::field_helpers::i32_to_field;
+use o1_utils::{field_helpers::i32_to_field, FieldHelpers};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_with::serde_as;
-use std::iter;
+use std::{iter, ops::Range};
 use thiserror::Error;
 
 /// Represents an error found when computing the lookup constraint system
@@ -33,6 +34,17 @@ pub enum LookupError {
     },
     #[error("The table with id 0 must have an entry of all zeros")]
     TableIDZeroMustHaveZeroEntry,
+    #[error("The table with id {0} has no entries")]
+    EmptyTable(i32),
+    #[error("The table with id {0} is registered more than once with different entries")]
+    TableIDCollision(i32),
+    #[error("The combined lookup table does not fit in the rows reserved for it. Observed: {length}, reserved: {rows:?}")]
+    LookupTableOutOfRows { length: usize, rows: Range<usize> },
+    #[error("The rows reserved for the lookup tables {rows:?} collide with the last rows of the domain, from row {first_unusable_row}")]
+    LookupTableRowsOutOfDomain {
+        rows: Range<usize>,
+        first_unusable_row: usize,
+    },
 }
 
 /// Lookup selectors
@@ -46,6 +58,18 @@ pub struct LookupSelectors<T> {
     pub range_check: Option<T>,
     /// Foreign field multiplication pattern lookup selector
     pub ffmul: Option<T>,
+    /// Vector lookup pattern lookup selector
+    #[serde(default)]
+    pub vector_lookup: Option<T>,
+    /// Byte range check pattern lookup selector
+    #[serde(default)]
+    pub range_check_u8: Option<T>,
+    /// 16-bit range check pattern lookup selector
+    #[serde(default)]
+    pub range_check_u16: Option<T>,
+    /// 64-bit modular addition pattern lookup selector
+    #[serde(default)]
+    pub add_u64: Option<T>,
 }
 
 #[serde_as]
@@ -59,6 +83,18 @@ struct LookupSelectorsSerdeAs<F: FftField> {
     pub range_check: Option<E<F, D<F>>>,
     #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
     pub ffmul: Option<E<F, D<F>>>,
+    #[serde(default)]
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub vector_lookup: Option<E<F, D<F>>>,
+    #[serde(default)]
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub range_check_u8: Option<E<F, D<F>>>,
+    #[serde(default)]
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub range_check_u16: Option<E<F, D<F>>>,
+    #[serde(default)]
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    pub add_u64: Option<E<F, D<F>>>,
 }
 
 impl<F: FftField> serde_with::SerializeAs<LookupSelectors<E<F, D<F>>>>
@@ -73,6 +109,10 @@ impl<F: FftField> serde_with::SerializeAs<LookupSelectors<E<F, D<F>>>>
             lookup: val.lookup.clone(),
             range_check: val.range_check.clone(),
             ffmul: val.ffmul.clone(),
+            vector_lookup: val.vector_lookup.clone(),
+            range_check_u8: val.range_check_u8.clone(),
+            range_check_u16: val.range_check_u16.clone(),
+            add_u64: val.add_u64.clone(),
         };
         repr.serialize(serializer)
     }
@@ -90,12 +130,20 @@ impl<'de, F: FftField> serde_with::DeserializeAs<'de, LookupSelectors<E<F, D<F>>
             lookup,
             range_check,
             ffmul,
+            vector_lookup,
+            range_check_u8,
+            range_check_u16,
+            add_u64,
         } = LookupSelectorsSerdeAs::deserialize(deserializer)?;
         Ok(LookupSelectors {
             xor,
             lookup,
             range_check,
             ffmul,
+            vector_lookup,
+            range_check_u8,
+            range_check_u16,
+            add_u64,
         })
     }
 }
@@ -109,6 +157,10 @@ impl<T> std::ops::Index<LookupPattern> for LookupSelectors<T> {
             LookupPattern::Lookup => &self.lookup,
             LookupPattern::RangeCheck => &self.range_check,
             LookupPattern::ForeignFieldMul => &self.ffmul,
+            LookupPattern::VectorLookup => &self.vector_lookup,
+            LookupPattern::RangeCheckU8 => &self.range_check_u8,
+            LookupPattern::RangeCheckU16 => &self.range_check_u16,
+            LookupPattern::AddU64 => &self.add_u64,
         }
     }
 }
@@ -120,6 +172,10 @@ impl<T> std::ops::IndexMut<LookupPattern> for LookupSelectors<T> {
             LookupPattern::Lookup => &mut self.lookup,
             LookupPattern::RangeCheck => &mut self.range_check,
             LookupPattern::ForeignFieldMul => &mut self.ffmul,
+            LookupPattern::VectorLookup => &mut self.vector_lookup,
+            LookupPattern::RangeCheckU8 => &mut self.range_check_u8,
+            LookupPattern::RangeCheckU16 => &mut self.range_check_u16,
+            LookupPattern::AddU64 => &mut self.add_u64,
         }
     }
 }
@@ -131,6 +187,10 @@ impl<T> LookupSelectors<T> {
             lookup,
             range_check,
             ffmul,
+            vector_lookup,
+            range_check_u8,
+            range_check_u16,
+            add_u64,
         } = self;
         // This closure isn't really redundant -- it shields the parameter from a copy -- but
         // clippy isn't smart enough to figure that out..
@@ -141,6 +201,10 @@ impl<T> LookupSelectors<T> {
             lookup: lookup.map(f),
             range_check: range_check.map(f),
             ffmul: ffmul.map(f),
+            vector_lookup: vector_lookup.map(f),
+            range_check_u8: range_check_u8.map(f),
+            range_check_u16: range_check_u16.map(f),
+            add_u64: add_u64.map(f),
         }
     }
 
@@ -150,6 +214,10 @@ impl<T> LookupSelectors<T> {
             lookup: self.lookup.as_ref(),
             range_check: self.range_check.as_ref(),
             ffmul: self.ffmul.as_ref(),
+            vector_lookup: self.vector_lookup.as_ref(),
+            range_check_u8: self.range_check_u8.as_ref(),
+            range_check_u16: self.range_check_u16.as_ref(),
+            add_u64: self.add_u64.as_ref(),
         }
     }
 }
@@ -191,10 +259,85 @@ pub struct LookupConstraintSystem<F: FftField> {
     /// Configuration for the lookup constraint.
     #[serde(bound = "LookupConfiguration<F>: Serialize + DeserializeOwned")]
     pub configuration: LookupConfiguration<F>,
+
+    /// The digest of the inputs the lookup constraint system was created from, see [inputs_digest].
+    /// It allows to reuse a precomputed lookup constraint system for the same circuit.
+    #[serde(default)]
+    pub inputs_digest: Vec<u8>,
+}
+
+/// Computes the digest of the inputs of [LookupConstraintSystem::create]:
+/// the types of the gates, the lookup tables, the configuration of the runtime tables,
+/// the lookup argument and the rows reserved for the tables, for a domain of the size of `domain`.
+/// Two circuits with the same digest have the same lookup constraint system.
+pub fn inputs_digest<F: PrimeField>(
+    gates: &[CircuitGate<F>],
+    lookup_tables: &[LookupTable<F>],
+    runtime_tables: Option<&[RuntimeTableCfg<F>]>,
+    argument: LookupArgument,
+    table_rows: Option<&Range<usize>>,
+    domain: &EvaluationDomains<F>,
+) -> Vec<u8> {
+    fn absorb_column<F: PrimeField>(hasher: &mut Blake2b512, column: &[F]) {
+        hasher.update((column.len() as u64).to_le_bytes());
+        for x in column {
+            hasher.update(x.to_bytes());
+        }
+    }
+
+    let mut hasher = Blake2b512::new();
+
+    hasher.update(domain.d1.size().to_le_bytes());
+    hasher.update([argument as u8]);
+    for gate in gates {
+        hasher.update([gate.typ as u8]);
+    }
+
+    hasher.update((lookup_tables.len() as u64).to_le_bytes());
+    for table in lookup_tables {
+        hasher.update(table.id.to_le_bytes());
+        hasher.update((table.data.len() as u64).to_le_bytes());
+        for column in &table.data {
+            absorb_column(&mut hasher, column);
+        }
+    }
+
+    match runtime_tables {
+        None => hasher.update([0u8]),
+        Some(runtime_tables) => {
+            hasher.update([1u8]);
+            hasher.update((runtime_tables.len() as u64).to_le_bytes());
+            for runtime_table in runtime_tables {
+                match runtime_table {
+                    RuntimeTableCfg::Indexed(RuntimeTableSpec { id, len }) => {
+                        hasher.update([0u8]);
+                        hasher.update(id.to_le_bytes());
+                        hasher.update((*len as u64).to_le_bytes());
+                    }
+                    RuntimeTableCfg::Custom { id, first_column } => {
+                        hasher.update([1u8]);
+                        hasher.update(id.to_le_bytes());
+                        absorb_column(&mut hasher, first_column);
+                    }
+                }
+            }
+        }
+    }
+
+    // the default placement of the tables is not absorbed,
+    // so that the digests of the circuits without reserved rows are unchanged
+    if let Some(rows) = table_rows {
+        hasher.update((rows.start as u64).to_le_bytes());
+        hasher.update((rows.end as u64).to_le_bytes());
+    }
+
+    hasher.finalize().to_vec()
 }
 
 impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
-    /// Create the `LookupConstraintSystem`.
+    /// Create the `LookupConstraintSystem`, proving the lookups with the given `argument`.
+    /// The concatenated lookup table is placed in the rows `table_rows` of the domain if given,
+    /// otherwise it starts at the first row.
     ///
     /// # Errors
     ///
@@ -203,12 +346,25 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
         gates: &[CircuitGate<F>],
         lookup_tables: Vec<LookupTable<F>>,
         runtime_tables: Option<Vec<RuntimeTableCfg<F>>>,
+        argument: LookupArgument,
+        table_rows: Option<Range<usize>>,
         domain: &EvaluationDomains<F>,
     ) -> Result<Option<Self>, LookupError> {
         //~ 1. If no lookup is used in the circuit, do not create a lookup index
         match LookupInfo::create_from_gates(gates, runtime_tables.is_some()) {
             None => Ok(None),
-            Some(lookup_info) => {
+            Some(mut lookup_info) => {
+                lookup_info.features.argument = argument;
+
+                let inputs_digest = inputs_digest(
+                    gates,
+                    &lookup_tables,
+                    runtime_tables.as_deref(),
+                    argument,
+                    table_rows.as_ref(),
+                    domain,
+                );
+
                 let d1_size = domain.d1.size();
 
                 // The maximum number of entries that can be provided across all tables.
@@ -217,6 +373,15 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                 // product is 1, we cannot use those rows to store any values.
                 let max_num_entries = d1_size - (ZK_ROWS as usize) - 1;
 
+                // The rows reserved for the concatenated table must not collide with those rows.
+                let table_rows = table_rows.unwrap_or(0..max_num_entries);
+                if table_rows.end > max_num_entries {
+                    return Err(LookupError::LookupTableRowsOutOfDomain {
+                        rows: table_rows,
+                        first_unusable_row: max_num_entries,
+                    });
+                }
+
                 //~ 2. Get the lookup selectors and lookup tables (TODO: how?)
                 let (lookup_selectors, gate_lookup_tables) =
                     lookup_info.selector_polynomials_and_tables(domain, gates);
@@ -227,13 +392,27 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                     .chain(lookup_tables.into_iter())
                     .collect();
 
+                //~ 4. Check that every table has entries, and that tables sharing an ID are identical
+                //~    (a custom table can not extend a built-in table, or another custom table).
+                for (i, table) in lookup_tables.iter().enumerate() {
+                    if table.data.is_empty() || table.data[0].is_empty() {
+                        return Err(LookupError::EmptyTable(table.id));
+                    }
+                    if lookup_tables[..i]
+                        .iter()
+                        .any(|other| other.id == table.id && other != table)
+                    {
+                        return Err(LookupError::TableIDCollision(table.id));
+                    }
+                }
+
                 let mut has_table_id_0 = false;
 
                 // if we are using runtime tables
                 let (runtime_table_offset, runtime_selector) =
                     if let Some(runtime_tables) = &runtime_tables {
                         // save the offset of the end of the table
-                        let mut runtime_table_offset = 0;
+                        let mut runtime_table_offset = table_rows.start;
                         for table in &lookup_tables {
                             runtime_table_offset += table.len();
                         }
@@ -298,7 +477,7 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                         (None, None)
                     };
 
-                //~ 4. Get the highest number of columns `max_table_width`
+                //~ 5. Get the highest number of columns `max_table_width`
                 //~    that a lookup table can have.
                 let max_table_width = lookup_tables
                     .iter()
@@ -306,7 +485,7 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                     .max()
                     .unwrap_or(0);
 
-                //~ 5. Create the concatenated table of all the fixed lookup tables.
+                //~ 6. Create the concatenated table of all the fixed lookup tables.
                 //~    It will be of height the size of the domain,
                 //~    and of width the maximum width of any of the lookup tables.
                 //~    In addition, create an additional column to store all the tables' table IDs.
@@ -353,6 +532,12 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                 let mut non_zero_table_id = false;
                 let mut has_table_id_0_with_zero_entry = false;
 
+                // the rows before the rows reserved for the tables hold the dummy value
+                lookup_table
+                    .iter_mut()
+                    .for_each(|col| col.extend(repeat_n(F::zero(), table_rows.start)));
+                table_ids.extend(repeat_n(F::zero(), table_rows.start));
+
                 for table in &lookup_tables {
                     let table_len = table.data[0].len();
 
@@ -397,16 +582,23 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                         maximum_allowed: max_num_entries - 1,
                     });
                 }
+                if lookup_table[0].len() > table_rows.end {
+                    return Err(LookupError::LookupTableOutOfRows {
+                        length: lookup_table[0].len() - table_rows.start,
+                        rows: table_rows,
+                    });
+                }
 
-                //~ 6. Pad the end of the concatened table with the dummy value.
+                //~ 7. Pad the end of the concatened table with the dummy value.
+                //~    If rows are reserved for the tables, the rows before them are dummy entries too.
                 lookup_table
                     .iter_mut()
                     .for_each(|col| col.extend(repeat_n(F::zero(), max_num_entries - col.len())));
 
-                //~ 7. Pad the end of the table id vector with 0s.
+                //~ 8. Pad the end of the table id vector with 0s.
                 table_ids.extend(repeat_n(F::zero(), max_num_entries - table_ids.len()));
 
-                //~ 8. pre-compute polynomial and evaluation form for the look up tables
+                //~ 9. pre-compute polynomial and evaluation form for the look up tables
                 let mut lookup_table_polys: Vec<DP<F>> = vec![];
                 let mut lookup_table8: Vec<E<F, D<F>>> = vec![];
                 for col in lookup_table {
@@ -416,7 +608,7 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                     lookup_table8.push(eval);
                 }
 
-                //~ 9. pre-compute polynomial and evaluation form for the table IDs,
+                //~ 10. pre-compute polynomial and evaluation form for the table IDs,
                 //~    only if a table with an ID different from zero was used.
                 let (table_ids, table_ids8) = if non_zero_table_id {
                     let table_ids: DP<F> =
@@ -443,6 +635,7 @@ impl<F: PrimeField + SquareRootField> LookupConstraintSystem<F> {
                     runtime_tables,
                     runtime_table_offset,
                     configuration,
+                    inputs_digest,
                 }))
             }
         }
@@ -0,0 +1,315 @@
+//! This module implements the logUp lookup argument, an alternative to plookup
+//! which can be selected with [Builder::lookup_argument](crate::circuits::constraints::Builder::lookup_argument).
+//! It reuses the columns of plookup: the single "sorted" column holds the multiplicities,
+//! and the aggregation column holds the running sum of the argument.
+
+use crate::{
+    circuits::{
+        expr::{prologue::*, Column, ConstantExpr},
+        gate::{CircuitGate, CurrOrNext},
+        lookup::{
+            constraints::{zk_patch, LookupConfiguration, CONSTRAINTS, ZK_ROWS},
+            lookups::{JointLookup, JointLookupValue, LocalPosition, LookupInfo},
+        },
+        wires::COLUMNS,
+    },
+    error::ProverError,
+};
+use ark_ff::{FftField, One, PrimeField, Zero};
+use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain as D};
+use rand::Rng;
+use std::collections::HashMap;
+use CurrOrNext::{Curr, Next};
+
+//~ Instead of sorting the lookups with the table, the logUp argument proves that
+//~ the lookups $f_{i,j}$ (combined with the joint combiner, and padded with the dummy value
+//~ to `max_lookups_per_row` lookups per row) are entries of the table $t$ with the identity
+//~
+//~ $$\sum_{i, j} \frac{1}{\gamma + f_{i,j}} = \sum_i \frac{m_i}{\gamma + t_i}$$
+//~
+//~ where $m_i$ is the number of lookups of the entry $t_i$ (only counted at its first occurrence in the table).
+//~ The prover commits to the multiplicities $m$ in place of the sorted columns,
+//~ and the aggregation polynomial is the running sum $\phi$ of the identity:
+//~
+//~ * $\phi(1) = 0$
+//~ * $\phi(\omega^{i+1}) = \phi(\omega^i) + \sum_j \frac{1}{\gamma + f_{i,j}} - \frac{m_i}{\gamma + t_i}$
+//~ * $\phi(\omega^{n - 4}) = 0$, on the row before the zero-knowledge rows
+//~
+//~ The transition is enforced multiplied by its denominators: with $F_i = \prod_j (\gamma + f_{i,j})$
+//~ and $F'_i = \sum_j \prod_{k \neq j} (\gamma + f_{i,k})$,
+//~
+//~ $$(\phi(\omega x) - \phi(x)) F (\gamma + t) = F' (\gamma + t) - m F$$
+//~
+//~ where $F$ and $F'$ are the sums over the lookup patterns of their selectors times their products,
+//~ as they are for the numerator of plookup.
+//~ This avoids the `max_lookups_per_row + 1` sorted columns of plookup,
+//~ at the cost of a single multiplicity column.
+
+/// Computes the multiplicities of the entries of the table required by the logUp argument.
+///
+/// # Errors
+///
+/// Will give error if a lookup is not an entry of the table.
+#[allow(clippy::too_many_arguments)]
+pub fn multiplicities<F: PrimeField>(
+    dummy_lookup_value: F,
+    joint_lookup_table_d8: &Evaluations<F, D<F>>,
+    d1: D<F>,
+    gates: &[CircuitGate<F>],
+    witness: &[Vec<F>; COLUMNS],
+    joint_combiner: F,
+    table_id_combiner: F,
+    lookup_info: &LookupInfo,
+) -> Result<Vec<F>, ProverError> {
+    let n = d1.size();
+    let lookup_rows = n - ZK_ROWS - 1;
+    let max_lookups_per_row = lookup_info.max_per_row;
+
+    // duplicate entries of the table are only counted at their first occurrence
+    let mut first_rows: HashMap<F, usize> = HashMap::new();
+    for (row, t) in joint_lookup_table_d8
+        .evals
+        .iter()
+        .step_by(8)
+        .take(lookup_rows)
+        .enumerate()
+    {
+        first_rows.entry(*t).or_insert(row);
+    }
+    let dummy_row = *first_rows
+        .get(&dummy_lookup_value)
+        .ok_or(ProverError::ValueNotInTable)?;
+
+    let mut multiplicities = vec![F::zero(); lookup_rows];
+    for (i, spec) in lookup_info
+        .by_row(gates)
+        .iter()
+        .enumerate()
+        // avoid zk rows
+        .take(lookup_rows)
+    {
+        let eval = |pos: LocalPosition| -> F {
+            let row = match pos.row {
+                Curr => i,
+                Next => i + 1,
+            };
+            witness[pos.column][row]
+        };
+        for joint_lookup in spec.iter() {
+            let joint_lookup_evaluation =
+                joint_lookup.evaluate(&joint_combiner, &table_id_combiner, &eval);
+            match first_rows.get(&joint_lookup_evaluation) {
+                None => return Err(ProverError::ValueNotInTable),
+                Some(row) => multiplicities[*row] += F::one(),
+            }
+        }
+        multiplicities[dummy_row] += F::from((max_lookups_per_row - spec.len()) as u64);
+    }
+
+    Ok(multiplicities)
+}
+
+/// Computes the aggregation polynomial of the logUp argument, whose kth entry is the sum of terms
+///
+///  \sum_{0 <= j < n} 1 / (gamma + f_{i,j}) - m_i / (gamma + t_i)
+///
+/// for i < k, where t_i is the ith entry in the table, m_i its multiplicity,
+/// and f_{i,j} is the jth lookup in the ith row of the witness (padded with the dummy lookup).
+///
+/// # Panics
+///
+/// Will panic if final evaluation is not 0.
+#[allow(clippy::too_many_arguments)]
+pub fn aggregation<R, F>(
+    dummy_lookup_value: F,
+    joint_lookup_table_d8: &Evaluations<F, D<F>>,
+    d1: D<F>,
+    gates: &[CircuitGate<F>],
+    witness: &[Vec<F>; COLUMNS],
+    joint_combiner: &F,
+    table_id_combiner: &F,
+    gamma: F,
+    multiplicities: &Evaluations<F, D<F>>,
+    rng: &mut R,
+    lookup_info: &LookupInfo,
+) -> Result<Evaluations<F, D<F>>, ProverError>
+where
+    R: Rng + ?Sized,
+    F: PrimeField,
+{
+    let n = d1.size();
+    let lookup_rows = n - ZK_ROWS - 1;
+    let max_lookups_per_row = lookup_info.max_per_row;
+
+    // the denominators to invert: the dummy lookup,
+    // followed by the table entry and the lookups of each row
+    let mut denominators = vec![gamma + dummy_lookup_value];
+    let mut num_lookups = Vec::with_capacity(lookup_rows);
+    for (i, (t, spec)) in joint_lookup_table_d8
+        .evals
+        .iter()
+        .step_by(8)
+        .zip(lookup_info.by_row(gates))
+        .take(lookup_rows)
+        .enumerate()
+    {
+        let eval = |pos: LocalPosition| -> F {
+            let row = match pos.row {
+                Curr => i,
+                Next => i + 1,
+            };
+            witness[pos.column][row]
+        };
+        denominators.push(gamma + t);
+        denominators.extend(
+            spec.iter()
+                .map(|j| gamma + j.evaluate(joint_combiner, table_id_combiner, &eval)),
+        );
+        num_lookups.push(spec.len());
+    }
+    ark_ff::fields::batch_inversion::<F>(&mut denominators);
+
+    let dummy_inverse = denominators[0];
+    let mut inverses = denominators[1..].iter();
+    let mut lookup_aggreg = Vec::with_capacity(lookup_rows + 1);
+    lookup_aggreg.push(F::zero());
+    for (i, num_lookups) in num_lookups.into_iter().enumerate() {
+        let table_inverse = *inverses.next().expect("one denominator per table entry");
+        let lookups: F = inverses.by_ref().take(num_lookups).sum();
+        let padding = F::from((max_lookups_per_row - num_lookups) as u64) * dummy_inverse;
+        let prev = lookup_aggreg[i];
+        lookup_aggreg.push(prev + lookups + padding - multiplicities[i] * table_inverse);
+    }
+
+    let res = zk_patch(lookup_aggreg, d1, rng);
+
+    // check that the final evaluation is equal to 0
+    if cfg!(debug_assertions) {
+        let final_val = res.evals[d1.size() - (ZK_ROWS + 1)];
+        if !final_val.is_zero() {
+            panic!("aggregation incorrect: {final_val}");
+        }
+    }
+
+    Ok(res)
+}
+
+/// Specifies the constraints of the logUp argument as expressions,
+/// padded to the [CONSTRAINTS] of plookup.
+///
+/// Note that the logUp argument is not supported with feature flags (see [super::constraints::constraints]).
+///
+/// # Panics
+///
+/// Will panic if single `element` length is bigger than `max_per_row` length.
+pub fn constraints<F: FftField>(configuration: &LookupConfiguration<F>) -> Vec<E<F>> {
+    let lookup_info = &configuration.lookup_info;
+
+    let column = |col: Column| E::cell(col, Curr);
+    let gamma = E::<F>::Constant(ConstantExpr::Gamma);
+
+    let joint_combiner = E::Constant(ConstantExpr::JointCombiner);
+    let table_id_combiner = (1..lookup_info.max_joint_size)
+        .fold(joint_combiner.clone(), |acc, _| {
+            acc * joint_combiner.clone()
+        });
+
+    // combine the columns of the dummy lookup row
+    let dummy_lookup = {
+        let expr_dummy: JointLookupValue<E<F>> = JointLookup {
+            entry: configuration
+                .dummy_lookup
+                .entry
+                .iter()
+                .map(|x| E::Constant(ConstantExpr::Literal(*x)))
+                .collect(),
+            table_id: E::Constant(ConstantExpr::Literal(configuration.dummy_lookup.table_id)),
+        };
+        expr_dummy.evaluate(&joint_combiner, &table_id_combiner)
+    };
+
+    // the product of the terms, and the sum of the products of all the terms but one
+    let products = |terms: Vec<E<F>>| {
+        terms.into_iter().fold(
+            (E::one(), E::zero()),
+            |(product, sum): (E<F>, E<F>), term| {
+                (product.clone() * term.clone(), sum * term + product)
+            },
+        )
+    };
+
+    // the terms of a row, padded with the dummy lookup to `max_per_row` lookups
+    let terms = |lookups: Vec<E<F>>| -> Vec<E<F>> {
+        assert!(lookups.len() <= lookup_info.max_per_row);
+        let padding = lookup_info.max_per_row - lookups.len();
+        lookups
+            .into_iter()
+            .chain(std::iter::repeat(gamma.clone() + dummy_lookup.clone()).take(padding))
+            .collect()
+    };
+
+    // to toggle dummy queries when we do not have any lookups in a row
+    // (1 minus the sum of the lookup selectors)
+    let non_lookup_indicator = lookup_info
+        .features
+        .patterns
+        .into_iter()
+        .fold(E::one(), |acc: E<F>, spec| {
+            acc - column(Column::LookupKindIndex(spec))
+        });
+
+    // F and F' of the lookups, summed over the patterns of lookups
+    let (f_product, f_sum) = {
+        let (product, sum) = products(terms(vec![]));
+        let dummy_rows = (
+            non_lookup_indicator.clone() * product,
+            non_lookup_indicator * sum,
+        );
+
+        lookup_info
+            .features
+            .patterns
+            .into_iter()
+            .fold(dummy_rows, |(f_product, f_sum), spec| {
+                let eval = |pos: LocalPosition| witness(pos.column, pos.row);
+                let lookups: Vec<_> = spec
+                    .lookups::<F>()
+                    .iter()
+                    .map(|j| gamma.clone() + j.evaluate(&joint_combiner, &table_id_combiner, &eval))
+                    .collect();
+                let (product, sum) = products(terms(lookups));
+                let selector = column(Column::LookupKindIndex(spec));
+                (
+                    f_product + selector.clone() * product,
+                    f_sum + selector * sum,
+                )
+            })
+    };
+
+    let t_term = gamma + column(Column::LookupTable);
+    let multiplicity = column(Column::LookupSorted(0));
+
+    // (phi(x w) - phi(x)) F (gamma + t) = F' (gamma + t) - m F
+    let aggreg_equation = (E::cell(Column::LookupAggreg, Next) - column(Column::LookupAggreg))
+        * f_product.clone()
+        * t_term.clone()
+        - (f_sum * t_term - multiplicity * f_product);
+
+    let final_lookup_row: i32 = -(ZK_ROWS as i32) - 1;
+
+    let mut res = vec![
+        // the running sum except for the last 4 rows
+        // (contains the zk-rows and the last value of the running sum)
+        E::VanishesOnLast4Rows * aggreg_equation,
+        // the initial value of the running sum
+        E::UnnormalizedLagrangeBasis(0) * column(Column::LookupAggreg),
+        // Check that the final value of the running sum is 0
+        E::UnnormalizedLagrangeBasis(final_lookup_row) * column(Column::LookupAggreg),
+    ];
+
+    // Padding to make sure that the position of the runtime tables constraints is always
+    // consistent.
+    res.extend((res.len()..CONSTRAINTS as usize).map(|_| E::zero()));
+
+    res
+}
@@ -3,8 +3,8 @@ use crate::circuits::{
     gate::{CircuitGate, CurrOrNext, GateType},
     lookup::index::LookupSelectors,
     lookup::tables::{
-        combine_table_entry, get_table, GateLookupTable, LookupTable, RANGE_CHECK_TABLE_ID,
-        XOR_TABLE_ID,
+        combine_table_entry, get_table, GateLookupTable, LookupTable, BYTE_TABLE_ID,
+        RANGE_CHECK_TABLE_ID, U16_TABLE_ID, XOR_TABLE_ID,
     },
 };
 use ark_ff::{Field, One, PrimeField, Zero};
@@ -47,6 +47,14 @@ pub struct LookupPatterns {
     pub lookup: bool,
     pub range_check: bool,
     pub foreign_field_mul: bool,
+    #[serde(default)]
+    pub vector_lookup: bool,
+    #[serde(default)]
+    pub range_check_u8: bool,
+    #[serde(default)]
+    pub range_check_u16: bool,
+    #[serde(default)]
+    pub add_u64: bool,
 }
 
 impl IntoIterator for LookupPatterns {
@@ -60,9 +68,13 @@ impl IntoIterator for LookupPatterns {
             lookup,
             range_check,
             foreign_field_mul,
+            vector_lookup,
+            range_check_u8,
+            range_check_u16,
+            add_u64,
         } = self;
 
-        let mut patterns = Vec::with_capacity(5);
+        let mut patterns = Vec::with_capacity(8);
 
         if xor {
             patterns.push(LookupPattern::Xor)
@@ -76,6 +88,18 @@ impl IntoIterator for LookupPatterns {
         if foreign_field_mul {
             patterns.push(LookupPattern::ForeignFieldMul)
         }
+        if vector_lookup {
+            patterns.push(LookupPattern::VectorLookup)
+        }
+        if range_check_u8 {
+            patterns.push(LookupPattern::RangeCheckU8)
+        }
+        if range_check_u16 {
+            patterns.push(LookupPattern::RangeCheckU16)
+        }
+        if add_u64 {
+            patterns.push(LookupPattern::AddU64)
+        }
         patterns.into_iter()
     }
 }
@@ -89,6 +113,10 @@ impl std::ops::Index<LookupPattern> for LookupPatterns {
             LookupPattern::Lookup => &self.lookup,
             LookupPattern::RangeCheck => &self.range_check,
             LookupPattern::ForeignFieldMul => &self.foreign_field_mul,
+            LookupPattern::VectorLookup => &self.vector_lookup,
+            LookupPattern::RangeCheckU8 => &self.range_check_u8,
+            LookupPattern::RangeCheckU16 => &self.range_check_u16,
+            LookupPattern::AddU64 => &self.add_u64,
         }
     }
 }
@@ -100,6 +128,10 @@ impl std::ops::IndexMut<LookupPattern> for LookupPatterns {
             LookupPattern::Lookup => &mut self.lookup,
             LookupPattern::RangeCheck => &mut self.range_check,
             LookupPattern::ForeignFieldMul => &mut self.foreign_field_mul,
+            LookupPattern::VectorLookup => &mut self.vector_lookup,
+            LookupPattern::RangeCheckU8 => &mut self.range_check_u8,
+            LookupPattern::RangeCheckU16 => &mut self.range_check_u16,
+            LookupPattern::AddU64 => &mut self.add_u64,
         }
     }
 }
@@ -128,6 +160,21 @@ impl LookupPatterns {
     }
 }
 
+/// The argument used to prove that the lookups are entries of the lookup tables.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ocaml_types",
+    derive(ocaml::IntoValue, ocaml::FromValue, ocaml_gen::Enum)
+)]
+pub enum LookupArgument {
+    /// The plookup argument, which sorts the lookups with the table
+    #[default]
+    Plookup,
+    /// The logUp argument, which sums the inverses of the lookups and of the table
+    /// weighted by their multiplicities, see [crate::circuits::lookup::logup]
+    LogUp,
+}
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(
     feature = "ocaml_types",
@@ -140,6 +187,9 @@ pub struct LookupFeatures {
     pub joint_lookup_used: bool,
     /// True if runtime lookup tables are used.
     pub uses_runtime_tables: bool,
+    /// The argument used to prove the lookups
+    #[serde(default)]
+    pub argument: LookupArgument,
 }
 
 impl LookupFeatures {
@@ -152,6 +202,7 @@ impl LookupFeatures {
             patterns,
             uses_runtime_tables,
             joint_lookup_used,
+            argument: LookupArgument::default(),
         }
     }
 }
@@ -182,6 +233,15 @@ impl LookupInfo {
         }
     }
 
+    /// The number of columns committed to before the aggregation polynomial:
+    /// the sorted columns of plookup, or the multiplicities of logUp.
+    pub fn num_sorted(&self) -> usize {
+        match self.features.argument {
+            LookupArgument::Plookup => self.max_per_row + 1,
+            LookupArgument::LogUp => 1,
+        }
+    }
+
     pub fn create_from_gates<F: PrimeField>(
         gates: &[CircuitGate<F>],
         uses_runtime_tables: bool,
@@ -386,15 +446,24 @@ pub enum LookupPattern {
     Lookup,
     RangeCheck,
     ForeignFieldMul,
+    VectorLookup,
+    RangeCheckU8,
+    RangeCheckU16,
+    AddU64,
 }
 
 impl LookupPattern {
     /// Returns the maximum number of lookups per row that are used by the pattern.
     pub fn max_lookups_per_row(&self) -> usize {
         match self {
-            LookupPattern::Xor | LookupPattern::RangeCheck => 4,
+            LookupPattern::Xor
+            | LookupPattern::RangeCheck
+            | LookupPattern::RangeCheckU8
+            | LookupPattern::RangeCheckU16
+            | LookupPattern::AddU64 => 4,
             LookupPattern::Lookup => 3,
             LookupPattern::ForeignFieldMul => 2,
+            LookupPattern::VectorLookup => 1,
         }
     }
 
@@ -402,8 +471,13 @@ impl LookupPattern {
     pub fn max_joint_size(&self) -> u32 {
         match self {
             LookupPattern::Xor => 3,
+            LookupPattern::VectorLookup => 6,
             LookupPattern::Lookup => 2,
-            LookupPattern::ForeignFieldMul | LookupPattern::RangeCheck => 1,
+            LookupPattern::ForeignFieldMul
+            | LookupPattern::RangeCheck
+            | LookupPattern::RangeCheckU8
+            | LookupPattern::RangeCheckU16
+            | LookupPattern::AddU64 => 1,
         }
     }
 
@@ -496,6 +570,65 @@ impl LookupPattern {
                     },
                 ]
             }
+            LookupPattern::VectorLookup => {
+                // 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14
+                // i v v v v v v - - - -  -  -  -  -
+                //
+                // the values are wired columns, so that they can be copied from other gates
+                vec![JointLookup {
+                    table_id: LookupTableID::WitnessColumn(0),
+                    entry: (1..=6)
+                        .map(|column| SingleLookup {
+                            value: vec![(F::one(), curr_row(column))],
+                        })
+                        .collect(),
+                }]
+            }
+            LookupPattern::RangeCheckU8 | LookupPattern::RangeCheckU16 => {
+                let (table_id, limb_bits) = if *self == LookupPattern::RangeCheckU8 {
+                    (BYTE_TABLE_ID, 8)
+                } else {
+                    (U16_TABLE_ID, 16)
+                };
+                let next_row = |column| LocalPosition {
+                    row: CurrOrNext::Next,
+                    column,
+                };
+                // 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14
+                // v v v v - - - - - - -  -  -  -  -
+                // v - - - - - - - - - -  -  -  -  -
+                //
+                // the limbs are the differences of consecutive values,
+                // the last value being the first one of the next row
+                (0..4)
+                    .map(|i| {
+                        let next = if i < 3 { curr_row(i + 1) } else { next_row(0) };
+                        JointLookup {
+                            table_id: LookupTableID::Constant(table_id),
+                            entry: vec![SingleLookup {
+                                value: vec![
+                                    (F::one(), curr_row(i)),
+                                    (-F::from(2u64).pow([limb_bits]), next),
+                                ],
+                            }],
+                        }
+                    })
+                    .collect()
+            }
+            LookupPattern::AddU64 => {
+                (7..=10)
+                    .map(|column| {
+                        //   0 1 2 3 4 5 6 7 8 9 10 11 12 13 14
+                        //   - - - - - - - L L L L  -  -  -  -
+                        JointLookup {
+                            table_id: LookupTableID::Constant(U16_TABLE_ID),
+                            entry: vec![SingleLookup {
+                                value: vec![(F::one(), curr_row(column))],
+                            }],
+                        }
+                    })
+                    .collect()
+            }
         }
     }
 
@@ -503,9 +636,11 @@ impl LookupPattern {
     pub fn table(&self) -> Option<GateLookupTable> {
         match self {
             LookupPattern::Xor => Some(GateLookupTable::Xor),
-            LookupPattern::Lookup => None,
+            LookupPattern::Lookup | LookupPattern::VectorLookup => None,
             LookupPattern::RangeCheck => Some(GateLookupTable::RangeCheck),
             LookupPattern::ForeignFieldMul => Some(GateLookupTable::RangeCheck),
+            LookupPattern::RangeCheckU8 => Some(GateLookupTable::Byte),
+            LookupPattern::RangeCheckU16 | LookupPattern::AddU64 => Some(GateLookupTable::U16),
         }
     }
 
@@ -515,6 +650,10 @@ impl LookupPattern {
         use GateType::*;
         match (gate_type, curr_or_next) {
             (Lookup, Curr) => Some(LookupPattern::Lookup),
+            (VectorLookup, Curr) => Some(LookupPattern::VectorLookup),
+            (RangeCheckU8, Curr) => Some(LookupPattern::RangeCheckU8),
+            (RangeCheckU16, Curr) => Some(LookupPattern::RangeCheckU16),
+            (AddU64, Curr) => Some(LookupPattern::AddU64),
             (RangeCheck0, Curr) | (RangeCheck1, Curr | Next) | (Rot64, Curr) => {
                 Some(LookupPattern::RangeCheck)
             }
@@ -533,6 +672,10 @@ impl GateType {
             LookupPattern::Lookup,
             LookupPattern::RangeCheck,
             LookupPattern::ForeignFieldMul,
+            LookupPattern::VectorLookup,
+            LookupPattern::RangeCheckU8,
+            LookupPattern::RangeCheckU16,
+            LookupPattern::AddU64,
         ]
     }
 }
@@ -2,7 +2,9 @@
 //! See <https://eprint.iacr.org/2020/315.pdf>
 
 pub mod constraints;
+pub mod gadget;
 pub mod index;
+pub mod logup;
 pub mod lookups;
 pub mod runtime_tables;
 pub mod tables;
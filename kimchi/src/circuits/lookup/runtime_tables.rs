@@ -8,6 +8,7 @@ use crate::circuits::{
 };
 use ark_ff::Field;
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 
 /// The specification of a runtime table.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,7 +22,9 @@ pub struct RuntimeTableSpec {
 /// Use this type at setup time, to list all the runtime tables.
 ///
 /// Note: care must be taken as table IDs can collide with IDs of other types of lookup tables.
+#[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "F: ark_serialize::CanonicalSerialize + ark_serialize::CanonicalDeserialize")]
 pub enum RuntimeTableCfg<F> {
     /// An indexed runtime table has a counter (starting at zero) in its first column.
     Indexed(RuntimeTableSpec),
@@ -30,6 +33,7 @@ pub enum RuntimeTableCfg<F> {
         /// The table ID.
         id: i32,
         /// The content of the first column of the runtime table.
+        #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
         first_column: Vec<F>,
     },
 }
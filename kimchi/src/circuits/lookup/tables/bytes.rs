@@ -0,0 +1,35 @@
+//! Byte and 16-bit range check tables
+
+//~ The byte table is a single-column table containing the numbers from 0 to 2^8 (excluded),
+//~ and the 16-bit table is a single-column table containing the numbers from 0 to 2^16 (excluded).
+//~ They are used to check that values fit in 8 or 16 bits, for the gadgets operating on bytes.
+//~ Note that the 16-bit table needs a domain of at least 2^17 rows.
+
+use crate::circuits::lookup::tables::{LookupTable, BYTE_TABLE_ID, U16_TABLE_ID};
+use ark_ff::Field;
+
+/// The byte check is performed on 8-bit values, i.e. those in `[0, 2^8)`
+pub const BYTE_UPPERBOUND: u32 = 1 << 8;
+
+/// The 16-bit check is performed on 16-bit values, i.e. those in `[0, 2^16)`
+pub const U16_UPPERBOUND: u32 = 1 << 16;
+
+/// A single-column table containing the numbers from 0 to [`BYTE_UPPERBOUND`] (exclusive)
+pub fn byte_table<F: Field>() -> LookupTable<F> {
+    LookupTable {
+        id: BYTE_TABLE_ID,
+        data: vec![(0..BYTE_UPPERBOUND).map(F::from).collect()],
+    }
+}
+
+/// A single-column table containing the numbers from 0 to [`U16_UPPERBOUND`] (exclusive)
+pub fn u16_table<F: Field>() -> LookupTable<F> {
+    LookupTable {
+        id: U16_TABLE_ID,
+        data: vec![(0..U16_UPPERBOUND).map(F::from).collect()],
+    }
+}
+
+pub const BYTE_TABLE_SIZE: usize = BYTE_UPPERBOUND as usize;
+
+pub const U16_TABLE_SIZE: usize = U16_UPPERBOUND as usize;
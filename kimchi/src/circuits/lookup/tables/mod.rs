@@ -1,7 +1,9 @@
 use ark_ff::{FftField, One, Zero};
 use poly_commitment::PolyComm;
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 
+pub mod bytes;
 pub mod range_check;
 pub mod xor;
 
@@ -11,6 +13,12 @@ pub const XOR_TABLE_ID: i32 = 0;
 
 /// The range check table ID.
 pub const RANGE_CHECK_TABLE_ID: i32 = 1;
+
+/// The byte range check table ID.
+pub const BYTE_TABLE_ID: i32 = 2;
+
+/// The 16-bit range check table ID.
+pub const U16_TABLE_ID: i32 = 3;
 //~ spec:endcode
 
 /// Enumerates the different 'fixed' lookup tables used by individual gates
@@ -18,12 +26,28 @@ pub const RANGE_CHECK_TABLE_ID: i32 = 1;
 pub enum GateLookupTable {
     Xor,
     RangeCheck,
+    Byte,
+    U16,
 }
 
+/// The fixed lookup tables used by the gates, in the order of their IDs
+pub const GATE_LOOKUP_TABLES: [GateLookupTable; 4] = [
+    GateLookupTable::Xor,
+    GateLookupTable::RangeCheck,
+    GateLookupTable::Byte,
+    GateLookupTable::U16,
+];
+
 /// A table of values that can be used for a lookup, along with the ID for the table.
-#[derive(Debug, Clone)]
+/// The values are serialized as hexadecimal strings in human-readable formats,
+/// so that a table can be exported to check the lookups of a witness outside of the prover.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "F: ark_serialize::CanonicalSerialize + ark_serialize::CanonicalDeserialize")]
 pub struct LookupTable<F> {
     pub id: i32,
+    /// The columns of the table
+    #[serde_as(as = "Vec<Vec<o1_utils::serialization::SerdeAs>>")]
     pub data: Vec<Vec<F>>,
 }
 
@@ -56,6 +80,27 @@ where
     pub fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
+
+    /// Returns the number of columns of the table, which is the length of its entries.
+    pub fn width(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the entry of the table at `row`, with a value per column.
+    pub fn entry(&self, row: usize) -> Vec<F> {
+        self.data.iter().map(|col| col[row]).collect()
+    }
+
+    /// Returns the entries of the table, in the order of its rows.
+    pub fn entries(&self) -> impl Iterator<Item = Vec<F>> + '_ {
+        (0..self.len()).map(|row| self.entry(row))
+    }
+
+    /// Return true if the table has the entry, with a linear search of its rows.
+    pub fn contains(&self, entry: &[F]) -> bool {
+        entry.len() == self.width()
+            && (0..self.len()).any(|row| self.data.iter().zip(entry).all(|(col, v)| col[row] == *v))
+    }
 }
 
 /// Returns the lookup table associated to a [`GateLookupTable`].
@@ -63,15 +108,37 @@ pub fn get_table<F: FftField>(table_name: GateLookupTable) -> LookupTable<F> {
     match table_name {
         GateLookupTable::Xor => xor::xor_table(),
         GateLookupTable::RangeCheck => range_check::range_check_table(),
+        GateLookupTable::Byte => bytes::byte_table(),
+        GateLookupTable::U16 => bytes::u16_table(),
     }
 }
 
+/// Returns the fixed lookup tables used by the gates, in the order of [`GATE_LOOKUP_TABLES`].
+pub fn gate_lookup_tables<F: FftField>() -> Vec<LookupTable<F>> {
+    GATE_LOOKUP_TABLES
+        .iter()
+        .map(|table| get_table(*table))
+        .collect()
+}
+
 impl GateLookupTable {
+    /// Returns the ID of the lookup table associated to a [`GateLookupTable`].
+    pub fn id(&self) -> i32 {
+        match self {
+            GateLookupTable::Xor => XOR_TABLE_ID,
+            GateLookupTable::RangeCheck => RANGE_CHECK_TABLE_ID,
+            GateLookupTable::Byte => BYTE_TABLE_ID,
+            GateLookupTable::U16 => U16_TABLE_ID,
+        }
+    }
+
     /// Returns the lookup table associated to a [`GateLookupTable`].
     pub fn table_size(&self) -> usize {
         match self {
             GateLookupTable::Xor => xor::TABLE_SIZE,
             GateLookupTable::RangeCheck => range_check::TABLE_SIZE,
+            GateLookupTable::Byte => bytes::BYTE_TABLE_SIZE,
+            GateLookupTable::U16 => bytes::U16_TABLE_SIZE,
         }
     }
 }
@@ -2,15 +2,25 @@
 pub mod macros;
 
 pub mod argument;
+pub mod builder;
+pub mod compiled;
 pub mod constraints;
+pub mod custom_gate;
 pub mod domain_constant_evaluation;
 pub mod domains;
 pub mod expr;
 pub mod gate;
 pub mod lookup;
+pub mod optimizer;
+pub mod packing;
 pub mod polynomial;
 pub mod polynomials;
+pub mod public_input;
 pub mod scalars;
+pub mod scheduler;
 mod serialization_helper;
+pub mod stats;
+pub mod sub_circuit;
+pub mod template;
 pub mod wires;
 pub mod witness;
@@ -0,0 +1,199 @@
+//! This module implements the optimization of the gates of a circuit before building its constraint system,
+//! to reduce the number of rows (and the domain size) of machine-generated circuits.
+//!
+//! The optimizer:
+//! - removes the `Zero` gates, which do not constrain their row, unless a multi-row gate
+//!   constrains them as its next row (the row before them must be a `Generic` or `Zero` gate),
+//! - removes the copy gates, the single generic gates whose only operation asserts that two of their cells
+//!   are equal (as `l - o = 0`), since the copy constraints of the permutation already check it:
+//!   the cells copied to one of these cells are copied to the other one instead,
+//! - removes the cells of the removed rows from the copy constraints, keeping the copies between the other cells,
+//! - packs the remaining single generic gates two per row (see [Packing]).
+//!
+//! The public input rows are kept, and the gates are kept in order.
+//! The returned [Optimization] lays out the witness of the original circuit in the optimized circuit.
+
+use crate::circuits::{
+    gate::{CircuitGate, GateType},
+    packing::Packing,
+    polynomial::COLUMNS,
+    polynomials::generic::GENERIC_REGISTERS,
+    wires::{Wire, PERMUTS},
+};
+use ark_ff::PrimeField;
+use std::array;
+
+/// The position of the rows of a circuit in its optimized circuit, see [Optimization::create]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Optimization {
+    // for each row of the original circuit, its row in the circuit without the removed gates
+    rows: Vec<Option<usize>>,
+    // the packing of the single generic gates of the circuit without the removed gates
+    packing: Packing,
+}
+
+// Returns the two cells of a copy gate, asserting their equality
+fn copied_cells<F: PrimeField>(gate: &CircuitGate<F>, row: usize) -> Option<(Wire, Wire)> {
+    // the multiplication, the constant and the second operation are unused
+    if gate.typ != GateType::Generic || !gate.coeffs.iter().skip(GENERIC_REGISTERS).all(F::is_zero)
+    {
+        return None;
+    }
+    let coeff = |col: usize| gate.coeffs.get(col).copied().unwrap_or_else(F::zero);
+    let cols: Vec<usize> = (0..GENERIC_REGISTERS)
+        .filter(|col| !coeff(*col).is_zero())
+        .collect();
+    match cols[..] {
+        [a, b] if (coeff(a) + coeff(b)).is_zero() => {
+            Some((Wire { row, col: a }, Wire { row, col: b }))
+        }
+        _ => None,
+    }
+}
+
+// The root of a cell in the union of the copy constraints
+pub(crate) fn find(parents: &mut [usize], mut cell: usize) -> usize {
+    while parents[cell] != cell {
+        parents[cell] = parents[parents[cell]];
+        cell = parents[cell];
+    }
+    cell
+}
+
+pub(crate) fn union(parents: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (find(parents, a), find(parents, b));
+    parents[a] = b;
+}
+
+impl Optimization {
+    /// Optimizes a circuit whose first `public` rows are public inputs.
+    /// Returns the optimized circuit, and the position of the rows of the original circuit in it.
+    pub fn create<F: PrimeField>(
+        gates: &[CircuitGate<F>],
+        public: usize,
+    ) -> (Vec<CircuitGate<F>>, Self) {
+        Self::create_with_seed(gates, public, 0)
+    }
+
+    /// Optimizes a circuit as [Self::create] does,
+    /// packing its single generic gates with the layout `seed` (see [Packing::create_with_seed]).
+    pub fn create_with_seed<F: PrimeField>(
+        gates: &[CircuitGate<F>],
+        public: usize,
+        seed: u64,
+    ) -> (Vec<CircuitGate<F>>, Self) {
+        // the removed rows, and the cells asserted equal by the removed copy gates
+        let mut copies = vec![];
+        let removed: Vec<bool> = gates
+            .iter()
+            .enumerate()
+            .map(|(row, gate)| {
+                // no gate constrains the row as its next row
+                let removable = row >= public
+                    && (row == 0
+                        || matches!(gates[row - 1].typ, GateType::Generic | GateType::Zero));
+                if !removable {
+                    return false;
+                }
+                if gate.typ == GateType::Zero {
+                    return true;
+                }
+                match copied_cells(gate, row) {
+                    Some(pair) => {
+                        copies.push(pair);
+                        true
+                    }
+                    None => false,
+                }
+            })
+            .collect();
+
+        let mut rows = Vec::with_capacity(gates.len());
+        let mut num_rows = 0;
+        for removed in &removed {
+            if *removed {
+                rows.push(None);
+            } else {
+                rows.push(Some(num_rows));
+                num_rows += 1;
+            }
+        }
+
+        // the sets of cells constrained equal, by the permutation and by the copy gates
+        let index = |wire: Wire| wire.row * PERMUTS + wire.col;
+        let mut parents: Vec<usize> = (0..gates.len() * PERMUTS).collect();
+        for (row, gate) in gates.iter().enumerate() {
+            for (col, wire) in gate.wires.iter().enumerate() {
+                union(&mut parents, index(Wire { row, col }), index(*wire));
+            }
+        }
+        for (a, b) in copies {
+            union(&mut parents, index(a), index(b));
+        }
+        let roots: Vec<usize> = (0..parents.len())
+            .map(|cell| find(&mut parents, cell))
+            .collect();
+
+        // the sets with a cell in a removed row are wired again, the others are kept as they are
+        let mut rewired = vec![false; parents.len()];
+        for row in (0..gates.len()).filter(|row| removed[*row]) {
+            for col in 0..PERMUTS {
+                rewired[roots[index(Wire { row, col })]] = true;
+            }
+        }
+
+        let cell = |wire: Wire| rows[wire.row].map(|row| Wire { row, col: wire.col });
+        let mut reduced: Vec<CircuitGate<F>> = Vec::with_capacity(num_rows);
+        let mut cycles: Vec<Vec<Wire>> = vec![vec![]; parents.len()];
+        for (row, gate) in gates.iter().enumerate().filter(|(row, _)| !removed[*row]) {
+            let wires = array::from_fn(|col| {
+                let root = roots[index(Wire { row, col })];
+                if rewired[root] {
+                    cycles[root].push(cell(Wire { row, col }).unwrap());
+                }
+                // the sets that are kept have no cell in a removed row
+                cell(gate.wires[col]).unwrap_or(Wire { row, col })
+            });
+            reduced.push(CircuitGate::new(gate.typ, wires, gate.coeffs.clone()));
+        }
+        for cycle in cycles.iter().filter(|cycle| !cycle.is_empty()) {
+            for (i, wire) in cycle.iter().enumerate() {
+                reduced[wire.row].wires[wire.col] = cycle[(i + 1) % cycle.len()];
+            }
+        }
+
+        let (optimized, packing) = Packing::create_with_seed(&reduced, public, seed);
+        (optimized, Optimization { rows, packing })
+    }
+
+    /// The number of rows of the optimized circuit
+    pub fn num_rows(&self) -> usize {
+        self.packing.num_rows()
+    }
+
+    /// The number of rows of the original circuit removed or packed by the optimization
+    pub fn removed_rows(&self) -> usize {
+        self.rows.len() - self.num_rows()
+    }
+
+    /// The position of a cell of the original circuit in the optimized circuit,
+    /// or `None` if its row was removed, or if it is an unused cell of a packed gate (see [Packing::cell])
+    pub fn cell(&self, wire: Wire) -> Option<Wire> {
+        self.rows[wire.row].and_then(|row| self.packing.cell(Wire { row, col: wire.col }))
+    }
+
+    /// Lays out the witness of the original circuit (with a row per gate) in the optimized circuit
+    pub fn optimize_witness<F: PrimeField>(
+        &self,
+        witness: &[Vec<F>; COLUMNS],
+    ) -> [Vec<F>; COLUMNS] {
+        let reduced: [Vec<F>; COLUMNS] = array::from_fn(|col| {
+            witness[col]
+                .iter()
+                .zip(&self.rows)
+                .filter_map(|(value, row)| row.map(|_| *value))
+                .collect()
+        });
+        self.packing.pack_witness(&reduced)
+    }
+}
@@ -0,0 +1,185 @@
+//! This module implements the packing of the single generic gates of a circuit into shared rows.
+//!
+//! A `Generic` gate checks two independent operations per row, but small gadgets
+//! (additions, boolean checks, constants) often create generic gates with a single operation,
+//! leaving the second half of the row unused. The packer moves each other such gate into the
+//! unused half of the previous one, and removes its row, so that control-heavy circuits need fewer rows.
+//!
+//! A single generic gate is only packed if:
+//! - it is not a public input row,
+//! - the cells of its second half are not wired to other cells,
+//! - the gate on the row before it is a `Generic` or `Zero` gate
+//!   (so that no multi-row gate constrains it as its next row).
+//!
+//! By default, the gates are paired greedily in order. A non-zero layout seed (see [Packing::create_with_seed])
+//! shuffles the packable gates before pairing them, to explore other layouts of the same circuit.
+//! In both cases the packing of a circuit only depends on the circuit and the seed:
+//! the shuffle uses its own generator rather than an external one whose output may change across versions,
+//! so that the layout (and the digest of the circuit) is stable across builds.
+//! The returned [Packing] lays out the witness of the original circuit in the packed circuit.
+
+use crate::circuits::{
+    gate::{CircuitGate, GateType},
+    polynomial::COLUMNS,
+    polynomials::generic::{GENERIC_COEFFS, GENERIC_REGISTERS},
+    wires::{Wire, PERMUTS},
+};
+use ark_ff::PrimeField;
+use std::array;
+
+/// The position of the rows of a circuit in its packed circuit, see [Packing::create]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Packing {
+    // for each row of the original circuit, its row in the packed circuit
+    // and the offset of its columns (non-zero for the gates moved to the second half of a row)
+    rows: Vec<(usize, usize)>,
+    num_rows: usize,
+}
+
+// Returns whether the gate of a row is a single generic gate, whose second half is unused and unwired
+pub(crate) fn is_single_generic<F: PrimeField>(gate: &CircuitGate<F>, row: usize) -> bool {
+    gate.typ == GateType::Generic
+        && gate.coeffs.iter().skip(GENERIC_COEFFS).all(F::is_zero)
+        && (GENERIC_REGISTERS..PERMUTS).all(|col| gate.wires[col] == Wire { row, col })
+}
+
+// Returns whether the gate of a row can share its row with another single generic gate
+fn is_packable<F: PrimeField>(gates: &[CircuitGate<F>], row: usize) -> bool {
+    is_single_generic(&gates[row], row)
+        && (row == 0 || matches!(gates[row - 1].typ, GateType::Generic | GateType::Zero))
+}
+
+// A splitmix64 generator, whose output is fixed for a given seed
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+// The pairs (host, moved) of packable gates, in order for the seed 0 and shuffled by the seed otherwise
+fn pairs<F: PrimeField>(gates: &[CircuitGate<F>], public: usize, seed: u64) -> Vec<(usize, usize)> {
+    let mut packable: Vec<usize> = (public..gates.len())
+        .filter(|row| is_packable(gates, *row))
+        .collect();
+    if seed != 0 {
+        let mut rng = SplitMix64(seed);
+        for i in (1..packable.len()).rev() {
+            let j = (rng.next() % (i as u64 + 1)) as usize;
+            packable.swap(i, j);
+        }
+    }
+    packable
+        .chunks_exact(2)
+        .map(|pair| (pair[0].min(pair[1]), pair[0].max(pair[1])))
+        .collect()
+}
+
+// The coefficients of the first operation of a generic gate
+fn first_operation<F: PrimeField>(gate: &CircuitGate<F>) -> impl Iterator<Item = F> + '_ {
+    gate.coeffs
+        .iter()
+        .copied()
+        .chain(std::iter::repeat(F::zero()))
+        .take(GENERIC_COEFFS)
+}
+
+impl Packing {
+    /// Packs the single generic gates of a circuit whose first `public` rows are public inputs.
+    /// Returns the packed circuit, and the position of the rows of the original circuit in it.
+    pub fn create<F: PrimeField>(
+        gates: &[CircuitGate<F>],
+        public: usize,
+    ) -> (Vec<CircuitGate<F>>, Self) {
+        Self::create_with_seed(gates, public, 0)
+    }
+
+    /// Packs the single generic gates of a circuit as [Self::create] does,
+    /// pairing them in the order given by the layout `seed` (the seed 0 keeps them in order).
+    /// All the seeds give the same number of rows.
+    pub fn create_with_seed<F: PrimeField>(
+        gates: &[CircuitGate<F>],
+        public: usize,
+        seed: u64,
+    ) -> (Vec<CircuitGate<F>>, Self) {
+        // the row hosting each moved gate, which always comes after it
+        let mut hosts = vec![None; gates.len()];
+        for (host, row) in pairs(gates, public, seed) {
+            hosts[row] = Some(host);
+        }
+
+        let mut rows: Vec<(usize, usize)> = Vec::with_capacity(gates.len());
+        let mut num_rows = 0;
+        for host in &hosts {
+            match host {
+                Some(host) => rows.push((rows[*host].0, GENERIC_REGISTERS)),
+                None => {
+                    rows.push((num_rows, 0));
+                    num_rows += 1;
+                }
+            }
+        }
+        let packing = Packing { rows, num_rows };
+
+        // the unwired cells of the second half of the moved gates are not in the packed circuit,
+        // and the self-loops of the second half of their host are replaced below
+        let wire = |wire: Wire| packing.cell(wire).unwrap_or(wire);
+        let mut packed: Vec<CircuitGate<F>> = Vec::with_capacity(num_rows);
+        for (gate, host) in gates.iter().zip(&hosts) {
+            let wires = array::from_fn(|col| wire(gate.wires[col]));
+            match host {
+                None => packed.push(CircuitGate::new(gate.typ, wires, gate.coeffs.clone())),
+                Some(host) => {
+                    let host_gate = &mut packed[packing.rows[*host].0];
+                    let mut coeffs: Vec<F> = first_operation(host_gate).collect();
+                    coeffs.extend(first_operation(gate));
+                    host_gate.coeffs = coeffs;
+                    host_gate.wires[GENERIC_REGISTERS..2 * GENERIC_REGISTERS]
+                        .copy_from_slice(&wires[..GENERIC_REGISTERS]);
+                }
+            }
+        }
+
+        (packed, packing)
+    }
+
+    /// The number of rows of the packed circuit
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// The position of a cell of the original circuit in the packed circuit,
+    /// or `None` if it is an unused cell of a moved gate (beyond its first 3 columns)
+    pub fn cell(&self, wire: Wire) -> Option<Wire> {
+        let (row, offset) = self.rows[wire.row];
+        if offset != 0 && wire.col >= GENERIC_REGISTERS {
+            return None;
+        }
+        Some(Wire {
+            row,
+            col: wire.col + offset,
+        })
+    }
+
+    /// Lays out the witness of the original circuit (with a row per gate) in the packed circuit
+    pub fn pack_witness<F: PrimeField>(&self, witness: &[Vec<F>; COLUMNS]) -> [Vec<F>; COLUMNS] {
+        let mut packed: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![F::zero(); self.num_rows]);
+        // the moved gates come after their host, and overwrite its unused cells
+        for (row, (packed_row, offset)) in self.rows.iter().enumerate() {
+            let num_cols = if *offset == 0 {
+                COLUMNS
+            } else {
+                GENERIC_REGISTERS
+            };
+            for col in 0..num_cols {
+                packed[col + offset][*packed_row] = witness[col][row];
+            }
+        }
+        packed
+    }
+}
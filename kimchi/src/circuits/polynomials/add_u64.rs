@@ -0,0 +1,174 @@
+//! This module includes the definition of the `AddU64` circuit gate for the addition of 64-bit words
+//! modulo $2^{64}$, and its witness code generation.
+
+use crate::{
+    circuits::{
+        argument::{Argument, ArgumentEnv, ArgumentType},
+        expr::{constraints::ExprOps, Cache},
+        gate::{CircuitGate, GateType},
+        lookup::{
+            self,
+            tables::{GateLookupTable, LookupTable},
+        },
+        polynomial::COLUMNS,
+        wires::Wire,
+        witness::{Solve, SolverEnv},
+    },
+    error::SolveError,
+};
+use ark_ff::PrimeField;
+use std::{array, marker::PhantomData};
+
+/// Column of the 16-bit limbs of the sum, from the least significant one
+const LIMBS_COLUMN: usize = 7;
+
+/// Number of 16-bit limbs of the sum
+const NUM_LIMBS: usize = 4;
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Extends a circuit with a modular addition of two 64-bit words
+    /// Includes:
+    /// - 1 AddU64 gate
+    /// Input:
+    /// - gates : the full circuit
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the words should come from the copy of other cells checking that they are 64-bit long
+    pub fn extend_add_u64(gates: &mut Vec<Self>) -> usize {
+        let (next_row, mut add_gates) = Self::create_add_u64(gates.len());
+        gates.append(&mut add_gates);
+        next_row
+    }
+
+    /// Creates a modular addition of two 64-bit words, see [Self::extend_add_u64]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_add_u64(new_row: usize) -> (usize, Vec<Self>) {
+        let add_gates = vec![CircuitGate::new(
+            GateType::AddU64,
+            Wire::for_row(new_row),
+            vec![],
+        )];
+        (new_row + add_gates.len(), add_gates)
+    }
+}
+
+/// Get the 64-bit addition lookup table
+pub fn lookup_table<F: PrimeField>() -> LookupTable<F> {
+    lookup::tables::get_table::<F>(GateLookupTable::U16)
+}
+
+//~ `AddU64` constrains the addition of two 64-bit words $a$ and $b$ modulo $2^{64}$, with its carry:
+//~
+//~ $$a + b = carry \cdot 2^{64} + sum$$
+//~
+//~ * This circuit gate is used to add 64-bit words in a single row, for example in Keccak or in virtual machines.
+//~ * This gate operates on the `Curr` row.
+//~
+//~ The words, the sum and the carry are in wired columns, so that they can be copied from (or to) other gates.
+//~ The sum is decomposed into 4 limbs of 16 bits, which are looked up in the 16-bit table
+//~ (so that the circuit needs a domain of at least $2^{17}$ rows):
+//~
+//~ | Column | `Curr`        |
+//~ | ------ | ------------- |
+//~ |      0 | copy `a`      |
+//~ |      1 | copy `b`      |
+//~ |      2 | `sum`         |
+//~ |      3 | `carry`       |
+//~ |      7 | `sum_limb0`   |
+//~ |      8 | `sum_limb1`   |
+//~ |      9 | `sum_limb2`   |
+//~ |     10 | `sum_limb3`   |
+//~
+//~ with the following constraints:
+//~
+//~ * $carry \cdot (carry - 1) = 0$
+//~ * $a + b - sum - carry \cdot 2^{64} = 0$
+//~ * $sum - \sum_{i=0}^{3} 2^{16 i} \cdot sum\_limb_i = 0$
+//~
+//~ The lookups check that $sum < 2^{64}$. The words must be checked to fit in 64 bits by other gates,
+//~ so that $a + b < 2^{65}$ and both sides of the addition are the same integer:
+//~ the sum and the carry are then unique.
+//~
+#[derive(Default)]
+pub struct AddU64<F>(PhantomData<F>);
+
+impl<F> Argument<F> for AddU64<F>
+where
+    F: PrimeField,
+{
+    const ARGUMENT_TYPE: ArgumentType = ArgumentType::Gate(GateType::AddU64);
+    const CONSTRAINTS: u32 = 3;
+
+    // Constraints for the addition of two 64-bit words modulo 2^64
+    //   * Operates on Curr row
+    //   * The sum is decomposed into 16-bit limbs, which are looked up
+    fn constraint_checks<T: ExprOps<F>>(env: &ArgumentEnv<F, T>, _cache: &mut Cache) -> Vec<T> {
+        let a = env.witness_curr(0);
+        let b = env.witness_curr(1);
+        let sum = env.witness_curr(2);
+        let carry = env.witness_curr(3);
+
+        // C1: the carry is a bit
+        let mut constraints = vec![carry.boolean()];
+
+        // C2: a + b = carry * 2^64 + sum
+        constraints.push(a + b - sum.clone() - carry * T::two_pow(64));
+
+        // C3: sum is the composition of its limbs
+        let limbs = (0..NUM_LIMBS).rev().fold(T::zero(), |acc, i| {
+            acc * T::two_pow(16) + env.witness_curr(LIMBS_COLUMN + i)
+        });
+        constraints.push(sum - limbs);
+
+        constraints
+    }
+}
+
+impl<F: PrimeField> Solve<F> for AddU64<F> {
+    // The sum and the carry are computed from the words, and the limbs from the sum
+    fn solve(env: &mut SolverEnv<F>, _coeffs: &[F]) -> Result<bool, SolveError> {
+        if let (Some(a), Some(b)) = (env.curr_u64(0), env.curr_u64(1)) {
+            let (sum, carry) = a.overflowing_add(b);
+            env.set_curr(2, F::from(sum))?;
+            env.set_curr(3, F::from(carry))?;
+        }
+        if let Some(sum) = env.curr_u64(2) {
+            for i in 0..NUM_LIMBS {
+                env.set_curr(LIMBS_COLUMN + i, F::from((sum >> (16 * i)) & 0xFFFF))?;
+            }
+        }
+        Ok((0..4)
+            .chain(LIMBS_COLUMN..LIMBS_COLUMN + NUM_LIMBS)
+            .all(|col| env.curr(col).is_some()))
+    }
+}
+
+/// Extends the modular addition row to the full witness
+/// Input
+/// - witness: full witness of the circuit
+/// - a: the first 64-bit word
+/// - b: the second 64-bit word
+/// Output
+/// - the sum of the words modulo 2^64, and the carry of the addition
+pub fn extend_add_u64_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    a: u64,
+    b: u64,
+) -> (u64, bool) {
+    let (sum, carry) = a.overflowing_add(b);
+    let add_witness: [F; COLUMNS] = array::from_fn(|col| match col {
+        0 => F::from(a),
+        1 => F::from(b),
+        2 => F::from(sum),
+        3 => F::from(carry),
+        LIMBS_COLUMN..=10 => F::from((sum >> (16 * (col - LIMBS_COLUMN))) & 0xFFFF),
+        _ => F::zero(),
+    });
+    for col in 0..COLUMNS {
+        witness[col].push(add_witness[col]);
+    }
+    (sum, carry)
+}
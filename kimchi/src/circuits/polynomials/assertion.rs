@@ -0,0 +1,145 @@
+//! This module includes the gadgets asserting that cells are equal to constants, or nonzero,
+//! and their witness code generation.
+//! Note that this module does not include a new gate type, the gadgets reuse the `Generic` gate.
+
+//~ The assertion gadgets constrain cells copied from other gates, two per `Generic` row.
+//~
+//~ The gadget asserting that the cells $x_1, \dots, x_n$ are equal to the constants $c_1, \dots, c_n$
+//~ lays out the constraints $x_i - c_i = 0$, in the left inputs of the halves of its `Generic` gates.
+//~
+//~ The gadget asserting that the cells $x_1, \dots, x_n$ are nonzero lays out the constraints
+//~ $x_i \cdot y_i - 1 = 0$, where the right input $y_i$ of each half is the inverse of $x_i$ in the witness.
+//~ Such a $y_i$ only exists when $x_i \neq 0$, so that a zero $x_i$ has no satisfying witness.
+//~ Note that the inverse is not constrained by another gate, and should not be copied to other cells.
+//~
+//~ The asserted cells are at the positions given by `assertion_input`: the left input of each half,
+//~ from the first half of the first row.
+//~
+
+use crate::circuits::{
+    gate::CircuitGate,
+    polynomial::COLUMNS,
+    polynomials::generic::{GenericGateSpec, GENERIC_REGISTERS},
+    wires::Wire,
+};
+use ark_ff::PrimeField;
+
+/// Returns the position of the `i`-th asserted cell of an assertion gadget starting at row `new_row`,
+/// as a `(row, column)` pair
+pub fn assertion_input(new_row: usize, i: usize) -> (usize, usize) {
+    (new_row + i / 2, GENERIC_REGISTERS * (i % 2))
+}
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Extends a circuit with the assertions that cells are equal to constants
+    /// Includes:
+    /// - ceil(n/2) Double Generic gates constraining `x - c = 0` for every two constants in each row
+    /// Input:
+    /// - gates     : the full circuit
+    /// - constants : the constants of the asserted cells, in their order
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the asserted cells (see [assertion_input]) should be copied from other cells
+    pub fn extend_assert_constant(gates: &mut Vec<Self>, constants: &[F]) -> usize {
+        let (next_row, mut assertion_gates) = Self::create_assert_constant(gates.len(), constants);
+        gates.append(&mut assertion_gates);
+        next_row
+    }
+
+    /// Creates the assertions that cells are equal to constants, see [Self::extend_assert_constant]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_assert_constant(new_row: usize, constants: &[F]) -> (usize, Vec<Self>) {
+        let gates: Vec<Self> = constants
+            .chunks(2)
+            .enumerate()
+            .map(|(i, pair)| {
+                CircuitGate::create_generic_gadget(
+                    Wire::for_row(new_row + i),
+                    GenericGateSpec::Const(pair[0]),
+                    pair.get(1).copied().map(GenericGateSpec::Const),
+                )
+            })
+            .collect();
+        (new_row + gates.len(), gates)
+    }
+
+    /// Extends a circuit with the assertions that `n` cells are nonzero
+    /// Includes:
+    /// - ceil(n/2) Double Generic gates constraining `x * y - 1 = 0` for every two cells in each row,
+    ///   with the inverse `y` of the cell `x`
+    /// Input:
+    /// - gates     : the full circuit
+    /// - n         : the number of asserted cells
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the asserted cells (see [assertion_input]) should be copied from other cells
+    pub fn extend_assert_nonzero(gates: &mut Vec<Self>, n: usize) -> usize {
+        let (next_row, mut assertion_gates) = Self::create_assert_nonzero(gates.len(), n);
+        gates.append(&mut assertion_gates);
+        next_row
+    }
+
+    /// Creates the assertions that `n` cells are nonzero, see [Self::extend_assert_nonzero]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_assert_nonzero(new_row: usize, n: usize) -> (usize, Vec<Self>) {
+        let gates: Vec<Self> = (0..(n + 1) / 2)
+            .map(|i| {
+                CircuitGate::create_generic_gadget(
+                    Wire::for_row(new_row + i),
+                    GenericGateSpec::NonZero,
+                    (2 * i + 1 < n).then_some(GenericGateSpec::NonZero),
+                )
+            })
+            .collect();
+        (new_row + gates.len(), gates)
+    }
+}
+
+// Extends the witness with the rows of the halves of an assertion gadget, from the values of their inputs
+fn extend_halves<F: PrimeField>(witness: &mut [Vec<F>; COLUMNS], halves: &[(F, F)]) {
+    for pair in halves.chunks(2) {
+        for col in witness.iter_mut() {
+            col.push(F::zero());
+        }
+        let row = witness[0].len() - 1;
+        for (half, (left, right)) in pair.iter().enumerate() {
+            witness[GENERIC_REGISTERS * half][row] = *left;
+            witness[GENERIC_REGISTERS * half + 1][row] = *right;
+        }
+    }
+}
+
+/// Extends the witness with the assertions that cells are equal to constants
+/// Input
+/// - witness: full witness of the circuit
+/// - values: the values of the asserted cells
+pub fn extend_assert_constant_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    values: &[F],
+) {
+    let halves: Vec<_> = values.iter().map(|value| (*value, F::zero())).collect();
+    extend_halves(witness, &halves);
+}
+
+/// Extends the witness with the assertions that cells are nonzero, and returns whether they are all nonzero,
+/// since the witness of a zero value does not satisfy the gadget
+/// Input
+/// - witness: full witness of the circuit
+/// - values: the values of the asserted cells
+pub fn extend_assert_nonzero_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    values: &[F],
+) -> bool {
+    let halves: Vec<_> = values
+        .iter()
+        .map(|value| (*value, value.inverse().unwrap_or_else(F::zero)))
+        .collect();
+    extend_halves(witness, &halves);
+    values.iter().all(|value| !value.is_zero())
+}
@@ -0,0 +1,606 @@
+//! This module includes the definition of the comparison gadgets and the witness code generation,
+//! for field-encoded integers of up to 64 bits: less-than, sign and absolute value,
+//! and the assertions that an unsigned integer is less than a constant or in a constant interval.
+//! Note that this module does not include a `Compare` gate type, the gadgets reuse the `Generic` and `RangeCheck0` gates.
+use crate::circuits::{
+    gate::{CircuitGate, Connect},
+    polynomial::COLUMNS,
+    wires::Wire,
+};
+use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::BigUint;
+use o1_utils::FieldHelpers;
+use std::array;
+
+use super::range_check;
+
+//~ We implement comparisons of integers of $k \leq 64$ bits as gadgets, needing no new gate type for them.
+//~ Instead, they reuse the `Generic` gate and the `RangeCheck0` gate with its two most significant limbs wired to zero,
+//~ which checks that a value fits in 64 bits.
+//~
+//~ An unsigned $k$-bit integer is a field element in $[0, 2^k)$, and a signed $k$-bit integer is a field element
+//~ in $[-2^{k-1}, 2^{k-1})$, where negative values are encoded as $p - |x|$ as the result of native field arithmetic.
+//~ The gadgets assume that their inputs are already known (or constrained) to be $k$-bit integers,
+//~ for example because they are copied from the output of a range check.
+//~
+//~ Both gadgets rely on taking the top bit of a $(m + 1)$-bit value $v$, by constraining
+//~ $$v = b \cdot 2^m + r$$
+//~ with $b$ boolean and $r < 2^m$. The bound on $r$ is obtained from two 64-bit range checks,
+//~ one of $r$ and one of $r' = r + 2^{64} - 2^m$, exactly as the bound of the `Rot64` gate.
+//~
+//~ ##### Less-than
+//~
+//~ For two $k$-bit integers $a$ and $b$, both unsigned or both signed, the value $d = b - a + 2^k - 1$ is in $[0, 2^{k+1} - 1)$,
+//~ and its top bit $lt$ (the coefficient of $2^k$) is $1$ if and only if $a < b$.
+//~
+//~ | Row | `CircuitGate` | Purpose                                                       |
+//~ | --- | ------------- | ------------------------------------------------------------- |
+//~ | i   | `Generic`     | $d = b - a + 2^k - 1$ and $d = lt \cdot 2^k + r$              |
+//~ | i+1 | `Generic`     | $lt \cdot lt = lt$ and $r' = r + 2^{64} - 2^k$                |
+//~ | i+2 | `RangeCheck0` | $r < 2^{64}$                                                  |
+//~ | i+3 | `RangeCheck0` | $r' < 2^{64}$                                                 |
+//~
+//~ The inputs $a$ and $b$ are in columns 0 and 1 of the first row, and the output $lt$ is in column 3 of the first row.
+//~
+//~ ##### Sign and absolute value
+//~
+//~ For a signed $k$-bit integer $x$, the value $u = x + 2^{k-1}$ is in $[0, 2^k)$,
+//~ and its top bit $nn$ (the coefficient of $2^{k-1}$) is $1$ if and only if $x \geq 0$.
+//~ Then the sign is $s = 1 - nn$, which is $1$ for negative values, and the absolute value is $(2 \cdot nn - 1) \cdot x$.
+//~
+//~ | Row | `CircuitGate` | Purpose                                                       |
+//~ | --- | ------------- | ------------------------------------------------------------- |
+//~ | i   | `Generic`     | $x + 2^{k-1} = nn \cdot 2^{k-1} + r$ and $nn \cdot nn = nn$   |
+//~ | i+1 | `Generic`     | $|x| = 2 \cdot nn \cdot x - x$ and $s = 1 - nn$               |
+//~ | i+2 | `Generic`     | $r' = r + 2^{64} - 2^{k-1}$                                   |
+//~ | i+3 | `RangeCheck0` | $r < 2^{64}$                                                  |
+//~ | i+4 | `RangeCheck0` | $r' < 2^{64}$                                                 |
+//~
+//~ The input $x$ is in column 0 of the first row, the absolute value is in column 2 of the second row,
+//~ and the sign is in column 5 of the second row.
+//~
+//~ ##### Constant bounds
+//~
+//~ For an unsigned integer $x$ of at most 64 bits and a constant $0 < c < 2^{64}$, the value $y = x + 2^{64} - c$
+//~ is less than $2^{64}$ if and only if $x < c$, since there is no wraparound of the field.
+//~ So the assertion $x < c$ is a single 64-bit range check of $y$, with no need for a comparison bit.
+//~
+//~ | Row | `CircuitGate` | Purpose                                                       |
+//~ | --- | ------------- | ------------------------------------------------------------- |
+//~ | i   | `Generic`     | $y = x + 2^{64} - c$                                          |
+//~ | i+1 | `RangeCheck0` | $y < 2^{64}$                                                  |
+//~
+//~ The assertion $a \leq x \leq b$ for constants $a \leq b < 2^{64}$ checks that $d = x - a$ is in $[0, b - a]$,
+//~ with a 64-bit range check of $d$ (which fails if $x < a$, as $d$ is then a field element close to $p$)
+//~ and the assertion that $d < b - a + 1$.
+//~
+//~ | Row | `CircuitGate` | Purpose                                                       |
+//~ | --- | ------------- | ------------------------------------------------------------- |
+//~ | i   | `Generic`     | $d = x - a$ and $e = d + 2^{64} - (b - a + 1)$                |
+//~ | i+1 | `RangeCheck0` | $d < 2^{64}$                                                  |
+//~ | i+2 | `RangeCheck0` | $e < 2^{64}$                                                  |
+//~
+//~ In both gadgets, the input $x$ is in column 0 of the first row, and must be known (or constrained) to fit in 64 bits.
+//~
+//~ As for the rotation, the gadgets need a zero cell to check that the range checked values fit in 64 bits.
+
+/// Number of rows of the less-than gadget
+pub const LESS_THAN_ROWS: usize = 4;
+
+/// Number of rows of the sign and absolute value gadget
+pub const SIGN_ABS_ROWS: usize = 5;
+
+/// Number of rows of the less-than-constant gadget
+pub const LESS_THAN_CONSTANT_ROWS: usize = 2;
+
+/// Number of rows of the interval gadget
+pub const INTERVAL_ROWS: usize = 3;
+
+/// Returns the position of the cell containing the output of a less-than gadget starting at row `new_row`,
+/// as a `(row, column)` pair. The inputs are in columns 0 and 1 of row `new_row`.
+pub fn less_than_output(new_row: usize) -> (usize, usize) {
+    (new_row, 3)
+}
+
+/// Returns the position of the cell containing the sign (1 for negative values) computed by a
+/// sign and absolute value gadget starting at row `new_row`, as a `(row, column)` pair.
+/// The input is in column 0 of row `new_row`.
+pub fn sign_output(new_row: usize) -> (usize, usize) {
+    (new_row + 1, 5)
+}
+
+/// Returns the position of the cell containing the absolute value computed by a
+/// sign and absolute value gadget starting at row `new_row`, as a `(row, column)` pair.
+/// The input is in column 0 of row `new_row`.
+pub fn abs_output(new_row: usize) -> (usize, usize) {
+    (new_row + 1, 2)
+}
+
+// Checks that the integers of a comparison of length `bits` fit in the range checks
+fn check_bits(bits: u32) {
+    assert!(
+        bits > 0 && bits <= 64,
+        "Comparisons are only supported for integers of 1 to 64 bits"
+    );
+}
+
+// The coefficients of a generic gate `left_coeff * l + right_coeff * r + output_coeff * o + mul_coeff * l * r + constant`
+fn generic_coeffs<F: PrimeField>(
+    left_coeff: F,
+    right_coeff: F,
+    output_coeff: F,
+    mul_coeff: F,
+    constant: F,
+) -> [F; 5] {
+    [left_coeff, right_coeff, output_coeff, mul_coeff, constant]
+}
+
+// Creates a double generic gate from the coefficients of its two gates
+fn create_double_generic<F: PrimeField>(
+    row: usize,
+    gate1: [F; 5],
+    gate2: [F; 5],
+) -> CircuitGate<F> {
+    let mut coeffs = [F::zero(); 10];
+    coeffs[..5].copy_from_slice(&gate1);
+    coeffs[5..].copy_from_slice(&gate2);
+    CircuitGate::create_generic(Wire::for_row(row), coeffs)
+}
+
+// 2^64 - 2^bits, the value added to a remainder of `bits` length so that it fits in 64 bits
+fn bound<F: PrimeField>(bits: u32) -> F {
+    F::from(2u128.pow(64) - 2u128.pow(bits))
+}
+
+// 2^64 - constant, the value added to an integer less than `constant` so that it fits in 64 bits
+fn constant_bound<F: PrimeField>(constant: u128) -> F {
+    F::from(2u128.pow(64) - constant)
+}
+
+// Checks that the bound of a less-than-constant gadget is a positive 64-bit integer
+fn check_constant(constant: u64) {
+    assert!(
+        constant > 0,
+        "No integer is less than the constant 0 of the comparison"
+    );
+}
+
+// Checks that the bounds of an interval gadget are in order
+fn check_interval(low: u64, high: u64) {
+    assert!(
+        low <= high,
+        "The lower bound of the interval must not be greater than its upper bound"
+    );
+}
+
+// The number of integers in the interval [low, high]
+fn interval_size(low: u64, high: u64) -> u128 {
+    (high - low) as u128 + 1
+}
+
+impl<F: PrimeField + SquareRootField> CircuitGate<F> {
+    /// Extends a less-than gadget comparing two integers of `bits` length to a circuit
+    /// Includes:
+    /// - 2 Generic gates to compute the comparison bit and the bound of the remainder
+    /// - 2 RangeCheck0 gates to check the remainder and its bound
+    /// Input:
+    /// - gates    : the full circuit
+    /// - bits     : the length of the integers, up to 64
+    /// - zero_row : the row of the Generic gate to constrain the 64-bit checks
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the inputs should come from the copy of other cells so it is intrinsic that they are `bits` length
+    /// - the output is at the position given by [less_than_output]
+    pub fn extend_less_than(gates: &mut Vec<Self>, bits: u32, zero_row: usize) -> usize {
+        let start_row = gates.len();
+        let (_, mut lt_gates) = Self::create_less_than(start_row, bits);
+        gates.append(&mut lt_gates);
+        // Check that 2 most significant limbs of the remainder and its bound are zero
+        gates.connect_64bit(zero_row, start_row + 2);
+        gates.connect_64bit(zero_row, start_row + 3);
+        gates.len()
+    }
+
+    /// Creates a less-than gadget comparing two integers of `bits` length, see [Self::extend_less_than]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    /// Warning:
+    /// - need to check that the 2 most significant limbs of the range checked values are zero
+    pub fn create_less_than(new_row: usize, bits: u32) -> (usize, Vec<Self>) {
+        check_bits(bits);
+        let two_to_bits = F::from(2u128.pow(bits));
+        let mut lt_gates = vec![
+            // -a + b - d + 2^k - 1 = 0
+            // 2^k * lt + r - d = 0
+            create_double_generic(
+                new_row,
+                generic_coeffs(
+                    -F::one(),
+                    F::one(),
+                    -F::one(),
+                    F::zero(),
+                    two_to_bits - F::one(),
+                ),
+                generic_coeffs(two_to_bits, F::one(), -F::one(), F::zero(), F::zero()),
+            ),
+            // lt * lt - lt = 0
+            // r - r' + 2^64 - 2^k = 0
+            create_double_generic(
+                new_row + 1,
+                generic_coeffs(F::zero(), F::zero(), -F::one(), F::one(), F::zero()),
+                generic_coeffs(F::one(), F::zero(), -F::one(), F::zero(), bound(bits)),
+            ),
+        ];
+        let mut next_row = new_row + lt_gates.len();
+        CircuitGate::extend_range_check(&mut lt_gates, &mut next_row);
+        CircuitGate::extend_range_check(&mut lt_gates, &mut next_row);
+
+        // copy d, lt and r (indices are relative to the gadget, wires are not)
+        lt_gates.connect_cell_pair((0, 2), (0, 5));
+        lt_gates.connect_cell_pair((0, 3), (1, 0));
+        lt_gates.connect_cell_pair((1, 0), (1, 1));
+        lt_gates.connect_cell_pair((1, 1), (1, 2));
+        lt_gates.connect_cell_pair((0, 4), (1, 3));
+        lt_gates.connect_cell_pair((1, 3), (2, 0));
+        // copy r'
+        lt_gates.connect_cell_pair((1, 5), (3, 0));
+
+        (next_row, lt_gates)
+    }
+
+    /// Extends a sign and absolute value gadget of a signed integer of `bits` length to a circuit
+    /// Includes:
+    /// - 3 Generic gates to compute the sign, the absolute value and the bound of the remainder
+    /// - 2 RangeCheck0 gates to check the remainder and its bound
+    /// Input:
+    /// - gates    : the full circuit
+    /// - bits     : the length of the integer, up to 64
+    /// - zero_row : the row of the Generic gate to constrain the 64-bit checks
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the input should come from the copy of another cell so it is intrinsic that it is `bits` length
+    /// - the outputs are at the positions given by [sign_output] and [abs_output]
+    pub fn extend_sign_abs(gates: &mut Vec<Self>, bits: u32, zero_row: usize) -> usize {
+        let start_row = gates.len();
+        let (_, mut sign_gates) = Self::create_sign_abs(start_row, bits);
+        gates.append(&mut sign_gates);
+        // Check that 2 most significant limbs of the remainder and its bound are zero
+        gates.connect_64bit(zero_row, start_row + 3);
+        gates.connect_64bit(zero_row, start_row + 4);
+        gates.len()
+    }
+
+    /// Creates a sign and absolute value gadget of a signed integer of `bits` length, see [Self::extend_sign_abs]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    /// Warning:
+    /// - need to check that the 2 most significant limbs of the range checked values are zero
+    pub fn create_sign_abs(new_row: usize, bits: u32) -> (usize, Vec<Self>) {
+        check_bits(bits);
+        let half = F::from(2u128.pow(bits - 1));
+        let mut sign_gates = vec![
+            // x - 2^(k-1) * nn - r + 2^(k-1) = 0
+            // nn * nn - nn = 0
+            create_double_generic(
+                new_row,
+                generic_coeffs(F::one(), -half, -F::one(), F::zero(), half),
+                generic_coeffs(F::zero(), F::zero(), -F::one(), F::one(), F::zero()),
+            ),
+            // 2 * nn * x - x - abs = 0
+            // nn + s - 1 = 0
+            create_double_generic(
+                new_row + 1,
+                generic_coeffs(F::zero(), -F::one(), -F::one(), F::from(2u64), F::zero()),
+                generic_coeffs(F::one(), F::zero(), F::one(), F::zero(), -F::one()),
+            ),
+            // r - r' + 2^64 - 2^(k-1) = 0
+            create_double_generic(
+                new_row + 2,
+                generic_coeffs(F::one(), F::zero(), -F::one(), F::zero(), bound(bits - 1)),
+                [F::zero(); 5],
+            ),
+        ];
+        let mut next_row = new_row + sign_gates.len();
+        CircuitGate::extend_range_check(&mut sign_gates, &mut next_row);
+        CircuitGate::extend_range_check(&mut sign_gates, &mut next_row);
+
+        // copy x and nn (indices are relative to the gadget, wires are not)
+        sign_gates.connect_cell_pair((0, 0), (1, 1));
+        sign_gates.connect_cell_pair((0, 1), (0, 3));
+        sign_gates.connect_cell_pair((0, 3), (0, 4));
+        sign_gates.connect_cell_pair((0, 4), (0, 5));
+        sign_gates.connect_cell_pair((0, 5), (1, 0));
+        sign_gates.connect_cell_pair((1, 0), (1, 3));
+        // copy r and r'
+        sign_gates.connect_cell_pair((0, 2), (2, 0));
+        sign_gates.connect_cell_pair((2, 0), (3, 0));
+        sign_gates.connect_cell_pair((2, 2), (4, 0));
+
+        (next_row, sign_gates)
+    }
+
+    /// Extends a less-than-constant gadget asserting that a 64-bit unsigned integer is less than `constant`
+    /// Includes:
+    /// - 1 Generic gate to shift the integer by the bound
+    /// - 1 RangeCheck0 gate to check the shifted integer
+    /// Input:
+    /// - gates    : the full circuit
+    /// - constant : the bound of the integer, which is positive
+    /// - zero_row : the row of the Generic gate to constrain the 64-bit checks
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the input should come from the copy of another cell so it is intrinsic that it fits in 64 bits
+    /// - the input is in column 0 of the first row
+    pub fn extend_less_than_constant(
+        gates: &mut Vec<Self>,
+        constant: u64,
+        zero_row: usize,
+    ) -> usize {
+        let start_row = gates.len();
+        let (_, mut lt_gates) = Self::create_less_than_constant(start_row, constant);
+        gates.append(&mut lt_gates);
+        // Check that 2 most significant limbs of the shifted integer are zero
+        gates.connect_64bit(zero_row, start_row + 1);
+        gates.len()
+    }
+
+    /// Creates a less-than-constant gadget, see [Self::extend_less_than_constant]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    /// Panics if `constant` is zero.
+    /// Warning:
+    /// - need to check that the 2 most significant limbs of the range checked value are zero
+    pub fn create_less_than_constant(new_row: usize, constant: u64) -> (usize, Vec<Self>) {
+        check_constant(constant);
+        let mut lt_gates = vec![
+            // x - y + 2^64 - c = 0
+            create_double_generic(
+                new_row,
+                generic_coeffs(
+                    F::one(),
+                    F::zero(),
+                    -F::one(),
+                    F::zero(),
+                    constant_bound(constant as u128),
+                ),
+                [F::zero(); 5],
+            ),
+        ];
+        let mut next_row = new_row + lt_gates.len();
+        CircuitGate::extend_range_check(&mut lt_gates, &mut next_row);
+
+        // copy y (indices are relative to the gadget, wires are not)
+        lt_gates.connect_cell_pair((0, 2), (1, 0));
+
+        (next_row, lt_gates)
+    }
+
+    /// Extends an interval gadget asserting that a 64-bit unsigned integer is in the interval `[low, high]`
+    /// Includes:
+    /// - 1 Generic gate to compute the distance of the integer to the lower bound, and its shift by the size of the interval
+    /// - 2 RangeCheck0 gates to check the distance and its shift
+    /// Input:
+    /// - gates    : the full circuit
+    /// - low      : the lower bound of the interval
+    /// - high     : the upper bound of the interval, included
+    /// - zero_row : the row of the Generic gate to constrain the 64-bit checks
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the input should come from the copy of another cell so it is intrinsic that it fits in 64 bits
+    /// - the input is in column 0 of the first row
+    pub fn extend_interval(gates: &mut Vec<Self>, low: u64, high: u64, zero_row: usize) -> usize {
+        let start_row = gates.len();
+        let (_, mut interval_gates) = Self::create_interval(start_row, low, high);
+        gates.append(&mut interval_gates);
+        // Check that 2 most significant limbs of the distance and its shift are zero
+        gates.connect_64bit(zero_row, start_row + 1);
+        gates.connect_64bit(zero_row, start_row + 2);
+        gates.len()
+    }
+
+    /// Creates an interval gadget, see [Self::extend_interval]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    /// Panics if `low` is greater than `high`.
+    /// Warning:
+    /// - need to check that the 2 most significant limbs of the range checked values are zero
+    pub fn create_interval(new_row: usize, low: u64, high: u64) -> (usize, Vec<Self>) {
+        check_interval(low, high);
+        let mut interval_gates = vec![
+            // x - d - a = 0
+            // d - e + 2^64 - (b - a + 1) = 0
+            create_double_generic(
+                new_row,
+                generic_coeffs(F::one(), F::zero(), -F::one(), F::zero(), -F::from(low)),
+                generic_coeffs(
+                    F::one(),
+                    F::zero(),
+                    -F::one(),
+                    F::zero(),
+                    constant_bound(interval_size(low, high)),
+                ),
+            ),
+        ];
+        let mut next_row = new_row + interval_gates.len();
+        CircuitGate::extend_range_check(&mut interval_gates, &mut next_row);
+        CircuitGate::extend_range_check(&mut interval_gates, &mut next_row);
+
+        // copy d and e (indices are relative to the gadget, wires are not)
+        interval_gates.connect_cell_pair((0, 2), (0, 3));
+        interval_gates.connect_cell_pair((0, 3), (1, 0));
+        interval_gates.connect_cell_pair((0, 5), (2, 0));
+
+        (next_row, interval_gates)
+    }
+}
+
+// Splits a field element holding a value of `bits + 1` length into its top bit and the remainder
+fn split_top_bit<F: PrimeField>(value: F, bits: u32) -> (F, F) {
+    let value = value.to_biguint();
+    assert!(
+        value < BigUint::from(2u128.pow(bits + 1)),
+        "Inputs of the comparison must be integers of the given length"
+    );
+    let top = &value >> bits;
+    let rest = value - (&top << bits);
+    (
+        F::from_biguint(&top).unwrap(),
+        F::from_biguint(&rest).unwrap(),
+    )
+}
+
+// Extends the witness with the rows of the two 64-bit range checks of a remainder of `bits` length
+fn extend_bound_witness<F: PrimeField>(witness: &mut [Vec<F>; COLUMNS], rest: F, bits: u32) {
+    range_check::witness::extend_single(witness, rest);
+    range_check::witness::extend_single(witness, rest + bound::<F>(bits));
+}
+
+/// Extends the less-than rows to the full witness
+/// Input
+/// - witness: full witness of the circuit
+/// - left: first integer of `bits` length, either unsigned or signed as a field element
+/// - right: second integer of `bits` length, of the same kind as `left`
+/// - bits: length of the integers, up to 64
+/// Output
+/// - the result of the comparison `left < right`, which is also at the position given by [less_than_output]
+/// Warning:
+/// - don't forget to include a public input row with zero value
+pub fn extend_less_than_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    left: F,
+    right: F,
+    bits: u32,
+) -> bool {
+    check_bits(bits);
+    let two_to_bits = F::from(2u128.pow(bits));
+    let diff = right - left + two_to_bits - F::one();
+    let (lt, rest) = split_top_bit(diff, bits);
+
+    let lt_witness: [Vec<F>; COLUMNS] = array::from_fn(|col| match col {
+        0 => vec![left, lt],
+        1 => vec![right, lt],
+        2 => vec![diff, lt],
+        3 => vec![lt, rest],
+        4 => vec![rest, F::zero()],
+        5 => vec![diff, rest + bound::<F>(bits)],
+        _ => vec![F::zero(); 2],
+    });
+    for col in 0..COLUMNS {
+        witness[col].extend(lt_witness[col].iter());
+    }
+    extend_bound_witness(witness, rest, bits);
+
+    lt.is_one()
+}
+
+/// Extends the sign and absolute value rows to the full witness
+/// Input
+/// - witness: full witness of the circuit
+/// - value: signed integer of `bits` length as a field element
+/// - bits: length of the integer, up to 64
+/// Output
+/// - the sign (`true` for negative values) and the absolute value of the integer,
+///   which are also at the positions given by [sign_output] and [abs_output]
+/// Warning:
+/// - don't forget to include a public input row with zero value
+pub fn extend_sign_abs_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    value: F,
+    bits: u32,
+) -> (bool, F) {
+    check_bits(bits);
+    let half = F::from(2u128.pow(bits - 1));
+    let (nonneg, rest) = split_top_bit(value + half, bits - 1);
+    let sign = F::one() - nonneg;
+    let abs = if nonneg.is_one() { value } else { -value };
+
+    let sign_witness: [Vec<F>; COLUMNS] = array::from_fn(|col| match col {
+        0 => vec![value, nonneg, rest],
+        1 => vec![nonneg, value, F::zero()],
+        2 => vec![rest, abs, rest + bound::<F>(bits - 1)],
+        3 => vec![nonneg, nonneg, F::zero()],
+        4 => vec![nonneg, F::zero(), F::zero()],
+        5 => vec![nonneg, sign, F::zero()],
+        _ => vec![F::zero(); 3],
+    });
+    for col in 0..COLUMNS {
+        witness[col].extend(sign_witness[col].iter());
+    }
+    extend_bound_witness(witness, rest, bits - 1);
+
+    (sign.is_one(), abs)
+}
+
+/// Extends the less-than-constant rows to the full witness
+/// Input
+/// - witness: full witness of the circuit
+/// - value: unsigned integer of at most 64 bits, as a field element
+/// - constant: the bound of the integer
+/// Output
+/// - whether the integer is less than the constant, otherwise the witness does not satisfy the gadget
+/// Warning:
+/// - don't forget to include a public input row with zero value
+pub fn extend_less_than_constant_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    value: F,
+    constant: u64,
+) -> bool {
+    check_constant(constant);
+    let shifted = value + constant_bound::<F>(constant as u128);
+
+    let lt_witness: [Vec<F>; COLUMNS] = array::from_fn(|col| match col {
+        0 => vec![value],
+        2 => vec![shifted],
+        _ => vec![F::zero()],
+    });
+    for col in 0..COLUMNS {
+        witness[col].extend(lt_witness[col].iter());
+    }
+    range_check::witness::extend_single(witness, shifted);
+
+    value.to_biguint() < BigUint::from(constant)
+}
+
+/// Extends the interval rows to the full witness
+/// Input
+/// - witness: full witness of the circuit
+/// - value: unsigned integer of at most 64 bits, as a field element
+/// - low: the lower bound of the interval
+/// - high: the upper bound of the interval, included
+/// Output
+/// - whether the integer is in the interval, otherwise the witness does not satisfy the gadget
+/// Warning:
+/// - don't forget to include a public input row with zero value
+pub fn extend_interval_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    value: F,
+    low: u64,
+    high: u64,
+) -> bool {
+    check_interval(low, high);
+    let distance = value - F::from(low);
+    let shifted = distance + constant_bound::<F>(interval_size(low, high));
+
+    let interval_witness: [Vec<F>; COLUMNS] = array::from_fn(|col| match col {
+        0 => vec![value],
+        2 | 3 => vec![distance],
+        5 => vec![shifted],
+        _ => vec![F::zero()],
+    });
+    for col in 0..COLUMNS {
+        witness[col].extend(interval_witness[col].iter());
+    }
+    range_check::witness::extend_single(witness, distance);
+    range_check::witness::extend_single(witness, shifted);
+
+    let value = value.to_biguint();
+    BigUint::from(low) <= value && value <= BigUint::from(high)
+}
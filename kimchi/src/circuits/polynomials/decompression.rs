@@ -0,0 +1,287 @@
+//! This module includes the gadgets decompressing a point from its x-coordinate and the parity of its y-coordinate,
+//! over a native curve or over a foreign curve, and their witness code generation.
+//! Note that this module does not include a new gate type, the gadgets reuse the `Generic` gate,
+//! and the range check and foreign field gates of the ECDSA layout.
+
+//~ The decompression gadget recovers the point $(x, y)$ of a compressed point $(x, s)$,
+//~ where $s$ is the sign bit, the parity of $y$ (as in the compressed public keys of Mina and of SEC 1),
+//~ so that compressed points are inputs of the circuit without their y-coordinates.
+//~ The y-coordinate $y$ is an element of the witness, one of the square roots of $x^3 + a x + b$ out of the circuit.
+//~
+//~ Over a native curve $E$ of equation $y^2 = x^3 + b$ (as for the Schnorr gadget), the gadget lays out:
+//~
+//~ * the curve check $x \cdot x - x_2 = 0$, $x_2 \cdot x - x_3 = 0$ and $y \cdot y - x_3 - b = 0$ in `Generic` gates,
+//~ * the boolean $s \cdot s - s = 0$ in the second half of the `Generic` gate of $y$,
+//~ * the parity check of $y$, as for the Schnorr gadget: its 3 limbs of 88 bits are canonical,
+//~   recomposed into $y$ in `Generic` gates, and the least significant bit of the decomposition of the
+//~   lowest limb is copied to $s$.
+//~
+//~ Over a foreign curve of base field $\mathbb{F}_p$, $x$ and $y$ are canonical elements of the ECDSA layout,
+//~ with the check $y^2 = x^3 + a x + b$ modulo $p$, and the least significant bit of the decomposition of
+//~ the lowest limb of $y$ is copied to the boolean $s$, since $y < p$ and $p$ is odd.
+//~
+//~ Since the two square roots $y$ and $p - y$ have different parities, the y-coordinate is unique.
+//~ The positions of the inputs $x, s$ and of the output $y$ are given by `decompression_cell`
+//~ and `foreign_decompression_cell`.
+//~
+
+use crate::{
+    circuits::{
+        gate::{CircuitGate, Connect},
+        polynomial::COLUMNS,
+        polynomials::{
+            ecdsa::{
+                curve::ForeignCurve,
+                layout::{Layout, Point},
+                ELEM_ROWS,
+            },
+            schnorr::{curve_b, generic_gate, layout_lowest_bit, layout_parity},
+        },
+    },
+    curve::KimchiCurve,
+};
+use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::BigUint;
+use num_traits::Zero;
+use o1_utils::FieldHelpers;
+use std::array;
+
+/// Number of rows of the curve check of the native gadget, before its parity check
+const CURVE_ROWS: usize = 2;
+
+/// The cells of the decompression gadgets
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecompressionCell {
+    /// The x-coordinate of the point, an input
+    X,
+    /// The sign bit, the parity of the y-coordinate, an input
+    Sign,
+    /// The y-coordinate of the point, the output
+    Y,
+}
+
+/// Returns the position of a cell of the native decompression gadget starting at row `new_row`,
+/// as a `(row, column)` pair
+pub fn decompression_cell(new_row: usize, cell: DecompressionCell) -> (usize, usize) {
+    let (row, col) = match cell {
+        DecompressionCell::X => (0, 0),
+        DecompressionCell::Sign => (1, 3),
+        DecompressionCell::Y => (1, 0),
+    };
+    (new_row + row, col)
+}
+
+/// Returns the position of the cell containing the `limb`-th limb (from the least significant one)
+/// of a coordinate of the foreign decompression gadget starting at row `new_row`, or of the sign bit
+/// for the limb 0, as a `(row, column)` pair
+pub fn foreign_decompression_cell(
+    new_row: usize,
+    cell: DecompressionCell,
+    limb: usize,
+) -> (usize, usize) {
+    // the coordinates are the first elements, followed by the sign bit
+    let elem = match cell {
+        DecompressionCell::X => 0,
+        DecompressionCell::Y => 1,
+        DecompressionCell::Sign => {
+            assert_eq!(limb, 0, "The sign bit has a single cell");
+            return (new_row + 2 * ELEM_ROWS, 0);
+        }
+    };
+    assert!(limb < 3, "An element only has three limbs");
+    (new_row + ELEM_ROWS * elem + limb, 0)
+}
+
+// Lays out the decompression of a point over a foreign curve, with its coordinates and its sign bit for the witness
+fn layout_foreign_decompression<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    curve: &ForeignCurve,
+    values: Option<(&BigUint, &BigUint, bool)>,
+) {
+    let p = &curve.modulus;
+    let point = Point {
+        x: layout.witness(values.map(|(x, _, _)| x.clone()), p),
+        y: layout.witness(values.map(|(_, y, _)| y.clone()), p),
+    };
+    let sign = layout.bit(values.map(|(_, _, sign)| sign));
+    layout.assert_on_curve(curve, &point);
+    let parity = layout_lowest_bit(layout, &point.y);
+    layout.connect(sign.cell, parity.cell);
+}
+
+impl<F: PrimeField + SquareRootField> CircuitGate<F> {
+    /// Extends a circuit with the decompression of a point of the curve `C` of base field `F`
+    /// Includes:
+    /// - the curve check of the point and the sign bit in generic gates
+    /// - the parity check of the y-coordinate (see the module)
+    /// Input:
+    /// - gates   : the full circuit
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the x-coordinate and the sign bit (see [decompression_cell]) should be copied from other cells
+    pub fn extend_decompression<C: KimchiCurve<BaseField = F>>(gates: &mut Vec<Self>) -> usize {
+        let (next_row, mut decompression_gates) = Self::create_decompression::<C>(gates.len());
+        gates.append(&mut decompression_gates);
+        next_row
+    }
+
+    /// Creates the decompression of a point, see [Self::extend_decompression]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_decompression<C: KimchiCurve<BaseField = F>>(
+        new_row: usize,
+    ) -> (usize, Vec<Self>) {
+        let (zero, one) = (F::zero(), F::one());
+        let b = curve_b::<C>();
+
+        // the curve check x * x - xx = 0, xx * x - xxx = 0 and y * y - xxx - b = 0, and s * s - s = 0
+        let square = [zero, zero, -one, one, zero];
+        let on_curve = [zero, zero, -one, one, -b];
+        let boolean = [-one, zero, zero, one, zero];
+        let mut gates = vec![
+            generic_gate(new_row, [square, square]),
+            generic_gate(new_row + 1, [on_curve, boolean]),
+        ];
+
+        // the parity of the y-coordinate
+        let mut layout = Layout::new(new_row + CURVE_ROWS, false);
+        let (y, parity) = layout_parity(&mut layout, None);
+        let (mut parity_gates, _) = layout.finalize();
+        gates.append(&mut parity_gates);
+
+        // wire the gadget (indices are relative to the gadget, wires are not)
+        let x = (0, 0);
+        gates.connect_cell_pair(x, (0, 1));
+        gates.connect_cell_pair(x, (0, 4));
+        gates.connect_cell_pair((0, 2), (0, 3));
+        gates.connect_cell_pair((0, 5), (1, 2));
+        gates.connect_cell_pair((1, 0), (1, 1));
+        gates.connect_cell_pair((1, 3), (1, 4));
+        gates.connect_cell_pair((1, 0), (y.0 - new_row, y.1));
+        gates.connect_cell_pair((1, 3), (parity.cell.0 - new_row, parity.cell.1));
+
+        (new_row + gates.len(), gates)
+    }
+
+    /// Extends a circuit with the decompression of a point of a foreign curve
+    /// Includes:
+    /// - the canonical coordinates of the point and the sign bit
+    /// - the curve check of the point and the parity check of its y-coordinate (see the module)
+    /// Input:
+    /// - gates   : the full circuit
+    /// - curve   : the foreign curve
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the x-coordinate and the sign bit (see [foreign_decompression_cell]) should be copied from other cells
+    pub fn extend_foreign_decompression(gates: &mut Vec<Self>, curve: &ForeignCurve) -> usize {
+        let (next_row, mut decompression_gates) =
+            Self::create_foreign_decompression(gates.len(), curve);
+        gates.append(&mut decompression_gates);
+        next_row
+    }
+
+    /// Creates the decompression of a point of a foreign curve, see [Self::extend_foreign_decompression]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_foreign_decompression(
+        new_row: usize,
+        curve: &ForeignCurve,
+    ) -> (usize, Vec<Self>) {
+        let mut layout = Layout::new(new_row, false);
+        layout_foreign_decompression(&mut layout, curve, None);
+        let (decompression_gates, _) = layout.finalize();
+        (new_row + decompression_gates.len(), decompression_gates)
+    }
+}
+
+// The square root of a value with the given parity, if the value is a square
+fn square_root<F: PrimeField + SquareRootField>(value: F, sign: bool) -> Option<F> {
+    value.sqrt().map(|root| {
+        if root.to_biguint().bit(0) == sign {
+            root
+        } else {
+            -root
+        }
+    })
+}
+
+/// Extends the witness with the decompression of a point over a native curve, and returns
+/// its y-coordinate, or `None` when `x` is not the x-coordinate of a point of the curve,
+/// since the witness then does not satisfy the gadget
+/// Input
+/// - witness: full witness of the circuit
+/// - x: the x-coordinate of the point
+/// - sign: the sign bit, the parity of the y-coordinate
+pub fn extend_decompression_witness<C: KimchiCurve>(
+    witness: &mut [Vec<C::BaseField>; COLUMNS],
+    x: C::BaseField,
+    sign: bool,
+) -> Option<C::BaseField>
+where
+    C::BaseField: PrimeField + SquareRootField,
+{
+    let xx = x.square();
+    let xxx = xx * x;
+    let y = square_root(xxx + curve_b::<C>(), sign);
+    let y_value = y.unwrap_or_else(C::BaseField::zero);
+
+    // the curve check and the sign bit
+    let s = C::BaseField::from(u64::from(sign));
+    let rows = [
+        [x, x, xx, xx, x, xxx],
+        [y_value, y_value, xxx, s, s, C::BaseField::zero()],
+    ];
+    let mut w: [Vec<C::BaseField>; COLUMNS] =
+        array::from_fn(|_| vec![C::BaseField::zero(); CURVE_ROWS]);
+    for (row, values) in rows.into_iter().enumerate() {
+        for (col, value) in values.into_iter().enumerate() {
+            w[col][row] = value;
+        }
+    }
+
+    // the parity of the y-coordinate
+    let mut layout = Layout::new(witness[0].len() + CURVE_ROWS, true);
+    layout_parity(&mut layout, Some(y_value));
+    let (_, parity_witness) = layout.finalize();
+    let parity_witness = parity_witness.expect("the witness is laid out");
+    for ((col, cells), parity_cells) in witness.iter_mut().zip(w).zip(parity_witness) {
+        col.extend(cells);
+        col.extend(parity_cells);
+    }
+    y
+}
+
+/// Extends the witness with the decompression of a point over a foreign curve, and returns
+/// its y-coordinate, or `None` when `x` is not the x-coordinate of a point of the curve,
+/// since the witness then does not satisfy the gadget
+/// Input
+/// - witness: full witness of the circuit
+/// - curve: the foreign curve
+/// - x: the x-coordinate of the point, smaller than the modulus
+/// - sign: the sign bit, the parity of the y-coordinate
+pub fn extend_foreign_decompression_witness<F: PrimeField + SquareRootField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    curve: &ForeignCurve,
+    x: &BigUint,
+    sign: bool,
+) -> Option<BigUint> {
+    let p = &curve.modulus;
+    assert!(x < p, "The x-coordinate is smaller than the modulus");
+    let y = curve
+        .y_coordinate(x)
+        .map(|y| if y.bit(0) == sign { y } else { (p - y) % p });
+    let y_value = y.clone().unwrap_or_else(BigUint::zero);
+
+    let mut layout = Layout::new(witness[0].len(), true);
+    layout_foreign_decompression(&mut layout, curve, Some((x, &y_value, sign)));
+    let (_, decompression_witness) = layout.finalize();
+    let decompression_witness = decompression_witness.expect("the witness is laid out");
+    for (col, cells) in witness.iter_mut().zip(decompression_witness) {
+        col.extend(cells);
+    }
+    y
+}
@@ -0,0 +1,321 @@
+//! This module includes the gadget verifying a batch of ECDSA signatures over a foreign curve
+//! with a random linear combination, and its witness code generation.
+//! Note that this module does not include a new gate type, the gadget reuses the `Poseidon` gate,
+//! and the foreign field gates, the range check gates and the `Generic` gate of the ECDSA layout.
+
+//~ The batch gadget verifies $N$ signatures $(r_i, s_i)$ of message hashes with public keys $Q_i$
+//~ over a foreign curve $E$ of base field $\mathbb{F}_p$ and of prime order $n$, as the ECDSA gadget,
+//~ with a single multi-scalar multiplication instead of $N$ double-and-add.
+//~ The scalars $z_i$ of the hashes are reduced as for the ECDSA gadget, and the native modulus is smaller than $n$.
+//~
+//~ Each signature is valid when the point $R_i = s_i^{-1} (z_i G + r_i Q_i)$ has the x-coordinate $x_{R_i} \equiv r_i \pmod n$,
+//~ that is when $s_i R_i = z_i G + r_i Q_i$ for a point $R_i$ of the witness with $x_{R_i} = r_i + k_i n$,
+//~ for a boolean $k_i$.
+//~ The gadget checks the random linear combination of these equations with the coefficients $\rho_i$, with $\rho_0 = s_0^{-1}$:
+//~ $$R_0 = \left( \sum_i \rho_i z_i \right) G + \sum_i (\rho_i r_i) Q_i - \sum_{i > 0} (\rho_i s_i) R_i$$
+//~ which holds for an invalid signature only with a negligible probability,
+//~ since the coefficients $\rho_i$ for $i > 0$ are squeezed from a Poseidon sponge (of the native field)
+//~ which absorbed the limbs of all the inputs and of all the points $R_i$, after they are fixed.
+//~
+//~ The gadget lays out:
+//~
+//~ * the inputs $z_i, r_i, s_i$ (modulo $n$) and $Q_i = (x_{Q_i}, y_{Q_i})$ (modulo $p$) of each signature, in their order,
+//~   then the points $R_i$, all checked to be canonical and on the curve,
+//~ * the checks $x_{R_i} = r_i + k_i n$ without overflow of the modulus $p$,
+//~ * the absorptions of the limbs, two per `Generic` row added to the state of the sponge, and their `Poseidon` permutations,
+//~ * each coefficient $\rho_i$ as an element of the witness checked to be smaller than the native modulus,
+//~   whose native recomposition is copied to a squeezed element of the state,
+//~ * the inverses of $r_i$ and $s_i$ modulo $n$, so that they are not zero,
+//~ * the scalars $\rho_i z_i$, summed into the scalar of $G$, $\rho_i r_i$ and $- \rho_i s_i$ modulo $n$, with their bits,
+//~ * the multi-scalar multiplication of the fixed point $G$, of the points $Q_i$ and of the points $R_i$ for $i > 0$
+//~   (see the multi-scalar multiplication gadget), whose result is constrained to be $R_0$.
+//~
+//~ The $2 N$ scalars share the doublings of the multi-scalar multiplication, so that the batch needs
+//~ about as many doublings as a single signature.
+//~ The inputs of the gadget are at the positions given by `ecdsa_batch_input_limb`.
+//~
+
+use crate::{
+    circuits::{
+        gate::CircuitGate,
+        polynomial::COLUMNS,
+        polynomials::{foreign_curve_msm, foreign_curve_mul::MulBase, poseidon::SPONGE_WIDTH},
+    },
+    curve::KimchiCurve,
+};
+use ark_ff::{PrimeField, SquareRootField};
+use mina_poseidon::poseidon::ArithmeticSpongeParams;
+use num_bigint::BigUint;
+use num_traits::Zero;
+use o1_utils::FieldHelpers;
+use std::array;
+
+use super::{
+    curve::{inverse, ForeignCurve, ForeignPoint},
+    hash_to_scalar,
+    layout::{Bit, Elem, Layout, Limb, Point},
+    verify_ecdsa, EcdsaInput, EcdsaSignature, ELEM_ROWS,
+};
+
+/// Number of inputs of each signature of the batch
+const SIGNATURE_INPUTS: usize = 5;
+
+/// Returns the position of the cell containing the `limb`-th limb (from the least significant one)
+/// of an input of the `i`-th signature of the batch gadget starting at row `new_row`, as a `(row, column)` pair
+pub fn ecdsa_batch_input_limb(
+    new_row: usize,
+    i: usize,
+    input: EcdsaInput,
+    limb: usize,
+) -> (usize, usize) {
+    assert!(limb < 3, "An element only has three limbs");
+    (
+        new_row + ELEM_ROWS * (SIGNATURE_INPUTS * i + input as usize) + limb,
+        0,
+    )
+}
+
+// Lays out the coefficient of a squeezed element of the sponge, as an element modulo the order
+fn layout_coefficient<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    squeezed: &Limb<F>,
+) -> Elem<F> {
+    let value = layout.limb_value(squeezed);
+    let elem = layout.witness(value.map(|value| value.to_biguint()), &F::modulus_biguint());
+    let cell = layout.native(&elem);
+    layout.copy(squeezed, cell);
+    elem
+}
+
+// Lays out the coefficients rho_1, ..., rho_{N - 1} squeezed from the sponge which absorbed the limbs of the elements
+fn layout_coefficients<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    params: &'static ArithmeticSpongeParams<F>,
+    elems: &[&Elem<F>],
+    num_coefficients: usize,
+) -> Vec<Elem<F>> {
+    if num_coefficients == 0 {
+        return vec![];
+    }
+    let limbs: Vec<Limb<F>> = elems.iter().flat_map(|elem| elem.limbs).collect();
+    let mut state = layout.absorb(params, &[Limb::Const(F::zero()); SPONGE_WIDTH], &limbs);
+    let mut coefficients = vec![];
+    for i in 0..num_coefficients {
+        // the rate is 2, and the state is permuted after two squeezed elements
+        if i > 0 && i % 2 == 0 {
+            state = layout.permutation(params, &state);
+        }
+        coefficients.push(layout_coefficient(layout, &state[i % 2]));
+    }
+    coefficients
+}
+
+// Lays out the verification of a batch of signatures, with the values of the inputs and of the points R for the witness
+fn layout_ecdsa_batch<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    params: &'static ArithmeticSpongeParams<F>,
+    curve: &ForeignCurve,
+    num_signatures: usize,
+    values: Option<(&[[BigUint; SIGNATURE_INPUTS]], &[ForeignPoint])>,
+) {
+    let (p, n) = (&curve.modulus, &curve.order);
+    assert!(num_signatures > 0, "The batch has signatures");
+    assert!(
+        p < &(n * 2u32),
+        "the base field modulus is smaller than twice the order"
+    );
+    assert!(
+        &F::modulus_biguint() < n,
+        "the native modulus is smaller than the order"
+    );
+
+    let moduli = [n, n, n, p, p];
+    let signatures: Vec<[Elem<F>; SIGNATURE_INPUTS]> = (0..num_signatures)
+        .map(|i| {
+            array::from_fn(|j| {
+                layout.witness(values.map(|(inputs, _)| inputs[i][j].clone()), moduli[j])
+            })
+        })
+        .collect();
+    let points: Vec<Point<F>> = (0..num_signatures)
+        .map(|i| Point {
+            x: layout.witness(values.map(|(_, points)| points[i].0.clone()), p),
+            y: layout.witness(values.map(|(_, points)| points[i].1.clone()), p),
+        })
+        .collect();
+
+    // the public keys and the points R are on the curve, and x_R = r + k n
+    let mut public_keys = vec![];
+    for ([_, r, _, x, y], point) in signatures.iter().zip(&points) {
+        let public_key = Point {
+            x: x.clone(),
+            y: y.clone(),
+        };
+        layout.assert_on_curve(curve, &public_key);
+        layout.assert_on_curve(curve, point);
+        let k = layout.bit(point.x.value.as_ref().map(|x| x >= n));
+        let kn = layout.select_constant(&k, n);
+        let rkn = layout.add_no_overflow(r, &kn, p);
+        layout.assert_eq(&rkn, &point.x);
+        public_keys.push(public_key);
+    }
+
+    // the coefficients, from the inverse of s_0
+    let elems: Vec<&Elem<F>> = signatures
+        .iter()
+        .flatten()
+        .chain(points.iter().flat_map(|point| [&point.x, &point.y]))
+        .collect();
+    let mut coefficients = vec![layout.inverse(&signatures[0][EcdsaInput::S as usize], n)];
+    coefficients.extend(layout_coefficients(
+        layout,
+        params,
+        &elems,
+        num_signatures - 1,
+    ));
+
+    // the scalars of G, of the public keys and of the points R (but R_0)
+    let zero = layout.constant(&BigUint::zero());
+    let mut generator_scalar = None;
+    let mut key_scalars = vec![];
+    let mut point_scalars = vec![];
+    for (i, ([hash, r, s, _, _], rho)) in signatures.iter().zip(&coefficients).enumerate() {
+        layout.inverse(r, n);
+        if i > 0 {
+            layout.inverse(s, n);
+            let rho_s = layout.mul(rho, s, n);
+            point_scalars.push(layout.sub(&zero, &rho_s, n));
+        }
+        let rho_z = layout.mul(rho, hash, n);
+        generator_scalar = Some(match generator_scalar {
+            Some(sum) => layout.add(&sum, &rho_z, n),
+            None => rho_z,
+        });
+        key_scalars.push(layout.mul(rho, r, n));
+    }
+    let generator_scalar = generator_scalar.expect("the batch has signatures");
+
+    // the multi-scalar multiplication is R_0
+    let num_bits = n.bits() as usize;
+    let mut bases = vec![MulBase::Fixed(curve.generator.clone())];
+    let mut terms: Vec<(Vec<Bit>, Point<F>)> = vec![(
+        layout.bits(&generator_scalar, num_bits),
+        layout.constant_point(&curve.generator),
+    )];
+    for (scalar, point) in key_scalars
+        .iter()
+        .zip(&public_keys)
+        .chain(point_scalars.iter().zip(&points[1..]))
+    {
+        bases.push(MulBase::Variable);
+        terms.push((layout.bits(scalar, num_bits), point.clone()));
+    }
+    let result = foreign_curve_msm::msm(layout, curve, &bases, &terms);
+    layout.assert_eq(&result.x, &points[0].x);
+    layout.assert_eq(&result.y, &points[0].y);
+}
+
+impl<F: PrimeField + SquareRootField> CircuitGate<F> {
+    /// Extends a circuit with the verification of a batch of ECDSA signatures over a foreign curve,
+    /// with the sponge of the curve `G` of scalar field `F`
+    /// Includes:
+    /// - the foreign field gates, range checks and generic gates of the verification (see the module)
+    /// - the Poseidon gates of the coefficients of the random linear combination
+    /// Input:
+    /// - gates          : the full circuit
+    /// - curve          : the foreign curve of the signatures
+    /// - num_signatures : the number of signatures of the batch
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the inputs (see [ecdsa_batch_input_limb]) should be copied from other cells, such as the public input
+    pub fn extend_ecdsa_batch<G: KimchiCurve<ScalarField = F>>(
+        gates: &mut Vec<Self>,
+        curve: &ForeignCurve,
+        num_signatures: usize,
+    ) -> usize {
+        let (next_row, mut batch_gates) =
+            Self::create_ecdsa_batch::<G>(gates.len(), curve, num_signatures);
+        gates.append(&mut batch_gates);
+        next_row
+    }
+
+    /// Creates the verification of a batch of ECDSA signatures, see [Self::extend_ecdsa_batch]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_ecdsa_batch<G: KimchiCurve<ScalarField = F>>(
+        new_row: usize,
+        curve: &ForeignCurve,
+        num_signatures: usize,
+    ) -> (usize, Vec<Self>) {
+        let mut layout = Layout::new(new_row, false);
+        layout_ecdsa_batch(&mut layout, G::sponge_params(), curve, num_signatures, None);
+        let (batch_gates, _) = layout.finalize();
+        (new_row + batch_gates.len(), batch_gates)
+    }
+}
+
+/// Extends the witness with the verification of a batch of ECDSA signatures, and returns whether they are all valid,
+/// since the witness of an invalid signature does not satisfy the gadget
+/// Input
+/// - witness: full witness of the circuit
+/// - curve: the foreign curve of the signatures
+/// - signatures: the message hashes (as big-endian bytes), the signatures and the public keys of the batch,
+///   whose parts are smaller than the order and whose coordinates are smaller than the base field modulus
+pub fn extend_ecdsa_batch_witness<G: KimchiCurve>(
+    witness: &mut [Vec<G::ScalarField>; COLUMNS],
+    curve: &ForeignCurve,
+    signatures: &[(&[u8], EcdsaSignature, ForeignPoint)],
+) -> bool
+where
+    G::ScalarField: PrimeField + SquareRootField,
+{
+    let (p, n) = (&curve.modulus, &curve.order);
+    let mut inputs = vec![];
+    let mut points = vec![];
+    for (msg_hash, signature, public_key) in signatures {
+        let EcdsaSignature { r, s } = signature;
+        let (x, y) = public_key;
+        assert!(
+            r < n && s < n,
+            "The parts of the signature are smaller than the order"
+        );
+        assert!(
+            x < p && y < p,
+            "The coordinates of the public key are smaller than the modulus"
+        );
+        let z = hash_to_scalar(curve, msg_hash);
+
+        // R = s^-1 (z G + r Q), or the generator when it is not a point
+        let point = if s.is_zero() {
+            None
+        } else {
+            let w = inverse(s, n);
+            curve.add(
+                &curve.scalar_mul(&curve.generator, &(&z * &w % n)),
+                &curve.scalar_mul(public_key, &(r * &w % n)),
+            )
+        };
+        points.push(point.unwrap_or_else(|| curve.generator.clone()));
+        inputs.push([z, r.clone(), s.clone(), x.clone(), y.clone()]);
+    }
+
+    let mut layout = Layout::new(witness[0].len(), true);
+    layout_ecdsa_batch(
+        &mut layout,
+        G::sponge_params(),
+        curve,
+        signatures.len(),
+        Some((&inputs, &points)),
+    );
+    let (_, batch_witness) = layout.finalize();
+    let batch_witness = batch_witness.expect("the witness is laid out");
+    for (col, cells) in witness.iter_mut().zip(batch_witness) {
+        col.extend(cells);
+    }
+
+    signatures.iter().all(|(msg_hash, signature, public_key)| {
+        verify_ecdsa(curve, msg_hash, signature, public_key)
+    })
+}
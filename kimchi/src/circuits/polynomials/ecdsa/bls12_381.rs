@@ -0,0 +1,68 @@
+//! The points of the group G1 of BLS12-381 (see [ForeignCurve::bls12_381_g1]),
+//! in the compressed encoding of the public keys of the Ethereum consensus layer.
+//!
+//! A point is encoded as the 48 big-endian bytes of its x-coordinate, whose 3 most significant bits are flags:
+//! the compression flag (always set), the infinity flag, and the sign flag, which is set if the y-coordinate
+//! is the largest of the two square roots.
+//!
+//! Note: the foreign field gates have 3 limbs of 88 bits, which do not fit the base field of BLS12-381,
+//! so that the points are only decoded out of the circuit.
+
+use num_bigint::BigUint;
+
+use super::curve::{ForeignCurve, ForeignPoint};
+
+/// Number of bytes of a compressed point of G1
+pub const G1_COMPRESSED_BYTES: usize = 48;
+
+// The flags of the most significant byte of a compressed point
+const COMPRESSION_FLAG: u8 = 0x80;
+const INFINITY_FLAG: u8 = 0x40;
+const SIGN_FLAG: u8 = 0x20;
+
+// Checks whether y is the largest of the square roots y and -y modulo p
+fn is_largest_root(y: &BigUint, p: &BigUint) -> bool {
+    y > &((p - y) % p)
+}
+
+/// Compresses a point of G1
+pub fn compress_g1(point: &ForeignPoint) -> [u8; G1_COMPRESSED_BYTES] {
+    let curve = ForeignCurve::bls12_381_g1();
+    let (x, y) = point;
+    let bytes = x.to_bytes_be();
+    let mut compressed = [0u8; G1_COMPRESSED_BYTES];
+    compressed[G1_COMPRESSED_BYTES - bytes.len()..].copy_from_slice(&bytes);
+    compressed[0] |= COMPRESSION_FLAG;
+    if is_largest_root(y, &curve.modulus) {
+        compressed[0] |= SIGN_FLAG;
+    }
+    compressed
+}
+
+/// Decompresses a public key, a point of G1 of the order of the generator,
+/// or returns `None` if the encoding is invalid, or if the point is the point at infinity
+/// or is not in the subgroup of the generator (as the `KeyValidate` of the BLS signatures)
+pub fn decompress_public_key(compressed: &[u8; G1_COMPRESSED_BYTES]) -> Option<ForeignPoint> {
+    let curve = ForeignCurve::bls12_381_g1();
+    let flags = compressed[0];
+    if flags & COMPRESSION_FLAG == 0 || flags & INFINITY_FLAG != 0 {
+        return None;
+    }
+    let mut bytes = *compressed;
+    bytes[0] &= !(COMPRESSION_FLAG | INFINITY_FLAG | SIGN_FLAG);
+    let x = BigUint::from_bytes_be(&bytes);
+    if x >= curve.modulus {
+        return None;
+    }
+    let y = curve.y_coordinate(&x)?;
+    let y = if is_largest_root(&y, &curve.modulus) == (flags & SIGN_FLAG != 0) {
+        y
+    } else {
+        (&curve.modulus - y) % &curve.modulus
+    };
+    let point = (x, y);
+    curve
+        .scalar_mul(&point, &curve.order)
+        .is_none()
+        .then_some(point)
+}
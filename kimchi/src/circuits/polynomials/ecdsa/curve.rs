@@ -0,0 +1,177 @@
+//! Arithmetic of the points of a foreign curve in short Weierstrass form,
+//! used to compute the witness of the ECDSA gadget
+
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use o1_utils::foreign_field::BigUintForeignFieldHelpers;
+
+/// An affine point of a foreign curve, as its coordinates `(x, y)`
+pub type ForeignPoint = (BigUint, BigUint);
+
+/// A foreign curve `y^2 = x^3 + a x + b` over the field of modulus `modulus`,
+/// with a generator of prime order `order`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForeignCurve {
+    /// Modulus of the base field
+    pub modulus: BigUint,
+    /// Order of the generator, the modulus of the scalar field
+    pub order: BigUint,
+    /// Coefficient `a` of the curve
+    pub a: BigUint,
+    /// Coefficient `b` of the curve
+    pub b: BigUint,
+    /// Generator of the group of the points
+    pub generator: ForeignPoint,
+}
+
+// The tag of the offset point of the scalar multiplications
+const OFFSET_TAG: &[u8] = b"kimchi ecdsa offset";
+
+pub(crate) fn from_hex(hex: &str) -> BigUint {
+    BigUint::parse_bytes(hex.as_bytes(), 16).expect("a hexadecimal constant")
+}
+
+/// Computes the inverse of `x` modulo the prime `modulus`, or zero if `x` is zero
+pub fn inverse(x: &BigUint, modulus: &BigUint) -> BigUint {
+    x.modpow(&(modulus - 2u32), modulus)
+}
+
+impl ForeignCurve {
+    /// The secp256k1 curve of Bitcoin and Ethereum
+    pub fn secp256k1() -> Self {
+        Self {
+            modulus: from_hex("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"),
+            order: from_hex("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"),
+            a: BigUint::zero(),
+            b: BigUint::from(7u32),
+            generator: (
+                from_hex("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+                from_hex("483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"),
+            ),
+        }
+    }
+
+    /// The P-256 curve (secp256r1) of NIST, of the WebAuthn and TLS signatures
+    pub fn p256() -> Self {
+        let modulus = from_hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff");
+        Self {
+            order: from_hex("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551"),
+            a: &modulus - 3u32,
+            b: from_hex("5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b"),
+            generator: (
+                from_hex("6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296"),
+                from_hex("4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"),
+            ),
+            modulus,
+        }
+    }
+
+    /// The group G1 of BLS12-381, of the public keys of the Ethereum consensus layer,
+    /// whose generator has the prime order of the scalar field of BLS12-381.
+    /// Warning: its base field of 381 bits does not fit in the 3 limbs of the foreign field gates
+    /// (see [ForeignCurve::is_supported]), so that it is only supported out of the circuit
+    pub fn bls12_381_g1() -> Self {
+        Self {
+            modulus: from_hex("1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab"),
+            order: from_hex("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"),
+            a: BigUint::zero(),
+            b: BigUint::from(4u32),
+            generator: (
+                from_hex("17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"),
+                from_hex("08b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1"),
+            ),
+        }
+    }
+
+    /// Checks that the gadgets over the native field `F` support the curve,
+    /// whose base field and scalar field must fit in the foreign field gates
+    pub fn is_supported<F: PrimeField>(&self) -> bool {
+        let max = BigUint::max_foreign_field_modulus::<F>();
+        self.modulus <= max && self.order <= max
+    }
+
+    // The right-hand side of the equation of the curve, x^3 + a x + b
+    fn rhs(&self, x: &BigUint) -> BigUint {
+        (x * x * x + &self.a * x + &self.b) % &self.modulus
+    }
+
+    /// Checks that the point is on the curve
+    pub fn is_on_curve(&self, point: &ForeignPoint) -> bool {
+        let (x, y) = point;
+        x < &self.modulus && y < &self.modulus && y * y % &self.modulus == self.rhs(x)
+    }
+
+    /// Negates a point
+    pub fn neg(&self, point: &ForeignPoint) -> ForeignPoint {
+        let (x, y) = point;
+        (x.clone(), (&self.modulus - y) % &self.modulus)
+    }
+
+    /// Adds two points, where `None` is the point at infinity
+    pub fn add(
+        &self,
+        left: &Option<ForeignPoint>,
+        right: &Option<ForeignPoint>,
+    ) -> Option<ForeignPoint> {
+        let ((x1, y1), (x2, y2)) = match (left, right) {
+            (None, _) => return right.clone(),
+            (_, None) => return left.clone(),
+            (Some(left), Some(right)) => (left, right),
+        };
+        let p = &self.modulus;
+        let lambda = if x1 == x2 {
+            if (y1 + y2) % p == BigUint::zero() {
+                return None;
+            }
+            // the tangent of the curve, 3 x^2 + a over 2 y
+            (BigUint::from(3u32) * x1 * x1 + &self.a) * inverse(&(y1 * 2u32 % p), p) % p
+        } else {
+            (y2 + p - y1) * inverse(&((x2 + p - x1) % p), p) % p
+        };
+        let x3 = (&lambda * &lambda + p + p - x1 - x2) % p;
+        let y3 = (lambda * (x1 + p - &x3) + p - y1) % p;
+        Some((x3, y3))
+    }
+
+    /// Multiplies a point by a scalar, where `None` is the point at infinity
+    pub fn scalar_mul(&self, point: &ForeignPoint, scalar: &BigUint) -> Option<ForeignPoint> {
+        let point = Some(point.clone());
+        (0..scalar.bits()).rev().fold(None, |acc, i| {
+            let acc = self.add(&acc, &acc);
+            if scalar.bit(i) {
+                self.add(&acc, &point)
+            } else {
+                acc
+            }
+        })
+    }
+
+    /// Returns a y-coordinate of the point of x-coordinate `x`, the other one being its negation,
+    /// or `None` if there is no such point on the curve
+    pub fn y_coordinate(&self, x: &BigUint) -> Option<BigUint> {
+        let p = &self.modulus;
+        assert_eq!(
+            p % 4u32,
+            BigUint::from(3u32),
+            "the square roots are powers for a modulus of 3 mod 4"
+        );
+        let rhs = self.rhs(x);
+        let y = rhs.modpow(&((p + BigUint::one()) / 4u32), p);
+        (&y * &y % p == rhs).then_some(y)
+    }
+
+    /// Returns the offset point of the scalar multiplications of the gadgets,
+    /// whose discrete logarithm is unknown: the first point of the curve whose
+    /// x-coordinate is at least the big-endian integer of the tag "kimchi ecdsa offset"
+    pub fn offset(&self) -> ForeignPoint {
+        let p = &self.modulus;
+        let mut x = BigUint::from_bytes_be(OFFSET_TAG) % p;
+        loop {
+            if let Some(y) = self.y_coordinate(&x) {
+                return (x, y);
+            }
+            x = (x + 1u32) % p;
+        }
+    }
+}
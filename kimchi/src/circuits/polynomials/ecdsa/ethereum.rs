@@ -0,0 +1,163 @@
+//! A circuit template verifying the signature of the hash of an Ethereum signed message
+//! (as signed by `personal_sign`), which packages the byte limbs gadget and the ECDSA gadget over secp256k1.
+//!
+//! The message is prefixed with `"\x19Ethereum Signed Message:\n"` and its length in decimal,
+//! and hashed with Keccak-256. The address of a public key is the last 20 bytes of the Keccak-256 hash
+//! of its uncompressed coordinates.
+//!
+//! The public input of the circuit is made of the 32 bytes of the hash of the message,
+//! followed by the 64 bytes of the coordinates of the public key, one byte per public input row.
+//! The byte limbs gadgets recompose the hash and the coordinates into foreign field elements,
+//! the hash is reduced modulo the order of the curve, and the ECDSA gadget verifies the signature
+//! (as for the other signed messages, such as the WebAuthn assertions).
+//!
+//! Note: there is no Keccak-256 permutation gadget yet, so the hashes are computed out of the circuit,
+//! and the circuit only proves the signature of the hash by the public key of its public input:
+//! neither the hash of the message nor the address of the public key are constrained in the circuit.
+//! The verifier computes the public input with [ethereum_public_input], from the message and the public key,
+//! which checks that the public key is the one of the address.
+
+use crate::circuits::{gate::CircuitGate, polynomial::COLUMNS, polynomials::keccak};
+use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::BigUint;
+use num_traits::Zero;
+use std::array;
+
+use super::{
+    curve::{self, ForeignCurve, ForeignPoint},
+    signed_hash::{self, coordinate_bytes, SIGNED_HASH_PUBLIC_INPUTS},
+    EcdsaSignature,
+};
+
+/// The prefix of the Ethereum signed messages, before the length of the message
+pub const ETHEREUM_MESSAGE_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n";
+
+/// Number of public inputs of the circuit: the bytes of the hash and of the public key
+pub const ETHEREUM_PUBLIC_INPUTS: usize = SIGNED_HASH_PUBLIC_INPUTS;
+
+/// An Ethereum signature, with its recovery id `v` (27 or 28, or 0 or 1)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EthereumSignature {
+    pub r: BigUint,
+    pub s: BigUint,
+    pub v: u8,
+}
+
+impl EthereumSignature {
+    /// Creates a signature from its 65 bytes `r || s || v`
+    pub fn from_bytes(bytes: &[u8; 65]) -> Self {
+        Self {
+            r: BigUint::from_bytes_be(&bytes[..32]),
+            s: BigUint::from_bytes_be(&bytes[32..64]),
+            v: bytes[64],
+        }
+    }
+}
+
+/// Returns the hash of an Ethereum signed message, Keccak-256 of the message with its prefix
+pub fn ethereum_message_hash(message: &[u8]) -> [u8; 32] {
+    let mut prefixed = ETHEREUM_MESSAGE_PREFIX.to_vec();
+    prefixed.extend(message.len().to_string().as_bytes());
+    prefixed.extend(message);
+    keccak::keccak256(&prefixed)
+}
+
+/// Returns the Ethereum address of a public key
+pub fn ethereum_address(public_key: &ForeignPoint) -> [u8; 20] {
+    let (x, y) = public_key;
+    let mut bytes = coordinate_bytes(x).to_vec();
+    bytes.extend(coordinate_bytes(y));
+    let hash = keccak::keccak256(&bytes);
+    array::from_fn(|i| hash[12 + i])
+}
+
+/// Recovers the public key of an ECDSA signature over secp256k1 of a message hash,
+/// or returns `None` if the signature is invalid
+pub fn recover_public_key(
+    curve: &ForeignCurve,
+    msg_hash: &[u8],
+    signature: &EthereumSignature,
+) -> Option<ForeignPoint> {
+    let (p, n) = (&curve.modulus, &curve.order);
+    let EthereumSignature { r, s, v } = signature;
+    let recovery_id = if *v >= 27 { v - 27 } else { *v };
+    if recovery_id > 3 || r.is_zero() || r >= n || s.is_zero() || s >= n {
+        return None;
+    }
+
+    // the point R of x-coordinate r (or r + n) and of the parity of the recovery id
+    let x = if recovery_id & 2 == 0 {
+        r.clone()
+    } else {
+        r + n
+    };
+    if &x >= p {
+        return None;
+    }
+    let y = curve.y_coordinate(&x)?;
+    let y = if y.bit(0) == (recovery_id & 1 == 1) {
+        y
+    } else {
+        p - y
+    };
+
+    // Q = r^(-1) (s R - z G)
+    let r_inv = curve::inverse(r, n);
+    let z = super::hash_to_scalar(curve, msg_hash);
+    let u1 = (n - z) * &r_inv % n;
+    let u2 = s * r_inv % n;
+    let public_key = curve.add(
+        &curve.scalar_mul(&curve.generator, &u1),
+        &curve.scalar_mul(&(x, y), &u2),
+    )?;
+    let signature = EcdsaSignature {
+        r: r.clone(),
+        s: s.clone(),
+    };
+    super::verify_ecdsa(curve, msg_hash, &signature, &public_key).then_some(public_key)
+}
+
+/// Returns the public input of the circuit for a message and a public key,
+/// or `None` if the public key is not the one of the address
+pub fn ethereum_public_input<F: PrimeField>(
+    address: &[u8; 20],
+    message: &[u8],
+    public_key: &ForeignPoint,
+) -> Option<Vec<F>> {
+    if ethereum_address(public_key) != *address {
+        return None;
+    }
+    Some(signed_hash::signed_hash_public_input(
+        &ethereum_message_hash(message),
+        public_key,
+    ))
+}
+
+/// Creates the gates of the circuit verifying the signature of the hash of an Ethereum signed message,
+/// with [ETHEREUM_PUBLIC_INPUTS] public inputs (see the module)
+pub fn ethereum_gates<F: PrimeField + SquareRootField>() -> Vec<CircuitGate<F>> {
+    signed_hash::signed_hash_gates(&ForeignCurve::secp256k1())
+}
+
+/// Creates the witness of the circuit verifying the signature of the hash of an Ethereum signed message,
+/// and returns it with the public key of the signature,
+/// or `None` if the signature is invalid or if its public key is not the one of the address
+pub fn ethereum_witness<F: PrimeField + SquareRootField>(
+    address: &[u8; 20],
+    message: &[u8],
+    signature: &EthereumSignature,
+) -> Option<([Vec<F>; COLUMNS], ForeignPoint)> {
+    let curve = ForeignCurve::secp256k1();
+    let msg_hash = ethereum_message_hash(message);
+    let public_key = recover_public_key(&curve, &msg_hash, signature)?;
+    if ethereum_address(&public_key) != *address {
+        return None;
+    }
+    let ecdsa_signature = EcdsaSignature {
+        r: signature.r.clone(),
+        s: signature.s.clone(),
+    };
+    let witness =
+        signed_hash::signed_hash_witness(&curve, &msg_hash, &ecdsa_signature, &public_key);
+    Some((witness, public_key))
+}
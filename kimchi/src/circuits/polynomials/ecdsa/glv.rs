@@ -0,0 +1,124 @@
+//! The GLV endomorphism of a foreign curve such as secp256k1, which decomposes a scalar multiplication
+//! into a double-scalar multiplication by scalars of half the bits, and its layout
+
+use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::{BigInt, BigUint, Sign};
+use num_integer::Integer;
+use num_traits::{Signed, Zero};
+
+use super::{
+    curve::{from_hex, ForeignCurve, ForeignPoint},
+    layout::{Bit, Elem, Layout, Point},
+};
+
+/// The endomorphism `phi(x, y) = (beta x, y)` of a curve `y^2 = x^3 + b`,
+/// which multiplies the points of the group of the generator by `lambda`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GlvEndomorphism {
+    /// The cube root of unity of the base field of the endomorphism
+    pub beta: BigUint,
+    /// The cube root of unity of the scalar field, the eigenvalue of the endomorphism
+    pub lambda: BigUint,
+    /// A reduced basis `[(a1, b1), (a2, b2)]` of the lattice of the pairs `(k1, k2)`
+    /// such that `k1 + k2 lambda = 0` modulo the order
+    pub basis: [(BigInt, BigInt); 2],
+}
+
+impl GlvEndomorphism {
+    /// The endomorphism of secp256k1, with the basis of libsecp256k1
+    pub fn secp256k1() -> Self {
+        let a1 = BigInt::from(from_hex("3086d221a7d46bcde86c90e49284eb15"));
+        let b1 = -BigInt::from(from_hex("e4437ed6010e88286f547fa90abfe4c3"));
+        let a2 = BigInt::from(from_hex("114ca50f7a8e2f3f657c1108d9d44cfd8"));
+        Self {
+            beta: from_hex("7ae96a2b657c07106e64479eac3434e99cf0497512f58995c1396c28719501ee"),
+            lambda: from_hex("5363ad4cc05c30e0a5261c028812645a122e22ea20816678df02967c1b23bd72"),
+            basis: [(a1.clone(), b1), (a2, a1)],
+        }
+    }
+
+    /// Applies the endomorphism to a point of the curve
+    pub fn apply(&self, curve: &ForeignCurve, point: &ForeignPoint) -> ForeignPoint {
+        let (x, y) = point;
+        (&self.beta * x % &curve.modulus, y.clone())
+    }
+
+    // The lengths of the sides of the box [0, |a1| + |a2|) x [0, |b1| + |b2|) of the decompositions
+    fn box_sides(&self) -> (BigInt, BigInt) {
+        let [(a1, b1), (a2, b2)] = &self.basis;
+        (a1.abs() + a2.abs(), b1.abs() + b2.abs())
+    }
+
+    /// The number of bits of the scalars of the decompositions
+    pub fn scalar_bits(&self) -> usize {
+        let (side1, side2) = self.box_sides();
+        let one = BigInt::from(1u32);
+        (side1 - &one).bits().max((side2 - one).bits()) as usize
+    }
+
+    /// Decomposes a scalar `k` into the scalars `(k1, k2)` of at most [Self::scalar_bits] bits
+    /// such that `k1 + k2 lambda = k` modulo the order
+    pub fn decompose(&self, scalar: &BigUint) -> (BigUint, BigUint) {
+        let [(a1, b1), (a2, b2)] = &self.basis;
+        let zero = BigInt::zero();
+        // the lowest corner of the parallelogram spanned by the basis, within the box
+        let low1 = a1.min(&zero) + a2.min(&zero);
+        let low2 = b1.min(&zero) + b2.min(&zero);
+
+        // reduces (k, 0) + low into the parallelogram, whose coordinates in the basis are in [0, 1)
+        let (t1, t2) = (
+            BigInt::from_biguint(Sign::Plus, scalar.clone()) + &low1,
+            low2.clone(),
+        );
+        let det = a1 * b2 - a2 * b1;
+        let x = (&t1 * b2 - &t2 * a2).div_floor(&det);
+        let y = (a1 * &t2 - b1 * &t1).div_floor(&det);
+        let k1 = t1 - &x * a1 - &y * a2 - low1;
+        let k2 = t2 - x * b1 - y * b2 - low2;
+
+        let (side1, side2) = self.box_sides();
+        assert!(
+            !k1.is_negative() && k1 < side1 && !k2.is_negative() && k2 < side2,
+            "the decomposition is in the box"
+        );
+        (k1.magnitude().clone(), k2.magnitude().clone())
+    }
+}
+
+/// Lays out the decomposition of a scalar `k` modulo the order into the scalars `k1` and `k2`
+/// such that `k1 + k2 lambda = k`, and returns their bits (from the most significant one)
+pub fn decompose_scalar<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    curve: &ForeignCurve,
+    glv: &GlvEndomorphism,
+    scalar: &Elem<F>,
+) -> [Vec<Bit>; 2] {
+    let n = &curve.order;
+    let num_bits = glv.scalar_bits();
+    assert!(
+        num_bits < n.bits() as usize,
+        "the scalars of the decomposition are smaller than the order"
+    );
+    let scalars = scalar.value.as_ref().map(|k| glv.decompose(k));
+    let k1 = layout.witness(scalars.as_ref().map(|(k1, _)| k1.clone()), n);
+    let k2 = layout.witness(scalars.as_ref().map(|(_, k2)| k2.clone()), n);
+    let lambda = layout.constant(&glv.lambda);
+    let k2_lambda = layout.mul(&k2, &lambda, n);
+    let sum = layout.add(&k1, &k2_lambda, n);
+    layout.assert_eq(&sum, scalar);
+    [layout.bits(&k1, num_bits), layout.bits(&k2, num_bits)]
+}
+
+/// Lays out the image of a point by the endomorphism
+pub fn endomorphism_point<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    curve: &ForeignCurve,
+    glv: &GlvEndomorphism,
+    point: &Point<F>,
+) -> Point<F> {
+    let beta = layout.constant(&glv.beta);
+    Point {
+        x: layout.mul(&point.x, &beta, &curve.modulus),
+        y: point.y.clone(),
+    }
+}
@@ -0,0 +1,778 @@
+//! Layout of the foreign field and foreign curve operations of the ECDSA gadget,
+//! shared by its circuit and its witness
+//!
+//! The layout creates the gates of the operations, their copy constraints and,
+//! when the values of the inputs are known, their witness.
+//! The constants are shared by all their uses, in generic gates appended by [Layout::finalize].
+
+use crate::circuits::{
+    gate::{CircuitGate, Connect, GateType},
+    polynomial::COLUMNS,
+    polynomials::{
+        foreign_field_add::{self, witness::FFOps},
+        foreign_field_mul,
+        generic::{DOUBLE_GENERIC_COEFFS, GENERIC_COEFFS, GENERIC_REGISTERS},
+        poseidon::{self, POS_ROWS_PER_HASH, SPONGE_WIDTH},
+        range_check,
+    },
+    wires::Wire,
+};
+use ark_ff::{PrimeField, SquareRootField};
+use mina_poseidon::poseidon::ArithmeticSpongeParams;
+use num_bigint::BigUint;
+use num_traits::Zero;
+use o1_utils::foreign_field::{BigUintForeignFieldHelpers, ForeignFieldHelpers, LIMB_BITS};
+use std::array;
+
+use super::curve::{inverse, ForeignCurve, ForeignPoint};
+
+/// A cell of the witness, as a `(row, column)` pair
+pub type Cell = (usize, usize);
+
+/// A limb of a foreign field element, in a cell or a constant of the circuit
+#[derive(Clone, Copy, Debug)]
+pub enum Limb<F> {
+    Cell(Cell),
+    Const(F),
+}
+
+/// A foreign field element of 3 limbs, from the least significant one,
+/// with its value when the witness is laid out
+#[derive(Clone, Debug)]
+pub struct Elem<F> {
+    pub limbs: [Limb<F>; 3],
+    pub value: Option<BigUint>,
+}
+
+/// A point of the foreign curve, which is not the point at infinity
+#[derive(Clone, Debug)]
+pub struct Point<F> {
+    pub x: Elem<F>,
+    pub y: Elem<F>,
+}
+
+/// A boolean in a cell, with its value when the witness is laid out
+#[derive(Clone, Copy, Debug)]
+pub struct Bit {
+    pub cell: Cell,
+    pub value: Option<bool>,
+}
+
+/// An input of a generic gate
+pub enum Src<F> {
+    /// A copy of a limb
+    Copy(Limb<F>),
+    /// A new cell, with its value when the witness is laid out
+    New(Option<F>),
+}
+
+fn known<T>(value: Option<T>) -> T {
+    value.expect("the values are known when the witness is laid out")
+}
+
+impl<F> Elem<F> {
+    fn val(&self) -> &BigUint {
+        known(self.value.as_ref())
+    }
+}
+
+/// The gates, copy constraints and witness of a sequence of operations
+pub struct Layout<F: PrimeField> {
+    start_row: usize,
+    gates: Vec<CircuitGate<F>>,
+    witness: Option<[Vec<F>; COLUMNS]>,
+    // The row of the generic gate whose second half is free
+    half: Option<usize>,
+    // The constants and the cells which copy them
+    constants: Vec<(F, Vec<Cell>)>,
+}
+
+impl<F: PrimeField + SquareRootField> Layout<F> {
+    /// Creates a layout starting at row `start_row`, with its witness or only its gates
+    pub fn new(start_row: usize, with_witness: bool) -> Self {
+        Self {
+            start_row,
+            gates: vec![],
+            witness: with_witness.then(|| array::from_fn(|_| vec![])),
+            half: None,
+            constants: vec![],
+        }
+    }
+
+    /// Returns the row after the gates of the layout
+    pub fn next_row(&self) -> usize {
+        self.start_row + self.gates.len()
+    }
+
+    /// Computes a value when the witness is laid out
+    pub fn value<T>(&self, f: impl FnOnce() -> T) -> Option<T> {
+        self.witness.as_ref().map(|_| f())
+    }
+
+    /// Lays out the shared constants, and returns the gates and the witness of the layout
+    pub fn finalize(mut self) -> (Vec<CircuitGate<F>>, Option<[Vec<F>; COLUMNS]>) {
+        for (constant, uses) in std::mem::take(&mut self.constants) {
+            let [cell, _, _] = self.generic(
+                [F::one(), F::zero(), F::zero(), F::zero(), -constant],
+                Src::New(Some(constant)),
+                Src::New(Some(F::zero())),
+            );
+            for dst in uses {
+                self.connect(cell, dst);
+            }
+        }
+        (self.gates, self.witness)
+    }
+
+    /// Appends rows of gates, and their witness when it is laid out
+    pub fn push(
+        &mut self,
+        gates: Vec<CircuitGate<F>>,
+        witness: impl FnOnce() -> [Vec<F>; COLUMNS],
+    ) {
+        if let Some(layout_witness) = self.witness.as_mut() {
+            let rows = witness();
+            assert_eq!(rows[0].len(), gates.len(), "a witness row per gate");
+            for (col, cells) in layout_witness.iter_mut().zip(rows) {
+                col.extend(cells);
+            }
+        }
+        self.gates.extend(gates);
+    }
+
+    fn set(&mut self, (row, col): Cell, value: Option<F>) {
+        if let (Some(witness), Some(value)) = (self.witness.as_mut(), value) {
+            witness[col][row - self.start_row] = value;
+        }
+    }
+
+    /// Returns the value of a limb when the witness is laid out
+    pub fn limb_value(&self, limb: &Limb<F>) -> Option<F> {
+        match limb {
+            Limb::Cell((row, col)) => self
+                .witness
+                .as_ref()
+                .map(|witness| witness[*col][row - self.start_row]),
+            Limb::Const(constant) => Some(*constant),
+        }
+    }
+
+    /// Connects two cells of the layout with a copy constraint
+    pub fn connect(&mut self, (row1, col1): Cell, (row2, col2): Cell) {
+        let start = self.start_row;
+        self.gates
+            .connect_cell_pair((row1 - start, col1), (row2 - start, col2));
+    }
+
+    /// Copies a limb to a cell of the layout
+    pub fn copy(&mut self, limb: &Limb<F>, dst: Cell) {
+        match limb {
+            Limb::Cell(src) => self.connect(*src, dst),
+            Limb::Const(constant) => match self.constants.iter_mut().find(|(c, _)| c == constant) {
+                Some((_, uses)) => uses.push(dst),
+                None => self.constants.push((*constant, vec![dst])),
+            },
+        }
+    }
+
+    fn copy_elem(&mut self, elem: &Elem<F>, cells: [Cell; 3]) {
+        for (limb, cell) in elem.limbs.iter().zip(cells) {
+            self.copy(limb, cell);
+        }
+    }
+
+    /// Constrains two elements to be equal
+    pub fn assert_eq(&mut self, left: &Elem<F>, right: &Elem<F>) {
+        for (left, right) in left.limbs.iter().zip(&right.limbs) {
+            match (left, right) {
+                (Limb::Cell(cell), limb) | (limb, Limb::Cell(cell)) => self.copy(limb, *cell),
+                (Limb::Const(left), Limb::Const(right)) => {
+                    assert_eq!(left, right, "the constants are equal")
+                }
+            }
+        }
+    }
+
+    /// Lays out half of a generic gate of native field elements, whose output is computed from its inputs
+    /// when its coefficient is not zero, and returns the cells of the half gate
+    pub fn generic(
+        &mut self,
+        coeffs: [F; GENERIC_COEFFS],
+        left: Src<F>,
+        right: Src<F>,
+    ) -> [Cell; 3] {
+        let (row, half) = match self.half.take() {
+            Some(row) => (row, 1),
+            None => {
+                let row = self.next_row();
+                let gate = CircuitGate::new(
+                    GateType::Generic,
+                    Wire::for_row(row),
+                    vec![F::zero(); DOUBLE_GENERIC_COEFFS],
+                );
+                self.push(vec![gate], || array::from_fn(|_| vec![F::zero()]));
+                self.half = Some(row);
+                (row, 0)
+            }
+        };
+        let start = self.start_row;
+        self.gates[row - start].coeffs[half * GENERIC_COEFFS..(half + 1) * GENERIC_COEFFS]
+            .copy_from_slice(&coeffs);
+
+        let cells: [Cell; 3] = array::from_fn(|i| (row, half * GENERIC_REGISTERS + i));
+        let mut values = [None; 2];
+        for ((src, cell), value) in [left, right].into_iter().zip(cells).zip(&mut values) {
+            *value = match src {
+                Src::Copy(limb) => {
+                    self.copy(&limb, cell);
+                    self.limb_value(&limb)
+                }
+                Src::New(new) => new,
+            };
+            self.set(cell, *value);
+        }
+        let [l, r, o, m, c] = coeffs;
+        let output = if o.is_zero() {
+            Some(F::zero())
+        } else {
+            values[0]
+                .zip(values[1])
+                .map(|(left, right)| -(l * left + r * right + m * left * right + c) / o)
+        };
+        self.set(cells[2], output);
+        cells
+    }
+
+    /// Lays out the output of a generic gate, from its coefficients and inputs
+    pub fn generic_output(
+        &mut self,
+        coeffs: [F; GENERIC_COEFFS],
+        left: Src<F>,
+        right: Src<F>,
+    ) -> Cell {
+        let [_, _, output] = self.generic(coeffs, left, right);
+        output
+    }
+
+    /// Lays out a boolean
+    pub fn bit(&mut self, value: Option<bool>) -> Bit {
+        let value_field = value.map(F::from);
+        // b * b - b = 0
+        let [left, right, _] = self.generic(
+            [-F::one(), F::zero(), F::zero(), F::one(), F::zero()],
+            Src::New(value_field),
+            Src::New(value_field),
+        );
+        self.connect(left, right);
+        Bit { cell: left, value }
+    }
+
+    /// Lays out the bits of an element of at most `num_bits` bits, from the most significant one
+    pub fn bits(&mut self, elem: &Elem<F>, num_bits: usize) -> Vec<Bit> {
+        let mut bits = vec![];
+        for limb in (0..3).rev() {
+            let limb_bits = num_bits.saturating_sub(LIMB_BITS * limb).min(LIMB_BITS);
+            let mut acc = Limb::Const(F::zero());
+            for i in (0..limb_bits).rev() {
+                let bit = self.bit(self.value(|| elem.val().bit((LIMB_BITS * limb + i) as u64)));
+                // 2 acc + b - acc' = 0
+                acc = Limb::Cell(self.generic_output(
+                    [F::from(2u64), F::one(), -F::one(), F::zero(), F::zero()],
+                    Src::Copy(acc),
+                    Src::Copy(Limb::Cell(bit.cell)),
+                ));
+                bits.push(bit);
+            }
+            match (acc, elem.limbs[limb]) {
+                (acc, Limb::Cell(cell)) => self.copy(&acc, cell),
+                (Limb::Cell(cell), limb) => self.copy(&limb, cell),
+                (Limb::Const(acc), Limb::Const(limb)) => {
+                    assert_eq!(acc, limb, "the constant fits in the number of bits")
+                }
+            }
+        }
+        bits
+    }
+
+    /// Lays out the native field element `l0 + 2^88 l1 + 2^176 l2` of the limbs of an element,
+    /// which is its value when it is smaller than the native modulus, and returns its cell
+    pub fn native(&mut self, elem: &Elem<F>) -> Cell {
+        let two_to_limb = F::two_to_limb();
+        // l0 + 2^88 l1 - acc = 0
+        let acc = self.generic_output(
+            [F::one(), two_to_limb, -F::one(), F::zero(), F::zero()],
+            Src::Copy(elem.limbs[0]),
+            Src::Copy(elem.limbs[1]),
+        );
+        // acc + 2^176 l2 - out = 0
+        self.generic_output(
+            [
+                F::one(),
+                two_to_limb * two_to_limb,
+                -F::one(),
+                F::zero(),
+                F::zero(),
+            ],
+            Src::Copy(Limb::Cell(acc)),
+            Src::Copy(elem.limbs[2]),
+        )
+    }
+
+    /// Lays out the Poseidon permutation of the state of a sponge of native field elements,
+    /// and returns the permuted state
+    pub fn permutation(
+        &mut self,
+        params: &'static ArithmeticSpongeParams<F>,
+        state: &[Limb<F>; SPONGE_WIDTH],
+    ) -> [Limb<F>; SPONGE_WIDTH] {
+        let row = self.next_row();
+        let (gates, output_row) = CircuitGate::create_poseidon_gadget(
+            row,
+            [Wire::for_row(row), Wire::for_row(row + POS_ROWS_PER_HASH)],
+            &params.round_constants,
+        );
+        let input: Option<Vec<F>> = state.iter().map(|limb| self.limb_value(limb)).collect();
+        self.push(gates, || {
+            let mut witness = array::from_fn(|_| vec![F::zero(); POS_ROWS_PER_HASH + 1]);
+            let input = known(input);
+            poseidon::generate_witness(0, params, &mut witness, array::from_fn(|i| input[i]));
+            witness
+        });
+        for (i, limb) in state.iter().enumerate() {
+            self.copy(limb, (row, i));
+        }
+        array::from_fn(|i| Limb::Cell((output_row, i)))
+    }
+
+    /// Lays out the absorption of native field elements by a sponge of rate 2 from its state,
+    /// adding each pair of elements to the first cells of the state before a permutation,
+    /// and returns the permuted state
+    pub fn absorb(
+        &mut self,
+        params: &'static ArithmeticSpongeParams<F>,
+        state: &[Limb<F>; SPONGE_WIDTH],
+        input: &[Limb<F>],
+    ) -> [Limb<F>; SPONGE_WIDTH] {
+        let mut state = *state;
+        for pair in input.chunks(2) {
+            for (cell, elem) in state.iter_mut().zip(pair) {
+                *cell = match *cell {
+                    Limb::Const(constant) if constant.is_zero() => *elem,
+                    // s + a - s' = 0
+                    current => Limb::Cell(self.generic_output(
+                        [F::one(), F::one(), -F::one(), F::zero(), F::zero()],
+                        Src::Copy(current),
+                        Src::Copy(*elem),
+                    )),
+                };
+            }
+            state = self.permutation(params, &state);
+        }
+        state
+    }
+
+    /// Lays out the disjunction of two booleans, `b1 + b2 - b1 b2`
+    pub fn or(&mut self, b1: &Bit, b2: &Bit) -> Bit {
+        let cell = self.generic_output(
+            [F::one(), F::one(), -F::one(), -F::one(), F::zero()],
+            Src::Copy(Limb::Cell(b1.cell)),
+            Src::Copy(Limb::Cell(b2.cell)),
+        );
+        let value = b1.value.zip(b2.value).map(|(b1, b2)| b1 || b2);
+        Bit { cell, value }
+    }
+
+    /// Lays out the exclusive disjunction of two booleans, `b1 + b2 - 2 b1 b2`
+    pub fn xor(&mut self, b1: &Bit, b2: &Bit) -> Bit {
+        let cell = self.generic_output(
+            [F::one(), F::one(), -F::one(), -F::from(2u64), F::zero()],
+            Src::Copy(Limb::Cell(b1.cell)),
+            Src::Copy(Limb::Cell(b2.cell)),
+        );
+        let value = b1.value.zip(b2.value).map(|(b1, b2)| b1 != b2);
+        Bit { cell, value }
+    }
+
+    /// Lays out the constant if the boolean is true, and zero otherwise
+    pub fn select_constant(&mut self, bit: &Bit, value: &BigUint) -> Elem<F> {
+        let limbs = value.to_field_limbs().map(|limb: F| {
+            // c b - out = 0
+            Limb::Cell(self.generic_output(
+                [limb, F::zero(), -F::one(), F::zero(), F::zero()],
+                Src::Copy(Limb::Cell(bit.cell)),
+                Src::New(Some(F::zero())),
+            ))
+        });
+        let value = bit
+            .value
+            .map(|bit| if bit { value.clone() } else { BigUint::zero() });
+        Elem { limbs, value }
+    }
+
+    /// Lays out the entry of a table of constants at the index of one or two booleans (from the most significant one),
+    /// as the multilinear interpolation of the table in the coefficients of a generic gate per limb,
+    /// `c0 + (c2 - c0) b1 + (c1 - c0) b0 + (c3 - c2 - c1 + c0) b1 b0` for two booleans
+    pub fn select_constant_table(&mut self, bits: &[Bit], table: &[BigUint]) -> Elem<F> {
+        assert!(
+            !bits.is_empty() && bits.len() <= 2 && table.len() == 1 << bits.len(),
+            "the table has an entry for each index of one or two booleans"
+        );
+        let limbs: Vec<[F; 3]> = table.iter().map(|entry| entry.to_field_limbs()).collect();
+        let limbs = array::from_fn(|i| {
+            let c: Vec<F> = limbs.iter().map(|entry| entry[i]).collect();
+            // c0 + (c1 - c0) b - out = 0, or the interpolation over two booleans
+            let (coeffs, right) = match bits {
+                [_] => (
+                    [c[1] - c[0], F::zero(), -F::one(), F::zero(), c[0]],
+                    Src::New(Some(F::zero())),
+                ),
+                [_, low] => (
+                    [
+                        c[2] - c[0],
+                        c[1] - c[0],
+                        -F::one(),
+                        c[3] - c[2] - c[1] + c[0],
+                        c[0],
+                    ],
+                    Src::Copy(Limb::Cell(low.cell)),
+                ),
+                _ => unreachable!(),
+            };
+            Limb::Cell(self.generic_output(coeffs, Src::Copy(Limb::Cell(bits[0].cell)), right))
+        });
+        let value = bits
+            .iter()
+            .try_fold(0usize, |index, bit| {
+                bit.value.map(|bit| 2 * index + usize::from(bit))
+            })
+            .map(|index| table[index].clone());
+        Elem { limbs, value }
+    }
+
+    /// Lays out a limb as b (u - v) + v
+    pub fn select_limb(&mut self, bit: &Bit, u: &Limb<F>, v: &Limb<F>) -> Limb<F> {
+        let one = F::one();
+        let zero = F::zero();
+        // u - v - d = 0
+        let d = self.generic_output([one, -one, -one, zero, zero], Src::Copy(*u), Src::Copy(*v));
+        // b d - e = 0
+        let e = self.generic_output(
+            [zero, zero, -one, one, zero],
+            Src::Copy(Limb::Cell(bit.cell)),
+            Src::Copy(Limb::Cell(d)),
+        );
+        // e + v - out = 0
+        Limb::Cell(self.generic_output(
+            [one, one, -one, zero, zero],
+            Src::Copy(Limb::Cell(e)),
+            Src::Copy(*v),
+        ))
+    }
+
+    /// Lays out the element `u` if the boolean is true, and `v` otherwise
+    pub fn select(&mut self, bit: &Bit, u: &Elem<F>, v: &Elem<F>) -> Elem<F> {
+        let limbs = array::from_fn(|i| self.select_limb(bit, &u.limbs[i], &v.limbs[i]));
+        let value = bit.value.map(|bit| {
+            if bit {
+                u.val().clone()
+            } else {
+                v.val().clone()
+            }
+        });
+        Elem { limbs, value }
+    }
+
+    /// Returns the element of a constant
+    pub fn constant(&self, value: &BigUint) -> Elem<F> {
+        Elem {
+            limbs: value.to_field_limbs().map(Limb::Const),
+            value: self.value(|| value.clone()),
+        }
+    }
+
+    // Lays out a multi-range-check of limbs, and returns the cells of the limbs
+    fn multi_range_check(&mut self, limbs: Option<[F; 3]>) -> [Cell; 3] {
+        let row = self.next_row();
+        let (_, gates) = CircuitGate::create_multi_range_check(row);
+        self.push(gates, || {
+            range_check::witness::create_multi_limbs(&known(limbs))
+        });
+        array::from_fn(|i| range_check::gadget::multi_range_check_limb(row, 0, i))
+    }
+
+    // Lays out a multi-range-check of the limbs of the cells
+    fn range_check_cells(&mut self, cells: [Cell; 3], value: Option<&BigUint>) {
+        let checked = self.multi_range_check(value.map(|value| value.to_field_limbs()));
+        for (cell, checked) in cells.into_iter().zip(checked) {
+            self.connect(cell, checked);
+        }
+    }
+
+    // Copies the constants of a bound addition row: the right input 2^264, and the overflow 1
+    fn bound_constants(&mut self, row: usize) {
+        self.copy(&Limb::Const(F::zero()), (row, 3));
+        self.copy(&Limb::Const(F::zero()), (row, 4));
+        self.copy(&Limb::Const(F::two_to_limb()), (row, 5));
+        self.copy(&Limb::Const(F::one()), (row, 6));
+    }
+
+    // Lays out the bound addition x + 2^264 - f and the range check of its result,
+    // which check that the element of range checked limbs is smaller than the modulus
+    fn bound_check(&mut self, cells: [Cell; 3], value: Option<&BigUint>, modulus: &BigUint) {
+        let row = self.next_row();
+        let (_, gates) = CircuitGate::create_single_ffadd(row, FFOps::Add, modulus);
+        self.push(gates, || {
+            let mut witness = array::from_fn(|_| vec![]);
+            foreign_field_add::witness::extend_witness_bound_addition(
+                &mut witness,
+                &known(value).to_field_limbs(),
+                &modulus.to_field_limbs(),
+            );
+            witness
+        });
+        for (i, cell) in cells.into_iter().enumerate() {
+            self.connect(cell, (row, i));
+        }
+        self.bound_constants(row);
+        let bound = self.value(|| known(value) + modulus.negate());
+        self.range_check_cells(array::from_fn(|i| (row + 1, i)), bound.as_ref());
+    }
+
+    /// Lays out an element of the witness, checked to be smaller than the modulus
+    pub fn witness(&mut self, value: Option<BigUint>, modulus: &BigUint) -> Elem<F> {
+        let cells = self.multi_range_check(value.as_ref().map(|value| value.to_field_limbs()));
+        self.bound_check(cells, value.as_ref(), modulus);
+        Elem {
+            limbs: cells.map(Limb::Cell),
+            value,
+        }
+    }
+
+    // Lays out an addition or a subtraction of elements modulo the modulus,
+    // and returns its row and its result, checked to be smaller than the modulus
+    fn add_sub(
+        &mut self,
+        left: &Elem<F>,
+        right: &Elem<F>,
+        op: FFOps,
+        modulus: &BigUint,
+    ) -> (usize, Elem<F>) {
+        let row = self.next_row();
+        let (_, gates) = CircuitGate::create_chain_ffadd(row, &[op], modulus);
+        self.push(gates, || {
+            let inputs = match op {
+                FFOps::Double => vec![left.val().clone()],
+                _ => vec![left.val().clone(), right.val().clone()],
+            };
+            foreign_field_add::witness::create_chain(&inputs, &[op], modulus.clone())
+        });
+        self.copy_elem(left, array::from_fn(|i| (row, i)));
+        // a doubling reads its left input as its right input, its right input cells are unused
+        if op != FFOps::Double {
+            self.copy_elem(right, array::from_fn(|i| (row, 3 + i)));
+        }
+        self.bound_constants(row + 1);
+
+        let value = self.value(|| match op {
+            FFOps::Add | FFOps::Double => (left.val() + right.val()) % modulus,
+            FFOps::Sub => (modulus + left.val() - right.val()) % modulus,
+        });
+        let result = array::from_fn(|i| (row + 1, i));
+        self.range_check_cells(result, value.as_ref());
+        let bound = self.value(|| known(value.as_ref()) + modulus.negate());
+        self.range_check_cells(array::from_fn(|i| (row + 2, i)), bound.as_ref());
+        let result = Elem {
+            limbs: result.map(Limb::Cell),
+            value,
+        };
+        (row, result)
+    }
+
+    /// Lays out the addition of elements modulo the modulus
+    pub fn add(&mut self, left: &Elem<F>, right: &Elem<F>, modulus: &BigUint) -> Elem<F> {
+        self.add_sub(left, right, FFOps::Add, modulus).1
+    }
+
+    /// Lays out the doubling of an element modulo the modulus,
+    /// which reads the element once (see [FFOps::Double])
+    pub fn double(&mut self, elem: &Elem<F>, modulus: &BigUint) -> Elem<F> {
+        self.add_sub(elem, elem, FFOps::Double, modulus).1
+    }
+
+    /// Lays out the addition of elements without reduction modulo the modulus
+    pub fn add_no_overflow(
+        &mut self,
+        left: &Elem<F>,
+        right: &Elem<F>,
+        modulus: &BigUint,
+    ) -> Elem<F> {
+        let (row, result) = self.add_sub(left, right, FFOps::Add, modulus);
+        self.copy(&Limb::Const(F::zero()), (row, 6));
+        result
+    }
+
+    /// Lays out the subtraction of elements modulo the modulus
+    pub fn sub(&mut self, left: &Elem<F>, right: &Elem<F>, modulus: &BigUint) -> Elem<F> {
+        self.add_sub(left, right, FFOps::Sub, modulus).1
+    }
+
+    // Lays out a multiplication of elements modulo the modulus with the range checks
+    // of its carries and of its quotient, and returns the cells of its remainder
+    fn ffmul(&mut self, left: &Elem<F>, right: &Elem<F>, modulus: &BigUint) -> [Cell; 3] {
+        let row = self.next_row();
+        let (_, gates) = CircuitGate::create_foreign_field_mul(row, modulus);
+        let mut checks = None;
+        self.push(gates, || {
+            let (witness, external_checks) =
+                foreign_field_mul::witness::create(left.val(), right.val(), modulus);
+            checks = Some(external_checks);
+            witness
+        });
+        self.copy_elem(left, array::from_fn(|i| (row, i)));
+        self.copy_elem(right, array::from_fn(|i| (row, 3 + i)));
+
+        // carry1_lo, product1_lo and product1_hi_0
+        let carries = self.multi_range_check(checks.as_ref().map(|checks| checks.multi_ranges[0]));
+        self.connect((row, 6), carries[0]);
+        self.connect((row + 1, 5), carries[1]);
+        self.connect((row + 1, 6), carries[2]);
+
+        // quotient bound
+        let bound_row = self.next_row();
+        let (_, gates) = CircuitGate::create_compact_multi_range_check(bound_row);
+        self.push(gates, || {
+            range_check::witness::create_multi_compact_limbs(
+                &known(checks.as_ref()).compact_multi_ranges[0],
+            )
+        });
+        self.connect((row + 1, 3), (bound_row + 2, 1));
+        self.connect((row + 1, 4), (bound_row, 0));
+
+        array::from_fn(|i| (row + 1, i))
+    }
+
+    /// Lays out the multiplication of elements modulo the modulus
+    pub fn mul(&mut self, left: &Elem<F>, right: &Elem<F>, modulus: &BigUint) -> Elem<F> {
+        let cells = self.ffmul(left, right, modulus);
+        let value = self.value(|| left.val() * right.val() % modulus);
+        self.range_check_cells(cells, value.as_ref());
+        self.bound_check(cells, value.as_ref(), modulus);
+        Elem {
+            limbs: cells.map(Limb::Cell),
+            value,
+        }
+    }
+
+    /// Constrains the product of elements modulo the modulus to be an element
+    pub fn assert_mul(
+        &mut self,
+        left: &Elem<F>,
+        right: &Elem<F>,
+        product: &Elem<F>,
+        modulus: &BigUint,
+    ) {
+        let cells = self.ffmul(left, right, modulus);
+        self.copy_elem(product, cells);
+    }
+
+    /// Lays out an element of the witness whose product with `right` is `product`
+    /// modulo the prime modulus, where `right` must be constrained to be nonzero
+    pub fn div(&mut self, product: &Elem<F>, right: &Elem<F>, modulus: &BigUint) -> Elem<F> {
+        let value = self.value(|| product.val() * inverse(right.val(), modulus) % modulus);
+        let quotient = self.witness(value, modulus);
+        self.assert_mul(&quotient, right, product, modulus);
+        quotient
+    }
+
+    /// Lays out the inverse of an element modulo the prime modulus,
+    /// which constrains the element to be nonzero
+    pub fn inverse(&mut self, elem: &Elem<F>, modulus: &BigUint) -> Elem<F> {
+        let one = self.constant(&BigUint::from(1u32));
+        self.div(&one, elem, modulus)
+    }
+
+    /// Returns the point of a constant
+    pub fn constant_point(&self, point: &ForeignPoint) -> Point<F> {
+        Point {
+            x: self.constant(&point.0),
+            y: self.constant(&point.1),
+        }
+    }
+
+    /// Constrains a point to be on the curve
+    pub fn assert_on_curve(&mut self, curve: &ForeignCurve, point: &Point<F>) {
+        let p = &curve.modulus;
+        let yy = self.mul(&point.y, &point.y, p);
+        let xx = self.mul(&point.x, &point.x, p);
+        let xxx = self.mul(&xx, &point.x, p);
+        let b = self.constant(&curve.b);
+        let mut rhs = self.add(&xxx, &b, p);
+        if !curve.a.is_zero() {
+            let a = self.constant(&curve.a);
+            let ax = self.mul(&a, &point.x, p);
+            rhs = self.add(&rhs, &ax, p);
+        }
+        self.assert_eq(&yy, &rhs);
+    }
+
+    // Lays out the point of a line of slope lambda through the point p and the point of x-coordinate x,
+    // which is the third intersection of the line with the curve, negated
+    fn chord_point(
+        &mut self,
+        curve: &ForeignCurve,
+        lambda: &Elem<F>,
+        point: &Point<F>,
+        x: &Elem<F>,
+    ) -> Point<F> {
+        let p = &curve.modulus;
+        // x3 = lambda^2 - x1 - x2
+        let lambda2 = self.mul(lambda, lambda, p);
+        let x3 = self.sub(&lambda2, &point.x, p);
+        let x3 = self.sub(&x3, x, p);
+        // y3 = lambda (x1 - x3) - y1
+        let dx = self.sub(&point.x, &x3, p);
+        let y3 = self.mul(lambda, &dx, p);
+        let y3 = self.sub(&y3, &point.y, p);
+        Point { x: x3, y: y3 }
+    }
+
+    /// Lays out the addition of two points, which are constrained to have distinct x-coordinates
+    pub fn add_points(
+        &mut self,
+        curve: &ForeignCurve,
+        left: &Point<F>,
+        right: &Point<F>,
+    ) -> Point<F> {
+        let p = &curve.modulus;
+        let dx = self.sub(&right.x, &left.x, p);
+        let dy = self.sub(&right.y, &left.y, p);
+        // the x-coordinates are distinct
+        self.inverse(&dx, p);
+        let lambda = self.div(&dy, &dx, p);
+        self.chord_point(curve, &lambda, left, &right.x)
+    }
+
+    /// Lays out the doubling of a point of the curve, which is nonzero for a curve of odd order
+    pub fn double_point(&mut self, curve: &ForeignCurve, point: &Point<F>) -> Point<F> {
+        let p = &curve.modulus;
+        // lambda 2 y = 3 x^2 + a
+        let xx = self.mul(&point.x, &point.x, p);
+        let xx2 = self.double(&xx, p);
+        let mut slope = self.add(&xx2, &xx, p);
+        if !curve.a.is_zero() {
+            let a = self.constant(&curve.a);
+            slope = self.add(&slope, &a, p);
+        }
+        let y2 = self.double(&point.y, p);
+        let lambda = self.div(&slope, &y2, p);
+        self.chord_point(curve, &lambda, point, &point.x)
+    }
+
+    /// Lays out the point `u` if the boolean is true, and `v` otherwise
+    pub fn select_point(&mut self, bit: &Bit, u: &Point<F>, v: &Point<F>) -> Point<F> {
+        Point {
+            x: self.select(bit, &u.x, &v.x),
+            y: self.select(bit, &u.y, &v.y),
+        }
+    }
+}
@@ -0,0 +1,381 @@
+//! This module includes the ECDSA signature verification gadget over a foreign curve, such as secp256k1 or P-256,
+//! and its witness code generation.
+//! Note that this module does not include a new gate type, the gadget reuses the foreign field gates,
+//! the range check gates and the `Generic` gate.
+
+//~ The ECDSA gadget verifies a signature $(r, s)$ of a message hash $h$ with a public key $Q$
+//~ over a foreign curve $E$ of base field $\mathbb{F}_p$ and of prime order $n$, with $p < 2n$ (as for secp256k1 and P-256).
+//~ The hash is truncated to the leftmost $\lceil \log_2 n \rceil$ bits and reduced modulo $n$ into the scalar $z$.
+//~
+//~ The foreign field elements are in 3 limbs of 88 bits. The inputs $z, r, s$ (modulo $n$) and
+//~ $Q = (x_Q, y_Q)$ (modulo $p$) are the first 5 elements of the gadget,
+//~ whose limbs are at the positions given by `ecdsa_input_limb`.
+//~ Each element of the witness and each result of an operation is checked to be *canonical*:
+//~ its limbs are range checked with a multi-range-check, and the bound addition $x + 2^{264} - f$
+//~ of its modulus $f$ is range checked as well, so that $x < f$.
+//~ The additions and subtractions are `ForeignFieldAdd` gates, and the multiplications are
+//~ `ForeignFieldMul` gates, with the range checks of their carries and quotient bounds.
+//~ The gadget checks that:
+//~
+//~ * $Q$ is on the curve, $y_Q^2 = x_Q^3 + a x_Q + b$,
+//~ * $s$ and $r$ have inverses modulo $n$, so they are not zero, and $w = s^{-1}$,
+//~ * $u_1 = z w$ and $u_2 = r w$ modulo $n$, and their bits,
+//~   with booleans $b \cdot b - b = 0$ and accumulations $2 acc + b$ in `Generic` gates,
+//~ * $R = u_1 G + u_2 Q$, with a double-and-add over the bits from the most significant one:
+//~   the accumulator starts from an offset point $A$ of unknown discrete logarithm,
+//~   is doubled at each bit, and is added the point
+//~   $G + Q$, $G$ or $Q$ according to the bits of $u_1$ and $u_2$ (when one of them is set),
+//~   which is selected limb by limb as $b (u - v) + v$ in `Generic` gates.
+//~   The final accumulator is added $-2^{m} A$, where $m$ is the number of bits of $n$,
+//~ * $x_R = r + k n$ without overflow of the modulus $p$, for a boolean $k$, so that $x_R \equiv r \pmod n$.
+//~
+//~ The addition of two points checks that their x-coordinates are distinct, with an inverse of their difference,
+//~ and the doubling does not need it since the curve has no point of order 2.
+//~ Both compute their slope $\lambda$ from a multiplication with a remainder copied from a constrained element:
+//~ $\lambda (x_2 - x_1) = y_2 - y_1$ for the addition, and $\lambda \cdot 2 y = 3 x^2 + a$ for the doubling.
+//~ An accumulator which would reach $\pm G$, $\pm Q$ or $\pm (G + Q)$ fails the verification,
+//~ which only happens with a negligible probability for the offset $A$, and so does a public key $Q = \pm G$.
+//~
+//~ With a GLV endomorphism $\phi(x, y) = (\beta x, y)$ of the curve, which multiplies its points by a scalar $\mu$
+//~ (as for secp256k1), the variant of the gadget `create_ecdsa_glv` decomposes each scalar $u$ into scalars $u'$ and $u''$
+//~ of about half the bits of $n$, with $u' + u'' \mu = u$ modulo $n$.
+//~ The scalars $u'$ and $u''$ are elements of the witness, whose bits are laid out as above (which bounds them).
+//~ Then $R = u_1' G + u_1'' \phi(G) + u_2' Q + u_2'' \phi(Q)$ is computed as in the multi-scalar multiplication gadget,
+//~ with the fixed points $G$ and $\phi(G)$, and the points $Q$ and $\phi(Q) = (\beta x_Q, y_Q)$, which needs half of the doublings.
+//~ The decomposition is computed out of the circuit with a reduced basis of the lattice of the pairs $(k', k'')$
+//~ such that $k' + k'' \mu = 0$, so that both scalars are non-negative, of at most 129 bits for secp256k1.
+//~
+//~ The constants of the gadget are in `Generic` gates $l - c = 0$ after its other gates,
+//~ copied to all their uses.
+//~
+
+pub mod batch;
+pub mod bls12_381;
+pub mod curve;
+pub mod ethereum;
+pub mod glv;
+pub mod layout;
+mod signed_hash;
+pub mod webauthn;
+
+use crate::circuits::{
+    gate::CircuitGate,
+    polynomial::COLUMNS,
+    polynomials::{foreign_curve_msm, foreign_curve_mul::MulBase, range_check},
+};
+use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use std::array;
+
+use self::{
+    curve::{ForeignCurve, ForeignPoint},
+    glv::GlvEndomorphism,
+    layout::{Elem, Layout, Point},
+};
+
+/// Number of rows of an element of the witness of the gadget
+pub(crate) const ELEM_ROWS: usize = 2 * range_check::gadget::MULTI_RANGE_CHECK_ROWS + 2;
+
+/// The inputs of the ECDSA gadget, in their order
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EcdsaInput {
+    /// The scalar of the message hash, modulo the order
+    Hash,
+    /// The `r` part of the signature, modulo the order
+    R,
+    /// The `s` part of the signature, modulo the order
+    S,
+    /// The x-coordinate of the public key, modulo the base field modulus
+    PublicKeyX,
+    /// The y-coordinate of the public key, modulo the base field modulus
+    PublicKeyY,
+}
+
+/// An ECDSA signature, with its parts modulo the order of the curve
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EcdsaSignature {
+    pub r: BigUint,
+    pub s: BigUint,
+}
+
+/// Returns the position of the cell containing the `limb`-th limb (from the least significant one)
+/// of an input of the ECDSA gadget starting at row `new_row`, as a `(row, column)` pair
+pub fn ecdsa_input_limb(new_row: usize, input: EcdsaInput, limb: usize) -> (usize, usize) {
+    assert!(limb < 3, "An element only has three limbs");
+    (new_row + ELEM_ROWS * input as usize + limb, 0)
+}
+
+/// Returns the scalar of a message hash, from its leftmost bits up to the number of bits of the order
+pub fn hash_to_scalar(curve: &ForeignCurve, msg_hash: &[u8]) -> BigUint {
+    let hash = BigUint::from_bytes_be(msg_hash);
+    let hash_bits = 8 * msg_hash.len() as u64;
+    let order_bits = curve.order.bits();
+    let hash = if hash_bits > order_bits {
+        hash >> (hash_bits - order_bits)
+    } else {
+        hash
+    };
+    hash % &curve.order
+}
+
+/// Verifies an ECDSA signature of a message hash with a public key, out of the circuit
+pub fn verify_ecdsa(
+    curve: &ForeignCurve,
+    msg_hash: &[u8],
+    signature: &EcdsaSignature,
+    public_key: &ForeignPoint,
+) -> bool {
+    let n = &curve.order;
+    let EcdsaSignature { r, s } = signature;
+    if r.is_zero() || r >= n || s.is_zero() || s >= n || !curve.is_on_curve(public_key) {
+        return false;
+    }
+    let w = curve::inverse(s, n);
+    let u1 = hash_to_scalar(curve, msg_hash) * &w % n;
+    let u2 = r * w % n;
+    let point = curve.add(
+        &curve.scalar_mul(&curve.generator, &u1),
+        &curve.scalar_mul(public_key, &u2),
+    );
+    matches!(point, Some((x, _)) if &(x % n) == r)
+}
+
+// Lays out u1 G + u2 Q with a double-and-add over the bits of the scalars
+fn double_and_add<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    curve: &ForeignCurve,
+    public_key: &Point<F>,
+    u1: &Elem<F>,
+    u2: &Elem<F>,
+) -> Point<F> {
+    let num_bits = curve.order.bits() as usize;
+    let bits1 = layout.bits(u1, num_bits);
+    let bits2 = layout.bits(u2, num_bits);
+
+    // the double-and-add from the offset
+    let generator = layout.constant_point(&curve.generator);
+    let sum = layout.add_points(curve, &generator, public_key);
+    let offset = curve.offset();
+    let mut acc = layout.constant_point(&offset);
+    for (b1, b2) in bits1.iter().zip(&bits2) {
+        let double = layout.double_point(curve, &acc);
+        let added = layout.select_point(b1, &sum, public_key);
+        let added = layout.select_point(b2, &added, &generator);
+        let added = layout.add_points(curve, &double, &added);
+        let bit = layout.or(b1, b2);
+        acc = layout.select_point(&bit, &added, &double);
+    }
+    let correction = curve
+        .scalar_mul(&offset, &(BigUint::one() << num_bits))
+        .expect("the offset is not of small order");
+    let correction = layout.constant_point(&curve.neg(&correction));
+    layout.add_points(curve, &acc, &correction)
+}
+
+// Lays out u1 G + u2 Q as the multi-scalar multiplication of G, phi(G), Q and phi(Q)
+// by the halves of the GLV decompositions of the scalars
+fn glv_mul<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    curve: &ForeignCurve,
+    glv: &GlvEndomorphism,
+    public_key: &Point<F>,
+    u1: &Elem<F>,
+    u2: &Elem<F>,
+) -> Point<F> {
+    let [a1, a2] = glv::decompose_scalar(layout, curve, glv, u1);
+    let [b1, b2] = glv::decompose_scalar(layout, curve, glv, u2);
+    let endo_generator = glv.apply(curve, &curve.generator);
+    let endo_key = glv::endomorphism_point(layout, curve, glv, public_key);
+    let bases = [
+        MulBase::Fixed(curve.generator.clone()),
+        MulBase::Fixed(endo_generator.clone()),
+        MulBase::Variable,
+        MulBase::Variable,
+    ];
+    let terms = [
+        (a1, layout.constant_point(&curve.generator)),
+        (a2, layout.constant_point(&endo_generator)),
+        (b1, public_key.clone()),
+        (b2, endo_key),
+    ];
+    foreign_curve_msm::msm(layout, curve, &bases, &terms)
+}
+
+// Lays out the verification of a signature, with the values of the inputs for the witness
+fn layout_ecdsa<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    curve: &ForeignCurve,
+    glv: Option<&GlvEndomorphism>,
+    inputs: Option<[BigUint; 5]>,
+) {
+    let (p, n) = (&curve.modulus, &curve.order);
+    assert!(
+        p < &(n * 2u32),
+        "the base field modulus is smaller than twice the order"
+    );
+
+    let moduli = [n, n, n, p, p];
+    let [hash, r, s, x, y]: [Elem<F>; 5] = array::from_fn(|i| {
+        layout.witness(inputs.as_ref().map(|inputs| inputs[i].clone()), moduli[i])
+    });
+    let public_key = Point { x, y };
+    layout.assert_on_curve(curve, &public_key);
+
+    // the scalars of the generator and of the public key, and r is not zero
+    let w = layout.inverse(&s, n);
+    layout.inverse(&r, n);
+    let u1 = layout.mul(&hash, &w, n);
+    let u2 = layout.mul(&r, &w, n);
+    let result = match glv {
+        Some(glv) => glv_mul(layout, curve, glv, &public_key, &u1, &u2),
+        None => double_and_add(layout, curve, &public_key, &u1, &u2),
+    };
+
+    // x_R = r + k n
+    let k = layout.bit(result.x.value.as_ref().map(|x| x >= n));
+    let kn = layout.select_constant(&k, n);
+    let rkn = layout.add_no_overflow(&r, &kn, p);
+    layout.assert_eq(&rkn, &result.x);
+}
+
+impl<F: PrimeField + SquareRootField> CircuitGate<F> {
+    /// Extends a circuit with the verification of an ECDSA signature over a foreign curve
+    /// Includes:
+    /// - the foreign field gates, range checks and generic gates of the verification (see the module)
+    /// Input:
+    /// - gates : the full circuit
+    /// - curve : the foreign curve of the signature
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the inputs (see [ecdsa_input_limb]) should be copied from other cells, such as the public input
+    pub fn extend_ecdsa(gates: &mut Vec<Self>, curve: &ForeignCurve) -> usize {
+        let (next_row, mut ecdsa_gates) = Self::create_ecdsa(gates.len(), curve);
+        gates.append(&mut ecdsa_gates);
+        next_row
+    }
+
+    /// Creates the verification of an ECDSA signature, see [Self::extend_ecdsa]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_ecdsa(new_row: usize, curve: &ForeignCurve) -> (usize, Vec<Self>) {
+        let mut layout = Layout::new(new_row, false);
+        layout_ecdsa(&mut layout, curve, None, None);
+        let (ecdsa_gates, _) = layout.finalize();
+        (new_row + ecdsa_gates.len(), ecdsa_gates)
+    }
+
+    /// Extends a circuit with the verification of an ECDSA signature over a foreign curve with a GLV endomorphism,
+    /// with the inputs of [Self::extend_ecdsa] and about half of its doublings
+    /// Includes:
+    /// - the foreign field gates, range checks and generic gates of the verification (see the module)
+    /// Input:
+    /// - gates : the full circuit
+    /// - curve : the foreign curve of the signature
+    /// - glv   : the endomorphism of the curve
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the inputs (see [ecdsa_input_limb]) should be copied from other cells, such as the public input
+    pub fn extend_ecdsa_glv(
+        gates: &mut Vec<Self>,
+        curve: &ForeignCurve,
+        glv: &GlvEndomorphism,
+    ) -> usize {
+        let (next_row, mut ecdsa_gates) = Self::create_ecdsa_glv(gates.len(), curve, glv);
+        gates.append(&mut ecdsa_gates);
+        next_row
+    }
+
+    /// Creates the verification of an ECDSA signature with a GLV endomorphism, see [Self::extend_ecdsa_glv]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_ecdsa_glv(
+        new_row: usize,
+        curve: &ForeignCurve,
+        glv: &GlvEndomorphism,
+    ) -> (usize, Vec<Self>) {
+        let mut layout = Layout::new(new_row, false);
+        layout_ecdsa(&mut layout, curve, Some(glv), None);
+        let (ecdsa_gates, _) = layout.finalize();
+        (new_row + ecdsa_gates.len(), ecdsa_gates)
+    }
+}
+
+/// Extends the witness with the verification of an ECDSA signature
+/// Input
+/// - witness: full witness of the circuit
+/// - curve: the foreign curve of the signature
+/// - msg_hash: the message hash, as big-endian bytes
+/// - signature: the signature, whose parts are smaller than the order
+/// - public_key: the public key, whose coordinates are smaller than the base field modulus
+/// Warning
+/// - the witness of an invalid signature does not satisfy the constraints of the gadget
+pub fn extend_ecdsa_witness<F: PrimeField + SquareRootField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    curve: &ForeignCurve,
+    msg_hash: &[u8],
+    signature: &EcdsaSignature,
+    public_key: &ForeignPoint,
+) {
+    extend_witness(witness, curve, None, msg_hash, signature, public_key);
+}
+
+/// Extends the witness with the verification of an ECDSA signature with a GLV endomorphism
+/// Input
+/// - witness: full witness of the circuit
+/// - curve: the foreign curve of the signature
+/// - glv: the endomorphism of the curve
+/// - msg_hash: the message hash, as big-endian bytes
+/// - signature: the signature, whose parts are smaller than the order
+/// - public_key: the public key, whose coordinates are smaller than the base field modulus
+/// Warning
+/// - the witness of an invalid signature does not satisfy the constraints of the gadget
+pub fn extend_ecdsa_glv_witness<F: PrimeField + SquareRootField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    curve: &ForeignCurve,
+    glv: &GlvEndomorphism,
+    msg_hash: &[u8],
+    signature: &EcdsaSignature,
+    public_key: &ForeignPoint,
+) {
+    extend_witness(witness, curve, Some(glv), msg_hash, signature, public_key);
+}
+
+fn extend_witness<F: PrimeField + SquareRootField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    curve: &ForeignCurve,
+    glv: Option<&GlvEndomorphism>,
+    msg_hash: &[u8],
+    signature: &EcdsaSignature,
+    public_key: &ForeignPoint,
+) {
+    let (p, n) = (&curve.modulus, &curve.order);
+    assert!(
+        &signature.r < n && &signature.s < n,
+        "The parts of the signature are smaller than the order"
+    );
+    let (x, y) = public_key;
+    assert!(
+        x < p && y < p,
+        "The coordinates of the public key are smaller than the modulus"
+    );
+
+    let inputs = [
+        hash_to_scalar(curve, msg_hash),
+        signature.r.clone(),
+        signature.s.clone(),
+        x.clone(),
+        y.clone(),
+    ];
+    let mut layout = Layout::new(witness[0].len(), true);
+    layout_ecdsa(&mut layout, curve, glv, Some(inputs));
+    let (_, ecdsa_witness) = layout.finalize();
+    let ecdsa_witness = ecdsa_witness.expect("the witness is laid out");
+    for (col, cells) in witness.iter_mut().zip(ecdsa_witness) {
+        col.extend(cells);
+    }
+}
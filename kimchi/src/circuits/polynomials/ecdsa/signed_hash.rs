@@ -0,0 +1,157 @@
+//! The circuit verifying the ECDSA signature of a message hash of 32 bytes with a public key,
+//! both given in the public input byte by byte, shared by the circuit templates of the signed messages.
+//!
+//! The public input of the circuit is made of the 32 bytes of the hash of the message,
+//! followed by the 64 bytes of the coordinates of the public key, one byte per public input row.
+//! The byte limbs gadgets recompose the hash and the coordinates into foreign field elements,
+//! the hash is reduced modulo the order of the curve, and the ECDSA gadget verifies the signature.
+
+use crate::circuits::{
+    gate::{CircuitGate, Connect},
+    polynomial::COLUMNS,
+    polynomials::{
+        foreign_field_add::bytes::{
+            bytes_to_limbs_byte, bytes_to_limbs_limb, extend_bytes_to_limbs_witness,
+        },
+        generic::GenericGateSpec,
+    },
+    wires::Wire,
+};
+use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::BigUint;
+use num_traits::One;
+use std::array;
+
+use super::{
+    curve::{ForeignCurve, ForeignPoint},
+    layout::Layout,
+    EcdsaInput, EcdsaSignature, ELEM_ROWS,
+};
+
+/// Number of bytes of a hash, and of a coordinate of a public key
+pub(super) const HASH_BYTES: usize = 32;
+
+/// Number of public inputs of the circuit: the bytes of the hash and of the public key
+pub(super) const SIGNED_HASH_PUBLIC_INPUTS: usize = 3 * HASH_BYTES;
+
+// The big-endian bytes of a coordinate
+pub(super) fn coordinate_bytes(coordinate: &BigUint) -> [u8; HASH_BYTES] {
+    let bytes = coordinate.to_bytes_be();
+    assert!(bytes.len() <= HASH_BYTES, "The coordinate fits in 32 bytes");
+    let mut padded = [0u8; HASH_BYTES];
+    padded[HASH_BYTES - bytes.len()..].copy_from_slice(&bytes);
+    padded
+}
+
+// The public input of the circuit, the bytes of the hash and of the coordinates of the public key
+pub(super) fn signed_hash_public_input<F: PrimeField>(
+    msg_hash: &[u8; HASH_BYTES],
+    public_key: &ForeignPoint,
+) -> Vec<F> {
+    let (x, y) = public_key;
+    let bytes = [*msg_hash, coordinate_bytes(x), coordinate_bytes(y)];
+    bytes.iter().flatten().map(|byte| F::from(*byte)).collect()
+}
+
+// Lays out the reduction z + k n = h of a hash h of 32 bytes modulo the order n, with a boolean k,
+// where h is the first element of the layout, and z the second one
+fn layout_reduction<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    curve: &ForeignCurve,
+    hash: Option<BigUint>,
+) {
+    let n = &curve.order;
+    let bound = BigUint::one() << (8 * HASH_BYTES);
+    assert!(n * 2u32 > bound, "a hash is smaller than twice the order");
+
+    let h = layout.witness(hash.clone(), &bound);
+    let z = layout.witness(hash.as_ref().map(|hash| hash % n), n);
+    let k = layout.bit(hash.as_ref().map(|hash| hash >= n));
+    let kn = layout.select_constant(&k, n);
+    let zkn = layout.add_no_overflow(&z, &kn, &bound);
+    layout.assert_eq(&zkn, &h);
+}
+
+// Creates the gates of the circuit over a curve, with [SIGNED_HASH_PUBLIC_INPUTS] public inputs
+pub(super) fn signed_hash_gates<F: PrimeField + SquareRootField>(
+    curve: &ForeignCurve,
+) -> Vec<CircuitGate<F>> {
+    let mut gates: Vec<CircuitGate<F>> = (0..SIGNED_HASH_PUBLIC_INPUTS)
+        .map(|row| {
+            CircuitGate::create_generic_gadget(Wire::for_row(row), GenericGateSpec::Pub, None)
+        })
+        .collect();
+
+    // the first rows of the gadgets
+    let hash_row = gates.len();
+    let x_row = CircuitGate::extend_bytes_to_limbs(&mut gates, HASH_BYTES);
+    let y_row = CircuitGate::extend_bytes_to_limbs(&mut gates, HASH_BYTES);
+    let reduction = CircuitGate::extend_bytes_to_limbs(&mut gates, HASH_BYTES);
+    let mut layout = Layout::new(reduction, false);
+    layout_reduction(&mut layout, curve, None);
+    let (mut reduction_gates, _) = layout.finalize();
+    gates.append(&mut reduction_gates);
+    let ecdsa = gates.len();
+    CircuitGate::extend_ecdsa(&mut gates, curve);
+
+    // the bytes of the public input
+    for (i, start) in [hash_row, x_row, y_row].into_iter().enumerate() {
+        for byte in 0..HASH_BYTES {
+            gates.connect_cell_pair(
+                (HASH_BYTES * i + byte, 0),
+                bytes_to_limbs_byte(start, HASH_BYTES, byte),
+            );
+        }
+    }
+    for limb in 0..3 {
+        // the hash to reduce, and its scalar
+        gates.connect_cell_pair(
+            bytes_to_limbs_limb(hash_row, HASH_BYTES, limb),
+            (reduction + limb, 0),
+        );
+        gates.connect_cell_pair(
+            (reduction + ELEM_ROWS + limb, 0),
+            super::ecdsa_input_limb(ecdsa, EcdsaInput::Hash, limb),
+        );
+        // the public key
+        gates.connect_cell_pair(
+            bytes_to_limbs_limb(x_row, HASH_BYTES, limb),
+            super::ecdsa_input_limb(ecdsa, EcdsaInput::PublicKeyX, limb),
+        );
+        gates.connect_cell_pair(
+            bytes_to_limbs_limb(y_row, HASH_BYTES, limb),
+            super::ecdsa_input_limb(ecdsa, EcdsaInput::PublicKeyY, limb),
+        );
+    }
+    gates
+}
+
+// Creates the witness of the circuit over a curve, for a signature of the hash with the public key
+pub(super) fn signed_hash_witness<F: PrimeField + SquareRootField>(
+    curve: &ForeignCurve,
+    msg_hash: &[u8; HASH_BYTES],
+    signature: &EcdsaSignature,
+    public_key: &ForeignPoint,
+) -> [Vec<F>; COLUMNS] {
+    let mut witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![]);
+    for value in signed_hash_public_input::<F>(msg_hash, public_key) {
+        for (col, cells) in witness.iter_mut().enumerate() {
+            cells.push(if col == 0 { value } else { F::zero() });
+        }
+    }
+    let (x, y) = public_key;
+    extend_bytes_to_limbs_witness(&mut witness, msg_hash);
+    extend_bytes_to_limbs_witness(&mut witness, &coordinate_bytes(x));
+    extend_bytes_to_limbs_witness(&mut witness, &coordinate_bytes(y));
+
+    let mut layout = Layout::new(witness[0].len(), true);
+    layout_reduction(&mut layout, curve, Some(BigUint::from_bytes_be(msg_hash)));
+    let (_, reduction_witness) = layout.finalize();
+    let reduction_witness = reduction_witness.expect("the witness is laid out");
+    for (col, cells) in witness.iter_mut().zip(reduction_witness) {
+        col.extend(cells);
+    }
+
+    super::extend_ecdsa_witness(&mut witness, curve, msg_hash, signature, public_key);
+    witness
+}
@@ -0,0 +1,118 @@
+//! A circuit template verifying the signature of a WebAuthn assertion (as signed by a passkey),
+//! which packages the byte limbs gadget and the ECDSA gadget over P-256.
+//!
+//! The authenticator signs the concatenation of its authenticator data and of the SHA-256 hash of the
+//! client data (a JSON string, which contains the challenge of the relying party), with ECDSA over P-256
+//! and SHA-256 (the COSE algorithm ES256), and encodes its signature in DER.
+//!
+//! The public input of the circuit is made of the 32 bytes of the signed hash,
+//! followed by the 64 bytes of the coordinates of the public key, one byte per public input row,
+//! as for the Ethereum signed messages.
+//!
+//! Note: there is no SHA-256 gadget, so the hashes are computed out of the circuit:
+//! the verifier computes the public input with [webauthn_public_input], from the authenticator data,
+//! the client data and the public key, and checks the contents of the assertion (its challenge,
+//! its origin and its flags) itself.
+
+use crate::circuits::{gate::CircuitGate, polynomial::COLUMNS};
+use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use super::{
+    curve::{ForeignCurve, ForeignPoint},
+    signed_hash::{self, SIGNED_HASH_PUBLIC_INPUTS},
+    EcdsaSignature,
+};
+
+/// Number of public inputs of the circuit: the bytes of the hash and of the public key
+pub const WEBAUTHN_PUBLIC_INPUTS: usize = SIGNED_HASH_PUBLIC_INPUTS;
+
+// The DER tags of a sequence and of an integer
+const DER_SEQUENCE: u8 = 0x30;
+const DER_INTEGER: u8 = 0x02;
+
+/// Returns the signed hash of an assertion, SHA-256 of the authenticator data and of the hash of the client data
+pub fn webauthn_message_hash(authenticator_data: &[u8], client_data_json: &[u8]) -> [u8; 32] {
+    let client_data_hash = Sha256::digest(client_data_json);
+    let mut hasher = Sha256::new();
+    hasher.update(authenticator_data);
+    hasher.update(client_data_hash);
+    hasher.finalize().into()
+}
+
+// Splits a DER element of the tag, with a short length, into its contents and the following bytes
+fn der_element(bytes: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    let (&element_tag, rest) = bytes.split_first()?;
+    let (&len, rest) = rest.split_first()?;
+    let len = usize::from(len);
+    if element_tag != tag || len >= 0x80 || rest.len() < len {
+        return None;
+    }
+    Some(rest.split_at(len))
+}
+
+// The value of the contents of a DER integer, which is positive and minimally encoded
+fn der_integer(contents: &[u8]) -> Option<BigUint> {
+    match contents {
+        [] => None,
+        [first, ..] if first & 0x80 != 0 => None,
+        [0, second, ..] if second & 0x80 == 0 => None,
+        _ => Some(BigUint::from_bytes_be(contents)),
+    }
+}
+
+/// Parses a DER signature, the sequence of the integers `r` and `s`,
+/// or returns `None` if the encoding is invalid
+pub fn parse_der_signature(der: &[u8]) -> Option<EcdsaSignature> {
+    let (sequence, rest) = der_element(der, DER_SEQUENCE)?;
+    let (r, sequence) = der_element(sequence, DER_INTEGER)?;
+    let (s, sequence) = der_element(sequence, DER_INTEGER)?;
+    if !rest.is_empty() || !sequence.is_empty() {
+        return None;
+    }
+    Some(EcdsaSignature {
+        r: der_integer(r)?,
+        s: der_integer(s)?,
+    })
+}
+
+/// Returns the public input of the circuit for an assertion and a public key
+pub fn webauthn_public_input<F: PrimeField>(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    public_key: &ForeignPoint,
+) -> Vec<F> {
+    signed_hash::signed_hash_public_input(
+        &webauthn_message_hash(authenticator_data, client_data_json),
+        public_key,
+    )
+}
+
+/// Creates the gates of the circuit verifying the signature of a WebAuthn assertion,
+/// with [WEBAUTHN_PUBLIC_INPUTS] public inputs (see the module)
+pub fn webauthn_gates<F: PrimeField + SquareRootField>() -> Vec<CircuitGate<F>> {
+    signed_hash::signed_hash_gates(&ForeignCurve::p256())
+}
+
+/// Creates the witness of the circuit verifying the signature of a WebAuthn assertion,
+/// or returns `None` if the signature is invalid
+pub fn webauthn_witness<F: PrimeField + SquareRootField>(
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    signature: &EcdsaSignature,
+    public_key: &ForeignPoint,
+) -> Option<[Vec<F>; COLUMNS]> {
+    let curve = ForeignCurve::p256();
+    let msg_hash = webauthn_message_hash(authenticator_data, client_data_json);
+    let (x, y) = public_key;
+    if x >= &curve.modulus
+        || y >= &curve.modulus
+        || !super::verify_ecdsa(&curve, &msg_hash, signature, public_key)
+    {
+        return None;
+    }
+    Some(signed_hash::signed_hash_witness(
+        &curve, &msg_hash, signature, public_key,
+    ))
+}
@@ -9,7 +9,7 @@ use crate::{
         expr::{
             self,
             constraints::{boolean, ExprOps},
-            Cache,
+            Cache, E,
         },
         gate::{CircuitGate, GateType},
         wires::{GateWires, COLUMNS},
@@ -130,6 +130,18 @@ impl<F: PrimeField> CircuitGate<F> {
         row: usize,
         witness: &[Vec<F>; COLUMNS],
         cs: &ConstraintSystem<F>,
+    ) -> Result<(), String> {
+        let constraints = EndosclMul::constraints(&mut Cache::default());
+        self.verify_endomul_with::<G>(row, witness, cs, &constraints)
+    }
+
+    /// Verify the `EndoMul` gate with its constraints, which do not depend on the rows of the witness
+    pub(crate) fn verify_endomul_with<G: KimchiCurve<ScalarField = F>>(
+        &self,
+        row: usize,
+        witness: &[Vec<F>; COLUMNS],
+        cs: &ConstraintSystem<F>,
+        constraints: &[E<F>],
     ) -> Result<(), String> {
         ensure_eq!(self.typ, GateType::EndoMul, "incorrect gate type");
 
@@ -150,7 +162,6 @@ impl<F: PrimeField> CircuitGate<F> {
         let evals: ProofEvaluations<PointEvaluations<G::ScalarField>> =
             ProofEvaluations::dummy_with_witness_evaluations(this, next);
 
-        let constraints = EndosclMul::constraints(&mut Cache::default());
         for (i, c) in constraints.iter().enumerate() {
             match c.evaluate_(cs.domain.d1, pt, &evals, &constants) {
                 Ok(x) => {
@@ -0,0 +1,144 @@
+//! This module includes the definition of the foreign curve point addition gadget
+//! and its witness code generation.
+//! Note that this module does not include a new gate type, the gadget reuses the foreign field gates,
+//! the range check gates and the `Generic` gate, as laid out by the ECDSA gadget.
+
+//~ The foreign curve addition gadget adds two affine points $P_1 = (x_1, y_1)$ and $P_2 = (x_2, y_2)$
+//~ of a curve $y^2 = x^3 + a x + b$ over a foreign field $\mathbb{F}_p$, into $P_3 = (x_3, y_3)$.
+//~ The coordinates are foreign field elements of 3 limbs of 88 bits, and the 6 coordinates
+//~ $x_1, y_1, x_2, y_2, x_3, y_3$ are the first 6 elements of the gadget, each one checked to be canonical
+//~ with a multi-range-check of its limbs and of its bound addition (as in the ECDSA gadget),
+//~ at the positions given by `foreign_curve_add_limb`.
+//~
+//~ The gadget is a convenience composition of the foreign field gates, there is no dedicated gate for the addition.
+//~ The addition is incomplete, it computes the slope $\lambda$ of the chord through the points with
+//~ `ForeignFieldAdd` and `ForeignFieldMul` gates:
+//~
+//~ * $x_2 - x_1$ has an inverse, so that the points are not equal or opposite,
+//~ * $\lambda (x_2 - x_1) = y_2 - y_1$,
+//~ * $x_3 = \lambda^2 - x_1 - x_2$,
+//~ * $y_3 = \lambda (x_1 - x_3) - y_1$.
+//~
+//~ The gadget does not check that the points are on the curve, the inputs must be constrained to be
+//~ on the curve, for example by the gadget which outputs them or with the on-curve check of the ECDSA gadget.
+//~ The constants of the gadget are in `Generic` gates $l - c = 0$ after its other gates,
+//~ and the modulus $p$ is in the coefficients of the foreign field gates.
+//~
+
+use crate::circuits::{gate::CircuitGate, polynomial::COLUMNS};
+use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::BigUint;
+use std::array;
+
+use super::ecdsa::{
+    curve::{ForeignCurve, ForeignPoint},
+    layout::{Elem, Layout, Point},
+    ELEM_ROWS,
+};
+
+/// The coordinates of the foreign curve addition gadget, in their order
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurveAddValue {
+    /// The x-coordinate of the left point
+    LeftX,
+    /// The y-coordinate of the left point
+    LeftY,
+    /// The x-coordinate of the right point
+    RightX,
+    /// The y-coordinate of the right point
+    RightY,
+    /// The x-coordinate of the sum
+    SumX,
+    /// The y-coordinate of the sum
+    SumY,
+}
+
+/// Returns the position of the cell containing the `limb`-th limb (from the least significant one)
+/// of a coordinate of the foreign curve addition gadget starting at row `new_row`, as a `(row, column)` pair
+pub fn foreign_curve_add_limb(new_row: usize, value: CurveAddValue, limb: usize) -> (usize, usize) {
+    assert!(limb < 3, "An element only has three limbs");
+    (new_row + ELEM_ROWS * value as usize + limb, 0)
+}
+
+// Lays out the addition of two points, with the values of the coordinates for the witness
+fn layout_foreign_curve_add<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    curve: &ForeignCurve,
+    values: Option<[BigUint; 6]>,
+) {
+    let [x1, y1, x2, y2, x3, y3]: [Elem<F>; 6] = array::from_fn(|i| {
+        layout.witness(
+            values.as_ref().map(|values| values[i].clone()),
+            &curve.modulus,
+        )
+    });
+    let sum = layout.add_points(curve, &Point { x: x1, y: y1 }, &Point { x: x2, y: y2 });
+    layout.assert_eq(&sum.x, &x3);
+    layout.assert_eq(&sum.y, &y3);
+}
+
+impl<F: PrimeField + SquareRootField> CircuitGate<F> {
+    /// Extends a circuit with the addition of two points of a foreign curve
+    /// Includes:
+    /// - the foreign field gates, range checks and generic gates of the addition (see the module)
+    /// Input:
+    /// - gates : the full circuit
+    /// - curve : the foreign curve of the points
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the points (see [foreign_curve_add_limb]) should be copied from cells which constrain them
+    ///   to be on the curve, and they must not be equal or opposite
+    pub fn extend_foreign_curve_add(gates: &mut Vec<Self>, curve: &ForeignCurve) -> usize {
+        let (next_row, mut add_gates) = Self::create_foreign_curve_add(gates.len(), curve);
+        gates.append(&mut add_gates);
+        next_row
+    }
+
+    /// Creates the addition of two points of a foreign curve, see [Self::extend_foreign_curve_add]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_foreign_curve_add(new_row: usize, curve: &ForeignCurve) -> (usize, Vec<Self>) {
+        let mut layout = Layout::new(new_row, false);
+        layout_foreign_curve_add(&mut layout, curve, None);
+        let (add_gates, _) = layout.finalize();
+        (new_row + add_gates.len(), add_gates)
+    }
+}
+
+/// Extends the witness with the addition of two points of a foreign curve, and returns their sum
+/// Input
+/// - witness: full witness of the circuit
+/// - curve: the foreign curve of the points
+/// - left: the left point, whose coordinates are smaller than the base field modulus
+/// - right: the right point, whose coordinates are smaller than the base field modulus
+/// Panics if the points have the same x-coordinate
+pub fn extend_foreign_curve_add_witness<F: PrimeField + SquareRootField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    curve: &ForeignCurve,
+    left: &ForeignPoint,
+    right: &ForeignPoint,
+) -> ForeignPoint {
+    let p = &curve.modulus;
+    let ((x1, y1), (x2, y2)) = (left, right);
+    assert!(
+        x1 < p && y1 < p && x2 < p && y2 < p,
+        "The coordinates of the points are smaller than the modulus"
+    );
+    assert_ne!(x1, x2, "The points have distinct x-coordinates");
+
+    let sum = curve
+        .add(&Some(left.clone()), &Some(right.clone()))
+        .expect("the sum of points of distinct x-coordinates is not the point at infinity");
+    let (x3, y3) = &sum;
+    let values = [x1, y1, x2, y2, x3, y3].map(|value| value.clone());
+    let mut layout = Layout::new(witness[0].len(), true);
+    layout_foreign_curve_add(&mut layout, curve, Some(values));
+    let (_, add_witness) = layout.finalize();
+    let add_witness = add_witness.expect("the witness is laid out");
+    for (col, cells) in witness.iter_mut().zip(add_witness) {
+        col.extend(cells);
+    }
+    sum
+}
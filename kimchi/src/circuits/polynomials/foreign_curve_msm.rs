@@ -0,0 +1,448 @@
+//! This module includes the definition of the multi-scalar multiplication gadget over a foreign curve
+//! and its witness code generation.
+//! Note that this module does not include a new gate type, the gadget reuses the foreign field gates,
+//! the range check gates and the `Generic` gate, as laid out by the ECDSA gadget.
+
+//~ The multi-scalar multiplication gadget computes $R = \sum_j k_j P_j$ for scalars $k_j$ modulo the order $n$
+//~ of a foreign curve and points $P_j$, each one either a point of the witness or a fixed point of the circuit.
+//~ The result $R$, then each scalar $k_j$ followed by its point $P_j$ (when it is not fixed) are the first elements
+//~ of the gadget, each one checked to be canonical, at the positions given by `foreign_curve_msm_limb`.
+//~
+//~ The gadget generalizes the windowed scalar multiplication, sharing the doublings of the accumulator
+//~ between all the scalars. The points are split into groups of $g$ consecutive points,
+//~ and each group $\gamma$ has a joint table of the points
+//~ $$T_{d_1, \dots, d_g} = \sum_i d_i P_i + (\gamma + 1) C$$
+//~ for the digits $d_i < 2^w$ of a window of $w$ bits of its scalars, where $C$ is the offset point of the curve.
+//~ The entries are indexed by the concatenation of the bits of the digits (from the first point),
+//~ and each one is the sum of a previous entry and a point, in the circuit for a group with a point of the witness,
+//~ or precomputed as constants for a group of fixed points.
+//~ The offsets of the groups are distinct multiples of $C$, so that the entries of two groups do not collide
+//~ (even for the same points).
+//~
+//~ The accumulator starts from the sum of the entries of the most significant window of all the groups,
+//~ and for each of the following windows it is doubled $w$ times and added the entry of each group,
+//~ so that it is finally added the constant $- \left( \sum_i 2^{w i} \right) \left( \sum_\gamma (\gamma + 1) \right) C$.
+//~ As for the windowed multiplication, the additions are incomplete, which fails with a negligible probability,
+//~ and the gadget requires $R$ not to be the point at infinity.
+//~
+//~ Every window of a group needs one addition and $2^{g w} - 1$ selections of points, and its table
+//~ needs $2^{g w} - 1$ additions when it has a point of the witness, while the $\ell$ doublings are shared.
+//~ The gadget chooses $g$ and $w$ (with $g w \leq 4$) minimizing this estimated number of rows
+//~ for its number of points and their bases, see `msm_window`.
+//~
+
+use crate::circuits::{gate::CircuitGate, polynomial::COLUMNS};
+use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::BigUint;
+
+use super::{
+    ecdsa::{
+        curve::{ForeignCurve, ForeignPoint},
+        layout::{Bit, Layout, Point},
+        ELEM_ROWS,
+    },
+    foreign_curve_mul::{offset_factor, select_table, MulBase, MAX_WINDOW},
+};
+
+/// The windowing of a multi-scalar multiplication
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MsmWindow {
+    /// The number of points of each joint table
+    pub points: usize,
+    /// The number of bits of each scalar in a window
+    pub bits: usize,
+}
+
+impl MsmWindow {
+    /// The number of bits of the index of a joint table of `points` points
+    fn index_bits(&self, points: usize) -> usize {
+        self.bits * points
+    }
+}
+
+/// The elements of the multi-scalar multiplication gadget
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MsmValue {
+    /// The x-coordinate of the result
+    ResultX,
+    /// The y-coordinate of the result
+    ResultY,
+    /// The `j`-th scalar, modulo the order
+    Scalar(usize),
+    /// The x-coordinate of the `j`-th point, only for a variable base
+    PointX(usize),
+    /// The y-coordinate of the `j`-th point, only for a variable base
+    PointY(usize),
+}
+
+/// Returns the position of the cell containing the `limb`-th limb (from the least significant one)
+/// of an element of the multi-scalar multiplication gadget of `bases` starting at row `new_row`,
+/// as a `(row, column)` pair
+pub fn foreign_curve_msm_limb(
+    new_row: usize,
+    bases: &[MulBase],
+    value: MsmValue,
+    limb: usize,
+) -> (usize, usize) {
+    assert!(limb < 3, "An element only has three limbs");
+    // the index of the element of the j-th scalar, after the result and the previous points
+    let scalar = |j: usize| {
+        assert!(j < bases.len(), "The multiplication has the term");
+        let variables = bases[..j]
+            .iter()
+            .filter(|base| **base == MulBase::Variable)
+            .count();
+        2 + j + 2 * variables
+    };
+    let point = |j: usize| {
+        assert!(j < bases.len(), "The multiplication has the term");
+        assert_eq!(bases[j], MulBase::Variable, "The point is in the witness");
+        scalar(j) + 1
+    };
+    let elem = match value {
+        MsmValue::ResultX => 0,
+        MsmValue::ResultY => 1,
+        MsmValue::Scalar(j) => scalar(j),
+        MsmValue::PointX(j) => point(j),
+        MsmValue::PointY(j) => point(j) + 1,
+    };
+    (new_row + ELEM_ROWS * elem + limb, 0)
+}
+
+// The numbers of rows of an addition, of a doubling and of a selection of points
+fn point_op_rows<F: PrimeField + SquareRootField>(curve: &ForeignCurve) -> (usize, usize, usize) {
+    let mut layout = Layout::<F>::new(0, false);
+    let point = |layout: &mut Layout<F>| Point {
+        x: layout.witness(None, &curve.modulus),
+        y: layout.witness(None, &curve.modulus),
+    };
+    let (left, right) = (point(&mut layout), point(&mut layout));
+    let bit = layout.bit(None);
+
+    let start = layout.next_row();
+    layout.add_points(curve, &left, &right);
+    let add = layout.next_row() - start;
+    let start = layout.next_row();
+    layout.double_point(curve, &left);
+    let double = layout.next_row() - start;
+    let start = layout.next_row();
+    layout.select_point(&bit, &left, &right);
+    let select = layout.next_row() - start;
+    (add, double, select)
+}
+
+/// Returns the windowing of the multi-scalar multiplication of the bases which minimizes its estimated number
+/// of rows (see the module), with joint tables of at most `2^MAX_WINDOW` entries
+pub fn msm_window<F: PrimeField + SquareRootField>(
+    curve: &ForeignCurve,
+    bases: &[MulBase],
+) -> MsmWindow {
+    scalar_bits_window::<F>(curve, bases, curve.order.bits() as usize)
+}
+
+// The windowing of `msm_window`, for scalars of `num_bits` bits
+fn scalar_bits_window<F: PrimeField + SquareRootField>(
+    curve: &ForeignCurve,
+    bases: &[MulBase],
+    num_bits: usize,
+) -> MsmWindow {
+    assert!(!bases.is_empty(), "The multiplication has terms");
+    let (add, double, select) = point_op_rows::<F>(curve);
+    let candidates = (1..=bases.len().min(MAX_WINDOW))
+        .flat_map(|points| (1..=MAX_WINDOW / points).map(move |bits| MsmWindow { points, bits }));
+    candidates
+        .min_by_key(|window| {
+            let num_windows = (num_bits + window.bits - 1) / window.bits;
+            let groups = bases.chunks(window.points).map(|group| {
+                let entries = (1 << window.index_bits(group.len())) - 1;
+                let table = if group.contains(&MulBase::Variable) {
+                    entries * add
+                } else {
+                    0
+                };
+                num_windows * (add + entries * select) + table
+            });
+            num_bits * double + groups.sum::<usize>()
+        })
+        .expect("there are windowings")
+}
+
+/// Lays out the joint table of the points `sum_i d_i P_i + offset` for the digits `d_i < 2^window`,
+/// indexed by the concatenation of the digits from the first point
+pub fn joint_table<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    curve: &ForeignCurve,
+    points: &[Point<F>],
+    offset: &ForeignPoint,
+    window: usize,
+) -> Vec<Point<F>> {
+    let mut table = vec![layout.constant_point(offset)];
+    for index in 1..1 << (window * points.len()) {
+        // the position of the least significant digit which is not zero, from the last point
+        let position = index.trailing_zeros() as usize / window;
+        let point = &points[points.len() - 1 - position];
+        let entry = layout.add_points(curve, &table[index - (1 << (window * position))], point);
+        table.push(entry);
+    }
+    table
+}
+
+/// Returns the precomputed joint table of the fixed points `sum_i d_i P_i + offset` for the digits `d_i < 2^window`,
+/// indexed by the concatenation of the digits from the first point
+pub fn fixed_joint_table<F: PrimeField + SquareRootField>(
+    layout: &Layout<F>,
+    curve: &ForeignCurve,
+    points: &[ForeignPoint],
+    offset: &ForeignPoint,
+    window: usize,
+) -> Vec<Point<F>> {
+    let mut entries = vec![offset.clone()];
+    for index in 1..1 << (window * points.len()) {
+        let position = index.trailing_zeros() as usize / window;
+        let point = Some(points[points.len() - 1 - position].clone());
+        let entry = curve
+            .add(
+                &Some(entries[index - (1 << (window * position))].clone()),
+                &point,
+            )
+            .expect("the entries of the table are not the point at infinity");
+        entries.push(entry);
+    }
+    entries
+        .iter()
+        .map(|entry| layout.constant_point(entry))
+        .collect()
+}
+
+/// Lays out the multi-scalar multiplication of the joint tables of groups of points by the scalars of the bits
+/// of each group (from the most significant one), where `offset` is the sum of the offsets of the tables,
+/// see the module, including the final addition of the negated offset
+pub fn windowed_msm<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    curve: &ForeignCurve,
+    groups: &[(Vec<&[Bit]>, Vec<Point<F>>)],
+    offset: &ForeignPoint,
+    window: usize,
+) -> Point<F> {
+    let num_bits = groups
+        .first()
+        .and_then(|(scalars, _)| scalars.first())
+        .expect("The multiplication has terms")
+        .len();
+    assert!(
+        num_bits > 0
+            && groups
+                .iter()
+                .flat_map(|(scalars, _)| scalars)
+                .all(|bits| bits.len() == num_bits),
+        "The scalars have the same number of bits"
+    );
+
+    // adds the entries of the window of bits to the accumulator
+    let add_entries = |layout: &mut Layout<F>, acc: Option<Point<F>>, start: usize, end: usize| {
+        groups
+            .iter()
+            .fold(acc, |acc, (scalars, table)| {
+                let index: Vec<Bit> = scalars
+                    .iter()
+                    .flat_map(|bits| bits[start..end].iter().copied())
+                    .collect();
+                let entry = select_table(layout, &index, table);
+                Some(match acc {
+                    Some(acc) => layout.add_points(curve, &acc, &entry),
+                    None => entry,
+                })
+            })
+            .expect("the multiplication has terms")
+    };
+
+    // the most significant window is the shortest one
+    let top = num_bits - window * ((num_bits - 1) / window);
+    let mut acc = add_entries(layout, None, 0, top);
+    for start in (top..num_bits).step_by(window) {
+        for _ in 0..window {
+            acc = layout.double_point(curve, &acc);
+        }
+        acc = add_entries(layout, Some(acc), start, start + window);
+    }
+    let total_offset = curve
+        .scalar_mul(offset, &offset_factor(num_bits, window))
+        .expect("the offset is not of small order");
+    let correction = layout.constant_point(&curve.neg(&total_offset));
+    layout.add_points(curve, &acc, &correction)
+}
+
+/// Lays out the multi-scalar multiplication of the points of the bases by the scalars of their bits,
+/// all of the same number of bits (from the most significant one), where the points of the fixed bases
+/// are their constant points, with the windowing minimizing its estimated number of rows, see the module
+pub fn msm<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    curve: &ForeignCurve,
+    bases: &[MulBase],
+    terms: &[(Vec<Bit>, Point<F>)],
+) -> Point<F> {
+    assert_eq!(bases.len(), terms.len(), "There is a term per base");
+    let num_bits = terms
+        .first()
+        .map(|(bits, _)| bits.len())
+        .expect("The multiplication has terms");
+    let window = scalar_bits_window::<F>(curve, bases, num_bits);
+
+    // the joint tables of the groups, with the offsets (i + 1) C
+    let mut offset = None;
+    let mut groups = vec![];
+    for (i, (group, group_bases)) in terms
+        .chunks(window.points)
+        .zip(bases.chunks(window.points))
+        .enumerate()
+    {
+        let group_offset = curve
+            .scalar_mul(&curve.offset(), &BigUint::from(i + 1))
+            .expect("the offset is not of small order");
+        let fixed: Option<Vec<ForeignPoint>> = group_bases
+            .iter()
+            .map(|base| match base {
+                MulBase::Fixed(point) => Some(point.clone()),
+                MulBase::Variable => None,
+            })
+            .collect();
+        let table = match fixed {
+            Some(points) => fixed_joint_table(layout, curve, &points, &group_offset, window.bits),
+            None => {
+                let points: Vec<Point<F>> = group.iter().map(|(_, point)| point.clone()).collect();
+                joint_table(layout, curve, &points, &group_offset, window.bits)
+            }
+        };
+        offset = curve.add(&offset, &Some(group_offset));
+        let scalars: Vec<&[Bit]> = group.iter().map(|(bits, _)| bits.as_slice()).collect();
+        groups.push((scalars, table));
+    }
+
+    let offset = offset.expect("the sum of the offsets is not the point at infinity");
+    windowed_msm(layout, curve, &groups, &offset, window.bits)
+}
+
+// Lays out the multi-scalar multiplication, with the values of the scalars, of the points and of the result
+// for the witness
+fn layout_foreign_curve_msm<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    curve: &ForeignCurve,
+    bases: &[MulBase],
+    values: Option<(&[BigUint], &[ForeignPoint], ForeignPoint)>,
+) {
+    let (p, n) = (&curve.modulus, &curve.order);
+    let result = Point {
+        x: layout.witness(values.as_ref().map(|(_, _, (x, _))| x.clone()), p),
+        y: layout.witness(values.as_ref().map(|(_, _, (_, y))| y.clone()), p),
+    };
+    let mut terms = vec![];
+    for (j, base) in bases.iter().enumerate() {
+        let scalar = layout.witness(values.as_ref().map(|(scalars, _, _)| scalars[j].clone()), n);
+        let point = match base {
+            MulBase::Variable => Point {
+                x: layout.witness(values.as_ref().map(|(_, points, _)| points[j].0.clone()), p),
+                y: layout.witness(values.as_ref().map(|(_, points, _)| points[j].1.clone()), p),
+            },
+            MulBase::Fixed(point) => layout.constant_point(point),
+        };
+        terms.push((scalar, point));
+    }
+    let terms: Vec<(Vec<Bit>, Point<F>)> = terms
+        .into_iter()
+        .map(|(scalar, point)| (layout.bits(&scalar, n.bits() as usize), point))
+        .collect();
+
+    let product = msm(layout, curve, bases, &terms);
+    layout.assert_eq(&product.x, &result.x);
+    layout.assert_eq(&product.y, &result.y);
+}
+
+impl<F: PrimeField + SquareRootField> CircuitGate<F> {
+    /// Extends a circuit with the multi-scalar multiplication of points of a foreign curve
+    /// Includes:
+    /// - the foreign field gates, range checks and generic gates of the multiplication (see the module)
+    /// Input:
+    /// - gates : the full circuit
+    /// - curve : the foreign curve of the points
+    /// - bases : the points of the witness or the fixed points to multiply, in the order of the terms
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the scalars and the points of the witness (see [foreign_curve_msm_limb]) should be copied from other cells,
+    ///   the points being constrained to be on the curve, and the result must not be the point at infinity
+    pub fn extend_foreign_curve_msm(
+        gates: &mut Vec<Self>,
+        curve: &ForeignCurve,
+        bases: &[MulBase],
+    ) -> usize {
+        let (next_row, mut msm_gates) = Self::create_foreign_curve_msm(gates.len(), curve, bases);
+        gates.append(&mut msm_gates);
+        next_row
+    }
+
+    /// Creates the multi-scalar multiplication of points, see [Self::extend_foreign_curve_msm]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_foreign_curve_msm(
+        new_row: usize,
+        curve: &ForeignCurve,
+        bases: &[MulBase],
+    ) -> (usize, Vec<Self>) {
+        let mut layout = Layout::new(new_row, false);
+        layout_foreign_curve_msm(&mut layout, curve, bases, None);
+        let (msm_gates, _) = layout.finalize();
+        (new_row + msm_gates.len(), msm_gates)
+    }
+}
+
+/// Extends the witness with the multi-scalar multiplication of points of a foreign curve, and returns the result
+/// Input
+/// - witness: full witness of the circuit
+/// - curve: the foreign curve of the points
+/// - bases: the bases of the gadget
+/// - scalars: the scalars of the terms, smaller than the order
+/// - points: the points of the terms, which are the fixed ones of the fixed bases
+pub fn extend_foreign_curve_msm_witness<F: PrimeField + SquareRootField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    curve: &ForeignCurve,
+    bases: &[MulBase],
+    scalars: &[BigUint],
+    points: &[ForeignPoint],
+) -> ForeignPoint {
+    assert!(
+        scalars.len() == bases.len() && points.len() == bases.len(),
+        "There is a scalar and a point per base"
+    );
+    for ((base, scalar), point) in bases.iter().zip(scalars).zip(points) {
+        assert!(
+            scalar < &curve.order,
+            "The scalar is smaller than the order"
+        );
+        assert!(curve.is_on_curve(point), "The point is on the curve");
+        if let MulBase::Fixed(fixed) = base {
+            assert_eq!(fixed, point, "The point is the fixed base");
+        }
+    }
+
+    let result = scalars
+        .iter()
+        .zip(points)
+        .fold(None, |acc, (scalar, point)| {
+            curve.add(&acc, &curve.scalar_mul(point, scalar))
+        })
+        .expect("the result is not the point at infinity");
+    let mut layout = Layout::new(witness[0].len(), true);
+    layout_foreign_curve_msm(
+        &mut layout,
+        curve,
+        bases,
+        Some((scalars, points, result.clone())),
+    );
+    let (_, msm_witness) = layout.finalize();
+    let msm_witness = msm_witness.expect("the witness is laid out");
+    for (col, cells) in witness.iter_mut().zip(msm_witness) {
+        col.extend(cells);
+    }
+    result
+}
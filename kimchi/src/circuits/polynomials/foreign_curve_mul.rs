@@ -0,0 +1,452 @@
+//! This module includes the definition of the windowed scalar multiplication gadget over a foreign curve
+//! and its witness code generation.
+//! Note that this module does not include a new gate type, the gadget reuses the foreign field gates,
+//! the range check gates and the `Generic` gate, as laid out by the ECDSA gadget.
+
+//~ The windowed scalar multiplication gadget computes $R = k P$ for a scalar $k$ modulo the order $n$
+//~ of a foreign curve and a point $P$, which is either a point of the witness or a fixed point of the circuit.
+//~ The scalar $k$, the result $R$ and the point $P$ (when it is not fixed) are the first elements of the gadget,
+//~ each one checked to be canonical, at the positions given by `foreign_curve_mul_limb`.
+//~
+//~ The $\ell$ bits of $k$, as many as the bits of $n$, are split into $m = \lceil \ell / w \rceil$ windows of $w$ bits
+//~ from the least significant one,
+//~ so that the most significant window may be shorter, and $k = \sum_i 2^{w i} k_i$.
+//~ The table of the window holds the points $T_d = d P + C$ for $d < 2^w$, where $C$ is the offset point of the curve,
+//~ whose discrete logarithm is unknown:
+//~
+//~ * for a point of the witness, $T_0 = C$ and $T_d = T_{d-1} + P$ are computed in the circuit,
+//~ * for a fixed point, the table is precomputed and its coordinates are constants of the circuit.
+//~
+//~ The point $T_{k_i}$ of each window is selected with a binary tree of selections $b (u - v) + v$
+//~ over the bits of the window, from the most significant one.
+//~ The accumulator starts from the point of the most significant window, and for each of the following windows
+//~ it is doubled $w$ times and added the point of the window, so that it ends at
+//~ $$k P + \left( \sum_{i=0}^{m-1} 2^{w i} \right) C$$
+//~ and it is finally added the constant $- \left( \sum_i 2^{w i} \right) C$.
+//~ The point additions are incomplete: an accumulator which would reach a point of the table (or its negation)
+//~ fails the multiplication, which only happens with a negligible probability for the offset $C$,
+//~ and the gadget requires $k \neq 0$ (since $0 P$ is the point at infinity).
+//~
+//~ A window of $w$ bits needs $w$ doublings, one addition and $2^w - 1$ selections of points,
+//~ instead of $w$ doublings, $w$ additions and $w$ selections for the double-and-add,
+//~ and the table of a point of the witness needs $2^w - 1$ additions.
+//~
+//~ The fixed-base variant of the gadget `create_foreign_curve_fixed_mul`, for a fixed point $P$ of the circuit
+//~ (such as the generator of secp256k1), embeds a precomputed table per window in the coefficients of its gates,
+//~ and needs no doubling. The windows have $2$ bits from the least significant one (the most significant
+//~ window may have a single bit), and the table of the window $i$ holds the points $T_{i, d} = d 4^i P + 2^i C$.
+//~ Each limb of the coordinates of $T_{i, k_i}$ is the multilinear interpolation of the limbs $c_d$ of the table
+//~ over the bits $b_1, b_0$ of the window, in the coefficients of a single `Generic` gate:
+//~ $$c_0 + (c_2 - c_0) b_1 + (c_1 - c_0) b_0 + (c_3 - c_2 - c_1 + c_0) b_1 b_0 - l = 0$$
+//~ or $c_0 + (c_1 - c_0) b - l = 0$ for a window of a single bit, so that the selected limbs are canonical
+//~ without range checks. The accumulator starts from the point of the least significant window, and is added
+//~ the point of each following window, so that it ends at $k P + (2^m - 1) C$, and it is finally added the constant
+//~ $-(2^m - 1) C$. Since the offsets of the accumulator and of the points of the window are distinct multiples of $C$,
+//~ the incomplete additions only fail with a negligible probability, and the gadget requires $k \neq 0$ as well.
+//~ A window of $2$ bits needs $6$ halves of `Generic` gates and one addition, instead of $2$ doublings,
+//~ one addition and the selections of the variable-base path, and the table needs no element of the witness.
+//~
+
+use crate::circuits::{gate::CircuitGate, polynomial::COLUMNS};
+use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+use super::ecdsa::{
+    curve::{ForeignCurve, ForeignPoint},
+    layout::{Bit, Layout, Point},
+    ELEM_ROWS,
+};
+
+/// Maximum number of bits of a window of the gadget
+pub const MAX_WINDOW: usize = 4;
+
+/// The base point of the scalar multiplication gadget
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MulBase {
+    /// A point of the witness, whose table is computed in the circuit
+    Variable,
+    /// A fixed point, whose table is precomputed
+    Fixed(ForeignPoint),
+}
+
+/// The elements of the scalar multiplication gadget, in their order
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurveMulValue {
+    /// The scalar, modulo the order
+    Scalar,
+    /// The x-coordinate of the result
+    ResultX,
+    /// The y-coordinate of the result
+    ResultY,
+    /// The x-coordinate of the point, only for a variable base
+    PointX,
+    /// The y-coordinate of the point, only for a variable base
+    PointY,
+}
+
+/// Returns the position of the cell containing the `limb`-th limb (from the least significant one)
+/// of an element of the scalar multiplication gadget starting at row `new_row`, as a `(row, column)` pair
+pub fn foreign_curve_mul_limb(new_row: usize, value: CurveMulValue, limb: usize) -> (usize, usize) {
+    assert!(limb < 3, "An element only has three limbs");
+    (new_row + ELEM_ROWS * value as usize + limb, 0)
+}
+
+fn check_window(window: usize) {
+    assert!(
+        window > 0 && window <= MAX_WINDOW,
+        "Windows are only supported for 1 to 4 bits"
+    );
+}
+
+/// Returns the sum of the factors `2^(w i)` of the offset of the windows of `window` bits of a scalar of `num_bits` bits
+pub fn offset_factor(num_bits: usize, window: usize) -> BigUint {
+    let num_windows = (num_bits + window - 1) / window;
+    (0..num_windows).fold(BigUint::zero(), |acc, i| {
+        acc + (BigUint::one() << (window * i))
+    })
+}
+
+/// Lays out the table of the points `d P + C` for `d < 2^window`, where `C` is the offset of the curve
+pub fn window_table<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    curve: &ForeignCurve,
+    point: &Point<F>,
+    window: usize,
+) -> Vec<Point<F>> {
+    check_window(window);
+    let mut table = vec![layout.constant_point(&curve.offset())];
+    for d in 1..1 << window {
+        let entry = layout.add_points(curve, &table[d - 1], point);
+        table.push(entry);
+    }
+    table
+}
+
+/// Returns the precomputed table of the points `d P + C` for `d < 2^window`,
+/// where `P` is a fixed point and `C` is the offset of the curve
+pub fn fixed_window_table<F: PrimeField + SquareRootField>(
+    layout: &Layout<F>,
+    curve: &ForeignCurve,
+    point: &ForeignPoint,
+    window: usize,
+) -> Vec<Point<F>> {
+    check_window(window);
+    let point = Some(point.clone());
+    let mut entry = Some(curve.offset());
+    let mut table = vec![];
+    for _ in 0..1 << window {
+        let constant = entry
+            .as_ref()
+            .expect("the entries of the table are not the point at infinity");
+        table.push(layout.constant_point(constant));
+        entry = curve.add(&entry, &point);
+    }
+    table
+}
+
+/// Lays out the selection of the entry of a table at the index of the bits, from the most significant one,
+/// where the table has (at least) `2^bits.len()` entries
+pub fn select_table<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    bits: &[Bit],
+    table: &[Point<F>],
+) -> Point<F> {
+    match bits.split_first() {
+        None => table[0].clone(),
+        Some((bit, rest)) => {
+            let half = 1 << rest.len();
+            let low = select_table(layout, rest, &table[..half]);
+            let high = select_table(layout, rest, &table[half..2 * half]);
+            layout.select_point(bit, &high, &low)
+        }
+    }
+}
+
+/// Lays out the windowed multiplication of the point of a table by the scalar of the bits (from the most significant one),
+/// see the module, including the final addition of the negated offset
+pub fn windowed_mul<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    curve: &ForeignCurve,
+    bits: &[Bit],
+    table: &[Point<F>],
+    window: usize,
+) -> Point<F> {
+    check_window(window);
+    assert!(!bits.is_empty(), "The scalar has bits");
+    // the most significant window is the shortest one
+    let top = bits.len() - window * ((bits.len() - 1) / window);
+    let mut acc = select_table(layout, &bits[..top], table);
+    for window_bits in bits[top..].chunks(window) {
+        for _ in 0..window {
+            acc = layout.double_point(curve, &acc);
+        }
+        let entry = select_table(layout, window_bits, table);
+        acc = layout.add_points(curve, &acc, &entry);
+    }
+    let offset = curve
+        .scalar_mul(&curve.offset(), &offset_factor(bits.len(), window))
+        .expect("the offset is not of small order");
+    let correction = layout.constant_point(&curve.neg(&offset));
+    layout.add_points(curve, &acc, &correction)
+}
+
+/// Number of bits of the windows of the fixed-base multiplication with the tables in the coefficients of the gates
+pub const FIXED_WINDOW: usize = 2;
+
+/// Lays out the multiplication of a fixed point by the scalar of the bits (from the most significant one),
+/// with the tables of the windows in the coefficients of the generic gates (see the module),
+/// including the final addition of the negated offset
+pub fn fixed_base_mul<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    curve: &ForeignCurve,
+    point: &ForeignPoint,
+    bits: &[Bit],
+) -> Point<F> {
+    assert!(!bits.is_empty(), "The scalar has bits");
+    let constant = |point: Option<ForeignPoint>| {
+        point.expect("the entries of the tables are not the point at infinity")
+    };
+    // the base 4^i P and the offset 2^i C of the window i
+    let mut base = Some(point.clone());
+    let mut offset = Some(curve.offset());
+    let mut acc: Option<Point<F>> = None;
+    for window_bits in bits.rchunks(FIXED_WINDOW) {
+        let mut entry = offset.clone();
+        let mut table = vec![];
+        for _ in 0..1 << window_bits.len() {
+            table.push(constant(entry.clone()));
+            entry = curve.add(&entry, &base);
+        }
+        let (xs, ys): (Vec<_>, Vec<_>) = table.into_iter().unzip();
+        let selected = Point {
+            x: layout.select_constant_table(window_bits, &xs),
+            y: layout.select_constant_table(window_bits, &ys),
+        };
+        acc = Some(match acc {
+            None => selected,
+            Some(acc) => layout.add_points(curve, &acc, &selected),
+        });
+        for _ in 0..FIXED_WINDOW {
+            base = curve.add(&base, &base);
+        }
+        offset = curve.add(&offset, &offset);
+    }
+    // the offset is now 2^m C
+    let correction = curve.add(&offset, &Some(curve.neg(&curve.offset())));
+    let correction = layout.constant_point(&curve.neg(&constant(correction)));
+    let acc = acc.expect("the scalar has a window");
+    layout.add_points(curve, &acc, &correction)
+}
+
+// Lays out the scalar multiplication, with the values of the scalar, of the result and of the point for the witness
+fn layout_foreign_curve_mul<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    curve: &ForeignCurve,
+    window: usize,
+    base: &MulBase,
+    values: Option<(BigUint, ForeignPoint, ForeignPoint)>,
+) {
+    let (p, n) = (&curve.modulus, &curve.order);
+    let scalar = layout.witness(values.as_ref().map(|(k, _, _)| k.clone()), n);
+    let result = Point {
+        x: layout.witness(values.as_ref().map(|(_, (x, _), _)| x.clone()), p),
+        y: layout.witness(values.as_ref().map(|(_, (_, y), _)| y.clone()), p),
+    };
+    let table = match base {
+        MulBase::Variable => {
+            let point = Point {
+                x: layout.witness(values.as_ref().map(|(_, _, (x, _))| x.clone()), p),
+                y: layout.witness(values.as_ref().map(|(_, _, (_, y))| y.clone()), p),
+            };
+            window_table(layout, curve, &point, window)
+        }
+        MulBase::Fixed(point) => fixed_window_table(layout, curve, point, window),
+    };
+    let bits = layout.bits(&scalar, n.bits() as usize);
+    let product = windowed_mul(layout, curve, &bits, &table, window);
+    layout.assert_eq(&product.x, &result.x);
+    layout.assert_eq(&product.y, &result.y);
+}
+
+// Lays out the fixed-base multiplication with the tables in the coefficients of the gates,
+// with the values of the scalar and of the result for the witness
+fn layout_foreign_curve_fixed_mul<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    curve: &ForeignCurve,
+    point: &ForeignPoint,
+    values: Option<(BigUint, ForeignPoint)>,
+) {
+    let (p, n) = (&curve.modulus, &curve.order);
+    let scalar = layout.witness(values.as_ref().map(|(k, _)| k.clone()), n);
+    let result = Point {
+        x: layout.witness(values.as_ref().map(|(_, (x, _))| x.clone()), p),
+        y: layout.witness(values.as_ref().map(|(_, (_, y))| y.clone()), p),
+    };
+    let bits = layout.bits(&scalar, n.bits() as usize);
+    let product = fixed_base_mul(layout, curve, point, &bits);
+    layout.assert_eq(&product.x, &result.x);
+    layout.assert_eq(&product.y, &result.y);
+}
+
+impl<F: PrimeField + SquareRootField> CircuitGate<F> {
+    /// Extends a circuit with the windowed scalar multiplication of a point of a foreign curve
+    /// Includes:
+    /// - the foreign field gates, range checks and generic gates of the multiplication (see the module)
+    /// Input:
+    /// - gates  : the full circuit
+    /// - curve  : the foreign curve of the point
+    /// - window : the number of bits of the windows, up to [MAX_WINDOW]
+    /// - base   : the point of the witness or the fixed point to multiply
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the scalar and the point of the witness (see [foreign_curve_mul_limb]) should be copied from other cells,
+    ///   the point being constrained to be on the curve, and the scalar must not be zero
+    pub fn extend_foreign_curve_mul(
+        gates: &mut Vec<Self>,
+        curve: &ForeignCurve,
+        window: usize,
+        base: &MulBase,
+    ) -> usize {
+        let (next_row, mut mul_gates) =
+            Self::create_foreign_curve_mul(gates.len(), curve, window, base);
+        gates.append(&mut mul_gates);
+        next_row
+    }
+
+    /// Creates the windowed scalar multiplication of a point, see [Self::extend_foreign_curve_mul]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_foreign_curve_mul(
+        new_row: usize,
+        curve: &ForeignCurve,
+        window: usize,
+        base: &MulBase,
+    ) -> (usize, Vec<Self>) {
+        let mut layout = Layout::new(new_row, false);
+        layout_foreign_curve_mul(&mut layout, curve, window, base, None);
+        let (mul_gates, _) = layout.finalize();
+        (new_row + mul_gates.len(), mul_gates)
+    }
+
+    /// Extends a circuit with the multiplication of a fixed point of a foreign curve,
+    /// whose precomputed tables are in the coefficients of the gates
+    /// Includes:
+    /// - the foreign field gates, range checks and generic gates of the multiplication (see the module)
+    /// Input:
+    /// - gates  : the full circuit
+    /// - curve  : the foreign curve of the point
+    /// - point  : the fixed point to multiply
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the scalar (see [foreign_curve_mul_limb]) should be copied from other cells, and must not be zero
+    pub fn extend_foreign_curve_fixed_mul(
+        gates: &mut Vec<Self>,
+        curve: &ForeignCurve,
+        point: &ForeignPoint,
+    ) -> usize {
+        let (next_row, mut mul_gates) =
+            Self::create_foreign_curve_fixed_mul(gates.len(), curve, point);
+        gates.append(&mut mul_gates);
+        next_row
+    }
+
+    /// Creates the multiplication of a fixed point, see [Self::extend_foreign_curve_fixed_mul]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_foreign_curve_fixed_mul(
+        new_row: usize,
+        curve: &ForeignCurve,
+        point: &ForeignPoint,
+    ) -> (usize, Vec<Self>) {
+        let mut layout = Layout::new(new_row, false);
+        layout_foreign_curve_fixed_mul(&mut layout, curve, point, None);
+        let (mul_gates, _) = layout.finalize();
+        (new_row + mul_gates.len(), mul_gates)
+    }
+}
+
+/// Extends the witness with the windowed scalar multiplication of a point of a foreign curve, and returns the result
+/// Input
+/// - witness: full witness of the circuit
+/// - curve: the foreign curve of the point
+/// - window: the number of bits of the windows, up to [MAX_WINDOW]
+/// - base: the base of the gadget
+/// - scalar: the scalar, smaller than the order and not zero
+/// - point: the point to multiply, which is the fixed one of a fixed base
+pub fn extend_foreign_curve_mul_witness<F: PrimeField + SquareRootField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    curve: &ForeignCurve,
+    window: usize,
+    base: &MulBase,
+    scalar: &BigUint,
+    point: &ForeignPoint,
+) -> ForeignPoint {
+    assert!(
+        !scalar.is_zero() && scalar < &curve.order,
+        "The scalar is not zero and smaller than the order"
+    );
+    assert!(curve.is_on_curve(point), "The point is on the curve");
+    if let MulBase::Fixed(fixed) = base {
+        assert_eq!(fixed, point, "The point is the fixed base");
+    }
+
+    let result = curve
+        .scalar_mul(point, scalar)
+        .expect("the multiple of a point by a scalar smaller than the order is not zero");
+    let mut layout = Layout::new(witness[0].len(), true);
+    layout_foreign_curve_mul(
+        &mut layout,
+        curve,
+        window,
+        base,
+        Some((scalar.clone(), result.clone(), point.clone())),
+    );
+    let (_, mul_witness) = layout.finalize();
+    let mul_witness = mul_witness.expect("the witness is laid out");
+    for (col, cells) in witness.iter_mut().zip(mul_witness) {
+        col.extend(cells);
+    }
+    result
+}
+
+/// Extends the witness with the multiplication of a fixed point of a foreign curve,
+/// with the tables in the coefficients of the gates, and returns the result
+/// Input
+/// - witness: full witness of the circuit
+/// - curve: the foreign curve of the point
+/// - point: the fixed point of the gadget
+/// - scalar: the scalar, smaller than the order and not zero
+pub fn extend_foreign_curve_fixed_mul_witness<F: PrimeField + SquareRootField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    curve: &ForeignCurve,
+    point: &ForeignPoint,
+    scalar: &BigUint,
+) -> ForeignPoint {
+    assert!(
+        !scalar.is_zero() && scalar < &curve.order,
+        "The scalar is not zero and smaller than the order"
+    );
+    assert!(curve.is_on_curve(point), "The point is on the curve");
+
+    let result = curve
+        .scalar_mul(point, scalar)
+        .expect("the multiple of a point by a scalar smaller than the order is not zero");
+    let mut layout = Layout::new(witness[0].len(), true);
+    layout_foreign_curve_fixed_mul(
+        &mut layout,
+        curve,
+        point,
+        Some((scalar.clone(), result.clone())),
+    );
+    let (_, mul_witness) = layout.finalize();
+    let mul_witness = mul_witness.expect("the witness is laid out");
+    for (col, cells) in witness.iter_mut().zip(mul_witness) {
+        col.extend(cells);
+    }
+    result
+}
@@ -0,0 +1,209 @@
+//! This module includes the definition of the gadget recomposing the limbs of a foreign field element
+//! from its bytes, such as the output of a hash gadget, and its witness code generation.
+//! Note that this gadget does not include a new gate type, it reuses the `Generic` and range check gates.
+
+//~ The byte limbs gadget recomposes the 3 limbs of 88 bits of a foreign field element,
+//~ as used by the foreign field gates, from a big-endian array of $n \leq 33$ bytes $b_0, \dots, b_{n-1}$,
+//~ such as a message hash:
+//~ $$v = \sum_{i=0}^{n-1} b_i 2^{8 (n - 1 - i)} = v_0 + 2^{88} v_1 + 2^{176} v_2$$
+//~ Each limb $v_j$ is made of the (up to) 11 bytes $b_{n - 11 (j + 1)}, \dots, b_{n - 1 - 11 j}$,
+//~ accumulated from the most significant one in `Generic` gates, a byte per half gate:
+//~ the first one is $b - acc = 0$, and the next ones are $2^8 acc + b - acc' = 0$.
+//~ The limb of an array too short is constrained to zero with a half gate $acc = 0$.
+//~ The gate of a byte holds it in its right input, to be copied from the cell which constrains it,
+//~ and the accumulators are wired to the next gates.
+//~
+//~ The limbs are then multi-range-checked, so that they can be copied to the inputs of the foreign field gates.
+//~ Their decomposition into bytes is unique only if the bytes are already constrained to be at most 255,
+//~ for example by the gadget which outputs them or with the byte range check gadget.
+//~
+//~ | Row   | `CircuitGate` | Purpose                                     |
+//~ | ----- | ------------- | ------------------------------------------- |
+//~ | i...  | `Generic`     | Accumulate the bytes of the limbs           |
+//~ | ...   | `RangeCheck0` | Range check $v_0$                           |
+//~ | ...   | `RangeCheck0` | Range check $v_1$                           |
+//~ | ...   | `RangeCheck1` | Range check $v_2$                           |
+//~ | ...   | `Zero`        |                                             |
+//~
+//~ For example, the 32 bytes of a hash need 16 `Generic` rows (11, 11 and 10 bytes) and the 4 rows of the range check.
+//~ Note that the recomposed element is not reduced modulo a foreign field modulus.
+
+use crate::circuits::{
+    gate::{CircuitGate, Connect},
+    polynomial::COLUMNS,
+    polynomials::{
+        generic::{DOUBLE_GENERIC_COEFFS, GENERIC_COEFFS, GENERIC_REGISTERS},
+        range_check::{self, gadget::MULTI_RANGE_CHECK_ROWS},
+    },
+    wires::Wire,
+};
+use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::BigUint;
+use o1_utils::foreign_field::{BigUintForeignFieldHelpers, LIMB_BITS};
+use std::array;
+
+/// Maximum number of bytes of the gadget, the bytes of 3 limbs
+pub const MAX_BYTES: usize = 3 * LIMB_BYTES;
+
+/// Number of bytes of a full limb
+const LIMB_BYTES: usize = LIMB_BITS / 8;
+
+// The number of bytes of the `limb`-th limb of an array of `num_bytes` bytes
+fn limb_bytes(num_bytes: usize, limb: usize) -> usize {
+    num_bytes.saturating_sub(LIMB_BYTES * limb).min(LIMB_BYTES)
+}
+
+// The number of half generic gates of each limb, from the least significant one
+fn limb_halves(num_bytes: usize) -> [usize; 3] {
+    assert!(
+        num_bytes > 0 && num_bytes <= MAX_BYTES,
+        "Byte limbs are only supported for 1 to 33 bytes"
+    );
+    array::from_fn(|limb| limb_bytes(num_bytes, limb).max(1))
+}
+
+// The index of the first half generic gate of the `limb`-th limb
+fn first_half(num_bytes: usize, limb: usize) -> usize {
+    limb_halves(num_bytes)[..limb].iter().sum()
+}
+
+fn num_generic_rows(num_bytes: usize) -> usize {
+    (limb_halves(num_bytes).iter().sum::<usize>() + 1) / 2
+}
+
+// The cell of the `i`-th register of the half generic gate `half`, relative to the gadget
+fn half_cell(half: usize, i: usize) -> (usize, usize) {
+    (half / 2, GENERIC_REGISTERS * (half % 2) + i)
+}
+
+/// Returns the number of rows of the byte limbs gadget of `num_bytes` bytes
+/// Panics if `num_bytes` is not in `1..=33`
+pub fn bytes_to_limbs_rows(num_bytes: usize) -> usize {
+    num_generic_rows(num_bytes) + MULTI_RANGE_CHECK_ROWS
+}
+
+/// Returns the position of the cell of the `byte`-th byte (in big-endian order) of the byte limbs gadget
+/// of `num_bytes` bytes starting at row `new_row`, as a `(row, column)` pair
+/// Panics if `num_bytes` is not in `1..=33`, or if `byte` is not smaller than `num_bytes`
+pub fn bytes_to_limbs_byte(new_row: usize, num_bytes: usize, byte: usize) -> (usize, usize) {
+    assert!(byte < num_bytes, "The byte is in the array");
+    // the position of the byte from the least significant one
+    let pos = num_bytes - 1 - byte;
+    let limb = pos / LIMB_BYTES;
+    let half = first_half(num_bytes, limb) + limb_bytes(num_bytes, limb) - 1 - pos % LIMB_BYTES;
+    let (row, col) = half_cell(half, 1);
+    (new_row + row, col)
+}
+
+/// Returns the position of the cell of the `limb`-th limb (from the least significant one)
+/// of the byte limbs gadget of `num_bytes` bytes starting at row `new_row`, as a `(row, column)` pair
+/// Panics if `num_bytes` is not in `1..=33`
+pub fn bytes_to_limbs_limb(new_row: usize, num_bytes: usize, limb: usize) -> (usize, usize) {
+    range_check::gadget::multi_range_check_limb(new_row + num_generic_rows(num_bytes), 0, limb)
+}
+
+impl<F: PrimeField + SquareRootField> CircuitGate<F> {
+    /// Extends a circuit with a byte limbs gadget of `num_bytes` bytes
+    /// Includes:
+    /// - Generic gates to accumulate the bytes of each limb, one byte per half gate
+    /// - 1 multi-range-check of the limbs (4 rows)
+    /// Input:
+    /// - gates     : the full circuit
+    /// - num_bytes : the number of bytes of the element, up to 33
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the bytes (at the positions given by [bytes_to_limbs_byte]) should be copied from cells
+    ///   which constrain them to be bytes
+    /// - the limbs are at the positions given by [bytes_to_limbs_limb]
+    pub fn extend_bytes_to_limbs(gates: &mut Vec<Self>, num_bytes: usize) -> usize {
+        let (next_row, mut limbs_gates) = Self::create_bytes_to_limbs(gates.len(), num_bytes);
+        gates.append(&mut limbs_gates);
+        next_row
+    }
+
+    /// Creates a byte limbs gadget of `num_bytes` bytes, see [Self::extend_bytes_to_limbs]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_bytes_to_limbs(new_row: usize, num_bytes: usize) -> (usize, Vec<Self>) {
+        let halves = limb_halves(num_bytes);
+        let generic_rows = num_generic_rows(num_bytes);
+
+        let mut coeffs = vec![[F::zero(); DOUBLE_GENERIC_COEFFS]; generic_rows];
+        let mut half = 0;
+        for (limb, num_halves) in halves.iter().enumerate() {
+            for i in 0..*num_halves {
+                let half_coeffs: [F; GENERIC_COEFFS] = if limb_bytes(num_bytes, limb) == 0 {
+                    // acc = 0
+                    [F::zero(), F::zero(), F::one(), F::zero(), F::zero()]
+                } else if i == 0 {
+                    // b - acc = 0
+                    [F::zero(), F::one(), -F::one(), F::zero(), F::zero()]
+                } else {
+                    // 2^8 acc + b - acc' = 0
+                    [F::from(256u64), F::one(), -F::one(), F::zero(), F::zero()]
+                };
+                coeffs[half / 2][GENERIC_COEFFS * (half % 2)..GENERIC_COEFFS * (half % 2 + 1)]
+                    .copy_from_slice(&half_coeffs);
+                half += 1;
+            }
+        }
+        let mut limbs_gates: Vec<Self> = coeffs
+            .into_iter()
+            .enumerate()
+            .map(|(i, coeffs)| CircuitGate::create_generic(Wire::for_row(new_row + i), coeffs))
+            .collect();
+        let (next_row, mut rc_gates) =
+            CircuitGate::create_multi_range_check(new_row + generic_rows);
+        limbs_gates.append(&mut rc_gates);
+
+        // wire the accumulators (indices are relative to the gadget, wires are not)
+        for (limb, num_halves) in halves.iter().enumerate() {
+            let first = first_half(num_bytes, limb);
+            for half in first + 1..first + num_halves {
+                limbs_gates.connect_cell_pair(half_cell(half - 1, 2), half_cell(half, 0));
+            }
+            let (row, col) = bytes_to_limbs_limb(0, num_bytes, limb);
+            limbs_gates.connect_cell_pair(half_cell(first + num_halves - 1, 2), (row, col));
+        }
+
+        (next_row, limbs_gates)
+    }
+}
+
+/// Extends the byte limbs rows of an array of bytes to the full witness
+/// Input
+/// - witness: full witness of the circuit
+/// - bytes: the bytes of the element, in big-endian order, up to 33
+/// Panics if there are no bytes or more than 33
+pub fn extend_bytes_to_limbs_witness<F: PrimeField>(witness: &mut [Vec<F>; COLUMNS], bytes: &[u8]) {
+    let num_bytes = bytes.len();
+    let halves = limb_halves(num_bytes);
+    let generic_rows = num_generic_rows(num_bytes);
+
+    let mut limbs_witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![F::zero(); generic_rows]);
+    let mut half = 0;
+    for (limb, num_halves) in halves.iter().enumerate() {
+        // the bytes of the limb, from the most significant one
+        let end = num_bytes.saturating_sub(LIMB_BYTES * limb);
+        let limb_slice = &bytes[end - limb_bytes(num_bytes, limb)..end];
+        let mut acc = F::zero();
+        for i in 0..*num_halves {
+            let (row, col) = half_cell(half, 0);
+            if let Some(byte) = limb_slice.get(i) {
+                limbs_witness[col][row] = acc;
+                limbs_witness[col + 1][row] = F::from(*byte);
+                acc = acc * F::from(256u64) + F::from(*byte);
+                limbs_witness[col + 2][row] = acc;
+            }
+            half += 1;
+        }
+    }
+    for col in 0..COLUMNS {
+        witness[col].extend(limbs_witness[col].iter());
+    }
+
+    let limbs = BigUint::from_bytes_be(bytes).to_field_limbs::<F>();
+    range_check::witness::extend_multi(witness, limbs[0], limbs[1], limbs[2]);
+}
@@ -98,6 +98,8 @@ use std::{array, marker::PhantomData};
 //~
 //~ The sign of the operation (whether it is an addition or a subtraction) is stored in the fourth coefficient as
 //~ a value +1 (for addition) or -1 (for subtraction). The first 3 coefficients are the 3 limbs of the foreign modulus.
+//~ The fifth coefficient is 1 for a doubling (and 0 otherwise): the constraints of a doubling read its left input
+//~ as its right input, so that its right input cells are not used, and need neither copies nor range checks.
 //~ One could lay this out as a double-width gate for chained foreign additions and a final row, e.g.:
 //~
 //~ | col | `ForeignFieldAdd`        | chain `ForeignFieldAdd` | final `ForeignFieldAdd` | final `Zero`      |
@@ -155,9 +157,15 @@ where
         let left_input_mi = env.witness_curr(1);
         let left_input_hi = env.witness_curr(2);
 
-        let right_input_lo = env.witness_curr(3);
-        let right_input_mi = env.witness_curr(4);
-        let right_input_hi = env.witness_curr(5);
+        // the right input of a doubling is its left input, its right input cells are not read
+        let double = env.coeff(4);
+        let right_input = |i: usize| {
+            let right = env.witness_curr(3 + i);
+            right.clone() + double.clone() * (env.witness_curr(i) - right)
+        };
+        let right_input_lo = right_input(0);
+        let right_input_mi = right_input(1);
+        let right_input_hi = right_input(2);
 
         // sign in <7 to be able to check against public input of opcodes
         let field_overflow = env.witness_curr(6);
@@ -9,7 +9,53 @@ use crate::circuits::{
     wires::Wire,
 };
 
-use super::witness::FFOps;
+use super::witness::{limb_cells, ChainOpCells, FFOps};
+
+/// The cells of a chain of foreign field additions, see [CircuitGate::create_foreign_field_add]
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ChainCells {
+    /// the cells of each operation, in the order of the operations
+    pub ops: Vec<ChainOpCells>,
+    /// the `ForeignFieldAdd` row of the final bound addition,
+    /// whose left input is the result of the chain
+    pub bound_row: usize,
+    /// the field overflow of the bound addition, to be copied from a public input containing the 1 value
+    pub bound_overflow: Wire,
+    /// the limbs of the bound, on the `Zero` row after the bound addition, to be range checked
+    pub bound: [Wire; 3],
+    /// the row after the chain
+    pub next_row: usize,
+}
+
+impl ChainCells {
+    /// Returns the cells of a chain of operations starting at row `start_row`
+    pub fn new(start_row: usize, opcodes: &[FFOps]) -> Self {
+        let ops = opcodes
+            .iter()
+            .zip(start_row..)
+            .map(|(opcode, row)| ChainOpCells {
+                row,
+                left: limb_cells(row, 0),
+                // the right input of a doubling is its left input
+                right: limb_cells(row, if *opcode == FFOps::Double { 0 } else { 3 }),
+                result: limb_cells(row + 1, 0),
+            })
+            .collect();
+        let bound_row = start_row + opcodes.len();
+        Self {
+            ops,
+            bound_row,
+            bound_overflow: Wire::new(bound_row, 6),
+            bound: limb_cells(bound_row + 1, 0),
+            next_row: bound_row + 2,
+        }
+    }
+
+    /// The cells of the result of the chain
+    pub fn result(&self) -> [Wire; 3] {
+        limb_cells(self.bound_row, 0)
+    }
+}
 
 impl<F: PrimeField + SquareRootField> CircuitGate<F> {
     /// Create foreign field addition gate chain without range checks (needs to wire the range check for result bound manually)
@@ -30,6 +76,10 @@ impl<F: PrimeField + SquareRootField> CircuitGate<F> {
     ///      (n+1)         -> 1 Zero row for bound result
     /// ]
     ///
+    /// The doubling rows ([FFOps::Double]) have a doubling coefficient, so that their constraints
+    /// read their left input as their right input: their right input cells are unused,
+    /// and do not need to be wired nor range checked.
+    ///
     /// Warning:
     /// - Wire the range check for result bound manually
     /// - Connect to public input containing the 1 value for the overflow in the final bound check
@@ -58,6 +108,7 @@ impl<F: PrimeField + SquareRootField> CircuitGate<F> {
         for (i, opcode) in opcodes.iter().enumerate() {
             let mut coeffs = foreign_field_modulus.to_vec();
             coeffs.push(opcode.sign::<F>());
+            coeffs.push(opcode.double::<F>());
             circuit_gates.append(&mut vec![CircuitGate {
                 typ: GateType::ForeignFieldAdd,
                 wires: Wire::for_row(next_row + i),
@@ -66,6 +117,7 @@ impl<F: PrimeField + SquareRootField> CircuitGate<F> {
         }
         let mut final_coeffs = foreign_field_modulus.to_vec();
         final_coeffs.push(FFOps::Add.sign::<F>());
+        final_coeffs.push(FFOps::Add.double::<F>());
         // Then the final bound gate and the zero gate
         circuit_gates.append(&mut vec![
             CircuitGate {
@@ -82,7 +134,32 @@ impl<F: PrimeField + SquareRootField> CircuitGate<F> {
         (start_row + circuit_gates.len(), circuit_gates)
     }
 
+    /// Create a chain of foreign field additions as [Self::create_chain_ffadd] does,
+    /// with the cells of its inputs and results, to be wired to the other gadgets
+    /// - Inputs
+    ///   - starting row
+    ///   - operations to perform
+    ///   - modulus of the foreign field
+    /// - Outputs tuple (cells, circuit_gates) where
+    ///   - cells         - the cells of the chain, and the row after it
+    ///   - circuit_gates - vector of circuit gates comprising this gate
+    ///
+    /// Warning:
+    /// - Range check the bound (see [ChainCells::bound])
+    /// - Connect the overflow of the bound addition (see [ChainCells::bound_overflow])
+    ///   to a public input containing the 1 value
+    pub fn create_foreign_field_add(
+        start_row: usize,
+        opcodes: &[FFOps],
+        foreign_field_modulus: &BigUint,
+    ) -> (ChainCells, Vec<Self>) {
+        let (_, circuit_gates) =
+            Self::create_chain_ffadd(start_row, opcodes, foreign_field_modulus);
+        (ChainCells::new(start_row, opcodes), circuit_gates)
+    }
+
     /// Create a single foreign field addition gate. This is used for example in the final bound check.
+    /// As in [Self::create_chain_ffadd], a doubling reads its left input as its right input.
     /// - Inputs
     ///   - starting row
     ///   - operation to perform
@@ -106,6 +183,7 @@ impl<F: PrimeField + SquareRootField> CircuitGate<F> {
         let foreign_field_modulus = foreign_field_modulus.to_field_limbs::<F>();
         let mut coeffs = foreign_field_modulus.to_vec();
         coeffs.push(operation.sign::<F>());
+        coeffs.push(operation.double::<F>());
         let circuit_gates = vec![
             CircuitGate {
                 typ: GateType::ForeignFieldAdd,
@@ -144,6 +222,29 @@ impl<F: PrimeField + SquareRootField> CircuitGate<F> {
         gates.connect_cell_pair((pub_row, 0), (*curr_row - 2, 6));
     }
 
+    /// Extend a chain of foreign field additions, see [Self::create_foreign_field_add].
+    /// It already wires the overflow of the bound addition to the public input at `pub_row`,
+    /// as [Self::extend_chain_ffadd] does.
+    /// - Inputs
+    ///   - gates: vector of gates to extend
+    ///   - pub_row: row of the public input containing the 1 value
+    ///   - opcodes: operations to perform
+    ///   - foreign_field_modulus: modulus of the foreign field
+    /// - Outputs the cells of the chain
+    pub fn extend_foreign_field_add(
+        gates: &mut Vec<Self>,
+        pub_row: usize,
+        opcodes: &[FFOps],
+        foreign_field_modulus: &BigUint,
+    ) -> ChainCells {
+        let (cells, mut add_gates) =
+            Self::create_foreign_field_add(gates.len(), opcodes, foreign_field_modulus);
+        gates.append(&mut add_gates);
+        let overflow = cells.bound_overflow;
+        gates.connect_cell_pair((pub_row, 0), (overflow.row, overflow.col));
+        cells
+    }
+
     /// Extend a single foreign field addition gate followed by a zero row containing the result
     pub fn extend_single_ffadd(
         gates: &mut Vec<Self>,
@@ -1,5 +1,6 @@
 //! Foreign Field Addition module
 
+pub mod bytes;
 pub mod circuitgates;
 pub mod gadget;
 pub mod witness;
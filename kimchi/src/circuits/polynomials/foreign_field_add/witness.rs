@@ -5,14 +5,18 @@ use crate::circuits::witness::Variables;
 use crate::{
     circuits::{
         polynomial::COLUMNS,
-        witness::{self, ConstantCell, VariableCell, WitnessCell},
+        polynomials::foreign_field_add::{circuitgates::ForeignFieldAdd, gadget::ChainCells},
+        wires::Wire,
+        witness::{self, ConstantCell, Solve, SolverEnv, VariableCell, WitnessCell},
     },
+    error::SolveError,
     variable_map,
 };
 use ark_ff::PrimeField;
 use num_bigint::BigUint;
 use o1_utils::foreign_field::{
-    BigUintForeignFieldHelpers, ForeignElement, ForeignFieldHelpers, HI, LO, MI,
+    BigUintForeignFieldHelpers, FieldArrayCompose, ForeignElement, ForeignFieldHelpers,
+    BINARY_MODULUS_EXP, HI, LO, MI,
 };
 use std::array;
 
@@ -23,6 +27,9 @@ pub enum FFOps {
     Add,
     /// Subtraction
     Sub,
+    /// Doubling: the addition of the left input to itself, which has no right input
+    /// (its gate reads the left input as its right input)
+    Double,
 }
 
 /// Implementation of the FFOps enum
@@ -30,10 +37,19 @@ impl FFOps {
     /// Returns the sign of the operation as a field element
     pub fn sign<F: PrimeField>(&self) -> F {
         match self {
-            FFOps::Add => F::one(),
+            FFOps::Add | FFOps::Double => F::one(),
             FFOps::Sub => -F::one(),
         }
     }
+
+    /// Returns the doubling coefficient of the operation as a field element,
+    /// which is 1 for a doubling and 0 otherwise
+    pub fn double<F: PrimeField>(&self) -> F {
+        match self {
+            FFOps::Double => F::one(),
+            FFOps::Add | FFOps::Sub => F::zero(),
+        }
+    }
 }
 
 // Given a left and right inputs to an addition or subtraction, and a modulus, it computes
@@ -122,8 +138,111 @@ fn compute_ffadd_values<F: PrimeField>(
     (result, sign, field_overflow, carry_bot)
 }
 
+impl<F: PrimeField> Solve<F> for ForeignFieldAdd<F> {
+    // The field overflow and the carry are computed from the inputs, as well as the result,
+    // which is the left input of the next row
+    fn solve(env: &mut SolverEnv<F>, coeffs: &[F]) -> Result<bool, SolveError> {
+        // the right input of a doubling is its left input
+        let double = coeffs.get(4).map_or(false, |double| double.is_one());
+        let right_cols = if double { 0..3 } else { 3..6 };
+        let inputs: Option<Vec<F>> = (0..3).chain(right_cols).map(|col| env.curr(col)).collect();
+        if let Some((field_overflow, carry, result)) =
+            inputs.and_then(|inputs| solve_ffadd_values(&inputs, coeffs))
+        {
+            env.set_curr(6, field_overflow)?;
+            env.set_curr(7, carry)?;
+            for (col, limb) in result.into_iter().enumerate() {
+                env.set_next(col, limb)?;
+            }
+        }
+        let input_cols = if double { 0..3 } else { 0..6 };
+        Ok(input_cols.chain(6..8).all(|col| env.curr(col).is_some()))
+    }
+}
+
+// Computes the field overflow, the carry and the limbs of the result of an operation
+// from the limbs of its inputs and the coefficients of its gate, if the result fits in the limbs
+fn solve_ffadd_values<F: PrimeField>(inputs: &[F], coeffs: &[F]) -> Option<(F, F, [F; 3])> {
+    let [left, right, modulus]: [[F; 3]; 3] =
+        [&inputs[0..3], &inputs[3..6], &coeffs[0..3]].map(|limbs| array::from_fn(|i| limbs[i]));
+    let sign = coeffs[3];
+    let (a, b, f) = (left.compose(), right.compose(), modulus.compose());
+
+    // result = left + sign * right - field_overflow * modulus
+    let (field_overflow, result) = if sign == F::one() {
+        let sum = a + b;
+        if sum >= f {
+            (sign, sum - f)
+        } else {
+            (F::zero(), sum)
+        }
+    } else if a >= b {
+        (F::zero(), a - b)
+    } else if &f + &a >= b {
+        (sign, f + a - b)
+    } else {
+        return None;
+    };
+    if result.bits() > BINARY_MODULUS_EXP as u64 {
+        return None;
+    }
+    let result = ForeignElement::<F, 3>::from_biguint(result).limbs;
+
+    let carry = (compact_limb(&left[LO], &left[MI]) + compact_limb(&right[LO], &right[MI]) * sign
+        - compact_limb(&modulus[LO], &modulus[MI]) * field_overflow
+        - compact_limb(&result[LO], &result[MI]))
+        / F::two_to_2limb();
+    Some((field_overflow, carry, result))
+}
+
+/// The cells of the limbs of the inputs and of the result of an operation of a chain
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct ChainOpCells {
+    /// the `ForeignFieldAdd` row of the operation
+    pub row: usize,
+    /// the limbs of the left input
+    pub left: [Wire; 3],
+    /// the limbs of the right input (the left input for a doubling, whose right input cells are unused)
+    pub right: [Wire; 3],
+    /// the limbs of the result, which are the left input of the next row
+    pub result: [Wire; 3],
+}
+
+/// The witness of a chain of foreign field additions, with the cells of its intermediate results.
+/// The rows are relative to the first row of the chain.
+#[derive(Debug, Clone)]
+pub struct ChainWitness<F> {
+    /// the witness of the chain
+    pub witness: [Vec<F>; COLUMNS],
+    /// the cells of each operation, in the order of the operations
+    pub ops: Vec<ChainOpCells>,
+    /// the `ForeignFieldAdd` row of the final bound addition,
+    /// whose left input is the result of the chain
+    pub bound_row: usize,
+    /// the limbs of the bound, on the `Zero` row after the bound addition
+    pub bound: [Wire; 3],
+}
+
+impl<F: PrimeField> ChainWitness<F> {
+    /// The values of the limbs of some cells of the witness
+    pub fn limbs(&self, cells: [Wire; 3]) -> [F; 3] {
+        cells.map(|cell| self.witness[cell.col][cell.row])
+    }
+
+    /// The cells of the result of the chain
+    pub fn result(&self) -> [Wire; 3] {
+        limb_cells(self.bound_row, 0)
+    }
+}
+
+// The cells of the 3 limbs starting at a cell
+pub(super) fn limb_cells(row: usize, col: usize) -> [Wire; 3] {
+    array::from_fn(|i| Wire { row, col: col + i })
+}
+
 /// Creates a FFAdd witness (including `ForeignFieldAdd` rows, and one final `ForeignFieldAdd` row for bound)
-/// inputs: list of all inputs to the chain of additions/subtractions
+/// inputs: list of all inputs to the chain of additions/subtractions,
+///         the first left input followed by the right input of each operation other than a doubling
 /// opcode: true for addition, false for subtraction
 /// modulus: modulus of the foreign field
 pub fn create_chain<F: PrimeField>(
@@ -131,6 +250,16 @@ pub fn create_chain<F: PrimeField>(
     opcodes: &[FFOps],
     modulus: BigUint,
 ) -> [Vec<F>; COLUMNS] {
+    create_chain_witness(inputs, opcodes, modulus).witness
+}
+
+/// Creates the witness of a chain of foreign field additions as [create_chain] does,
+/// with the cells of the result of each operation and of the final bound check
+pub fn create_chain_witness<F: PrimeField>(
+    inputs: &Vec<BigUint>,
+    opcodes: &[FFOps],
+    modulus: BigUint,
+) -> ChainWitness<F> {
     if modulus > BigUint::max_foreign_field_modulus::<F>() {
         panic!(
             "foreign_field_modulus exceeds maximum: {} > {}",
@@ -139,10 +268,11 @@ pub fn create_chain<F: PrimeField>(
         );
     }
 
-    let num = inputs.len() - 1; // number of chained additions
-
-    // make sure there are as many operands as operations
-    assert_eq!(opcodes.len(), num);
+    // make sure there are as many right operands as operations reading one
+    assert_eq!(
+        inputs.len() - 1,
+        opcodes.iter().filter(|op| **op != FFOps::Double).count()
+    );
 
     // Make sure that the inputs are smaller than the modulus just in case
     let inputs: Vec<BigUint> = inputs.iter().map(|input| input % modulus.clone()).collect();
@@ -152,29 +282,40 @@ pub fn create_chain<F: PrimeField>(
     let foreign_modulus = ForeignElement::from_biguint(modulus);
 
     let mut left = ForeignElement::from_biguint(inputs[0].clone());
+    let mut rights = inputs[1..].iter();
 
-    for i in 0..num {
+    for (i, opcode) in opcodes.iter().enumerate() {
         // Create foreign field addition row
         for w in &mut witness {
             w.extend(std::iter::repeat(F::zero()).take(1));
         }
-        let right = ForeignElement::from_biguint(inputs[i + 1].clone());
+        // a doubling is the addition of the left input to itself, whose right input cells are unused
+        let (right, opcode) = match opcode {
+            FFOps::Double => (left.to_biguint(), FFOps::Add),
+            _ => (rights.next().unwrap().clone(), *opcode),
+        };
+        let right = ForeignElement::from_biguint(right);
         let (output, _sign, ovf, carry) =
-            compute_ffadd_values(&left, &right, opcodes[i], &foreign_modulus);
-        init_ffadd_row(
-            &mut witness,
-            i,
-            left.limbs,
-            [right[LO], right[MI], right[HI]],
-            ovf,
-            carry,
-        );
+            compute_ffadd_values(&left, &right, opcode, &foreign_modulus);
+        let right_cells = if opcodes[i] == FFOps::Double {
+            [F::zero(); 3]
+        } else {
+            [right[LO], right[MI], right[HI]]
+        };
+        init_ffadd_row(&mut witness, i, left.limbs, right_cells, ovf, carry);
         left = output; // output is next left input
     }
 
     extend_witness_bound_addition(&mut witness, &left.limbs, &foreign_modulus.limbs);
 
-    witness
+    // the results are on the rows after the operations, and the bound after the bound addition
+    let cells = ChainCells::new(0, opcodes);
+    ChainWitness {
+        witness,
+        ops: cells.ops,
+        bound_row: cells.bound_row,
+        bound: cells.bound,
+    }
 }
 
 fn init_ffadd_row<F: PrimeField>(
@@ -39,8 +39,9 @@ use crate::circuits::{
     gate::{CircuitGate, GateType},
     polynomial::COLUMNS,
     wires::GateWires,
+    witness::{Solve, SolverEnv},
 };
-use crate::{curve::KimchiCurve, prover_index::ProverIndex};
+use crate::{curve::KimchiCurve, error::SolveError, prover_index::ProverIndex};
 use ark_ff::{FftField, PrimeField, Zero};
 use ark_poly::univariate::DensePolynomial;
 use std::array;
@@ -111,6 +112,51 @@ where
     }
 }
 
+impl<F: PrimeField> Solve<F> for Generic<F> {
+    // Each generic gate computes the register of its equation which is unknown,
+    // if it is the only one
+    fn solve(env: &mut SolverEnv<F>, coeffs: &[F]) -> Result<bool, SolveError> {
+        let mut solved = true;
+        for half in 0..2 {
+            let [l, r, o, m, c]: [F; GENERIC_COEFFS] = array::from_fn(|i| {
+                coeffs
+                    .get(half * GENERIC_COEFFS + i)
+                    .copied()
+                    .unwrap_or_else(F::zero)
+            });
+            let cols: [usize; GENERIC_REGISTERS] = array::from_fn(|i| half * GENERIC_REGISTERS + i);
+            // the registers which are not in the equation do not need a value
+            let used = [
+                !l.is_zero() || !m.is_zero(),
+                !r.is_zero() || !m.is_zero(),
+                !o.is_zero(),
+            ];
+            let values: [Option<F>; GENERIC_REGISTERS] = array::from_fn(|i| {
+                if used[i] {
+                    env.curr(cols[i])
+                } else {
+                    Some(F::zero())
+                }
+            });
+            let computed = match values {
+                [Some(a), Some(b), None] => Some((2, -(l * a + r * b + m * a * b + c) / o)),
+                [None, Some(b), Some(out)] => (l + m * b)
+                    .inverse()
+                    .map(|inv| (0, -(r * b + o * out + c) * inv)),
+                [Some(a), None, Some(out)] => (r + m * a)
+                    .inverse()
+                    .map(|inv| (1, -(l * a + o * out + c) * inv)),
+                _ => None,
+            };
+            if let Some((i, value)) = computed {
+                env.set_curr(cols[i], value)?;
+            }
+            solved &= (0..GENERIC_REGISTERS).all(|i| !used[i] || env.curr(cols[i]).is_some());
+        }
+        Ok(solved)
+    }
+}
+
 /// The different type of computation that are possible with a generic gate.
 /// This type is useful to create a generic gate via the [`CircuitGate::create_generic_gadget`] function.
 pub enum GenericGateSpec<F> {
@@ -132,6 +178,8 @@ pub enum GenericGateSpec<F> {
     },
     /// A constant, the constructor contains the constant itself
     Const(F),
+    /// A nonzero value, whose inverse is the right operand
+    NonZero,
     /// A public gate
     Pub,
 }
@@ -171,6 +219,10 @@ impl<F: PrimeField> CircuitGate<F> {
                 coeffs[0] = F::one();
                 coeffs[4] = -cst;
             }
+            GenericGateSpec::NonZero => {
+                coeffs[3] = F::one();
+                coeffs[4] = -F::one();
+            }
             GenericGateSpec::Pub => {
                 coeffs[0] = F::one();
             }
@@ -196,6 +248,10 @@ impl<F: PrimeField> CircuitGate<F> {
                 coeffs[5] = F::one();
                 coeffs[9] = -cst;
             }
+            Some(GenericGateSpec::NonZero) => {
+                coeffs[8] = F::one();
+                coeffs[9] = -F::one();
+            }
             Some(GenericGateSpec::Pub) => {
                 coeffs[5] = F::one();
                 unimplemented!();
@@ -0,0 +1,526 @@
+//! This module includes the hash-to-curve gadget over a foreign curve, with the simplified SWU map
+//! of RFC 9380 (such as the suites `P256_XMD:SHA-256_SSWU_RO_` and `secp256k1_XMD:SHA-256_SSWU_RO_`),
+//! and its witness code generation.
+//! Note that this module does not include a new gate type, the gadget reuses the `Generic` gate,
+//! and the range check and foreign field gates of the ECDSA layout.
+
+//~ The hash-to-curve gadget computes the point $P = M(u_0) + M(u_1)$ of a foreign curve
+//~ $y^2 = x^3 + a x + b$ over $\mathbb{F}_p$ of prime order (such as P-256 or secp256k1),
+//~ where $M$ is the simplified SWU map of RFC 9380 of a non-square constant $Z$,
+//~ so that a circuit hashes messages to points (for the BLS signatures or the VRFs).
+//~ The simplified SWU map needs $a b \neq 0$: for a curve with $a = 0$ (such as secp256k1),
+//~ $M$ is the simplified SWU map to an isogenous curve $E'$ of $a' b' \neq 0$, followed by the isogeny
+//~ $$(x', y') \mapsto \left(\frac{x_{num}(x')}{x_{den}(x')}, y' \frac{y_{num}(x')}{y_{den}(x')}\right)$$
+//~ to the curve, of the constants of RFC 9380 (the 3-isogeny of secp256k1).
+//~ The field elements $u_0$ and $u_1$ are the output of the `hash_to_field` of the message,
+//~ which is computed out of the circuit with `expand_message_xmd` and SHA-256 (there is no SHA-256 gadget),
+//~ and the gadget then follows the random oracle construction `hash_to_curve` of RFC 9380
+//~ (whose cofactor clearing is the identity for a curve of prime order).
+//~
+//~ The elements $u_0$, $u_1$ and the coordinates $x, y$ of $P$ are the first 4 elements of the gadget,
+//~ canonical elements of the ECDSA layout at the positions given by `hash_to_curve_limb`.
+//~ For each element $u$, the gadget lays out the map with the foreign field gates of the layout:
+//~
+//~ * $t = Z^2 u^4 + Z u^2$, which has an inverse,
+//~ * $x_1 = (-b / a) (1 + 1 / t)$ and $x_2 = Z u^2 x_1$,
+//~ * the x-coordinate $x = s (x_1 - x_2) + x_2$ for a boolean $s$ of the witness,
+//~ * the y-coordinate $y$, an element of the witness, with the check $y^2 = x^3 + a x + b$,
+//~ * the sign check: the least significant bits of the lowest limbs of $u$ and $y$ are equal,
+//~   since $u, y < p$ and $p$ is odd,
+//~ * with an isogeny, its polynomials evaluated with the Horner rule, and the divisions by
+//~   $x_{den}(x')$ and $y_{den}(x')$, which are nonzero on $E'$: for the 3-isogeny of secp256k1,
+//~   they are powers of $x' - x_0$, where $x_0$ is the x-coordinate of the points of order 3 of the kernel,
+//~   which are not points of $E'$ of prime order.
+//~
+//~ With an isogeny, $x_1$, $x_2$ and $y$ are the coordinates of points of $E'$, of the coefficients $a', b'$.
+//~ Since $g(x_2) = Z^3 u^6 g(x_1)$ for $g(x) = x^3 + a x + b$, and $Z$ is not a square, exactly one of
+//~ $g(x_1)$ and $g(x_2)$ is a square (as $g$ has no root on a curve of prime order),
+//~ so that the boolean $s$ and the point are unique. Finally, the gadget adds $M(u_0)$ and $M(u_1)$
+//~ with the incomplete addition of the layout, and copies the sum to $x, y$.
+//~
+//~ The exceptional case $t = 0$ of the map, and the sum of points of the same x-coordinate,
+//~ only happen with a negligible probability for the output of `hash_to_field`, and have no witness.
+//~
+
+use crate::circuits::{gate::CircuitGate, polynomial::COLUMNS};
+use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use sha2::{Digest, Sha256};
+use std::array;
+
+use super::{
+    ecdsa::{
+        curve::{from_hex, inverse, ForeignCurve, ForeignPoint},
+        layout::{Elem, Layout, Point},
+        ELEM_ROWS,
+    },
+    schnorr::layout_lowest_bit,
+};
+
+// The number of bytes of SHA-256 and of its blocks, for expand_message_xmd
+const SHA256_BYTES: usize = 32;
+const SHA256_BLOCK_BYTES: usize = 64;
+
+// The security parameter of hash_to_field, in bits
+const SECURITY_BITS: u64 = 128;
+
+/// The elements of the hash-to-curve gadget, in their order
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashToCurveValue {
+    /// The first element of the output of `hash_to_field`
+    U0,
+    /// The second element of the output of `hash_to_field`
+    U1,
+    /// The x-coordinate of the point, the output
+    ResultX,
+    /// The y-coordinate of the point, the output
+    ResultY,
+}
+
+/// Returns the position of the cell containing the `limb`-th limb (from the least significant one)
+/// of an element of the hash-to-curve gadget starting at row `new_row`, as a `(row, column)` pair
+pub fn hash_to_curve_limb(new_row: usize, value: HashToCurveValue, limb: usize) -> (usize, usize) {
+    assert!(limb < 3, "An element only has three limbs");
+    (new_row + ELEM_ROWS * value as usize + limb, 0)
+}
+
+// Checks whether an element is a square modulo the prime p, with the Euler criterion
+fn is_square(x: &BigUint, p: &BigUint) -> bool {
+    x.modpow(&((p - 1u32) >> 1), p) != p - 1u32
+}
+
+// Evaluates a polynomial of coefficients from the constant one modulo the prime p
+fn evaluate(coeffs: &[BigUint], x: &BigUint, p: &BigUint) -> BigUint {
+    coeffs
+        .iter()
+        .rev()
+        .fold(BigUint::zero(), |acc, coeff| (acc * x + coeff) % p)
+}
+
+/// An isogeny from a curve E', the domain of the simplified SWU map, to the curve of a hash-to-curve,
+/// given by `x = x_num(x') / x_den(x')` and `y = y' y_num(x') / y_den(x')` for a point `(x', y')` of E',
+/// as the isogeny maps of RFC 9380
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Isogeny {
+    /// The curve E' of the domain, with the coefficients `a' b' != 0`
+    pub domain: ForeignCurve,
+    /// The coefficients of the numerator of the x-coordinate, from the constant one
+    pub x_num: Vec<BigUint>,
+    /// The coefficients of the denominator of the x-coordinate, from the constant one
+    pub x_den: Vec<BigUint>,
+    /// The coefficients of the numerator of the y-coordinate, from the constant one
+    pub y_num: Vec<BigUint>,
+    /// The coefficients of the denominator of the y-coordinate, from the constant one
+    pub y_den: Vec<BigUint>,
+}
+
+impl Isogeny {
+    /// The 3-isogeny of the suite `secp256k1_XMD:SHA-256_SSWU_RO_` to secp256k1,
+    /// from the curve E' of `a' = 0x3f87...4533` and `b' = 1771`, whose generator is its offset point
+    pub fn secp256k1() -> Self {
+        let secp256k1 = ForeignCurve::secp256k1();
+        let mut domain = ForeignCurve {
+            a: from_hex("3f8731abdd661adca08a5558f0f5d272e953d363cb6f0e5d405447c01a444533"),
+            b: BigUint::from(1771u32),
+            generator: (BigUint::zero(), BigUint::zero()),
+            ..secp256k1
+        };
+        domain.generator = domain.offset();
+        let coeffs = |hex: &[&str]| hex.iter().map(|hex| from_hex(hex)).collect();
+        Self {
+            domain,
+            x_num: coeffs(&[
+                "8e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38daaaaa8c7",
+                "07d3d4c80bc321d5b9f315cea7fd44c5d595d2fc0bf63b92dfff1044f17c6581",
+                "534c328d23f234e6e2a413deca25caece4506144037c40314ecbd0b53d9dd262",
+                "8e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38e38daaaaa88c",
+            ]),
+            x_den: coeffs(&[
+                "d35771193d94918a9ca34ccbb7b640dd86cd409542f8487d9fe6b745781eb49b",
+                "edadc6f64383dc1df7c4b2d51b54225406d36b641f5e41bbc52a56612a8c6d14",
+                "1",
+            ]),
+            y_num: coeffs(&[
+                "4bda12f684bda12f684bda12f684bda12f684bda12f684bda12f684b8e38e23c",
+                "c75e0c32d5cb7c0fa9d0a54b12a0a6d5647ab046d686da6fdffc90fc201d71a3",
+                "29a6194691f91a73715209ef6512e576722830a201be2018a765e85a9ecee931",
+                "2f684bda12f684bda12f684bda12f684bda12f684bda12f684bda12f38e38d84",
+            ]),
+            y_den: coeffs(&[
+                "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffff93b",
+                "7a06534bb8bdb49fd5e9e6632722c2989467c1bfc8e8d978dfb425d2685c2573",
+                "6484aa716545ca2cf3a70c3fa8fe337e0a3d21162f0d6299a7bf8192bfd2a76f",
+                "1",
+            ]),
+        }
+    }
+
+    /// Maps a point of E' to a point of the curve
+    pub fn map(&self, (x, y): &ForeignPoint) -> ForeignPoint {
+        let p = &self.domain.modulus;
+        let frac = |num: &[BigUint], den: &[BigUint]| {
+            evaluate(num, x, p) * inverse(&evaluate(den, x, p), p) % p
+        };
+        (
+            frac(&self.x_num, &self.x_den),
+            y * frac(&self.y_num, &self.y_den) % p,
+        )
+    }
+}
+
+/// The simplified SWU map of RFC 9380 to a foreign curve `y^2 = x^3 + a x + b`, with `a b != 0`,
+/// or followed by an isogeny from a curve of `a' b' != 0`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SswuMap {
+    /// The curve of the points, of prime order and of a modulus of 3 mod 4
+    pub curve: ForeignCurve,
+    /// The non-square constant Z of the map
+    pub z: BigUint,
+    /// The isogeny to the curve, when the simplified SWU map is to an isogenous curve
+    pub isogeny: Option<Isogeny>,
+}
+
+impl SswuMap {
+    /// Creates the map to a curve with the constant `z`, as chosen by RFC 9380 for the curve
+    pub fn new(curve: ForeignCurve, z: BigUint) -> Self {
+        Self::create(curve, z, None)
+    }
+
+    /// Creates the map to an isogenous curve with the constant `z`, followed by the isogeny to a curve,
+    /// as chosen by RFC 9380 for the curve
+    pub fn with_isogeny(curve: ForeignCurve, z: BigUint, isogeny: Isogeny) -> Self {
+        assert_eq!(
+            curve.modulus, isogeny.domain.modulus,
+            "The isogeny is over the base field of the curve"
+        );
+        assert!(
+            curve.is_on_curve(&isogeny.map(&isogeny.domain.generator)),
+            "The isogeny maps the points of its domain to the curve"
+        );
+        Self::create(curve, z, Some(isogeny))
+    }
+
+    fn create(curve: ForeignCurve, z: BigUint, isogeny: Option<Isogeny>) -> Self {
+        let map = Self { curve, z, isogeny };
+        let sswu_curve = map.sswu_curve();
+        let p = &sswu_curve.modulus;
+        assert!(
+            !sswu_curve.a.is_zero() && !sswu_curve.b.is_zero(),
+            "The curve of the simplified SWU map has the coefficients a b != 0"
+        );
+        assert!(map.z < *p && !is_square(&map.z, p), "Z is not a square");
+        let exceptional = map.exceptional_x();
+        assert!(
+            sswu_curve.y_coordinate(&exceptional).is_some(),
+            "g(b / (Z a)) is a square"
+        );
+        map
+    }
+
+    /// The map to P-256 of the suite `P256_XMD:SHA-256_SSWU_RO_`, with Z = -10
+    pub fn p256() -> Self {
+        let curve = ForeignCurve::p256();
+        let z = &curve.modulus - 10u32;
+        Self::new(curve, z)
+    }
+
+    /// The map to secp256k1 of the suite `secp256k1_XMD:SHA-256_SSWU_RO_`, with Z = -11
+    /// and its 3-isogeny (see [Isogeny::secp256k1])
+    pub fn secp256k1() -> Self {
+        let curve = ForeignCurve::secp256k1();
+        let z = &curve.modulus - 11u32;
+        Self::with_isogeny(curve, z, Isogeny::secp256k1())
+    }
+
+    /// The curve of the simplified SWU map, the domain of the isogeny if any
+    pub fn sswu_curve(&self) -> &ForeignCurve {
+        self.isogeny
+            .as_ref()
+            .map_or(&self.curve, |isogeny| &isogeny.domain)
+    }
+
+    // The constant -b / a
+    fn c1(&self) -> BigUint {
+        let curve = self.sswu_curve();
+        let p = &curve.modulus;
+        (p - &curve.b) * inverse(&curve.a, p) % p
+    }
+
+    // The x-coordinate b / (Z a) of the exceptional case t = 0
+    fn exceptional_x(&self) -> BigUint {
+        let curve = self.sswu_curve();
+        let p = &curve.modulus;
+        &curve.b * inverse(&(&self.z * &curve.a % p), p) % p
+    }
+
+    // The element t = Z^2 u^4 + Z u^2, and Z u^2
+    fn t(&self, u: &BigUint) -> (BigUint, BigUint) {
+        let p = &self.curve.modulus;
+        let zu2 = &self.z * u * u % p;
+        ((&zu2 * &zu2 + &zu2) % p, zu2)
+    }
+
+    // Returns the point of u on the curve of the simplified SWU map, before the isogeny,
+    // and whether g(x_1) is a square, the choice of x_1 rather than x_2
+    fn map_with_choice(&self, u: &BigUint) -> (ForeignPoint, bool) {
+        let curve = self.sswu_curve();
+        let p = &curve.modulus;
+        let (t, zu2) = self.t(u);
+        let x1 = if t.is_zero() {
+            self.exceptional_x()
+        } else {
+            self.c1() * (BigUint::one() + inverse(&t, p)) % p
+        };
+        let (x, y, square) = match curve.y_coordinate(&x1) {
+            Some(y) => (x1, y, true),
+            None => {
+                let x2 = zu2 * x1 % p;
+                let y = curve
+                    .y_coordinate(&x2)
+                    .expect("g(x_2) is a square when g(x_1) is not");
+                (x2, y, false)
+            }
+        };
+        // the sign of y is the sign of u
+        let y = if y.bit(0) == u.bit(0) { y } else { p - y };
+        ((x, y), square)
+    }
+
+    /// Maps an element of the base field to a point of the curve, as `map_to_curve` of RFC 9380
+    pub fn map_to_curve(&self, u: &BigUint) -> ForeignPoint {
+        let (point, _) = self.map_with_choice(u);
+        match &self.isogeny {
+            Some(isogeny) => isogeny.map(&point),
+            None => point,
+        }
+    }
+
+    /// Hashes two elements of the base field to a point of the curve, as the sum of their maps,
+    /// or returns `None` for the point at infinity
+    pub fn hash_field_to_curve(&self, u: &[BigUint; 2]) -> Option<ForeignPoint> {
+        let [q0, q1] = u.clone().map(|u| Some(self.map_to_curve(&u)));
+        self.curve.add(&q0, &q1)
+    }
+}
+
+/// Expands a message into `len` bytes with a domain separation tag, with `expand_message_xmd`
+/// of RFC 9380 and SHA-256
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len: usize) -> Vec<u8> {
+    let blocks = (len + SHA256_BYTES - 1) / SHA256_BYTES;
+    assert!(
+        blocks <= 255 && len <= 0xffff && dst.len() <= 255,
+        "The output and the tag are not too long"
+    );
+    let dst_prime = [dst, &[dst.len() as u8]].concat();
+    let b0 = Sha256::new()
+        .chain_update([0u8; SHA256_BLOCK_BYTES])
+        .chain_update(msg)
+        .chain_update((len as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst_prime)
+        .finalize();
+    let mut bi = Sha256::new()
+        .chain_update(b0)
+        .chain_update([1u8])
+        .chain_update(&dst_prime)
+        .finalize();
+    let mut bytes = bi.to_vec();
+    for i in 2..=blocks {
+        let xor: Vec<u8> = b0.iter().zip(bi.iter()).map(|(b0, bi)| b0 ^ bi).collect();
+        bi = Sha256::new()
+            .chain_update(xor)
+            .chain_update([i as u8])
+            .chain_update(&dst_prime)
+            .finalize();
+        bytes.extend_from_slice(&bi);
+    }
+    bytes.truncate(len);
+    bytes
+}
+
+/// Hashes a message with a domain separation tag to two elements of the base field of a curve,
+/// as `hash_to_field` of RFC 9380 with `expand_message_xmd` and SHA-256
+pub fn hash_to_field(curve: &ForeignCurve, msg: &[u8], dst: &[u8]) -> [BigUint; 2] {
+    let p = &curve.modulus;
+    let elem_len = ((p.bits() + SECURITY_BITS + 7) / 8) as usize;
+    let bytes = expand_message_xmd(msg, dst, 2 * elem_len);
+    array::from_fn(|i| BigUint::from_bytes_be(&bytes[i * elem_len..(i + 1) * elem_len]) % p)
+}
+
+/// Hashes a message with a domain separation tag to a point of the curve of the map,
+/// as `hash_to_curve` of RFC 9380, or returns `None` for the point at infinity
+pub fn hash_to_curve(map: &SswuMap, msg: &[u8], dst: &[u8]) -> Option<ForeignPoint> {
+    map.hash_field_to_curve(&hash_to_field(&map.curve, msg, dst))
+}
+
+// Lays out the evaluation of a polynomial of degree at least 1, of coefficients from the constant one,
+// with the Horner rule
+fn layout_polynomial<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    coeffs: &[BigUint],
+    x: &Elem<F>,
+    p: &BigUint,
+) -> Elem<F> {
+    let (leading, coeffs) = coeffs
+        .split_last()
+        .expect("the polynomial has a leading coefficient");
+    let mut acc = if leading.is_one() {
+        x.clone()
+    } else {
+        let leading = layout.constant(leading);
+        layout.mul(&leading, x, p)
+    };
+    for (i, coeff) in coeffs.iter().enumerate().rev() {
+        let coeff = layout.constant(coeff);
+        acc = layout.add(&acc, &coeff, p);
+        if i > 0 {
+            acc = layout.mul(&acc, x, p);
+        }
+    }
+    acc
+}
+
+// Lays out the isogeny of a point of its domain
+fn layout_isogeny<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    isogeny: &Isogeny,
+    point: &Point<F>,
+) -> Point<F> {
+    let p = &isogeny.domain.modulus;
+    let x_num = layout_polynomial(layout, &isogeny.x_num, &point.x, p);
+    let x_den = layout_polynomial(layout, &isogeny.x_den, &point.x, p);
+    let y_num = layout_polynomial(layout, &isogeny.y_num, &point.x, p);
+    let y_den = layout_polynomial(layout, &isogeny.y_den, &point.x, p);
+    // the denominators are nonzero on the points of the domain (see the module)
+    let y_frac = layout.div(&y_num, &y_den, p);
+    Point {
+        x: layout.div(&x_num, &x_den, p),
+        y: layout.mul(&point.y, &y_frac, p),
+    }
+}
+
+// Lays out the map of an element of the layout to a point
+fn layout_map_to_curve<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    map: &SswuMap,
+    u: &Elem<F>,
+) -> Point<F> {
+    let curve = map.sswu_curve();
+    let p = &curve.modulus;
+    let choice = u.value.as_ref().map(|u| map.map_with_choice(u));
+
+    // t = Z^2 u^4 + Z u^2
+    let z = layout.constant(&map.z);
+    let u2 = layout.mul(u, u, p);
+    let zu2 = layout.mul(&z, &u2, p);
+    let zu2_2 = layout.mul(&zu2, &zu2, p);
+    let t = layout.add(&zu2_2, &zu2, p);
+
+    // x_1 = (-b / a) (1 + 1 / t) and x_2 = Z u^2 x_1
+    let t_inv = layout.inverse(&t, p);
+    let one = layout.constant(&BigUint::one());
+    let sum = layout.add(&one, &t_inv, p);
+    let c1 = layout.constant(&map.c1());
+    let x1 = layout.mul(&c1, &sum, p);
+    let x2 = layout.mul(&zu2, &x1, p);
+
+    let square = layout.bit(choice.as_ref().map(|(_, square)| *square));
+    let point = Point {
+        x: layout.select(&square, &x1, &x2),
+        y: layout.witness(choice.map(|((_, y), _)| y), p),
+    };
+    layout.assert_on_curve(curve, &point);
+
+    // the sign of y is the sign of u
+    let sign_u = layout_lowest_bit(layout, u);
+    let sign_y = layout_lowest_bit(layout, &point.y);
+    layout.connect(sign_u.cell, sign_y.cell);
+    match &map.isogeny {
+        Some(isogeny) => layout_isogeny(layout, isogeny, &point),
+        None => point,
+    }
+}
+
+// Lays out the hash of two elements to a point, with the values of the elements and of the point for the witness
+fn layout_hash_to_curve<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    map: &SswuMap,
+    values: Option<[BigUint; 4]>,
+) {
+    let p = &map.curve.modulus;
+    let [u0, u1, x, y]: [Elem<F>; 4] =
+        array::from_fn(|i| layout.witness(values.as_ref().map(|values| values[i].clone()), p));
+    let q0 = layout_map_to_curve(layout, map, &u0);
+    let q1 = layout_map_to_curve(layout, map, &u1);
+    let sum = layout.add_points(&map.curve, &q0, &q1);
+    layout.assert_eq(&sum.x, &x);
+    layout.assert_eq(&sum.y, &y);
+}
+
+impl<F: PrimeField + SquareRootField> CircuitGate<F> {
+    /// Extends a circuit with the hash of two elements of the base field to a point of a foreign curve
+    /// Includes:
+    /// - the foreign field gates, range checks and generic gates of the maps and of the addition (see the module)
+    /// Input:
+    /// - gates : the full circuit
+    /// - map   : the simplified SWU map to the foreign curve
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the elements (see [hash_to_curve_limb]) should be copied from other cells,
+    ///   and the point copied to the cells which use it
+    pub fn extend_hash_to_curve(gates: &mut Vec<Self>, map: &SswuMap) -> usize {
+        let (next_row, mut hash_gates) = Self::create_hash_to_curve(gates.len(), map);
+        gates.append(&mut hash_gates);
+        next_row
+    }
+
+    /// Creates the hash of two elements of the base field to a point of a foreign curve,
+    /// see [Self::extend_hash_to_curve]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_hash_to_curve(new_row: usize, map: &SswuMap) -> (usize, Vec<Self>) {
+        let mut layout = Layout::new(new_row, false);
+        layout_hash_to_curve(&mut layout, map, None);
+        let (hash_gates, _) = layout.finalize();
+        (new_row + hash_gates.len(), hash_gates)
+    }
+}
+
+/// Extends the witness with the hash of two elements of the base field to a point of a foreign curve,
+/// and returns the point
+/// Input
+/// - witness: full witness of the circuit
+/// - map: the simplified SWU map to the foreign curve
+/// - u: the elements, the output of [hash_to_field], smaller than the base field modulus
+/// Panics if an element is an exceptional case of the map, or if the points of the elements
+/// have the same x-coordinate (which only happens with a negligible probability)
+pub fn extend_hash_to_curve_witness<F: PrimeField + SquareRootField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    map: &SswuMap,
+    u: &[BigUint; 2],
+) -> ForeignPoint {
+    let p = &map.curve.modulus;
+    assert!(
+        u.iter().all(|u| u < p && !map.t(u).0.is_zero()),
+        "The elements are smaller than the modulus, and are not exceptional cases of the map"
+    );
+    let [q0, q1] = u.clone().map(|u| map.map_to_curve(&u));
+    assert_ne!(q0.0, q1.0, "The points have distinct x-coordinates");
+    let (x, y) = map
+        .curve
+        .add(&Some(q0), &Some(q1))
+        .expect("the sum of points of distinct x-coordinates is not the point at infinity");
+
+    let [u0, u1] = u.clone();
+    let mut layout = Layout::new(witness[0].len(), true);
+    layout_hash_to_curve(&mut layout, map, Some([u0, u1, x.clone(), y.clone()]));
+    let (_, hash_witness) = layout.finalize();
+    let hash_witness = hash_witness.expect("the witness is laid out");
+    for (col, cells) in witness.iter_mut().zip(hash_witness) {
+        col.extend(cells);
+    }
+    (x, y)
+}
@@ -7,12 +7,16 @@ use crate::circuits::{
     gate::{CircuitGate, Connect},
     polynomial::COLUMNS,
     polynomials::{
-        generic::GenericGateSpec,
+        generic::{GenericGateSpec, GENERIC_REGISTERS},
         rot::{self, RotMode},
+        xor,
     },
     wires::Wire,
 };
 
+/// A 5x5 table of rotation offsets, indexed by the `x` and `y` coordinates of the lanes
+pub type RotTable = [[u32; 5]; 5];
+
 /// Creates the 5x5 table of rotation bits for Keccak modulo 64
 /// | x \ y |  0 |  1 |  2 |  3 |  4 |
 /// | ----- | -- | -- | -- | -- | -- |
@@ -21,7 +25,7 @@ use crate::circuits::{
 /// | 2     | 62 |  6 | 43 | 15 | 61 |
 /// | 3     | 28 | 55 | 25 | 21 | 56 |
 /// | 4     | 27 | 20 | 39 |  8 | 14 |
-pub const ROT_TAB: [[u32; 5]; 5] = [
+pub const ROT_TAB: RotTable = [
     [0, 36, 3, 41, 18],
     [1, 44, 10, 45, 2],
     [62, 6, 43, 15, 61],
@@ -29,33 +33,208 @@ pub const ROT_TAB: [[u32; 5]; 5] = [
     [27, 20, 39, 8, 14],
 ];
 
+/// The table of rotation bits for Keccak-f\[800\], with 32-bit lanes
+pub const ROT_TAB_800: RotTable = rot_table(32);
+
+/// Creates the table of rotation bits of Keccak-f\[25 * `lane_bits`\],
+/// which are the offsets of [ROT_TAB] modulo the width of the lanes.
+/// Note: `lane_bits` should be a power of two up to 64.
+pub const fn rot_table(lane_bits: u32) -> RotTable {
+    let mut table = [[0; 5]; 5];
+    let mut x = 0;
+    while x < 5 {
+        let mut y = 0;
+        while y < 5 {
+            table[x][y] = ROT_TAB[x][y] % lane_bits;
+            y += 1;
+        }
+        x += 1;
+    }
+    table
+}
+
+/// Repeats a lane of `lane_bits` bits to fill a 64-bit word.
+/// Rotating the resulting word by `rot < lane_bits` bits yields the rotation of
+/// the lane in each of its `lane_bits` chunks, so that the 64-bit rotation gates
+/// can be used for the reduced-width variants of Keccak.
+/// Panics if `lane_bits` does not divide 64 or if `lane` is larger than `lane_bits` bits.
+pub fn spread_lane(lane: u64, lane_bits: u32) -> u64 {
+    assert!(
+        lane_bits > 0 && 64 % lane_bits == 0,
+        "Lane width must divide 64"
+    );
+    assert!(
+        lane_bits == 64 || lane < 1 << lane_bits,
+        "Lane is larger than its width"
+    );
+    (0..64 / lane_bits).fold(0, |word, i| word | lane << (i * lane_bits))
+}
+
+/// The round constants of Keccak-f\[1600\], added to the first lane by the iota step of each round
+pub const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Number of bytes absorbed by each permutation of Keccak-256
+pub const KECCAK256_RATE: usize = 136;
+
+/// Applies the Keccak-f\[1600\] permutation to a state of lanes indexed by their `x` and `y` coordinates,
+/// out of the circuit
+pub fn keccak_f1600(state: &mut [[u64; 5]; 5]) {
+    for round_constant in ROUND_CONSTANTS {
+        // theta
+        let c: [u64; 5] = array::from_fn(|x| state[x].iter().fold(0, |acc, lane| acc ^ lane));
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for lane in state[x].iter_mut() {
+                *lane ^= d;
+            }
+        }
+        // rho and pi
+        let mut b = [[0u64; 5]; 5];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y][(2 * x + 3 * y) % 5] = state[x][y].rotate_left(ROT_TAB[x][y]);
+            }
+        }
+        // chi
+        for x in 0..5 {
+            for y in 0..5 {
+                state[x][y] = b[x][y] ^ (!b[(x + 1) % 5][y] & b[(x + 2) % 5][y]);
+            }
+        }
+        // iota
+        state[0][0] ^= round_constant;
+    }
+}
+
+/// Computes the Keccak-256 hash of a message (with the original Keccak padding, as in Ethereum),
+/// out of the circuit
+pub fn keccak256(message: &[u8]) -> [u8; 32] {
+    let mut padded = message.to_vec();
+    padded.push(0x01);
+    padded.resize(
+        padded.len() + (KECCAK256_RATE - padded.len() % KECCAK256_RATE) % KECCAK256_RATE,
+        0,
+    );
+    *padded.last_mut().expect("the padding is not empty") |= 0x80;
+
+    // the bytes of the lanes are in little-endian order, and the lane `i` is at `x = i mod 5, y = i / 5`
+    let mut state = [[0u64; 5]; 5];
+    for block in padded.chunks(KECCAK256_RATE) {
+        for (i, lane) in block.chunks(8).enumerate() {
+            state[i % 5][i / 5] ^= u64::from_le_bytes(lane.try_into().expect("a lane has 8 bytes"));
+        }
+        keccak_f1600(&mut state);
+    }
+    let mut hash = [0u8; 32];
+    for (i, bytes) in hash.chunks_mut(8).enumerate() {
+        bytes.copy_from_slice(&state[i % 5][i / 5].to_le_bytes());
+    }
+    hash
+}
+
 impl<F: PrimeField + SquareRootField> CircuitGate<F> {
     /// Creates Keccak gadget.
     /// Right now it only creates an initial generic gate with all zeros starting on `new_row` and then
     /// calls the Keccak rotation gadget
     pub fn create_keccak(new_row: usize) -> (usize, Vec<Self>) {
+        Self::create_keccak_with_table(new_row, &ROT_TAB)
+    }
+
+    /// Creates Keccak gadget for a given table of rotation offsets, see [Self::create_keccak]
+    pub fn create_keccak_with_table(new_row: usize, rot_tab: &RotTable) -> (usize, Vec<Self>) {
         // Initial Generic gate to constrain the prefix of the output to be zero
         let mut gates = vec![CircuitGate::<F>::create_generic_gadget(
             Wire::for_row(new_row),
             GenericGateSpec::Pub,
             None,
         )];
-        Self::create_keccak_rot(&mut gates, new_row + 1, new_row)
+        Self::create_keccak_rot(&mut gates, new_row + 1, new_row, rot_tab)
+    }
+
+    /// Creates the iota step of the round `round` of Keccak-f\[1600\], the XOR of a lane with the round constant
+    /// Includes:
+    /// - 4 Xor16 gates for the XOR of the lane with the round constant
+    /// - 1 Generic gate constraining the final row of the XOR to zero with itself,
+    ///   and the round constant to the one of its coefficients
+    /// Input:
+    /// - new_row : row to start the iota step
+    /// - round   : the round, whose constant is in [ROUND_CONSTANTS]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    /// Warning:
+    /// - the lane is in column 0 of the first row, and should come from the copy of another cell
+    ///   so it is intrinsic that it is 64-bits length,
+    /// - the output is in column 2 of the first row
+    /// Panics if `round` is not smaller than 24
+    pub fn create_keccak_iota(new_row: usize, round: usize) -> (usize, Vec<Self>) {
+        let round_constant = ROUND_CONSTANTS[round];
+        let (next_row, mut gates) = Self::create_xor_gadget(new_row, 64);
+        // the final row checks fin_in1, fin_in2, fin_out are zero and that the round constant is l - rc = 0
+        let zero_row = gates.len() - 1;
+        gates[zero_row] = CircuitGate::create_generic_gadget(
+            Wire::for_row(new_row + zero_row),
+            GenericGateSpec::Const(F::zero()),
+            Some(GenericGateSpec::Const(F::from(round_constant))),
+        );
+        // copy the cells (indices are relative to the gadget, wires are not)
+        gates.connect_cell_pair((zero_row, 0), (zero_row, 1));
+        gates.connect_cell_pair((zero_row, 0), (zero_row, 2));
+        gates.connect_cell_pair((0, 1), (zero_row, GENERIC_REGISTERS));
+        (next_row, gates)
     }
 
-    /// Creates Keccak rotation gates for the whole table (skipping the rotation by 0)
+    /// Extends the iota step of the round `round` of Keccak-f\[1600\], see [Self::create_keccak_iota]
+    /// Output:
+    /// - new row index
+    pub fn extend_keccak_iota(gates: &mut Vec<Self>, round: usize) -> usize {
+        let (next_row, mut iota_gates) = Self::create_keccak_iota(gates.len(), round);
+        gates.append(&mut iota_gates);
+        next_row
+    }
+
+    /// Creates Keccak rotation gates for the whole table `rot_tab` (skipping the rotations by 0),
+    /// for example [ROT_TAB] for Keccak-f\[1600\] or [ROT_TAB_800] for Keccak-f\[800\].
+    /// Panics if the table contains an offset larger than 63.
     pub fn create_keccak_rot(
         gates: &mut Vec<Self>,
         new_row: usize,
         zero_row: usize,
+        rot_tab: &RotTable,
     ) -> (usize, Vec<Self>) {
         let mut rot_row = new_row;
-        for row in ROT_TAB {
+        for &row in rot_tab {
             for rot in row {
                 // if rotation by 0 bits, no need to create a gate for it
                 if rot == 0 {
                     continue;
                 }
+                assert!(rot < 64, "Rotation value must be less than 64");
                 let mut rot64_gates = Self::create_rot64(rot_row, rot);
                 rot_row += rot64_gates.len();
                 // Append them to the full gates vector
@@ -69,11 +248,17 @@ impl<F: PrimeField + SquareRootField> CircuitGate<F> {
 }
 
 /// Create a Keccak rotation (whole table)
-/// Input: state (5x5) array of words to be rotated
-pub fn create_witness_keccak_rot<F: PrimeField>(state: [[u64; 5]; 5]) -> [Vec<F>; COLUMNS] {
+/// Input:
+/// - state: (5x5) array of words to be rotated
+/// - rot_tab: table of rotation offsets, the same as the one of the gates
+/// Note: for reduced-width variants, the lanes of the state should be spread with [spread_lane]
+pub fn create_witness_keccak_rot<F: PrimeField>(
+    state: [[u64; 5]; 5],
+    rot_tab: &RotTable,
+) -> [Vec<F>; COLUMNS] {
     // First generic gate with all zeros to constrain that the two most significant limbs of shifted output are zeros
     let mut witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![F::zero()]);
-    for (x, row) in ROT_TAB.iter().enumerate() {
+    for (x, row) in rot_tab.iter().enumerate() {
         for (y, &rot) in row.iter().enumerate() {
             if rot == 0 {
                 continue;
@@ -83,3 +268,20 @@ pub fn create_witness_keccak_rot<F: PrimeField>(state: [[u64; 5]; 5]) -> [Vec<F>
     }
     witness
 }
+
+/// Extends the iota step of the round `round` to the full witness, see [CircuitGate::create_keccak_iota]
+/// Input
+/// - witness: full witness of the circuit
+/// - lane: the lane XORed with the round constant
+/// - round: the round, whose constant is in [ROUND_CONSTANTS]
+/// Panics if `round` is not smaller than 24
+pub fn extend_keccak_iota_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    lane: u64,
+    round: usize,
+) {
+    let round_constant = F::from(ROUND_CONSTANTS[round]);
+    xor::extend_xor_witness(witness, F::from(lane), round_constant, 64);
+    let zero_row = witness[0].len() - 1;
+    witness[GENERIC_REGISTERS][zero_row] = round_constant;
+}
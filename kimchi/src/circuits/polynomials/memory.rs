@@ -0,0 +1,482 @@
+//! This module includes the definition of the read/write memory gadget and its witness code generation.
+//! Note that this module does not include a new gate type, the gadget reuses the `Lookup`, `Generic`
+//! and `RangeCheck0` gates with three indexed runtime tables (see [memory_runtime_tables_setup]).
+//!
+//! The gadget models a zero-initialized memory accessed by a sequence of loads and stores,
+//! whose kinds are fixed by the circuit and whose addresses and values are in the witness,
+//! so that VM-style circuits (such as the Cairo circuits of turshi) can copy them from their own gates.
+//! Each access $t$ reads the value $old_t$ at the address $a_t$ and writes the value $new_t$,
+//! where a load writes back the value it reads ($new_t = old_t$, with a copy constraint).
+//!
+//! The accesses are written by the prover in three runtime tables indexed by $t$,
+//! of the addresses $t \mapsto a_t$, the old values $t \mapsto old_t$ and the new values $t \mapsto new_t$.
+//! The accesses in execution order look up their entries $(t, a_t)$, $(t, old_t)$ and $(t, new_t)$,
+//! with $t$ a constant of the circuit, so that each runtime table is the function of the accesses.
+//! The same accesses sorted by address, and then by time, also look up their entries, so that they are accesses
+//! of the execution. Then, for each pair of consecutive sorted accesses $i - 1$ and $i$,
+//! with $s$ the boolean flag of the accesses to the same address, the gadget checks that
+//!
+//! * $s \cdot (a_i - a_{i-1}) = 0$
+//! * $d = (a_i - a_{i-1}) + s \cdot (t_i - t_{i-1}) - 1$ fits in 64 bits
+//! * $old_i = s \cdot new_{i-1}$
+//!
+//! and that the first sorted access reads zero.
+//! The second check orders the accesses strictly, so that they are all distinct, and are a permutation of
+//! the accesses of the execution. The last check is the consistency of the memory:
+//! each access reads the value written by the previous access to its address, or zero for the first one.
+//! This needs $O(n)$ rows for $n$ accesses, rather than the quadratic number of constraints comparing each pair of accesses.
+//!
+//! | Row          | `CircuitGate` | Purpose                                                         |
+//! | ------------ | ------------- | --------------------------------------------------------------- |
+//! | 0            | `Generic`     | Constrain the IDs of the address and old value tables           |
+//! | 1            | `Generic`     | Constrain the ID of the new value table, and a zero cell        |
+//! | 2...         | `Generic`     | Constrain the times $t$ of the accesses, two per row            |
+//! | ...          | `Lookup`      | Look up the execution accesses, three per row and table         |
+//! | ...          | `Lookup`      | Look up the sorted accesses, three per row and table            |
+//! | ...          | `Generic`     | Check each pair of consecutive sorted accesses, in 4 rows       |
+//! | ...          | `RangeCheck0` | Check that $d$ fits in 64 bits                                  |
+//!
+//! The addresses and values of the accesses are in the cells given by [memory_address_cell] and [memory_value_cell].
+//! The addresses must be integers of up to 64 bits, the values can be any field elements.
+
+use crate::circuits::{
+    gate::{CircuitGate, Connect, GateType},
+    lookup::{
+        gadget::LOOKUPS_PER_ROW,
+        runtime_tables::{RuntimeTable, RuntimeTableCfg, RuntimeTableSpec},
+    },
+    polynomial::COLUMNS,
+    polynomials::{generic::GenericGateSpec, range_check},
+    wires::Wire,
+};
+use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::BigUint;
+use o1_utils::{field_helpers::i32_to_field, FieldHelpers};
+use std::collections::HashMap;
+
+/// Number of runtime tables of the memory gadget
+pub const MEMORY_TABLES: usize = 3;
+
+// The tables of the addresses, the old values and the new values of the accesses
+const ADDRESS: usize = 0;
+const OLD: usize = 1;
+const NEW: usize = 2;
+
+/// Number of rows checking each pair of consecutive sorted accesses
+pub const MEMORY_CHECK_ROWS: usize = 5;
+
+/// The kind of a memory access, fixed by the circuit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemoryOp {
+    /// Reads the value at an address
+    Load,
+    /// Writes a value at an address
+    Store,
+}
+
+/// A memory access of the witness
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemoryAccess<F> {
+    /// Reads the value at an address
+    Load { address: F },
+    /// Writes a value at an address
+    Store { address: F, value: F },
+}
+
+impl<F: Copy> MemoryAccess<F> {
+    /// The kind of the access
+    pub fn op(&self) -> MemoryOp {
+        match self {
+            MemoryAccess::Load { .. } => MemoryOp::Load,
+            MemoryAccess::Store { .. } => MemoryOp::Store,
+        }
+    }
+
+    /// The address of the access
+    pub fn address(&self) -> F {
+        match self {
+            MemoryAccess::Load { address } | MemoryAccess::Store { address, .. } => *address,
+        }
+    }
+}
+
+// The rows of the gadget, relatively to its first row
+struct Layout {
+    num_accesses: usize,
+}
+
+impl Layout {
+    fn create(num_accesses: usize) -> Self {
+        assert!(
+            num_accesses > 0,
+            "The memory gadget needs at least one access"
+        );
+        Layout { num_accesses }
+    }
+
+    fn key_rows(&self) -> usize {
+        (self.num_accesses + 1) / 2
+    }
+
+    fn lookup_rows(&self) -> usize {
+        (self.num_accesses + LOOKUPS_PER_ROW - 1) / LOOKUPS_PER_ROW
+    }
+
+    // The cell of the constant time of an access
+    fn key_cell(&self, t: usize) -> (usize, usize) {
+        (2 + t / 2, 3 * (t % 2))
+    }
+
+    // The lookup row of an access of the execution in a table
+    fn execution_row(&self, table: usize, t: usize) -> usize {
+        2 + self.key_rows() + MEMORY_TABLES * (t / LOOKUPS_PER_ROW) + table
+    }
+
+    // The lookup row of a sorted access in a table
+    fn sorted_row(&self, table: usize, i: usize) -> usize {
+        self.execution_row(table, i) + MEMORY_TABLES * self.lookup_rows()
+    }
+
+    // The first row of the check of the sorted access i > 0 with the previous one
+    fn check_row(&self, i: usize) -> usize {
+        2 + self.key_rows() + 2 * MEMORY_TABLES * self.lookup_rows() + MEMORY_CHECK_ROWS * (i - 1)
+    }
+
+    fn num_rows(&self) -> usize {
+        self.check_row(self.num_accesses)
+    }
+}
+
+// The columns of the time and the value of an access in its lookup rows
+fn key_col(t: usize) -> usize {
+    2 * (t % LOOKUPS_PER_ROW) + 1
+}
+
+fn value_col(t: usize) -> usize {
+    key_col(t) + 1
+}
+
+// Checks that the IDs of the runtime tables can be custom table IDs
+fn check_table_id(first_table_id: i32) {
+    assert!(
+        first_table_id > 0,
+        "The tables of the memory gadget must have positive IDs"
+    );
+}
+
+/// Returns the number of rows of the memory gadget of `num_accesses` accesses
+pub fn memory_rows(num_accesses: usize) -> usize {
+    Layout::create(num_accesses).num_rows()
+}
+
+/// Returns the position of the cell containing the address of the access `t` of a memory gadget
+/// of `num_accesses` accesses starting at row `new_row`, as a `(row, column)` pair.
+pub fn memory_address_cell(new_row: usize, num_accesses: usize, t: usize) -> (usize, usize) {
+    let layout = Layout::create(num_accesses);
+    (new_row + layout.execution_row(ADDRESS, t), value_col(t))
+}
+
+/// Returns the position of the cell containing the value stored by the access `t` of a memory gadget
+/// of `num_accesses` accesses starting at row `new_row` (or the value loaded, if it is a load),
+/// as a `(row, column)` pair.
+pub fn memory_value_cell(new_row: usize, num_accesses: usize, t: usize) -> (usize, usize) {
+    let layout = Layout::create(num_accesses);
+    (new_row + layout.execution_row(NEW, t), value_col(t))
+}
+
+/// Returns the configuration of the runtime tables of a memory gadget of `num_accesses` accesses,
+/// of IDs `first_table_id` to `first_table_id + 2`, to be given to the constraint system.
+/// Panics if `first_table_id` is not positive.
+pub fn memory_runtime_tables_setup<F>(
+    first_table_id: i32,
+    num_accesses: usize,
+) -> Vec<RuntimeTableCfg<F>> {
+    check_table_id(first_table_id);
+    (0..MEMORY_TABLES)
+        .map(|table| {
+            RuntimeTableCfg::Indexed(RuntimeTableSpec {
+                id: first_table_id + table as i32,
+                len: num_accesses,
+            })
+        })
+        .collect()
+}
+
+impl<F: PrimeField + SquareRootField> CircuitGate<F> {
+    /// Extends a circuit with a memory gadget of a sequence of loads and stores
+    /// Includes:
+    /// - 2 Generic gates to fix the IDs of the tables
+    /// - 1 Generic gate for every 2 accesses to fix their times
+    /// - 6 Lookup gates for every 3 accesses to look up the accesses and the sorted accesses
+    /// - 4 Generic gates and 1 RangeCheck0 gate for every access but the first one, to check the sorted accesses
+    /// Input:
+    /// - gates          : the full circuit
+    /// - first_table_id : the ID of the first runtime table, see [memory_runtime_tables_setup]
+    /// - ops            : the kinds of the accesses, in execution order
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the addresses and values are at the positions given by [memory_address_cell] and [memory_value_cell]
+    pub fn extend_memory(gates: &mut Vec<Self>, first_table_id: i32, ops: &[MemoryOp]) -> usize {
+        let (next_row, mut memory_gates) = Self::create_memory(gates.len(), first_table_id, ops);
+        gates.append(&mut memory_gates);
+        next_row
+    }
+
+    /// Creates a memory gadget of a sequence of loads and stores, see [Self::extend_memory]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    /// Panics if `ops` is empty, or if `first_table_id` is not positive.
+    pub fn create_memory(
+        new_row: usize,
+        first_table_id: i32,
+        ops: &[MemoryOp],
+    ) -> (usize, Vec<Self>) {
+        check_table_id(first_table_id);
+        let layout = Layout::create(ops.len());
+        let num_accesses = ops.len();
+        let table_id =
+            |table: usize| GenericGateSpec::Const(i32_to_field(first_table_id + table as i32));
+        let time = |t: usize| GenericGateSpec::Const(F::from(t as u64));
+
+        let mut memory_gates = vec![
+            CircuitGate::create_generic_gadget(
+                Wire::for_row(new_row),
+                table_id(ADDRESS),
+                Some(table_id(OLD)),
+            ),
+            CircuitGate::create_generic_gadget(
+                Wire::for_row(new_row + 1),
+                table_id(NEW),
+                Some(GenericGateSpec::Const(F::zero())),
+            ),
+        ];
+        for t in (0..num_accesses).step_by(2) {
+            let second = if t + 1 < num_accesses {
+                Some(time(t + 1))
+            } else {
+                None
+            };
+            memory_gates.push(CircuitGate::create_generic_gadget(
+                Wire::for_row(new_row + memory_gates.len()),
+                time(t),
+                second,
+            ));
+        }
+        for _ in 0..2 * MEMORY_TABLES * layout.lookup_rows() {
+            memory_gates.push(CircuitGate::new(
+                GateType::Lookup,
+                Wire::for_row(new_row + memory_gates.len()),
+                vec![],
+            ));
+        }
+
+        // a - a' - da = 0, t - t' - dt = 0
+        // s * da = 0, s * s - s = 0
+        // s * dt - m = 0, da + m - d - 1 = 0
+        // s * new' - old = 0
+        let difference = [F::one(), -F::one(), -F::one(), F::zero(), F::zero()];
+        let product = [F::zero(), F::zero(), -F::one(), F::one(), F::zero()];
+        let checks = [
+            (difference, difference),
+            (
+                [F::zero(), F::zero(), F::zero(), F::one(), F::zero()],
+                product,
+            ),
+            (
+                product,
+                [F::one(), F::one(), -F::one(), F::zero(), -F::one()],
+            ),
+            (product, [F::zero(); 5]),
+        ];
+        for _ in 1..num_accesses {
+            for (gate1, gate2) in checks {
+                let mut coeffs = [F::zero(); 10];
+                coeffs[..5].copy_from_slice(&gate1);
+                coeffs[5..].copy_from_slice(&gate2);
+                memory_gates.push(CircuitGate::create_generic(
+                    Wire::for_row(new_row + memory_gates.len()),
+                    coeffs,
+                ));
+            }
+            let mut next_row = new_row + memory_gates.len();
+            CircuitGate::extend_range_check(&mut memory_gates, &mut next_row);
+        }
+
+        // copy the IDs of the tables (indices are relative to the gadget, wires are not)
+        let id_cells = [(0, 0), (0, 3), (1, 0)];
+        for (table, id_cell) in id_cells.into_iter().enumerate() {
+            for t in (0..num_accesses).step_by(LOOKUPS_PER_ROW) {
+                memory_gates.connect_cell_pair(id_cell, (layout.execution_row(table, t), 0));
+                memory_gates.connect_cell_pair(id_cell, (layout.sorted_row(table, t), 0));
+            }
+        }
+
+        // copy the times of the accesses, and the value read by the loads to the value they write
+        for (t, op) in ops.iter().enumerate() {
+            for table in 0..MEMORY_TABLES {
+                memory_gates.connect_cell_pair(
+                    layout.key_cell(t),
+                    (layout.execution_row(table, t), key_col(t)),
+                );
+            }
+            if *op == MemoryOp::Load {
+                memory_gates.connect_cell_pair(
+                    (layout.execution_row(OLD, t), value_col(t)),
+                    (layout.execution_row(NEW, t), value_col(t)),
+                );
+            }
+        }
+
+        // copy the times of the sorted accesses, and check that the first one reads zero
+        for i in 0..num_accesses {
+            let key = (layout.sorted_row(ADDRESS, i), key_col(i));
+            memory_gates.connect_cell_pair(key, (layout.sorted_row(OLD, i), key_col(i)));
+            memory_gates.connect_cell_pair(key, (layout.sorted_row(NEW, i), key_col(i)));
+        }
+        memory_gates.connect_cell_pair((layout.sorted_row(OLD, 0), value_col(0)), (1, 3));
+
+        // copy the cells of the checks of the sorted accesses
+        for i in 1..num_accesses {
+            let row = layout.check_row(i);
+            let sorted_value = |table: usize, i: usize| (layout.sorted_row(table, i), value_col(i));
+            let sorted_key = |i: usize| (layout.sorted_row(ADDRESS, i), key_col(i));
+            // the addresses, times and values
+            memory_gates.connect_cell_pair(sorted_value(ADDRESS, i), (row, 0));
+            memory_gates.connect_cell_pair(sorted_value(ADDRESS, i - 1), (row, 1));
+            memory_gates.connect_cell_pair(sorted_key(i), (row, 3));
+            memory_gates.connect_cell_pair(sorted_key(i - 1), (row, 4));
+            memory_gates.connect_cell_pair(sorted_value(NEW, i - 1), (row + 3, 1));
+            memory_gates.connect_cell_pair(sorted_value(OLD, i), (row + 3, 2));
+            // s
+            for cell in [
+                (row + 1, 3),
+                (row + 1, 4),
+                (row + 1, 5),
+                (row + 2, 0),
+                (row + 3, 0),
+            ] {
+                memory_gates.connect_cell_pair((row + 1, 0), cell);
+            }
+            // da, dt and m
+            memory_gates.connect_cell_pair((row, 2), (row + 1, 1));
+            memory_gates.connect_cell_pair((row, 2), (row + 2, 3));
+            memory_gates.connect_cell_pair((row, 5), (row + 2, 1));
+            memory_gates.connect_cell_pair((row + 2, 2), (row + 2, 4));
+            // d, with its 2 most significant limbs to zero
+            memory_gates.connect_cell_pair((row + 2, 5), (row + 4, 0));
+            memory_gates.connect_cell_pair((1, 3), (row + 4, 1));
+            memory_gates.connect_cell_pair((1, 3), (row + 4, 2));
+        }
+
+        (new_row + memory_gates.len(), memory_gates)
+    }
+}
+
+// Converts an address to an integer, to sort the accesses
+fn address_to_biguint<F: PrimeField>(address: &F) -> BigUint {
+    let address = address.to_biguint();
+    assert!(
+        address.bits() <= 64,
+        "The addresses of the memory gadget must fit in 64 bits"
+    );
+    address
+}
+
+// Extends the witness with a row
+fn extend_row<F: PrimeField>(witness: &mut [Vec<F>; COLUMNS], cells: &[F]) {
+    for (col, column) in witness.iter_mut().enumerate() {
+        column.push(cells.get(col).copied().unwrap_or_else(F::zero));
+    }
+}
+
+// Extends the witness with the lookup rows of accesses (t, address, old, new), three per row and table
+fn extend_lookup_rows<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    first_table_id: i32,
+    accesses: &[[F; 4]],
+) {
+    for chunk in accesses.chunks(LOOKUPS_PER_ROW) {
+        for table in 0..MEMORY_TABLES {
+            let mut cells = vec![i32_to_field(first_table_id + table as i32)];
+            // unused slots repeat the last access
+            for slot in 0..LOOKUPS_PER_ROW {
+                let access = chunk[slot.min(chunk.len() - 1)];
+                cells.extend([access[0], access[1 + table]]);
+            }
+            extend_row(witness, &cells);
+        }
+    }
+}
+
+/// Extends the memory rows to the full witness
+/// Input
+/// - witness: full witness of the circuit
+/// - first_table_id: the ID of the first runtime table
+/// - accesses: the accesses in execution order, of the kinds given to the gadget
+/// Output
+/// - the runtime tables of the memory, to be given to the prover
+/// Note: the loads read the value of the last store to their address, or zero.
+/// Panics if `accesses` is empty, or if an address does not fit in 64 bits.
+pub fn extend_memory_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    first_table_id: i32,
+    accesses: &[MemoryAccess<F>],
+) -> Vec<RuntimeTable<F>> {
+    check_table_id(first_table_id);
+    Layout::create(accesses.len());
+
+    // the accesses (t, address, old, new) in execution order
+    let mut memory = HashMap::new();
+    let executed: Vec<[F; 4]> = accesses
+        .iter()
+        .enumerate()
+        .map(|(t, access)| {
+            let address = access.address();
+            let old = memory.get(&address).copied().unwrap_or_else(F::zero);
+            let new = match access {
+                MemoryAccess::Load { .. } => old,
+                MemoryAccess::Store { value, .. } => *value,
+            };
+            memory.insert(address, new);
+            [F::from(t as u64), address, old, new]
+        })
+        .collect();
+    let mut sorted = executed.clone();
+    sorted.sort_by_cached_key(|access| address_to_biguint(&access[1]));
+
+    // the IDs of the tables, the zero cell and the times
+    let table_id = |table: usize| i32_to_field::<F>(first_table_id + table as i32);
+    extend_row(
+        witness,
+        &[table_id(ADDRESS), F::zero(), F::zero(), table_id(OLD)],
+    );
+    extend_row(witness, &[table_id(NEW)]);
+    for times in executed.chunks(2) {
+        let second = times.get(1).map(|access| access[0]).unwrap_or_else(F::zero);
+        extend_row(witness, &[times[0][0], F::zero(), F::zero(), second]);
+    }
+
+    extend_lookup_rows(witness, first_table_id, &executed);
+    extend_lookup_rows(witness, first_table_id, &sorted);
+
+    for pair in sorted.windows(2) {
+        let ([t0, a0, _, new0], [t1, a1, old1, _]) = (pair[0], pair[1]);
+        let s = if a1 == a0 { F::one() } else { F::zero() };
+        let (da, dt) = (a1 - a0, t1 - t0);
+        let m = s * dt;
+        let d = da + m - F::one();
+        extend_row(witness, &[a1, a0, da, t1, t0, dt]);
+        extend_row(witness, &[s, da, F::zero(), s, s, s]);
+        extend_row(witness, &[s, dt, m, da, m, d]);
+        extend_row(witness, &[s, new0, old1]);
+        range_check::witness::extend_single(witness, d);
+    }
+    (0..MEMORY_TABLES)
+        .map(|table| RuntimeTable {
+            id: first_table_id + table as i32,
+            data: executed.iter().map(|access| access[1 + table]).collect(),
+        })
+        .collect()
+}
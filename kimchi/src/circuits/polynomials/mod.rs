@@ -1,15 +1,34 @@
+pub mod add_u64;
 pub mod and;
+pub mod assertion;
+pub mod compare;
 pub mod complete_add;
+pub mod decompression;
+pub mod ecdsa;
 pub mod endomul_scalar;
 pub mod endosclmul;
+pub mod foreign_curve_add;
+pub mod foreign_curve_msm;
+pub mod foreign_curve_mul;
 pub mod foreign_field_add;
 pub mod foreign_field_mul;
 pub mod generic;
+pub mod hash_to_curve;
+pub mod keccak;
+pub mod memory;
+pub mod mux;
 pub mod not;
+pub mod pedersen;
 pub mod permutation;
 pub mod poseidon;
 pub mod range_check;
 pub mod rot;
+pub mod schnorr;
+pub mod select;
+pub mod set_membership;
+pub mod shift;
 pub mod turshi;
 pub mod varbasemul;
+pub mod vrf;
+pub mod x25519;
 pub mod xor;
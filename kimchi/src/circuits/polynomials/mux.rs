@@ -0,0 +1,261 @@
+//! This module includes the multiplexer gadget, selecting one of `n` values by an index,
+//! and its witness code generation.
+//!
+//! The multiplexer is a tree of selections (see [crate::circuits::polynomials::select]):
+//! the level `j` of the tree selects between the pairs of values of the level before it
+//! with the bit `j` of the index, from the `n` values of the multiplexer to its output
+//! (the last value of a level of odd size is passed to the next level as is).
+//! The `Select` gates constrain the bits of the index to be booleans,
+//! and `Generic` gates recompose the index from its bits, from the most significant one.
+//!
+//! If `n` is a power of two, the bits of the index range check it.
+//! Otherwise, the index is looked up in a table of the integers below `n` (see [mux_index_table]),
+//! which must be registered with the circuit: the table has `n` entries, so that `n` is bounded
+//! by the size of the domain.
+//!
+//! | Rows                  | `CircuitGate`       | Purpose                                       |
+//! | --------------------- | ------------------- | --------------------------------------------- |
+//! | 1 per 2 selections    | `Select`            | Select between the pairs of values of a level |
+//! | 1 per bit but one     | `Generic`           | Recompose the index: $acc' = 2 \cdot acc + b$ |
+//! | 2 if `n` is not $2^k$ | `Generic`, `Lookup` | Look up the index in the table of the indices |
+//!
+//! The values, the index and the output are in wired cells (see [MuxLayout]),
+//! so that they can be copied from (or to) other gates.
+
+use crate::circuits::{
+    gate::{CircuitGate, Connect},
+    lookup::{
+        gadget::{self, num_lookup_rows},
+        tables::LookupTable,
+    },
+    polynomial::COLUMNS,
+    polynomials::{
+        generic::GenericGateSpec,
+        select::{self, select_cells, select_condition, SELECT_WORDS},
+    },
+    wires::Wire,
+};
+use ark_ff::PrimeField;
+
+/// The layout of a multiplexer gadget, with the positions of its cells as `(row, column)` pairs
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MuxLayout {
+    /// the cells of the values
+    pub values: Vec<(usize, usize)>,
+    /// the cell of the index
+    pub index: (usize, usize),
+    /// the cell of the selected value
+    pub output: (usize, usize),
+    /// the row after the gadget
+    pub next_row: usize,
+    // the first row and the number of selections of each level of the tree
+    levels: Vec<(usize, usize)>,
+    // the first row recomposing the index
+    recompose_row: usize,
+    // the first row of the lookup of the index, if any
+    lookup_row: Option<usize>,
+}
+
+impl MuxLayout {
+    /// Returns the layout of a multiplexer of `num_values` values starting at row `new_row`
+    ///
+    /// # Panics
+    ///
+    /// Will panic if there are less than two values.
+    pub fn new(new_row: usize, num_values: usize) -> Self {
+        assert!(num_values > 1, "the multiplexer needs at least two values");
+        let mut levels = vec![];
+        let mut row = new_row;
+        let mut count = num_values;
+        while count > 1 {
+            let words = count / 2;
+            levels.push((row, words));
+            row += (words + SELECT_WORDS - 1) / SELECT_WORDS;
+            count -= words;
+        }
+        let recompose_row = row;
+        row += levels.len() - 1;
+        let lookup_row = (!num_values.is_power_of_two()).then_some(row);
+        if lookup_row.is_some() {
+            row += 1 + num_lookup_rows(1);
+        }
+
+        let mut layout = MuxLayout {
+            values: vec![],
+            index: (0, 0),
+            // the last level has a single selection
+            output: select_cells(levels[levels.len() - 1].0, 0)[2],
+            next_row: row,
+            levels,
+            recompose_row,
+            lookup_row,
+        };
+        layout.values = (0..num_values).map(|i| layout.value_cell(0, i)).collect();
+        layout.index = match layout.bits() {
+            1 => select_condition(new_row),
+            bits => (recompose_row + bits - 2, 2),
+        };
+        layout
+    }
+
+    /// Returns the number of bits of the index, which is the number of levels of the tree
+    pub fn bits(&self) -> usize {
+        self.levels.len()
+    }
+
+    // The cell of the value `i` of the level `level` of the tree
+    fn value_cell(&self, level: usize, i: usize) -> (usize, usize) {
+        let (row, words) = self.levels[level];
+        if i < 2 * words {
+            // the odd values are selected by the bit 1
+            let [x, y, _] = select_cells(row, i / 2);
+            if i % 2 == 1 {
+                x
+            } else {
+                y
+            }
+        } else {
+            self.value_cell(level + 1, words)
+        }
+    }
+}
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Extends a circuit with a multiplexer selecting one of `num_values` values by an index
+    /// Includes:
+    /// - 1 Select gate for every 2 selections of the tree
+    /// - 1 Generic gate for every bit of the index but one
+    /// - 1 Generic gate and 1 Lookup gate if `num_values` is not a power of two
+    /// Input:
+    /// - gates       : the full circuit
+    /// - num_values  : the number of values
+    /// - index_table : the ID of the table created by [mux_index_table],
+    ///   only used if `num_values` is not a power of two
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the values and the index should be copied from other cells (see [MuxLayout])
+    pub fn extend_mux(gates: &mut Vec<Self>, num_values: usize, index_table: i32) -> usize {
+        let (next_row, mut mux_gates) = Self::create_mux(gates.len(), num_values, index_table);
+        gates.append(&mut mux_gates);
+        next_row
+    }
+
+    /// Creates a multiplexer selecting one of `num_values` values by an index,
+    /// see [Self::extend_mux]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_mux(new_row: usize, num_values: usize, index_table: i32) -> (usize, Vec<Self>) {
+        let layout = MuxLayout::new(new_row, num_values);
+        let mut mux_gates: Vec<Self> = layout
+            .levels
+            .iter()
+            .flat_map(|(row, words)| Self::create_select(*row, *words).1)
+            .collect();
+        for row in layout.recompose_row..layout.recompose_row + layout.bits() - 1 {
+            mux_gates.push(CircuitGate::create_generic_gadget(
+                Wire::for_row(row),
+                GenericGateSpec::Add {
+                    left_coeff: Some(F::from(2u64)),
+                    right_coeff: None,
+                    output_coeff: None,
+                },
+                None,
+            ));
+        }
+        if let Some(row) = layout.lookup_row {
+            mux_gates.append(&mut Self::create_lookup(row, index_table, 1).1);
+        }
+
+        let next_row = layout.next_row;
+
+        // the wiring (indices are relative to the gadget, wires are not)
+        let layout = MuxLayout::new(0, num_values);
+        // the outputs of a level are the values of the next level
+        for level in 0..layout.bits() - 1 {
+            let (row, words) = layout.levels[level];
+            for i in 0..words {
+                let output = select_cells(row, i)[2];
+                mux_gates.connect_cell_pair(output, layout.value_cell(level + 1, i));
+            }
+        }
+        // the index is recomposed from the bits, from the most significant one
+        let bit = |level: usize| select_condition(layout.levels[level].0);
+        let mut acc = bit(layout.bits() - 1);
+        for (i, row) in (layout.recompose_row..).take(layout.bits() - 1).enumerate() {
+            mux_gates.connect_cell_pair(acc, (row, 0));
+            mux_gates.connect_cell_pair(bit(layout.bits() - 2 - i), (row, 1));
+            acc = (row, 2);
+        }
+        if let Some(row) = layout.lookup_row {
+            mux_gates.connect_cell_pair(layout.index, (row + 1, 1));
+        }
+
+        (next_row, mux_gates)
+    }
+}
+
+/// Returns the lookup table of the indices of a multiplexer of `num_values` values,
+/// the integers below `num_values`, with the ID `table_id`
+pub fn mux_index_table<F: PrimeField>(table_id: i32, num_values: usize) -> LookupTable<F> {
+    LookupTable {
+        id: table_id,
+        data: vec![(0..num_values as u64).map(F::from).collect()],
+    }
+}
+
+/// Extends the multiplexer rows to the full witness
+/// Input
+/// - witness: full witness of the circuit
+/// - index_table: the ID of the table of the indices (see [CircuitGate::extend_mux])
+/// - index: the index of the selected value
+/// - values: the values of the multiplexer
+/// Output
+/// - the selected value
+/// Panics if the index is not the index of a value.
+pub fn extend_mux_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    index_table: i32,
+    index: usize,
+    values: &[F],
+) -> F {
+    assert!(
+        index < values.len(),
+        "the index is not the index of a value"
+    );
+    let layout = MuxLayout::new(0, values.len());
+
+    // the selections of each level, with the bit of the index of the level
+    let mut level_values = values.to_vec();
+    for level in 0..layout.bits() {
+        let bit = (index >> level) & 1 == 1;
+        let words = level_values.len() / 2;
+        let x: Vec<F> = (0..words).map(|i| level_values[2 * i + 1]).collect();
+        let y: Vec<F> = (0..words).map(|i| level_values[2 * i]).collect();
+        let mut next_values = select::extend_select_witness(witness, bit, &x, &y);
+        next_values.extend(level_values.get(2 * words));
+        level_values = next_values;
+    }
+
+    // the recomposition of the index, from the most significant bit
+    let bit = |level: usize| F::from((index >> level) & 1 == 1);
+    let mut acc = bit(layout.bits() - 1);
+    for level in (0..layout.bits() - 1).rev() {
+        let next_acc = acc.double() + bit(level);
+        for (col, cell) in witness.iter_mut().enumerate() {
+            cell.push(match col {
+                0 => acc,
+                1 => bit(level),
+                2 => next_acc,
+                _ => F::zero(),
+            });
+        }
+        acc = next_acc;
+    }
+
+    if layout.lookup_row.is_some() {
+        gadget::extend_lookup_witness(witness, index_table, &[(acc, F::zero())]);
+    }
+    level_values[0]
+}
@@ -0,0 +1,596 @@
+//! This module includes the gadgets verifying the opening of a Pedersen commitment to a vector of values,
+//! over a native curve or over a foreign curve, and their witness code generation.
+//! Note that this module does not include a new gate type, the native gadget reuses the `VarBaseMul`,
+//! `CompleteAdd` and `Generic` gates as the Schnorr gadget, and the foreign gadget reuses the
+//! multi-scalar multiplication of fixed points over a foreign curve.
+
+//~ The Pedersen gadget verifies the opening $(v_1, \dots, v_k, r)$ of a commitment
+//~ $C = \sum_i v_i G_i + r H$ to a vector of values, for fixed generators $G_1, \dots, G_k, H$
+//~ of unknown discrete logarithms (such as the ones of the URS of the polynomial commitments).
+//~
+//~ Over a native curve $E$ (as for the Schnorr gadget, Pallas in a circuit over the Vesta scalar field),
+//~ the values and the blinding factor $r$ are native field elements, which are smaller than the order of $E$.
+//~ The gadget computes the products $V_j = 2 (k_j + 1) P_j$ of the $k + 1$ pairs $(k_j, P_j)$
+//~ of the values with their generators and of $r$ with $H$, which are never the point at infinity
+//~ (even for a value $0$), and checks that
+//~ $$\sum_j V_j - 2 \sum_j P_j = 2 C$$
+//~ It lays out:
+//~
+//~ * the constants $0$, $-2 \sum_j P_j$, and each generator $P_j$ and its double in `Generic` gates $l - c = 0$,
+//~ * the shifted scalar of each $k_j + 1$ in a `Generic` gate, as for the Schnorr gadget but with the constraint
+//~   $k' - w q - k_j + d - 1 = 0$,
+//~ * the `CompleteAdd` gate of $2 C$,
+//~ * the variable base multiplications $V_j$, with `VarBaseMul` gates from the constant accumulators $2 P_j$,
+//~ * the `CompleteAdd` gates of the sums of the $V_j$ and of $- 2 \sum_j P_j$, whose result is copied to $2 C$,
+//~   with their infinity flags copied to zero.
+//~
+//~ The doubling formulas do not depend on the constant $b$ of the curve $y^2 = x^3 + b$,
+//~ so that $2 C$ is on the curve only when $C$ is, and $C$ is unique since the order of the curve is odd.
+//~ The inputs of the gadget are at the positions given by `pedersen_input`:
+//~ each value and the blinding factor in the output of the `Generic` gate of its shifted scalar,
+//~ and $C$ in the first inputs of its doubling.
+//~
+//~ Over a foreign curve, the values and the blinding factor are scalars modulo the order of the curve,
+//~ and the gadget is the multi-scalar multiplication of the fixed points $G_1, \dots, G_k, H$,
+//~ whose result is the commitment $C$ (which must not be the point at infinity).
+//~ Its inputs are at the positions given by `foreign_pedersen_limb`.
+//~
+
+use crate::{
+    circuits::{
+        gate::{CircuitGate, Connect, GateType},
+        polynomial::COLUMNS,
+        polynomials::{
+            ecdsa::{
+                curve::{ForeignCurve, ForeignPoint},
+                layout::{Cell, Layout, Point},
+                ELEM_ROWS,
+            },
+            foreign_curve_msm,
+            foreign_curve_mul::MulBase,
+            generic::{GENERIC_COEFFS, GENERIC_REGISTERS},
+            schnorr::{
+                complete_add_witness, coordinates, generic_gate, mul_rows, native_scalar,
+                scalar_shift, shifted_bits,
+            },
+            varbasemul,
+        },
+        wires::Wire,
+    },
+    curve::KimchiCurve,
+};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use o1_utils::FieldHelpers;
+use poly_commitment::{commitment::CommitmentCurve, srs::SRS};
+use std::array;
+
+/// The fixed generators of the Pedersen commitments to vectors of values over a native curve `C`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PedersenGenerators<C: KimchiCurve> {
+    /// The generators of the values
+    pub g: Vec<C>,
+    /// The generator of the blinding factor
+    pub h: C,
+}
+
+impl<C: KimchiCurve> PedersenGenerators<C>
+where
+    C::BaseField: PrimeField,
+{
+    /// Creates the generators, which should be of unknown discrete logarithms
+    pub fn new(g: Vec<C>, h: C) -> Self {
+        assert!(!g.is_empty(), "There is a generator of a value");
+        for generator in g.iter().chain([&h]) {
+            assert!(
+                generator.to_coordinates().is_some(),
+                "The generators are not the point at infinity"
+            );
+        }
+        Self { g, h }
+    }
+
+    /// The generators of `n` values from the URS of the polynomial commitments
+    pub fn from_srs(srs: &SRS<C>, n: usize) -> Self {
+        assert!(n <= srs.g.len(), "The URS has a generator per value");
+        Self::new(srs.g[..n].to_vec(), srs.h)
+    }
+
+    /// Returns the commitment to values with a blinding factor
+    pub fn commit(&self, values: &[C::BaseField], blinding: C::BaseField) -> C {
+        assert_eq!(values.len(), self.g.len(), "There is a value per generator");
+        values
+            .iter()
+            .chain([&blinding])
+            .zip(self.generators())
+            .fold(C::Projective::zero(), |acc, (value, generator)| {
+                acc + generator.mul(native_scalar::<C>(*value).into_repr())
+            })
+            .into_affine()
+    }
+
+    // The generators of the values, then of the blinding factor
+    fn generators(&self) -> impl Iterator<Item = &C> {
+        self.g.iter().chain([&self.h])
+    }
+}
+
+/// The inputs of the Pedersen gadgets
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PedersenInput {
+    /// The `i`-th value
+    Value(usize),
+    /// The blinding factor
+    Blinding,
+    /// The x-coordinate of the commitment
+    CommitmentX,
+    /// The y-coordinate of the commitment
+    CommitmentY,
+}
+
+// The number of constants of a commitment to `num_values` values: zero, the offset, and the generators and their doubles
+fn num_constants(num_values: usize) -> usize {
+    3 + 4 * (num_values + 1)
+}
+
+// The cell of the `i`-th constant
+fn constant(i: usize) -> Cell {
+    (i / 2, GENERIC_REGISTERS * (i % 2))
+}
+
+// The cells of the constants of the gadget
+const ZERO: Cell = (0, 0);
+const OFFSET: [Cell; 2] = [(0, 3), (1, 0)];
+
+// The cells of the `j`-th generator, and of its double
+fn generator_cells(j: usize) -> [[Cell; 2]; 2] {
+    let first = 3 + 4 * j;
+    [
+        [constant(first), constant(first + 1)],
+        [constant(first + 2), constant(first + 3)],
+    ]
+}
+
+// The constants of the gadget: zero, the offset - 2 (G_1 + ... + G_k + H), and the generators and their doubles
+fn constants<C: KimchiCurve>(generators: &PedersenGenerators<C>) -> Vec<C::BaseField>
+where
+    C::BaseField: PrimeField,
+{
+    let double = |point: &C| point.into_projective().double().into_affine();
+    let sum = generators
+        .generators()
+        .fold(C::Projective::zero(), |acc, generator| {
+            acc + generator.into_projective()
+        });
+    let (offset_x, offset_y) = coordinates(&(-sum.double()).into_affine());
+    let mut values = vec![C::BaseField::zero(), offset_x, offset_y];
+    for generator in generators.generators() {
+        let (x, y) = coordinates(generator);
+        let (double_x, double_y) = coordinates(&double(generator));
+        values.extend([x, y, double_x, double_y]);
+    }
+    values
+}
+
+// The first rows of the parts of the native gadget, relative to its first row
+struct PedersenRows {
+    scalars: usize,
+    double: usize,
+    mul: usize,
+    sums: usize,
+    next: usize,
+}
+
+impl PedersenRows {
+    fn new<F: PrimeField>(num_values: usize) -> Self {
+        let terms = num_values + 1;
+        let scalars = (num_constants(num_values) + 1) / 2;
+        let double = scalars + terms;
+        let mul = double + 1;
+        let sums = mul + terms * mul_rows::<F>();
+        Self {
+            scalars,
+            double,
+            mul,
+            sums,
+            next: sums + terms,
+        }
+    }
+
+    // The first row of the multiplication of the `j`-th term
+    fn mul_row<F: PrimeField>(&self, j: usize) -> usize {
+        self.mul + j * mul_rows::<F>()
+    }
+}
+
+/// Returns the position of the cell of an input of the native Pedersen gadget of `num_values` values
+/// starting at row `new_row`, as a `(row, column)` pair
+pub fn pedersen_input(new_row: usize, num_values: usize, input: PedersenInput) -> (usize, usize) {
+    // the rows of the inputs do not depend on the number of rows of the multiplications
+    let scalars = (num_constants(num_values) + 1) / 2;
+    let (row, col) = match input {
+        PedersenInput::Value(i) => {
+            assert!(i < num_values, "The commitment has the value");
+            (scalars + i, 5)
+        }
+        PedersenInput::Blinding => (scalars + num_values, 5),
+        PedersenInput::CommitmentX => (scalars + num_values + 1, 0),
+        PedersenInput::CommitmentY => (scalars + num_values + 1, 1),
+    };
+    (new_row + row, col)
+}
+
+/// Returns the position of the cell containing the `limb`-th limb (from the least significant one)
+/// of an input of the foreign Pedersen gadget of `num_values` values starting at row `new_row`,
+/// as a `(row, column)` pair
+pub fn foreign_pedersen_limb(
+    new_row: usize,
+    num_values: usize,
+    input: PedersenInput,
+    limb: usize,
+) -> (usize, usize) {
+    assert!(limb < 3, "An element only has three limbs");
+    // the commitment is the result of the multi-scalar multiplication, followed by its scalars
+    let elem = match input {
+        PedersenInput::CommitmentX => 0,
+        PedersenInput::CommitmentY => 1,
+        PedersenInput::Value(i) => {
+            assert!(i < num_values, "The commitment has the value");
+            2 + i
+        }
+        PedersenInput::Blinding => 2 + num_values,
+    };
+    (new_row + ELEM_ROWS * elem + limb, 0)
+}
+
+// Lays out the opening of a commitment over a foreign curve, with the values, the blinding factor
+// and the commitment for the witness
+fn layout_foreign_pedersen<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    curve: &ForeignCurve,
+    generators: &[ForeignPoint],
+    values: Option<(&[BigUint], &BigUint, &ForeignPoint)>,
+) {
+    let (p, n) = (&curve.modulus, &curve.order);
+    let commitment = Point {
+        x: layout.witness(values.map(|(_, _, (x, _))| x.clone()), p),
+        y: layout.witness(values.map(|(_, _, (_, y))| y.clone()), p),
+    };
+    let scalars: Vec<_> = (0..generators.len())
+        .map(|j| {
+            let scalar =
+                values.map(|(scalars, blinding, _)| scalars.get(j).unwrap_or(blinding).clone());
+            layout.witness(scalar, n)
+        })
+        .collect();
+    let bases: Vec<MulBase> = generators
+        .iter()
+        .map(|generator| MulBase::Fixed(generator.clone()))
+        .collect();
+    let terms: Vec<_> = scalars
+        .iter()
+        .zip(generators)
+        .map(|(scalar, generator)| {
+            (
+                layout.bits(scalar, n.bits() as usize),
+                layout.constant_point(generator),
+            )
+        })
+        .collect();
+
+    let product = foreign_curve_msm::msm(layout, curve, &bases, &terms);
+    layout.assert_eq(&product.x, &commitment.x);
+    layout.assert_eq(&product.y, &commitment.y);
+}
+
+impl<F: PrimeField + SquareRootField> CircuitGate<F> {
+    /// Extends a circuit with the opening of a Pedersen commitment over the curve `C` of base field `F`
+    /// Includes:
+    /// - the constants and the shifted scalars in generic gates
+    /// - the variable base multiplications and the complete additions of the commitment (see the module)
+    /// Input:
+    /// - gates      : the full circuit
+    /// - generators : the fixed generators of the commitment
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the values, the blinding factor and the commitment (see [pedersen_input]) should be copied from other cells
+    pub fn extend_pedersen<C: KimchiCurve<BaseField = F>>(
+        gates: &mut Vec<Self>,
+        generators: &PedersenGenerators<C>,
+    ) -> usize {
+        let (next_row, mut pedersen_gates) = Self::create_pedersen(gates.len(), generators);
+        gates.append(&mut pedersen_gates);
+        next_row
+    }
+
+    /// Creates the opening of a Pedersen commitment over a native curve, see [Self::extend_pedersen]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_pedersen<C: KimchiCurve<BaseField = F>>(
+        new_row: usize,
+        generators: &PedersenGenerators<C>,
+    ) -> (usize, Vec<Self>) {
+        let num_values = generators.g.len();
+        let terms = num_values + 1;
+        let rows = PedersenRows::new::<F>(num_values);
+        let q = F::from_biguint(&(C::ScalarField::modulus_biguint() % F::modulus_biguint()))
+            .expect("the value is reduced");
+        let d =
+            F::from_biguint(&scalar_shift::<C>()).expect("the shift is smaller than the modulus");
+        let (zero, one) = (F::zero(), F::one());
+
+        // the constants l - c = 0
+        let mut gates: Vec<Self> = constants(generators)
+            .chunks(2)
+            .enumerate()
+            .map(|(i, pair)| {
+                let second = pair
+                    .get(1)
+                    .map_or([zero; GENERIC_COEFFS], |c| [one, zero, zero, zero, -*c]);
+                generic_gate(new_row + i, [[one, zero, zero, zero, -pair[0]], second])
+            })
+            .collect();
+
+        // the shifted scalars w * w - w = 0 and k' - w q - k + d - 1 = 0
+        for _ in 0..terms {
+            gates.push(generic_gate(
+                new_row + gates.len(),
+                [
+                    [-one, zero, zero, one, zero],
+                    [one, -q, -one, zero, d - one],
+                ],
+            ));
+        }
+
+        // the double of the commitment, the multiplications and the sums
+        let complete_add =
+            |row: usize| CircuitGate::new(GateType::CompleteAdd, Wire::for_row(row), vec![]);
+        gates.push(complete_add(new_row + gates.len()));
+        for _ in 0..terms * (mul_rows::<F>() / 2) {
+            let row = new_row + gates.len();
+            gates.extend(CircuitGate::create_vbmul(&[
+                Wire::for_row(row),
+                Wire::for_row(row + 1),
+            ]));
+        }
+        for _ in 0..terms {
+            gates.push(complete_add(new_row + gates.len()));
+        }
+
+        // wire the gadget (indices are relative to the gadget, wires are not)
+        for j in 0..terms {
+            let row = rows.scalars + j;
+            let mul = rows.mul_row::<F>(j);
+            let [base, double_base] = generator_cells(j);
+            gates.connect_cell_pair((row, 0), (row, 1));
+            gates.connect_cell_pair((row, 0), (row, 4));
+            gates.connect_cell_pair((mul + mul_rows::<F>() - 2, 5), (row, 3));
+            gates.connect_cell_pair(ZERO, (mul, 4));
+            for chunk in 0..mul_rows::<F>() / 2 {
+                let row = mul + 2 * chunk;
+                if chunk > 0 {
+                    gates.connect_cell_pair((row - 2, 5), (row, 4));
+                    gates.connect_cell_pair((row - 1, 0), (row, 2));
+                    gates.connect_cell_pair((row - 1, 1), (row, 3));
+                }
+                gates.connect_cell_pair(base[0], (row, 0));
+                gates.connect_cell_pair(base[1], (row, 1));
+            }
+            gates.connect_cell_pair(double_base[0], (mul, 2));
+            gates.connect_cell_pair(double_base[1], (mul, 3));
+        }
+
+        // V_0 + ... + V_k - 2 (G_1 + ... + G_k + H) = 2 C
+        let product = |j: usize| rows.mul_row::<F>(j) + mul_rows::<F>() - 1;
+        for j in 0..terms {
+            let row = rows.sums + j;
+            let left = if j == 0 {
+                (product(0), 0)
+            } else {
+                (row - 1, 4)
+            };
+            let right = if j + 1 < terms {
+                [(product(j + 1), 0), (product(j + 1), 1)]
+            } else {
+                OFFSET
+            };
+            gates.connect_cell_pair(left, (row, 0));
+            gates.connect_cell_pair((left.0, left.1 + 1), (row, 1));
+            gates.connect_cell_pair(right[0], (row, 2));
+            gates.connect_cell_pair(right[1], (row, 3));
+        }
+        let double = rows.double;
+        gates.connect_cell_pair((double, 0), (double, 2));
+        gates.connect_cell_pair((double, 1), (double, 3));
+        gates.connect_cell_pair((rows.next - 1, 4), (double, 4));
+        gates.connect_cell_pair((rows.next - 1, 5), (double, 5));
+        for row in (rows.sums..rows.next).chain([double]) {
+            gates.connect_cell_pair(ZERO, (row, 6));
+        }
+
+        (new_row + gates.len(), gates)
+    }
+
+    /// Extends a circuit with the opening of a Pedersen commitment over a foreign curve
+    /// Includes:
+    /// - the foreign field gates, range checks and generic gates of the multi-scalar multiplication
+    ///   of the generators (see the module)
+    /// Input:
+    /// - gates      : the full circuit
+    /// - curve      : the foreign curve of the commitment
+    /// - generators : the fixed generators of the values, then the one of the blinding factor
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the values, the blinding factor and the commitment (see [foreign_pedersen_limb]) should be copied from
+    ///   other cells, and the commitment must not be the point at infinity
+    pub fn extend_foreign_pedersen(
+        gates: &mut Vec<Self>,
+        curve: &ForeignCurve,
+        generators: &[ForeignPoint],
+    ) -> usize {
+        let (next_row, mut pedersen_gates) =
+            Self::create_foreign_pedersen(gates.len(), curve, generators);
+        gates.append(&mut pedersen_gates);
+        next_row
+    }
+
+    /// Creates the opening of a Pedersen commitment over a foreign curve, see [Self::extend_foreign_pedersen]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_foreign_pedersen(
+        new_row: usize,
+        curve: &ForeignCurve,
+        generators: &[ForeignPoint],
+    ) -> (usize, Vec<Self>) {
+        assert!(
+            generators.len() >= 2,
+            "There are generators of a value and of the blinding factor"
+        );
+        let mut layout = Layout::new(new_row, false);
+        layout_foreign_pedersen(&mut layout, curve, generators, None);
+        let (pedersen_gates, _) = layout.finalize();
+        (new_row + pedersen_gates.len(), pedersen_gates)
+    }
+}
+
+/// Extends the witness with the opening of a Pedersen commitment over a native curve, and returns
+/// whether the opening is valid, since the witness of an invalid opening does not satisfy the gadget
+/// Input
+/// - witness: full witness of the circuit
+/// - generators: the fixed generators of the commitment
+/// - values: the committed values
+/// - blinding: the blinding factor
+/// - commitment: the commitment, which is not the point at infinity
+pub fn extend_pedersen_witness<C: KimchiCurve>(
+    witness: &mut [Vec<C::BaseField>; COLUMNS],
+    generators: &PedersenGenerators<C>,
+    values: &[C::BaseField],
+    blinding: C::BaseField,
+    commitment: &C,
+) -> bool
+where
+    C::BaseField: PrimeField + SquareRootField,
+{
+    assert_eq!(
+        values.len(),
+        generators.g.len(),
+        "There is a value per generator"
+    );
+    let rows = PedersenRows::new::<C::BaseField>(values.len());
+    let mut w: [Vec<C::BaseField>; COLUMNS] =
+        array::from_fn(|_| vec![C::BaseField::zero(); rows.next]);
+
+    // the constants
+    let constants = constants(generators);
+    for (i, value) in constants.iter().enumerate() {
+        let (row, col) = constant(i);
+        w[col][row] = *value;
+    }
+
+    // the multiplications 2 (k + 1) P, and their shifted scalars
+    let mut products = vec![];
+    for (j, (scalar, generator)) in values
+        .iter()
+        .chain([&blinding])
+        .zip(generators.generators())
+        .enumerate()
+    {
+        let (bits, wrap) = shifted_bits::<C>(&(scalar.to_biguint() + BigUint::one()));
+        let double = coordinates(&generator.into_projective().double().into_affine());
+        let result = varbasemul::witness(
+            &mut w,
+            rows.mul_row::<C::BaseField>(j),
+            coordinates(generator),
+            &bits,
+            double,
+        );
+        let row = rows.scalars + j;
+        let wrap = C::BaseField::from(u64::from(wrap));
+        w[0][row] = wrap;
+        w[1][row] = wrap;
+        w[3][row] = result.n;
+        w[4][row] = wrap;
+        w[5][row] = *scalar;
+        products.push(result.acc);
+    }
+
+    // the sums of the products and of the offset, and the double of the commitment
+    let offset = (constants[1], constants[2]);
+    let mut sum = products[0];
+    for (j, point) in products[1..].iter().chain([&offset]).enumerate() {
+        sum = complete_add_witness(&mut w, rows.sums + j, sum, *point);
+    }
+    let commitment_coordinates = coordinates(commitment);
+    complete_add_witness(
+        &mut w,
+        rows.double,
+        commitment_coordinates,
+        commitment_coordinates,
+    );
+
+    for (col, cells) in witness.iter_mut().zip(w) {
+        col.extend(cells);
+    }
+    generators.commit(values, blinding) == *commitment
+}
+
+/// Extends the witness with the opening of a Pedersen commitment over a foreign curve, and returns
+/// whether the opening is valid, since the witness of an invalid opening does not satisfy the gadget
+/// Input
+/// - witness: full witness of the circuit
+/// - curve: the foreign curve of the commitment
+/// - generators: the fixed generators of the values, then the one of the blinding factor
+/// - values: the committed values, modulo the order
+/// - blinding: the blinding factor, modulo the order
+/// - commitment: the commitment, which is on the curve
+/// Warning:
+/// - the opened point should not be the point at infinity, which does not satisfy the gadget
+pub fn extend_foreign_pedersen_witness<F: PrimeField + SquareRootField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    curve: &ForeignCurve,
+    generators: &[ForeignPoint],
+    values: &[BigUint],
+    blinding: &BigUint,
+    commitment: &ForeignPoint,
+) -> bool {
+    assert_eq!(
+        values.len() + 1,
+        generators.len(),
+        "There is a generator per value and for the blinding factor"
+    );
+    assert!(
+        values.iter().chain([blinding]).all(|k| k < &curve.order),
+        "The scalars are smaller than the order"
+    );
+    assert!(
+        curve.is_on_curve(commitment),
+        "The commitment is on the curve"
+    );
+
+    let opened = values
+        .iter()
+        .chain([blinding])
+        .zip(generators)
+        .fold(None, |acc, (scalar, generator)| {
+            curve.add(&acc, &curve.scalar_mul(generator, scalar))
+        });
+    let mut layout = Layout::new(witness[0].len(), true);
+    layout_foreign_pedersen(
+        &mut layout,
+        curve,
+        generators,
+        Some((values, blinding, commitment)),
+    );
+    let (_, pedersen_witness) = layout.finalize();
+    let pedersen_witness = pedersen_witness.expect("the witness is laid out");
+    for (col, cells) in witness.iter_mut().zip(pedersen_witness) {
+        col.extend(cells);
+    }
+    opened.as_ref() == Some(commitment)
+}
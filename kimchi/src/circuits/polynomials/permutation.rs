@@ -479,11 +479,20 @@ impl<F: PrimeField, G: KimchiCurve<ScalarField = F>> ProverIndex<G> {
         };
 
         //~ Finally, randomize the last `EVAL_POINTS` evaluations $z(g^{n-2})$ and $z(g^{n-1})$,
-        //~ in order to add zero-knowledge to the protocol.
-        z[n - 2] = F::rand(rng);
-        z[n - 1] = F::rand(rng);
+        //~ in order to add zero-knowledge to the protocol
+        //~ (they are set to zero when the blinding of the permutation is disabled).
+        for e in &mut z[n - 2..] {
+            *e = if self.blinding.permutation {
+                F::rand(rng)
+            } else {
+                F::zero()
+            };
+        }
 
-        let res = Evaluations::<F, D<F>>::from_vec_and_domain(z, self.cs.domain.d1).interpolate();
+        let res = self.interpolate(Evaluations::<F, D<F>>::from_vec_and_domain(
+            z,
+            self.cs.domain.d1,
+        ));
         Ok(res)
     }
 }
@@ -0,0 +1,439 @@
+//! This module includes the definition of the arbitrary-width range check gadget and its witness code generation.
+//! Note that this module does not include a new gate type, the gadget reuses the `Generic`, `RangeCheck0` and `RangeCheck1` gates.
+
+//~ The range check gadget of `n` bits checks that a value fits in any $n \leq 255$ bits.
+//~ It splits the value into limbs of $w$ bits, with $w = 64$ or $w = 88$,
+//~ $$v = \sum_{i=0}^{k-1} 2^{w i} \cdot c_i$$
+//~ and recombines the limbs into the value with `Generic` gates.
+//~ A range checked value fits either in 64 bits, with a `RangeCheck0` gate whose two most significant 12-bit limbs
+//~ are wired to zero (relying on its 12-bit lookups and 2-bit crumbs),
+//~ or in 88 bits, as one of the three values of a multi range check (3 values in 4 rows).
+//~ The full limbs are range checked in their $w$ bits.
+//~
+//~ The most significant limb $c_{k-1}$ has $t = n - w (k - 1)$ bits, and is checked in one of these ways:
+//~
+//~ * if $t$ is 64 or 88, with a range check of $t$ bits, as a full limb,
+//~ * with range checks of $b > t$ bits of $c_{k-1}$ and of the bound $c_{k-1} + 2^b - 2^t$, with $b = 64$ or $b = 88$,
+//~   as the `Rot64` gate does,
+//~ * with a boolean decomposition in `Generic` gates, $c \cdot c = c$ for one bit,
+//~   and $c = \sum_{i=0}^{t-1} 2^i b_i$ with booleans $b_i$ otherwise.
+//~
+//~ The gadget uses the limb length and the check of the most significant limb which need the fewest rows
+//~ (the first one in the above order, with 64-bit limbs first, when several of them need as many rows).
+//~ For example, the limbs of 88 bits make a range check of 176 bits fit in 5 rows instead of 6,
+//~ and the most significant limbs of up to 2 bits are checked with a boolean decomposition.
+//~
+//~ The `Generic` gates come first, two per row, followed by the `RangeCheck0` rows, and by the multi range checks,
+//~ whose unused values are zero.
+//~ The gadget needs a zero cell to check that the 64-bit range checked values fit in 64 bits,
+//~ and the checked value is at the position given by `range_check_bits_input`.
+
+use crate::circuits::{
+    gate::{CircuitGate, Connect, GateType},
+    polynomial::COLUMNS,
+    polynomials::generic::{DOUBLE_GENERIC_COEFFS, GENERIC_COEFFS},
+    wires::Wire,
+};
+use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::BigUint;
+use o1_utils::FieldHelpers;
+use std::array;
+
+use super::{
+    gadget::{multi_range_check_limb, MULTI_RANGE_CHECK_ROWS},
+    witness::{extend_multi, extend_single},
+};
+
+/// Maximum number of bits that the gadget can check
+pub const MAX_BITS: u32 = 255;
+
+// The lengths of the values checked by a RangeCheck0 row and by a multi range check
+const SINGLE_CHECK_BITS: u32 = 64;
+const MULTI_CHECK_BITS: u32 = 88;
+
+// The variables of the gadget
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Var {
+    // the checked value
+    Value,
+    // the i-th limb
+    Limb(usize),
+    // the sum of the first (i + 2) limbs
+    Acc(usize),
+    // the bound of the most significant limb
+    Bound,
+    // the i-th bit of the most significant limb
+    Bit(usize),
+    // the sum of the first (i + 2) bits of the most significant limb
+    BitAcc(usize),
+    // an unused cell
+    Unused,
+}
+
+// The generic gates of the gadget
+#[derive(Clone, Copy)]
+enum Constraint {
+    // left + 2^shift * right - output = 0
+    Add(u64),
+    // left * left - left = 0
+    Boolean,
+    // left + 2^width - 2^bits - output = 0, for the given bits and width
+    Bound(u32, u32),
+}
+
+impl Constraint {
+    fn coeffs<F: PrimeField>(self) -> [F; GENERIC_COEFFS] {
+        match self {
+            Constraint::Add(shift) => {
+                [F::one(), F::two_pow(shift), -F::one(), F::zero(), F::zero()]
+            }
+            Constraint::Boolean => [-F::one(), F::zero(), F::zero(), F::one(), F::zero()],
+            Constraint::Bound(bits, width) => [
+                F::one(),
+                F::zero(),
+                -F::one(),
+                F::zero(),
+                F::two_pow(width as u64) - F::two_pow(bits as u64),
+            ],
+        }
+    }
+}
+
+// The check of the most significant limb
+#[derive(Clone, Copy)]
+enum TopCheck {
+    // a range check of the given width, which is the length of the limb
+    Full(u32),
+    // range checks of the given width of the limb and of its bound
+    Bound(u32),
+    // a boolean decomposition
+    Boolean,
+}
+
+// The layout of the gadget: the generic gates with their variables,
+// the variables checked by each RangeCheck0 row and by the multi range checks
+struct Layout {
+    limb_bits: u32,
+    top_bits: u32,
+    top_check: TopCheck,
+    generic: Vec<(Constraint, [Var; 3])>,
+    single_checks: Vec<Var>,
+    multi_checks: Vec<Var>,
+}
+
+impl Layout {
+    fn create(bits: u32) -> Self {
+        assert!(
+            bits > 0 && bits <= MAX_BITS,
+            "Range checks are only supported for 1 to 255 bits"
+        );
+        let mut best: Option<Self> = None;
+        for limb_bits in [SINGLE_CHECK_BITS, MULTI_CHECK_BITS] {
+            let top_bits = bits - limb_bits * ((bits - 1) / limb_bits);
+            for top_check in [
+                TopCheck::Full(SINGLE_CHECK_BITS),
+                TopCheck::Bound(SINGLE_CHECK_BITS),
+                TopCheck::Full(MULTI_CHECK_BITS),
+                TopCheck::Bound(MULTI_CHECK_BITS),
+                TopCheck::Boolean,
+            ] {
+                let valid = match top_check {
+                    TopCheck::Full(width) => top_bits == width,
+                    TopCheck::Bound(width) => top_bits < width,
+                    TopCheck::Boolean => true,
+                };
+                if !valid {
+                    continue;
+                }
+                let layout = Self::with_limbs(bits, limb_bits, top_check);
+                if best
+                    .as_ref()
+                    .map_or(true, |best| layout.num_rows() < best.num_rows())
+                {
+                    best = Some(layout);
+                }
+            }
+        }
+        best.expect("a most significant limb can always be decomposed")
+    }
+
+    fn with_limbs(bits: u32, limb_bits: u32, top_check: TopCheck) -> Self {
+        let num_limbs = ((bits + limb_bits - 1) / limb_bits) as usize;
+        let top_bits = bits - limb_bits * (num_limbs as u32 - 1);
+        // a single limb is the value itself
+        let limb = |i: usize| {
+            if num_limbs == 1 {
+                Var::Value
+            } else {
+                Var::Limb(i)
+            }
+        };
+
+        let mut layout = Layout {
+            limb_bits,
+            top_bits,
+            top_check,
+            generic: vec![],
+            single_checks: vec![],
+            multi_checks: vec![],
+        };
+
+        // recombine the limbs: acc_0 = c_0 + 2^w c_1, acc_i = acc_{i-1} + 2^(w(i+1)) c_(i+1), the last one being the value
+        let limbs: Vec<Var> = (0..num_limbs).map(limb).collect();
+        layout.recombine(&limbs, limb_bits as u64, Var::Acc, Var::Value);
+
+        // the full limbs
+        for i in 0..num_limbs - 1 {
+            layout.check(limb(i), limb_bits);
+        }
+
+        // the most significant limb
+        let top = limb(num_limbs - 1);
+        match top_check {
+            TopCheck::Full(width) => layout.check(top, width),
+            TopCheck::Bound(width) => {
+                layout.generic.push((
+                    Constraint::Bound(top_bits, width),
+                    [top, Var::Unused, Var::Bound],
+                ));
+                layout.check(top, width);
+                layout.check(Var::Bound, width);
+            }
+            TopCheck::Boolean if top_bits == 1 => {
+                layout
+                    .generic
+                    .push((Constraint::Boolean, [top, top, Var::Unused]));
+            }
+            TopCheck::Boolean => {
+                let top_vars: Vec<Var> = (0..top_bits as usize).map(Var::Bit).collect();
+                for bit in &top_vars {
+                    layout
+                        .generic
+                        .push((Constraint::Boolean, [*bit, *bit, Var::Unused]));
+                }
+                layout.recombine(&top_vars, 1, Var::BitAcc, top);
+            }
+        }
+
+        layout
+    }
+
+    // Adds the generic gates recombining `parts` of `width` bits into `output`,
+    // with the partial sums given by `acc`
+    fn recombine(&mut self, parts: &[Var], width: u64, acc: fn(usize) -> Var, output: Var) {
+        for i in 1..parts.len() {
+            let left = if i == 1 { parts[0] } else { acc(i - 2) };
+            let out = if i == parts.len() - 1 {
+                output
+            } else {
+                acc(i - 1)
+            };
+            self.generic
+                .push((Constraint::Add(width * i as u64), [left, parts[i], out]));
+        }
+    }
+
+    // Adds a range check of `width` bits of `var`
+    fn check(&mut self, var: Var, width: u32) {
+        if width == SINGLE_CHECK_BITS {
+            self.single_checks.push(var);
+        } else {
+            self.multi_checks.push(var);
+        }
+    }
+
+    fn num_generic_rows(&self) -> usize {
+        (self.generic.len() + 1) / 2
+    }
+
+    fn num_multi_checks(&self) -> usize {
+        (self.multi_checks.len() + 2) / 3
+    }
+
+    fn num_rows(&self) -> usize {
+        self.num_generic_rows()
+            + self.single_checks.len()
+            + MULTI_RANGE_CHECK_ROWS * self.num_multi_checks()
+    }
+
+    // The first row of the multi range checks, relative to the first row of the gadget
+    fn multi_checks_row(&self) -> usize {
+        self.num_generic_rows() + self.single_checks.len()
+    }
+
+    // The cells of the gadget with their variables, relative to its first row
+    fn cells(&self) -> Vec<((usize, usize), Var)> {
+        let mut cells = vec![];
+        for (i, (_, vars)) in self.generic.iter().enumerate() {
+            for (j, var) in vars.iter().enumerate() {
+                cells.push(((i / 2, 3 * (i % 2) + j), *var));
+            }
+        }
+        let offset = self.num_generic_rows();
+        for (i, var) in self.single_checks.iter().enumerate() {
+            cells.push(((offset + i, 0), *var));
+        }
+        let offset = self.multi_checks_row();
+        for (i, var) in self.multi_checks.iter().enumerate() {
+            cells.push((multi_range_check_limb(offset, i / 3, i % 3), *var));
+        }
+        cells
+    }
+}
+
+/// Returns the number of rows of the range check gadget of `bits` length
+/// Panics if `bits` is not in `1..=255`
+pub fn range_check_bits_rows(bits: u32) -> usize {
+    Layout::create(bits).num_rows()
+}
+
+/// Returns the position of the cell containing the value checked by a range check gadget of `bits` length
+/// starting at row `new_row`, as a `(row, column)` pair.
+/// Panics if `bits` is not in `1..=255`
+pub fn range_check_bits_input(new_row: usize, bits: u32) -> (usize, usize) {
+    let ((row, col), _) = Layout::create(bits)
+        .cells()
+        .into_iter()
+        .find(|(_, var)| *var == Var::Value)
+        .expect("the value is in the gadget");
+    (new_row + row, col)
+}
+
+impl<F: PrimeField + SquareRootField> CircuitGate<F> {
+    /// Extends a circuit with a range check gadget of `bits` length
+    /// Includes:
+    /// - Generic gates to recombine the 64-bit or 88-bit limbs of the value, and to check its most significant limb
+    /// - 1 RangeCheck0 gate per 64-bit range checked value
+    /// - 1 multi range check per three 88-bit range checked values
+    /// Input:
+    /// - gates    : the full circuit
+    /// - bits     : the number of bits of the checked value, up to 255
+    /// - zero_row : the row of the Generic gate to constrain the 64-bit checks
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the checked value is at the position given by [range_check_bits_input]
+    pub fn extend_range_check_bits(gates: &mut Vec<Self>, bits: u32, zero_row: usize) -> usize {
+        let start_row = gates.len();
+        let (next_row, mut rc_gates) = Self::create_range_check_bits(start_row, bits);
+        let layout = Layout::create(bits);
+        let first_check = start_row + layout.num_generic_rows();
+        gates.append(&mut rc_gates);
+        // Check that 2 most significant limbs of each 64-bit range checked value are zero
+        for row in first_check..start_row + layout.multi_checks_row() {
+            gates.connect_64bit(zero_row, row);
+        }
+        next_row
+    }
+
+    /// Creates a range check gadget of `bits` length, see [Self::extend_range_check_bits]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    /// Warning:
+    /// - need to check that the 2 most significant limbs of the 64-bit range checked values are zero
+    pub fn create_range_check_bits(new_row: usize, bits: u32) -> (usize, Vec<Self>) {
+        let layout = Layout::create(bits);
+
+        let mut rc_gates: Vec<Self> = layout
+            .generic
+            .chunks(2)
+            .enumerate()
+            .map(|(i, pair)| {
+                let mut coeffs = [F::zero(); DOUBLE_GENERIC_COEFFS];
+                for (j, (constraint, _)) in pair.iter().enumerate() {
+                    coeffs[GENERIC_COEFFS * j..GENERIC_COEFFS * (j + 1)]
+                        .copy_from_slice(&constraint.coeffs());
+                }
+                CircuitGate::create_generic(Wire::for_row(new_row + i), coeffs)
+            })
+            .collect();
+        let mut next_row = new_row + rc_gates.len();
+        for _ in &layout.single_checks {
+            rc_gates.push(CircuitGate::new(
+                GateType::RangeCheck0,
+                Wire::for_row(next_row),
+                vec![F::zero()],
+            ));
+            next_row += 1;
+        }
+        let (next_row, mut multi_gates) =
+            CircuitGate::create_multi_range_checks(next_row, layout.num_multi_checks());
+        rc_gates.append(&mut multi_gates);
+
+        // copy the cells of each variable (indices are relative to the gadget, wires are not)
+        let cells = layout.cells();
+        for (i, (cell, var)) in cells.iter().enumerate() {
+            if *var == Var::Unused {
+                continue;
+            }
+            if let Some((next, _)) = cells[i + 1..].iter().find(|(_, other)| other == var) {
+                rc_gates.connect_cell_pair(*cell, *next);
+            }
+        }
+
+        (next_row, rc_gates)
+    }
+}
+
+/// Extends the range check rows of a value of `bits` length to the full witness
+/// Input
+/// - witness: full witness of the circuit
+/// - value: the value to be checked, as a field element
+/// - bits: the number of bits of the value, up to 255
+/// Panics if `value` does not fit in `bits` bits
+/// Warning:
+/// - don't forget to include a public input row with zero value
+pub fn extend_range_check_bits_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    value: F,
+    bits: u32,
+) {
+    let layout = Layout::create(bits);
+    let big = value.to_biguint();
+    assert!(
+        big.bits() <= bits as u64,
+        "Value must fit in the given number of bits"
+    );
+
+    let limb_bits = layout.limb_bits as usize;
+    let top = (bits as usize - 1) / limb_bits;
+    let mask = |n: usize| -> BigUint { (BigUint::from(1u8) << n) - 1u8 };
+    let limb = |i: usize| -> BigUint { (&big >> (limb_bits * i)) & mask(limb_bits) };
+    let eval = |var: Var| -> F {
+        match var {
+            Var::Value => value,
+            Var::Limb(i) => F::from_biguint(&limb(i)).unwrap(),
+            Var::Acc(i) => F::from_biguint(&(&big & &mask(limb_bits * (i + 2)))).unwrap(),
+            Var::Bound => {
+                let width = match layout.top_check {
+                    TopCheck::Bound(width) => width,
+                    _ => unreachable!("the bound is only checked with a bound check"),
+                };
+                F::from_biguint(&limb(top)).unwrap() + F::two_pow(width as u64)
+                    - F::two_pow(layout.top_bits as u64)
+            }
+            Var::Bit(i) => F::from(big.bit((limb_bits * top + i) as u64)),
+            Var::BitAcc(i) => F::from_biguint(&(limb(top) & mask(i + 2))).unwrap(),
+            Var::Unused => F::zero(),
+        }
+    };
+
+    let generic_rows = layout.num_generic_rows();
+    let mut rc_witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![F::zero(); generic_rows]);
+    for ((row, col), var) in layout.cells() {
+        if row < generic_rows {
+            rc_witness[col][row] = eval(var);
+        }
+    }
+    for col in 0..COLUMNS {
+        witness[col].extend(rc_witness[col].iter());
+    }
+    for var in &layout.single_checks {
+        extend_single(witness, eval(*var));
+    }
+    for vars in layout.multi_checks.chunks(3) {
+        let value = |i: usize| vars.get(i).map_or(F::zero(), |var| eval(*var));
+        extend_multi(witness, value(0), value(1), value(2));
+    }
+}
@@ -0,0 +1,219 @@
+//! This module includes the definition of the byte range check gadget and its witness code generation.
+//! Note that the `RangeCheckU16` and `RangeCheckU8` gates of this gadget have no constraints, only lookups.
+
+//~ The byte range check gadget checks that a value is a packed array of $n \leq 31$ bytes,
+//~ that is $v < 2^{8n}$, with lookups into the built-in 16-bit and byte tables.
+//~ Each `RangeCheckU16` or `RangeCheckU8` row holds 4 values $v_0, v_1, v_2, v_3$ in its first columns,
+//~ and looks up the 4 limbs
+//~ $$v_i - 2^b \cdot v_{i+1}$$
+//~ in the table of $b$-bit values (with $b = 16$ or $b = 8$), where $v_4$ is the first value of the next row.
+//~ The first value of the gadget is the checked value, and the last row of the gadget is a `Generic` gate
+//~ constraining its first value to zero, so that the checked value is the sum of the shifted limbs.
+//~
+//~ The limbs are ordered from the least significant one: the 16-bit limbs come first,
+//~ 8 bytes per `RangeCheckU16` row, followed by the remaining bytes in up to two `RangeCheckU8` rows.
+//~ When the last `RangeCheckU8` row has fewer than 4 limbs, its unused values are wired to the zero of the last row.
+//~
+//~ | Row     | `CircuitGate`   | Purpose                                      |
+//~ | ------- | --------------- | -------------------------------------------- |
+//~ | i...    | `RangeCheckU16` | Check 4 16-bit limbs, the first value is $v$ |
+//~ | ...     | `RangeCheckU8`  | Check up to 4 byte limbs                     |
+//~ | last    | `Generic`       | Constrain the final value to zero            |
+//~
+//~ For example, a 31-byte value needs 3 `RangeCheckU16` rows, 2 `RangeCheckU8` rows and the `Generic` row.
+
+use crate::{
+    circuits::{
+        gate::{CircuitGate, Connect, GateType},
+        lookup::{
+            self,
+            tables::{GateLookupTable, LookupTable},
+        },
+        polynomial::COLUMNS,
+        polynomials::generic::GenericGateSpec,
+        wires::Wire,
+        witness::SolverEnv,
+    },
+    error::SolveError,
+};
+use ark_ff::PrimeField;
+use o1_utils::FieldHelpers;
+use std::array;
+
+/// Maximum number of bytes that the gadget can check, the bytes that fit in a field element
+pub const MAX_BYTES: usize = 31;
+
+/// Number of limbs checked by each row
+const LIMBS_PER_ROW: usize = 4;
+
+/// Number of bytes checked by each `RangeCheckU16` row
+const BYTES_PER_U16_ROW: usize = 8;
+
+// The layout of the gadget: its number of RangeCheckU16 rows, and of byte limbs
+struct Layout {
+    u16_rows: usize,
+    u8_limbs: usize,
+}
+
+impl Layout {
+    fn create(num_bytes: usize) -> Self {
+        assert!(
+            num_bytes > 0 && num_bytes <= MAX_BYTES,
+            "Byte range checks are only supported for 1 to 31 bytes"
+        );
+        Layout {
+            u16_rows: num_bytes / BYTES_PER_U16_ROW,
+            u8_limbs: num_bytes % BYTES_PER_U16_ROW,
+        }
+    }
+
+    fn u8_rows(&self) -> usize {
+        (self.u8_limbs + LIMBS_PER_ROW - 1) / LIMBS_PER_ROW
+    }
+
+    fn num_rows(&self) -> usize {
+        self.u16_rows + self.u8_rows() + 1
+    }
+
+    // The offset (in bits) of the i-th value of the gadget
+    fn offset(&self, i: usize) -> usize {
+        let u16_limbs = LIMBS_PER_ROW * self.u16_rows;
+        if i < u16_limbs {
+            16 * i
+        } else {
+            16 * u16_limbs + 8 * (i - u16_limbs)
+        }
+    }
+}
+
+/// Returns the number of rows of the byte range check gadget of `num_bytes` bytes
+/// Panics if `num_bytes` is not in `1..=31`
+pub fn range_check_bytes_rows(num_bytes: usize) -> usize {
+    Layout::create(num_bytes).num_rows()
+}
+
+/// Packs a byte array into field elements of [MAX_BYTES] bytes (the last one possibly shorter),
+/// in little-endian order, so that each of them can be checked with the gadget
+pub fn pack_bytes<F: PrimeField>(bytes: &[u8]) -> Vec<F> {
+    bytes
+        .chunks(MAX_BYTES)
+        .map(|chunk| F::from_le_bytes_mod_order(chunk))
+        .collect()
+}
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Extends a circuit with a byte range check gadget of `num_bytes` bytes
+    /// Includes:
+    /// - 1 RangeCheckU16 gate for every 8 bytes
+    /// - 1 RangeCheckU8 gate for every 4 of the remaining bytes
+    /// - 1 Generic gate to constrain the final value to zero
+    /// Input:
+    /// - gates     : the full circuit
+    /// - num_bytes : the number of bytes of the checked value, up to 31
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the checked value is in the first column of the first row of the gadget
+    pub fn extend_range_check_bytes(gates: &mut Vec<Self>, num_bytes: usize) -> usize {
+        let (next_row, mut bytes_gates) = Self::create_range_check_bytes(gates.len(), num_bytes);
+        gates.append(&mut bytes_gates);
+        next_row
+    }
+
+    /// Creates a byte range check gadget of `num_bytes` bytes, see [Self::extend_range_check_bytes]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_range_check_bytes(new_row: usize, num_bytes: usize) -> (usize, Vec<Self>) {
+        let layout = Layout::create(num_bytes);
+        let mut bytes_gates: Vec<_> = (0..layout.u16_rows)
+            .map(|_| GateType::RangeCheckU16)
+            .chain((0..layout.u8_rows()).map(|_| GateType::RangeCheckU8))
+            .enumerate()
+            .map(|(i, typ)| CircuitGate::new(typ, Wire::for_row(new_row + i), vec![]))
+            .collect();
+        let zero_row = bytes_gates.len();
+        bytes_gates.push(CircuitGate::create_generic_gadget(
+            Wire::for_row(new_row + zero_row),
+            GenericGateSpec::Const(F::zero()),
+            None,
+        ));
+
+        // wire the unused values of the last row to zero (indices are relative to the gadget, wires are not)
+        let last_limbs = layout.u8_limbs % LIMBS_PER_ROW;
+        if last_limbs != 0 {
+            for col in last_limbs..LIMBS_PER_ROW {
+                bytes_gates.connect_cell_pair((zero_row - 1, col), (zero_row, 0));
+            }
+        }
+
+        (new_row + bytes_gates.len(), bytes_gates)
+    }
+}
+
+/// Get the byte lookup table used by the `RangeCheckU8` gates
+pub fn lookup_table_u8<F: PrimeField>() -> LookupTable<F> {
+    lookup::tables::get_table::<F>(GateLookupTable::Byte)
+}
+
+/// Get the 16-bit lookup table used by the `RangeCheckU16` gates
+pub fn lookup_table_u16<F: PrimeField>() -> LookupTable<F> {
+    lookup::tables::get_table::<F>(GateLookupTable::U16)
+}
+
+/// Solves a `RangeCheckU16` or `RangeCheckU8` row, whose limbs have `limb_bits` bits, from its first value:
+/// the next values, including the first value of the next row, are the first value shifted by the limbs before them.
+/// Returns whether the values of the row are known
+///
+/// # Errors
+///
+/// Will give error if a computed value already has another value.
+pub fn solve_range_check_bytes<F: PrimeField>(
+    env: &mut SolverEnv<F>,
+    limb_bits: usize,
+) -> Result<bool, SolveError> {
+    if let Some(value) = env.curr(0) {
+        let big = value.to_biguint();
+        let shifted = |i: usize| F::from_biguint(&(&big >> (limb_bits * i))).unwrap();
+        for col in 1..LIMBS_PER_ROW {
+            env.set_curr(col, shifted(col))?;
+        }
+        env.set_next(0, shifted(LIMBS_PER_ROW))?;
+    }
+    Ok((0..LIMBS_PER_ROW).all(|col| env.curr(col).is_some()))
+}
+
+/// Extends the byte range check rows of a value of `num_bytes` bytes to the full witness
+/// Input
+/// - witness: full witness of the circuit
+/// - value: the value to be checked, a packed array of bytes in little-endian order (see [pack_bytes])
+/// - num_bytes: the number of bytes of the value, up to 31
+/// Panics if `value` does not fit in `num_bytes` bytes
+pub fn extend_range_check_bytes_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    value: F,
+    num_bytes: usize,
+) {
+    let layout = Layout::create(num_bytes);
+    let big = value.to_biguint();
+    assert!(
+        big.bits() <= 8 * num_bytes as u64,
+        "Value must fit in the given number of bytes"
+    );
+
+    let num_rows = layout.num_rows();
+    let bytes_witness: [Vec<F>; COLUMNS] = array::from_fn(|col| {
+        (0..num_rows)
+            .map(|row| {
+                if col < LIMBS_PER_ROW {
+                    F::from_biguint(&(&big >> layout.offset(LIMBS_PER_ROW * row + col))).unwrap()
+                } else {
+                    F::zero()
+                }
+            })
+            .collect()
+    });
+    for col in 0..COLUMNS {
+        witness[col].extend(bytes_witness[col].iter());
+    }
+}
@@ -20,6 +20,17 @@ use super::circuitgates::{RangeCheck0, RangeCheck1};
 
 pub const GATE_COUNT: usize = 2;
 
+/// Number of rows of a multi-range-check gadget
+pub const MULTI_RANGE_CHECK_ROWS: usize = 4;
+
+/// Returns the position of the cell containing the `limb`-th limb of the `i`-th value
+/// checked by a batch of multi-range-check gadgets starting at row `start_row`
+/// (see [CircuitGate::create_multi_range_checks]), as a `(row, column)` pair.
+pub fn multi_range_check_limb(start_row: usize, i: usize, limb: usize) -> (usize, usize) {
+    assert!(limb < 3, "A multi-range-check only has three limbs");
+    (start_row + MULTI_RANGE_CHECK_ROWS * i + limb, 0)
+}
+
 impl<F: PrimeField + SquareRootField> CircuitGate<F> {
     /// Create range check gate for constraining three 88-bit values.
     ///     Inputs the starting row
@@ -53,6 +64,30 @@ impl<F: PrimeField + SquareRootField> CircuitGate<F> {
         gates.extend_from_slice(&circuit_gates);
     }
 
+    /// Create a batch of `count` multi-range-check gadgets, one after the other,
+    /// with the copy constraints of each gadget.
+    ///     Inputs the starting row and the number of gadgets
+    ///     Outputs tuple (`next_row`, `circuit_gates`) where
+    ///       `next_row`      - next row after the gadgets
+    ///       `circuit_gates` - vector of circuit gates comprising the gadgets
+    /// The witness of the batch is created by [super::witness::create_multi_batch],
+    /// and the limbs of the values are at the positions given by [multi_range_check_limb].
+    pub fn create_multi_range_checks(start_row: usize, count: usize) -> (usize, Vec<Self>) {
+        let mut circuit_gates = Vec::with_capacity(MULTI_RANGE_CHECK_ROWS * count);
+        let mut next_row = start_row;
+        for _ in 0..count {
+            Self::extend_multi_range_check(&mut circuit_gates, &mut next_row);
+        }
+        (next_row, circuit_gates)
+    }
+
+    /// Create a batch of `count` multi-range-check gadgets by extending the existing gates
+    pub fn extend_multi_range_checks(gates: &mut Vec<Self>, curr_row: &mut usize, count: usize) {
+        let (next_row, circuit_gates) = Self::create_multi_range_checks(*curr_row, count);
+        *curr_row = next_row;
+        gates.extend_from_slice(&circuit_gates);
+    }
+
     /// Create single range check gate
     ///     Inputs the starting row
     ///     Outputs tuple (`next_row`, `circuit_gates`) where
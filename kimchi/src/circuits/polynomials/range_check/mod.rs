@@ -1,5 +1,7 @@
 //! Range check gate module
 
+pub mod bits;
+pub mod bytes;
 pub mod circuitgates;
 pub mod gadget;
 pub mod witness;
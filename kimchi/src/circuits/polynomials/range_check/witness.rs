@@ -5,6 +5,7 @@ use num_bigint::BigUint;
 use num_integer::Integer;
 use o1_utils::field_helpers::BigUintFieldHelpers;
 use o1_utils::{FieldHelpers, ForeignElement};
+use rayon::prelude::*;
 use std::array;
 
 use crate::circuits::witness::Variables;
@@ -219,6 +220,41 @@ pub fn extend_multi_from_fe<F: PrimeField>(
     extend_multi(witness, fe.limbs[0], fe.limbs[1], fe.limbs[2]);
 }
 
+/// Create the witness of a batch of multi-range-check gadgets, one for each value of at most 264 bits,
+/// decomposed into its three 88-bit limbs. The values are decomposed in parallel.
+/// The gates of the batch are created by
+/// [crate::circuits::gate::CircuitGate::create_multi_range_checks].
+///
+/// # Panics
+///
+/// Will panic if a value does not fit in 264 bits.
+pub fn create_multi_batch<F: PrimeField>(values: &[BigUint]) -> [Vec<F>; COLUMNS] {
+    let gadgets: Vec<[Vec<F>; COLUMNS]> = values
+        .par_iter()
+        .map(|value| {
+            assert!(
+                *value < BigUint::binary_modulus(),
+                "The values of a multi-range-check must fit in 264 bits"
+            );
+            create_multi_limbs(&value.to_field_limbs())
+        })
+        .collect();
+    array::from_fn(|col| {
+        gadgets
+            .iter()
+            .flat_map(|gadget| gadget[col].iter().copied())
+            .collect()
+    })
+}
+
+/// Extend an existing witness with a batch of multi-range-check gadgets, see [create_multi_batch]
+pub fn extend_multi_batch<F: PrimeField>(witness: &mut [Vec<F>; COLUMNS], values: &[BigUint]) {
+    let batch_witness = create_multi_batch(values);
+    for col in 0..COLUMNS {
+        witness[col].extend(batch_witness[col].iter())
+    }
+}
+
 /// Extend an existing witness with a single range check witness for foreign field element
 pub fn extend<F: PrimeField>(witness: &mut [Vec<F>; COLUMNS], fe: F) {
     let limbs_witness = create(fe);
@@ -15,11 +15,13 @@ use crate::{
         },
         polynomial::COLUMNS,
         wires::Wire,
-        witness::{self, VariableBitsCell, VariableCell, Variables, WitnessCell},
+        witness::{self, Solve, SolverEnv, VariableBitsCell, VariableCell, Variables, WitnessCell},
     },
+    error::SolveError,
     variable_map,
 };
 use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::BigUint;
 use std::{array, marker::PhantomData};
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -70,6 +72,44 @@ impl<F: PrimeField + SquareRootField> CircuitGate<F> {
         gates.len()
     }
 
+    /// Extend one rotation of a word copied from a cell of the circuit, with the range check of the word
+    /// Includes:
+    /// - 1 Rot64 gate and 1 RangeCheck0 gate to rotate the word (see [Self::extend_rot])
+    /// - 1 RangeCheck0 gate to constrain the word to 64 bits
+    /// Input:
+    /// - gates : the full circuit
+    /// - input : the cell of the word, as a `(row, column)` pair
+    /// - rot : the rotation offset
+    /// - side : the rotation side
+    /// - zero_row : the row of the Generic gate to constrain the 64-bit checks of the word and of the shifted word
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the rotated word should be copied to the cells which use it
+    pub fn extend_rot_from_cell(
+        gates: &mut Vec<Self>,
+        input: (usize, usize),
+        rot: u32,
+        side: RotMode,
+        zero_row: usize,
+    ) -> usize {
+        let rot_row = gates.len();
+        let (word_row, mut rot_gates) = Self::create_rot(rot_row, rot, side);
+        gates.append(&mut rot_gates);
+        gates.push(CircuitGate {
+            typ: GateType::RangeCheck0,
+            wires: Wire::for_row(word_row),
+            coeffs: vec![F::zero()],
+        });
+        // Copy the word of the input cell to the rotation and to its range check
+        gates.connect_cell_pair(input, (rot_row, 0));
+        gates.connect_cell_pair((rot_row, 0), (word_row, 0));
+        // Check that 2 most significant limbs of shifted and of the word are zero
+        gates.connect_64bit(zero_row, rot_row + 1);
+        gates.connect_64bit(zero_row, word_row);
+        gates.len()
+    }
+
     /// Create one rotation
     /// Right now it only creates a Generic gate followed by the Rot64 gates
     /// It allows to configure left or right rotation.
@@ -252,6 +292,41 @@ where
     }
 }
 
+impl<F: PrimeField> Solve<F> for Rot64<F> {
+    // The word is rotated by the offset of the coefficient, and the limbs of the bound are computed,
+    // as well as the shifted word and its limbs in the range check of the next row
+    fn solve(env: &mut SolverEnv<F>, coeffs: &[F]) -> Result<bool, SolveError> {
+        if let Some(word) = env.curr_u64(0) {
+            let two_to_rot: BigUint = coeffs[0].into();
+            let rot = two_to_rot.bits() - 1;
+            let shifted = (word as u128) << rot;
+            let excess = (shifted >> 64) as u64;
+            let shifted = shifted as u64;
+            let bound = (excess as u128 + (1u128 << 64) - (1u128 << rot)) as u64;
+            env.set_curr(1, F::from(shifted + excess))?;
+            env.set_curr(2, F::from(excess))?;
+            env.set_next(0, F::from(shifted))?;
+            for col in 1..COLUMNS {
+                if col >= 3 {
+                    env.set_curr(col, F::from(range_check_limb(bound, col)))?;
+                }
+                env.set_next(col, F::from(range_check_limb(shifted, col)))?;
+            }
+        }
+        Ok((0..COLUMNS).all(|col| env.curr(col).is_some()))
+    }
+}
+
+// The limb of a 64-bit value in a column of its RangeCheck0 row (see range_check_0_row)
+fn range_check_limb(value: u64, col: usize) -> u64 {
+    let (start, bits) = if col < 7 {
+        (88 - 12 * col, 12)
+    } else {
+        (2 * (COLUMNS - 1 - col), 2)
+    };
+    ((u128::from(value) >> start) as u64) & ((1 << bits) - 1)
+}
+
 // ROTATION WITNESS COMPUTATION
 
 fn layout_rot64<F: PrimeField>(curr_row: usize) -> [[Box<dyn WitnessCell<F>>; COLUMNS]; 2] {
@@ -349,3 +424,39 @@ pub fn extend_rot<F: PrimeField>(
         bound.into(),
     );
 }
+
+/// Extends the rot rows of a word copied from a cell to the full witness,
+/// with the range check row of the word (see [CircuitGate::extend_rot_from_cell])
+/// Input
+/// - witness: full witness of the circuit
+/// - input: cell of the word, as a `(row, column)` pair
+/// - word: claimed value of the word
+/// - rot:  rotation offset
+/// - side: side of the rotation, either left or right
+/// Panics if the claimed word is not the value of the input cell, or is longer than 64 bits
+pub fn extend_rot_from_cell<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    (row, col): (usize, usize),
+    word: F,
+    rot: u32,
+    side: RotMode,
+) {
+    assert_eq!(
+        witness[col][row], word,
+        "The claimed word must be the value of the input cell"
+    );
+    let word: BigUint = word.into();
+    let word = u64::try_from(word).expect("The word must be 64 bits long");
+    extend_rot(witness, word, rot, side);
+
+    let word_row = witness[0].len();
+    for col in witness.iter_mut() {
+        col.push(F::zero());
+    }
+    witness::init(
+        witness,
+        word_row,
+        &[range_check_0_row("word", word_row)],
+        &variable_map!["word" => F::from(word)],
+    );
+}
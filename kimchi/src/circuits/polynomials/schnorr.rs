@@ -0,0 +1,755 @@
+//! This module includes the Schnorr signature verification gadget over a native curve,
+//! for the signatures of Mina over Pallas, and its witness code generation.
+//! Note that this module does not include a new gate type, the gadget reuses the `Poseidon`,
+//! `VarBaseMul`, `CompleteAdd` and `Generic` gates, and the range check and foreign field gates
+//! of the ECDSA layout to check the parity of a coordinate.
+
+//~ The Schnorr gadget verifies a Mina signature $(r_x, s)$ of a message $m_1, \dots, m_k$ of native field elements
+//~ with a public key $P$, over a curve $E$ whose base field is the native field $\mathbb{F}_p$
+//~ and whose scalar field $\mathbb{F}_q$ is larger (as Pallas in a circuit over the Vesta scalar field).
+//~ The challenge $e$ is the Poseidon hash, with the kimchi sponge of the native field, of
+//~ $m_1, \dots, m_k, x_P, y_P, r_x$, from the sponge which absorbed the domain string
+//~ (padded with `*` to 20 bytes) and was squeezed, as the hasher of the Mina signer.
+//~ The signature is valid when $R = s G - e P$ is not the point at infinity,
+//~ $x_R = r_x$ and $y_R$ is even.
+//~
+//~ The gadget lays out:
+//~
+//~ * the constants $0$, $G$, $2 G$ and the third element of the initial sponge state in `Generic` gates $l - c = 0$,
+//~ * for each pair of elements of the hash input, a `Generic` row adding them to the sponge state,
+//~   and the 12 rows of a Poseidon permutation, so that $e$ is the first element of the last state,
+//~ * the variable base multiplications $V_e = 2 e P$ and $V_s = 2 s G$, with `VarBaseMul` gates
+//~   (see below) from the accumulators $2 P$ (a `CompleteAdd` gate) and $2 G$ (a constant),
+//~ * the checks that $P$ and $R = (r_x, y_R)$ are on the curve $y^2 = x^3 + b$ in `Generic` gates,
+//~ * the `CompleteAdd` gates of $2 R$ and $2 R + V_e$, which is copied to $V_s$,
+//~   with their infinity flags copied to zero, so that $2 R = 2 s G - 2 e P$ and $R = s G - e P$
+//~   since the order of the curve is odd,
+//~ * the parity check of $y_R$: its 3 limbs of 88 bits are canonical (as the elements of the ECDSA gadget),
+//~   recomposed into $y_R$ in `Generic` gates, and the least significant bit of the decomposition of the
+//~   lowest limb is copied to zero.
+//~
+//~ The `VarBaseMul` gates of $n$ bits (the number of bits of $p$) compute $(2 k' + 2^n + 1) T$ from the
+//~ accumulator $2 T$, for the bits of $k'$ from the most significant one.
+//~ The gadget multiplies by the shifted scalar $k' = k - d + w q$, with $d = (2^n + 1 - q) / 2$ and a boolean $w$
+//~ (so that $0 \leq k' < 2^n$), which gives $(2 k + (2 w + 1) q) T = 2 k T$.
+//~ The accumulation $k'$ of the bits of the gates is constrained to $k' - w q - k + d = 0$ in a `Generic` gate
+//~ with the boolean $w$, for $k = e$ and for $k = s$ (reduced modulo $p$).
+//~ Note that the bits are not constrained to be the canonical bits of $k'$,
+//~ so that the scalar is only constrained modulo $p$, as in the scalar multiplications of Mina.
+//~
+//~ The inputs of the gadget are at the positions given by `schnorr_input`:
+//~ the message, the public key and $r_x$ in the `Generic` gates of the hash input (in their order),
+//~ and $s$ in the output of the `Generic` gate of its shifted scalar.
+//~
+
+use crate::{
+    circuits::{
+        gate::{CircuitGate, Connect, GateType},
+        polynomial::COLUMNS,
+        polynomials::{
+            ecdsa::layout::{Bit, Cell, Elem, Layout, Limb},
+            generic::{DOUBLE_GENERIC_COEFFS, GENERIC_COEFFS, GENERIC_REGISTERS},
+            poseidon::{self, POS_ROWS_PER_HASH, SPONGE_WIDTH},
+            varbasemul,
+        },
+        wires::Wire,
+    },
+    curve::KimchiCurve,
+};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, Field, PrimeField, SquareRootField};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    poseidon::{ArithmeticSponge, Sponge},
+};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use o1_utils::{foreign_field::LIMB_BITS, FieldHelpers};
+use poly_commitment::commitment::CommitmentCurve;
+use std::array;
+
+/// Maximum number of bytes of a domain string
+pub const MAX_DOMAIN_BYTES: usize = 20;
+
+/// Number of rows of the constants of the gadget
+const CONSTANT_ROWS: usize = 3;
+
+/// Number of rows of the absorption of two elements: a generic row, the Poseidon rows and their output row
+const ABSORPTION_ROWS: usize = POS_ROWS_PER_HASH + 2;
+
+/// Number of rows of the checks that the public key and `R` are on the curve
+const CURVE_ROWS: usize = 3;
+
+/// A Mina signature over the curve `C`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SchnorrSignature<C: KimchiCurve> {
+    pub rx: C::BaseField,
+    pub s: C::ScalarField,
+}
+
+/// The inputs of the Schnorr gadget
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SchnorrInput {
+    /// The `i`-th element of the message
+    Message(usize),
+    /// The x-coordinate of the public key
+    PublicKeyX,
+    /// The y-coordinate of the public key
+    PublicKeyY,
+    /// The x-coordinate of `R` of the signature
+    Rx,
+    /// The scalar `s` of the signature, modulo the native modulus
+    S,
+}
+
+// The field element of a domain string, padded with `*` to 20 bytes, as in the Mina hasher
+fn domain_to_field<F: PrimeField>(domain: &str) -> F {
+    assert!(
+        domain.len() <= MAX_DOMAIN_BYTES,
+        "The domain string has at most 20 bytes"
+    );
+    let mut bytes = format!("{domain:*<MAX_DOMAIN_BYTES$}").into_bytes();
+    bytes.resize(F::size_in_bytes(), 0);
+    F::from_bytes(&bytes).expect("the domain string fits in a field element")
+}
+
+// The sponge which absorbed the domain string and was squeezed, the initial state of the challenges
+pub(crate) fn initial_sponge<C: KimchiCurve>(
+    domain: &str,
+) -> ArithmeticSponge<C::BaseField, PlonkSpongeConstantsKimchi>
+where
+    C::BaseField: PrimeField,
+{
+    let mut sponge = ArithmeticSponge::new(C::OtherCurve::sponge_params());
+    sponge.absorb(&[domain_to_field(domain)]);
+    sponge.squeeze();
+    sponge
+}
+
+pub(crate) fn coordinates<C: KimchiCurve>(point: &C) -> (C::BaseField, C::BaseField) {
+    point
+        .to_coordinates()
+        .expect("the point is not the point at infinity")
+}
+
+/// Returns the challenge of a signature, the hash of the message, of the public key and of `rx`
+pub fn schnorr_challenge<C: KimchiCurve>(
+    domain: &str,
+    message: &[C::BaseField],
+    public_key: &C,
+    rx: C::BaseField,
+) -> C::BaseField
+where
+    C::BaseField: PrimeField,
+{
+    let (x, y) = coordinates(public_key);
+    let mut sponge = initial_sponge::<C>(domain);
+    sponge.absorb(message);
+    sponge.absorb(&[x, y, rx]);
+    sponge.squeeze()
+}
+
+// The scalar of a native field element, such as a challenge, which is smaller than the scalar field modulus
+pub(crate) fn native_scalar<C: KimchiCurve>(elem: C::BaseField) -> C::ScalarField
+where
+    C::BaseField: PrimeField,
+{
+    C::ScalarField::from_biguint(&elem.to_biguint())
+        .expect("the base field is smaller than the scalar field")
+}
+
+/// Verifies a signature of a message with a public key, as the Mina signer
+pub fn verify_schnorr<C: KimchiCurve>(
+    domain: &str,
+    message: &[C::BaseField],
+    public_key: &C,
+    signature: &SchnorrSignature<C>,
+) -> bool
+where
+    C::BaseField: PrimeField,
+{
+    let challenge = schnorr_challenge(domain, message, public_key, signature.rx);
+    let e = native_scalar::<C>(challenge);
+    let r =
+        C::prime_subgroup_generator().mul(signature.s.into_repr()) - public_key.mul(e.into_repr());
+    match r.into_affine().to_coordinates() {
+        Some((x, y)) => x == signature.rx && y.into_repr().is_even(),
+        None => false,
+    }
+}
+
+// The number of bits of the variable base multiplications, which is a multiple of 5
+fn mul_bits<F: PrimeField>() -> usize {
+    let num_bits = F::size_in_bits();
+    assert_eq!(num_bits % 5, 0, "the scalars are multiples of 5 bits");
+    num_bits
+}
+
+// The number of rows of a variable base multiplication, two rows per 5 bits
+pub(crate) fn mul_rows<F: PrimeField>() -> usize {
+    2 * mul_bits::<F>() / 5
+}
+
+// The shift d = (2^n + 1 - q) / 2 of the scalars of the variable base multiplications of n bits
+pub(crate) fn scalar_shift<C: KimchiCurve>() -> BigUint
+where
+    C::BaseField: PrimeField,
+{
+    let q = C::ScalarField::modulus_biguint();
+    assert!(
+        q > C::BaseField::modulus_biguint(),
+        "the scalar field is larger than the base field"
+    );
+    ((BigUint::one() << mul_bits::<C::BaseField>()) + 1u32 - q) / 2u32
+}
+
+// The bits of the shifted scalar k - d + w q (from the most significant one), and the boolean w
+pub(crate) fn shifted_bits<C: KimchiCurve>(scalar: &BigUint) -> (Vec<bool>, bool)
+where
+    C::BaseField: PrimeField,
+{
+    let d = scalar_shift::<C>();
+    let wrap = scalar < &d;
+    let shifted = if wrap {
+        scalar + C::ScalarField::modulus_biguint() - d
+    } else {
+        scalar - d
+    };
+    let bits = (0..mul_bits::<C::BaseField>())
+        .rev()
+        .map(|i| shifted.bit(i as u64))
+        .collect();
+    (bits, wrap)
+}
+
+// The curve constant b of y^2 = x^3 + b, checked on the generator and its double
+pub(crate) fn curve_b<C: KimchiCurve>() -> C::BaseField {
+    let rhs = |(x, y): (C::BaseField, C::BaseField)| y.square() - x.square() * x;
+    let generator = C::prime_subgroup_generator();
+    let b = rhs(coordinates(&generator));
+    let double = generator.into_projective().double().into_affine();
+    assert_eq!(rhs(coordinates(&double)), b, "the curve is y^2 = x^3 + b");
+    b
+}
+
+// The first rows of the parts of the gadget, relative to its first row
+struct SchnorrRows {
+    scalars: usize,
+    curve: usize,
+    double_key: usize,
+    mul_key: usize,
+    mul_generator: usize,
+    double_r: usize,
+    sum: usize,
+    parity: usize,
+}
+
+impl SchnorrRows {
+    fn new<F: PrimeField>(msg_len: usize) -> Self {
+        let scalars = scalars_row(msg_len);
+        let curve = scalars + 2;
+        let double_key = curve + CURVE_ROWS;
+        let mul_key = double_key + 1;
+        let mul_generator = mul_key + mul_rows::<F>();
+        let double_r = mul_generator + mul_rows::<F>();
+        let sum = double_r + 1;
+        Self {
+            scalars,
+            curve,
+            double_key,
+            mul_key,
+            mul_generator,
+            double_r,
+            sum,
+            parity: sum + 1,
+        }
+    }
+}
+
+// The number of absorptions of pairs of the message, the public key and rx
+fn num_absorptions(msg_len: usize) -> usize {
+    (msg_len + 3 + 1) / 2
+}
+
+// The row of the absorption of the `i`-th pair of the hash input
+fn absorption_row(i: usize) -> usize {
+    CONSTANT_ROWS + ABSORPTION_ROWS * i
+}
+
+// The output row of the Poseidon permutation of the `i`-th pair of the hash input
+fn permutation_output_row(i: usize) -> usize {
+    absorption_row(i) + 1 + POS_ROWS_PER_HASH
+}
+
+// The cell of the `i`-th element of the hash input
+fn hash_input(i: usize) -> Cell {
+    (absorption_row(i / 2), GENERIC_REGISTERS * (i % 2))
+}
+
+// The row of the shifted scalars, after the absorptions
+fn scalars_row(msg_len: usize) -> usize {
+    absorption_row(num_absorptions(msg_len))
+}
+
+/// Returns the position of the cell of an input of the Schnorr gadget of a message of `msg_len` elements
+/// starting at row `new_row`, as a `(row, column)` pair
+pub fn schnorr_input(new_row: usize, msg_len: usize, input: SchnorrInput) -> (usize, usize) {
+    let (row, col) = match input {
+        SchnorrInput::Message(i) => {
+            assert!(i < msg_len, "The element is in the message");
+            hash_input(i)
+        }
+        SchnorrInput::PublicKeyX => hash_input(msg_len),
+        SchnorrInput::PublicKeyY => hash_input(msg_len + 1),
+        SchnorrInput::Rx => hash_input(msg_len + 2),
+        SchnorrInput::S => (scalars_row(msg_len) + 1, 5),
+    };
+    (new_row + row, col)
+}
+
+// The first cell of the `half`-th generic half of the curve checks
+fn curve_half(curve_row: usize, half: usize) -> Cell {
+    (curve_row + half / 2, GENERIC_REGISTERS * (half % 2))
+}
+
+// A double generic gate of two halves
+pub(crate) fn generic_gate<F: PrimeField>(
+    row: usize,
+    halves: [[F; GENERIC_COEFFS]; 2],
+) -> CircuitGate<F> {
+    let mut coeffs = [F::zero(); DOUBLE_GENERIC_COEFFS];
+    coeffs[..GENERIC_COEFFS].copy_from_slice(&halves[0]);
+    coeffs[GENERIC_COEFFS..].copy_from_slice(&halves[1]);
+    CircuitGate::create_generic(Wire::for_row(row), coeffs)
+}
+
+// The constants of the gadget: zero, the generator, its double and the third element of the initial state
+fn constants<C: KimchiCurve>(domain: &str) -> [C::BaseField; 2 * CONSTANT_ROWS]
+where
+    C::BaseField: PrimeField,
+{
+    let generator = C::prime_subgroup_generator();
+    let (gx, gy) = coordinates(&generator);
+    let (g2x, g2y) = coordinates(&generator.into_projective().double().into_affine());
+    let state = initial_sponge::<C>(domain).state;
+    [C::BaseField::zero(), gx, gy, g2x, g2y, state[2]]
+}
+
+// The cells of the constants of the gadget, in their order
+const ZERO: Cell = (0, 0);
+const GENERATOR: [Cell; 2] = [(0, 3), (1, 0)];
+const DOUBLE_GENERATOR: [Cell; 2] = [(1, 3), (2, 0)];
+const INITIAL_STATE: Cell = (2, 3);
+
+// Lays out the bits of the lowest limb of an element, and returns its least significant bit
+pub(crate) fn layout_lowest_bit<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    elem: &Elem<F>,
+) -> Bit {
+    let low = Elem {
+        limbs: [
+            elem.limbs[0],
+            Limb::Const(F::zero()),
+            Limb::Const(F::zero()),
+        ],
+        value: elem
+            .value
+            .as_ref()
+            .map(|value| value % (BigUint::one() << LIMB_BITS)),
+    };
+    let bits = layout.bits(&low, LIMB_BITS);
+    *bits.last().expect("the limb has bits")
+}
+
+// Lays out the parity of a native field element: its canonical limbs, their recomposition,
+// and the bits of its lowest limb, and returns the cell of the element and its least significant bit
+pub(crate) fn layout_parity<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    value: Option<F>,
+) -> (Cell, Bit) {
+    let elem = layout.witness(value.map(|value| value.to_biguint()), &F::modulus_biguint());
+    let cell = layout.native(&elem);
+    (cell, layout_lowest_bit(layout, &elem))
+}
+
+impl<F: PrimeField + SquareRootField> CircuitGate<F> {
+    /// Extends a circuit with the verification of a Mina signature over the curve `C` of base field `F`
+    /// Includes:
+    /// - the constants, the Poseidon hash of the challenge and the shifted scalars in generic gates
+    /// - the variable base multiplications and the complete additions of the verification equation
+    /// - the parity check of `R` (see the module)
+    /// Input:
+    /// - gates   : the full circuit
+    /// - domain  : the domain string of the hash of the challenge, up to 20 bytes
+    /// - msg_len : the number of elements of the message
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the message, the public key and the signature (see [schnorr_input]) should be copied from other cells
+    pub fn extend_schnorr<C: KimchiCurve<BaseField = F>>(
+        gates: &mut Vec<Self>,
+        domain: &str,
+        msg_len: usize,
+    ) -> usize {
+        let (next_row, mut schnorr_gates) = Self::create_schnorr::<C>(gates.len(), domain, msg_len);
+        gates.append(&mut schnorr_gates);
+        next_row
+    }
+
+    /// Creates the verification of a Mina signature, see [Self::extend_schnorr]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_schnorr<C: KimchiCurve<BaseField = F>>(
+        new_row: usize,
+        domain: &str,
+        msg_len: usize,
+    ) -> (usize, Vec<Self>) {
+        let rows = SchnorrRows::new::<F>(msg_len);
+        let q = F::from_biguint(&(C::ScalarField::modulus_biguint() % F::modulus_biguint()))
+            .expect("the value is reduced");
+        let d =
+            F::from_biguint(&scalar_shift::<C>()).expect("the shift is smaller than the modulus");
+        let b = curve_b::<C>();
+        let initial_state = initial_sponge::<C>(domain).state;
+        let round_constants = &C::OtherCurve::sponge_params().round_constants;
+        let (zero, one) = (F::zero(), F::one());
+
+        // the constants l - c = 0
+        let mut gates: Vec<Self> = constants::<C>(domain)
+            .chunks(2)
+            .enumerate()
+            .map(|(i, pair)| {
+                generic_gate(
+                    new_row + i,
+                    [
+                        [one, zero, zero, zero, -pair[0]],
+                        [one, zero, zero, zero, -pair[1]],
+                    ],
+                )
+            })
+            .collect();
+
+        // the absorptions s + a - s' = 0, from the initial state s for the first one
+        let hash_len = msg_len + 3;
+        for i in 0..num_absorptions(msg_len) {
+            let halves = array::from_fn(|half| {
+                if 2 * i + half >= hash_len {
+                    [zero; GENERIC_COEFFS]
+                } else if i == 0 {
+                    [one, zero, -one, zero, initial_state[half]]
+                } else {
+                    [one, one, -one, zero, zero]
+                }
+            });
+            gates.push(generic_gate(new_row + gates.len(), halves));
+            let row = new_row + gates.len();
+            let (mut poseidon_gates, _) = CircuitGate::create_poseidon_gadget(
+                row,
+                [Wire::for_row(row), Wire::for_row(row + POS_ROWS_PER_HASH)],
+                round_constants,
+            );
+            gates.append(&mut poseidon_gates);
+        }
+
+        // the shifted scalars w * w - w = 0 and k' - w q - k + d = 0
+        for _ in 0..2 {
+            gates.push(generic_gate(
+                new_row + gates.len(),
+                [[-one, zero, zero, one, zero], [one, -q, -one, zero, d]],
+            ));
+        }
+
+        // the curve checks x * x - xx = 0, xx * x - xxx = 0 and y * y - xxx - b = 0 (of P and of R)
+        let square = [zero, zero, -one, one, zero];
+        let on_curve = [zero, zero, -one, one, -b];
+        for halves in [[square, square], [on_curve, square], [square, on_curve]] {
+            gates.push(generic_gate(new_row + gates.len(), halves));
+        }
+
+        // the double of the public key, the multiplications, the double of R and the sum
+        let complete_add =
+            |row: usize| CircuitGate::new(GateType::CompleteAdd, Wire::for_row(row), vec![]);
+        gates.push(complete_add(new_row + gates.len()));
+        for _ in 0..2 * (mul_rows::<F>() / 2) {
+            let row = new_row + gates.len();
+            gates.extend(CircuitGate::create_vbmul(&[
+                Wire::for_row(row),
+                Wire::for_row(row + 1),
+            ]));
+        }
+        gates.push(complete_add(new_row + gates.len()));
+        gates.push(complete_add(new_row + gates.len()));
+
+        // the parity of the y-coordinate of R
+        let mut layout = Layout::new(new_row + rows.parity, false);
+        let (y_r, parity) = layout_parity(&mut layout, None);
+        layout.copy(&Limb::Const(zero), parity.cell);
+        let (mut parity_gates, _) = layout.finalize();
+        gates.append(&mut parity_gates);
+
+        // wire the gadget (indices are relative to the gadget, wires are not)
+        let key_x = hash_input(msg_len);
+        let key_y = hash_input(msg_len + 1);
+        let rx = hash_input(msg_len + 2);
+        let ry = (rows.double_r, 1);
+
+        // the states of the sponge
+        for i in 0..num_absorptions(msg_len) {
+            let row = absorption_row(i);
+            for half in 0..2 {
+                let input = (row + 1, half);
+                if 2 * i + half >= hash_len {
+                    gates.connect_cell_pair((permutation_output_row(i - 1), half), input);
+                } else {
+                    if i > 0 {
+                        gates.connect_cell_pair(
+                            (permutation_output_row(i - 1), half),
+                            (row, GENERIC_REGISTERS * half + 1),
+                        );
+                    }
+                    gates.connect_cell_pair((row, GENERIC_REGISTERS * half + 2), input);
+                }
+            }
+            let state = if i == 0 {
+                INITIAL_STATE
+            } else {
+                (permutation_output_row(i - 1), 2)
+            };
+            gates.connect_cell_pair(state, (row + 1, 2));
+        }
+
+        // the shifted scalars of e and s, and their multiplications
+        let e = (permutation_output_row(num_absorptions(msg_len) - 1), 0);
+        for (i, mul) in [rows.mul_key, rows.mul_generator].into_iter().enumerate() {
+            let row = rows.scalars + i;
+            gates.connect_cell_pair((row, 0), (row, 1));
+            gates.connect_cell_pair((row, 0), (row, 4));
+            gates.connect_cell_pair((mul + mul_rows::<F>() - 2, 5), (row, 3));
+            gates.connect_cell_pair(ZERO, (mul, 4));
+            for chunk in 1..mul_rows::<F>() / 2 {
+                let row = mul + 2 * chunk;
+                gates.connect_cell_pair((row - 2, 5), (row, 4));
+                gates.connect_cell_pair((row - 1, 0), (row, 2));
+                gates.connect_cell_pair((row - 1, 1), (row, 3));
+            }
+        }
+        gates.connect_cell_pair(e, (rows.scalars, 5));
+        for chunk in 0..mul_rows::<F>() / 2 {
+            let (key_row, generator_row) =
+                (rows.mul_key + 2 * chunk, rows.mul_generator + 2 * chunk);
+            gates.connect_cell_pair(key_x, (key_row, 0));
+            gates.connect_cell_pair(key_y, (key_row, 1));
+            gates.connect_cell_pair(GENERATOR[0], (generator_row, 0));
+            gates.connect_cell_pair(GENERATOR[1], (generator_row, 1));
+        }
+        gates.connect_cell_pair(DOUBLE_GENERATOR[0], (rows.mul_generator, 2));
+        gates.connect_cell_pair(DOUBLE_GENERATOR[1], (rows.mul_generator, 3));
+
+        // the curve checks of the public key and of R
+        for (i, (x, y)) in [(key_x, key_y), (rx, ry)].into_iter().enumerate() {
+            // the cells of x * x - xx, xx * x - xxx and y * y - xxx - b
+            let [square, cube, on_curve] = [0, 1, 2].map(|j| curve_half(rows.curve, 3 * i + j));
+            gates.connect_cell_pair(x, square);
+            gates.connect_cell_pair(x, (square.0, square.1 + 1));
+            gates.connect_cell_pair((square.0, square.1 + 2), cube);
+            gates.connect_cell_pair(x, (cube.0, cube.1 + 1));
+            gates.connect_cell_pair(y, on_curve);
+            gates.connect_cell_pair(y, (on_curve.0, on_curve.1 + 1));
+            gates.connect_cell_pair((cube.0, cube.1 + 2), (on_curve.0, on_curve.1 + 2));
+        }
+
+        // 2 P, 2 R and 2 R + 2 e P = 2 s G
+        let double_key = rows.double_key;
+        for (col, cell) in [key_x, key_y, key_x, key_y].into_iter().enumerate() {
+            gates.connect_cell_pair(cell, (double_key, col));
+        }
+        gates.connect_cell_pair((double_key, 4), (rows.mul_key, 2));
+        gates.connect_cell_pair((double_key, 5), (rows.mul_key, 3));
+        gates.connect_cell_pair(rx, (rows.double_r, 0));
+        gates.connect_cell_pair(rx, (rows.double_r, 2));
+        gates.connect_cell_pair(ry, (rows.double_r, 3));
+        let sum = rows.sum;
+        for col in 0..2 {
+            gates.connect_cell_pair((rows.double_r, 4 + col), (sum, col));
+            gates.connect_cell_pair((rows.mul_key + mul_rows::<F>() - 1, col), (sum, 2 + col));
+            gates.connect_cell_pair(
+                (rows.mul_generator + mul_rows::<F>() - 1, col),
+                (sum, 4 + col),
+            );
+        }
+        for row in [double_key, rows.double_r, sum] {
+            gates.connect_cell_pair(ZERO, (row, 6));
+        }
+        gates.connect_cell_pair(ry, (y_r.0 - new_row, y_r.1));
+
+        (new_row + gates.len(), gates)
+    }
+}
+
+// Fills the witness of a complete addition row, and returns the sum
+pub(crate) fn complete_add_witness<F: Field>(
+    witness: &mut [Vec<F>; COLUMNS],
+    row: usize,
+    (x1, y1): (F, F),
+    (x2, y2): (F, F),
+) -> (F, F) {
+    let same_x = x1 == x2;
+    let inf = same_x && y1 != y2;
+    let s = if same_x {
+        let x1_squared = x1.square();
+        (x1_squared.double() + x1_squared) / y1.double()
+    } else {
+        (y2 - y1) / (x2 - x1)
+    };
+    let x3 = s.square() - x1 - x2;
+    let y3 = s * (x1 - x3) - y1;
+    let inf_z = if inf {
+        (y2 - y1).inverse().expect("the y-coordinates are distinct")
+    } else {
+        F::zero()
+    };
+    let x21_inv = (x2 - x1).inverse().unwrap_or_else(F::zero);
+    let values = [
+        x1,
+        y1,
+        x2,
+        y2,
+        x3,
+        y3,
+        F::from(u64::from(inf)),
+        F::from(u64::from(same_x)),
+        s,
+        inf_z,
+        x21_inv,
+    ];
+    for (col, value) in values.into_iter().enumerate() {
+        witness[col][row] = value;
+    }
+    (x3, y3)
+}
+
+/// Extends the witness with the verification of a Mina signature, and returns whether the signature is valid,
+/// since the witness of an invalid signature does not satisfy the gadget
+/// Input
+/// - witness: full witness of the circuit
+/// - domain: the domain string of the hash of the challenge, up to 20 bytes
+/// - message: the elements of the message
+/// - public_key: the public key, which is not the point at infinity
+/// - signature: the signature
+pub fn extend_schnorr_witness<C: KimchiCurve>(
+    witness: &mut [Vec<C::BaseField>; COLUMNS],
+    domain: &str,
+    message: &[C::BaseField],
+    public_key: &C,
+    signature: &SchnorrSignature<C>,
+) -> bool
+where
+    C::BaseField: PrimeField + SquareRootField,
+{
+    let rows = SchnorrRows::new::<C::BaseField>(message.len());
+    let mut w: [Vec<C::BaseField>; COLUMNS] =
+        array::from_fn(|_| vec![C::BaseField::zero(); rows.parity]);
+    let (key_x, key_y) = coordinates(public_key);
+
+    // the constants
+    for (i, constant) in constants::<C>(domain).into_iter().enumerate() {
+        w[GENERIC_REGISTERS * (i % 2)][i / 2] = constant;
+    }
+
+    // the absorptions and the permutations
+    let mut hash_input = message.to_vec();
+    hash_input.extend([key_x, key_y, signature.rx]);
+    let mut state = initial_sponge::<C>(domain).state;
+    for (i, pair) in hash_input.chunks(2).enumerate() {
+        let row = absorption_row(i);
+        let mut input: [C::BaseField; SPONGE_WIDTH] = array::from_fn(|j| state[j]);
+        for (half, value) in pair.iter().enumerate() {
+            let col = GENERIC_REGISTERS * half;
+            w[col][row] = *value;
+            w[col + 1][row] = if i == 0 {
+                C::BaseField::zero()
+            } else {
+                state[half]
+            };
+            input[half] += value;
+            w[col + 2][row] = input[half];
+        }
+        poseidon::generate_witness(row + 1, C::OtherCurve::sponge_params(), &mut w, input);
+        let output = permutation_output_row(i);
+        state = (0..SPONGE_WIDTH).map(|j| w[j][output]).collect();
+    }
+    let challenge = state[0];
+
+    // the multiplications 2 e P and 2 s G, and their shifted scalars
+    let double_key = complete_add_witness(&mut w, rows.double_key, (key_x, key_y), (key_x, key_y));
+    let generator = C::prime_subgroup_generator();
+    let double_generator = coordinates(&generator.into_projective().double().into_affine());
+    let s = signature.s.to_biguint();
+    let scalars = [
+        (challenge.to_biguint(), challenge),
+        (
+            s.clone(),
+            C::BaseField::from_biguint(&(s % C::BaseField::modulus_biguint()))
+                .expect("the value is reduced"),
+        ),
+    ];
+    let muls = [
+        (rows.mul_key, (key_x, key_y), double_key),
+        (
+            rows.mul_generator,
+            coordinates(&generator),
+            double_generator,
+        ),
+    ];
+    let mut results = vec![];
+    for (i, ((scalar, scalar_field), (row, base, acc))) in scalars.into_iter().zip(muls).enumerate()
+    {
+        let (bits, wrap) = shifted_bits::<C>(&scalar);
+        let result = varbasemul::witness(&mut w, row, base, &bits, acc);
+        let scalar_row = rows.scalars + i;
+        let wrap = C::BaseField::from(u64::from(wrap));
+        w[0][scalar_row] = wrap;
+        w[1][scalar_row] = wrap;
+        w[3][scalar_row] = result.n;
+        w[4][scalar_row] = wrap;
+        w[5][scalar_row] = scalar_field;
+        results.push(result.acc);
+    }
+
+    // R = s G - e P, or the generator when it is the point at infinity
+    let e = native_scalar::<C>(challenge);
+    let r = (generator.mul(signature.s.into_repr()) - public_key.mul(e.into_repr())).into_affine();
+    let (rx, ry) = r
+        .to_coordinates()
+        .unwrap_or_else(|| coordinates(&generator));
+
+    // the curve checks
+    for (i, (x, y)) in [(key_x, key_y), (rx, ry)].into_iter().enumerate() {
+        let xx = x.square();
+        let values = [[x, x, xx], [xx, x, xx * x], [y, y, xx * x]];
+        for (j, [left, right, output]) in values.into_iter().enumerate() {
+            let (row, col) = curve_half(rows.curve, 3 * i + j);
+            w[col][row] = left;
+            w[col + 1][row] = right;
+            w[col + 2][row] = output;
+        }
+    }
+
+    // 2 R and 2 R + 2 e P
+    let double_r = complete_add_witness(&mut w, rows.double_r, (rx, ry), (rx, ry));
+    complete_add_witness(&mut w, rows.sum, double_r, results[0]);
+
+    // the parity of the y-coordinate of R
+    let mut layout = Layout::new(witness[0].len() + rows.parity, true);
+    let (_, parity) = layout_parity(&mut layout, Some(ry));
+    layout.copy(&Limb::Const(C::BaseField::zero()), parity.cell);
+    let (_, parity_witness) = layout.finalize();
+    let parity_witness = parity_witness.expect("the witness is laid out");
+    for ((col, cells), parity_cells) in witness.iter_mut().zip(w).zip(parity_witness) {
+        col.extend(cells);
+        col.extend(parity_cells);
+    }
+
+    verify_schnorr(domain, message, public_key, signature)
+}
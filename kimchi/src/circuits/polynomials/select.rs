@@ -0,0 +1,239 @@
+//! This module includes the definition of the `Select` circuit gate for the conditional selection
+//! between words, `out = b ? x : y`, and its witness code generation.
+//! The gadgets select between words sharing a condition,
+//! such as the limbs of foreign field elements.
+
+use crate::{
+    circuits::{
+        argument::{Argument, ArgumentEnv, ArgumentType},
+        expr::{constraints::ExprOps, Cache},
+        gate::{CircuitGate, GateType},
+        polynomial::COLUMNS,
+        wires::Wire,
+        witness::{Solve, SolverEnv},
+    },
+    error::SolveError,
+};
+use ark_ff::PrimeField;
+use std::{array, marker::PhantomData};
+
+/// Number of words selected by a `Select` row
+pub const SELECT_WORDS: usize = 2;
+
+/// Number of limbs of the foreign field elements selected by [CircuitGate::create_select_foreign]
+pub const FOREIGN_LIMBS: usize = 3;
+
+/// Returns the cells `(x, y, out)` of the `i`-th word of a selection gadget
+/// starting at row `new_row`, as `(row, column)` pairs
+pub fn select_cells(new_row: usize, i: usize) -> [(usize, usize); 3] {
+    let row = new_row + i / SELECT_WORDS;
+    let col = 1 + 3 * (i % SELECT_WORDS);
+    [(row, col), (row, col + 1), (row, col + 2)]
+}
+
+/// Returns the cell of the condition of a selection gadget starting at row `new_row`,
+/// as a `(row, column)` pair
+pub fn select_condition(new_row: usize) -> (usize, usize) {
+    (new_row, 0)
+}
+
+impl<F: PrimeField> CircuitGate<F> {
+    /// Extends a circuit with the selection between `words` pairs of words with the same condition
+    /// Includes:
+    /// - ceil(words/2) Select gates, whose conditions are wired together
+    /// Input:
+    /// - gates : the full circuit
+    /// - words : the number of selected words
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the condition (see [select_condition]) and the words (see [select_cells])
+    ///   should be copied from other cells
+    pub fn extend_select(gates: &mut Vec<Self>, words: usize) -> usize {
+        let (next_row, mut select_gates) = Self::create_select(gates.len(), words);
+        gates.append(&mut select_gates);
+        next_row
+    }
+
+    /// Creates the selection between `words` pairs of words, see [Self::extend_select]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_select(new_row: usize, words: usize) -> (usize, Vec<Self>) {
+        assert!(words > 0, "the selection needs at least one word");
+        let num_rows = (words + SELECT_WORDS - 1) / SELECT_WORDS;
+        let select_gates = (0..num_rows)
+            .map(|i| {
+                // the conditions of the rows are wired in a cycle
+                let mut wires = Wire::for_row(new_row + i);
+                wires[0] = Wire {
+                    row: new_row + (i + 1) % num_rows,
+                    col: 0,
+                };
+                CircuitGate::new(GateType::Select, wires, vec![])
+            })
+            .collect();
+        (new_row + num_rows, select_gates)
+    }
+
+    /// Extends a circuit with the selection between two foreign field elements
+    /// Includes:
+    /// - 2 Select gates, the first one selecting the two lowest limbs
+    /// Input:
+    /// - gates : the full circuit
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the condition and the limbs should be copied from other cells (see [Self::extend_select])
+    pub fn extend_select_foreign(gates: &mut Vec<Self>) -> usize {
+        Self::extend_select(gates, FOREIGN_LIMBS)
+    }
+
+    /// Creates the selection between two foreign field elements, see [Self::extend_select_foreign]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_select_foreign(new_row: usize) -> (usize, Vec<Self>) {
+        Self::create_select(new_row, FOREIGN_LIMBS)
+    }
+}
+
+//~ `Select` constrains the conditional selection $out_i = b ? x_i : y_i$ of two words
+//~ for a boolean $b$, written as
+//~
+//~ $$out_i = y_i + b \cdot (x_i - y_i)$$
+//~
+//~ * This circuit gate is used to express the branches of the gadgets, instead of generic gates.
+//~ * This gate operates on the `Curr` row.
+//~
+//~ The condition and the words are in wired columns,
+//~ so that they can be copied from (or to) other gates.
+//~ The selection between more than two words, such as the three limbs of a foreign field element,
+//~ uses several rows whose conditions are wired together.
+//~ A word left unused is zero, which satisfies the constraints.
+//~
+//~ | Column | `Curr`        |
+//~ | ------ | ------------- |
+//~ |      0 | copy `b`      |
+//~ |      1 | copy `x0`     |
+//~ |      2 | copy `y0`     |
+//~ |      3 | copy `out0`   |
+//~ |      4 | copy `x1`     |
+//~ |      5 | copy `y1`     |
+//~ |      6 | copy `out1`   |
+//~
+//~ with the following constraints:
+//~
+//~ * $b \cdot (b - 1) = 0$
+//~ * $out_0 - y_0 - b \cdot (x_0 - y_0) = 0$
+//~ * $out_1 - y_1 - b \cdot (x_1 - y_1) = 0$
+//~
+#[derive(Default)]
+pub struct Select<F>(PhantomData<F>);
+
+impl<F> Argument<F> for Select<F>
+where
+    F: PrimeField,
+{
+    const ARGUMENT_TYPE: ArgumentType = ArgumentType::Gate(GateType::Select);
+    const CONSTRAINTS: u32 = 3;
+
+    // Constraints for the selection between two pairs of words
+    //   * Operates on Curr row
+    //   * The condition is shared by the words
+    fn constraint_checks<T: ExprOps<F>>(env: &ArgumentEnv<F, T>, _cache: &mut Cache) -> Vec<T> {
+        let b = env.witness_curr(0);
+
+        // C1: the condition is a bit
+        let mut constraints = vec![b.boolean()];
+
+        // C2, C3: out = y + b * (x - y)
+        for i in 0..SELECT_WORDS {
+            let x = env.witness_curr(1 + 3 * i);
+            let y = env.witness_curr(2 + 3 * i);
+            let out = env.witness_curr(3 + 3 * i);
+            constraints.push(out - y.clone() - b.clone() * (x - y));
+        }
+
+        constraints
+    }
+}
+
+impl<F: PrimeField> Solve<F> for Select<F> {
+    // The selected words are copied to the outputs (or back), once the condition is known
+    fn solve(env: &mut SolverEnv<F>, _coeffs: &[F]) -> Result<bool, SolveError> {
+        let b = match env.curr(0) {
+            Some(b) if b.is_zero() || b.is_one() => b,
+            _ => return Ok(false),
+        };
+        let mut solved = true;
+        for i in 0..SELECT_WORDS {
+            let selected = if b.is_one() { 1 + 3 * i } else { 2 + 3 * i };
+            let out = 3 + 3 * i;
+            match (env.curr(selected), env.curr(out)) {
+                (Some(value), None) => env.set_curr(out, value)?,
+                (None, Some(value)) => env.set_curr(selected, value)?,
+                (Some(_), Some(_)) => (),
+                // the words left unused are zero
+                (None, None) => {
+                    solved &= env.curr(1 + 3 * i).is_none() && env.curr(2 + 3 * i).is_none()
+                }
+            }
+        }
+        Ok(solved)
+    }
+}
+
+/// Extends the selection rows to the full witness
+/// Input
+/// - witness: full witness of the circuit
+/// - condition: whether the words of `x` are selected, instead of the words of `y`
+/// - x: the words selected if the condition holds
+/// - y: the words selected otherwise
+/// Output
+/// - the selected words
+pub fn extend_select_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    condition: bool,
+    x: &[F],
+    y: &[F],
+) -> Vec<F> {
+    assert_eq!(x.len(), y.len(), "the selection is between as many words");
+    let out: Vec<F> = x
+        .iter()
+        .zip(y)
+        .map(|(x, y)| if condition { *x } else { *y })
+        .collect();
+    let words: Vec<[F; 3]> = (0..x.len()).map(|i| [x[i], y[i], out[i]]).collect();
+    for row_words in words.chunks(SELECT_WORDS) {
+        let select_witness: [F; COLUMNS] = array::from_fn(|col| match col {
+            0 => F::from(condition),
+            1..=6 => row_words
+                .get((col - 1) / 3)
+                .map_or(F::zero(), |word| word[(col - 1) % 3]),
+            _ => F::zero(),
+        });
+        for (col, value) in witness.iter_mut().zip(select_witness) {
+            col.push(value);
+        }
+    }
+    out
+}
+
+/// Extends the witness with the selection between two foreign field elements, given by their limbs
+/// Input
+/// - witness: full witness of the circuit
+/// - condition: whether `x` is selected, instead of `y`
+/// - x: the limbs of the element selected if the condition holds
+/// - y: the limbs of the element selected otherwise
+/// Output
+/// - the limbs of the selected element
+pub fn extend_select_foreign_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    condition: bool,
+    x: [F; FOREIGN_LIMBS],
+    y: [F; FOREIGN_LIMBS],
+) -> [F; FOREIGN_LIMBS] {
+    let out = extend_select_witness(witness, condition, &x, &y);
+    array::from_fn(|i| out[i])
+}
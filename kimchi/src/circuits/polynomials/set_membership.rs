@@ -0,0 +1,276 @@
+//! This module includes the definition of the set membership and non-membership gadgets
+//! and their witness code generation, for static sets fixed at circuit construction time.
+//! Note that this module does not include a new gate type, the gadgets reuse the `Lookup`,
+//! `Generic` and `RangeCheck0` gates with custom lookup tables built from the set.
+//!
+//! The membership gadget is the fixed-table lookup gadget (see [crate::circuits::lookup::gadget])
+//! into the table of the elements of the set, built by [set_membership_table].
+//! Each `Lookup` row checks that up to 3 values are elements of the set,
+//! the i-th value being at the position given by [set_membership_input].
+//!
+//! The non-membership gadget is restricted to sets of 64-bit integers. It looks up a pair `(lo, hi)`
+//! in the table of the gaps between consecutive elements of the set, built by [set_non_membership_table],
+//! with the sentinels $-1$ before the smallest element and $2^{64}$ after the largest one.
+//! Then it checks that $lo < x < hi$, with two 64-bit range checks of
+//! $$d_{lo} = x - lo - 1 \text{ and } d_{hi} = hi - x - 1$$
+//! Since $d_{lo} + d_{hi} = hi - lo - 2$ is less than $2^{65}$, the comparison holds over the integers,
+//! so that no element of the set is equal to $x$.
+//!
+//! | Row | `CircuitGate` | Purpose                                                       |
+//! | --- | ------------- | ------------------------------------------------------------- |
+//! | i   | `Generic`     | Constrain the ID of the gaps table                            |
+//! | i+1 | `Lookup`      | Look up the gap `(lo, hi)`, in columns 1 and 2                |
+//! | i+2 | `Generic`     | $d_{lo} = x - lo - 1$ and $d_{hi} = hi - x - 1$               |
+//! | i+3 | `RangeCheck0` | $d_{lo} < 2^{64}$                                             |
+//! | i+4 | `RangeCheck0` | $d_{hi} < 2^{64}$                                             |
+//!
+//! The input $x$ is in column 0 of the third row, at the position given by [set_non_membership_input].
+//! As for the comparisons, the gadget needs a zero cell to check that the range checked values fit in 64 bits.
+//!
+//! The tables must have non-zero IDs that are not used by other tables of the circuit:
+//! the dummy entry of the lookups is a zero entry of the table of ID 0.
+
+use crate::circuits::{
+    gate::{CircuitGate, Connect},
+    lookup::{gadget, tables::LookupTable},
+    polynomial::COLUMNS,
+    polynomials::range_check,
+    wires::Wire,
+};
+use ark_ff::{PrimeField, SquareRootField};
+use o1_utils::FieldHelpers;
+use std::array;
+
+/// Number of rows of the non-membership gadget
+pub const SET_NON_MEMBERSHIP_ROWS: usize = 5;
+
+/// Returns the position of the cell containing the i-th value checked by a membership gadget
+/// starting at row `new_row`, as a `(row, column)` pair.
+pub fn set_membership_input(new_row: usize, i: usize) -> (usize, usize) {
+    let slot = i % gadget::LOOKUPS_PER_ROW;
+    (new_row + 1 + i / gadget::LOOKUPS_PER_ROW, 2 * slot + 1)
+}
+
+/// Returns the position of the cell containing the value checked by a non-membership gadget
+/// starting at row `new_row`, as a `(row, column)` pair.
+pub fn set_non_membership_input(new_row: usize) -> (usize, usize) {
+    (new_row + 2, 0)
+}
+
+// Checks that the ID of a table of the gadgets can be a custom table ID
+fn check_table_id(table_id: i32) {
+    assert!(
+        table_id != 0,
+        "The tables of the set gadgets cannot have the ID 0"
+    );
+}
+
+// Converts an integer of the gaps to a field element
+fn to_field<F: PrimeField>(value: i128) -> F {
+    if value < 0 {
+        -F::from(value.unsigned_abs())
+    } else {
+        F::from(value as u128)
+    }
+}
+
+// Converts a field element to a 64-bit integer
+fn to_u64<F: PrimeField>(value: &F) -> u64 {
+    let value = value.to_biguint();
+    assert!(
+        value.bits() <= 64,
+        "The non-membership gadget only supports 64-bit integers"
+    );
+    value.iter_u64_digits().next().unwrap_or(0)
+}
+
+// The gaps (lo, hi) between the consecutive distinct elements of the set, with the sentinels -1 and 2^64,
+// so that the values not in the set are exactly the values lo < x < hi of a gap
+fn gaps<F: PrimeField>(set: &[F]) -> Vec<(i128, i128)> {
+    let mut elements: Vec<i128> = set.iter().map(|x| to_u64(x) as i128).collect();
+    elements.sort_unstable();
+    elements.dedup();
+
+    std::iter::once(-1)
+        .chain(elements.iter().copied())
+        .zip(elements.iter().copied().chain(std::iter::once(1 << 64)))
+        .filter(|(lo, hi)| hi - lo > 1)
+        .collect()
+}
+
+/// Builds the table of ID `table_id` of the elements of a set, used by the membership gadget.
+/// Panics if `table_id` is zero.
+pub fn set_membership_table<F: PrimeField>(table_id: i32, set: Vec<F>) -> LookupTable<F> {
+    check_table_id(table_id);
+    LookupTable {
+        id: table_id,
+        data: vec![set],
+    }
+}
+
+/// Builds the table of ID `table_id` of the gaps between the elements of a set, used by the non-membership gadget.
+/// Panics if `table_id` is zero, or if an element of the set does not fit in 64 bits.
+pub fn set_non_membership_table<F: PrimeField>(table_id: i32, set: Vec<F>) -> LookupTable<F> {
+    check_table_id(table_id);
+    let (lo, hi) = gaps(&set)
+        .into_iter()
+        .map(|(lo, hi)| (to_field::<F>(lo), to_field::<F>(hi)))
+        .unzip();
+    LookupTable {
+        id: table_id,
+        data: vec![lo, hi],
+    }
+}
+
+impl<F: PrimeField + SquareRootField> CircuitGate<F> {
+    /// Extends a circuit with a membership gadget checking that `num_values` values are elements of a set
+    /// Includes:
+    /// - 1 Generic gate to fix the table ID
+    /// - 1 Lookup gate for every 3 values
+    /// Input:
+    /// - gates      : the full circuit
+    /// - table_id   : the ID of the table of the set, see [set_membership_table]
+    /// - num_values : the number of checked values
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the i-th value is at the position given by [set_membership_input]
+    pub fn extend_set_membership(gates: &mut Vec<Self>, table_id: i32, num_values: usize) -> usize {
+        let (next_row, mut set_gates) =
+            Self::create_set_membership(gates.len(), table_id, num_values);
+        gates.append(&mut set_gates);
+        next_row
+    }
+
+    /// Creates a membership gadget checking that `num_values` values are elements of a set,
+    /// see [Self::extend_set_membership]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    /// Panics if `table_id` or `num_values` is zero.
+    pub fn create_set_membership(
+        new_row: usize,
+        table_id: i32,
+        num_values: usize,
+    ) -> (usize, Vec<Self>) {
+        check_table_id(table_id);
+        Self::create_lookup(new_row, table_id, num_values)
+    }
+
+    /// Extends a circuit with a non-membership gadget checking that a 64-bit integer is not an element of a set
+    /// Includes:
+    /// - 1 Generic gate to fix the table ID
+    /// - 1 Lookup gate to look up the gap of the value
+    /// - 1 Generic gate to compute the distances of the value to the bounds of the gap
+    /// - 2 RangeCheck0 gates to check the distances
+    /// Input:
+    /// - gates    : the full circuit
+    /// - table_id : the ID of the table of the gaps of the set, see [set_non_membership_table]
+    /// - zero_row : the row of the Generic gate to constrain the 64-bit checks
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the value is at the position given by [set_non_membership_input]
+    pub fn extend_set_non_membership(
+        gates: &mut Vec<Self>,
+        table_id: i32,
+        zero_row: usize,
+    ) -> usize {
+        let start_row = gates.len();
+        let (_, mut set_gates) = Self::create_set_non_membership(start_row, table_id);
+        gates.append(&mut set_gates);
+        // Check that 2 most significant limbs of the distances are zero
+        gates.connect_64bit(zero_row, start_row + 3);
+        gates.connect_64bit(zero_row, start_row + 4);
+        gates.len()
+    }
+
+    /// Creates a non-membership gadget checking that a 64-bit integer is not an element of a set,
+    /// see [Self::extend_set_non_membership]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    /// Panics if `table_id` is zero.
+    /// Warning:
+    /// - need to check that the 2 most significant limbs of the range checked values are zero
+    pub fn create_set_non_membership(new_row: usize, table_id: i32) -> (usize, Vec<Self>) {
+        check_table_id(table_id);
+        let (mut next_row, mut set_gates) = Self::create_lookup(new_row, table_id, 1);
+        // x - lo - d_lo - 1 = 0
+        // hi - x - d_hi - 1 = 0
+        let distance = [F::one(), -F::one(), -F::one(), F::zero(), -F::one()];
+        let mut coeffs = [F::zero(); 10];
+        coeffs[..5].copy_from_slice(&distance);
+        coeffs[5..].copy_from_slice(&distance);
+        set_gates.push(CircuitGate::create_generic(Wire::for_row(next_row), coeffs));
+        next_row += 1;
+        CircuitGate::extend_range_check(&mut set_gates, &mut next_row);
+        CircuitGate::extend_range_check(&mut set_gates, &mut next_row);
+
+        // copy lo, hi and x (indices are relative to the gadget, wires are not)
+        set_gates.connect_cell_pair((1, 1), (2, 1));
+        set_gates.connect_cell_pair((1, 2), (2, 3));
+        set_gates.connect_cell_pair((2, 0), (2, 4));
+        // copy the distances
+        set_gates.connect_cell_pair((2, 2), (3, 0));
+        set_gates.connect_cell_pair((2, 5), (4, 0));
+
+        (next_row, set_gates)
+    }
+}
+
+/// Extends the membership rows to the full witness
+/// Input
+/// - witness: full witness of the circuit
+/// - table_id: the ID of the table of the set
+/// - values: the values checked to be elements of the set
+/// Panics if `values` is empty.
+pub fn extend_set_membership_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    table_id: i32,
+    values: &[F],
+) {
+    let lookups: Vec<_> = values.iter().map(|value| (*value, F::zero())).collect();
+    gadget::extend_lookup_witness(witness, table_id, &lookups);
+}
+
+/// Extends the non-membership rows to the full witness
+/// Input
+/// - witness: full witness of the circuit
+/// - table_id: the ID of the table of the gaps of the set
+/// - set: the elements of the set, as given to [set_non_membership_table]
+/// - value: the 64-bit integer checked not to be an element of the set
+/// Panics if `value` is an element of the set, or if it does not fit in 64 bits.
+/// Warning:
+/// - don't forget to include a public input row with zero value
+pub fn extend_set_non_membership_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    table_id: i32,
+    set: &[F],
+    value: F,
+) {
+    let x = to_u64(&value) as i128;
+    let (lo, hi) = gaps(set)
+        .into_iter()
+        .find(|(lo, hi)| *lo < x && x < *hi)
+        .expect("The value must not be an element of the set");
+    let d_lo: F = to_field(x - lo - 1);
+    let d_hi: F = to_field(hi - x - 1);
+    let (lo, hi): (F, F) = (to_field(lo), to_field(hi));
+
+    gadget::extend_lookup_witness(witness, table_id, &[(lo, hi)]);
+    let distance_witness: [Vec<F>; COLUMNS] = array::from_fn(|col| match col {
+        0 => vec![value],
+        1 => vec![lo],
+        2 => vec![d_lo],
+        3 => vec![hi],
+        4 => vec![value],
+        5 => vec![d_hi],
+        _ => vec![F::zero()],
+    });
+    for col in 0..COLUMNS {
+        witness[col].extend(distance_witness[col].iter());
+    }
+    range_check::witness::extend_single(witness, d_lo);
+    range_check::witness::extend_single(witness, d_hi);
+}
@@ -0,0 +1,174 @@
+//! This module includes the definition of the logical shift gadgets and the witness code generation,
+//! for 32-bit and 64-bit words.
+//! Note that this module does not include a `Shift` gate type, the shifts reuse the `Rot64` gate.
+use crate::circuits::{
+    gate::{CircuitGate, Connect},
+    polynomial::COLUMNS,
+    wires::Wire,
+};
+use ark_ff::{PrimeField, SquareRootField};
+use std::array;
+
+use super::{
+    generic::GenericGateSpec,
+    rot::{self, RotMode},
+};
+
+//~ We implement the logical shifts as gadgets reusing the `Rot64` gate, needing no new gate type for them.
+//~
+//~ Recall that the `Rot64` gate rotating a 64-bit word by $r$ bits to the left constrains
+//~ $$word \cdot 2^{r} = excess \cdot 2^{64} + shifted$$
+//~ where $shifted < 2^{64}$ is range checked in the next row, and $excess < 2^{r}$ is checked with the bound in the same row.
+//~ That is, the $shifted$ value is already the logical left shift of $word$ by $r$ bits, and the $excess$ value
+//~ of a rotation by $64 - r$ bits to the left is the logical right shift of $word$ by $r$ bits.
+//~
+//~ For 32-bit words, the right shift is the same as for 64-bit words. The left shift of a 32-bit word by $r$ bits
+//~ is obtained from a rotation by $32 + r$ bits, whose $shifted$ value is $(word \ll r \bmod 2^{32}) \cdot 2^{32}$,
+//~ followed by a `Generic` gate dividing it by $2^{32}$.
+//~
+//~ | Width | Side  | Rows                               | Output cell                   |
+//~ | ----- | ----- | ---------------------------------- | ----------------------------- |
+//~ | 64    | Left  | `Rot64`($r$), `RangeCheck0`        | $shifted$ (row 1, column 0)   |
+//~ | 64    | Right | `Rot64`($64-r$), `RangeCheck0`     | $excess$ (row 0, column 2)    |
+//~ | 32    | Left  | `Rot64`($32+r$), `RangeCheck0`, `Generic` | $shifted / 2^{32}$ (row 2, column 1) |
+//~ | 32    | Right | `Rot64`($64-r$), `RangeCheck0`     | $excess$ (row 0, column 2)    |
+//~
+//~ As for the rotation, the input word is in column 0 of the first row,
+//~ and the gadget needs a zero cell to check that the $shifted$ value fits in 64 bits.
+
+/// Returns the offset of the left rotation performing a shift of a word of `bits` length
+/// by `shift` bits towards `side`
+/// Panics if `bits` is neither 32 nor 64, or if `shift` is not in `1..bits`
+fn shift_rotation(bits: u32, shift: u32, side: RotMode) -> u32 {
+    assert!(
+        bits == 32 || bits == 64,
+        "Shifts are only supported for 32-bit and 64-bit words"
+    );
+    assert!(
+        shift > 0 && shift < bits,
+        "Shift value must be non-zero and less than the length of the word"
+    );
+    match (bits, side) {
+        (64, RotMode::Left) => shift,
+        (32, RotMode::Left) => 32 + shift,
+        (_, RotMode::Right) => 64 - shift,
+        _ => unreachable!(),
+    }
+}
+
+/// Returns the position of the cell containing the output of a shift gadget starting at row `new_row`,
+/// as a `(row, column)` pair.
+/// Panics if `bits` is neither 32 nor 64.
+pub fn shift_output(new_row: usize, bits: u32, side: RotMode) -> (usize, usize) {
+    assert!(
+        bits == 32 || bits == 64,
+        "Shifts are only supported for 32-bit and 64-bit words"
+    );
+    match (bits, side) {
+        (64, RotMode::Left) => (new_row + 1, 0),
+        (32, RotMode::Left) => (new_row + 2, 1),
+        (_, RotMode::Right) => (new_row, 2),
+        _ => unreachable!(),
+    }
+}
+
+impl<F: PrimeField + SquareRootField> CircuitGate<F> {
+    /// Extends a logical shift gadget of a word of `bits` length (32 or 64) to a circuit
+    /// Includes:
+    /// - 1 Rot64 gate and 1 RangeCheck0 gate to perform the shift
+    /// - 1 Generic gate to take the top half of the shifted word, only for left shifts of 32-bit words
+    /// Input:
+    /// - gates    : the full circuit
+    /// - bits     : the length of the word, 32 or 64
+    /// - shift    : the shift offset, in `1..bits`
+    /// - side     : the shift side
+    /// - zero_row : the row of the Generic gate to constrain the 64-bit check of shifted word
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - witness word should come from the copy of another cell so it is intrinsic that it is `bits` length
+    /// - the output is at the position given by [shift_output]
+    pub fn extend_shift(
+        gates: &mut Vec<Self>,
+        bits: u32,
+        shift: u32,
+        side: RotMode,
+        zero_row: usize,
+    ) -> usize {
+        let start_row = gates.len();
+        let (_, mut shift_gates) = Self::create_shift(start_row, bits, shift, side);
+        gates.append(&mut shift_gates);
+        // Check that 2 most significant limbs of shifted are zero
+        gates.connect_64bit(zero_row, start_row + 1);
+        gates.len()
+    }
+
+    /// Creates a logical shift gadget of a word of `bits` length (32 or 64), see [Self::extend_shift]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    /// Warning:
+    /// - need to check that the 2 most significant limbs of shifted are zero
+    pub fn create_shift(
+        new_row: usize,
+        bits: u32,
+        shift: u32,
+        side: RotMode,
+    ) -> (usize, Vec<Self>) {
+        let rot = shift_rotation(bits, shift, side);
+        let mut shift_gates = Self::create_rot64(new_row, rot);
+
+        if bits == 32 && side == RotMode::Left {
+            // shifted - 2^32 * output = 0
+            let half_row = new_row + shift_gates.len();
+            shift_gates.push(CircuitGate::create_generic_gadget(
+                Wire::for_row(half_row),
+                GenericGateSpec::Add {
+                    left_coeff: None,
+                    right_coeff: Some(-F::from(2u64.pow(32))),
+                    output_coeff: Some(F::zero()),
+                },
+                None,
+            ));
+            // copy the shifted word (indices are relative to the gadget, wires are not)
+            shift_gates.connect_cell_pair((1, 0), (2, 0));
+        }
+
+        (new_row + shift_gates.len(), shift_gates)
+    }
+}
+
+/// Extends the shift rows to the full witness
+/// Input
+/// - witness: full witness of the circuit
+/// - word: word of `bits` length to be shifted
+/// - bits: length of the word, 32 or 64
+/// - shift: shift offset
+/// - side: side of the shift, either left or right
+/// Warning:
+/// - don't forget to include a public input row with zero value
+pub fn extend_shift_witness<F: PrimeField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    word: u64,
+    bits: u32,
+    shift: u32,
+    side: RotMode,
+) {
+    if bits == 32 {
+        assert!(word < 1 << 32, "Word must be 32 bits long");
+    }
+    let rot = shift_rotation(bits, shift, side);
+    rot::extend_rot(witness, word, rot, RotMode::Left);
+
+    if bits == 32 && side == RotMode::Left {
+        let shifted = word << rot;
+        let half_witness: [Vec<F>; COLUMNS] = array::from_fn(|col| match col {
+            0 => vec![F::from(shifted)],
+            1 => vec![F::from(shifted >> 32)],
+            _ => vec![F::zero()],
+        });
+        for col in 0..COLUMNS {
+            witness[col].extend(half_witness[col].iter());
+        }
+    }
+}
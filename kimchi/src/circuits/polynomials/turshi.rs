@@ -182,13 +182,17 @@ impl<F: PrimeField + SquareRootField> CircuitGate<F> {
         witness: &[Vec<F>; COLUMNS],
         cs: &ConstraintSystem<F>,
     ) -> Result<(), String> {
-        // assignments
-        let curr: [F; COLUMNS] = array::from_fn(|i| witness[i][row]);
-        let mut next: [F; COLUMNS] = array::from_fn(|_| F::zero());
-        if self.typ != GateType::Zero {
-            next = array::from_fn(|i| witness[i][row + 1]);
-        }
+        let constraint = Self::cairo_linearized_constraint(self.typ);
+        self.verify_cairo_gate_with::<G>(row, witness, cs, &constraint)
+    }
 
+    /// Returns the constant term of the linearized constraints of a Cairo gate type,
+    /// which does not depend on the rows of the witness
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `constraint linearization` fails.
+    pub(crate) fn cairo_linearized_constraint(typ: GateType) -> E<F> {
         // column polynomials
         let polys = {
             let mut h = std::collections::HashSet::new();
@@ -205,14 +209,30 @@ impl<F: PrimeField + SquareRootField> CircuitGate<F> {
 
         // assign powers of alpha to these gates
         let mut alphas = Alphas::<F>::default();
-        alphas.register(ArgumentType::Gate(self.typ), Instruction::<F>::CONSTRAINTS);
+        alphas.register(ArgumentType::Gate(typ), Instruction::<F>::CONSTRAINTS);
 
         // Get constraints for this circuit gate
-        let constraints =
-            circuit_gate_combined_constraints(self.typ, &alphas, &mut Cache::default());
+        let constraints = circuit_gate_combined_constraints(typ, &alphas, &mut Cache::default());
 
         // Linearize
-        let linearized = constraints.linearize(polys).unwrap();
+        constraints.linearize(polys).unwrap().constant_term
+    }
+
+    /// verifies that the Cairo gate constraints are solved by the witness,
+    /// with the constant term of its linearized constraints (see [Self::cairo_linearized_constraint])
+    pub(crate) fn verify_cairo_gate_with<G: KimchiCurve<ScalarField = F>>(
+        &self,
+        row: usize,
+        witness: &[Vec<F>; COLUMNS],
+        cs: &ConstraintSystem<F>,
+        constraint: &E<F>,
+    ) -> Result<(), String> {
+        // assignments
+        let curr: [F; COLUMNS] = array::from_fn(|i| witness[i][row]);
+        let mut next: [F; COLUMNS] = array::from_fn(|_| F::zero());
+        if self.typ != GateType::Zero {
+            next = array::from_fn(|i| witness[i][row + 1]);
+        }
 
         // Setup proof evaluations
         let rng = &mut StdRng::from_seed([0u8; 32]);
@@ -231,10 +251,7 @@ impl<F: PrimeField + SquareRootField> CircuitGate<F> {
         let pt = F::rand(rng);
 
         // Evaluate constraints
-        match linearized
-            .constant_term
-            .evaluate_(cs.domain.d1, pt, &evals, &constants)
-        {
+        match constraint.evaluate_(cs.domain.d1, pt, &evals, &constants) {
             Ok(x) => {
                 if x == F::zero() {
                     Ok(())
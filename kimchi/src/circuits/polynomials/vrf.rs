@@ -0,0 +1,636 @@
+//! This module includes the gadget verifying the evaluation of a Mina-style VRF over a native curve,
+//! for the VRF of the Mina consensus over Pallas, and its witness code generation.
+//! Note that this module does not include a new gate type, the gadget reuses the `Poseidon`,
+//! `VarBaseMul`, `CompleteAdd` and `Generic` gates as the Schnorr gadget, in the ECDSA layout.
+
+//~ The VRF gadget verifies the evaluation $o$ of the verifiable random function of Mina
+//~ (the integrated VRF of Ouroboros Praos) on a message $m_1, \dots, m_k$ of native field elements,
+//~ with a public key $P = s G$ and its secret key $s$, over a curve $E: y^2 = x^3 + b$
+//~ whose base field is the native field $\mathbb{F}_p$ and whose scalar field $\mathbb{F}_q$ is larger
+//~ (as Pallas in a circuit over the Vesta scalar field):
+//~
+//~ * the message is hashed to $t$ with Poseidon, from the sponge of the domain string `MinaVrfMessage`
+//~   (as the challenges of the Schnorr gadget),
+//~ * $t$ is mapped to the point $H$ of $E$ with the group map of the polynomial commitments
+//~   (of Shallue and van de Woestijne, see the `groupmap` crate), and the even y-coordinate,
+//~ * the output is the Poseidon hash $o$ of $m_1, \dots, m_k, x_U, y_U$, from the sponge of the domain string
+//~   `MinaVrfOutput`, of the point $U = s H$.
+//~
+//~ The gadget lays out:
+//~
+//~ * the inputs, two per half of a `Generic` gate, at the positions given by `vrf_input`,
+//~ * the absorptions and the `Poseidon` permutations of the two hashes,
+//~ * the group map of $t$, in `Generic` gates: the three candidates $x_1, x_2, x_3$ of the x-coordinate
+//~   (with the constants $u$, $f(u)$, $\sqrt{-3 u^2}$ and $1 / (3 u^2)$ of the setup of the group map,
+//~   and the inverse $\alpha$ of $(t^2 + f(u)) t^2$), and for $x_1$ and $x_2$ a boolean $b_i$ and a root $r_i$
+//~   with $r_i^2 = f(x_i) (z + b_i (1 - z))$ for a non-square constant $z$, so that $b_i$ is true exactly
+//~   when $f(x_i)$ is a square (as $f$ has no root on a curve of prime order),
+//~ * the x-coordinate of $H$, the first candidate (a selection with $b_1$ and $b_2$) whose $f(x)$ is a square,
+//~   and its y-coordinate, whose 3 limbs of 88 bits are canonical and whose least significant bit is zero,
+//~   with the check $y^2 = f(x)$ (the third candidate is a square when the others are not),
+//~ * the variable base multiplications $V_G = 2 s G$ and $V_H = 2 s H$ of `VarBaseMul` gates, from the accumulators $2 G$
+//~   (a constant) and $2 H$ (a `CompleteAdd` gate), with the shifted scalar of $s$ as in the Schnorr gadget,
+//~ * the `CompleteAdd` gates of $2 P$ and of $2 U$ for a point $U$ of the witness, which are copied to $V_G$ and $V_H$,
+//~   so that $P = s G$ and $U = s H$ since the order of the curve is odd.
+//~
+//~ The accumulations of the bits of the two multiplications are copied to each other after each 5 bits,
+//~ so that the multiplications share the same bits (and not only their accumulation modulo $p$),
+//~ and the output is unique for a public key.
+//~ The message hashes of the exceptional cases $(t^2 + f(u)) t^2 = 0$ of the group map only happen with a
+//~ negligible probability, and have no witness.
+//~
+
+use crate::{
+    circuits::{
+        gate::{CircuitGate, GateType},
+        polynomial::COLUMNS,
+        polynomials::{
+            ecdsa::layout::{Bit, Cell, Layout, Limb, Src},
+            generic::{GENERIC_COEFFS, GENERIC_REGISTERS},
+            schnorr::{
+                complete_add_witness, coordinates, curve_b, initial_sponge, layout_parity,
+                mul_rows, scalar_shift, shifted_bits,
+            },
+            varbasemul,
+        },
+        wires::Wire,
+    },
+    curve::KimchiCurve,
+};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, Field, PrimeField, SquareRootField};
+use mina_poseidon::poseidon::Sponge;
+use num_traits::Zero;
+use o1_utils::FieldHelpers;
+use poly_commitment::commitment::CommitmentCurve;
+use std::array;
+
+/// The domain string of the hash of the messages
+pub const VRF_MESSAGE_DOMAIN: &str = "MinaVrfMessage";
+
+/// The domain string of the hash of the outputs
+pub const VRF_OUTPUT_DOMAIN: &str = "MinaVrfOutput";
+
+/// Number of inputs of the gadget, other than the message
+const VRF_INPUTS: usize = 4;
+
+/// The inputs of the VRF gadget
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VrfInput {
+    /// The `i`-th element of the message
+    Message(usize),
+    /// The x-coordinate of the public key
+    PublicKeyX,
+    /// The y-coordinate of the public key
+    PublicKeyY,
+    /// The secret key, modulo the native modulus
+    SecretKey,
+    /// The output of the VRF
+    Output,
+}
+
+/// Returns the position of the cell of an input of the VRF gadget of a message of `msg_len` elements
+/// starting at row `new_row`, as a `(row, column)` pair
+pub fn vrf_input(new_row: usize, msg_len: usize, input: VrfInput) -> (usize, usize) {
+    let i = match input {
+        VrfInput::Message(i) => {
+            assert!(i < msg_len, "The element is in the message");
+            i
+        }
+        VrfInput::PublicKeyX => msg_len,
+        VrfInput::PublicKeyY => msg_len + 1,
+        VrfInput::SecretKey => msg_len + 2,
+        VrfInput::Output => msg_len + 3,
+    };
+    // the inputs are two per half of a generic gate, in their order
+    let half = i / 2;
+    (new_row + half / 2, GENERIC_REGISTERS * (half % 2) + i % 2)
+}
+
+// The constants of the group map of a curve y^2 = x^3 + b, as the setup of the `groupmap` crate,
+// and a non-square of the field
+struct GroupMapConstants<F> {
+    b: F,
+    u: F,
+    fu: F,
+    sqrt_neg_three_u_squared_minus_u_over_2: F,
+    sqrt_neg_three_u_squared: F,
+    inv_three_u_squared: F,
+    non_square: F,
+}
+
+impl<F: PrimeField + SquareRootField> GroupMapConstants<F> {
+    fn new(b: F) -> Self {
+        let curve = |x: F| x.square() * x + b;
+        // the first u = 1, 2, ... with f(u) != 0
+        let mut u = F::one();
+        while curve(u).is_zero() {
+            u += F::one();
+        }
+        let three_u_squared = u.square() * F::from(3u64);
+        let sqrt_neg_three_u_squared = (-three_u_squared)
+            .sqrt()
+            .expect("-3 u^2 is a square for the curves of the group map");
+        // the first non-square 2, 3, ...
+        let mut non_square = F::from(2u64);
+        while !non_square.legendre().is_qnr() {
+            non_square += F::one();
+        }
+        Self {
+            b,
+            u,
+            fu: curve(u),
+            sqrt_neg_three_u_squared_minus_u_over_2: (sqrt_neg_three_u_squared - u) / F::from(2u64),
+            sqrt_neg_three_u_squared,
+            inv_three_u_squared: three_u_squared
+                .inverse()
+                .expect("3 u^2 is not zero in a field of odd characteristic"),
+            non_square,
+        }
+    }
+
+    fn curve(&self, x: F) -> F {
+        x.square() * x + self.b
+    }
+
+    // The candidates of the x-coordinate of the point of t,
+    // or None for the exceptional cases (t^2 + f(u)) t^2 = 0
+    fn potential_xs(&self, t: F) -> Option<[F; 3]> {
+        let t2 = t.square();
+        let alpha = ((t2 + self.fu) * t2).inverse()?;
+        let x1 = self.sqrt_neg_three_u_squared_minus_u_over_2
+            - t2.square() * alpha * self.sqrt_neg_three_u_squared;
+        let x2 = -self.u - x1;
+        let t2_plus_fu = t2 + self.fu;
+        let x3 = self.u - t2_plus_fu.square() * (alpha * t2_plus_fu) * self.inv_three_u_squared;
+        Some([x1, x2, x3])
+    }
+}
+
+// The even square root of the y-coordinate of a point
+fn even_sqrt<F: PrimeField + SquareRootField>(rhs: F) -> F {
+    let y = rhs.sqrt().expect("the x-coordinate is on the curve");
+    if y.into_repr().is_even() {
+        y
+    } else {
+        -y
+    }
+}
+
+/// Returns the hash of a message to a native field element, the input of the group map
+pub fn vrf_message_hash<C: KimchiCurve>(message: &[C::BaseField]) -> C::BaseField
+where
+    C::BaseField: PrimeField,
+{
+    let mut sponge = initial_sponge::<C>(VRF_MESSAGE_DOMAIN);
+    sponge.absorb(message);
+    sponge.squeeze()
+}
+
+/// Returns the point of a message on the curve: the group map of its hash, with the even y-coordinate
+///
+/// # Panics
+///
+/// Will panic if the hash of the message is an exceptional case of the group map,
+/// which only happens with a negligible probability.
+pub fn vrf_message_point<C: KimchiCurve>(message: &[C::BaseField]) -> C
+where
+    C::BaseField: PrimeField + SquareRootField,
+{
+    let constants = GroupMapConstants::new(curve_b::<C>());
+    let xs = constants
+        .potential_xs(vrf_message_hash::<C>(message))
+        .expect("the hash is not an exceptional case of the group map");
+    let x = xs
+        .into_iter()
+        .find(|x| constants.curve(*x).legendre().is_qr())
+        .expect("a candidate is on the curve");
+    C::of_coordinates(x, even_sqrt(constants.curve(x)))
+}
+
+/// Returns the output of the VRF on a message with a secret key
+pub fn vrf_output<C: KimchiCurve>(
+    message: &[C::BaseField],
+    secret_key: C::ScalarField,
+) -> C::BaseField
+where
+    C::BaseField: PrimeField + SquareRootField,
+{
+    let point = vrf_message_point::<C>(message)
+        .mul(secret_key.into_repr())
+        .into_affine();
+    let (x, y) = coordinates(&point);
+    let mut sponge = initial_sponge::<C>(VRF_OUTPUT_DOMAIN);
+    sponge.absorb(message);
+    sponge.absorb(&[x, y]);
+    sponge.squeeze()
+}
+
+// The coefficients of the generic gate of a product, l r - out = 0
+fn mul_coeffs<F: PrimeField>() -> [F; GENERIC_COEFFS] {
+    [F::zero(), F::zero(), -F::one(), F::one(), F::zero()]
+}
+
+// Lays out the right-hand side x^3 + b of the equation of the curve
+fn layout_curve<F: PrimeField + SquareRootField>(layout: &mut Layout<F>, b: F, x: Limb<F>) -> Cell {
+    let xx = layout.generic_output(mul_coeffs(), Src::Copy(x), Src::Copy(x));
+    // xx x + b - out = 0
+    layout.generic_output(
+        [F::zero(), F::zero(), -F::one(), F::one(), b],
+        Src::Copy(Limb::Cell(xx)),
+        Src::Copy(x),
+    )
+}
+
+// Lays out the boolean of a candidate of the group map, which is true when f(x) is a square,
+// with the root of f(x) or of z f(x) for the non-square z
+fn layout_square_bit<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    constants: &GroupMapConstants<F>,
+    fx: Cell,
+) -> Bit {
+    let (zero, one, z) = (F::zero(), F::one(), constants.non_square);
+    let value = layout.limb_value(&Limb::Cell(fx));
+    let bit = layout.bit(value.map(|fx| fx.legendre().is_qr()));
+    let root = value.map(|fx| {
+        let square = if fx.legendre().is_qr() { fx } else { z * fx };
+        square.sqrt().expect("f(x) or z f(x) is a square")
+    });
+
+    let m = layout.generic_output(
+        mul_coeffs(),
+        Src::Copy(Limb::Cell(bit.cell)),
+        Src::Copy(Limb::Cell(fx)),
+    );
+    let [left, right, square] = layout.generic(mul_coeffs(), Src::New(root), Src::New(root));
+    layout.connect(left, right);
+    // z f + (1 - z) b f - r r = 0
+    let [_, _, out] = layout.generic(
+        [z, one - z, -one, zero, zero],
+        Src::Copy(Limb::Cell(fx)),
+        Src::Copy(Limb::Cell(m)),
+    );
+    layout.connect(out, square);
+    bit
+}
+
+// Lays out the group map of a native field element, and returns the coordinates of its point
+fn layout_group_map<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    constants: &GroupMapConstants<F>,
+    t: Limb<F>,
+) -> [Limb<F>; 2] {
+    let (zero, one) = (F::zero(), F::one());
+    let (u, fu) = (constants.u, constants.fu);
+
+    // alpha (t^2 + f(u)) t^2 - 1 = 0
+    let t2 = Limb::Cell(layout.generic_output(mul_coeffs(), Src::Copy(t), Src::Copy(t)));
+    let t4 = Limb::Cell(layout.generic_output(mul_coeffs(), Src::Copy(t2), Src::Copy(t2)));
+    let alpha_inv = Limb::Cell(layout.generic_output(
+        [fu, zero, -one, one, zero],
+        Src::Copy(t2),
+        Src::Copy(t2),
+    ));
+    let alpha_value = layout.limb_value(&alpha_inv).map(|alpha_inv| {
+        alpha_inv
+            .inverse()
+            .expect("the hash is not an exceptional case of the group map")
+    });
+    let [alpha, _, _] = layout.generic(
+        [zero, zero, zero, one, -one],
+        Src::New(alpha_value),
+        Src::Copy(alpha_inv),
+    );
+    let alpha = Limb::Cell(alpha);
+
+    // x1 = c1 - c2 t^4 alpha and x2 = - u - x1
+    let x1 = Limb::Cell(layout.generic_output(
+        [
+            zero,
+            zero,
+            -one,
+            -constants.sqrt_neg_three_u_squared,
+            constants.sqrt_neg_three_u_squared_minus_u_over_2,
+        ],
+        Src::Copy(t4),
+        Src::Copy(alpha),
+    ));
+    let x2 = Limb::Cell(layout.generic_output(
+        [-one, zero, -one, zero, -u],
+        Src::Copy(x1),
+        Src::New(Some(zero)),
+    ));
+
+    // x3 = u - c3 v^2 (alpha v), with v = t^2 + f(u)
+    let v = Limb::Cell(layout.generic_output(
+        [one, zero, -one, zero, fu],
+        Src::Copy(t2),
+        Src::New(Some(zero)),
+    ));
+    let v2 = Limb::Cell(layout.generic_output(mul_coeffs(), Src::Copy(v), Src::Copy(v)));
+    let alpha_v = Limb::Cell(layout.generic_output(mul_coeffs(), Src::Copy(alpha), Src::Copy(v)));
+    let x3 = Limb::Cell(layout.generic_output(
+        [zero, zero, -one, -constants.inv_three_u_squared, u],
+        Src::Copy(v2),
+        Src::Copy(alpha_v),
+    ));
+
+    // the first candidate whose f(x) is a square
+    let f1 = layout_curve(layout, constants.b, x1);
+    let b1 = layout_square_bit(layout, constants, f1);
+    let f2 = layout_curve(layout, constants.b, x2);
+    let b2 = layout_square_bit(layout, constants, f2);
+    let x23 = layout.select_limb(&b2, &x2, &x3);
+    let x = layout.select_limb(&b1, &x1, &x23);
+
+    // the even y-coordinate, with y y - f(x) = 0
+    let fx = layout_curve(layout, constants.b, x);
+    let y_value = layout.limb_value(&Limb::Cell(fx)).map(even_sqrt);
+    let (y, parity) = layout_parity(layout, y_value);
+    layout.copy(&Limb::Const(zero), parity.cell);
+    let yy = layout.generic_output(
+        mul_coeffs(),
+        Src::Copy(Limb::Cell(y)),
+        Src::Copy(Limb::Cell(y)),
+    );
+    layout.connect(yy, fx);
+    [x, Limb::Cell(y)]
+}
+
+// Lays out the complete addition of two points, whose sum is constrained not to be the point at infinity
+fn layout_complete_add<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    left: [Limb<F>; 2],
+    right: [Limb<F>; 2],
+) -> [Limb<F>; 2] {
+    let row = layout.next_row();
+    let values: Option<Vec<F>> = left
+        .iter()
+        .chain(&right)
+        .map(|limb| layout.limb_value(limb))
+        .collect();
+    layout.push(
+        vec![CircuitGate::new(
+            GateType::CompleteAdd,
+            Wire::for_row(row),
+            vec![],
+        )],
+        || {
+            let values = values.expect("the values are known when the witness is laid out");
+            let mut witness = array::from_fn(|_| vec![F::zero()]);
+            complete_add_witness(
+                &mut witness,
+                0,
+                (values[0], values[1]),
+                (values[2], values[3]),
+            );
+            witness
+        },
+    );
+    for (col, limb) in left.iter().chain(&right).enumerate() {
+        layout.copy(limb, (row, col));
+    }
+    layout.copy(&Limb::Const(F::zero()), (row, 6));
+    [Limb::Cell((row, 4)), Limb::Cell((row, 5))]
+}
+
+// Lays out the variable base multiplication 2 k T from the accumulator 2 T, with the bits of the shifted scalar
+// of k (see the Schnorr gadget), and returns the product and the accumulations of the bits after each 5 bits
+fn layout_varbase_mul<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    base: [Limb<F>; 2],
+    double: [Limb<F>; 2],
+    bits: Option<&[bool]>,
+) -> ([Cell; 2], Vec<Cell>) {
+    let row = layout.next_row();
+    let rows = mul_rows::<F>();
+    let gates = (0..rows / 2)
+        .flat_map(|chunk| {
+            let chunk_row = row + 2 * chunk;
+            CircuitGate::create_vbmul(&[Wire::for_row(chunk_row), Wire::for_row(chunk_row + 1)])
+        })
+        .collect();
+    let values: Option<Vec<F>> = base
+        .iter()
+        .chain(&double)
+        .map(|limb| layout.limb_value(limb))
+        .collect();
+    layout.push(gates, || {
+        let values = values.expect("the values are known when the witness is laid out");
+        let bits = bits.expect("the bits are known when the witness is laid out");
+        let mut witness = array::from_fn(|_| vec![F::zero(); rows]);
+        varbasemul::witness(
+            &mut witness,
+            0,
+            (values[0], values[1]),
+            bits,
+            (values[2], values[3]),
+        );
+        witness
+    });
+
+    let mut accumulations = vec![];
+    for chunk in 0..rows / 2 {
+        let chunk_row = row + 2 * chunk;
+        layout.copy(&base[0], (chunk_row, 0));
+        layout.copy(&base[1], (chunk_row, 1));
+        if chunk == 0 {
+            layout.copy(&double[0], (chunk_row, 2));
+            layout.copy(&double[1], (chunk_row, 3));
+            layout.copy(&Limb::Const(F::zero()), (chunk_row, 4));
+        } else {
+            layout.connect((chunk_row - 1, 0), (chunk_row, 2));
+            layout.connect((chunk_row - 1, 1), (chunk_row, 3));
+            layout.connect((chunk_row - 2, 5), (chunk_row, 4));
+        }
+        accumulations.push((chunk_row, 5));
+    }
+    let product_row = row + rows - 1;
+    ([(product_row, 0), (product_row, 1)], accumulations)
+}
+
+// Lays out the verification of the evaluation of the VRF, with the message, the public key
+// and the secret key for the witness
+fn layout_vrf<F: PrimeField + SquareRootField, C: KimchiCurve<BaseField = F>>(
+    layout: &mut Layout<F>,
+    msg_len: usize,
+    values: Option<(&[F], &C, C::ScalarField)>,
+) {
+    assert!(msg_len > 0, "The message has an element");
+    let params = C::OtherCurve::sponge_params();
+    let constants = GroupMapConstants::new(curve_b::<C>());
+    let (zero, one) = (F::zero(), F::one());
+
+    // the inputs, two per half of a generic gate
+    let inputs: Vec<Option<F>> = match values {
+        Some((message, public_key, secret_key)) => {
+            let (x, y) = coordinates(public_key);
+            let secret_native = F::from_biguint(&(secret_key.to_biguint() % F::modulus_biguint()))
+                .expect("the value is reduced");
+            message
+                .iter()
+                .copied()
+                .chain([x, y, secret_native, vrf_output::<C>(message, secret_key)])
+                .map(Some)
+                .collect()
+        }
+        None => vec![None; msg_len + VRF_INPUTS],
+    };
+    let mut cells = vec![];
+    for pair in inputs.chunks(2) {
+        let right = pair.get(1).copied().unwrap_or(Some(zero));
+        let [left_cell, right_cell, _] =
+            layout.generic([zero; GENERIC_COEFFS], Src::New(pair[0]), Src::New(right));
+        cells.extend([left_cell, right_cell]);
+    }
+    let message: Vec<Limb<F>> = cells[..msg_len]
+        .iter()
+        .map(|cell| Limb::Cell(*cell))
+        .collect();
+    let [public_x, public_y, secret_cell, output_cell]: [Cell; VRF_INPUTS] =
+        array::from_fn(|i| cells[msg_len + i]);
+    let public_key = [Limb::Cell(public_x), Limb::Cell(public_y)];
+
+    // the point H of the message, and its double
+    let message_state = initial_sponge::<C>(VRF_MESSAGE_DOMAIN).state;
+    let state = array::from_fn(|i| Limb::Const(message_state[i]));
+    let t = layout.absorb(params, &state, &message)[0];
+    let point = layout_group_map(layout, &constants, t);
+    let double_point = layout_complete_add(layout, point, point);
+
+    // the multiplications 2 s G and 2 s H with the same bits, and the shifted scalar of s
+    let shifted = values.map(|(_, _, secret_key)| shifted_bits::<C>(&secret_key.to_biguint()));
+    let generator = C::prime_subgroup_generator();
+    let (gx, gy) = coordinates(&generator);
+    let (g2x, g2y) = coordinates(&generator.into_projective().double().into_affine());
+    let bits = shifted.as_ref().map(|(bits, _)| bits.as_slice());
+    let (product_g, accumulations_g) = layout_varbase_mul(
+        layout,
+        [Limb::Const(gx), Limb::Const(gy)],
+        [Limb::Const(g2x), Limb::Const(g2y)],
+        bits,
+    );
+    let (product_h, accumulations_h) = layout_varbase_mul(layout, point, double_point, bits);
+    for (g, h) in accumulations_g.iter().zip(&accumulations_h) {
+        layout.connect(*g, *h);
+    }
+    let q = F::from_biguint(&(C::ScalarField::modulus_biguint() % F::modulus_biguint()))
+        .expect("the value is reduced");
+    let d = F::from_biguint(&scalar_shift::<C>()).expect("the shift is smaller than the modulus");
+    let wrap = layout.bit(shifted.as_ref().map(|(_, wrap)| *wrap));
+    let shifted_scalar = *accumulations_g.last().expect("the multiplication has bits");
+    // k' - w q + d - k = 0
+    let scalar = layout.generic_output(
+        [one, -q, -one, zero, d],
+        Src::Copy(Limb::Cell(shifted_scalar)),
+        Src::Copy(Limb::Cell(wrap.cell)),
+    );
+    layout.connect(scalar, secret_cell);
+
+    // 2 P = 2 s G, and 2 U = 2 s H
+    let double_public = layout_complete_add(layout, public_key, public_key);
+    let u_value = values.map(|(message, _, secret_key)| {
+        coordinates(
+            &vrf_message_point::<C>(message)
+                .mul(secret_key.into_repr())
+                .into_affine(),
+        )
+    });
+    let [ux, uy, _] = layout.generic(
+        [zero; GENERIC_COEFFS],
+        Src::New(u_value.map(|(x, _)| x)),
+        Src::New(u_value.map(|(_, y)| y)),
+    );
+    let u = [Limb::Cell(ux), Limb::Cell(uy)];
+    let double_u = layout_complete_add(layout, u, u);
+    for (left, right) in double_public
+        .iter()
+        .zip(&product_g)
+        .chain(double_u.iter().zip(&product_h))
+    {
+        layout.copy(left, *right);
+    }
+
+    // the output, the hash of the message and of U
+    let output_state = initial_sponge::<C>(VRF_OUTPUT_DOMAIN).state;
+    let state = array::from_fn(|i| Limb::Const(output_state[i]));
+    let mut hash_input = message;
+    hash_input.extend(u);
+    let hash = layout.absorb(params, &state, &hash_input)[0];
+    layout.copy(&hash, output_cell);
+}
+
+impl<F: PrimeField + SquareRootField> CircuitGate<F> {
+    /// Extends a circuit with the verification of the evaluation of a VRF over the curve `C` of base field `F`
+    /// Includes:
+    /// - the inputs, the group map and the shifted scalar in generic gates
+    /// - the Poseidon hashes of the message and of the output
+    /// - the variable base multiplications and the complete additions of the public key
+    ///   and of the evaluation (see the module)
+    /// Input:
+    /// - gates   : the full circuit
+    /// - msg_len : the number of elements of the message, at least one
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the message, the public key and the secret key (see [vrf_input]) should be copied from other cells,
+    ///   and the output copied to the cells which use it
+    pub fn extend_vrf<C: KimchiCurve<BaseField = F>>(
+        gates: &mut Vec<Self>,
+        msg_len: usize,
+    ) -> usize {
+        let (next_row, mut vrf_gates) = Self::create_vrf::<C>(gates.len(), msg_len);
+        gates.append(&mut vrf_gates);
+        next_row
+    }
+
+    /// Creates the verification of the evaluation of a VRF, see [Self::extend_vrf]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_vrf<C: KimchiCurve<BaseField = F>>(
+        new_row: usize,
+        msg_len: usize,
+    ) -> (usize, Vec<Self>) {
+        let mut layout = Layout::new(new_row, false);
+        layout_vrf::<F, C>(&mut layout, msg_len, None);
+        let (vrf_gates, _) = layout.finalize();
+        (new_row + vrf_gates.len(), vrf_gates)
+    }
+}
+
+/// Extends the witness with the verification of the evaluation of a VRF, and returns its output,
+/// or `None` if the public key is not the one of the secret key, since the witness then does not satisfy the gadget
+/// Input
+/// - witness: full witness of the circuit
+/// - message: the elements of the message
+/// - public_key: the public key, which is not the point at infinity
+/// - secret_key: the secret key
+/// Panics if the hash of the message is an exceptional case of the group map
+/// (which only happens with a negligible probability)
+pub fn extend_vrf_witness<C: KimchiCurve>(
+    witness: &mut [Vec<C::BaseField>; COLUMNS],
+    message: &[C::BaseField],
+    public_key: &C,
+    secret_key: C::ScalarField,
+) -> Option<C::BaseField>
+where
+    C::BaseField: PrimeField + SquareRootField,
+{
+    let mut layout = Layout::new(witness[0].len(), true);
+    layout_vrf::<C::BaseField, C>(
+        &mut layout,
+        message.len(),
+        Some((message, public_key, secret_key)),
+    );
+    let (_, vrf_witness) = layout.finalize();
+    let vrf_witness = vrf_witness.expect("the witness is laid out");
+    for (col, cells) in witness.iter_mut().zip(vrf_witness) {
+        col.extend(cells);
+    }
+
+    let expected = C::prime_subgroup_generator()
+        .mul(secret_key.into_repr())
+        .into_affine();
+    (expected == *public_key).then(|| vrf_output::<C>(message, secret_key))
+}
@@ -0,0 +1,308 @@
+//! This module includes the X25519 Diffie-Hellman gadget over Curve25519 (as a foreign curve),
+//! and its witness code generation.
+//! Note that this module does not include a new gate type, the gadget reuses the `Generic` gate,
+//! and the range check and foreign field gates of the ECDSA layout.
+
+//~ The X25519 gadget computes the X25519 function of RFC 7748, the u-coordinate of the multiple $[k] P$
+//~ of a point $P$ of Curve25519 $v^2 = u^3 + 486662 u^2 + u$ over $\mathbb{F}_p$, with $p = 2^{255} - 19$,
+//~ from the u-coordinate of $P$, so that a circuit proves the derivation of a shared secret
+//~ (for instance for the statements of verifiable decryption).
+//~
+//~ The scalar $k$, the u-coordinate $u$ of $P$ and the shared secret $x$ are the first 3 elements of the gadget,
+//~ canonical elements of the ECDSA layout (modulo $2^{255}$ for $k$, and modulo $p$ for $u$ and $x$),
+//~ whose limbs are at the positions given by `x25519_limb`.
+//~ The scalar is *clamped*: the bits of its decomposition (from the most significant one, as in the ECDSA gadget)
+//~ are copied to the constant $1$ for the bit $254$, and to the constant $0$ for the bits $0$, $1$ and $2$,
+//~ so that $k$ is a multiple of the cofactor $8$.
+//~
+//~ The gadget lays out the Montgomery ladder of RFC 7748 over the projective coordinates $(x_2 : z_2)$ and $(x_3 : z_3)$,
+//~ starting from $(1 : 0)$ and $(u : 1)$. For each bit $k_t$ from the bit $254$, the pairs are swapped when
+//~ $s = k_t \oplus k_{t+1}$ is set (with $k_{255} = 0$), where $s = b_1 + b_2 - 2 b_1 b_2$ in a `Generic` gate,
+//~ and are selected limb by limb as $b (u - v) + v$, then the step of the ladder computes
+//~
+//~ * $A = x_2 + z_2$, $B = x_2 - z_2$, $C = x_3 + z_3$ and $D = x_3 - z_3$,
+//~ * $x_3' = (D A + C B)^2$ and $z_3' = u (D A - C B)^2$,
+//~ * $x_2' = A^2 B^2$ and $z_2' = E (A^2 + a_{24} E)$, with $E = A^2 - B^2$ and $a_{24} = 121665$,
+//~
+//~ with the foreign field additions, subtractions and multiplications of the layout.
+//~ The pairs are swapped a last time with the bit $k_0$. Finally, the gadget checks that $z_2$ has an inverse,
+//~ and that $x \cdot z_2 = x_2$, so that $x$ is the u-coordinate $x_2 / z_2$ of $[k] P$.
+//~
+//~ The ladder reaches $z_2 = 0$ exactly for the points of small order, whose shared secret is zero,
+//~ and which RFC 7748 recommends to reject: such inputs have no witness.
+//~
+
+use crate::circuits::{gate::CircuitGate, polynomial::COLUMNS};
+use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+use super::ecdsa::{
+    curve::inverse,
+    layout::{Bit, Elem, Layout, Limb},
+    ELEM_ROWS,
+};
+
+/// Number of bits of the scalars, after clamping
+pub const X25519_SCALAR_BITS: usize = 255;
+
+/// The u-coordinate of the base point of Curve25519
+pub const X25519_BASE_POINT: u8 = 9;
+
+// The constant (486662 - 2) / 4 of the ladder
+const A24: u32 = 121665;
+
+/// The elements of the X25519 gadget, in their order
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum X25519Elem {
+    /// The clamped scalar, modulo 2^255
+    Scalar,
+    /// The u-coordinate of the point, modulo the base field modulus
+    U,
+    /// The shared secret, the u-coordinate of the product, the output
+    SharedSecret,
+}
+
+/// Returns the modulus of the base field of Curve25519, `2^255 - 19`
+pub fn x25519_modulus() -> BigUint {
+    (BigUint::one() << 255) - 19u32
+}
+
+/// Returns the position of the `limb`-th limb (from the least significant one) of an element
+/// of the X25519 gadget starting at row `new_row`, as a `(row, column)` pair
+pub fn x25519_limb(new_row: usize, elem: X25519Elem, limb: usize) -> (usize, usize) {
+    assert!(limb < 3, "An element only has three limbs");
+    (new_row + ELEM_ROWS * elem as usize + limb, 0)
+}
+
+/// Decodes and clamps a scalar of 32 bytes, as in RFC 7748
+pub fn clamp_scalar(scalar: &[u8; 32]) -> BigUint {
+    let mut bytes = *scalar;
+    bytes[0] &= 248;
+    bytes[31] &= 127;
+    bytes[31] |= 64;
+    BigUint::from_bytes_le(&bytes)
+}
+
+/// Decodes a u-coordinate of 32 bytes, as in RFC 7748: its most significant bit is masked,
+/// and it is reduced modulo the base field modulus
+pub fn decode_u_coordinate(u: &[u8; 32]) -> BigUint {
+    let mut bytes = *u;
+    bytes[31] &= 127;
+    BigUint::from_bytes_le(&bytes) % x25519_modulus()
+}
+
+/// Encodes a u-coordinate in 32 bytes
+pub fn encode_u_coordinate(u: &BigUint) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    let le = u.to_bytes_le();
+    bytes[..le.len()].copy_from_slice(&le);
+    bytes
+}
+
+// The Montgomery ladder out of the circuit, which returns the projective coordinates (x_2 : z_2) of [k] P
+fn ladder(k: &BigUint, u: &BigUint) -> (BigUint, BigUint) {
+    let p = &x25519_modulus();
+    let add = |a: &BigUint, b: &BigUint| (a + b) % p;
+    let sub = |a: &BigUint, b: &BigUint| (p + a - b) % p;
+    let mul = |a: &BigUint, b: &BigUint| a * b % p;
+    let (mut x2, mut z2) = (BigUint::one(), BigUint::zero());
+    let (mut x3, mut z3) = (u.clone(), BigUint::one());
+    let mut swap = false;
+    for t in (0..X25519_SCALAR_BITS).rev() {
+        let bit = k.bit(t as u64);
+        if swap != bit {
+            std::mem::swap(&mut x2, &mut x3);
+            std::mem::swap(&mut z2, &mut z3);
+        }
+        swap = bit;
+        let a = add(&x2, &z2);
+        let aa = mul(&a, &a);
+        let b = sub(&x2, &z2);
+        let bb = mul(&b, &b);
+        let e = sub(&aa, &bb);
+        let c = add(&x3, &z3);
+        let d = sub(&x3, &z3);
+        let da = mul(&d, &a);
+        let cb = mul(&c, &b);
+        let sum = add(&da, &cb);
+        let diff = sub(&da, &cb);
+        x3 = mul(&sum, &sum);
+        z3 = mul(u, &mul(&diff, &diff));
+        x2 = mul(&aa, &bb);
+        z2 = mul(&e, &add(&aa, &mul(&BigUint::from(A24), &e)));
+    }
+    if swap {
+        std::mem::swap(&mut x2, &mut x3);
+        std::mem::swap(&mut z2, &mut z3);
+    }
+    (x2, z2)
+}
+
+/// Computes the X25519 function of RFC 7748, the u-coordinate of the product of the point
+/// of u-coordinate `u` by the clamped scalar, which is zero for the points of small order
+pub fn x25519(scalar: &[u8; 32], u: &[u8; 32]) -> [u8; 32] {
+    let p = &x25519_modulus();
+    let (x2, z2) = ladder(&clamp_scalar(scalar), &decode_u_coordinate(u));
+    encode_u_coordinate(&(x2 * inverse(&z2, p) % p))
+}
+
+// A point in projective coordinates (x : z)
+struct Projective<F> {
+    x: Elem<F>,
+    z: Elem<F>,
+}
+
+// Lays out the swap of two points if the boolean is true
+fn layout_cswap<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    bit: &Bit,
+    p2: Projective<F>,
+    p3: Projective<F>,
+) -> (Projective<F>, Projective<F>) {
+    let swapped2 = Projective {
+        x: layout.select(bit, &p3.x, &p2.x),
+        z: layout.select(bit, &p3.z, &p2.z),
+    };
+    let swapped3 = Projective {
+        x: layout.select(bit, &p2.x, &p3.x),
+        z: layout.select(bit, &p2.z, &p3.z),
+    };
+    (swapped2, swapped3)
+}
+
+// Lays out a step of the ladder, the doubling of p2 and the differential addition of p2 and p3,
+// whose difference has the u-coordinate u
+fn layout_step<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    u: &Elem<F>,
+    p2: &Projective<F>,
+    p3: &Projective<F>,
+) -> (Projective<F>, Projective<F>) {
+    let p = &x25519_modulus();
+    let a = layout.add(&p2.x, &p2.z, p);
+    let aa = layout.mul(&a, &a, p);
+    let b = layout.sub(&p2.x, &p2.z, p);
+    let bb = layout.mul(&b, &b, p);
+    let e = layout.sub(&aa, &bb, p);
+    let c = layout.add(&p3.x, &p3.z, p);
+    let d = layout.sub(&p3.x, &p3.z, p);
+    let da = layout.mul(&d, &a, p);
+    let cb = layout.mul(&c, &b, p);
+
+    // the differential addition
+    let sum = layout.add(&da, &cb, p);
+    let diff = layout.sub(&da, &cb, p);
+    let x3 = layout.mul(&sum, &sum, p);
+    let diff2 = layout.mul(&diff, &diff, p);
+    let z3 = layout.mul(u, &diff2, p);
+
+    // the doubling
+    let x2 = layout.mul(&aa, &bb, p);
+    let a24 = layout.constant(&BigUint::from(A24));
+    let a24e = layout.mul(&a24, &e, p);
+    let aa_a24e = layout.add(&aa, &a24e, p);
+    let z2 = layout.mul(&e, &aa_a24e, p);
+    (Projective { x: x2, z: z2 }, Projective { x: x3, z: z3 })
+}
+
+// Lays out the X25519 function, with the values of the clamped scalar, of the u-coordinate
+// and of the shared secret for the witness
+fn layout_x25519<F: PrimeField + SquareRootField>(
+    layout: &mut Layout<F>,
+    values: Option<[BigUint; 3]>,
+) {
+    let p = &x25519_modulus();
+    let moduli = [BigUint::one() << X25519_SCALAR_BITS, p.clone(), p.clone()];
+    let [k, u, shared] = [0, 1, 2]
+        .map(|i| layout.witness(values.as_ref().map(|values| values[i].clone()), &moduli[i]));
+
+    // the clamping of the scalar
+    let bits = layout.bits(&k, X25519_SCALAR_BITS);
+    layout.copy(&Limb::Const(F::one()), bits[0].cell);
+    for bit in &bits[X25519_SCALAR_BITS - 3..] {
+        layout.copy(&Limb::Const(F::zero()), bit.cell);
+    }
+
+    let mut p2 = Projective {
+        x: layout.constant(&BigUint::one()),
+        z: layout.constant(&BigUint::zero()),
+    };
+    let mut p3 = Projective {
+        x: u.clone(),
+        z: layout.constant(&BigUint::one()),
+    };
+    for (t, bit) in bits.iter().enumerate() {
+        // the swap is the bit itself for the most significant bit
+        let swap = match t {
+            0 => *bit,
+            _ => layout.xor(&bits[t - 1], bit),
+        };
+        (p2, p3) = layout_cswap(layout, &swap, p2, p3);
+        (p2, p3) = layout_step(layout, &u, &p2, &p3);
+    }
+    let last = bits[X25519_SCALAR_BITS - 1];
+    let (p2, _) = layout_cswap(layout, &last, p2, p3);
+
+    // the shared secret is x_2 / z_2
+    layout.inverse(&p2.z, p);
+    layout.assert_mul(&shared, &p2.z, &p2.x, p);
+}
+
+impl<F: PrimeField + SquareRootField> CircuitGate<F> {
+    /// Extends a circuit with the X25519 function over Curve25519
+    /// Includes:
+    /// - the foreign field gates, range checks and generic gates of the Montgomery ladder (see the module)
+    /// Input:
+    /// - gates : the full circuit
+    /// Output:
+    /// - new row index
+    /// Warning:
+    /// - the scalar and the u-coordinate (see [x25519_limb]) should be copied from other cells,
+    ///   and the shared secret copied to the cells which use it
+    pub fn extend_x25519(gates: &mut Vec<Self>) -> usize {
+        let (next_row, mut x25519_gates) = Self::create_x25519(gates.len());
+        gates.append(&mut x25519_gates);
+        next_row
+    }
+
+    /// Creates the X25519 function, see [Self::extend_x25519]
+    /// Outputs tuple (next_row, circuit_gates) where
+    /// - next_row  : next row after this gadget
+    /// - gates     : vector of circuit gates comprising this gadget
+    pub fn create_x25519(new_row: usize) -> (usize, Vec<Self>) {
+        let mut layout = Layout::new(new_row, false);
+        layout_x25519(&mut layout, None);
+        let (x25519_gates, _) = layout.finalize();
+        (new_row + x25519_gates.len(), x25519_gates)
+    }
+}
+
+/// Extends the witness with the X25519 function, and returns the shared secret,
+/// or `None` for a point of small order, whose shared secret is zero,
+/// since the witness then does not satisfy the gadget
+/// Input
+/// - witness: full witness of the circuit
+/// - scalar: the scalar, as 32 bytes (which are clamped)
+/// - u: the u-coordinate of the point, as 32 bytes (which are decoded as in RFC 7748)
+pub fn extend_x25519_witness<F: PrimeField + SquareRootField>(
+    witness: &mut [Vec<F>; COLUMNS],
+    scalar: &[u8; 32],
+    u: &[u8; 32],
+) -> Option<[u8; 32]> {
+    let p = &x25519_modulus();
+    let k = clamp_scalar(scalar);
+    let u = decode_u_coordinate(u);
+    let (x2, z2) = ladder(&k, &u);
+    let shared = x2 * inverse(&z2, p) % p;
+    let shared_bytes = encode_u_coordinate(&shared);
+
+    let mut layout = Layout::new(witness[0].len(), true);
+    layout_x25519(&mut layout, Some([k, u, shared]));
+    let (_, x25519_witness) = layout.finalize();
+    let x25519_witness = x25519_witness.expect("the witness is laid out");
+    for (col, cells) in witness.iter_mut().zip(x25519_witness) {
+        col.extend(cells);
+    }
+    (!z2.is_zero()).then_some(shared_bytes)
+}
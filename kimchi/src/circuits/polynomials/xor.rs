@@ -12,8 +12,12 @@ use crate::{
         },
         polynomial::COLUMNS,
         wires::Wire,
-        witness::{self, ConstantCell, CopyBitsCell, VariableBitsCell, Variables, WitnessCell},
+        witness::{
+            self, ConstantCell, CopyBitsCell, Solve, SolverEnv, VariableBitsCell, Variables,
+            WitnessCell,
+        },
     },
+    error::SolveError,
     variable_map,
 };
 use ark_ff::{PrimeField, SquareRootField};
@@ -168,6 +172,38 @@ where
     }
 }
 
+impl<F: PrimeField> Solve<F> for Xor16<F> {
+    // The word which is unknown is the xor of the two others, then the words are decomposed
+    // into their nybbles and their words shifted by 16 bits, in the next row
+    fn solve(env: &mut SolverEnv<F>, _coeffs: &[F]) -> Result<bool, SolveError> {
+        let mut words: [Option<BigUint>; 3] = array::from_fn(|col| env.curr(col).map(|w| w.into()));
+        let unknown = match &words {
+            [Some(in1), Some(in2), None] => Some((2, BigUint::bitwise_xor(in1, in2))),
+            [Some(in1), None, Some(out)] => Some((1, BigUint::bitwise_xor(in1, out))),
+            [None, Some(in2), Some(out)] => Some((0, BigUint::bitwise_xor(in2, out))),
+            [Some(_), Some(_), Some(_)] => None,
+            _ => return Ok(false),
+        };
+        if let Some((col, word)) = unknown {
+            // the xor of two field elements can be too large for the field
+            match word.clone().to_field() {
+                Ok(value) => env.set_curr(col, value)?,
+                Err(_) => return Ok(false),
+            }
+            words[col] = Some(word);
+        }
+        for (i, word) in words.into_iter().enumerate() {
+            let word = word.expect("the words are known");
+            for j in 0..4 {
+                let nybble: BigUint = (&word >> (4 * j)) % 16u32;
+                env.set_curr(3 + 4 * i + j, nybble.to_field().expect("a nybble fits"))?;
+            }
+            env.set_next(i, (word >> 16).to_field().expect("a shifted word fits"))?;
+        }
+        Ok(true)
+    }
+}
+
 // Witness layout
 fn layout<F: PrimeField>(curr_row: usize, bits: usize) -> Vec<[Box<dyn WitnessCell<F>>; COLUMNS]> {
     let num_xor = num_xors(bits);
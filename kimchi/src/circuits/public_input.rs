@@ -0,0 +1,635 @@
+//! This module implements the [PublicInput] trait and the [PublicInputBuilder],
+//! to map typed values to the public input rows of a circuit.
+//!
+//! The public input of a circuit is a vector of native field elements, one per public input row.
+//! Instead of packing this vector by hand, a struct can derive [PublicInput](derive@PublicInput)
+//! to lay out its fields one after the other:
+//!
+//! ```
+//! use kimchi::circuits::public_input::PublicInput;
+//! use mina_curves::pasta::{Fp, Pallas};
+//! use o1_utils::foreign_field::ForeignElement;
+//!
+//! #[derive(PublicInput)]
+//! struct Claim {
+//!     hash: Fp,
+//!     amount: ForeignElement<Fp, 3>,
+//!     key: Pallas,
+//! }
+//!
+//! assert_eq!(<Claim as PublicInput<Fp>>::SIZE, 6);
+//! ```
+//!
+//! When the public inputs are only known at runtime, a [PublicInputBuilder] registers them by name,
+//! and the [PublicInputLayout] it builds reads and writes each of them in a public input vector:
+//!
+//! ```
+//! use kimchi::circuits::public_input::PublicInputBuilder;
+//! use mina_curves::pasta::Fp;
+//! use num_bigint::BigUint;
+//!
+//! let layout = PublicInputBuilder::new()
+//!     .field("hash")
+//!     .foreign_element("amount")
+//!     .scalar("challenge")
+//!     .build();
+//! assert_eq!(layout.size(), 6);
+//! assert_eq!(layout.rows("challenge"), Some(4..6));
+//!
+//! let mut public = layout.zero::<Fp>();
+//! layout.set_scalar(&mut public, "challenge", &(BigUint::from(1u8) << 254));
+//! assert_eq!(layout.scalar(&public, "challenge"), Ok(BigUint::from(1u8) << 254));
+//! ```
+
+use ark_ec::{short_weierstrass_jacobian::GroupAffine, ModelParameters, SWModelParameters};
+use ark_ff::{Field, Fp256, Fp256Parameters, Fp384, Fp384Parameters, PrimeField, Zero};
+use num_bigint::BigUint;
+use o1_utils::{foreign_field::ForeignElement, FieldHelpers};
+use std::{array, ops::Range};
+use thiserror::Error;
+
+pub use kimchi_derive::PublicInput;
+
+/// Errors that can arise when reading a [PublicInput]
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicInputError {
+    #[error("the public input is too short (expected at least {expected} elements, got {got})")]
+    TooShort { expected: usize, got: usize },
+
+    #[error("the public input has an unexpected length (expected {expected} elements, got {got})")]
+    IncorrectLength { expected: usize, got: usize },
+
+    #[error("the public input contains a point that is not in the group")]
+    InvalidPoint,
+
+    #[error(
+        "the public input contains a scalar which is not split in {} bits and a bit",
+        SCALAR_HIGH_BITS
+    )]
+    InvalidScalar,
+}
+
+/// A value that occupies [PublicInput::SIZE] public input rows of a circuit over `F`
+pub trait PublicInput<F>: Sized {
+    /// The number of field elements of the value
+    const SIZE: usize;
+
+    /// Appends the field elements of the value to the public input vector
+    fn write_public_input(&self, public: &mut Vec<F>);
+
+    /// Reads a value from the first [PublicInput::SIZE] elements of `public`
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `public` is too short, or does not encode a valid value.
+    fn read_public_input(public: &[F]) -> Result<Self, PublicInputError>;
+
+    /// The public input vector of the value
+    fn to_public_input(&self) -> Vec<F> {
+        let mut public = Vec::with_capacity(Self::SIZE);
+        self.write_public_input(&mut public);
+        public
+    }
+
+    /// Reads a value from a public input vector of exactly [PublicInput::SIZE] elements
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `public` is not of the expected length, or does not encode a valid value.
+    fn from_public_input(public: &[F]) -> Result<Self, PublicInputError> {
+        if public.len() != Self::SIZE {
+            return Err(PublicInputError::IncorrectLength {
+                expected: Self::SIZE,
+                got: public.len(),
+            });
+        }
+        Self::read_public_input(public)
+    }
+}
+
+/// Checks that `public` holds at least `size` elements
+fn check_len<F>(public: &[F], size: usize) -> Result<(), PublicInputError> {
+    if public.len() < size {
+        return Err(PublicInputError::TooShort {
+            expected: size,
+            got: public.len(),
+        });
+    }
+    Ok(())
+}
+
+// native field elements take a single row
+// (implemented for each representation, so that it does not overlap with the implementations below)
+macro_rules! impl_public_input_field {
+    ($field:ident, $params:ident) => {
+        impl<P: $params> PublicInput<$field<P>> for $field<P> {
+            const SIZE: usize = 1;
+
+            fn write_public_input(&self, public: &mut Vec<$field<P>>) {
+                public.push(*self);
+            }
+
+            fn read_public_input(public: &[$field<P>]) -> Result<Self, PublicInputError> {
+                check_len(public, 1)?;
+                Ok(public[0])
+            }
+        }
+    };
+}
+
+impl_public_input_field!(Fp256, Fp256Parameters);
+impl_public_input_field!(Fp384, Fp384Parameters);
+
+// foreign field elements take one row per limb
+impl<F: Field, const N: usize> PublicInput<F> for ForeignElement<F, N> {
+    const SIZE: usize = N;
+
+    fn write_public_input(&self, public: &mut Vec<F>) {
+        public.extend_from_slice(&self.limbs);
+    }
+
+    fn read_public_input(public: &[F]) -> Result<Self, PublicInputError> {
+        check_len(public, N)?;
+        Ok(ForeignElement::new(array::from_fn(|i| public[i])))
+    }
+}
+
+// points of a curve over the native field take two rows for their coordinates,
+// with the point at infinity encoded as (0, 0) (which is not on the curve, as its constant is non-zero)
+impl<P: SWModelParameters> PublicInput<<P as ModelParameters>::BaseField> for GroupAffine<P> {
+    const SIZE: usize = 2;
+
+    fn write_public_input(&self, public: &mut Vec<P::BaseField>) {
+        if self.infinity {
+            public.extend([P::BaseField::zero(), P::BaseField::zero()]);
+        } else {
+            public.extend([self.x, self.y]);
+        }
+    }
+
+    fn read_public_input(public: &[P::BaseField]) -> Result<Self, PublicInputError> {
+        check_len(public, 2)?;
+        let (x, y) = (public[0], public[1]);
+        if x.is_zero() && y.is_zero() {
+            return Ok(Self::zero());
+        }
+        let point = Self::new(x, y, false);
+        if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(PublicInputError::InvalidPoint);
+        }
+        Ok(point)
+    }
+}
+
+// arrays take the rows of their elements, in order
+impl<F, T: PublicInput<F>, const N: usize> PublicInput<F> for [T; N] {
+    const SIZE: usize = N * T::SIZE;
+
+    fn write_public_input(&self, public: &mut Vec<F>) {
+        for value in self {
+            value.write_public_input(public);
+        }
+    }
+
+    fn read_public_input(public: &[F]) -> Result<Self, PublicInputError> {
+        check_len(public, Self::SIZE)?;
+        let values = (0..N)
+            .map(|i| T::read_public_input(&public[i * T::SIZE..]))
+            .collect::<Result<Vec<_>, _>>()?;
+        // there are exactly N values
+        Ok(values.try_into().unwrap_or_else(|_| unreachable!()))
+    }
+}
+
+/// The number of limbs of the foreign field elements of a [PublicInputLayout]
+pub const FOREIGN_ELEMENT_LIMBS: usize = 3;
+
+/// The number of bits of the high part of the scalars of a [PublicInputLayout],
+/// the scalars of up to 255 bits being split in their high bits and their low bit,
+/// so that they fit in the native field even if their field is larger
+pub const SCALAR_HIGH_BITS: usize = 254;
+
+/// The kind of a named input of a [PublicInputLayout]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PublicInputKind {
+    /// A native field element, in one row
+    Field,
+    /// A foreign field element, in [FOREIGN_ELEMENT_LIMBS] rows for its limbs
+    ForeignElement,
+    /// A scalar of up to 255 bits, in two rows for its high bits and its low bit
+    Scalar,
+}
+
+impl PublicInputKind {
+    /// Returns the number of public input rows of an input of this kind
+    pub fn size(self) -> usize {
+        match self {
+            PublicInputKind::Field => 1,
+            PublicInputKind::ForeignElement => FOREIGN_ELEMENT_LIMBS,
+            PublicInputKind::Scalar => 2,
+        }
+    }
+}
+
+/// The builder of a [PublicInputLayout], registering the named inputs one after the other,
+/// see the [module documentation](self)
+#[derive(Clone, Debug, Default)]
+pub struct PublicInputBuilder {
+    inputs: Vec<(String, PublicInputKind, usize)>,
+    size: usize,
+}
+
+impl PublicInputBuilder {
+    /// Creates a builder without inputs
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers an input of a kind, in the rows after the previous inputs
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the name is already used.
+    pub fn input(mut self, name: &str, kind: PublicInputKind) -> Self {
+        assert!(
+            self.inputs.iter().all(|(input, _, _)| input != name),
+            "the name {name} is already used by a public input"
+        );
+        self.inputs.push((name.to_string(), kind, self.size));
+        self.size += kind.size();
+        self
+    }
+
+    /// Registers a native field element, see [Self::input]
+    pub fn field(self, name: &str) -> Self {
+        self.input(name, PublicInputKind::Field)
+    }
+
+    /// Registers a foreign field element, see [Self::input]
+    pub fn foreign_element(self, name: &str) -> Self {
+        self.input(name, PublicInputKind::ForeignElement)
+    }
+
+    /// Registers a scalar of up to 255 bits, see [Self::input]
+    pub fn scalar(self, name: &str) -> Self {
+        self.input(name, PublicInputKind::Scalar)
+    }
+
+    /// Returns the layout of the registered inputs
+    pub fn build(self) -> PublicInputLayout {
+        PublicInputLayout {
+            inputs: self.inputs,
+            size: self.size,
+        }
+    }
+}
+
+/// The layout of named inputs in the public input vector of a circuit,
+/// built by [PublicInputBuilder]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicInputLayout {
+    inputs: Vec<(String, PublicInputKind, usize)>,
+    size: usize,
+}
+
+impl PublicInputLayout {
+    /// Returns the number of elements of the public input vector
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the kind of an input, if any
+    pub fn kind(&self, name: &str) -> Option<PublicInputKind> {
+        self.find(name).map(|(kind, _)| kind)
+    }
+
+    /// Returns the rows of an input in the public input vector, if any
+    pub fn rows(&self, name: &str) -> Option<Range<usize>> {
+        self.find(name)
+            .map(|(kind, start)| start..start + kind.size())
+    }
+
+    /// Returns a public input vector of zeros, to be filled by the setters of the inputs
+    pub fn zero<F: Field>(&self) -> Vec<F> {
+        vec![F::zero(); self.size]
+    }
+
+    /// Sets a native field element in a public input vector
+    ///
+    /// # Panics
+    ///
+    /// Will panic if there is no field element `name`,
+    /// or if the vector does not have the layout size.
+    pub fn set_field<F: Field>(&self, public: &mut [F], name: &str, value: F) {
+        self.slice_mut(public, name, PublicInputKind::Field)[0] = value;
+    }
+
+    /// Sets a foreign field element in a public input vector
+    ///
+    /// # Panics
+    ///
+    /// Will panic if there is no foreign field element `name`,
+    /// or if the vector does not have the layout size.
+    pub fn set_foreign_element<F: Field>(
+        &self,
+        public: &mut [F],
+        name: &str,
+        value: &ForeignElement<F, FOREIGN_ELEMENT_LIMBS>,
+    ) {
+        self.slice_mut(public, name, PublicInputKind::ForeignElement)
+            .copy_from_slice(&value.limbs);
+    }
+
+    /// Sets a scalar in a public input vector, as its high bits and its low bit
+    ///
+    /// # Panics
+    ///
+    /// Will panic if there is no scalar `name`, if the vector does not have the layout size,
+    /// or if the scalar has more than 255 bits.
+    pub fn set_scalar<F: PrimeField>(&self, public: &mut [F], name: &str, value: &BigUint) {
+        assert!(
+            value.bits() <= SCALAR_HIGH_BITS as u64 + 1,
+            "the scalar has more than 255 bits"
+        );
+        let high = F::from_biguint(&(value >> 1u32)).expect("the high bits fit in the field");
+        let low = F::from(value.bit(0));
+        self.slice_mut(public, name, PublicInputKind::Scalar)
+            .copy_from_slice(&[high, low]);
+    }
+
+    /// Reads a native field element from a public input vector
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the vector does not have the layout size.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if there is no field element `name`.
+    pub fn field<F: Field>(&self, public: &[F], name: &str) -> Result<F, PublicInputError> {
+        Ok(self.slice(public, name, PublicInputKind::Field)?[0])
+    }
+
+    /// Reads a foreign field element from a public input vector
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the vector does not have the layout size.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if there is no foreign field element `name`.
+    pub fn foreign_element<F: Field>(
+        &self,
+        public: &[F],
+        name: &str,
+    ) -> Result<ForeignElement<F, FOREIGN_ELEMENT_LIMBS>, PublicInputError> {
+        ForeignElement::read_public_input(self.slice(
+            public,
+            name,
+            PublicInputKind::ForeignElement,
+        )?)
+    }
+
+    /// Reads a scalar from a public input vector
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the vector does not have the layout size,
+    /// or if the scalar is not split in its high bits and its low bit.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if there is no scalar `name`.
+    pub fn scalar<F: PrimeField>(
+        &self,
+        public: &[F],
+        name: &str,
+    ) -> Result<BigUint, PublicInputError> {
+        let rows = self.slice(public, name, PublicInputKind::Scalar)?;
+        let (high, low) = (rows[0].to_biguint(), rows[1]);
+        if high.bits() > SCALAR_HIGH_BITS as u64 || !(low.is_zero() || low.is_one()) {
+            return Err(PublicInputError::InvalidScalar);
+        }
+        Ok((high << 1u32) + low.to_biguint())
+    }
+
+    // Returns the kind and the first row of an input
+    fn find(&self, name: &str) -> Option<(PublicInputKind, usize)> {
+        self.inputs
+            .iter()
+            .find(|(input, _, _)| input == name)
+            .map(|(_, kind, start)| (*kind, *start))
+    }
+
+    // Returns the rows of an input of a kind, panicking if the layout has no such input
+    fn input_rows(&self, name: &str, kind: PublicInputKind) -> Range<usize> {
+        match self.find(name) {
+            Some((input_kind, start)) if input_kind == kind => start..start + kind.size(),
+            _ => panic!("the layout has no public input {name} of kind {kind:?}"),
+        }
+    }
+
+    // Returns the rows of an input in a public input vector of the layout size
+    fn slice<'a, F>(
+        &self,
+        public: &'a [F],
+        name: &str,
+        kind: PublicInputKind,
+    ) -> Result<&'a [F], PublicInputError> {
+        if public.len() != self.size {
+            return Err(PublicInputError::IncorrectLength {
+                expected: self.size,
+                got: public.len(),
+            });
+        }
+        Ok(&public[self.input_rows(name, kind)])
+    }
+
+    // Returns the rows of an input in a public input vector, which must have the layout size
+    fn slice_mut<'a, F>(
+        &self,
+        public: &'a mut [F],
+        name: &str,
+        kind: PublicInputKind,
+    ) -> &'a mut [F] {
+        assert_eq!(
+            public.len(),
+            self.size,
+            "the public input vector does not have the size of the layout"
+        );
+        &mut public[self.input_rows(name, kind)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::{AffineCurve, ProjectiveCurve};
+    use mina_curves::pasta::{Fp, Fq, Pallas};
+    use num_bigint::BigUint;
+
+    #[derive(PublicInput, Debug, Clone, PartialEq)]
+    struct Claim {
+        hash: Fp,
+        amount: ForeignElement<Fp, 3>,
+        keys: [Pallas; 2],
+    }
+
+    #[derive(PublicInput, Debug, PartialEq)]
+    struct Pair<T>(T, T);
+
+    fn claim() -> Claim {
+        let generator = Pallas::prime_subgroup_generator();
+        Claim {
+            hash: Fp::from(42u8),
+            amount: ForeignElement::from_biguint(BigUint::from(1u128 << 100)),
+            keys: [
+                generator,
+                (generator.into_projective() + generator.into_projective()).into_affine(),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_public_input_layout() {
+        let claim = claim();
+        assert_eq!(<Claim as PublicInput<Fp>>::SIZE, 1 + 3 + 2 * 2);
+
+        let public = claim.to_public_input();
+        assert_eq!(public.len(), <Claim as PublicInput<Fp>>::SIZE);
+        assert_eq!(public[0], claim.hash);
+        assert_eq!(&public[1..4], &claim.amount.limbs);
+        assert_eq!(public[4..6], [claim.keys[0].x, claim.keys[0].y]);
+        assert_eq!(public[6..8], [claim.keys[1].x, claim.keys[1].y]);
+
+        assert_eq!(Claim::from_public_input(&public), Ok(claim));
+    }
+
+    #[test]
+    fn test_public_input_generic() {
+        let pair = Pair(Fp::from(1u8), Fp::from(2u8));
+        assert_eq!(<Pair<Fp> as PublicInput<Fp>>::SIZE, 2);
+        let public = pair.to_public_input();
+        assert_eq!(public, vec![Fp::from(1u8), Fp::from(2u8)]);
+        assert_eq!(Pair::from_public_input(&public), Ok(pair));
+
+        // the point at infinity
+        let zero = Pallas::zero();
+        let public = zero.to_public_input();
+        assert_eq!(public, vec![Fp::zero(), Fp::zero()]);
+        assert_eq!(Pallas::from_public_input(&public), Ok(zero));
+    }
+
+    #[test]
+    fn test_public_input_errors() {
+        let public = claim().to_public_input();
+        assert_eq!(
+            Claim::from_public_input(&public[..7]),
+            Err(PublicInputError::IncorrectLength {
+                expected: 8,
+                got: 7
+            })
+        );
+        assert_eq!(
+            Claim::read_public_input(&public[..7]),
+            Err(PublicInputError::TooShort {
+                expected: 8,
+                got: 7
+            })
+        );
+
+        // a larger public input can be read from its prefix
+        let mut longer = public.clone();
+        longer.push(Fp::from(1u8));
+        assert_eq!(Claim::read_public_input(&longer), Ok(claim()));
+
+        // a point not on the curve
+        let mut invalid = public;
+        invalid[5] += Fp::from(1u8);
+        assert_eq!(
+            Claim::from_public_input(&invalid),
+            Err(PublicInputError::InvalidPoint)
+        );
+    }
+
+    fn layout() -> PublicInputLayout {
+        PublicInputBuilder::new()
+            .field("hash")
+            .foreign_element("amount")
+            .scalar("challenge")
+            .field("nonce")
+            .build()
+    }
+
+    #[test]
+    fn test_public_input_builder() {
+        let layout = layout();
+        assert_eq!(layout.size(), 1 + 3 + 2 + 1);
+        assert_eq!(layout.rows("amount"), Some(1..4));
+        assert_eq!(layout.rows("nonce"), Some(6..7));
+        assert_eq!(layout.kind("challenge"), Some(PublicInputKind::Scalar));
+        assert_eq!(layout.rows("key"), None);
+
+        // the largest scalar of the other field of the cycle
+        let challenge = Fq::modulus_biguint() - 1u8;
+        let amount = ForeignElement::from_biguint(BigUint::from(1u128 << 100));
+        let mut public = layout.zero();
+        layout.set_field(&mut public, "hash", Fp::from(42u8));
+        layout.set_foreign_element(&mut public, "amount", &amount);
+        layout.set_scalar(&mut public, "challenge", &challenge);
+        layout.set_field(&mut public, "nonce", Fp::from(7u8));
+
+        let mut expected = vec![Fp::from(42u8)];
+        expected.extend(amount.limbs);
+        expected.push(Fp::from_biguint(&(&challenge >> 1u32)).unwrap());
+        expected.push(Fp::from(challenge.bit(0)));
+        expected.push(Fp::from(7u8));
+        assert_eq!(public, expected);
+
+        assert_eq!(layout.field(&public, "hash"), Ok(Fp::from(42u8)));
+        assert_eq!(layout.foreign_element(&public, "amount"), Ok(amount));
+        assert_eq!(layout.scalar(&public, "challenge"), Ok(challenge));
+        assert_eq!(layout.field(&public, "nonce"), Ok(Fp::from(7u8)));
+    }
+
+    #[test]
+    fn test_public_input_builder_errors() {
+        let layout = layout();
+        let mut public = layout.zero::<Fp>();
+        assert_eq!(
+            layout.field(&public[..6], "hash"),
+            Err(PublicInputError::IncorrectLength {
+                expected: 7,
+                got: 6
+            })
+        );
+
+        // the low part of a scalar is not a bit
+        public[5] = Fp::from(2u8);
+        assert_eq!(
+            layout.scalar(&public, "challenge"),
+            Err(PublicInputError::InvalidScalar)
+        );
+        // the high part of a scalar has more than 254 bits
+        public[4] = -Fp::from(1u8);
+        public[5] = Fp::zero();
+        assert_eq!(
+            layout.scalar(&public, "challenge"),
+            Err(PublicInputError::InvalidScalar)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_public_input_builder_duplicate_name() {
+        PublicInputBuilder::new().field("hash").scalar("hash");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_public_input_builder_wrong_kind() {
+        let layout = layout();
+        let _ = layout.field(&layout.zero::<Fp>(), "amount");
+    }
+}
@@ -0,0 +1,215 @@
+//! This module implements the scheduling of the independent blocks of gates of a circuit,
+//! reordering them to reduce the number of rows (and the domain size) of the optimized circuit.
+//!
+//! The domain of a circuit is the smallest power of two holding its rows and its zero-knowledge rows,
+//! so that a circuit just above a power of two pays twice the proving time of the power of two.
+//! The optimizer (see [Optimization]) only removes the `Zero` gates and packs the single generic gates
+//! which follow a `Generic` or `Zero` gate, since a multi-row gate may constrain its next row:
+//! a block starting with such a gate after a block ending with another gate, such as a `Select`
+//! or an elliptic curve addition, keeps its row.
+//!
+//! A block is a range of consecutive rows of the circuit, given by its first row, whose gates do not
+//! constrain the first row of the next block: for example the rows of a gadget or of an instance of
+//! a sub-circuit (see [SubCircuitInstance::start_row](crate::circuits::sub_circuit::SubCircuitInstance)).
+//! The rows before the first block, such as the public input rows, are kept in place.
+//! The blocks can be wired to each other: moving rows keeps the copy constraints between their cells.
+//!
+//! The scheduler places the blocks ending with `Generic` or `Zero` gates before the blocks whose
+//! first gate can be removed or packed, and the other blocks before the blocks starting with
+//! other gates. The blocks are only reordered if it reduces the number of rows of the optimized
+//! circuit, and the schedule of a circuit only depends on the circuit and its blocks.
+//! The returned [Schedule] lays out the witness of the original circuit in the scheduled circuit,
+//! and reports the size of the domain of the optimized circuit.
+
+use crate::circuits::{
+    gate::{CircuitGate, GateType},
+    optimizer::Optimization,
+    packing::is_single_generic,
+    polynomial::COLUMNS,
+    polynomials::permutation::ZK_ROWS,
+    wires::Wire,
+};
+use ark_ff::PrimeField;
+use std::{array, ops::Range};
+
+/// The position of the rows of a circuit in its scheduled circuit, see [Schedule::create]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Schedule {
+    // for each row of the original circuit, its row in the scheduled circuit
+    rows: Vec<usize>,
+    // the indexes of the blocks, in their scheduled order
+    order: Vec<usize>,
+    // the number of rows of the optimized circuit
+    optimized_rows: usize,
+}
+
+// A block of rows, whether its first gate is removed or packed after a `Generic` or `Zero` gate,
+// and whether its last gate is not such a gate
+struct Block {
+    rows: Range<usize>,
+    packable_start: bool,
+    closed_end: bool,
+}
+
+// Returns whether no gate may constrain the row after a gate
+fn is_open<F: PrimeField>(gate: &CircuitGate<F>) -> bool {
+    matches!(gate.typ, GateType::Generic | GateType::Zero)
+}
+
+// Orders the blocks following the gates before them, which end with an open gate or not,
+// so that the packable starts follow an open gate when possible
+fn greedy_order(blocks: &[Block], mut open: bool) -> Vec<usize> {
+    let mut left: Vec<usize> = (0..blocks.len()).collect();
+    let mut order = Vec::with_capacity(blocks.len());
+    while !left.is_empty() {
+        // the preferred blocks, by packable start and closed end
+        let preferences = if open {
+            [(true, false), (true, true), (false, false), (false, true)]
+        } else {
+            [(false, true), (false, false), (true, false), (true, true)]
+        };
+        let i = preferences
+            .iter()
+            .find_map(|kind| {
+                left.iter().position(|block| {
+                    (blocks[*block].packable_start, blocks[*block].closed_end) == *kind
+                })
+            })
+            .expect("a block is left");
+        let block = left.remove(i);
+        open = !blocks[block].closed_end;
+        order.push(block);
+    }
+    order
+}
+
+// Lays out the blocks of a circuit in the given order, after the rows before the first block
+fn reorder<F: PrimeField>(
+    gates: &[CircuitGate<F>],
+    blocks: &[Block],
+    order: &[usize],
+) -> (Vec<CircuitGate<F>>, Vec<usize>) {
+    let first = blocks.first().map_or(gates.len(), |block| block.rows.start);
+    let mut rows: Vec<usize> = (0..gates.len()).collect();
+    let mut next_row = first;
+    for block in order {
+        for row in blocks[*block].rows.clone() {
+            rows[row] = next_row;
+            next_row += 1;
+        }
+    }
+
+    let mut scheduled = gates.to_vec();
+    for (row, gate) in gates.iter().enumerate() {
+        let wires = gate.wires.map(|wire| Wire {
+            row: rows[wire.row],
+            col: wire.col,
+        });
+        scheduled[rows[row]] = CircuitGate::new(gate.typ, wires, gate.coeffs.clone());
+    }
+    (scheduled, rows)
+}
+
+impl Schedule {
+    /// Schedules the blocks of a circuit whose first `public` rows are public inputs,
+    /// given by the first rows of the blocks, in increasing order.
+    /// Returns the scheduled circuit, and the position of the rows of the original circuit in it.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the first rows of the blocks are not increasing rows of the circuit,
+    /// after the public input rows.
+    pub fn create<F: PrimeField>(
+        gates: &[CircuitGate<F>],
+        public: usize,
+        blocks: &[usize],
+    ) -> (Vec<CircuitGate<F>>, Self) {
+        assert!(
+            blocks.first().map_or(true, |first| *first >= public),
+            "the blocks start before the public input rows"
+        );
+        assert!(
+            blocks.windows(2).all(|pair| pair[0] < pair[1])
+                && blocks.last().map_or(true, |last| *last < gates.len()),
+            "the first rows of the blocks are not increasing rows of the circuit"
+        );
+        let blocks: Vec<Block> = blocks
+            .iter()
+            .enumerate()
+            .map(|(i, start)| {
+                let end = blocks.get(i + 1).copied().unwrap_or(gates.len());
+                let first = &gates[*start];
+                Block {
+                    rows: *start..end,
+                    packable_start: first.typ == GateType::Zero || is_single_generic(first, *start),
+                    closed_end: !is_open(&gates[end - 1]),
+                }
+            })
+            .collect();
+
+        // the scheduled order is kept if it reduces the rows of the optimized circuit
+        let optimized_rows =
+            |gates: &[CircuitGate<F>]| Optimization::create(gates, public).1.num_rows();
+        let order: Vec<usize> = (0..blocks.len()).collect();
+        let open = blocks.first().map_or(true, |block| {
+            block.rows.start == 0 || is_open(&gates[block.rows.start - 1])
+        });
+        let greedy = greedy_order(&blocks, open);
+        let (greedy_gates, greedy_rows) = reorder(gates, &blocks, &greedy);
+        let rows = optimized_rows(gates);
+        let schedule_rows = optimized_rows(&greedy_gates);
+        if schedule_rows < rows {
+            let schedule = Schedule {
+                rows: greedy_rows,
+                order: greedy,
+                optimized_rows: schedule_rows,
+            };
+            (greedy_gates, schedule)
+        } else {
+            let schedule = Schedule {
+                rows: (0..gates.len()).collect(),
+                order,
+                optimized_rows: rows,
+            };
+            (gates.to_vec(), schedule)
+        }
+    }
+
+    /// The indexes of the blocks, in their order in the scheduled circuit
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    /// The number of rows of the scheduled circuit once optimized, see [Optimization]
+    pub fn optimized_rows(&self) -> usize {
+        self.optimized_rows
+    }
+
+    /// The size of the domain of the scheduled circuit once optimized,
+    /// if its lookup tables need fewer rows than its gates
+    pub fn domain_size(&self) -> usize {
+        (self.optimized_rows + ZK_ROWS as usize).next_power_of_two()
+    }
+
+    /// The position of a cell of the original circuit in the scheduled circuit
+    pub fn cell(&self, wire: Wire) -> Wire {
+        Wire {
+            row: self.rows[wire.row],
+            col: wire.col,
+        }
+    }
+
+    /// Lays out the witness of the original circuit (with a row per gate) in the scheduled circuit
+    pub fn schedule_witness<F: PrimeField>(
+        &self,
+        witness: &[Vec<F>; COLUMNS],
+    ) -> [Vec<F>; COLUMNS] {
+        array::from_fn(|col| {
+            let mut column = witness[col].clone();
+            for (row, scheduled_row) in self.rows.iter().enumerate() {
+                column[*scheduled_row] = witness[col][row];
+            }
+            column
+        })
+    }
+}
@@ -0,0 +1,147 @@
+//! This module implements [CircuitStats], the statistics of the rows used by a circuit:
+//! the rows of each gate type, the lookups of the gates, and the cells wired by the permutation,
+//! to see where the rows of a circuit go before proving it (see [ConstraintSystem::stats]),
+//! or before building it (see [Builder::stats](crate::circuits::constraints::Builder::stats)).
+
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::{CircuitGate, CurrOrNext, GateType},
+    lookup::lookups::LookupPattern,
+    polynomials::{generic::GENERIC_COEFFS, permutation::ZK_ROWS},
+    wires::{Wire, PERMUTS},
+};
+use ark_ff::PrimeField;
+use ark_poly::EvaluationDomain;
+use std::{collections::BTreeMap, fmt};
+
+/// The statistics of the rows of a circuit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CircuitStats {
+    /// The size of the evaluation domain
+    pub domain_size: usize,
+    /// The number of rows of the circuit, before its padding to the domain
+    pub rows: usize,
+    /// The number of rows reserved for zero-knowledge, at the end of the domain
+    pub zk_rows: usize,
+    /// The number of public inputs
+    pub public: usize,
+    /// The number of rows of each gate type (the padding is not counted)
+    pub gates: BTreeMap<GateType, usize>,
+    /// The number of generic rows holding a single generic gate, which could be packed two per row
+    pub single_generic_gates: usize,
+    /// The number of lookups of the gates
+    pub lookups: usize,
+    /// The number of entries of the runtime tables
+    pub runtime_table_entries: usize,
+    /// The number of cells of each wired column constrained by the permutation to other cells
+    pub wired_cells: [usize; PERMUTS],
+}
+
+impl CircuitStats {
+    /// The number of rows of the domain left free before the zero-knowledge rows
+    pub fn free_rows(&self) -> usize {
+        self.domain_size.saturating_sub(self.zk_rows + self.rows)
+    }
+}
+
+impl fmt::Display for CircuitStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} rows in a domain of size {} ({} free, {} zero-knowledge)",
+            self.rows,
+            self.domain_size,
+            self.free_rows(),
+            self.zk_rows
+        )?;
+        writeln!(f, "{} public inputs", self.public)?;
+        for (typ, rows) in &self.gates {
+            writeln!(f, "{typ:?}: {rows} rows")?;
+        }
+        writeln!(
+            f,
+            "{} single generic gates, {} lookups, {} runtime table entries",
+            self.single_generic_gates, self.lookups, self.runtime_table_entries
+        )?;
+        write!(f, "wired cells per column: {:?}", self.wired_cells)
+    }
+}
+
+impl CircuitStats {
+    // The statistics of the gates of a circuit, in a domain of size `domain_size`
+    pub(crate) fn create<F: PrimeField>(
+        all_gates: &[CircuitGate<F>],
+        public: usize,
+        domain_size: usize,
+        runtime_table_entries: usize,
+    ) -> Self {
+        // the padding is made of the trailing zero gates wired to themselves
+        let is_padding = |(row, gate): (usize, &CircuitGate<F>)| {
+            gate.typ == GateType::Zero && gate.wires == Wire::for_row(row)
+        };
+        let rows = all_gates
+            .iter()
+            .enumerate()
+            .rposition(|row_gate| !is_padding(row_gate))
+            .map_or(0, |row| row + 1);
+
+        let mut gates = BTreeMap::new();
+        let mut single_generic_gates = 0;
+        let mut lookups = 0;
+        let mut wired_cells = [0; PERMUTS];
+        for (row, gate) in all_gates.iter().enumerate().take(rows) {
+            *gates.entry(gate.typ).or_insert(0) += 1;
+            if gate.typ == GateType::Generic
+                && gate.coeffs.iter().skip(GENERIC_COEFFS).all(F::is_zero)
+            {
+                single_generic_gates += 1;
+            }
+
+            // the lookups of a row: the ones of its gate, and of the previous gate on the next row
+            let curr = LookupPattern::from_gate(gate.typ, CurrOrNext::Curr);
+            let next = row
+                .checked_sub(1)
+                .and_then(|prev| LookupPattern::from_gate(all_gates[prev].typ, CurrOrNext::Next));
+            lookups += [curr, next]
+                .iter()
+                .flatten()
+                .map(|pattern| pattern.lookups::<F>().len())
+                .sum::<usize>();
+
+            for (col, wire) in gate.wires.iter().enumerate() {
+                if *wire != (Wire { row, col }) {
+                    wired_cells[col] += 1;
+                }
+            }
+        }
+
+        CircuitStats {
+            domain_size,
+            rows,
+            zk_rows: ZK_ROWS as usize,
+            public,
+            gates,
+            single_generic_gates,
+            lookups,
+            runtime_table_entries,
+            wired_cells,
+        }
+    }
+}
+
+impl<F: PrimeField> ConstraintSystem<F> {
+    /// Returns the statistics of the rows of the circuit, see [CircuitStats]
+    pub fn stats(&self) -> CircuitStats {
+        let runtime_table_entries = self
+            .lookup_constraint_system
+            .as_ref()
+            .and_then(|lcs| lcs.runtime_tables.as_ref())
+            .map_or(0, |specs| specs.iter().map(|spec| spec.len).sum());
+        CircuitStats::create(
+            &self.gates,
+            self.public,
+            self.domain.d1.size(),
+            runtime_table_entries,
+        )
+    }
+}
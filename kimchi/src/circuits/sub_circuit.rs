@@ -0,0 +1,216 @@
+//! This module implements the [SubCircuit], a fragment of circuit laid out once
+//! and instantiated at any row of a circuit, with named input and output cells.
+//!
+//! A library of gadgets lays out the gates of a sub-circuit from the row 0, and names the cells
+//! of its inputs and outputs. Instantiating the sub-circuit at a row shifts the rows of its gates
+//! and of their wires, so that its internal copy constraints are kept, and gives a [SubCircuitInstance]
+//! with the absolute cells of its inputs and outputs, to be connected to the cells of the other gates
+//! (see [Connect::connect_cell_pair](crate::circuits::gate::Connect::connect_cell_pair)).
+//! A sub-circuit can itself be built from instances of other sub-circuits:
+//!
+//! ```
+//! use kimchi::circuits::{
+//!     gate::{CircuitGate, Connect},
+//!     polynomials::generic::GenericGateSpec,
+//!     sub_circuit::SubCircuit,
+//!     wires::Wire,
+//! };
+//! use mina_curves::pasta::Fp;
+//!
+//! // the sum of two values, in a generic gate
+//! let spec = GenericGateSpec::Add {
+//!     left_coeff: None,
+//!     right_coeff: None,
+//!     output_coeff: None,
+//! };
+//! let add = SubCircuit::<Fp>::new(vec![CircuitGate::create_generic_gadget(
+//!     Wire::for_row(0),
+//!     spec,
+//!     None,
+//! )])
+//! .unwrap()
+//! .with_input("left", (0, 0))
+//! .with_input("right", (0, 1))
+//! .with_output("sum", (0, 2));
+//!
+//! // the sum of three values, from two sums
+//! let mut gates = vec![];
+//! let first = add.extend(&mut gates);
+//! let second = add.extend(&mut gates);
+//! assert_eq!(second.input("left"), Some((1, 0)));
+//! gates.connect_cell_pair(first.output("sum").unwrap(), second.input("left").unwrap());
+//!
+//! let add3 = SubCircuit::new(gates)
+//!     .unwrap()
+//!     .with_input("a", first.input("left").unwrap())
+//!     .with_input("b", first.input("right").unwrap())
+//!     .with_input("c", second.input("right").unwrap())
+//!     .with_output("sum", second.output("sum").unwrap());
+//! assert_eq!(add3.num_rows(), 2);
+//! ```
+//!
+//! The witness of an instance is the witness of the sub-circuit: as the witness functions
+//! of the gadgets extend the witness with their rows, they do not depend on the row of the instance.
+
+use crate::{
+    circuits::{
+        builder::Cell,
+        gate::{check_wiring, CircuitGate},
+        wires::PERMUTS,
+    },
+    error::WiringError,
+};
+use ark_ff::PrimeField;
+
+// The named cells of a sub-circuit or of an instance
+type Ports = Vec<(String, Cell)>;
+
+// Returns the cell of a name
+fn find(ports: &[(String, Cell)], name: &str) -> Option<Cell> {
+    ports
+        .iter()
+        .find(|(port, _)| port == name)
+        .map(|(_, cell)| *cell)
+}
+
+// Returns the named cells shifted by `row` rows
+fn shift(ports: &[(String, Cell)], row: usize) -> Ports {
+    ports
+        .iter()
+        .map(|(name, (cell_row, col))| (name.clone(), (row + cell_row, *col)))
+        .collect()
+}
+
+/// A fragment of circuit laid out from the row 0, with named input and output cells,
+/// see the [module documentation](self)
+#[derive(Clone, Debug)]
+pub struct SubCircuit<F: PrimeField> {
+    gates: Vec<CircuitGate<F>>,
+    inputs: Ports,
+    outputs: Ports,
+}
+
+impl<F: PrimeField> SubCircuit<F> {
+    /// Creates a sub-circuit from its gates, laid out from the row 0, without named cells
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the wires of the gates are not a permutation of their cells
+    /// (see [check_wiring]), for example if a gate is wired to a row outside of the sub-circuit.
+    pub fn new(gates: Vec<CircuitGate<F>>) -> Result<Self, WiringError> {
+        check_wiring(&gates)?;
+        Ok(Self {
+            gates,
+            inputs: vec![],
+            outputs: vec![],
+        })
+    }
+
+    /// Names the cell of an input of the sub-circuit, as a `(row, column)` pair from its first row
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the name is already used, or if the cell is not a wired cell of the sub-circuit.
+    pub fn with_input(mut self, name: &str, cell: Cell) -> Self {
+        self.check_port(name, cell);
+        self.inputs.push((name.to_string(), cell));
+        self
+    }
+
+    /// Names the cell of an output of the sub-circuit, as a `(row, column)` pair from its first row
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the name is already used, or if the cell is not a wired cell of the sub-circuit.
+    pub fn with_output(mut self, name: &str, cell: Cell) -> Self {
+        self.check_port(name, cell);
+        self.outputs.push((name.to_string(), cell));
+        self
+    }
+
+    // Checks that a name is free, and that a cell can be copied to the other gates
+    fn check_port(&self, name: &str, (row, col): Cell) {
+        assert!(
+            find(&self.inputs, name).is_none() && find(&self.outputs, name).is_none(),
+            "the name {name} is already used by the sub-circuit"
+        );
+        assert!(
+            row < self.gates.len() && col < PERMUTS,
+            "the cell ({row}, {col}) is not a wired cell of the sub-circuit"
+        );
+    }
+
+    /// Returns the number of rows of the sub-circuit
+    pub fn num_rows(&self) -> usize {
+        self.gates.len()
+    }
+
+    /// Returns the cell of an input, from the first row of the sub-circuit
+    pub fn input(&self, name: &str) -> Option<Cell> {
+        find(&self.inputs, name)
+    }
+
+    /// Returns the cell of an output, from the first row of the sub-circuit
+    pub fn output(&self, name: &str) -> Option<Cell> {
+        find(&self.outputs, name)
+    }
+
+    /// Extends a circuit with an instance of the sub-circuit
+    /// Input:
+    /// - gates : the full circuit
+    /// Output:
+    /// - the instance, with the absolute cells of its inputs and outputs
+    pub fn extend(&self, gates: &mut Vec<CircuitGate<F>>) -> SubCircuitInstance {
+        let (instance, mut sub_gates) = self.create(gates.len());
+        gates.append(&mut sub_gates);
+        instance
+    }
+
+    /// Creates an instance of the sub-circuit at the row `new_row`, see [Self::extend]
+    /// Outputs tuple (instance, circuit_gates) where
+    /// - instance  : the instance, whose next row is the row after its gates
+    /// - gates     : vector of the gates of the sub-circuit, with the rows of their wires shifted
+    pub fn create(&self, new_row: usize) -> (SubCircuitInstance, Vec<CircuitGate<F>>) {
+        let gates = self
+            .gates
+            .iter()
+            .map(|gate| {
+                let mut gate = gate.clone();
+                for wire in gate.wires.iter_mut() {
+                    wire.row += new_row;
+                }
+                gate
+            })
+            .collect();
+        let instance = SubCircuitInstance {
+            start_row: new_row,
+            next_row: new_row + self.gates.len(),
+            inputs: shift(&self.inputs, new_row),
+            outputs: shift(&self.outputs, new_row),
+        };
+        (instance, gates)
+    }
+}
+
+/// An instance of a [SubCircuit] in a circuit, with the absolute cells of its inputs and outputs
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubCircuitInstance {
+    /// the first row of the instance
+    pub start_row: usize,
+    /// the row after the instance
+    pub next_row: usize,
+    inputs: Ports,
+    outputs: Ports,
+}
+
+impl SubCircuitInstance {
+    /// Returns the cell of an input of the instance, as a `(row, column)` pair
+    pub fn input(&self, name: &str) -> Option<Cell> {
+        find(&self.inputs, name)
+    }
+
+    /// Returns the cell of an output of the instance, as a `(row, column)` pair
+    pub fn output(&self, name: &str) -> Option<Cell> {
+        find(&self.outputs, name)
+    }
+}
@@ -0,0 +1,402 @@
+//! This module implements the [circuit!](crate::circuit) macro,
+//! to describe fixed-shape circuits as a sequence of gadget invocations.
+//!
+//! Writing a circuit by hand means keeping track of the row where each gadget starts,
+//! both when creating the gates and when creating the witness,
+//! and of the positions of the public inputs.
+//! The macro takes care of this index arithmetic: it lays out the public input rows first,
+//! then each gadget in order, and generates a module with
+//!
+//! * `Public<F>`: the typed public inputs of the circuit, with `to_vec()` giving the public input vector,
+//! * `Private<F>`: the typed private inputs of the circuit,
+//! * `Layout`: the first row of each public input and of each gadget, and the total number of rows,
+//! * `gates::<F>()`: the layout and the gates of the circuit, with the copy constraints applied,
+//! * `witness::<F>(&public, &private)`: the witness of the circuit,
+//! * `costs::<F>()`: the rows, lookups and copied cells of the public inputs and of each gadget, as a [CostReport].
+//!
+//! For example, the following circuit constrains the XOR of two public 64-bit words.
+//!
+//! ```
+//! use kimchi::{
+//!     circuit,
+//!     circuits::{gate::CircuitGate, polynomials::xor},
+//! };
+//!
+//! circuit! {
+//!     /// The XOR of two public 64-bit words
+//!     pub mod xor64 {
+//!         public { left, right }
+//!         private {}
+//!         gadget xor {
+//!             gates(gates, _layout) => CircuitGate::extend_xor_gadget(gates, 64),
+//!             witness(witness, public, _private) => {
+//!                 xor::extend_xor_witness(witness, public.left, public.right, 64)
+//!             },
+//!         }
+//!         copy {
+//!             (left, 0, 0) == (xor, 0, 0),
+//!             (right, 0, 0) == (xor, 0, 1),
+//!         }
+//!     }
+//! }
+//!
+//! fn main() {
+//!     use mina_curves::pasta::Fp;
+//!
+//!     let (layout, gates) = xor64::gates::<Fp>();
+//!     assert_eq!(layout.xor, 2);
+//!     assert_eq!(gates.len(), layout.num_rows);
+//!
+//!     let public = xor64::Public {
+//!         left: Fp::from(0b1100u64),
+//!         right: Fp::from(0b1010u64),
+//!     };
+//!     let witness = xor64::witness(&public, &xor64::Private::default());
+//!     assert_eq!(witness[0].len(), layout.num_rows);
+//!     assert_eq!(public.to_vec().len(), xor64::Public::<Fp>::LEN);
+//!
+//!     let costs = xor64::costs::<Fp>();
+//!     assert_eq!(costs.section("xor").unwrap().to_string(), "xor: 5 rows, 16 lookups, 5 copied cells");
+//!     assert_eq!(costs.total().rows, layout.num_rows);
+//! }
+//! ```
+//!
+//! Each gadget is described by two expressions:
+//!
+//! * `gates(gates, layout) => ...` extends `gates: &mut Vec<CircuitGate<F>>` with the gates of the gadget.
+//!   `layout: &Layout` gives the rows of the public inputs and of the previous gadgets
+//!   (the fields of the following gadgets are not set yet).
+//! * `witness(witness, public, private) => ...` extends `witness: &mut [Vec<F>; COLUMNS]` with the rows of the gadget,
+//!   given `public: &Public<F>` and `private: &Private<F>`.
+//!
+//! The `copy` section lists copy constraints between cells, given as `(name, row, column)`
+//! where `name` is a public input or a gadget, and `row` is relative to its first row.
+//!
+//! The cost report helps to find the gadgets worth optimizing: displayed, it gives one line per section
+//! of the circuit, as `xor: 5 rows, 16 lookups, 5 copied cells`, and a line of the totals.
+//! The copied cells are the cells of the rows which are wired to other cells by the permutation argument.
+//!
+//! Note: the generated module imports everything from its parent module,
+//! so the macro must be invoked at module level (not inside a function body).
+
+use crate::circuits::{
+    gate::{CircuitGate, CurrOrNext},
+    lookup::lookups::LookupPattern,
+    wires::Wire,
+};
+use ark_ff::{PrimeField, SquareRootField};
+use std::fmt;
+
+/// The fields over which a [circuit!](crate::circuit) can be instantiated
+pub trait TemplateField: PrimeField + SquareRootField {}
+
+impl<F: PrimeField + SquareRootField> TemplateField for F {}
+
+/// The resources used by a section of a circuit
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SectionCost {
+    /// The name of the gadget, `public` for the public input rows, or `total`
+    pub name: &'static str,
+    /// The number of rows
+    pub rows: usize,
+    /// The number of lookups of the gates of the rows
+    pub lookups: usize,
+    /// The number of cells of the rows in a copy constraint
+    pub copied_cells: usize,
+}
+
+impl fmt::Display for SectionCost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} rows, {} lookups, {} copied cells",
+            self.name, self.rows, self.lookups, self.copied_cells
+        )
+    }
+}
+
+/// The cost breakdown of a circuit, per section of consecutive rows
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CostReport {
+    /// The costs of the sections, in the order of their rows
+    pub sections: Vec<SectionCost>,
+}
+
+impl CostReport {
+    /// Computes the costs of the sections of a circuit, given by their names and their first rows in order,
+    /// each section ending at the first row of the next one (or at the end of the circuit)
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the first rows are not in order, or beyond the end of the circuit.
+    pub fn new<F: PrimeField>(
+        gates: &[CircuitGate<F>],
+        sections: &[(&'static str, usize)],
+    ) -> Self {
+        let ends = sections
+            .iter()
+            .skip(1)
+            .map(|(_, row)| *row)
+            .chain([gates.len()]);
+        let sections = sections
+            .iter()
+            .zip(ends)
+            .map(|(&(name, start), end)| {
+                assert!(
+                    start <= end && end <= gates.len(),
+                    "the sections are in order"
+                );
+                let rows = &gates[start..end];
+                let lookups = rows
+                    .iter()
+                    .flat_map(|gate| {
+                        [CurrOrNext::Curr, CurrOrNext::Next]
+                            .map(|curr_or_next| LookupPattern::from_gate(gate.typ, curr_or_next))
+                    })
+                    .flatten()
+                    .map(|pattern| pattern.lookups::<F>().len())
+                    .sum();
+                let copied_cells = rows
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, gate)| {
+                        gate.wires
+                            .iter()
+                            .enumerate()
+                            .filter(move |(col, wire)| **wire != Wire::new(start + i, *col))
+                    })
+                    .count();
+                SectionCost {
+                    name,
+                    rows: end - start,
+                    lookups,
+                    copied_cells,
+                }
+            })
+            .collect();
+        Self { sections }
+    }
+
+    /// Returns the cost of a section, if any
+    pub fn section(&self, name: &str) -> Option<&SectionCost> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// Returns the total cost of the sections
+    pub fn total(&self) -> SectionCost {
+        self.sections.iter().fold(
+            SectionCost {
+                name: "total",
+                rows: 0,
+                lookups: 0,
+                copied_cells: 0,
+            },
+            |total, section| SectionCost {
+                rows: total.rows + section.rows,
+                lookups: total.lookups + section.lookups,
+                copied_cells: total.copied_cells + section.copied_cells,
+                ..total
+            },
+        )
+    }
+}
+
+impl fmt::Display for CostReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for section in &self.sections {
+            writeln!(f, "{section}")?;
+        }
+        write!(f, "{}", self.total())
+    }
+}
+
+/// Describes a fixed-shape circuit as a sequence of gadget invocations,
+/// see the [module documentation](crate::circuits::template).
+#[macro_export]
+macro_rules! circuit {
+    (@inputs $(#[$meta:meta])* $name:ident {}) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+        pub struct $name<F> {
+            #[doc(hidden)]
+            pub _field: ::core::marker::PhantomData<F>,
+        }
+    };
+
+    (@inputs $(#[$meta:meta])* $name:ident { $($input:ident),+ }) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+        pub struct $name<F> {
+            $(
+                #[allow(missing_docs)]
+                pub $input: F,
+            )+
+        }
+    };
+
+    (
+        $(#[$meta:meta])*
+        $vis:vis mod $name:ident {
+            public { $($public:ident),* $(,)? }
+            private { $($private:ident),* $(,)? }
+            $(
+                gadget $gadget:ident {
+                    gates($gates:ident, $gates_layout:ident) => $create:expr,
+                    witness($witness:ident, $witness_public:ident, $witness_private:ident) => $extend:expr $(,)?
+                }
+            )*
+            $(
+                copy {
+                    $(
+                        ($from:ident, $from_row:expr, $from_col:expr) == ($to:ident, $to_row:expr, $to_col:expr)
+                    ),* $(,)?
+                }
+            )?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $crate::circuit!(
+                @inputs
+                /// The public inputs of the circuit, in the order of the public input rows
+                Public { $($public),* }
+            );
+
+            impl<F: Copy> Public<F> {
+                /// The names of the public inputs, in order
+                pub const NAMES: &'static [&'static str] = &[$(stringify!($public)),*];
+
+                /// The number of public inputs
+                pub const LEN: usize = Self::NAMES.len();
+
+                /// The public input vector expected by the prover and the verifier
+                pub fn to_vec(&self) -> ::std::vec::Vec<F> {
+                    vec![$(self.$public),*]
+                }
+            }
+
+            $crate::circuit!(
+                @inputs
+                /// The private inputs of the circuit
+                Private { $($private),* }
+            );
+
+            /// The first row of each public input and of each gadget of the circuit
+            #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+            pub struct Layout {
+                $(
+                    #[allow(missing_docs)]
+                    pub $public: usize,
+                )*
+                $(
+                    #[allow(missing_docs)]
+                    pub $gadget: usize,
+                )*
+                /// The total number of rows of the circuit
+                pub num_rows: usize,
+            }
+
+            /// Creates the gates of the circuit, and returns them with their layout
+            pub fn gates<F: $crate::circuits::template::TemplateField>() -> (
+                Layout,
+                ::std::vec::Vec<$crate::circuits::gate::CircuitGate<F>>,
+            ) {
+                #[allow(unused_imports)]
+                use $crate::circuits::gate::Connect as _;
+
+                let mut layout = Layout::default();
+                let mut gates = vec![];
+
+                $(
+                    layout.$public = gates.len();
+                    gates.push($crate::circuits::gate::CircuitGate::create_generic_gadget(
+                        $crate::circuits::wires::Wire::for_row(gates.len()),
+                        $crate::circuits::polynomials::generic::GenericGateSpec::Pub,
+                        None,
+                    ));
+                )*
+
+                $(
+                    layout.$gadget = gates.len();
+                    {
+                        let $gates = &mut gates;
+                        #[allow(unused_variables)]
+                        let $gates_layout = &layout;
+                        let _ = $create;
+                    }
+                )*
+
+                layout.num_rows = gates.len();
+
+                $($(
+                    gates.connect_cell_pair(
+                        (layout.$from + $from_row, $from_col),
+                        (layout.$to + $to_row, $to_col),
+                    );
+                )*)?
+
+                (layout, gates)
+            }
+
+            /// Creates the witness of the circuit
+            ///
+            /// # Panics
+            ///
+            /// Will panic if the rows added by a gadget to the witness do not match its gates.
+            pub fn witness<F: $crate::circuits::template::TemplateField>(
+                public: &Public<F>,
+                private: &Private<F>,
+            ) -> [::std::vec::Vec<F>; $crate::circuits::wires::COLUMNS] {
+                let (layout, _) = gates::<F>();
+                let mut witness: [::std::vec::Vec<F>; $crate::circuits::wires::COLUMNS] =
+                    ::std::array::from_fn(|_| vec![]);
+
+                for value in public.to_vec() {
+                    for (col, cells) in witness.iter_mut().enumerate() {
+                        cells.push(if col == 0 { value } else { F::zero() });
+                    }
+                }
+
+                $(
+                    assert_eq!(
+                        witness[0].len(),
+                        layout.$gadget,
+                        concat!("the witness rows before gadget `", stringify!($gadget), "` do not match the gates"),
+                    );
+                    {
+                        let $witness = &mut witness;
+                        #[allow(unused_variables)]
+                        let $witness_public = public;
+                        #[allow(unused_variables)]
+                        let $witness_private = private;
+                        $extend;
+                    }
+                )*
+
+                assert_eq!(
+                    witness[0].len(),
+                    layout.num_rows,
+                    "the witness rows do not match the gates",
+                );
+
+                let _ = private;
+                witness
+            }
+
+            /// Returns the rows, lookups and copied cells of the public inputs and of each gadget of the circuit
+            pub fn costs<F: $crate::circuits::template::TemplateField>(
+            ) -> $crate::circuits::template::CostReport {
+                let (layout, gates) = gates::<F>();
+                let public = (Public::<F>::LEN > 0).then_some(("public", 0));
+                let sections: ::std::vec::Vec<_> = public
+                    .into_iter()
+                    .chain([$((stringify!($gadget), layout.$gadget)),*])
+                    .collect();
+                $crate::circuits::template::CostReport::new(&gates, &sections)
+            }
+        }
+    };
+}
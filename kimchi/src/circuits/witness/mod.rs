@@ -3,6 +3,9 @@ mod constant_cell;
 mod copy_bits_cell;
 mod copy_cell;
 mod copy_shift_cell;
+mod redact;
+mod segments;
+mod solver;
 mod variable_bits_cell;
 mod variable_cell;
 mod variables;
@@ -12,6 +15,9 @@ pub use self::{
     copy_bits_cell::CopyBitsCell,
     copy_cell::CopyCell,
     copy_shift_cell::CopyShiftCell,
+    redact::RedactedWitness,
+    segments::WitnessSegments,
+    solver::{solve_partial_witness, solve_witness, Solve, SolverEnv},
     variable_bits_cell::VariableBitsCell,
     variable_cell::VariableCell,
     variables::{variable_map, variables, Variables},
@@ -0,0 +1,147 @@
+//! This module implements the redaction of failing witnesses, to attach reproducers to bug reports
+//! without leaking the secrets they contain.
+//!
+//! The redaction zeroes the private cells of the witness one copy cycle at a time
+//! (so that the copy constraints are not broken by the redaction itself),
+//! and keeps a change only if every row fails exactly as before.
+//! The public inputs are never redacted.
+
+use crate::{
+    circuits::{
+        constraints::GateError,
+        gate::VerifyPrecomputations,
+        polynomial::COLUMNS,
+        wires::{Wire, PERMUTS},
+    },
+    curve::KimchiCurve,
+    prover_index::ProverIndex,
+};
+use ark_ff::{PrimeField, SquareRootField};
+use std::collections::BTreeSet;
+
+/// A witness in which the cells that are not needed to reproduce a failure are zeroed,
+/// see [ProverIndex::redact_witness]
+#[derive(Debug)]
+pub struct RedactedWitness<F> {
+    /// The redacted witness
+    pub witness: [Vec<F>; COLUMNS],
+    /// The failures of the redacted witness, at the same rows as the original witness
+    pub failures: Vec<GateError>,
+    /// The private cells which could not be zeroed without changing the failures
+    pub kept: Vec<Wire>,
+}
+
+// Returns whether two rows fail in the same way, without comparing the messages of the gates,
+// which may contain the values of the cells
+fn same_failure(a: &Result<(), GateError>, b: &Result<(), GateError>) -> bool {
+    match (a, b) {
+        (Ok(()), Ok(())) => true,
+        (Err(GateError::DisconnectedWires(a0, a1)), Err(GateError::DisconnectedWires(b0, b1))) => {
+            a0 == b0 && a1 == b1
+        }
+        (Err(GateError::IncorrectPublic(a)), Err(GateError::IncorrectPublic(b))) => a == b,
+        (Err(GateError::Custom { row: a, .. }), Err(GateError::Custom { row: b, .. })) => a == b,
+        _ => false,
+    }
+}
+
+impl<F: PrimeField + SquareRootField, G: KimchiCurve<ScalarField = F>> ProverIndex<G> {
+    /// Redacts a `witness` which does not verify (see [ProverIndex::verify]),
+    /// by zeroing all the private cells which are not needed for the rows to fail as they do.
+    /// Returns `None` if the witness verifies, as there is no failure to reproduce.
+    ///
+    /// Note that the redaction is greedy: it tries each copy cycle of cells once,
+    /// in order, and needs to check the rows of the cycle (and the ones before them) for each.
+    /// The kept cells should still be reviewed before sharing the witness.
+    pub fn redact_witness(
+        &self,
+        witness: &[Vec<F>; COLUMNS],
+        public: &[F],
+    ) -> Option<RedactedWitness<F>> {
+        let length_witness = witness[0].len();
+        let num_rows = self.cs.gates.len();
+        let mut witness = self.pad_witness(witness);
+        let precomputations = VerifyPrecomputations::create(&self.cs.gates);
+
+        let failures: Vec<_> = (0..num_rows)
+            .map(|row| self.verify_row(row, &witness, public, &precomputations))
+            .collect();
+        if failures.iter().all(Result::is_ok) {
+            return None;
+        }
+
+        let is_public = |cell: &Wire| cell.col == 0 && cell.row < self.cs.public;
+        let mut visited = vec![[false; COLUMNS]; num_rows];
+        for row in 0..length_witness {
+            for col in 0..COLUMNS {
+                if visited[row][col] {
+                    continue;
+                }
+
+                // the cells which are copies of this one
+                let mut cells = vec![Wire { row, col }];
+                visited[row][col] = true;
+                if col < PERMUTS {
+                    let mut next = self.cs.gates[row].wires[col];
+                    while next.col < PERMUTS && !visited[next.row][next.col] {
+                        visited[next.row][next.col] = true;
+                        cells.push(next);
+                        next = self.cs.gates[next.row].wires[next.col];
+                    }
+                }
+
+                if cells.iter().any(is_public)
+                    || cells
+                        .iter()
+                        .all(|cell| witness[cell.col][cell.row].is_zero())
+                {
+                    continue;
+                }
+
+                let values: Vec<_> = cells
+                    .iter()
+                    .map(|cell| std::mem::take(&mut witness[cell.col][cell.row]))
+                    .collect();
+
+                // gates constrain their row, and possibly the next one
+                let rows: BTreeSet<_> = cells
+                    .iter()
+                    .flat_map(|cell| [cell.row.saturating_sub(1), cell.row])
+                    .collect();
+                let preserved = rows.into_iter().all(|row| {
+                    same_failure(
+                        &failures[row],
+                        &self.verify_row(row, &witness, public, &precomputations),
+                    )
+                });
+
+                if !preserved {
+                    for (cell, value) in cells.iter().zip(values) {
+                        witness[cell.col][cell.row] = value;
+                    }
+                }
+            }
+        }
+
+        let failures = (0..num_rows)
+            .filter_map(|row| {
+                self.verify_row(row, &witness, public, &precomputations)
+                    .err()
+            })
+            .collect();
+
+        for col in &mut witness {
+            col.truncate(length_witness);
+        }
+        let kept = (0..length_witness)
+            .flat_map(|row| (0..COLUMNS).map(move |col| Wire { row, col }))
+            .filter(|cell| !is_public(cell) && !witness[cell.col][cell.row].is_zero())
+            .collect();
+
+        Some(RedactedWitness {
+            witness,
+            failures,
+            kept,
+        })
+    }
+}
@@ -0,0 +1,95 @@
+//! This module implements the witnesses produced in segments of rows,
+//! to commit to the witness of a large circuit without holding all its columns in memory.
+//!
+//! A [WitnessSegments] computes the values of a column in a range of rows on demand,
+//! for example by running the witness functions of the gadgets placed in these rows,
+//! or by reading them from a file. [ProverIndex::commit_witness_segments] commits to each
+//! column one segment at a time, adding up the commitments of the segments:
+//! only a segment of `segment_rows` values is in memory at once, at the cost of computing
+//! the witness again for the other steps of the proof which need all of it
+//! (see [WitnessSegments::witness]).
+//! [create_recursive_with_segments](crate::proof::ProverProof::create_recursive_with_segments)
+//! creates a proof from such a witness, committing to it this way before computing it in full
+//! for its interpolation.
+//!
+//! Only the commitment to the witness is segmented: the other steps of the proof
+//! (the interpolation of the columns, the quotient over the extended domains and the evaluations)
+//! still hold the whole witness and its extended evaluations in memory,
+//! so that the memory of a proof is not bounded by the segments.
+
+use crate::{
+    circuits::{polynomial::COLUMNS, polynomials::permutation::ZK_ROWS},
+    curve::KimchiCurve,
+    error::ProverError,
+    prover_index::ProverIndex,
+};
+use ark_ff::{PrimeField, Zero};
+use ark_poly::EvaluationDomain;
+use poly_commitment::PolyComm;
+use std::{array, ops::Range};
+
+/// A witness whose columns are computed in segments of rows,
+/// see the [module documentation](self)
+pub trait WitnessSegments<F> {
+    /// The number of rows of the witness
+    fn num_rows(&self) -> usize;
+
+    /// Computes the values of the column `col` in a range of rows of the witness
+    fn segment(&self, col: usize, rows: Range<usize>) -> Vec<F>;
+
+    /// Computes all the columns of the witness
+    fn witness(&self) -> [Vec<F>; COLUMNS] {
+        array::from_fn(|col| self.segment(col, 0..self.num_rows()))
+    }
+}
+
+impl<F: Clone> WitnessSegments<F> for [Vec<F>; COLUMNS] {
+    fn num_rows(&self) -> usize {
+        self[0].len()
+    }
+
+    fn segment(&self, col: usize, rows: Range<usize>) -> Vec<F> {
+        self[col][rows].to_vec()
+    }
+}
+
+impl<F: PrimeField, G: KimchiCurve<ScalarField = F>> ProverIndex<G> {
+    /// Commits (non-hiding) to the columns of a witness,
+    /// computing and committing `segment_rows` rows of a column at a time.
+    /// The commitments are those of the columns padded with zeros to the domain (see
+    /// [commit_evaluations_non_hiding](poly_commitment::srs::SRS::commit_evaluations_non_hiding)),
+    /// and should be masked before being shared.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the witness leaves no room for the zero-knowledge rows in the domain.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `segment_rows` is zero, or if the Lagrange bases of the domain
+    /// are not in the SRS of the index.
+    pub fn commit_witness_segments(
+        &self,
+        witness: &impl WitnessSegments<F>,
+        segment_rows: usize,
+    ) -> Result<[PolyComm<G>; COLUMNS], ProverError> {
+        assert!(segment_rows > 0, "the segments have no row");
+        let domain = self.cs.domain.d1;
+        let num_rows = witness.num_rows();
+        if num_rows + ZK_ROWS as usize > domain.size() {
+            return Err(ProverError::NoRoomForZkInWitness);
+        }
+        Ok(array::from_fn(|col| {
+            (0..num_rows)
+                .step_by(segment_rows)
+                .map(|start| {
+                    let rows = start..std::cmp::min(start + segment_rows, num_rows);
+                    let segment = witness.segment(col, rows);
+                    self.srs.commit_evaluations_segment(domain, start, &segment)
+                })
+                .fold(PolyComm::new(vec![G::zero()], None), |sum, comm| {
+                    &sum + &comm
+                })
+        }))
+    }
+}
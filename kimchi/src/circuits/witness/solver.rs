@@ -0,0 +1,302 @@
+//! This module implements the solver of witnesses, filling in the cells of a witness
+//! computed by the gates of a circuit from its input cells.
+//!
+//! The gates implementing [Solve] compute some of their cells from the others,
+//! for example the output of a generic gate from its inputs, or an input from the output,
+//! and the limbs, carries and results of the `AddU64`, `Xor16`, `Rot64` and `ForeignFieldAdd` gates
+//! and of the byte range checks from their inputs.
+//! Starting from the public inputs and the given input cells,
+//! the solver repeats until no cell changes:
+//! - the value of a cell is copied to the cells wired to it,
+//! - each gate computes the cells it determines from the known cells of its row.
+//!
+//! The witness is solved when every gate has the cells it constrains: the unknown cells left,
+//! such as the unused columns of the rows, are zero. The gates without an implementation of [Solve]
+//! are only solved if all the cells of their rows are known, for example if they are given
+//! by the witness functions of their gadgets in a partial witness (see [solve_partial_witness]).
+//! This way, the witness of a circuit combining gadgets does not need its own witness function.
+//!
+//! Note that the solved witness is not verified: a gate whose cells are all given is not checked.
+
+use crate::{
+    circuits::{
+        builder::Cell,
+        gate::{check_wiring, CircuitGate, GateType},
+        polynomial::COLUMNS,
+        polynomials::{
+            add_u64::AddU64, foreign_field_add::circuitgates::ForeignFieldAdd, generic::Generic,
+            range_check::bytes::solve_range_check_bytes, rot::Rot64, select::Select, xor::Xor16,
+        },
+        wires::{Wire, PERMUTS},
+    },
+    error::SolveError,
+};
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use std::array;
+
+/// The computation of the cells of a gate from its known cells,
+/// see the [module documentation](self)
+pub trait Solve<F: PrimeField> {
+    /// Sets the cells of the gate determined by its known cells and its coefficients,
+    /// and returns whether all the cells constrained by the gate are known
+    ///
+    /// # Errors
+    ///
+    /// Will give error if a computed cell already has another value.
+    fn solve(env: &mut SolverEnv<F>, coeffs: &[F]) -> Result<bool, SolveError>;
+}
+
+/// The partial witness of a circuit, seen from the row of the gate being solved
+pub struct SolverEnv<'a, F> {
+    witness: &'a mut [Vec<Option<F>>; COLUMNS],
+    row: usize,
+    // the cells set by the gate
+    updates: Vec<Wire>,
+}
+
+impl<F: PrimeField> SolverEnv<'_, F> {
+    /// Returns the row of the gate
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// Returns the value of a cell of the row of the gate, if known
+    pub fn curr(&self, col: usize) -> Option<F> {
+        self.witness[col][self.row]
+    }
+
+    /// Returns the value of a cell of the row of the gate, if known and a 64-bit word
+    pub fn curr_u64(&self, col: usize) -> Option<u64> {
+        let value: BigUint = self.curr(col)?.into();
+        u64::try_from(value).ok()
+    }
+
+    /// Returns the value of a cell of the next row, if known
+    pub fn next(&self, col: usize) -> Option<F> {
+        self.witness[col].get(self.row + 1).copied().flatten()
+    }
+
+    /// Sets a cell of the row of the gate
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the cell already has another value.
+    pub fn set_curr(&mut self, col: usize, value: F) -> Result<(), SolveError> {
+        self.set(Wire::new(self.row, col), value)
+    }
+
+    /// Sets a cell of the next row
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the cell already has another value.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the gate is on the last row.
+    pub fn set_next(&mut self, col: usize, value: F) -> Result<(), SolveError> {
+        self.set(Wire::new(self.row + 1, col), value)
+    }
+
+    // Sets a cell, to be copied to the cells wired to it
+    fn set(&mut self, cell: Wire, value: F) -> Result<(), SolveError> {
+        if assign(self.witness, cell, value)? {
+            self.updates.push(cell);
+        }
+        Ok(())
+    }
+}
+
+// Sets a cell of a partial witness, and returns whether it was unknown
+fn assign<F: PrimeField>(
+    witness: &mut [Vec<Option<F>>; COLUMNS],
+    cell: Wire,
+    value: F,
+) -> Result<bool, SolveError> {
+    let known = &mut witness[cell.col][cell.row];
+    match *known {
+        Some(known) if known != value => Err(SolveError::Conflict(cell.row, cell.col)),
+        Some(_) => Ok(false),
+        None => {
+            *known = Some(value);
+            Ok(true)
+        }
+    }
+}
+
+// The copy cycles of the wired cells of a circuit, each copied once
+struct Copies {
+    cycles: Vec<Vec<Wire>>,
+    cycle_of: Vec<[usize; PERMUTS]>,
+    copied: Vec<bool>,
+}
+
+impl Copies {
+    // Follows the wires of the gates, which must be a permutation (see check_wiring)
+    fn new<F: PrimeField>(gates: &[CircuitGate<F>]) -> Self {
+        let mut cycles = vec![];
+        let mut cycle_of = vec![[usize::MAX; PERMUTS]; gates.len()];
+        for row in 0..gates.len() {
+            for col in 0..PERMUTS {
+                if cycle_of[row][col] != usize::MAX {
+                    continue;
+                }
+                let start = Wire::new(row, col);
+                let mut cycle = vec![start];
+                cycle_of[row][col] = cycles.len();
+                let mut cell = gates[row].wires[col];
+                while cell != start {
+                    cycle.push(cell);
+                    cycle_of[cell.row][cell.col] = cycles.len();
+                    cell = gates[cell.row].wires[cell.col];
+                }
+                cycles.push(cycle);
+            }
+        }
+        let copied = vec![false; cycles.len()];
+        Copies {
+            cycles,
+            cycle_of,
+            copied,
+        }
+    }
+
+    // Copies the value of a known cell to the cells of its cycle
+    fn copy<F: PrimeField>(
+        &mut self,
+        witness: &mut [Vec<Option<F>>; COLUMNS],
+        cell: Wire,
+    ) -> Result<(), SolveError> {
+        if cell.col >= PERMUTS {
+            return Ok(());
+        }
+        let cycle = self.cycle_of[cell.row][cell.col];
+        if std::mem::replace(&mut self.copied[cycle], true) {
+            return Ok(());
+        }
+        let value = witness[cell.col][cell.row].expect("the copied cells are known");
+        for other in &self.cycles[cycle] {
+            assign(witness, *other, value)?;
+        }
+        Ok(())
+    }
+}
+
+// Solves a gate with its implementation of Solve, if any
+fn solve_gate<F: PrimeField>(
+    gate: &CircuitGate<F>,
+    env: &mut SolverEnv<F>,
+) -> Result<bool, SolveError> {
+    match gate.typ {
+        GateType::Zero => Ok(true),
+        GateType::Generic => Generic::solve(env, &gate.coeffs),
+        GateType::Select => Select::solve(env, &gate.coeffs),
+        GateType::AddU64 => AddU64::solve(env, &gate.coeffs),
+        GateType::Xor16 => Xor16::solve(env, &gate.coeffs),
+        GateType::Rot64 => Rot64::solve(env, &gate.coeffs),
+        GateType::ForeignFieldAdd => ForeignFieldAdd::solve(env, &gate.coeffs),
+        GateType::RangeCheckU16 => solve_range_check_bytes(env, 16),
+        GateType::RangeCheckU8 => solve_range_check_bytes(env, 8),
+        // the lookups constrain the cells of their row which are wired
+        GateType::Lookup | GateType::VectorLookup => {
+            Ok((0..PERMUTS).all(|col| env.curr(col).is_some()))
+        }
+        _ => Ok((0..COLUMNS).all(|col| env.curr(col).is_some())),
+    }
+}
+
+/// Solves the witness of a circuit from its public inputs, in the first rows of its first column,
+/// and from the values of input cells, given as `(row, column)` pairs
+/// (see the [module documentation](self))
+///
+/// # Errors
+///
+/// Will give error if an input cell is not a cell of the circuit, if two inputs of the same cell
+/// differ, or as [solve_partial_witness].
+pub fn solve_witness<F: PrimeField>(
+    gates: &[CircuitGate<F>],
+    public: &[F],
+    inputs: &[(Cell, F)],
+) -> Result<[Vec<F>; COLUMNS], SolveError> {
+    let mut witness: [Vec<Option<F>>; COLUMNS] = array::from_fn(|_| vec![None; gates.len()]);
+    let public = public
+        .iter()
+        .enumerate()
+        .map(|(row, value)| ((row, 0), value));
+    let inputs = inputs.iter().map(|(cell, value)| (*cell, value));
+    for ((row, col), value) in public.chain(inputs) {
+        if row >= gates.len() || col >= COLUMNS {
+            return Err(SolveError::InputOutOfRange(row, col));
+        }
+        assign(&mut witness, Wire::new(row, col), *value)?;
+    }
+    solve_partial_witness(gates, witness)
+}
+
+/// Solves a partial witness of a circuit, whose known cells are `Some`
+/// (see the [module documentation](self)). The partial witness has a row per gate,
+/// with the public inputs in the first rows of its first column.
+///
+/// # Errors
+///
+/// Will give error if the partial witness does not have a row per gate,
+/// if the wiring of the circuit is not a permutation, if a cell computed or copied
+/// already has another value, or if the cells of a gate are not determined by the known cells.
+pub fn solve_partial_witness<F: PrimeField>(
+    gates: &[CircuitGate<F>],
+    mut witness: [Vec<Option<F>>; COLUMNS],
+) -> Result<[Vec<F>; COLUMNS], SolveError> {
+    if let Some((col, column)) = witness
+        .iter()
+        .enumerate()
+        .find(|(_, column)| column.len() != gates.len())
+    {
+        return Err(SolveError::ColumnLength(col, column.len(), gates.len()));
+    }
+    check_wiring(gates).map_err(SolveError::Wiring)?;
+
+    // the known cells are copied to the cells wired to them
+    let mut copies = Copies::new(gates);
+    for row in 0..gates.len() {
+        for col in 0..PERMUTS {
+            if witness[col][row].is_some() {
+                copies.copy(&mut witness, Wire::new(row, col))?;
+            }
+        }
+    }
+
+    // the gates are solved again until no cell changes,
+    // as a cell set by a gate can be needed by the gates before it
+    let mut solved = vec![false; gates.len()];
+    let mut updates = vec![];
+    loop {
+        let mut changed = false;
+        for (row, gate) in gates.iter().enumerate() {
+            let mut env = SolverEnv {
+                witness: &mut witness,
+                row,
+                updates,
+            };
+            solved[row] = solve_gate(gate, &mut env)?;
+            updates = env.updates;
+            changed |= !updates.is_empty();
+            for cell in updates.drain(..) {
+                copies.copy(&mut witness, cell)?;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    if let Some(row) = solved.iter().position(|solved| !solved) {
+        return Err(SolveError::Unsolved(row, gates[row].typ));
+    }
+    Ok(witness.map(|column| {
+        column
+            .into_iter()
+            .map(|cell| cell.unwrap_or_else(F::zero))
+            .collect()
+    }))
+}
@@ -0,0 +1,621 @@
+//! This module implements the commands of the `kimchi-cli` binary, enabled by the `cli` feature,
+//! and the formats of the files they read and write.
+//!
+//! The binary proves circuits over the Vesta curve from files, and verifies the proofs:
+//!
+//! ```text
+//! kimchi-cli witness <CIRCUIT> <ASSIGNMENTS> <WITNESS>
+//! kimchi-cli prove <CIRCUIT> <WITNESS> <SRS> <PROOF>
+//! kimchi-cli verify <CIRCUIT> <SRS> <PROOF>
+//! kimchi-cli tables <TABLES>
+//! ```
+//!
+//! All the files are encoded with MessagePack (see [rmp_serde]):
+//! the circuit is a [CircuitFile], the witness a [WitnessFile], the SRS an [SRS] of Vesta
+//! (as the precomputed SRS, see [crate::precomputed_srs]), and the proof a [ProofFile].
+//! The files of extension `.zst` are written compressed with zstd, and all the files are read
+//! compressed or not (see [crate::compression]).
+//!
+//! The witness can also be built from the values of the named variables of the circuit
+//! (see [CircuitFile::variables]), so that it can be produced without Rust.
+//! The assignments are read as JSON or CSV, depending on the extension of their file
+//! (see [read_assignments]), and the values are decimal integers, possibly negative:
+//!
+//! ```text
+//! {"x": "3", "y": 9}
+//! ```
+//!
+//! ```text
+//! name,value
+//! x,3
+//! y,9
+//! ```
+//!
+//! The `tables` command exports the fixed lookup tables of the gates (see [gate_lookup_tables])
+//! as a JSON array of [LookupTable] over the scalar field of Vesta, so that the lookups of a witness
+//! can be checked by other tools before proving. Each table has its `id` and its columns in `data`,
+//! whose values are little-endian hexadecimal strings:
+//!
+//! ```text
+//! [{"id": 0, "data": [["0f00…", …], …]}, …]
+//! ```
+
+use crate::{
+    circuits::{
+        constraints::ConstraintSystem,
+        gate::CircuitGate,
+        lookup::tables::{gate_lookup_tables, LookupTable},
+        wires::COLUMNS,
+    },
+    compression::{self, Compression, CompressionError},
+    error::{ProverError, SetupError, VerifyError},
+    proof::ProverProof,
+    prover_index::ProverIndex,
+    verifier::verify,
+};
+use ark_ff::Zero;
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Pallas, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use num_bigint::BigUint;
+use o1_utils::field_helpers::BigUintFieldHelpers;
+use poly_commitment::{
+    commitment::CommitmentCurve,
+    srs::{endos, SRS},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_with::serde_as;
+use std::{
+    array,
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    path::Path,
+    sync::Arc,
+};
+use thiserror::Error;
+
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+/// Errors that can arise when running the commands of the binary
+#[derive(Error, Debug)]
+pub enum CliError {
+    #[error("could not access the file {0}: {1}")]
+    Io(String, std::io::Error),
+
+    #[error("could not decode the file {0}: {1}")]
+    Decode(String, CompressionError),
+
+    #[error("could not encode the file {0}: {1}")]
+    Encode(String, CompressionError),
+
+    #[error("could not encode the file {0}: {1}")]
+    EncodeJson(String, serde_json::Error),
+
+    #[error("the witness has {0} columns instead of {COLUMNS}")]
+    WitnessColumns(usize),
+
+    #[error("could not parse the assignments of the file {0}: {1}")]
+    Assignments(String, String),
+
+    #[error("the assignments of the file {0} are neither JSON nor CSV")]
+    AssignmentsFormat(String),
+
+    #[error("the value {1} of the variable {0} is not an element of the field")]
+    InvalidValue(String, String),
+
+    #[error("the variable {0} is not declared by the circuit")]
+    UnknownVariable(String),
+
+    #[error("the variable {0} of the circuit is not assigned")]
+    UnassignedVariable(String),
+
+    #[error("the variable {0} is assigned to the cell ({1}, {2}), which is not in the circuit")]
+    InvalidCell(String, usize, usize),
+
+    #[error("the constraint system could not be built: {0}")]
+    Setup(#[from] SetupError),
+
+    #[error("the proof could not be created: {0}")]
+    Prover(#[from] ProverError),
+
+    #[error("the proof is invalid: {0}")]
+    Verify(#[from] VerifyError),
+}
+
+/// A circuit, as read by the binary
+#[derive(Serialize, Deserialize)]
+pub struct CircuitFile {
+    /// The number of public inputs of the circuit
+    pub public: usize,
+    /// The gates of the circuit
+    #[serde(bound = "CircuitGate<Fp>: Serialize + DeserializeOwned")]
+    pub gates: Vec<CircuitGate<Fp>>,
+    /// The named variables of the circuit, with the cells (row, column) of the witness they are assigned to
+    #[serde(default)]
+    pub variables: BTreeMap<String, Vec<(usize, usize)>>,
+}
+
+/// A witness of a circuit, as read by the binary.
+/// The public inputs are the first cells of its first column.
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+pub struct WitnessFile {
+    /// The columns of the witness
+    #[serde_as(as = "Vec<Vec<o1_utils::serialization::SerdeAs>>")]
+    pub columns: Vec<Vec<Fp>>,
+}
+
+/// A proof of a circuit with its public input, as written by the binary
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+pub struct ProofFile {
+    /// The proof
+    pub proof: ProverProof<Vesta>,
+    /// The public input of the proof
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    pub public_input: Vec<Fp>,
+}
+
+/// Reads a MessagePack file, which may be compressed
+///
+/// # Errors
+///
+/// Will give error if the file cannot be read or decoded.
+pub fn read_file<T: DeserializeOwned>(path: &Path) -> Result<T, CliError> {
+    let name = path.display().to_string();
+    let file = File::open(path).map_err(|e| CliError::Io(name.clone(), e))?;
+    compression::read(BufReader::new(file)).map_err(|e| CliError::Decode(name, e))
+}
+
+/// Writes a MessagePack file, compressed if its extension is `.zst`
+///
+/// # Errors
+///
+/// Will give error if the file cannot be created or encoded.
+pub fn write_file<T: Serialize>(path: &Path, value: &T) -> Result<(), CliError> {
+    let name = path.display().to_string();
+    let file = File::create(path).map_err(|e| CliError::Io(name.clone(), e))?;
+    compression::write(BufWriter::new(file), value, Compression::from_path(path))
+        .map_err(|e| CliError::Encode(name, e))
+}
+
+// Parses the decimal value of a variable, which is negated if it starts with '-'
+fn parse_value(name: &str, value: &str) -> Result<Fp, CliError> {
+    let invalid = || CliError::InvalidValue(name.to_string(), value.to_string());
+    let (negative, digits) = match value.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, value),
+    };
+    let value: Fp = digits
+        .parse::<BigUint>()
+        .map_err(|_| invalid())?
+        .to_field::<Fp>()
+        .map_err(|_| invalid())?;
+    Ok(if negative { -value } else { value })
+}
+
+// Parses the `name,value` lines of a CSV file, with an optional header
+fn parse_csv(name: &str, content: &str) -> Result<BTreeMap<String, Fp>, CliError> {
+    let mut assignments = BTreeMap::new();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (i == 0 && line == "name,value") {
+            continue;
+        }
+        let error = |reason: &str| {
+            CliError::Assignments(name.to_string(), format!("line {}: {reason}", i + 1))
+        };
+        let (variable, value) = line
+            .split_once(',')
+            .ok_or_else(|| error("expected name,value"))?;
+        let variable = variable.trim();
+        let value = parse_value(variable, value.trim())?;
+        if assignments.insert(variable.to_string(), value).is_some() {
+            return Err(error(&format!("the variable {variable} is assigned twice")));
+        }
+    }
+    Ok(assignments)
+}
+
+// Parses a JSON object of the variables, whose values are numbers or strings
+fn parse_json(name: &str, content: &str) -> Result<BTreeMap<String, Fp>, CliError> {
+    let values: BTreeMap<String, serde_json::Value> = serde_json::from_str(content)
+        .map_err(|e| CliError::Assignments(name.to_string(), e.to_string()))?;
+    values
+        .into_iter()
+        .map(|(variable, value)| {
+            let value = match value {
+                serde_json::Value::String(value) => parse_value(&variable, &value),
+                serde_json::Value::Number(value) => parse_value(&variable, &value.to_string()),
+                value => Err(CliError::InvalidValue(variable.clone(), value.to_string())),
+            }?;
+            Ok((variable, value))
+        })
+        .collect()
+}
+
+/// Reads the values assigned to the variables of a circuit, from a file
+/// of extension `.json` (an object of the variables) or `.csv` (the lines `name,value` of the variables)
+///
+/// # Errors
+///
+/// Will give error if the file cannot be read or parsed, or if a value is not an element of the field.
+pub fn read_assignments(path: &Path) -> Result<BTreeMap<String, Fp>, CliError> {
+    let name = path.display().to_string();
+    let content = fs::read_to_string(path).map_err(|e| CliError::Io(name.clone(), e))?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => parse_json(&name, &content),
+        Some("csv") => parse_csv(&name, &content),
+        _ => Err(CliError::AssignmentsFormat(name)),
+    }
+}
+
+/// Builds the witness of a circuit from the values assigned to its variables.
+/// The cells which are not assigned to any variable are zero.
+///
+/// # Errors
+///
+/// Will give error if a variable is not declared by the circuit or not assigned,
+/// or if it is assigned to a cell outside of the circuit.
+pub fn witness_from_assignments(
+    circuit: &CircuitFile,
+    assignments: &BTreeMap<String, Fp>,
+) -> Result<[Vec<Fp>; COLUMNS], CliError> {
+    if let Some(variable) = assignments
+        .keys()
+        .find(|variable| !circuit.variables.contains_key(*variable))
+    {
+        return Err(CliError::UnknownVariable(variable.clone()));
+    }
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); circuit.gates.len()]);
+    for (variable, cells) in &circuit.variables {
+        let value = assignments
+            .get(variable)
+            .ok_or_else(|| CliError::UnassignedVariable(variable.clone()))?;
+        for &(row, col) in cells {
+            let cell = witness
+                .get_mut(col)
+                .and_then(|column| column.get_mut(row))
+                .ok_or_else(|| CliError::InvalidCell(variable.clone(), row, col))?;
+            *cell = *value;
+        }
+    }
+    Ok(witness)
+}
+
+/// The `witness` command: builds the witness of the circuit of the file `circuit`
+/// from the assignments of its variables in the file `assignments`, and writes it to the file `output`
+///
+/// # Errors
+///
+/// Will give error if a file cannot be read or written, or if the witness cannot be built.
+pub fn witness(circuit: &Path, assignments: &Path, output: &Path) -> Result<(), CliError> {
+    let witness = witness_from_assignments(&read_file(circuit)?, &read_assignments(assignments)?)?;
+    write_file(
+        output,
+        &WitnessFile {
+            columns: witness.to_vec(),
+        },
+    )
+}
+
+/// The `tables` command: writes the fixed lookup tables of the gates to the JSON file `output`
+///
+/// # Errors
+///
+/// Will give error if the file cannot be created or encoded.
+pub fn tables(output: &Path) -> Result<(), CliError> {
+    let name = output.display().to_string();
+    let file = File::create(output).map_err(|e| CliError::Io(name.clone(), e))?;
+    let tables: Vec<LookupTable<Fp>> = gate_lookup_tables();
+    serde_json::to_writer(BufWriter::new(file), &tables).map_err(|e| CliError::EncodeJson(name, e))
+}
+
+/// Creates the prover index of a circuit, with an SRS
+///
+/// # Errors
+///
+/// Will give error if the constraint system of the circuit cannot be built.
+pub fn prover_index(
+    circuit: CircuitFile,
+    mut srs: SRS<Vesta>,
+) -> Result<ProverIndex<Vesta>, CliError> {
+    let cs = ConstraintSystem::<Fp>::create(circuit.gates)
+        .public(circuit.public)
+        .build()?;
+    srs.add_lagrange_basis(cs.domain.d1);
+    let (endo_q, _endo_r) = endos::<Pallas>();
+    Ok(ProverIndex::create(cs, endo_q, Arc::new(srs)))
+}
+
+/// The `prove` command: proves the circuit of the file `circuit` with the witness of the file `witness`,
+/// and writes the proof to the file `output`
+///
+/// # Errors
+///
+/// Will give error if a file cannot be read or written, or if the proof cannot be created.
+pub fn prove(circuit: &Path, witness: &Path, srs: &Path, output: &Path) -> Result<(), CliError> {
+    let index = prover_index(read_file(circuit)?, read_file(srs)?)?;
+    let WitnessFile { columns } = read_file(witness)?;
+    let num_columns = columns.len();
+    let witness: [Vec<Fp>; COLUMNS] = columns
+        .try_into()
+        .map_err(|_| CliError::WitnessColumns(num_columns))?;
+    let public_input = witness[0].iter().take(index.cs.public).copied().collect();
+
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof = ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], &index)?;
+    write_file(
+        output,
+        &ProofFile {
+            proof,
+            public_input,
+        },
+    )
+}
+
+/// The `verify` command: verifies the proof of the file `proof` for the circuit of the file `circuit`
+///
+/// # Errors
+///
+/// Will give error if a file cannot be read, or if the proof is invalid.
+pub fn verify_proof(circuit: &Path, srs: &Path, proof: &Path) -> Result<(), CliError> {
+    let index = prover_index(read_file(circuit)?, read_file(srs)?)?;
+    let ProofFile {
+        proof,
+        public_input,
+    } = read_file(proof)?;
+
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    verify::<Vesta, BaseSponge, ScalarSponge>(
+        &group_map,
+        &index.verifier_index(),
+        &proof,
+        &public_input,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuits::{
+        gate::Connect,
+        polynomials::generic::{
+            testing::{create_circuit, fill_in_witness},
+            GenericGateSpec,
+        },
+        wires::Wire,
+    };
+    use o1_utils::FieldHelpers;
+    use std::path::PathBuf;
+
+    // A path in the temporary directory, unique to the test
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("kimchi-cli-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn test_prove_and_verify_files() {
+        let public = vec![Fp::from(3u8); 5];
+        let gates = create_circuit(0, public.len());
+        let mut trace: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+        fill_in_witness(0, &mut trace, &public);
+
+        let [circuit, witness, srs, proof] = ["circuit", "witness", "srs", "proof"].map(temp_path);
+        write_file(
+            &circuit,
+            &CircuitFile {
+                public: public.len(),
+                gates,
+                variables: BTreeMap::new(),
+            },
+        )
+        .unwrap();
+        write_file(
+            &witness,
+            &WitnessFile {
+                columns: trace.to_vec(),
+            },
+        )
+        .unwrap();
+        write_file(&srs, &SRS::<Vesta>::create(64)).unwrap();
+
+        prove(&circuit, &witness, &srs, &proof).unwrap();
+        verify_proof(&circuit, &srs, &proof).unwrap();
+
+        // the proof does not verify for another public input
+        let mut proof_file: ProofFile = read_file(&proof).unwrap();
+        proof_file.public_input[0] += Fp::from(1u8);
+        write_file(&proof, &proof_file).unwrap();
+        assert!(matches!(
+            verify_proof(&circuit, &srs, &proof),
+            Err(CliError::Verify(_))
+        ));
+
+        // the witness must have all its columns
+        write_file(
+            &witness,
+            &WitnessFile {
+                columns: vec![vec![]],
+            },
+        )
+        .unwrap();
+        assert!(matches!(
+            prove(&circuit, &witness, &srs, &proof),
+            Err(CliError::WitnessColumns(1))
+        ));
+
+        for path in [circuit, witness, srs, proof] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    // A circuit of the public input x, and of y = x * x
+    fn square_circuit() -> CircuitFile {
+        let mut gates = vec![
+            CircuitGate::create_generic_gadget(Wire::for_row(0), GenericGateSpec::Pub, None),
+            CircuitGate::create_generic_gadget(
+                Wire::for_row(1),
+                GenericGateSpec::Mul {
+                    output_coeff: None,
+                    mul_coeff: None,
+                },
+                None,
+            ),
+        ];
+        gates.connect_cell_pair((0, 0), (1, 0));
+        gates.connect_cell_pair((1, 0), (1, 1));
+        CircuitFile {
+            public: 1,
+            gates,
+            variables: BTreeMap::from([
+                ("x".to_string(), vec![(0, 0), (1, 0), (1, 1)]),
+                ("y".to_string(), vec![(1, 2)]),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_witness_from_assignments() {
+        let [circuit, json, csv, witness_file, srs, proof] = [
+            "circuit",
+            "witness.json",
+            "witness.csv",
+            "witness",
+            "srs",
+            "proof",
+        ]
+        .map(temp_path);
+        write_file(&circuit, &square_circuit()).unwrap();
+        write_file(&srs, &SRS::<Vesta>::create(64)).unwrap();
+
+        // the proofs of the witnesses built from the JSON and the CSV assignments
+        fs::write(&json, r#"{"x": "-3", "y": 9}"#).unwrap();
+        fs::write(&csv, "name,value\nx,5\n\ny, 25\n").unwrap();
+        for (assignments, x) in [(&json, -Fp::from(3u8)), (&csv, Fp::from(5u8))] {
+            witness(&circuit, assignments, &witness_file).unwrap();
+            let WitnessFile { columns } = read_file(&witness_file).unwrap();
+            assert_eq!(columns[0][0], x);
+            assert_eq!(columns[2][1], x * x);
+            prove(&circuit, &witness_file, &srs, &proof).unwrap();
+            verify_proof(&circuit, &srs, &proof).unwrap();
+        }
+
+        for path in [circuit, json, csv, witness_file, srs, proof] {
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_compressed_files() {
+        let public = vec![Fp::from(3u8); 5];
+        let gates = create_circuit(0, public.len());
+        let mut trace: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+        fill_in_witness(0, &mut trace, &public);
+
+        let [circuit, witness, srs, proof] =
+            ["circuit.zst", "witness", "srs.zst", "proof.zst"].map(temp_path);
+        write_file(
+            &circuit,
+            &CircuitFile {
+                public: public.len(),
+                gates,
+                variables: BTreeMap::new(),
+            },
+        )
+        .unwrap();
+        write_file(
+            &witness,
+            &WitnessFile {
+                columns: trace.to_vec(),
+            },
+        )
+        .unwrap();
+        write_file(&srs, &SRS::<Vesta>::create(64)).unwrap();
+
+        prove(&circuit, &witness, &srs, &proof).unwrap();
+        verify_proof(&circuit, &srs, &proof).unwrap();
+        for path in [&circuit, &srs, &proof] {
+            assert!(fs::read(path).unwrap().starts_with(&compression::MAGIC));
+        }
+        assert!(!fs::read(&witness).unwrap().starts_with(&compression::MAGIC));
+
+        for path in [circuit, witness, srs, proof] {
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_tables() {
+        let path = temp_path("tables.json");
+        tables(&path).unwrap();
+        let file = File::open(&path).unwrap();
+        let exported: Vec<LookupTable<Fp>> = serde_json::from_reader(BufReader::new(file)).unwrap();
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(exported, gate_lookup_tables());
+        let xor = &exported[0];
+        assert!(xor.contains(&[Fp::from(5u8), Fp::from(3u8), Fp::from(6u8)]));
+        assert!(!xor.contains(&[Fp::from(5u8), Fp::from(3u8), Fp::from(7u8)]));
+    }
+
+    #[test]
+    fn test_invalid_assignments() {
+        let circuit = square_circuit();
+        let build = |assignments: &[(&str, Fp)]| {
+            let assignments = assignments
+                .iter()
+                .map(|(variable, value)| (variable.to_string(), *value))
+                .collect();
+            witness_from_assignments(&circuit, &assignments)
+        };
+        let one = Fp::from(1u8);
+        assert!(build(&[("x", one), ("y", one)]).is_ok());
+        assert!(matches!(
+            build(&[("x", one)]),
+            Err(CliError::UnassignedVariable(variable)) if variable == "y"
+        ));
+        assert!(matches!(
+            build(&[("x", one), ("y", one), ("z", one)]),
+            Err(CliError::UnknownVariable(variable)) if variable == "z"
+        ));
+
+        let mut circuit = square_circuit();
+        circuit.variables.insert("z".to_string(), vec![(2, 0)]);
+        assert!(matches!(
+            witness_from_assignments(
+                &circuit,
+                &BTreeMap::from([("x".into(), one), ("y".into(), one), ("z".into(), one)])
+            ),
+            Err(CliError::InvalidCell(_, 2, 0))
+        ));
+
+        // the values must be decimal elements of the field
+        assert_eq!(parse_value("x", "-1").unwrap(), -one);
+        for value in ["", "-", "0x1", "1.5", "a"] {
+            assert!(matches!(
+                parse_value("x", value),
+                Err(CliError::InvalidValue(..))
+            ));
+        }
+        assert!(parse_value("x", &Fp::modulus_biguint().to_string()).is_err());
+        assert!(parse_json("a.json", r#"{"x": [1]}"#).is_err());
+        assert!(parse_json("a.json", "x,1").is_err());
+        assert!(matches!(
+            parse_csv("a.csv", "x,1\nx,2"),
+            Err(CliError::Assignments(..))
+        ));
+        assert!(parse_csv("a.csv", "x;1").is_err());
+        assert!(matches!(
+            read_assignments(Path::new("Cargo.toml")),
+            Err(CliError::AssignmentsFormat(_))
+        ));
+    }
+}
@@ -0,0 +1,218 @@
+//! This module implements the optional compression of the serialized artifacts
+//! (proofs, prover and verifier indexes, SRS) with [zstd](https://facebook.github.io/zstd/).
+//!
+//! The artifacts are encoded with MessagePack (see [rmp_serde]). A compressed artifact starts with
+//! a header of [HEADER_LEN] bytes, made of the magic number [MAGIC], of the version [VERSION] of the format
+//! and of the codec of the compression, followed by the zstd stream of its encoding:
+//!
+//! ```text
+//! "KZST" | version (1 byte) | codec (1 byte) | zstd stream
+//! ```
+//!
+//! The decompression is transparent: [read] decodes the artifacts with or without a header,
+//! so that the artifacts serialized before their compression can still be read.
+//! The encoding of an artifact never starts with the magic number, as MessagePack encodes
+//! its structures and sequences as maps and arrays, whose first byte is not `K`.
+//! The decompression is streamed, so that a large artifact is decoded without being decompressed in memory.
+//!
+//! The zstd compression requires the `compression` feature. Without it, the artifacts
+//! are written and read without compression, and reading a compressed artifact gives
+//! [CompressionError::UnsupportedCodec].
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    io::{ErrorKind, Read, Write},
+    path::Path,
+};
+use thiserror::Error;
+
+/// The magic number of a compressed artifact
+pub const MAGIC: [u8; 4] = *b"KZST";
+
+/// The version of the format of the header
+pub const VERSION: u8 = 1;
+
+/// The number of bytes of the header of a compressed artifact
+pub const HEADER_LEN: usize = MAGIC.len() + 2;
+
+/// The default level of the zstd compression
+#[cfg(feature = "compression")]
+pub const DEFAULT_LEVEL: i32 = zstd::DEFAULT_COMPRESSION_LEVEL;
+
+// The codec of the zstd compression in the header
+const CODEC_ZSTD: u8 = 1;
+
+/// Errors that can arise when reading or writing an artifact
+#[derive(Error, Debug)]
+pub enum CompressionError {
+    #[error("could not access the artifact: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("the version {0} of the header is not supported")]
+    UnsupportedVersion(u8),
+
+    #[error("the codec {0} of the header is not supported")]
+    UnsupportedCodec(u8),
+
+    #[error("could not decode the artifact: {0}")]
+    Decode(#[from] rmp_serde::decode::Error),
+
+    #[error("could not encode the artifact: {0}")]
+    Encode(#[from] rmp_serde::encode::Error),
+}
+
+/// The compression of a serialized artifact
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+    /// The MessagePack encoding, without header
+    #[default]
+    None,
+    /// The zstd compression of the encoding at a level (from 1 to 22), after a header
+    #[cfg(feature = "compression")]
+    Zstd(i32),
+}
+
+impl Compression {
+    /// The zstd compression at the [DEFAULT_LEVEL]
+    #[cfg(feature = "compression")]
+    pub const fn zstd() -> Self {
+        Compression::Zstd(DEFAULT_LEVEL)
+    }
+
+    /// The compression of a file: zstd if its extension is `zst` (with the `compression` feature),
+    /// and none otherwise
+    #[cfg_attr(not(feature = "compression"), allow(unused_variables))]
+    pub fn from_path(path: &Path) -> Self {
+        #[cfg(feature = "compression")]
+        if path.extension().map_or(false, |ext| ext == "zst") {
+            return Self::zstd();
+        }
+        Compression::None
+    }
+}
+
+/// Serializes an artifact to a writer, with a compression
+///
+/// # Errors
+///
+/// Will give error if the artifact cannot be encoded, compressed or written.
+pub fn write<T: Serialize + ?Sized, W: Write>(
+    mut writer: W,
+    value: &T,
+    compression: Compression,
+) -> Result<(), CompressionError> {
+    match compression {
+        Compression::None => rmp_serde::encode::write(&mut writer, value)?,
+        #[cfg(feature = "compression")]
+        Compression::Zstd(level) => {
+            writer.write_all(&MAGIC)?;
+            writer.write_all(&[VERSION, CODEC_ZSTD])?;
+            let mut encoder = zstd::stream::write::Encoder::new(&mut writer, level)?;
+            rmp_serde::encode::write(&mut encoder, value)?;
+            encoder.finish()?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Deserializes an artifact from a reader, which is decompressed on the fly if it starts with a header
+///
+/// # Errors
+///
+/// Will give error if the artifact cannot be read, decompressed or decoded, or if its header is not supported.
+pub fn read<T: DeserializeOwned, R: Read>(mut reader: R) -> Result<T, CompressionError> {
+    // the first bytes of the artifact, which are decoded with the rest if they are not a header
+    let mut header = [0u8; HEADER_LEN];
+    let mut len = 0;
+    while len < HEADER_LEN {
+        match reader.read(&mut header[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    if len < HEADER_LEN || header[..MAGIC.len()] != MAGIC {
+        return Ok(rmp_serde::from_read((&header[..len]).chain(reader))?);
+    }
+
+    let (version, codec) = (header[MAGIC.len()], header[MAGIC.len() + 1]);
+    if version != VERSION {
+        return Err(CompressionError::UnsupportedVersion(version));
+    }
+    if codec != CODEC_ZSTD {
+        return Err(CompressionError::UnsupportedCodec(codec));
+    }
+    read_zstd(reader)
+}
+
+// Decodes the zstd stream of a compressed artifact
+#[cfg(feature = "compression")]
+fn read_zstd<T: DeserializeOwned, R: Read>(reader: R) -> Result<T, CompressionError> {
+    let decoder = zstd::stream::read::Decoder::new(reader)?;
+    Ok(rmp_serde::from_read(decoder)?)
+}
+
+// The zstd streams are only decoded with the `compression` feature
+#[cfg(not(feature = "compression"))]
+fn read_zstd<T, R>(_reader: R) -> Result<T, CompressionError> {
+    Err(CompressionError::UnsupportedCodec(CODEC_ZSTD))
+}
+
+#[cfg(all(test, feature = "compression"))]
+mod tests {
+    use super::*;
+    use crate::precomputed_srs::get_srs;
+    use mina_curves::pasta::Vesta;
+    use poly_commitment::srs::SRS;
+
+    #[test]
+    fn test_compressed_srs() {
+        let srs = get_srs::<Vesta>();
+        let mut plain = vec![];
+        write(&mut plain, &srs, Compression::None).unwrap();
+        let mut compressed = vec![];
+        write(&mut compressed, &srs, Compression::zstd()).unwrap();
+
+        assert_eq!(plain, rmp_serde::to_vec(&srs).unwrap());
+        assert_eq!(compressed[..MAGIC.len()], MAGIC);
+        for bytes in [&plain, &compressed] {
+            let decoded: SRS<Vesta> = read(bytes.as_slice()).unwrap();
+            assert_eq!(decoded, srs);
+        }
+    }
+
+    #[test]
+    fn test_compressed_size() {
+        let value = vec![0u64; 1 << 12];
+        let mut compressed = vec![];
+        write(&mut compressed, &value, Compression::zstd()).unwrap();
+        assert!(compressed.len() < rmp_serde::to_vec(&value).unwrap().len() / 16);
+        assert_eq!(read::<Vec<u64>, _>(compressed.as_slice()).unwrap(), value);
+    }
+
+    #[test]
+    fn test_unsupported_header() {
+        let mut compressed = vec![];
+        write(&mut compressed, &[1u64, 2, 3], Compression::Zstd(1)).unwrap();
+        let mut version = compressed.clone();
+        version[MAGIC.len()] = VERSION + 1;
+        assert!(matches!(
+            read::<Vec<u64>, _>(version.as_slice()),
+            Err(CompressionError::UnsupportedVersion(v)) if v == VERSION + 1
+        ));
+        let mut codec = compressed;
+        codec[MAGIC.len() + 1] = 0;
+        assert!(matches!(
+            read::<Vec<u64>, _>(codec.as_slice()),
+            Err(CompressionError::UnsupportedCodec(0))
+        ));
+
+        // a truncated header is decoded as MessagePack
+        assert!(matches!(
+            read::<Vec<u64>, _>(&MAGIC[..2]),
+            Err(CompressionError::Decode(_))
+        ));
+    }
+}
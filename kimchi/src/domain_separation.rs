@@ -0,0 +1,151 @@
+//! This module gathers all the domain separation tags used by kimchi in a single place.
+//!
+//! The tags are versioned with [DOMAIN_SEPARATION_VERSION]:
+//! changing any of them changes the digests and transcripts produced by the prover and verifier,
+//! so such a change must come with a new version.
+//!
+//! Applications that need distinct domains (for example one per deployment environment)
+//! can additionally inject a [DomainSeparator] in the constraint system,
+//! see [crate::circuits::constraints::Builder::domain_separator].
+//! Proofs created for one domain do not verify for another.
+
+use ark_ff::Field;
+use mina_poseidon::FqSponge;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The version of the domain separation tags below.
+pub const DOMAIN_SEPARATION_VERSION: u8 = 0;
+
+/// The prefix used when hashing a [crate::circuits::gate::Circuit].
+pub const CIRCUIT_DIGEST_PREFIX: &[u8; 15] = b"kimchi-circuit0";
+
+/// The prefix used to derive the blinding generator of the SRS.
+pub use poly_commitment::srs::SRS_MISC_PREFIX;
+
+/// The prefix absorbed before the public values of a proof when hashing them
+/// into a leaf of a [crate::public_commitment::PublicInputTree].
+pub const PUBLIC_INPUT_LEAF_PREFIX: &[u8; 18] = b"kimchi-public-leaf";
+
+/// The prefix absorbed before the children of a node of a [crate::public_commitment::PublicInputTree]
+/// when hashing them, so that no node is the digest of public values.
+pub const PUBLIC_INPUT_NODE_PREFIX: &[u8; 18] = b"kimchi-public-node";
+
+/// Errors that can arise when creating a [DomainSeparator]
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DomainSeparatorError {
+    #[error("the domain separation tag is too long (max {max} bytes, got {len})")]
+    TagTooLong { max: usize, len: usize },
+}
+
+/// An application-specific domain separator.
+///
+/// When present in a constraint system, it is absorbed first into the digest of the verifier index,
+/// and thus binds every challenge of the proof to the domain.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DomainSeparator {
+    /// The version of the domain separation tags
+    version: u8,
+    /// The application-specific tag
+    tag: Vec<u8>,
+}
+
+impl DomainSeparator {
+    /// The maximum length of a tag, so that the version, the length and the tag
+    /// always fit in a single field element (of at least 248 bits).
+    pub const MAX_TAG_LEN: usize = 29;
+
+    /// Creates a domain separator for the current [DOMAIN_SEPARATION_VERSION].
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `tag` is longer than [DomainSeparator::MAX_TAG_LEN] bytes.
+    pub fn new(tag: &[u8]) -> Result<Self, DomainSeparatorError> {
+        Self::with_version(DOMAIN_SEPARATION_VERSION, tag)
+    }
+
+    /// Creates a domain separator for a given `version` of the domain separation tags.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `tag` is longer than [DomainSeparator::MAX_TAG_LEN] bytes.
+    pub fn with_version(version: u8, tag: &[u8]) -> Result<Self, DomainSeparatorError> {
+        if tag.len() > Self::MAX_TAG_LEN {
+            return Err(DomainSeparatorError::TagTooLong {
+                max: Self::MAX_TAG_LEN,
+                len: tag.len(),
+            });
+        }
+        Ok(Self {
+            version,
+            tag: tag.to_vec(),
+        })
+    }
+
+    /// The version of the domain separation tags
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// The application-specific tag
+    pub fn tag(&self) -> &[u8] {
+        &self.tag
+    }
+
+    /// Encodes the domain separator as a field element,
+    /// with the little-endian bytes `version || len(tag) || tag`.
+    pub fn to_field<F: Field>(&self) -> F {
+        let mut bytes = vec![self.version, self.tag.len() as u8];
+        bytes.extend_from_slice(&self.tag);
+        bytes.iter().rev().fold(F::zero(), |acc, byte| {
+            acc * F::from(256u16) + F::from(*byte)
+        })
+    }
+
+    /// Absorbs the domain separator into an Fq-sponge.
+    pub fn absorb<Fq: Field, G, Fr, EFqSponge: FqSponge<Fq, G, Fr>>(&self, sponge: &mut EFqSponge) {
+        sponge.absorb_fq(&[self.to_field()]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mina_curves::pasta::Fq;
+    use o1_utils::FieldHelpers;
+
+    #[test]
+    fn test_domain_separator_encoding() {
+        let sep = DomainSeparator::new(b"testnet").unwrap();
+        assert_eq!(sep.version(), DOMAIN_SEPARATION_VERSION);
+        assert_eq!(sep.tag(), b"testnet");
+
+        // distinct tags and versions give distinct field elements
+        let other = DomainSeparator::new(b"mainnet").unwrap();
+        assert_ne!(sep.to_field::<Fq>(), other.to_field::<Fq>());
+        let newer = DomainSeparator::with_version(1, b"testnet").unwrap();
+        assert_ne!(sep.to_field::<Fq>(), newer.to_field::<Fq>());
+
+        // the length prevents collisions between tags with trailing zeros
+        let padded = DomainSeparator::new(b"testnet\0").unwrap();
+        assert_ne!(sep.to_field::<Fq>(), padded.to_field::<Fq>());
+
+        // the encoding matches the little-endian bytes of the field element
+        let longest = DomainSeparator::new(&[0xff; DomainSeparator::MAX_TAG_LEN]).unwrap();
+        let mut bytes = vec![
+            DOMAIN_SEPARATION_VERSION,
+            DomainSeparator::MAX_TAG_LEN as u8,
+        ];
+        bytes.extend([0xff; DomainSeparator::MAX_TAG_LEN]);
+        bytes.resize(Fq::size_in_bytes(), 0);
+        assert_eq!(longest.to_field::<Fq>(), Fq::from_bytes(&bytes).unwrap());
+
+        assert_eq!(
+            DomainSeparator::new(&[0; DomainSeparator::MAX_TAG_LEN + 1]),
+            Err(DomainSeparatorError::TagTooLong {
+                max: DomainSeparator::MAX_TAG_LEN,
+                len: DomainSeparator::MAX_TAG_LEN + 1
+            })
+        );
+    }
+}
@@ -0,0 +1,122 @@
+//! This module implements a checker of the equivalence of two versions of a circuit,
+//! which gains confidence that a refactoring of gadgets does not change their behavior.
+//!
+//! Each [`CircuitVersion`] is a list of gates with a number of public inputs,
+//! and the computation of its witness from some inputs (such as the inputs of its gadgets).
+//! [`check_equivalence`] samples inputs, computes the witnesses of both versions,
+//! and checks with the [`MockProver`] that:
+//!
+//! * both versions have the same number of public inputs,
+//! * the public inputs of their witnesses are the same,
+//! * the witness of one version satisfies its circuit if and only if the witness of the other one does.
+//!
+//! The sampled inputs should include invalid ones (for which the witnesses do not satisfy the circuits),
+//! so that their sets of satisfying inputs are compared, and not only their honest witnesses.
+//! Note that the checker samples the behaviors of both versions, and does not prove their equivalence.
+
+use crate::{
+    circuits::{constraints::ConstraintSystem, gate::CircuitGate, wires::COLUMNS},
+    curve::KimchiCurve,
+    error::SetupError,
+    mock_prover::MockProver,
+};
+use ark_ff::{PrimeField, SquareRootField};
+use rand::Rng;
+use thiserror::Error;
+
+/// A version of a circuit, with the computation of its witness from some inputs
+pub struct CircuitVersion<'a, F: PrimeField> {
+    cs: ConstraintSystem<F>,
+    witness: Box<dyn Fn(&[F]) -> [Vec<F>; COLUMNS] + 'a>,
+}
+
+impl<'a, F: PrimeField + SquareRootField> CircuitVersion<'a, F> {
+    /// Creates a version of a circuit from its gates, its number of public inputs
+    /// and the computation of its witness from the sampled inputs
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the constraint system of the gates cannot be created.
+    pub fn new(
+        gates: Vec<CircuitGate<F>>,
+        public: usize,
+        witness: impl Fn(&[F]) -> [Vec<F>; COLUMNS] + 'a,
+    ) -> Result<Self, SetupError> {
+        let cs = ConstraintSystem::create(gates).public(public).build()?;
+        Ok(Self {
+            cs,
+            witness: Box::new(witness),
+        })
+    }
+
+    /// Returns the constraint system of the version
+    pub fn cs(&self) -> &ConstraintSystem<F> {
+        &self.cs
+    }
+
+    // Computes the witness of the inputs, and returns its public inputs and if it satisfies the circuit
+    fn evaluate<G: KimchiCurve<ScalarField = F>>(&self, inputs: &[F]) -> (Vec<F>, bool) {
+        let witness = (self.witness)(inputs);
+        let public = witness[0]
+            .get(..self.cs.public)
+            .unwrap_or_default()
+            .to_vec();
+        let satisfied = MockProver::<G>::new(&self.cs, &witness, &public)
+            .verify()
+            .is_ok();
+        (public, satisfied)
+    }
+}
+
+/// A difference between two versions of a circuit
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EquivalenceError {
+    #[error("the versions have {left} and {right} public inputs")]
+    PublicLayout { left: usize, right: usize },
+
+    #[error("the witnesses of the sample {0} have different public inputs")]
+    PublicValues(usize),
+
+    #[error("the witnesses of the sample {sample} do not both satisfy or fail their circuits (left: {left}, right: {right})")]
+    Satisfiability {
+        sample: usize,
+        left: bool,
+        right: bool,
+    },
+}
+
+/// Checks that two versions of a circuit behave the same on `samples` inputs sampled by `inputs`, see the module
+///
+/// # Errors
+///
+/// Will give error on the first difference found between the versions.
+pub fn check_equivalence<G: KimchiCurve, R: Rng + ?Sized>(
+    left: &CircuitVersion<G::ScalarField>,
+    right: &CircuitVersion<G::ScalarField>,
+    samples: usize,
+    rng: &mut R,
+    mut inputs: impl FnMut(&mut R) -> Vec<G::ScalarField>,
+) -> Result<(), EquivalenceError> {
+    if left.cs.public != right.cs.public {
+        return Err(EquivalenceError::PublicLayout {
+            left: left.cs.public,
+            right: right.cs.public,
+        });
+    }
+    for sample in 0..samples {
+        let inputs = inputs(rng);
+        let (left_public, left_satisfied) = left.evaluate::<G>(&inputs);
+        let (right_public, right_satisfied) = right.evaluate::<G>(&inputs);
+        if left_public != right_public {
+            return Err(EquivalenceError::PublicValues(sample));
+        }
+        if left_satisfied != right_satisfied {
+            return Err(EquivalenceError::Satisfiability {
+                sample,
+                left: left_satisfied,
+                right: right_satisfied,
+            });
+        }
+    }
+    Ok(())
+}
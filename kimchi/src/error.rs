@@ -1,5 +1,12 @@
 //! This module implements the [`ProverError`] type.
 
+use crate::{
+    circuits::{
+        gate::GateType,
+        wires::{Wire, COLUMNS, PERMUTS},
+    },
+    version::ArithmetizationVersion,
+};
 use poly_commitment::error::CommitmentError;
 use thiserror::Error;
 
@@ -38,9 +45,18 @@ pub enum VerifyError {
     #[error("the commitment to {0} is of an unexpected size")]
     IncorrectCommitmentLength(&'static str),
 
+    #[error("the arithmetization version {0} of the proof differs from the version {1} of the verifier index")]
+    ArithmetizationVersionMismatch(ArithmetizationVersion, ArithmetizationVersion),
+
+    #[error("the arithmetization version {0} is not supported by this verifier")]
+    UnsupportedArithmetizationVersion(ArithmetizationVersion),
+
     #[error("the public input is of an unexpected size (expected {0})")]
     IncorrectPubicInputLength(usize),
 
+    #[error("the public output is of an unexpected size (expected {0})")]
+    IncorrectPublicOutputLength(usize),
+
     #[error("the previous challenges have an unexpected length (expected {0}, got {1})")]
     IncorrectPrevChallengesLength(usize, usize),
 
@@ -73,6 +89,31 @@ pub enum VerifyError {
 
     #[error("the commitment for {0:?} is missing")]
     MissingCommitment(crate::circuits::expr::Column),
+
+    #[error("the proof {0} of the session is not bound to the previous one")]
+    SessionChainBroken(usize),
+
+    #[error("the opening of the public input of the proof {0} does not match the commitment")]
+    PublicInputOpening(usize),
+}
+
+/// Errors that can arise when validating a witness before proving, see [crate::validation]
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WitnessError {
+    #[error("the witness column {0} has {1} rows instead of {2}")]
+    ColumnLength(usize, usize, usize),
+
+    #[error("the witness has {0} rows, but the domain leaves room for {1} rows before the zero-knowledge rows")]
+    TooManyRows(usize, usize),
+
+    #[error("the public input is of an unexpected size (expected {0}, got {1})")]
+    PublicInputLength(usize, usize),
+
+    #[error("the public input of the row {0} does not match the witness")]
+    PublicInputMismatch(usize),
+
+    #[error("the value of the range check gate of the row {0} does not fit in 88 bits")]
+    RangeCheck(usize),
 }
 
 /// Errors that can arise when preparing the setup
@@ -83,6 +124,75 @@ pub enum SetupError {
 
     #[error("the domain could not be constructed: {0}")]
     DomainCreation(&'static str),
+
+    #[error("the wiring of the circuit is not a permutation: {0}")]
+    Wiring(WiringError),
+}
+
+/// Errors that can arise when checking the wiring of a circuit,
+/// see [crate::circuits::gate::check_wiring]
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WiringError {
+    #[error("the cell {},{} is wired to the column {} of the row {}, which is not one of the {} wired columns", .cell.row, .cell.col, .wire.col, .wire.row, PERMUTS)]
+    ColumnOutOfRange { cell: Wire, wire: Wire },
+
+    #[error("the cell {},{} is wired to the row {}, outside of the {} rows of the circuit", .cell.row, .cell.col, .wire.row, .rows)]
+    RowOutOfRange { cell: Wire, wire: Wire, rows: usize },
+
+    #[error("the cells {},{} and {},{} are both wired to the cell {},{}, so that their cycle does not close", .cells[0].row, .cells[0].col, .cells[1].row, .cells[1].col, .wire.row, .wire.col)]
+    SharedWire { cells: [Wire; 2], wire: Wire },
+}
+
+/// Errors that can arise when solving a witness, see [crate::circuits::witness::solve_witness]
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveError {
+    #[error("the witness column {0} has {1} rows instead of {2}")]
+    ColumnLength(usize, usize, usize),
+
+    #[error("the input cell {0},{1} is not a cell of the circuit")]
+    InputOutOfRange(usize, usize),
+
+    #[error("the wiring of the circuit is not a permutation: {0}")]
+    Wiring(WiringError),
+
+    #[error("the cell {0},{1} is assigned two different values")]
+    Conflict(usize, usize),
+
+    #[error("the cells of the {1:?} gate of the row {0} are not determined by the known cells")]
+    Unsolved(usize, GateType),
+}
+
+/// Errors that can arise when loading a serialized circuit, see [crate::circuits::compiled]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CircuitFormatError {
+    #[error("the data is not a serialized circuit")]
+    NotACircuit,
+
+    #[error("the version {0} of the serialized circuit is not supported")]
+    UnsupportedVersion(u32),
+
+    #[error("the serialized circuit could not be decoded: {0}")]
+    Decoding(String),
+}
+
+/// Errors that can arise when converting a circuit or a witness to or from the format of o1js,
+/// see [crate::snarky::o1js]
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum O1jsFormatError {
+    #[error("the gate {1:?} of the row {0} is not supported by o1js")]
+    UnsupportedGate(usize, GateType),
+
+    #[error("the configuration of the circuit is not supported by o1js: {0}")]
+    UnsupportedConfiguration(&'static str),
+
+    #[error("the witness has {0} columns instead of {COLUMNS}")]
+    WitnessColumns(usize),
+
+    #[error("the witness column {0} has {1} rows instead of {2}")]
+    ColumnLength(usize, usize, usize),
+
+    #[error("the public input of the row {0} does not match the witness")]
+    PublicInputMismatch(usize),
 }
 
 /// Errors that can arise when creating a verifier index
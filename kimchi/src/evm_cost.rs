@@ -0,0 +1,232 @@
+//! This module implements a cost model of the verification of kimchi proofs by a verifier contract
+//! on the EVM, to evaluate the design choices of a circuit (its size, its public inputs, its lookups)
+//! against their on-chain cost before committing to them.
+//!
+//! A [`VerifierCostConfig`] is the part of the configuration of a verifier index that determines the cost
+//! of a verification: the number of chunks of the polynomials, of public inputs, of commitments of the
+//! linearization and of the lookup argument.
+//! [`VerifierCostConfig::estimate`] counts the elements of a proof sent in calldata, and the operations of
+//! the verifier, which it prices with a [`GasSchedule`].
+//!
+//! The verifier contract is assumed to check the commitments with the precompiles of elliptic curve addition
+//! and multiplication (each term of a multi-scalar multiplication being a multiplication and an addition),
+//! to hash its transcript with Keccak, and to compute in the scalar field with `MULMOD` and `ADDMOD`.
+//! Note that the estimates are approximations, which do not include the memory expansion and the control flow
+//! of the contract, and that an opening proof with the inner product argument requires a multi-scalar
+//! multiplication of the size of the SRS, which usually dominates the cost.
+
+use crate::{
+    circuits::wires::{COLUMNS, PERMUTS},
+    curve::KimchiCurve,
+    verifier_index::VerifierIndex,
+};
+use ark_ff::PrimeField;
+use ark_poly::EvaluationDomain;
+use o1_utils::{math, FieldHelpers};
+
+/// The costs in gas of the operations of a verifier contract
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GasSchedule {
+    /// The base cost of a transaction
+    pub transaction: u64,
+    /// The cost of a non-zero byte of calldata (EIP-2028)
+    pub calldata_byte: u64,
+    /// The cost of the precompile of an elliptic curve addition (EIP-1108)
+    pub ec_add: u64,
+    /// The cost of the precompile of an elliptic curve multiplication (EIP-1108)
+    pub ec_mul: u64,
+    /// The cost of a multiplication or an addition in the scalar field, with its stack operations
+    pub field_op: u64,
+    /// The cost of an inversion in the scalar field, with the modular exponentiation precompile
+    pub field_inverse: u64,
+    /// The base cost of a Keccak hash, for each challenge of the transcript
+    pub keccak: u64,
+    /// The cost of each word of 32 bytes hashed with Keccak
+    pub keccak_word: u64,
+}
+
+impl Default for GasSchedule {
+    fn default() -> Self {
+        Self {
+            transaction: 21_000,
+            calldata_byte: 16,
+            ec_add: 150,
+            ec_mul: 6_000,
+            field_op: 12,
+            field_inverse: 1_400,
+            keccak: 30,
+            keccak_word: 6,
+        }
+    }
+}
+
+/// The configuration of a verifier index that determines the cost of a verification
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifierCostConfig {
+    /// The size of the evaluation domain
+    pub domain_size: usize,
+    /// The maximal size of a chunk of a polynomial, the size of the SRS
+    pub max_poly_size: usize,
+    /// The number of public inputs
+    pub public: usize,
+    /// The number of previous challenges, for recursive proving
+    pub prev_challenges: usize,
+    /// The number of chunks of the quotient polynomial
+    pub quotient_chunks: usize,
+    /// The number of commitments of the index in the linearization
+    pub index_terms: usize,
+    /// The number of tokens of the evaluation of the linearization
+    pub linearization_tokens: usize,
+    /// The number of sorted polynomials (or multiplicities) of the lookup argument, if any
+    pub lookup_sorted: Option<usize>,
+    /// The number of commitments combined into the lookup table, including the table ids
+    pub lookup_table_columns: usize,
+    /// Whether the lookup argument has runtime tables
+    pub runtime_tables: bool,
+    /// The number of bytes of an element of the scalar field
+    pub field_bytes: usize,
+    /// The number of bytes of an uncompressed point
+    pub point_bytes: usize,
+}
+
+/// The estimated cost of the verification of a proof
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VerifierCost {
+    /// The number of points of the proof
+    pub proof_points: usize,
+    /// The number of scalars of the proof
+    pub proof_scalars: usize,
+    /// The number of bytes of calldata of the proof and of its public inputs
+    pub calldata_bytes: usize,
+    /// The number of terms of the multi-scalar multiplications of the verifier
+    pub msm_terms: usize,
+    /// The number of operations in the scalar field of the verifier
+    pub field_ops: usize,
+    /// The number of words hashed into the transcript
+    pub transcript_words: usize,
+    /// The gas of the calldata
+    pub calldata_gas: u64,
+    /// The gas of the multi-scalar multiplications
+    pub msm_gas: u64,
+    /// The gas of the operations in the scalar field and of the transcript
+    pub compute_gas: u64,
+    /// The total gas of the verification, including the base cost of the transaction
+    pub total_gas: u64,
+}
+
+impl VerifierCostConfig {
+    /// Extracts the configuration of a verifier index
+    pub fn from_index<G: KimchiCurve>(index: &VerifierIndex<G>) -> Self
+    where
+        G::BaseField: PrimeField,
+    {
+        let linearization = &index.linearization;
+        let linearization_tokens = linearization.constant_term.len()
+            + linearization
+                .index_terms
+                .iter()
+                .map(|(_, tokens)| tokens.len())
+                .sum::<usize>();
+        let lookup = index.lookup_index.as_ref();
+        Self {
+            domain_size: index.domain.size(),
+            max_poly_size: index.max_poly_size,
+            public: index.public,
+            prev_challenges: index.prev_challenges,
+            quotient_chunks: index.quotient_chunks,
+            index_terms: linearization.index_terms.len(),
+            linearization_tokens,
+            lookup_sorted: lookup.map(|li| li.lookup_info.num_sorted()),
+            lookup_table_columns: lookup.map_or(0, |li| {
+                li.lookup_table.len() + usize::from(li.table_ids.is_some())
+            }),
+            runtime_tables: lookup.map_or(false, |li| li.runtime_tables_selector.is_some()),
+            field_bytes: G::ScalarField::size_in_bytes(),
+            point_bytes: 2 * G::BaseField::size_in_bytes(),
+        }
+    }
+
+    // The number of chunks of the polynomials of the domain
+    fn chunks(&self) -> usize {
+        ((self.domain_size + self.max_poly_size - 1) / self.max_poly_size).max(1)
+    }
+
+    // The number of polynomials evaluated by the proof, and committed in the proof or in the index
+    fn evaluated_polynomials(&self) -> usize {
+        // the witness, the permutation, the sigmas but the last one, the coefficients, and the selectors
+        let circuit = COLUMNS + 1 + (PERMUTS - 1) + COLUMNS + 2;
+        // the sorted, the aggregation and the table, and the runtime table
+        let lookup = self
+            .lookup_sorted
+            .map_or(0, |sorted| sorted + 2 + usize::from(self.runtime_tables));
+        circuit + lookup
+    }
+
+    /// Estimates the cost of the verification of a proof with a gas schedule
+    pub fn estimate(&self, schedule: &GasSchedule) -> VerifierCost {
+        let chunks = self.chunks();
+        let rounds = math::ceil_log2(self.max_poly_size);
+
+        // the commitments: the witness, the permutation, the quotient, the lookup, and the opening proof
+        let lookup_commitments = self
+            .lookup_sorted
+            .map_or(0, |sorted| sorted + 1 + usize::from(self.runtime_tables));
+        let opening_points = 2 * rounds + 2;
+        let recursion_points = self.prev_challenges * chunks;
+        let proof_points = (COLUMNS + 1 + lookup_commitments) * chunks
+            + self.quotient_chunks
+            + opening_points
+            + recursion_points;
+
+        // the evaluations at two points, the evaluation of ft, the scalars of the opening proof,
+        // and the challenges of the previous proofs
+        let proof_scalars =
+            2 * self.evaluated_polynomials() * chunks + 1 + 2 + self.prev_challenges * rounds;
+        let calldata_bytes =
+            proof_points * self.point_bytes + (proof_scalars + self.public) * self.field_bytes;
+
+        // the multi-scalar multiplications of the public input, of the linearization, of ft,
+        // of the lookup table, of the combination of the evaluated commitments, and of the opening proof
+        let evaluated_commitments = self.prev_challenges + 2 + self.evaluated_polynomials();
+        let msm_terms = self.public * chunks
+            + (1 + self.index_terms) * chunks
+            + 2 * chunks
+            + self.quotient_chunks
+            + self.lookup_table_columns * chunks
+            + evaluated_commitments * chunks
+            + self.max_poly_size
+            + opening_points;
+
+        // the evaluation of the linearization, of the public input and of the zero-knowledge polynomial,
+        // the combination of the evaluations and of the chunks, the challenges of the opening proof
+        // and the polynomial of its challenges
+        let field_ops = self.linearization_tokens
+            + 4 * self.public
+            + 4 * self.evaluated_polynomials() * chunks
+            + 2 * (self.prev_challenges + 1) * rounds
+            + 2 * self.max_poly_size;
+        let field_inverses = 1 + rounds;
+        // the challenges of the transcript: beta, gamma, the joint combiner, alpha, zeta, v, u and the rounds
+        let challenges = 7 + rounds;
+        let transcript_words = (calldata_bytes + 31) / 32;
+
+        let calldata_gas = schedule.calldata_byte * calldata_bytes as u64;
+        let msm_gas = (schedule.ec_mul + schedule.ec_add) * msm_terms as u64;
+        let compute_gas = schedule.field_op * field_ops as u64
+            + schedule.field_inverse * field_inverses as u64
+            + schedule.keccak * challenges as u64
+            + schedule.keccak_word * transcript_words as u64;
+        VerifierCost {
+            proof_points,
+            proof_scalars,
+            calldata_bytes,
+            msm_terms,
+            field_ops,
+            transcript_words,
+            calldata_gas,
+            msm_gas,
+            compute_gas,
+            total_gas: schedule.transaction + calldata_gas + msm_gas + compute_gas,
+        }
+    }
+}
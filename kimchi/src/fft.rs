@@ -0,0 +1,71 @@
+//! This module implements the backends computing the FFTs of the prover, which interpolate
+//! the witness and the quotient, and evaluate the polynomials over the domains `d4` and `d8`.
+//!
+//! A prover index computes its FFTs with its backend, on the CPU by default (see
+//! [create_with_fft_backend](crate::prover_index::ProverIndex::create_with_fft_backend)).
+//! A backend, for example running on a GPU, can decline an FFT whose domain is too small
+//! to pay the transfer of the values to the device:
+//! the FFT is then computed on the CPU by arkworks.
+
+use ark_ff::{FftField, Zero};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain as D,
+    UVPolynomial,
+};
+use std::fmt::Debug;
+
+/// A backend computing the FFTs of the prover, see the [module documentation](self)
+pub trait FftBackend<F: FftField>: Debug + Send + Sync {
+    /// Replaces the coefficients of a polynomial, resized to the size of the domain,
+    /// by its evaluations over the domain, or returns `false` if the backend declines the FFT
+    fn fft_in_place(&self, domain: D<F>, coeffs: &mut Vec<F>) -> bool;
+
+    /// Replaces the evaluations of a polynomial over a domain by its coefficients,
+    /// or returns `false` if the backend declines the inverse FFT
+    fn ifft_in_place(&self, domain: D<F>, evals: &mut Vec<F>) -> bool;
+}
+
+/// The backend computing the FFTs on the CPU, with the parallel FFTs of arkworks
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CpuFft;
+
+impl<F: FftField> FftBackend<F> for CpuFft {
+    fn fft_in_place(&self, domain: D<F>, coeffs: &mut Vec<F>) -> bool {
+        domain.fft_in_place(coeffs);
+        true
+    }
+
+    fn ifft_in_place(&self, domain: D<F>, evals: &mut Vec<F>) -> bool {
+        domain.ifft_in_place(evals);
+        true
+    }
+}
+
+/// Evaluates a polynomial over a domain with a backend,
+/// or on the CPU if the backend declines the FFT
+pub fn evaluate_over_domain<F: FftField>(
+    backend: &dyn FftBackend<F>,
+    poly: &DensePolynomial<F>,
+    domain: D<F>,
+) -> Evaluations<F, D<F>> {
+    let mut evals = poly.coeffs.clone();
+    evals.resize(domain.size(), F::zero());
+    if !backend.fft_in_place(domain, &mut evals) {
+        domain.fft_in_place(&mut evals);
+    }
+    Evaluations::from_vec_and_domain(evals, domain)
+}
+
+/// Interpolates the evaluations of a polynomial over a domain with a backend,
+/// or on the CPU if the backend declines the inverse FFT
+pub fn interpolate<F: FftField>(
+    backend: &dyn FftBackend<F>,
+    evals: Evaluations<F, D<F>>,
+) -> DensePolynomial<F> {
+    let domain = evals.domain();
+    let mut coeffs = evals.evals;
+    if !backend.ifft_in_place(domain, &mut coeffs) {
+        domain.ifft_in_place(&mut coeffs);
+    }
+    DensePolynomial::from_coefficients_vec(coeffs)
+}
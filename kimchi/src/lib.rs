@@ -3,6 +3,9 @@
 #[macro_use]
 extern crate num_derive;
 
+// allows the derive macros to refer to `::kimchi` from within the crate
+extern crate self as kimchi;
+
 pub use groupmap;
 pub use mina_curves;
 pub use mina_poseidon;
@@ -12,20 +15,36 @@ pub use turshi;
 
 pub mod alphas;
 pub mod bench;
+pub mod blinding;
+#[cfg(feature = "ocaml_types")]
+pub mod caml;
 pub mod circuits;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod compression;
 pub mod curve;
+pub mod domain_separation;
+pub mod equivalence;
 pub mod error;
+pub mod evm_cost;
+pub mod fft;
 pub mod lagrange_basis_evaluations;
 pub mod linearization;
+pub mod metrics;
+pub mod mock_prover;
 pub mod oracles;
 pub mod plonk_sponge;
 pub mod precomputed_srs;
 pub mod proof;
 pub mod prover;
 pub mod prover_index;
+pub mod public_commitment;
+pub mod session;
 pub mod snarky;
+pub mod validation;
 pub mod verifier;
 pub mod verifier_index;
+pub mod version;
 
 #[cfg(test)]
 mod tests;
@@ -1,14 +1,16 @@
 //! This module implements the linearization.
 
 use crate::alphas::Alphas;
-use crate::circuits::argument::{Argument, ArgumentType};
+use crate::circuits::argument::{Argument, ArgumentType, DynArgument};
+use crate::circuits::custom_gate::{self, CustomGate};
 use crate::circuits::expr;
 use crate::circuits::lookup;
 use crate::circuits::lookup::{
     constraints::LookupConfiguration,
-    lookups::{LookupFeatures, LookupInfo, LookupPatterns},
+    lookups::{LookupArgument, LookupFeatures, LookupInfo, LookupPatterns},
 };
 use crate::circuits::polynomials::{
+    add_u64::AddU64,
     complete_add::CompleteAdd,
     endomul_scalar::EndomulScalar,
     endosclmul::EndosclMul,
@@ -18,6 +20,7 @@ use crate::circuits::polynomials::{
     poseidon::Poseidon,
     range_check::circuitgates::{RangeCheck0, RangeCheck1},
     rot,
+    select::Select,
     varbasemul::VarbaseMul,
     xor,
 };
@@ -29,8 +32,11 @@ use crate::circuits::{
     wires::COLUMNS,
 };
 use ark_ff::{FftField, PrimeField, SquareRootField, Zero};
+use o1_utils::{field_helpers::FieldHelpersError, FieldHelpers};
+use serde::{Deserialize, Serialize};
 
-/// Get the expresion of constraints.
+/// Get the expresion of constraints, with the constraints of the `custom_gates`
+/// (see [crate::circuits::custom_gate]).
 ///
 /// # Panics
 ///
@@ -38,6 +44,7 @@ use ark_ff::{FftField, PrimeField, SquareRootField, Zero};
 pub fn constraints_expr<F: PrimeField + SquareRootField>(
     feature_flags: Option<&FeatureFlags>,
     generic: bool,
+    custom_gates: &[CustomGate<F>],
 ) -> (Expr<ConstantExpr<F>>, Alphas<F>) {
     // register powers of alpha so that we don't reuse them across mutually inclusive constraints
     let mut powers_of_alpha = Alphas::<F>::default();
@@ -46,7 +53,10 @@ pub fn constraints_expr<F: PrimeField + SquareRootField>(
     // The gate type argument can just be the zero gate.
     powers_of_alpha.register(
         ArgumentType::Gate(GateType::Zero),
-        VarbaseMul::<F>::CONSTRAINTS,
+        std::cmp::max(
+            VarbaseMul::<F>::CONSTRAINTS,
+            custom_gate::max_constraints(custom_gates),
+        ),
     );
 
     let mut cache = expr::Cache::default();
@@ -153,6 +163,43 @@ pub fn constraints_expr<F: PrimeField + SquareRootField>(
         }
     }
 
+    {
+        let mut add_u64_expr = || AddU64::combined_constraints(&powers_of_alpha, &mut cache);
+        if let Some(feature_flags) = feature_flags {
+            if feature_flags.add_u64 {
+                expr += add_u64_expr();
+            }
+        } else {
+            expr += Expr::IfFeature(
+                FeatureFlag::AddU64,
+                Box::new(add_u64_expr()),
+                Box::new(Expr::zero()),
+            );
+        }
+    }
+
+    {
+        let mut select_expr = || Select::combined_constraints(&powers_of_alpha, &mut cache);
+        if let Some(feature_flags) = feature_flags {
+            if feature_flags.select {
+                expr += select_expr();
+            }
+        } else {
+            expr += Expr::IfFeature(
+                FeatureFlag::Select,
+                Box::new(select_expr()),
+                Box::new(Expr::zero()),
+            );
+        }
+    }
+
+    // the custom gates have no feature flag
+    for (selector, gate) in custom_gates.iter().enumerate() {
+        expr += gate
+            .argument(selector)
+            .combined_constraints(&powers_of_alpha, &mut cache);
+    }
+
     if generic {
         expr += generic::Generic::combined_constraints(&powers_of_alpha, &mut cache);
     }
@@ -186,9 +233,14 @@ pub fn constraints_expr<F: PrimeField + SquareRootField>(
                 lookup: true,
                 range_check: true,
                 foreign_field_mul: true,
+                vector_lookup: true,
+                range_check_u8: true,
+                range_check_u16: true,
+                add_u64: true,
             },
             uses_runtime_tables: true,
             joint_lookup_used: true,
+            argument: LookupArgument::Plookup,
         };
         let lookup_configuration = LookupConfiguration::new(LookupInfo::create(all_features));
         let constraints = lookup::constraints::constraints(&lookup_configuration, true);
@@ -220,9 +272,12 @@ pub fn constraints_expr<F: PrimeField + SquareRootField>(
 
     // Check that the feature flags correctly turn on or off the constraints generated by the given
     // flags.
+    // The logUp argument is not supported with feature flags, so there is nothing to compare with.
     if cfg!(feature = "check_feature_flags") {
-        if let Some(feature_flags) = feature_flags {
-            let (feature_flagged_expr, _) = constraints_expr(None, generic);
+        if let Some(feature_flags) =
+            feature_flags.filter(|flags| flags.lookup_features.argument == LookupArgument::Plookup)
+        {
+            let (feature_flagged_expr, _) = constraints_expr(None, generic, custom_gates);
             let feature_flagged_expr = feature_flagged_expr.apply_feature_flags(feature_flags);
             assert_eq!(expr, feature_flagged_expr);
         }
@@ -232,6 +287,107 @@ pub fn constraints_expr<F: PrimeField + SquareRootField>(
     (expr, powers_of_alpha)
 }
 
+/// Returns the exponents of the powers of alpha used by the constraints of each gate type with constraints,
+/// for the gates enabled by the `feature_flags` (or for all the gates if `None`).
+/// The gates are mutually exclusive, so that the constraints of each gate start at the first power of alpha
+/// of the gates (see [Alphas::exponents]).
+pub fn gate_alpha_exponents<F: PrimeField + SquareRootField>(
+    feature_flags: Option<&FeatureFlags>,
+) -> Vec<(GateType, std::ops::Range<u32>)> {
+    let enabled = |flag: fn(&FeatureFlags) -> bool| feature_flags.map_or(true, flag);
+    let (_, powers_of_alpha) = constraints_expr::<F>(feature_flags, true, &[]);
+    let gates = powers_of_alpha
+        .exponents()
+        .into_iter()
+        .find(|(argument_type, _)| *argument_type == ArgumentType::Gate(GateType::Zero))
+        .map(|(_, range)| range)
+        .expect("the gates always use powers of alpha");
+
+    [
+        (
+            generic::Generic::<F>::ARGUMENT_TYPE,
+            generic::Generic::<F>::CONSTRAINTS,
+            true,
+        ),
+        (
+            Poseidon::<F>::ARGUMENT_TYPE,
+            Poseidon::<F>::CONSTRAINTS,
+            true,
+        ),
+        (
+            CompleteAdd::<F>::ARGUMENT_TYPE,
+            CompleteAdd::<F>::CONSTRAINTS,
+            true,
+        ),
+        (
+            VarbaseMul::<F>::ARGUMENT_TYPE,
+            VarbaseMul::<F>::CONSTRAINTS,
+            true,
+        ),
+        (
+            EndosclMul::<F>::ARGUMENT_TYPE,
+            EndosclMul::<F>::CONSTRAINTS,
+            true,
+        ),
+        (
+            EndomulScalar::<F>::ARGUMENT_TYPE,
+            EndomulScalar::<F>::CONSTRAINTS,
+            true,
+        ),
+        (
+            RangeCheck0::<F>::ARGUMENT_TYPE,
+            RangeCheck0::<F>::CONSTRAINTS,
+            enabled(|flags| flags.range_check0),
+        ),
+        (
+            RangeCheck1::<F>::ARGUMENT_TYPE,
+            RangeCheck1::<F>::CONSTRAINTS,
+            enabled(|flags| flags.range_check1),
+        ),
+        (
+            ForeignFieldAdd::<F>::ARGUMENT_TYPE,
+            ForeignFieldAdd::<F>::CONSTRAINTS,
+            enabled(|flags| flags.foreign_field_add),
+        ),
+        (
+            ForeignFieldMul::<F>::ARGUMENT_TYPE,
+            ForeignFieldMul::<F>::CONSTRAINTS,
+            enabled(|flags| flags.foreign_field_mul),
+        ),
+        (
+            xor::Xor16::<F>::ARGUMENT_TYPE,
+            xor::Xor16::<F>::CONSTRAINTS,
+            enabled(|flags| flags.xor),
+        ),
+        (
+            rot::Rot64::<F>::ARGUMENT_TYPE,
+            rot::Rot64::<F>::CONSTRAINTS,
+            enabled(|flags| flags.rot),
+        ),
+        (
+            AddU64::<F>::ARGUMENT_TYPE,
+            AddU64::<F>::CONSTRAINTS,
+            enabled(|flags| flags.add_u64),
+        ),
+        (
+            Select::<F>::ARGUMENT_TYPE,
+            Select::<F>::CONSTRAINTS,
+            enabled(|flags| flags.select),
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, _, enabled)| *enabled)
+    .map(|(argument_type, constraints, _)| {
+        let gate_type = match argument_type {
+            ArgumentType::Gate(gate_type) => gate_type,
+            _ => unreachable!("the arguments of the gates are gate arguments"),
+        };
+        assert!(constraints <= gates.end - gates.start);
+        (gate_type, gates.start..gates.start + constraints)
+    })
+    .collect()
+}
+
 /// Adds the polynomials that are evaluated as part of the proof
 /// for the linearization to work.
 pub fn linearization_columns<F: FftField + SquareRootField>(
@@ -252,15 +408,22 @@ pub fn linearization_columns<F: FftField + SquareRootField>(
                 foreign_field_mul: true,
                 xor: true,
                 rot: true,
+                add_u64: true,
+                select: true,
                 lookup_features: LookupFeatures {
                     patterns: LookupPatterns {
                         xor: true,
                         lookup: true,
                         range_check: true,
                         foreign_field_mul: true,
+                        vector_lookup: true,
+                        range_check_u8: true,
+                        range_check_u16: true,
+                        add_u64: true,
                     },
                     joint_lookup_used: true,
                     uses_runtime_tables: true,
+                    argument: LookupArgument::Plookup,
                 },
             }
         }
@@ -284,7 +447,7 @@ pub fn linearization_columns<F: FftField + SquareRootField>(
 
     // the lookup polynomials
     if let Some(lookup_info) = lookup_info {
-        for i in 0..=lookup_info.max_per_row {
+        for i in 0..lookup_info.num_sorted() {
             h.insert(LookupSorted(i));
         }
         h.insert(LookupAggreg);
@@ -308,7 +471,7 @@ pub fn linearization_columns<F: FftField + SquareRootField>(
     h
 }
 
-/// Linearize the `expr`.
+/// Linearize the `expr`, with the constraints of the `custom_gates`.
 ///
 /// If the `feature_flags` argument is `None`, this will generate an expression using the
 /// `Expr::IfFeature` variant for each of the flags.
@@ -319,15 +482,81 @@ pub fn linearization_columns<F: FftField + SquareRootField>(
 pub fn expr_linearization<F: PrimeField + SquareRootField>(
     feature_flags: Option<&FeatureFlags>,
     generic: bool,
+    custom_gates: &[CustomGate<F>],
 ) -> (Linearization<Vec<PolishToken<F>>>, Alphas<F>) {
     let evaluated_cols = linearization_columns::<F>(feature_flags);
 
-    let (expr, powers_of_alpha) = constraints_expr(feature_flags, generic);
+    let (expr, powers_of_alpha) = constraints_expr(feature_flags, generic, custom_gates);
 
+    // the terms are compiled with their common subexpressions computed once
     let linearization = expr
         .linearize(evaluated_cols)
         .unwrap()
-        .map(|e| e.to_polish());
+        .map(|e| e.extract_common_subexpressions().to_polish());
 
     (linearization, powers_of_alpha)
 }
+
+/// A machine-readable export of the linearization evaluated by the verifier,
+/// for independent verifier implementations to be generated or audited against it.
+///
+/// The coefficients of the linearization are in [reverse Polish notation](PolishToken),
+/// and their field literals are the hexadecimal encodings of their little-endian bytes
+/// (see [o1_utils::FieldHelpers::to_hex]).
+/// The ranges of the powers of alpha of each argument are given by [Alphas::exponents].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LinearizationExport {
+    /// The ranges of the powers of alpha used by the arguments
+    pub powers_of_alpha: Vec<(ArgumentType, std::ops::Range<u32>)>,
+    /// The linearization, with hexadecimal literals
+    pub linearization: Linearization<Vec<PolishToken<String>>>,
+}
+
+impl LinearizationExport {
+    /// Exports a linearization and the powers of alpha it uses
+    pub fn new<F: PrimeField>(
+        linearization: &Linearization<Vec<PolishToken<F>>>,
+        powers_of_alpha: &Alphas<F>,
+    ) -> Self {
+        let export_tokens = |tokens: &Vec<PolishToken<F>>| {
+            tokens
+                .iter()
+                .map(|token| token.map_literal(|x| x.to_hex()))
+                .collect()
+        };
+        LinearizationExport {
+            powers_of_alpha: powers_of_alpha.exponents(),
+            linearization: linearization.map(export_tokens),
+        }
+    }
+
+    /// Imports the linearization back, parsing its literals as elements of `F`.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if a literal is not the hexadecimal encoding of an element of `F`.
+    pub fn linearization<F: PrimeField>(
+        &self,
+    ) -> Result<Linearization<Vec<PolishToken<F>>>, FieldHelpersError> {
+        let import_tokens = |tokens: &Vec<PolishToken<String>>| {
+            tokens
+                .iter()
+                .map(|token| match token {
+                    PolishToken::Literal(x) => F::from_hex(x).map(PolishToken::Literal),
+                    token => Ok(token.map_literal(|_| unreachable!())),
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let Linearization {
+            constant_term,
+            index_terms,
+        } = &self.linearization;
+        Ok(Linearization {
+            constant_term: import_tokens(constant_term)?,
+            index_terms: index_terms
+                .iter()
+                .map(|(col, tokens)| Ok((*col, import_tokens(tokens)?)))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
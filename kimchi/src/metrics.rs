@@ -0,0 +1,70 @@
+//! This module implements the timing of the phases of the prover, as [ProverMetrics] returned by
+//! [create_recursive_with_metrics](crate::proof::ProverProof::create_recursive_with_metrics).
+//!
+//! The prover only reads the clock when the metrics are requested,
+//! as [Instant] is not available on all targets (such as `wasm32-unknown-unknown`).
+
+use std::time::{Duration, Instant};
+
+/// A phase of the prover, in their order in the proof creation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ProverPhase {
+    /// The padding and the commitment of the witness and the public input, and their interpolation
+    Witness,
+    /// The runtime tables, the sorted columns and the aggregation of the lookup argument
+    Lookup,
+    /// The aggregation polynomial of the permutation argument
+    Permutation,
+    /// The computation of the quotient polynomial and its commitment
+    Quotient,
+    /// The evaluations of the polynomials and of the linearization at the evaluation points
+    Evaluations,
+    /// The opening proof of the inner product argument
+    Opening,
+}
+
+/// The time spent by the prover in each of its phases
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProverMetrics {
+    /// The phases of the proof, with their durations, in the order they ran
+    pub phases: Vec<(ProverPhase, Duration)>,
+}
+
+impl ProverMetrics {
+    /// The time spent in a phase, or zero if it did not run
+    pub fn duration(&self, phase: ProverPhase) -> Duration {
+        self.phases
+            .iter()
+            .filter(|(p, _)| *p == phase)
+            .map(|(_, duration)| *duration)
+            .sum()
+    }
+
+    /// The time spent in all the phases
+    pub fn total(&self) -> Duration {
+        self.phases.iter().map(|(_, duration)| *duration).sum()
+    }
+}
+
+// Records the end of the phases of the prover in the metrics, if requested
+pub(crate) struct PhaseTimer<'a> {
+    metrics: Option<(&'a mut ProverMetrics, Instant)>,
+}
+
+impl<'a> PhaseTimer<'a> {
+    // Starts the first phase
+    pub(crate) fn start(metrics: Option<&'a mut ProverMetrics>) -> Self {
+        PhaseTimer {
+            metrics: metrics.map(|metrics| (metrics, Instant::now())),
+        }
+    }
+
+    // Records the end of a phase, and starts the next one
+    pub(crate) fn end(&mut self, phase: ProverPhase) {
+        if let Some((metrics, start)) = &mut self.metrics {
+            let now = Instant::now();
+            metrics.phases.push((phase, now - *start));
+            *start = now;
+        }
+    }
+}
@@ -0,0 +1,359 @@
+//! This module implements a mock prover, which checks that a witness satisfies a constraint system
+//! without creating a proof.
+//!
+//! The [`MockProver`] evaluates the constraints of every gate, the copy constraints
+//! and the lookups directly on the rows of the witness, and reports all the failures with their rows.
+//! It requires no SRS and computes no commitment, which makes it much faster than
+//! creating and verifying a proof while a circuit is being developed.
+//!
+//! As with the prover, the constraints of the gates are checked on all the rows but the
+//! zero-knowledge rows, and the lookups are checked against the fixed tables of the
+//! constraint system and the runtime tables given to the mock prover.
+//!
+//! Note that the mock prover does not check the degree of the constraints,
+//! so a witness accepted by the mock prover can still fail to be proven if the
+//! constraint system is misconfigured.
+//! The verifications of the witness registered with the custom gates are not run either,
+//! only their constraints are checked (see [crate::circuits::custom_gate]).
+//!
+//! ```
+//! use kimchi::{
+//!     circuits::{
+//!         constraints::ConstraintSystem,
+//!         gate::{CircuitGate, GateType},
+//!         polynomials::generic::GenericGateSpec,
+//!         wires::Wire,
+//!     },
+//!     mock_prover::{MockProver, MockProverFailure},
+//! };
+//! use mina_curves::pasta::{Fp, Vesta};
+//! use std::array;
+//!
+//! // a public input, and a gate adding it to itself
+//! let gates = vec![
+//!     CircuitGate::create_generic_gadget(Wire::for_row(0), GenericGateSpec::Pub, None),
+//!     CircuitGate::create_generic_gadget(
+//!         Wire::for_row(1),
+//!         GenericGateSpec::Add {
+//!             left_coeff: None,
+//!             right_coeff: None,
+//!             output_coeff: None,
+//!         },
+//!         None,
+//!     ),
+//! ];
+//! let cs = ConstraintSystem::create(gates).public(1).build().unwrap();
+//!
+//! let mut witness: [Vec<Fp>; 15] = array::from_fn(|_| vec![Fp::from(0u32); 2]);
+//! witness[0][0] = Fp::from(3u32);
+//! witness[0][1] = Fp::from(3u32);
+//! witness[1][1] = Fp::from(3u32);
+//! witness[2][1] = Fp::from(6u32);
+//! let public = [Fp::from(3u32)];
+//! assert_eq!(MockProver::<Vesta>::new(&cs, &witness, &public).verify(), Ok(()));
+//!
+//! // another sum breaks the constraint of the second row
+//! witness[2][1] = Fp::from(7u32);
+//! assert_eq!(
+//!     MockProver::<Vesta>::new(&cs, &witness, &public).verify(),
+//!     Err(vec![MockProverFailure::Constraint {
+//!         row: 1,
+//!         typ: GateType::Generic,
+//!         index: 1,
+//!     }])
+//! );
+//! ```
+
+use crate::{
+    circuits::{
+        argument::{ArgumentEnv, ArgumentWitness},
+        constraints::ConstraintSystem,
+        expr::Constants,
+        gate::{CircuitGate, CurrOrNext, GateType},
+        lookup::{
+            index::LookupConstraintSystem, lookups::LocalPosition, runtime_tables::RuntimeTable,
+        },
+        polynomials::permutation::ZK_ROWS,
+        wires::{Wire, COLUMNS, PERMUTS},
+    },
+    curve::KimchiCurve,
+};
+use ark_ff::{One, Zero};
+use std::{array, collections::HashSet};
+use thiserror::Error;
+
+/// A check of the mock prover that the witness does not satisfy
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockProverFailure {
+    #[error("the witness columns are not all the same size")]
+    WitnessCsInconsistent,
+
+    #[error("the witness has {0} rows, which leaves no room for the zero-knowledge rows")]
+    NoRoomForZkInWitness(usize),
+
+    #[error("the public input is of an unexpected size (expected {expected}, got {got})")]
+    PublicInputLength { expected: usize, got: usize },
+
+    #[error(
+        "the runtime tables provided did not match the configuration of the constraint system"
+    )]
+    RuntimeTablesInconsistent,
+
+    #[error("the cell {src:?} is not equal to the cell {dst:?} it is wired to")]
+    CopyConstraint { src: Wire, dst: Wire },
+
+    #[error("the constraint {index} of the {typ:?} gate of row {row} is not satisfied")]
+    Constraint {
+        row: usize,
+        typ: GateType,
+        index: usize,
+    },
+
+    #[error("the lookup {index} of row {row} is not in the lookup tables")]
+    Lookup { row: usize, index: usize },
+}
+
+/// A prover checking a witness against a constraint system, without creating a proof
+pub struct MockProver<'a, G: KimchiCurve> {
+    cs: &'a ConstraintSystem<G::ScalarField>,
+    witness: &'a [Vec<G::ScalarField>; COLUMNS],
+    public: &'a [G::ScalarField],
+    runtime_tables: &'a [RuntimeTable<G::ScalarField>],
+}
+
+impl<'a, G: KimchiCurve> MockProver<'a, G> {
+    /// Creates a mock prover of a witness and its public input, without runtime tables
+    pub fn new(
+        cs: &'a ConstraintSystem<G::ScalarField>,
+        witness: &'a [Vec<G::ScalarField>; COLUMNS],
+        public: &'a [G::ScalarField],
+    ) -> Self {
+        MockProver {
+            cs,
+            witness,
+            public,
+            runtime_tables: &[],
+        }
+    }
+
+    /// Sets the runtime tables of the lookups, in the order of the configuration of the constraint system
+    #[must_use]
+    pub fn runtime_tables(mut self, runtime_tables: &'a [RuntimeTable<G::ScalarField>]) -> Self {
+        self.runtime_tables = runtime_tables;
+        self
+    }
+
+    /// Checks the witness, and returns all the failures found.
+    /// If the witness is malformed (see the first variants of [`MockProverFailure`]),
+    /// only that failure is returned.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the witness does not satisfy a check of the constraint system.
+    pub fn verify(&self) -> Result<(), Vec<MockProverFailure>> {
+        let witness = self.pad_witness().map_err(|failure| vec![failure])?;
+        if self.public.len() != self.cs.public {
+            return Err(vec![MockProverFailure::PublicInputLength {
+                expected: self.cs.public,
+                got: self.public.len(),
+            }]);
+        }
+        let table = match &self.cs.lookup_constraint_system {
+            Some(lcs) => Some(self.lookup_table(lcs).map_err(|failure| vec![failure])?),
+            None => None,
+        };
+
+        let mut failures = self.check_copies(&witness);
+        failures.extend(self.check_gates(&witness));
+        if let (Some(lcs), Some(table)) = (&self.cs.lookup_constraint_system, &table) {
+            failures.extend(Self::check_lookups(lcs, &self.cs.gates, &witness, table));
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures)
+        }
+    }
+
+    /// Panics with the failures of the checks of the witness, if any
+    pub fn assert_satisfied(&self) {
+        if let Err(failures) = self.verify() {
+            let failures: Vec<_> = failures.iter().map(ToString::to_string).collect();
+            panic!(
+                "the witness does not satisfy the constraint system:\n{}",
+                failures.join("\n")
+            );
+        }
+    }
+
+    // The rows on which the gates and the lookups are checked, the others are zero-knowledge rows
+    fn rows(&self) -> usize {
+        self.cs.domain.d1.size() - ZK_ROWS as usize
+    }
+
+    // Pads the witness with zeros to the size of the domain, as the prover does
+    fn pad_witness(&self) -> Result<[Vec<G::ScalarField>; COLUMNS], MockProverFailure> {
+        let length = self.witness[0].len();
+        if self.witness.iter().any(|w| w.len() != length) {
+            return Err(MockProverFailure::WitnessCsInconsistent);
+        }
+        if length > self.rows() {
+            return Err(MockProverFailure::NoRoomForZkInWitness(length));
+        }
+        Ok(array::from_fn(|i| {
+            let mut w = self.witness[i].clone();
+            w.resize(self.cs.domain.d1.size(), G::ScalarField::zero());
+            w
+        }))
+    }
+
+    fn check_copies(&self, witness: &[Vec<G::ScalarField>; COLUMNS]) -> Vec<MockProverFailure> {
+        let mut failures = vec![];
+        for (row, gate) in self.cs.gates.iter().enumerate() {
+            for col in 0..PERMUTS {
+                let dst = gate.wires[col];
+                if witness[col][row] != witness[dst.col][dst.row] {
+                    failures.push(MockProverFailure::CopyConstraint {
+                        src: Wire { row, col },
+                        dst,
+                    });
+                }
+            }
+        }
+        failures
+    }
+
+    fn check_gates(&self, witness: &[Vec<G::ScalarField>; COLUMNS]) -> Vec<MockProverFailure> {
+        // the challenges are one, as in the verification of the witness by the gates
+        let constants = || Constants {
+            alpha: G::ScalarField::one(),
+            beta: G::ScalarField::one(),
+            gamma: G::ScalarField::one(),
+            joint_combiner: Some(G::ScalarField::one()),
+            endo_coefficient: self.cs.endo,
+            mds: &G::sponge_params().mds,
+        };
+
+        let mut failures = vec![];
+        for (row, gate) in self.cs.gates.iter().enumerate().take(self.rows()) {
+            let argument_witness = ArgumentWitness {
+                curr: array::from_fn(|col| witness[col][row]),
+                next: array::from_fn(|col| witness[col][row + 1]),
+            };
+            let env = ArgumentEnv::create(argument_witness, gate.coeffs.clone(), constants());
+            let mut results = match self.cs.custom_gate(row) {
+                // a constraint which cannot be evaluated is not satisfied
+                Some(custom_gate) => custom_gate
+                    .evaluate_constraints(row, witness, self.cs, &constants())
+                    .unwrap_or_else(|_| vec![G::ScalarField::one(); custom_gate.constraints.len()]),
+                None => gate.evaluate_constraints(&env),
+            };
+            // the public input is added to the first generic constraint of the public rows
+            if let (Some(result), Some(public)) = (results.first_mut(), self.public.get(row)) {
+                *result -= public;
+            }
+            for (i, result) in results.iter().enumerate() {
+                if !result.is_zero() {
+                    failures.push(MockProverFailure::Constraint {
+                        row,
+                        typ: gate.typ,
+                        index: i + 1,
+                    });
+                }
+            }
+        }
+        failures
+    }
+
+    // The rows of the lookup tables, with the runtime tables, as table ids and entries
+    fn lookup_table(
+        &self,
+        lcs: &LookupConstraintSystem<G::ScalarField>,
+    ) -> Result<HashSet<(G::ScalarField, Vec<G::ScalarField>)>, MockProverFailure> {
+        let d1_size = self.cs.domain.d1.size();
+        let mut runtime = vec![G::ScalarField::zero(); d1_size];
+        match &lcs.runtime_tables {
+            Some(cfg_runtime_tables) => {
+                let expected_runtime: Vec<_> = cfg_runtime_tables
+                    .iter()
+                    .map(|rt| (rt.id, rt.len))
+                    .collect();
+                let runtime_tables: Vec<_> = self
+                    .runtime_tables
+                    .iter()
+                    .map(|rt| (rt.id, rt.data.len()))
+                    .collect();
+                if expected_runtime != runtime_tables {
+                    return Err(MockProverFailure::RuntimeTablesInconsistent);
+                }
+                let mut offset = lcs
+                    .runtime_table_offset
+                    .expect("runtime configuration missing offset");
+                for rt in self.runtime_tables {
+                    runtime[offset..offset + rt.data.len()].copy_from_slice(&rt.data);
+                    offset += rt.data.len();
+                }
+            }
+            None if !self.runtime_tables.is_empty() => {
+                return Err(MockProverFailure::RuntimeTablesInconsistent);
+            }
+            None => (),
+        }
+
+        // the tables are evaluated over d8, where the row i of d1 is the point 8i
+        Ok((0..self.rows())
+            .map(|row| {
+                let table_id = lcs
+                    .table_ids8
+                    .as_ref()
+                    .map_or_else(G::ScalarField::zero, |ids| ids.evals[8 * row]);
+                let entry = lcs
+                    .lookup_table8
+                    .iter()
+                    .enumerate()
+                    .map(|(col, table)| {
+                        let value = table.evals[8 * row];
+                        if col == 1 {
+                            value + runtime[row]
+                        } else {
+                            value
+                        }
+                    })
+                    .collect();
+                (table_id, entry)
+            })
+            .collect())
+    }
+
+    fn check_lookups(
+        lcs: &LookupConstraintSystem<G::ScalarField>,
+        gates: &[CircuitGate<G::ScalarField>],
+        witness: &[Vec<G::ScalarField>; COLUMNS],
+        table: &HashSet<(G::ScalarField, Vec<G::ScalarField>)>,
+    ) -> Vec<MockProverFailure> {
+        let width = lcs.lookup_table8.len();
+        let rows = witness[0].len() - ZK_ROWS as usize;
+
+        let mut failures = vec![];
+        let by_row = lcs.configuration.lookup_info.by_row(gates);
+        for (row, lookups) in by_row.iter().enumerate().take(rows) {
+            let eval = |pos: LocalPosition| {
+                let row = match pos.row {
+                    CurrOrNext::Curr => row,
+                    CurrOrNext::Next => row + 1,
+                };
+                witness[pos.column][row]
+            };
+            for (index, lookup) in lookups.iter().enumerate() {
+                let value = lookup.reduce(&eval);
+                let mut entry = value.entry;
+                entry.resize(width, G::ScalarField::zero());
+                if !table.contains(&(value.table_id, entry)) {
+                    failures.push(MockProverFailure::Lookup { row, index });
+                }
+            }
+        }
+        failures
+    }
+}
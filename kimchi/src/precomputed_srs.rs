@@ -10,7 +10,7 @@ use std::{fs::File, io::BufReader};
 
 use poly_commitment::srs::SRS;
 
-use crate::curve::KimchiCurve;
+use crate::{compression, curve::KimchiCurve};
 
 /// The size of the SRS that we serialize.
 pub const SERIALIZED_SRS_SIZE: u32 = 16;
@@ -23,7 +23,7 @@ fn get_srs_path<G: KimchiCurve>() -> PathBuf {
         .join(format!("{}.srs", G::NAME))
 }
 
-/// Obtains an SRS for a specific curve from disk.
+/// Obtains an SRS for a specific curve from disk, which may be compressed (see [crate::compression]).
 /// Panics if the SRS does not exists.
 pub fn get_srs<G>() -> SRS<G>
 where
@@ -33,7 +33,7 @@ where
     let file =
         File::open(srs_path.clone()).unwrap_or_else(|_| panic!("missing SRS file: {srs_path:?}"));
     let reader = BufReader::new(file);
-    rmp_serde::from_read(reader).unwrap()
+    compression::read(reader).unwrap()
 }
 
 #[cfg(test)]
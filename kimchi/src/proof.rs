@@ -1,9 +1,12 @@
 //! This module implements the data structures of a proof.
 
-use crate::circuits::{
-    expr::Column,
-    gate::GateType,
-    wires::{COLUMNS, PERMUTS},
+use crate::{
+    circuits::{
+        expr::Column,
+        gate::GateType,
+        wires::{COLUMNS, PERMUTS},
+    },
+    version::ArithmetizationVersion,
 };
 use ark_ec::AffineCurve;
 use ark_ff::{FftField, One, Zero};
@@ -42,7 +45,7 @@ pub struct PointEvaluations<Evals> {
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LookupEvaluations<Evals> {
-    /// sorted lookup table polynomial
+    /// sorted lookup table polynomial (the multiplicities, with the logUp argument)
     pub sorted: Vec<Evals>,
     /// lookup aggregation polynomial
     pub aggreg: Evals,
@@ -83,7 +86,8 @@ pub struct ProofEvaluations<Evals> {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(bound = "G: ark_serialize::CanonicalDeserialize + ark_serialize::CanonicalSerialize")]
 pub struct LookupCommitments<G: AffineCurve> {
-    /// Commitments to the sorted lookup table polynomial (may have chunks)
+    /// Commitments to the sorted lookup table polynomial (may have chunks),
+    /// or to the multiplicities of the table entries with the logUp argument
     pub sorted: Vec<PolyComm<G>>,
     /// Commitment to the lookup aggregation polynomial
     pub aggreg: PolyComm<G>,
@@ -126,6 +130,15 @@ pub struct ProverProof<G: AffineCurve> {
 
     /// The challenges underlying the optional polynomials folded into the proof
     pub prev_challenges: Vec<RecursionChallenge<G>>,
+
+    /// The digest of the previous proof of a [session](crate::session), bound to the transcript of this proof
+    #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
+    #[serde(default)]
+    pub prev_proof_digest: Option<G::BaseField>,
+
+    /// The version of the arithmetization of the proof
+    #[serde(default)]
+    pub arithmetization_version: ArithmetizationVersion,
 }
 
 /// A struct to store the challenges inside a `ProverProof`
@@ -407,6 +420,7 @@ impl<F> ProofEvaluations<F> {
             Column::Index(GateType::Generic) => Some(&self.generic_selector),
             Column::Index(GateType::Poseidon) => Some(&self.poseidon_selector),
             Column::Index(_) => None,
+            Column::Custom(_) => None,
             Column::Coefficient(i) => Some(&self.coefficients[i]),
             Column::Permutation(i) => Some(&self.s[i]),
         }
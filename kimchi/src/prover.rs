@@ -1,12 +1,17 @@
 //! This module implements prover's zk-proof primitive.
 
 use crate::{
+    blinding,
     circuits::{
         argument::{Argument, ArgumentType},
         expr::{self, l0_1, Constants, Environment, LookupEnvironment},
         gate::GateType,
-        lookup::{self, runtime_tables::RuntimeTable, tables::combine_table_entry},
+        lookup::{
+            self, lookups::LookupArgument, runtime_tables::RuntimeTable,
+            tables::combine_table_entry,
+        },
         polynomials::{
+            add_u64::AddU64,
             complete_add::CompleteAdd,
             endomul_scalar::EndomulScalar,
             endosclmul::EndosclMul,
@@ -17,20 +22,24 @@ use crate::{
             poseidon::Poseidon,
             range_check::circuitgates::{RangeCheck0, RangeCheck1},
             rot::Rot64,
+            select::Select,
             varbasemul::VarbaseMul,
             xor::Xor16,
         },
         wires::{COLUMNS, PERMUTS},
+        witness::WitnessSegments,
     },
     curve::KimchiCurve,
     error::ProverError,
     lagrange_basis_evaluations::LagrangeBasisEvaluations,
+    metrics::{PhaseTimer, ProverMetrics, ProverPhase},
     plonk_sponge::FrSponge,
     proof::{
         LookupCommitments, LookupEvaluations, PointEvaluations, ProofEvaluations,
         ProverCommitments, ProverProof, RecursionChallenge,
     },
     prover_index::ProverIndex,
+    version::ARITHMETIZATION_VERSION,
 };
 use ark_ec::ProjectiveCurve;
 use ark_ff::{FftField, Field, One, PrimeField, UniformRand, Zero};
@@ -47,6 +56,7 @@ use poly_commitment::{
     },
     evaluation_proof::DensePolynomialOrEvaluations,
 };
+use rand::{CryptoRng, RngCore};
 use rayon::prelude::*;
 use std::array;
 use std::collections::HashMap;
@@ -120,6 +130,9 @@ where
 {
     /// This function constructs prover's zk-proof from the witness & the `ProverIndex` against SRS instance
     ///
+    /// The randomness used for blinding is drawn from the operating system (see [`rand::rngs::OsRng`]).
+    /// Use [`ProverProof::create_with_rng`] to supply it.
+    ///
     /// # Errors
     ///
     /// Will give error if `create_recursive` process fails.
@@ -142,8 +155,40 @@ where
         )
     }
 
+    /// This function constructs prover's zk-proof from the witness & the `ProverIndex` against SRS instance,
+    /// drawing all blinding factors and zero-knowledge rows from the given `rng`
+    /// (see [`ProverProof::create_recursive_with_rng`]).
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `create_recursive_with_rng` process fails.
+    pub fn create_with_rng<
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+        RNG: RngCore + CryptoRng,
+    >(
+        groupmap: &G::Map,
+        witness: [Vec<G::ScalarField>; COLUMNS],
+        runtime_tables: &[RuntimeTable<G::ScalarField>],
+        index: &ProverIndex<G>,
+        rng: &mut RNG,
+    ) -> Result<Self> {
+        Self::create_recursive_with_rng::<EFqSponge, EFrSponge, RNG>(
+            groupmap,
+            witness,
+            runtime_tables,
+            index,
+            Vec::new(),
+            None,
+            rng,
+        )
+    }
+
     /// This function constructs prover's recursive zk-proof from the witness & the `ProverIndex` against SRS instance
     ///
+    /// The randomness used for blinding is drawn from the operating system (see [`rand::rngs::OsRng`]).
+    /// Use [`ProverProof::create_recursive_with_rng`] on targets without an OS entropy source.
+    ///
     /// # Errors
     ///
     /// Will give error if inputs(like `lookup_context.joint_lookup_table_d8`) are None.
@@ -154,6 +199,176 @@ where
     pub fn create_recursive<
         EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
         EFrSponge: FrSponge<G::ScalarField>,
+    >(
+        group_map: &G::Map,
+        witness: [Vec<G::ScalarField>; COLUMNS],
+        runtime_tables: &[RuntimeTable<G::ScalarField>],
+        index: &ProverIndex<G>,
+        prev_challenges: Vec<RecursionChallenge<G>>,
+        blinders: Option<[Option<PolyComm<G::ScalarField>>; COLUMNS]>,
+    ) -> Result<Self> {
+        Self::create_recursive_with_rng::<EFqSponge, EFrSponge, _>(
+            group_map,
+            witness,
+            runtime_tables,
+            index,
+            prev_challenges,
+            blinders,
+            &mut rand::rngs::OsRng,
+        )
+    }
+
+    /// This function constructs prover's recursive zk-proof from the witness & the `ProverIndex` against SRS instance,
+    /// drawing all blinding factors and zero-knowledge rows from the given `rng`.
+    ///
+    /// Passing a seeded RNG makes proof creation fully deterministic,
+    /// which allows the prover to run on targets such as `wasm32-unknown-unknown`.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if inputs(like `lookup_context.joint_lookup_table_d8`) are None.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `lookup_context.joint_lookup_table_d8` is None.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_recursive_with_rng<
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+        RNG: RngCore + CryptoRng,
+    >(
+        group_map: &G::Map,
+        witness: [Vec<G::ScalarField>; COLUMNS],
+        runtime_tables: &[RuntimeTable<G::ScalarField>],
+        index: &ProverIndex<G>,
+        prev_challenges: Vec<RecursionChallenge<G>>,
+        blinders: Option<[Option<PolyComm<G::ScalarField>>; COLUMNS]>,
+        rng: &mut RNG,
+    ) -> Result<Self> {
+        Self::create_internal::<EFqSponge, EFrSponge, RNG>(
+            group_map,
+            witness,
+            runtime_tables,
+            index,
+            prev_challenges,
+            blinders,
+            None,
+            None,
+            None,
+            rng,
+        )
+    }
+
+    /// This function constructs prover's recursive zk-proof as [`ProverProof::create_recursive_with_rng`],
+    /// and returns the time spent in each phase of the prover (see [crate::metrics]).
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `create_recursive_with_rng` process fails.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_recursive_with_metrics<
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+        RNG: RngCore + CryptoRng,
+    >(
+        group_map: &G::Map,
+        witness: [Vec<G::ScalarField>; COLUMNS],
+        runtime_tables: &[RuntimeTable<G::ScalarField>],
+        index: &ProverIndex<G>,
+        prev_challenges: Vec<RecursionChallenge<G>>,
+        blinders: Option<[Option<PolyComm<G::ScalarField>>; COLUMNS]>,
+        rng: &mut RNG,
+    ) -> Result<(Self, ProverMetrics)> {
+        let mut metrics = ProverMetrics::default();
+        let proof = Self::create_internal::<EFqSponge, EFrSponge, RNG>(
+            group_map,
+            witness,
+            runtime_tables,
+            index,
+            prev_challenges,
+            blinders,
+            None,
+            None,
+            Some(&mut metrics),
+            rng,
+        )?;
+        Ok((proof, metrics))
+    }
+
+    /// This function constructs prover's recursive zk-proof as [`ProverProof::create_recursive_with_rng`],
+    /// from a witness computed in segments of rows (see [WitnessSegments]).
+    /// The columns of the witness are committed `segment_rows` rows at a time
+    /// (see [ProverIndex::commit_witness_segments]), and then computed in full
+    /// for their interpolation and the other steps of the proof.
+    /// Only the commitment is segmented: the proof holds the whole witness
+    /// and its extended evaluations in memory, as [`ProverProof::create_recursive_with_rng`] does.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the witness leaves no room for the zero-knowledge rows in the domain,
+    /// or if `create_recursive_with_rng` process fails.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `segment_rows` is zero.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_recursive_with_segments<
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+        RNG: RngCore + CryptoRng,
+    >(
+        group_map: &G::Map,
+        witness: &impl WitnessSegments<G::ScalarField>,
+        segment_rows: usize,
+        runtime_tables: &[RuntimeTable<G::ScalarField>],
+        index: &ProverIndex<G>,
+        prev_challenges: Vec<RecursionChallenge<G>>,
+        blinders: Option<[Option<PolyComm<G::ScalarField>>; COLUMNS]>,
+        rng: &mut RNG,
+    ) -> Result<Self> {
+        let mut witness_comm = index.commit_witness_segments(witness, segment_rows)?;
+        let mut witness = witness.witness();
+
+        // the rows of the public outputs take the values of their output cells,
+        // whose differences with the values of the segments are added to the commitment
+        let outputs = index.cs.public - index.cs.public_outputs..index.cs.public;
+        let values = witness[0][outputs.clone()].to_vec();
+        index.cs.fill_public_outputs(&mut witness);
+        let differences: Vec<_> = witness[0][outputs.clone()]
+            .iter()
+            .zip(values)
+            .map(|(output, value)| *output - value)
+            .collect();
+        witness_comm[0] = &witness_comm[0]
+            + &index.srs.commit_evaluations_segment(
+                index.cs.domain.d1,
+                outputs.start,
+                &differences,
+            );
+
+        Self::create_internal::<EFqSponge, EFrSponge, RNG>(
+            group_map,
+            witness,
+            runtime_tables,
+            index,
+            prev_challenges,
+            blinders,
+            Some(witness_comm),
+            None,
+            None,
+            rng,
+        )
+    }
+
+    /// This function constructs prover's recursive zk-proof, committing to the witness from the
+    /// non-hiding commitments of its columns without the zero-knowledge rows `witness_comm` if given,
+    /// binding its transcript to `prev_proof_digest` when the proof is part of a [session](crate::session),
+    /// and timing its phases in `metrics` if any
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn create_internal<
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+        RNG: RngCore + CryptoRng,
     >(
         group_map: &G::Map,
         mut witness: [Vec<G::ScalarField>; COLUMNS],
@@ -161,7 +376,13 @@ where
         index: &ProverIndex<G>,
         prev_challenges: Vec<RecursionChallenge<G>>,
         blinders: Option<[Option<PolyComm<G::ScalarField>>; COLUMNS]>,
+        witness_comm: Option<[PolyComm<G>; COLUMNS]>,
+        prev_proof_digest: Option<G::BaseField>,
+        metrics: Option<&mut ProverMetrics>,
+        rng: &mut RNG,
     ) -> Result<Self> {
+        let mut timer = PhaseTimer::start(metrics);
+
         // make sure that the SRS is not smaller than the domain size
         let d1_size = index.cs.domain.d1.size();
         if index.srs.max_degree() < d1_size {
@@ -170,8 +391,8 @@ where
 
         let (_, endo_r) = G::endos();
 
-        // TODO: rng should be passed as arg
-        let rng = &mut rand::rngs::OsRng;
+        // the rows of the public outputs take the values of their output cells
+        index.cs.fill_public_outputs(&mut witness);
 
         // Verify the circuit satisfiability by the computed witness (baring plookup constraints)
         // Catch mistakes before proof generation.
@@ -217,6 +438,11 @@ where
         let verifier_index_digest = index.verifier_index_digest::<EFqSponge>();
         fq_sponge.absorb_fq(&[verifier_index_digest]);
 
+        //~ 1. If the proof is part of a session, absorb the digest of the previous proof.
+        if let Some(digest) = prev_proof_digest {
+            fq_sponge.absorb_fq(&[digest]);
+        }
+
         //~ 1. Absorb the commitments of the previous challenges with the Fq-sponge.
         for RecursionChallenge { comm, .. } in &prev_challenges {
             absorb_commitment(&mut fq_sponge, comm)
@@ -226,11 +452,12 @@ where
         //~    the polynomial that evaluates to $-p_i$ for the first `public_input_size` values of the domain,
         //~    and $0$ for the rest.
         let public = witness[0][0..index.cs.public].to_vec();
-        let public_poly = -Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
-            public,
-            index.cs.domain.d1,
-        )
-        .interpolate();
+        let public_poly = -index.interpolate(
+            Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
+                public,
+                index.cs.domain.d1,
+            ),
+        );
 
         //~ 1. Commit (non-hiding) to the negated public input polynomial.
         let public_comm = index.srs.commit_non_hiding(&public_poly, None);
@@ -256,31 +483,41 @@ where
         //~
         //~    Note: since the witness is in evaluation form,
         //~    we can use the `commit_evaluation` optimization.
+        // if the witness is given in segments, its columns are already committed without
+        // the zero-knowledge rows, and only the commitments of these rows are added
         let mut w_comm = vec![];
         for col in 0..COLUMNS {
-            // witness coeff -> witness eval
-            let witness_eval =
-                Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
-                    witness[col].clone(),
-                    index.cs.domain.d1,
-                );
+            let witness_com = match &witness_comm {
+                Some(comm) => {
+                    let zk_start = d1_size - ZK_ROWS as usize;
+                    &comm[col]
+                        + &index.srs.commit_evaluations_segment(
+                            index.cs.domain.d1,
+                            zk_start,
+                            &witness[col][zk_start..],
+                        )
+                }
+                None => {
+                    // witness coeff -> witness eval
+                    let witness_eval =
+                        Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
+                            witness[col].clone(),
+                            index.cs.domain.d1,
+                        );
+                    index
+                        .srs
+                        .commit_evaluations_non_hiding(index.cs.domain.d1, &witness_eval)
+                }
+            };
 
             let com = match blinders.as_ref().and_then(|b| b[col].as_ref()) {
                 // no blinders: blind the witness
-                None => index
-                    .srs
-                    .commit_evaluations(index.cs.domain.d1, &witness_eval, rng),
+                None => index.srs.mask(witness_com, rng),
                 // blinders: blind the witness with them
-                Some(blinder) => {
-                    // TODO: make this a function rather no? mask_with_custom()
-                    let witness_com = index
-                        .srs
-                        .commit_evaluations_non_hiding(index.cs.domain.d1, &witness_eval);
-                    index
-                        .srs
-                        .mask_custom(witness_com, blinder)
-                        .map_err(ProverError::WrongBlinders)?
-                }
+                Some(blinder) => index
+                    .srs
+                    .mask_custom(witness_com, blinder)
+                    .map_err(ProverError::WrongBlinders)?,
             };
 
             w_comm.push(com);
@@ -300,13 +537,14 @@ where
         //~    form so we can take advantage of the sparsity of the evaluations (i.e., there are many
         //~    0 entries and entries that have less-than-full-size field elemnts.)
         let witness_poly: [DensePolynomial<G::ScalarField>; COLUMNS] = array::from_fn(|i| {
-            Evaluations::<G::ScalarField, D<G::ScalarField>>::from_vec_and_domain(
+            index.interpolate(Evaluations::from_vec_and_domain(
                 witness[i].clone(),
                 index.cs.domain.d1,
-            )
-            .interpolate()
+            ))
         });
 
+        timer.end(ProverPhase::Witness);
+
         let mut lookup_context = LookupContext::default();
 
         //~ 1. If using lookup:
@@ -342,23 +580,32 @@ where
                     }
 
                     // zero-knowledge
-                    for e in evals.iter_mut().rev().take(ZK_ROWS as usize) {
-                        *e = <G::ScalarField as UniformRand>::rand(rng);
+                    if index.blinding.lookup {
+                        for e in evals.iter_mut().rev().take(ZK_ROWS as usize) {
+                            *e = <G::ScalarField as UniformRand>::rand(rng);
+                        }
                     }
 
                     // get coeff and evaluation form
-                    let runtime_table_contribution =
-                        Evaluations::from_vec_and_domain(evals, index.cs.domain.d1).interpolate();
+                    let runtime_table_contribution = index
+                        .interpolate(Evaluations::from_vec_and_domain(evals, index.cs.domain.d1));
 
                     let runtime_table_contribution_d8 =
-                        runtime_table_contribution.evaluate_over_domain_by_ref(index.cs.domain.d8);
+                        index.evaluate_over_domain(&runtime_table_contribution, index.cs.domain.d8);
 
                     (runtime_table_contribution, runtime_table_contribution_d8)
                 };
 
                 // commit the runtime polynomial
                 // (and save it to the proof)
-                let runtime_table_comm = index.srs.commit(&runtime_table_contribution, None, rng);
+                let runtime_table_comm = blinding::mask(
+                    &index.srs,
+                    index
+                        .srs
+                        .commit_non_hiding(&runtime_table_contribution, None),
+                    index.blinding.lookup,
+                    rng,
+                );
 
                 // absorb the commitment
                 absorb_commitment(&mut fq_sponge, &runtime_table_comm.commitment);
@@ -461,34 +708,57 @@ where
             };
 
             // TODO: This interpolation is avoidable.
-            let joint_lookup_table = joint_lookup_table_d8.interpolate_by_ref();
+            let joint_lookup_table = index.interpolate(joint_lookup_table_d8.clone());
 
             //~~ * Compute the sorted evaluations.
+            //~~   With the logUp argument, compute instead a single column with the multiplicity
+            //~~   of each entry of the table, which takes the place of the sorted evaluations below.
             // TODO: Once we switch to committing using lagrange commitments,
             // `witness` will be consumed when we interpolate, so interpolation will
             // have to moved below this.
-            let sorted: Vec<_> = lookup::constraints::sorted(
-                dummy_lookup_value,
-                &joint_lookup_table_d8,
-                index.cs.domain.d1,
-                &index.cs.gates,
-                &witness,
-                joint_combiner,
-                table_id_combiner,
-                &lcs.configuration.lookup_info,
-            )?;
+            let sorted: Vec<_> = match lcs.configuration.lookup_info.features.argument {
+                LookupArgument::Plookup => lookup::constraints::sorted(
+                    dummy_lookup_value,
+                    &joint_lookup_table_d8,
+                    index.cs.domain.d1,
+                    &index.cs.gates,
+                    &witness,
+                    joint_combiner,
+                    table_id_combiner,
+                    &lcs.configuration.lookup_info,
+                )?,
+                LookupArgument::LogUp => vec![lookup::logup::multiplicities(
+                    dummy_lookup_value,
+                    &joint_lookup_table_d8,
+                    index.cs.domain.d1,
+                    &index.cs.gates,
+                    &witness,
+                    joint_combiner,
+                    table_id_combiner,
+                    &lcs.configuration.lookup_info,
+                )?],
+            };
 
             //~~ * Randomize the last `EVALS` rows in each of the sorted polynomials
             //~~   in order to add zero-knowledge to the protocol.
             let sorted: Vec<_> = sorted
                 .into_iter()
-                .map(|chunk| lookup::constraints::zk_patch(chunk, index.cs.domain.d1, rng))
+                .map(|chunk| blinding::patch(chunk, index.cs.domain.d1, index.blinding.lookup, rng))
                 .collect();
 
             //~~ * Commit each of the sorted polynomials.
             let sorted_comms: Vec<_> = sorted
                 .iter()
-                .map(|v| index.srs.commit_evaluations(index.cs.domain.d1, v, rng))
+                .map(|v| {
+                    blinding::mask(
+                        &index.srs,
+                        index
+                            .srs
+                            .commit_evaluations_non_hiding(index.cs.domain.d1, v),
+                        index.blinding.lookup,
+                        rng,
+                    )
+                })
                 .collect();
 
             //~~ * Absorb each commitments to the sorted polynomials.
@@ -498,10 +768,13 @@ where
 
             // precompute different forms of the sorted polynomials for later
             // TODO: We can avoid storing these coefficients.
-            let sorted_coeffs: Vec<_> = sorted.iter().map(|e| e.clone().interpolate()).collect();
+            let sorted_coeffs: Vec<_> = sorted
+                .iter()
+                .map(|e| index.interpolate(e.clone()))
+                .collect();
             let sorted8: Vec<_> = sorted_coeffs
                 .iter()
-                .map(|v| v.evaluate_over_domain_by_ref(index.cs.domain.d8))
+                .map(|v| index.evaluate_over_domain(v, index.cs.domain.d8))
                 .collect();
 
             lookup_context.joint_combiner = Some(joint_combiner);
@@ -521,48 +794,79 @@ where
 
         //~ 1. If using lookup:
         if let Some(lcs) = &index.cs.lookup_constraint_system {
-            //~~ * Compute the lookup aggregation polynomial.
+            //~~ * Compute the lookup aggregation polynomial
+            //~~   (the running sum of the inverses with the logUp argument).
             let joint_lookup_table_d8 = lookup_context.joint_lookup_table_d8.as_ref().unwrap();
 
-            let aggreg = lookup::constraints::aggregation::<_, G::ScalarField>(
-                lookup_context.dummy_lookup_value.unwrap(),
-                joint_lookup_table_d8,
-                index.cs.domain.d1,
-                &index.cs.gates,
-                &witness,
-                &lookup_context.joint_combiner.unwrap(),
-                &lookup_context.table_id_combiner.unwrap(),
-                beta,
-                gamma,
-                lookup_context.sorted.as_ref().unwrap(),
-                rng,
-                &lcs.configuration.lookup_info,
-            )?;
+            let mut aggreg = match lcs.configuration.lookup_info.features.argument {
+                LookupArgument::Plookup => lookup::constraints::aggregation::<_, G::ScalarField>(
+                    lookup_context.dummy_lookup_value.unwrap(),
+                    joint_lookup_table_d8,
+                    index.cs.domain.d1,
+                    &index.cs.gates,
+                    &witness,
+                    &lookup_context.joint_combiner.unwrap(),
+                    &lookup_context.table_id_combiner.unwrap(),
+                    beta,
+                    gamma,
+                    lookup_context.sorted.as_ref().unwrap(),
+                    rng,
+                    &lcs.configuration.lookup_info,
+                )?,
+                LookupArgument::LogUp => lookup::logup::aggregation::<_, G::ScalarField>(
+                    lookup_context.dummy_lookup_value.unwrap(),
+                    joint_lookup_table_d8,
+                    index.cs.domain.d1,
+                    &index.cs.gates,
+                    &witness,
+                    &lookup_context.joint_combiner.unwrap(),
+                    &lookup_context.table_id_combiner.unwrap(),
+                    gamma,
+                    &lookup_context.sorted.as_ref().unwrap()[0],
+                    rng,
+                    &lcs.configuration.lookup_info,
+                )?,
+            };
+
+            // the zero-knowledge rows of the aggregation are zero without the lookup blinding
+            blinding::unpatch(&mut aggreg, index.blinding.lookup);
 
             //~~ * Commit to the aggregation polynomial.
-            let aggreg_comm = index
-                .srs
-                .commit_evaluations(index.cs.domain.d1, &aggreg, rng);
+            let aggreg_comm = blinding::mask(
+                &index.srs,
+                index
+                    .srs
+                    .commit_evaluations_non_hiding(index.cs.domain.d1, &aggreg),
+                index.blinding.lookup,
+                rng,
+            );
 
             //~~ * Absorb the commitment to the aggregation polynomial with the Fq-Sponge.
             absorb_commitment(&mut fq_sponge, &aggreg_comm.commitment);
 
             // precompute different forms of the aggregation polynomial for later
-            let aggreg_coeffs = aggreg.interpolate();
+            let aggreg_coeffs = index.interpolate(aggreg);
             // TODO: There's probably a clever way to expand the domain without
             // interpolating
-            let aggreg8 = aggreg_coeffs.evaluate_over_domain_by_ref(index.cs.domain.d8);
+            let aggreg8 = index.evaluate_over_domain(&aggreg_coeffs, index.cs.domain.d8);
 
             lookup_context.aggreg_comm = Some(aggreg_comm);
             lookup_context.aggreg_coeffs = Some(aggreg_coeffs);
             lookup_context.aggreg8 = Some(aggreg8);
         }
 
+        timer.end(ProverPhase::Lookup);
+
         //~ 1. Compute the permutation aggregation polynomial $z$.
         let z_poly = index.perm_aggreg(&witness, &beta, &gamma, rng)?;
 
         //~ 1. Commit (hidding) to the permutation aggregation polynomial $z$.
-        let z_comm = index.srs.commit(&z_poly, None, rng);
+        let z_comm = blinding::mask(
+            &index.srs,
+            index.srs.commit_non_hiding(&z_poly, None),
+            index.blinding.permutation,
+            rng,
+        );
 
         //~ 1. Absorb the permutation aggregation polynomial $z$ with the Fq-Sponge.
         absorb_commitment(&mut fq_sponge, &z_comm.commitment);
@@ -577,6 +881,8 @@ where
         let mut all_alphas = index.powers_of_alpha.clone();
         all_alphas.instantiate(alpha);
 
+        timer.end(ProverPhase::Permutation);
+
         //~ 1. Compute the quotient polynomial (the $t$ in $f = Z_H \cdot t$).
         //~    The quotient polynomial is computed by adding all these polynomials together:
         //~~ * the combined constraints for all the gates
@@ -600,7 +906,7 @@ where
             None
         };
 
-        let lagrange = index.cs.evaluate(&witness_poly, &z_poly);
+        let lagrange = index.cs.evaluate_with(index.fft(), &witness_poly, &z_poly);
         let env = {
             let mut index_evals = HashMap::new();
             use GateType::*;
@@ -654,6 +960,14 @@ where
                 index_evals.insert(GateType::Rot64, selector);
             }
 
+            if let Some(selector) = index.column_evaluations.add_u64_selector8.as_ref() {
+                index_evals.insert(GateType::AddU64, selector);
+            }
+
+            if let Some(selector) = index.column_evaluations.select_selector8.as_ref() {
+                index_evals.insert(GateType::Select, selector);
+            }
+
             let mds = &G::sponge_params().mds;
             Environment {
                 constants: Constants {
@@ -671,6 +985,7 @@ where
                 l0_1: l0_1(index.cs.domain.d1),
                 domain: index.cs.domain,
                 index: index_evals,
+                custom_index: index.column_evaluations.custom_selectors8.iter().collect(),
                 lookup: lookup_env,
             }
         };
@@ -704,6 +1019,9 @@ where
                 (perm, bnd)
             };
 
+            // the constraints of degree above 8n, over d16
+            let mut t16 = None;
+
             {
                 use crate::circuits::argument::DynArgument;
 
@@ -721,6 +1039,22 @@ where
                     .is_some();
                 let xor_enabled = index.column_evaluations.xor_selector8.is_some();
                 let rot_enabled = index.column_evaluations.rot_selector8.is_some();
+                let add_u64_enabled = index.column_evaluations.add_u64_selector8.is_some();
+                let select_enabled = index.column_evaluations.select_selector8.is_some();
+                let custom_arguments: Vec<_> = index
+                    .cs
+                    .custom_gates
+                    .iter()
+                    .enumerate()
+                    .map(|(selector, gate)| gate.argument(selector))
+                    .collect();
+
+                let mut constraints4 = expr::E::zero();
+                let mut types4 = vec![];
+                let mut constraints8 = expr::E::zero();
+                let mut types8 = vec![];
+                let mut constraints16 = expr::E::zero();
+                let mut types16 = vec![];
 
                 for gate in [
                     (
@@ -745,12 +1079,46 @@ where
                     (&Xor16::default(), xor_enabled),
                     // Rot gate
                     (&Rot64::default(), rot_enabled),
+                    // 64-bit modular addition gate
+                    (&AddU64::default(), add_u64_enabled),
+                    // Conditional selection gate
+                    (&Select::default(), select_enabled),
                 ]
                 .into_iter()
                 .filter_map(|(gate, is_enabled)| if is_enabled { Some(gate) } else { None })
-                {
+                .chain(
+                    custom_arguments
+                        .iter()
+                        .map(|gate| gate as &dyn DynArgument<_>),
+                ) {
+                    // the constraints are combined by evaluation domain,
+                    // so that their common subexpressions are evaluated once
                     let constraint = gate.combined_constraints(&all_alphas, &mut cache);
-                    let eval = constraint.evaluations(&env);
+                    let d1_size = index.cs.domain.d1.size;
+                    let degree = constraint.degree(d1_size);
+                    if degree > index.cs.max_constraint_degree() as u64 * d1_size {
+                        return Err(ProverError::Prover(
+                            "the degree of the constraints is above the bound of the quotient chunks",
+                        ));
+                    }
+                    let (constraints, types) = if degree <= 4 * d1_size {
+                        (&mut constraints4, &mut types4)
+                    } else if degree <= 8 * d1_size {
+                        (&mut constraints8, &mut types8)
+                    } else {
+                        (&mut constraints16, &mut types16)
+                    };
+                    *constraints += constraint;
+                    types.push(gate.argument_type());
+                }
+
+                for (constraints, types) in [(constraints4, types4), (constraints8, types8)] {
+                    if types.is_empty() {
+                        continue;
+                    }
+                    let eval = constraints
+                        .extract_common_subexpressions()
+                        .evaluations(&env);
                     if eval.domain().size == t4.domain().size {
                         t4 += &eval;
                     } else if eval.domain().size == t8.domain().size {
@@ -758,7 +1126,56 @@ where
                     } else {
                         panic!("Bad evaluation")
                     }
-                    check_constraint!(index, format!("{:?}", gate.argument_type()), eval);
+                    check_constraint!(index, format!("{types:?}"), eval);
+                }
+
+                if !types16.is_empty() {
+                    // the columns used by the gates are evaluated over d16 (the constraints of the gates
+                    // do not use the lookup columns)
+                    let d16 = index
+                        .cs
+                        .domain
+                        .d16()
+                        .ok_or(ProverError::Prover("the field has no domain of size 16n"))?;
+                    let to_d16 = |evals: &Evaluations<G::ScalarField, D<G::ScalarField>>| {
+                        index.evaluate_over_domain(&index.interpolate(evals.clone()), d16)
+                    };
+                    let witness16 =
+                        array::from_fn(|i| index.evaluate_over_domain(&witness_poly[i], d16));
+                    let coefficient16 = array::from_fn(|i| to_d16(&env.coefficient[i]));
+                    let vanishes_on_last_4_rows16 = to_d16(env.vanishes_on_last_4_rows);
+                    let z16 = index.evaluate_over_domain(&z_poly, d16);
+                    let index16: HashMap<_, _> = env
+                        .index
+                        .iter()
+                        .map(|(gate_type, evals)| (*gate_type, to_d16(*evals)))
+                        .collect();
+                    let custom_index16: Vec<_> = env
+                        .custom_index
+                        .iter()
+                        .map(|evals| to_d16(*evals))
+                        .collect();
+                    let env16 = Environment {
+                        constants: env.constants.clone(),
+                        witness: &witness16,
+                        coefficient: &coefficient16,
+                        vanishes_on_last_4_rows: &vanishes_on_last_4_rows16,
+                        z: &z16,
+                        l0_1: env.l0_1,
+                        domain: env.domain,
+                        index: index16
+                            .iter()
+                            .map(|(gate_type, evals)| (*gate_type, evals))
+                            .collect(),
+                        custom_index: custom_index16.iter().collect(),
+                        lookup: None,
+                    };
+
+                    let eval = constraints16
+                        .extract_common_subexpressions()
+                        .evaluations(&env16);
+                    check_constraint!(index, format!("{types16:?}"), eval);
+                    t16 = Some(eval);
                 }
             };
 
@@ -795,7 +1212,10 @@ where
             }
 
             // public polynomial
-            let mut f = t4.interpolate() + t8.interpolate();
+            let mut f = index.interpolate(t4) + index.interpolate(t8);
+            if let Some(t16) = t16 {
+                f += &index.interpolate(t16);
+            }
             f += &public_poly;
 
             // divide contributions with vanishing polynomial
@@ -817,8 +1237,12 @@ where
         let t_comm = {
             let mut t_comm = index.srs.commit(&quotient_poly, None, rng);
 
-            let expected_t_size = PERMUTS;
-            let dummies = expected_t_size - t_comm.commitment.unshifted.len();
+            let expected_t_size = index.cs.quotient_chunks;
+            let dummies = expected_t_size
+                .checked_sub(t_comm.commitment.unshifted.len())
+                .ok_or(ProverError::Prover(
+                    "the quotient polynomial has more chunks than the constraint system allows",
+                ))?;
             // Add `dummies` many hiding commitments to the 0 polynomial, since if the
             // number of commitments in `t_comm` is less than the max size, it means that
             // the higher degree coefficients of `t` are 0.
@@ -836,6 +1260,8 @@ where
         //~ 1. Absorb the the commitment of the quotient polynomial with the Fq-Sponge.
         absorb_commitment(&mut fq_sponge, &t_comm.commitment);
 
+        timer.end(ProverPhase::Quotient);
+
         //~ 1. Sample $\zeta'$ with the Fq-Sponge.
         let zeta_chal = ScalarChallenge(fq_sponge.challenge());
 
@@ -991,7 +1417,7 @@ where
                     let (_lin_constant, mut lin) =
                         index.linearization.to_polynomial(&env, zeta, &evals);
                     lin += &f;
-                    lin.interpolate()
+                    index.interpolate(lin)
                 };
 
                 drop(env);
@@ -1208,6 +1634,8 @@ where
             }
         }
 
+        timer.end(ProverPhase::Evaluations);
+
         //~ 1. Create an aggregated evaluation proof for all of these polynomials at $\zeta$ and $\zeta\omega$ using $u$ and $v$.
         let proof = index.srs.open(
             group_map,
@@ -1219,6 +1647,8 @@ where
             rng,
         );
 
+        timer.end(ProverPhase::Opening);
+
         let lookup = lookup_context
             .aggreg_comm
             .zip(lookup_context.sorted_comms)
@@ -1239,6 +1669,8 @@ where
             evals: chunked_evals,
             ft_eval1,
             prev_challenges,
+            prev_proof_digest,
+            arithmetization_version: ARITHMETIZATION_VERSION,
         })
     }
 }
@@ -1484,6 +1916,8 @@ pub mod caml {
                     .into_iter()
                     .map(Into::into)
                     .collect(),
+                prev_proof_digest: None,
+                arithmetization_version: ARITHMETIZATION_VERSION,
             };
 
             (proof, caml_pp.public.into_iter().map(Into::into).collect())
@@ -2,15 +2,19 @@
 
 use crate::{
     alphas::Alphas,
+    blinding::Blinding,
     circuits::{
         constraints::{ColumnEvaluations, ConstraintSystem},
         expr::{Linearization, PolishToken},
     },
     curve::KimchiCurve,
+    fft::{self, CpuFft, FftBackend},
     linearization::expr_linearization,
-    verifier_index::VerifierIndex,
+    verifier_index::{LookupVerifierIndex, VerifierIndex},
+};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain as D,
 };
-use ark_poly::EvaluationDomain;
 use mina_poseidon::FqSponge;
 use poly_commitment::srs::SRS;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -51,6 +55,21 @@ pub struct ProverIndex<G: KimchiCurve> {
     /// The verifier index digest corresponding to this prover index
     #[serde_as(as = "Option<o1_utils::serialization::SerdeAs>")]
     pub verifier_index_digest: Option<G::BaseField>,
+
+    /// The precomputed commitments of the lookup constraint system, see [ProverIndex::precompute_lookup_commitments]
+    #[serde(
+        default,
+        bound = "Option<LookupVerifierIndex<G>>: Serialize + DeserializeOwned"
+    )]
+    pub lookup_commitments: Option<LookupVerifierIndex<G>>,
+
+    /// The blinding of the permutation and lookup columns, see [Blinding]
+    #[serde(default)]
+    pub blinding: Blinding,
+
+    /// The backend computing the FFTs of the prover, on the CPU if none (see [fft])
+    #[serde(skip)]
+    pub fft_backend: Option<Arc<dyn FftBackend<G::ScalarField>>>,
 }
 //~spec:endcode
 
@@ -75,7 +94,8 @@ impl<G: KimchiCurve> ProverIndex<G> {
         cs.endo = endo_q;
 
         // pre-compute the linearization
-        let (linearization, powers_of_alpha) = expr_linearization(Some(&cs.feature_flags), true);
+        let (linearization, powers_of_alpha) =
+            expr_linearization(Some(&cs.feature_flags), true, &cs.custom_gates);
 
         let evaluated_column_coefficients = cs.evaluated_column_coefficients();
 
@@ -90,6 +110,61 @@ impl<G: KimchiCurve> ProverIndex<G> {
             column_evaluations,
             verifier_index: None,
             verifier_index_digest: None,
+            lookup_commitments: None,
+            blinding: Blinding::default(),
+            fft_backend: None,
+        }
+    }
+
+    /// Compiles the index from constraints as [ProverIndex::create],
+    /// with a backend computing the FFTs of the prover (see [fft])
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `polynomial segment size` is bigger than `circuit`.
+    pub fn create_with_fft_backend(
+        cs: ConstraintSystem<G::ScalarField>,
+        endo_q: G::ScalarField,
+        srs: Arc<SRS<G>>,
+        fft_backend: Arc<dyn FftBackend<G::ScalarField>>,
+    ) -> Self {
+        let mut index = Self::create(cs, endo_q, srs);
+        index.fft_backend = Some(fft_backend);
+        index
+    }
+
+    /// The backend computing the FFTs of the prover, [CpuFft] by default
+    pub fn fft(&self) -> &dyn FftBackend<G::ScalarField> {
+        self.fft_backend.as_deref().unwrap_or(&CpuFft)
+    }
+
+    /// Evaluates a polynomial over a domain with the FFT backend of the index
+    pub fn evaluate_over_domain(
+        &self,
+        poly: &DensePolynomial<G::ScalarField>,
+        domain: D<G::ScalarField>,
+    ) -> Evaluations<G::ScalarField, D<G::ScalarField>> {
+        fft::evaluate_over_domain(self.fft(), poly, domain)
+    }
+
+    /// Interpolates evaluations over a domain with the FFT backend of the index
+    pub fn interpolate(
+        &self,
+        evals: Evaluations<G::ScalarField, D<G::ScalarField>>,
+    ) -> DensePolynomial<G::ScalarField> {
+        fft::interpolate(self.fft(), evals)
+    }
+
+    /// Compute and store the commitments of the lookup tables and selectors,
+    /// so that they are serialized with the index and not computed again by [ProverIndex::verifier_index].
+    /// Together with [crate::circuits::constraints::Builder::lookup_precomputations],
+    /// this allows to skip the lookup precomputations when setting up the same circuit again.
+    ///
+    /// The commitments are only valid for the SRS of the index:
+    /// an index deserialized with another SRS must compute them again.
+    pub fn precompute_lookup_commitments(&mut self) {
+        if self.lookup_commitments.is_none() {
+            self.lookup_commitments = self.lookup_verifier_index();
         }
     }
 
@@ -141,7 +216,18 @@ pub mod testing {
         precomputed_srs,
     };
     use ark_ff::{PrimeField, SquareRootField};
+    use ark_poly::Radix2EvaluationDomain as D;
+    use once_cell::sync::Lazy;
     use poly_commitment::srs::endos;
+    use std::{
+        any::{Any, TypeId},
+        collections::HashMap,
+        sync::{Mutex, PoisonError},
+    };
+
+    // the SRS of the tests, with the Lagrange basis of a domain, per curve and size of the domain
+    type SrsCache = HashMap<(TypeId, usize), Arc<dyn Any + Send + Sync>>;
+    static TEST_SRS: Lazy<Mutex<SrsCache>> = Lazy::new(|| Mutex::new(HashMap::new()));
 
     /// Create new index for lookups.
     ///
@@ -170,16 +256,59 @@ pub mod testing {
             .build()
             .unwrap();
 
-        let mut srs = if cs.domain.d1.log_size_of_group <= precomputed_srs::SERIALIZED_SRS_SIZE {
-            // TODO: we should trim it if it's smaller
-            precomputed_srs::get_srs()
-        } else {
-            // TODO: we should resume the SRS generation starting from the serialized one
-            SRS::<G>::create(cs.domain.d1.size())
-        };
+        new_index_for_test_with_cs(cs)
+    }
+
+    /// Returns the SRS for a domain, with the Lagrange basis of the domain.
+    /// It is created once per curve and size of the domain, and shared by the tests.
+    pub fn srs_for_test<G: KimchiCurve>(domain: D<G::ScalarField>) -> Arc<SRS<G>>
+    where
+        G::BaseField: PrimeField,
+    {
+        let mut cache = TEST_SRS.lock().unwrap_or_else(PoisonError::into_inner);
+        let srs = cache
+            .entry((TypeId::of::<G>(), domain.size()))
+            .or_insert_with(|| {
+                let mut srs = if domain.log_size_of_group <= precomputed_srs::SERIALIZED_SRS_SIZE {
+                    // TODO: we should trim it if it's smaller
+                    precomputed_srs::get_srs()
+                } else {
+                    // TODO: we should resume the SRS generation starting from the serialized one
+                    SRS::<G>::create(domain.size())
+                };
+                srs.add_lagrange_basis(domain);
+                Arc::new(srs)
+            });
+        Arc::clone(srs)
+            .downcast()
+            .expect("the SRS of the tests is cached per curve")
+    }
 
-        srs.add_lagrange_basis(cs.domain.d1);
-        let srs = Arc::new(srs);
+    /// Create new index for a constraint system, with the SRS of [srs_for_test].
+    pub fn new_index_for_test_with_cs<G: KimchiCurve>(
+        cs: ConstraintSystem<G::ScalarField>,
+    ) -> ProverIndex<G>
+    where
+        G::BaseField: PrimeField,
+        G::ScalarField: PrimeField + SquareRootField,
+    {
+        let srs = srs_for_test::<G>(cs.domain.d1);
+        new_index_for_test_with_srs(cs, srs)
+    }
+
+    /// Create new index for a constraint system with a given SRS,
+    /// adding the Lagrange basis of the domain of the circuit to a copy of the SRS if it is missing.
+    pub fn new_index_for_test_with_srs<G: KimchiCurve>(
+        cs: ConstraintSystem<G::ScalarField>,
+        mut srs: Arc<SRS<G>>,
+    ) -> ProverIndex<G>
+    where
+        G::BaseField: PrimeField,
+        G::ScalarField: PrimeField + SquareRootField,
+    {
+        if !srs.lagrange_bases.contains_key(&cs.domain.d1.size()) {
+            Arc::make_mut(&mut srs).add_lagrange_basis(cs.domain.d1);
+        }
 
         let (endo_q, _endo_r) = endos::<G::OtherCurve>();
         ProverIndex::<G>::create(cs, endo_q, srs)
@@ -0,0 +1,196 @@
+//! This module implements the aggregation of the public inputs of a batch of proofs into one commitment.
+//!
+//! Each proof of the batch has a single public input, the digest of its public values
+//! (the Poseidon hash of [PUBLIC_INPUT_LEAF_PREFIX] and the values with the kimchi sponge
+//! of the scalar field, see [public_input_digest]),
+//! which its circuit computes from the values with the Poseidon gadget.
+//! The digests of the batch are the leaves of a Poseidon Merkle tree, a [PublicInputTree],
+//! whose root commits to the public values of all the proofs.
+//! Each proof is verified with the [PublicInputOpening] of its leaf in the tree,
+//! see [batch_verify_with_public_commitment], so that the verifier of the batch only processes
+//! one public input and a path of `log N` hashes per proof, however large the public values are.
+//!
+//! The nodes are hashed with [PUBLIC_INPUT_NODE_PREFIX], and the verifier checks that
+//! the index of an opening is one of the `N` leaves and that its path has the depth of the tree:
+//! a node of the tree cannot be opened as the public input of a proof.
+
+use crate::{
+    curve::KimchiCurve,
+    domain_separation::{PUBLIC_INPUT_LEAF_PREFIX, PUBLIC_INPUT_NODE_PREFIX},
+    error::VerifyError,
+    plonk_sponge::FrSponge,
+    proof::ProverProof,
+    verifier::{batch_verify, Context},
+    verifier_index::VerifierIndex,
+};
+use ark_ff::{PrimeField, Zero};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    poseidon::{ArithmeticSponge, Sponge},
+    FqSponge,
+};
+use std::slice;
+
+// Hashes a prefix and elements with the kimchi sponge of the scalar field of `G`
+fn hash<G: KimchiCurve>(prefix: &[u8], elems: &[G::ScalarField]) -> G::ScalarField {
+    let mut sponge =
+        ArithmeticSponge::<G::ScalarField, PlonkSpongeConstantsKimchi>::new(G::sponge_params());
+    sponge.absorb(&[G::ScalarField::from_le_bytes_mod_order(prefix)]);
+    sponge.absorb(elems);
+    sponge.squeeze()
+}
+
+// Hashes the children of a node of a tree
+fn hash_node<G: KimchiCurve>(left: G::ScalarField, right: G::ScalarField) -> G::ScalarField {
+    hash::<G>(PUBLIC_INPUT_NODE_PREFIX, &[left, right])
+}
+
+// The depth of a tree of `num_leaves` leaves, padded to a power of two
+fn depth(num_leaves: usize) -> usize {
+    num_leaves.next_power_of_two().trailing_zeros() as usize
+}
+
+/// Returns the digest of the public values of a proof, its public input in a batch with a public input commitment
+pub fn public_input_digest<G: KimchiCurve>(values: &[G::ScalarField]) -> G::ScalarField {
+    hash::<G>(PUBLIC_INPUT_LEAF_PREFIX, values)
+}
+
+/// A Poseidon Merkle tree of the public inputs of a batch of proofs,
+/// whose leaves are padded with zeros to a power of two
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicInputTree<G: KimchiCurve> {
+    // the number of leaves before padding
+    num_leaves: usize,
+    // the layers of the tree, from the leaves to the root
+    layers: Vec<Vec<G::ScalarField>>,
+}
+
+impl<G: KimchiCurve> PublicInputTree<G> {
+    /// Creates the tree of the public inputs of the proofs, in their order
+    pub fn new(mut leaves: Vec<G::ScalarField>) -> Self {
+        assert!(!leaves.is_empty(), "The batch has a proof");
+        let num_leaves = leaves.len();
+        leaves.resize(num_leaves.next_power_of_two(), G::ScalarField::zero());
+        let mut layers = vec![leaves];
+        while let Some(layer) = layers.last().filter(|layer| layer.len() > 1) {
+            let next = layer
+                .chunks(2)
+                .map(|children| hash_node::<G>(children[0], children[1]))
+                .collect();
+            layers.push(next);
+        }
+        Self { num_leaves, layers }
+    }
+
+    /// Returns the number of leaves of the tree, the public inputs of the batch, before padding
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    /// Returns the nodes of the tree at a depth, from the padded leaves (0) to the root
+    pub fn layer(&self, depth: usize) -> &[G::ScalarField] {
+        &self.layers[depth]
+    }
+
+    /// Returns the root of the tree, the commitment to the public inputs of the batch
+    pub fn root(&self) -> G::ScalarField {
+        self.layers.last().expect("the tree has a root")[0]
+    }
+
+    /// Returns the opening of the public input of the `index`-th proof
+    pub fn opening(&self, index: usize) -> PublicInputOpening<G::ScalarField> {
+        let leaves = &self.layers[0];
+        assert!(index < self.num_leaves, "The tree has the leaf");
+        let path = self.layers[..self.layers.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(depth, layer)| layer[(index >> depth) ^ 1])
+            .collect();
+        PublicInputOpening {
+            index,
+            leaf: leaves[index],
+            path,
+        }
+    }
+}
+
+/// The opening of the public input of a proof in a [PublicInputTree]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicInputOpening<F> {
+    /// The index of the proof in the batch
+    pub index: usize,
+    /// The public input of the proof, the digest of its public values
+    pub leaf: F,
+    /// The siblings of the path from the leaf to the root
+    pub path: Vec<F>,
+}
+
+impl<F: PrimeField> PublicInputOpening<F> {
+    /// Returns the root of the tree of the opening
+    pub fn root<G: KimchiCurve<ScalarField = F>>(&self) -> F {
+        self.path
+            .iter()
+            .enumerate()
+            .fold(self.leaf, |node, (depth, sibling)| {
+                if (self.index >> depth) & 1 == 0 {
+                    hash_node::<G>(node, *sibling)
+                } else {
+                    hash_node::<G>(*sibling, node)
+                }
+            })
+    }
+
+    /// Checks that the opening is the one of a leaf of a tree of `num_leaves` leaves with this `root`:
+    /// its index is one of the leaves, its path has the depth of the tree, and it gives the root
+    pub fn check<G: KimchiCurve<ScalarField = F>>(&self, root: F, num_leaves: usize) -> bool {
+        self.index < num_leaves && self.path.len() == depth(num_leaves) && self.root::<G>() == root
+    }
+}
+
+/// A proof of a batch with a public input commitment, and the opening of its public input
+pub struct CommittedContext<'a, G: KimchiCurve> {
+    /// The [VerifierIndex] associated to the proof, with a single public input
+    pub verifier_index: &'a VerifierIndex<G>,
+
+    /// The proof to verify
+    pub proof: &'a ProverProof<G>,
+
+    /// The opening of the public input of the proof
+    pub opening: &'a PublicInputOpening<G::ScalarField>,
+}
+
+/// Verifies a batch of proofs whose public inputs are committed by the root of a [PublicInputTree]
+/// of `num_leaves` leaves: checks the opening of the public input of each proof
+/// (see [PublicInputOpening::check]), and verifies the proofs in a batch.
+///
+/// # Errors
+///
+/// Will give error with the index of the first proof whose opening is not one of a leaf of the tree,
+/// or if any of the proofs does not verify.
+pub fn batch_verify_with_public_commitment<G, EFqSponge, EFrSponge>(
+    group_map: &G::Map,
+    root: G::ScalarField,
+    num_leaves: usize,
+    proofs: &[CommittedContext<G>],
+) -> Result<(), VerifyError>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
+    for (i, context) in proofs.iter().enumerate() {
+        if !context.opening.check::<G>(root, num_leaves) {
+            return Err(VerifyError::PublicInputOpening(i));
+        }
+    }
+    let contexts: Vec<_> = proofs
+        .iter()
+        .map(|context| Context {
+            verifier_index: context.verifier_index,
+            proof: context.proof,
+            public_input: slice::from_ref(&context.opening.leaf),
+        })
+        .collect();
+    batch_verify::<G, EFqSponge, EFrSponge>(group_map, &contexts)
+}
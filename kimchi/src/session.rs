@@ -0,0 +1,258 @@
+//! This module implements sessions of proofs.
+//!
+//! A session is a sequence of proofs in which each proof absorbs the digest of the previous one,
+//! right after the digest of its verifier index, at the start of its transcript.
+//! Every challenge of a proof of the session thus depends on all the proofs before it,
+//! and a verifier checking the chain of digests knows that the proofs were created in that order,
+//! without a recursion circuit.
+//!
+//! Note that the proofs of a session do not need to share the same circuit.
+
+use crate::{
+    circuits::{lookup::runtime_tables::RuntimeTable, wires::COLUMNS},
+    curve::KimchiCurve,
+    error::{ProverError, VerifyError},
+    plonk_sponge::FrSponge,
+    proof::{LookupEvaluations, PointEvaluations, ProofEvaluations, ProverProof},
+    prover_index::ProverIndex,
+    verifier::{batch_verify, Context},
+};
+use ark_ff::PrimeField;
+use mina_poseidon::FqSponge;
+use poly_commitment::commitment::absorb_commitment;
+use rand::{CryptoRng, RngCore};
+
+impl<G: KimchiCurve> ProverProof<G>
+where
+    G::BaseField: PrimeField,
+{
+    /// Computes the digest of the proof, which is absorbed by the next proof of a session.
+    /// It covers all the fields of the proof, including the digest of the previous proof
+    /// for the digests to chain the whole session.
+    pub fn digest<EFqSponge: FqSponge<G::BaseField, G, G::ScalarField>>(&self) -> G::BaseField {
+        let mut fq_sponge = EFqSponge::new(G::OtherCurve::sponge_params());
+
+        if let Some(digest) = self.prev_proof_digest {
+            fq_sponge.absorb_fq(&[digest]);
+        }
+
+        for chal in &self.prev_challenges {
+            fq_sponge.absorb_fr(&chal.chals);
+            absorb_commitment(&mut fq_sponge, &chal.comm);
+        }
+
+        let commitments = &self.commitments;
+        for comm in &commitments.w_comm {
+            absorb_commitment(&mut fq_sponge, comm);
+        }
+        absorb_commitment(&mut fq_sponge, &commitments.z_comm);
+        absorb_commitment(&mut fq_sponge, &commitments.t_comm);
+        if let Some(lookup) = &commitments.lookup {
+            for comm in &lookup.sorted {
+                absorb_commitment(&mut fq_sponge, comm);
+            }
+            absorb_commitment(&mut fq_sponge, &lookup.aggreg);
+            if let Some(runtime) = &lookup.runtime {
+                absorb_commitment(&mut fq_sponge, runtime);
+            }
+        }
+
+        absorb_evaluations::<G, EFqSponge>(&mut fq_sponge, &self.evals);
+        fq_sponge.absorb_fr(&[self.ft_eval1]);
+
+        let proof = &self.proof;
+        for (l, r) in &proof.lr {
+            fq_sponge.absorb_g(&[*l, *r]);
+        }
+        fq_sponge.absorb_g(&[proof.delta, proof.sg]);
+        fq_sponge.absorb_fr(&[proof.z1, proof.z2]);
+
+        fq_sponge.digest_fq()
+    }
+}
+
+// Absorbs all the evaluations of a proof into an Fq-sponge
+fn absorb_evaluations<G, EFqSponge: FqSponge<G::BaseField, G, G::ScalarField>>(
+    fq_sponge: &mut EFqSponge,
+    evals: &ProofEvaluations<PointEvaluations<Vec<G::ScalarField>>>,
+) where
+    G: KimchiCurve,
+{
+    let ProofEvaluations {
+        w,
+        z,
+        s,
+        coefficients,
+        lookup,
+        generic_selector,
+        poseidon_selector,
+    } = evals;
+
+    let mut points = vec![z, generic_selector, poseidon_selector];
+    points.extend(w.iter());
+    points.extend(coefficients.iter());
+    points.extend(s.iter());
+    if let Some(LookupEvaluations {
+        sorted,
+        aggreg,
+        table,
+        runtime,
+    }) = lookup
+    {
+        points.push(aggreg);
+        points.push(table);
+        points.extend(sorted.iter());
+        points.extend(runtime.iter());
+    }
+
+    for point in points {
+        fq_sponge.absorb_fr(&point.zeta);
+        fq_sponge.absorb_fr(&point.zeta_omega);
+    }
+}
+
+/// A session of proofs, which binds each proof it creates to the previous one
+#[derive(Clone, Debug)]
+pub struct Session<G: KimchiCurve> {
+    /// The digest of the last proof of the session
+    prev_proof_digest: Option<G::BaseField>,
+}
+
+impl<G: KimchiCurve> Default for Session<G> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<G: KimchiCurve> Session<G>
+where
+    G::BaseField: PrimeField,
+{
+    /// Creates a new session, whose first proof is not bound to any previous proof
+    pub fn new() -> Self {
+        Self {
+            prev_proof_digest: None,
+        }
+    }
+
+    /// Resumes a session, whose next proof is bound to the proof of digest `prev_proof_digest`
+    pub fn resume(prev_proof_digest: G::BaseField) -> Self {
+        Self {
+            prev_proof_digest: Some(prev_proof_digest),
+        }
+    }
+
+    /// The digest of the last proof of the session, if any
+    pub fn prev_proof_digest(&self) -> Option<G::BaseField> {
+        self.prev_proof_digest
+    }
+
+    /// Creates the next proof of the session, see [ProverProof::create]
+    ///
+    /// The randomness used for blinding is drawn from the operating system (see [`rand::rngs::OsRng`]).
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the proof creation fails, in which case the session is left unchanged.
+    pub fn prove<
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+    >(
+        &mut self,
+        group_map: &G::Map,
+        witness: [Vec<G::ScalarField>; COLUMNS],
+        runtime_tables: &[RuntimeTable<G::ScalarField>],
+        index: &ProverIndex<G>,
+    ) -> Result<ProverProof<G>, ProverError> {
+        self.prove_with_rng::<EFqSponge, EFrSponge, _>(
+            group_map,
+            witness,
+            runtime_tables,
+            index,
+            &mut rand::rngs::OsRng,
+        )
+    }
+
+    /// Creates the next proof of the session, drawing all blinding factors from the given `rng`,
+    /// see [ProverProof::create_recursive_with_rng]
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the proof creation fails, in which case the session is left unchanged.
+    pub fn prove_with_rng<
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+        RNG: RngCore + CryptoRng,
+    >(
+        &mut self,
+        group_map: &G::Map,
+        witness: [Vec<G::ScalarField>; COLUMNS],
+        runtime_tables: &[RuntimeTable<G::ScalarField>],
+        index: &ProverIndex<G>,
+        rng: &mut RNG,
+    ) -> Result<ProverProof<G>, ProverError> {
+        let proof = ProverProof::create_internal::<EFqSponge, EFrSponge, RNG>(
+            group_map,
+            witness,
+            runtime_tables,
+            index,
+            Vec::new(),
+            None,
+            None,
+            self.prev_proof_digest,
+            None,
+            rng,
+        )?;
+        self.prev_proof_digest = Some(proof.digest::<EFqSponge>());
+        Ok(proof)
+    }
+}
+
+/// Checks that the proofs form a session, in order:
+/// the first proof is bound to `prev_proof_digest` (`None` for a new session),
+/// and each of the next proofs to the previous one.
+/// This does not verify the proofs themselves, see [verify_session].
+///
+/// # Errors
+///
+/// Will give error with the index of the first proof that is not bound to the previous one.
+pub fn check_session_chain<G, EFqSponge>(
+    prev_proof_digest: Option<G::BaseField>,
+    proofs: &[&ProverProof<G>],
+) -> Result<(), VerifyError>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: FqSponge<G::BaseField, G, G::ScalarField>,
+{
+    let mut expected = prev_proof_digest;
+    for (i, proof) in proofs.iter().enumerate() {
+        if proof.prev_proof_digest != expected {
+            return Err(VerifyError::SessionChainBroken(i));
+        }
+        expected = Some(proof.digest::<EFqSponge>());
+    }
+    Ok(())
+}
+
+/// Verifies a session of proofs: checks that they are chained in order with [check_session_chain],
+/// and verifies them in a batch.
+///
+/// # Errors
+///
+/// Will give error if the proofs are not chained, or if any of them does not verify.
+pub fn verify_session<G, EFqSponge, EFrSponge>(
+    group_map: &G::Map,
+    prev_proof_digest: Option<G::BaseField>,
+    proofs: &[Context<G>],
+) -> Result<(), VerifyError>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
+    let chain: Vec<_> = proofs.iter().map(|context| context.proof).collect();
+    check_session_chain::<G, EFqSponge>(prev_proof_digest, &chain)?;
+    batch_verify::<G, EFqSponge, EFrSponge>(group_map, proofs)
+}
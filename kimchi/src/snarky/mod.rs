@@ -1,3 +1,4 @@
 pub mod asm;
 pub mod constants;
 pub mod constraint_system;
+pub mod o1js;
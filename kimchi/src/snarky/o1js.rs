@@ -0,0 +1,200 @@
+//! This module implements the conversion of kimchi circuits and witnesses
+//! to and from the structured format of o1js, so that a circuit written in Rust can be used
+//! by the tooling of o1js, and a circuit compiled by o1js can be proven in Rust.
+//!
+//! - A circuit is an [O1jsCircuit]: the size of its public input and its gates,
+//!   as serialized by the bindings of o1js (see [Circuit]).
+//!   The gates keep their wiring, and their coefficients are little-endian hexadecimal strings.
+//! - A witness is an [O1jsWitness]: the public input and the columns of the witness,
+//!   whose field elements are decimal strings, as the `Field` values of o1js.
+//!
+//! The types are serialized with serde, for example to JSON with `serde_json`.
+//! Only the gates and the configuration supported by o1js can be exported:
+//! the circuit must not use the gates outside of [O1JS_GATES], nor lookup tables, runtime tables,
+//! public outputs or a configuration of the constraint system other than the default.
+
+use crate::{
+    circuits::{
+        compiled::CompiledCircuit,
+        constraints::DEFAULT_QUOTIENT_CHUNKS,
+        gate::{Circuit, CircuitGate, GateType},
+        lookup::lookups::LookupArgument,
+        wires::COLUMNS,
+    },
+    error::O1jsFormatError,
+};
+use ark_ff::{PrimeField, SquareRootField};
+use num_bigint::BigUint;
+use o1_utils::{BigUintFieldHelpers, FieldHelpers};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::{serde_as, DeserializeAs, SerializeAs};
+
+/// The gates known by o1js
+pub const O1JS_GATES: [GateType; 18] = [
+    GateType::Zero,
+    GateType::Generic,
+    GateType::Poseidon,
+    GateType::CompleteAdd,
+    GateType::VarBaseMul,
+    GateType::EndoMul,
+    GateType::EndoMulScalar,
+    GateType::Lookup,
+    GateType::CairoClaim,
+    GateType::CairoInstruction,
+    GateType::CairoFlags,
+    GateType::CairoTransition,
+    GateType::RangeCheck0,
+    GateType::RangeCheck1,
+    GateType::ForeignFieldAdd,
+    GateType::ForeignFieldMul,
+    GateType::Xor16,
+    GateType::Rot64,
+];
+
+/// A circuit in the format of o1js, see the [module documentation](self)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "F: ark_serialize::CanonicalSerialize + ark_serialize::CanonicalDeserialize")]
+pub struct O1jsCircuit<F: PrimeField> {
+    /// number of public inputs
+    pub public_input_size: usize,
+    /// the gates, with their wiring and coefficients
+    pub gates: Vec<CircuitGate<F>>,
+}
+
+impl<F: PrimeField> O1jsCircuit<F> {
+    /// Exports a circuit to the format of o1js.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if a gate of the circuit is not known by o1js.
+    pub fn new(circuit: &Circuit<F>) -> Result<Self, O1jsFormatError> {
+        if let Some((row, gate)) = circuit
+            .gates
+            .iter()
+            .enumerate()
+            .find(|(_, gate)| !O1JS_GATES.contains(&gate.typ))
+        {
+            return Err(O1jsFormatError::UnsupportedGate(row, gate.typ));
+        }
+        Ok(Self {
+            public_input_size: circuit.public_input_size,
+            gates: circuit.gates.to_vec(),
+        })
+    }
+
+    /// The circuit, to be built or serialized with the format of kimchi
+    pub fn circuit(&self) -> Circuit<F> {
+        Circuit::new(self.public_input_size, &self.gates)
+    }
+}
+
+impl<F: PrimeField + SquareRootField> O1jsCircuit<F> {
+    /// Exports a compiled circuit to the format of o1js.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if a gate of the circuit is not known by o1js,
+    /// or if the configuration of its constraint system is not the default one.
+    pub fn from_compiled(compiled: &CompiledCircuit<F>) -> Result<Self, O1jsFormatError> {
+        let unsupported = [
+            (!compiled.public_outputs.is_empty(), "public outputs"),
+            (compiled.prev_challenges != 0, "previous challenges"),
+            (!compiled.lookup_tables.is_empty(), "lookup tables"),
+            (compiled.runtime_tables.is_some(), "runtime tables"),
+            (
+                compiled.lookup_argument != LookupArgument::default(),
+                "lookup argument",
+            ),
+            (compiled.domain_separator.is_some(), "domain separator"),
+            (
+                compiled.quotient_chunks != DEFAULT_QUOTIENT_CHUNKS,
+                "quotient chunks",
+            ),
+        ];
+        if let Some((_, config)) = unsupported.into_iter().find(|(used, _)| *used) {
+            return Err(O1jsFormatError::UnsupportedConfiguration(config));
+        }
+        Self::new(&Circuit::new(compiled.public, &compiled.gates))
+    }
+
+    /// Imports the circuit as a compiled circuit, with the default configuration
+    /// of its constraint system (see [CompiledCircuit::new])
+    pub fn compiled(self) -> CompiledCircuit<F> {
+        CompiledCircuit {
+            public: self.public_input_size,
+            ..CompiledCircuit::new(self.gates)
+        }
+    }
+}
+
+/// A witness in the format of o1js, see the [module documentation](self)
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct O1jsWitness<F: PrimeField> {
+    /// the public input, in the first rows of the first column
+    #[serde_as(as = "Vec<DecimalField>")]
+    pub public_input: Vec<F>,
+    /// the columns of the witness
+    #[serde_as(as = "Vec<Vec<DecimalField>>")]
+    pub columns: Vec<Vec<F>>,
+}
+
+impl<F: PrimeField> O1jsWitness<F> {
+    /// Exports a witness, with its public input, to the format of o1js
+    pub fn new(witness: &[Vec<F>; COLUMNS], public_input: &[F]) -> Self {
+        Self {
+            public_input: public_input.to_vec(),
+            columns: witness.to_vec(),
+        }
+    }
+
+    /// Imports the witness and its public input.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the witness does not have [COLUMNS] columns of the same length,
+    /// or if the public input is not the start of its first column.
+    pub fn witness(self) -> Result<([Vec<F>; COLUMNS], Vec<F>), O1jsFormatError> {
+        let columns: [Vec<F>; COLUMNS] = self
+            .columns
+            .try_into()
+            .map_err(|columns: Vec<_>| O1jsFormatError::WitnessColumns(columns.len()))?;
+        let rows = columns[0].len();
+        if let Some((col, column)) = columns
+            .iter()
+            .enumerate()
+            .find(|(_, column)| column.len() != rows)
+        {
+            return Err(O1jsFormatError::ColumnLength(col, column.len(), rows));
+        }
+        if let Some(row) = (0..self.public_input.len())
+            .find(|row| columns[0].get(*row) != Some(&self.public_input[*row]))
+        {
+            return Err(O1jsFormatError::PublicInputMismatch(row));
+        }
+        Ok((columns, self.public_input))
+    }
+}
+
+// The decimal string of a field element, as the `Field` values of o1js
+struct DecimalField;
+
+impl<F: PrimeField> SerializeAs<F> for DecimalField {
+    fn serialize_as<S: Serializer>(value: &F, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&value.to_biguint())
+    }
+}
+
+impl<'de, F: PrimeField> DeserializeAs<'de, F> for DecimalField {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<F, D::Error> {
+        let decimal = String::deserialize(deserializer)?;
+        let value: BigUint = decimal.parse().map_err(D::Error::custom)?;
+        // the values above the modulus are rejected, instead of being reduced
+        value
+            .clone()
+            .to_field::<F>()
+            .ok()
+            .filter(|field: &F| field.to_biguint() == value)
+            .ok_or_else(|| D::Error::custom(format!("{decimal} is not an element of the field")))
+    }
+}
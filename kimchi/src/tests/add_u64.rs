@@ -0,0 +1,148 @@
+use std::array;
+
+use super::framework::TestFramework;
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::{CircuitGate, CircuitGateError, GateType},
+    polynomial::COLUMNS,
+    polynomials::add_u64,
+};
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+const RNG_SEED: [u8; 32] = [
+    0, 131, 43, 175, 229, 252, 206, 26, 67, 193, 86, 160, 1, 90, 131, 86, 168, 4, 95, 50, 48, 9,
+    192, 13, 250, 215, 172, 130, 24, 164, 162, 221,
+];
+
+// Creates a circuit of one addition per pair of words, and its witness
+fn create_add_u64(words: &[(u64, u64)]) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS]) {
+    let mut gates = vec![];
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    for (a, b) in words {
+        CircuitGate::extend_add_u64(&mut gates);
+        let (sum, carry) = add_u64::extend_add_u64_witness(&mut witness, *a, *b);
+        assert_eq!(
+            (sum, carry),
+            a.overflowing_add(*b),
+            "the sum is modulo 2^64, with its carry"
+        );
+    }
+    assert_eq!(witness[0].len(), gates.len());
+    (gates, witness)
+}
+
+// Checks the constraints of the row of the witness
+fn verify_row(
+    cs: &ConstraintSystem<Fp>,
+    witness: &[Vec<Fp>; COLUMNS],
+    row: usize,
+) -> Result<(), CircuitGateError> {
+    cs.gates[row].verify_witness::<Vesta>(row, witness, cs, &[])
+}
+
+fn prove_and_verify(
+    gates: Vec<CircuitGate<Fp>>,
+    witness: [Vec<Fp>; COLUMNS],
+) -> Result<(), String> {
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+}
+
+#[test]
+// Test that the additions with and without carry satisfy the constraints
+fn test_add_u64_witness() {
+    let words = [
+        (0, 0),
+        (1, 2),
+        (u64::MAX, 0),
+        (u64::MAX, 1),
+        (u64::MAX, u64::MAX),
+        (1 << 63, 1 << 63),
+    ];
+    let (gates, witness) = create_add_u64(&words);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+    for row in 0..words.len() {
+        assert_eq!(verify_row(&cs, &witness, row), Ok(()));
+    }
+
+    // the carries and the sums
+    assert_eq!(witness[3][2], Fp::from(0u64));
+    assert_eq!(witness[3][3], Fp::from(1u64));
+    assert_eq!(witness[2][3], Fp::from(0u64));
+    assert_eq!(witness[2][4], Fp::from(u64::MAX - 1));
+    assert_eq!(witness[2][5], Fp::from(0u64));
+}
+
+#[test]
+// Test that the wrong values of the carry and the sum fail their constraints
+fn test_add_u64_bad_constraints() {
+    let (gates, mut witness) = create_add_u64(&[(u64::MAX, 3)]);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    // C1: the carry is not a bit
+    witness[3][0] += Fp::from(1u64);
+    assert_eq!(
+        verify_row(&cs, &witness, 0),
+        Err(CircuitGateError::Constraint(GateType::AddU64, 1))
+    );
+    witness[3][0] -= Fp::from(1u64);
+
+    // C2: the sum is not the addition of the words
+    witness[2][0] += Fp::from(1u64);
+    witness[7][0] += Fp::from(1u64);
+    assert_eq!(
+        verify_row(&cs, &witness, 0),
+        Err(CircuitGateError::Constraint(GateType::AddU64, 2))
+    );
+    witness[2][0] -= Fp::from(1u64);
+    witness[7][0] -= Fp::from(1u64);
+
+    // C3: the limbs are not the decomposition of the sum
+    witness[10][0] += Fp::from(1u64);
+    assert_eq!(
+        verify_row(&cs, &witness, 0),
+        Err(CircuitGateError::Constraint(GateType::AddU64, 3))
+    );
+    witness[10][0] -= Fp::from(1u64);
+
+    assert_eq!(verify_row(&cs, &witness, 0), Ok(()));
+}
+
+#[test]
+// Test the proof of random additions
+fn test_add_u64_proof() {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    let words: Vec<(u64, u64)> = (0..8).map(|_| (rng.gen(), rng.gen())).collect();
+    let (gates, witness) = create_add_u64(&words);
+    prove_and_verify(gates, witness).unwrap();
+}
+
+#[test]
+// Test that the sum cannot skip the carry by overflowing 64 bits
+fn test_add_u64_sum_overflow() {
+    let (a, b) = (u64::MAX, 5);
+    let (gates, mut witness) = create_add_u64(&[(a, b)]);
+
+    // the carry is zero, and the sum is the integer a + b,
+    // whose most significant limb is above 16 bits
+    let sum = Fp::from(a) + Fp::from(b);
+    witness[2][0] = sum;
+    witness[3][0] = Fp::from(0u64);
+    witness[10][0] += Fp::from(1u64 << 16);
+    // the constraints hold, but not the lookup of the limb
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+    assert_eq!(verify_row(&cs, &witness, 0), Ok(()));
+    assert!(prove_and_verify(gates, witness).is_err());
+}
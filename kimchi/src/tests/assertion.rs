@@ -0,0 +1,127 @@
+use std::array;
+
+use super::{ecdsa::verify_rows, framework::TestFramework};
+use crate::{
+    circuit,
+    circuits::{
+        constraints::ConstraintSystem, gate::CircuitGate, polynomial::COLUMNS,
+        polynomials::assertion,
+    },
+};
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+const CONSTANT: u64 = 0xc0ffee;
+
+circuit! {
+    /// Asserts that a public input is nonzero, and that a private input is a constant
+    mod nonzero_constant {
+        public { value }
+        private { word }
+        gadget nonzero {
+            gates(gates, _layout) => CircuitGate::extend_assert_nonzero(gates, 1),
+            witness(witness, public, _private) => {
+                assertion::extend_assert_nonzero_witness(witness, &[public.value])
+            },
+        }
+        gadget constant {
+            gates(gates, _layout) => {
+                CircuitGate::extend_assert_constant(gates, &[From::from(CONSTANT)])
+            },
+            witness(witness, _public, private) => {
+                assertion::extend_assert_constant_witness(witness, &[private.word])
+            },
+        }
+        copy {
+            (value, 0, 0) == (nonzero, 0, 0),
+        }
+    }
+}
+
+#[test]
+// Test that the assertions of a circuit are proven, and that a zero public input is not
+fn test_assertion_circuit() {
+    let (layout, gates) = nonzero_constant::gates::<Fp>();
+    assert_eq!(layout.num_rows, 3);
+    let private = nonzero_constant::Private {
+        word: Fp::from(CONSTANT),
+    };
+    let public = nonzero_constant::Public {
+        value: Fp::from(42u32),
+    };
+    let witness = nonzero_constant::witness(&public, &private);
+
+    TestFramework::<Vesta>::default()
+        .gates(gates.clone())
+        .witness(witness)
+        .public_inputs(public.to_vec())
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+
+    let public = nonzero_constant::Public {
+        value: Fp::from(0u32),
+    };
+    let witness = nonzero_constant::witness(&public, &private);
+    let res = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(public.to_vec())
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>();
+    assert!(res.is_err());
+}
+
+#[test]
+// Test the assertions that cells are nonzero, two per row
+fn test_assert_nonzero() {
+    let (next_row, gates) = CircuitGate::<Fp>::create_assert_nonzero(0, 3);
+    assert_eq!(next_row, 2);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    let values = [Fp::from(1u32), Fp::from(2u32), -Fp::from(3u32)];
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    assert!(assertion::extend_assert_nonzero_witness(
+        &mut witness,
+        &values
+    ));
+    assert_eq!(witness[0].len(), next_row);
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+    let (row, col) = assertion::assertion_input(0, 2);
+    assert_eq!(witness[col][row], values[2]);
+
+    for zero in 0..3 {
+        let mut values = values;
+        values[zero] = Fp::from(0u32);
+        let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+        assert!(!assertion::extend_assert_nonzero_witness(
+            &mut witness,
+            &values
+        ));
+        assert!(verify_rows(&cs, &witness).is_err());
+    }
+}
+
+#[test]
+// Test the assertions that cells are equal to constants
+fn test_assert_constant() {
+    let constants = [Fp::from(0u32), Fp::from(CONSTANT)];
+    let (next_row, gates) = CircuitGate::create_assert_constant(0, &constants);
+    assert_eq!(next_row, 1);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    assertion::extend_assert_constant_witness(&mut witness, &constants);
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    assertion::extend_assert_constant_witness(&mut witness, &[constants[0], constants[0]]);
+    assert!(verify_rows(&cs, &witness).is_err());
+}
@@ -0,0 +1,95 @@
+use super::{ecdsa::verify_rows, framework::TestFramework};
+use crate::circuits::{
+    builder::CircuitBuilder,
+    constraints::ConstraintSystem,
+    gate::{CircuitGateError, GateType},
+    polynomials::rot::RotMode,
+};
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+// Builds the circuit checking that the public output is the rotation of the XOR of the public words
+fn xor_rot(values: Option<[u64; 3]>) -> CircuitBuilder<Fp> {
+    let mut builder = CircuitBuilder::new(values.is_some());
+    let [left, right, output] =
+        std::array::from_fn(|i| builder.public_input(values.map(|values| values[i].into())));
+    let xor = builder.xor(left, right, 64);
+    let rotated = builder.rot64(xor, 21, RotMode::Right);
+    builder.assert_equal(rotated, output);
+    builder
+}
+
+#[test]
+// Test that a circuit of variables has the same gates with and without witness, and is proven
+fn test_builder_xor_rot() {
+    let (left, right) = (0xDC811727DAF22EC1u64, 0x0123456789ABCDEFu64);
+    let output = (left ^ right).rotate_right(21);
+    let (gates, witness) = xor_rot(None).build();
+    assert!(witness.is_none());
+    let builder = xor_rot(Some([left, right, output]));
+    assert_eq!(builder.public(), 3);
+    let (witness_gates, witness) = builder.build();
+    let witness = witness.unwrap();
+    assert_eq!(witness[0].len(), gates.len());
+    for (gate, witness_gate) in gates.iter().zip(&witness_gates) {
+        assert_eq!(gate.typ, witness_gate.typ);
+        assert_eq!(gate.wires, witness_gate.wires);
+        assert_eq!(gate.coeffs, witness_gate.coeffs);
+    }
+
+    let cs = ConstraintSystem::create(gates.clone())
+        .public(3)
+        .build()
+        .unwrap();
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness.clone())
+        .public_inputs(witness[0][0..3].to_vec())
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+// Test that the equality of variables is a copy constraint
+fn test_builder_wrong_output() {
+    let (left, right) = (0xDC811727DAF22EC1u64, 0x0123456789ABCDEFu64);
+    let output = (left ^ right).rotate_left(21);
+    let (gates, witness) = xor_rot(Some([left, right, output])).build();
+    let cs = ConstraintSystem::create(gates).public(3).build().unwrap();
+    assert!(matches!(
+        verify_rows(&cs, &witness.unwrap()),
+        Err(CircuitGateError::CopyConstraint { .. })
+    ));
+}
+
+#[test]
+// Test the generic operations, two per generic gate, and the constants shared by their uses
+fn test_builder_generic() {
+    let mut builder = CircuitBuilder::<Fp>::new(true);
+    let x = builder.private_input(Some(Fp::from(6u64)));
+    let y = builder.private_input(Some(Fp::from(7u64)));
+    let five = builder.constant(Fp::from(5u64));
+    assert_eq!(builder.constant(Fp::from(5u64)), five);
+    let product = builder.mul(x, y);
+    let sum = builder.add(product, five);
+    let difference = builder.sub(sum, x);
+    let bit = builder.private_input(Some(Fp::from(1u64)));
+    builder.assert_boolean(bit);
+    assert_eq!(builder.value(difference), Some(Fp::from(41u64)));
+    assert_eq!(builder.num_rows(), 4);
+
+    let (gates, witness) = builder.build();
+    assert!(gates.iter().all(|gate| gate.typ == GateType::Generic));
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+    assert_eq!(verify_rows(&cs, &witness.unwrap()), Ok(()));
+}
@@ -0,0 +1,225 @@
+use crate::{
+    caml::v1::*,
+    circuits::{
+        gate::{CircuitGate, GateType},
+        polynomials::generic::testing::{create_circuit, fill_in_witness},
+        wires::{Wire, COLUMNS},
+    },
+    proof::{PointEvaluations, ProverProof},
+    prover_index::testing::new_index_for_test,
+    verifier::verify,
+};
+use ark_ff::Zero;
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{caml::CamlScalarChallenge, DefaultFqSponge, DefaultFrSponge},
+};
+use num_traits::FromPrimitive;
+use poly_commitment::commitment::{
+    caml::{CamlOpeningProof, CamlPolyComm},
+    CommitmentCurve,
+};
+use std::array;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+type Evals = PointEvaluations<Vec<Fp>>;
+
+#[test]
+// Test that the gate types of the version keep their discriminants
+fn test_caml_v1_gate_types() {
+    let discriminants = [
+        0, 1, 2, 3, 4, 5, 6, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+    ];
+    for (typ, discriminant) in GATE_TYPES.into_iter().zip(discriminants) {
+        assert_eq!(typ as u32, discriminant);
+        assert_eq!(GateType::from_u32(discriminant), Some(typ));
+    }
+}
+
+// The fields of the types of the version: destructuring them without `..`
+// fails to compile if a field is added, removed or changes type
+#[allow(dead_code, clippy::type_complexity)]
+mod shapes {
+    use super::*;
+
+    fn wire(CamlWire { row, col }: CamlWire) -> (ocaml::Int, ocaml::Int) {
+        (row, col)
+    }
+
+    fn gate(CamlCircuitGate { typ, wires, coeffs }: CamlCircuitGate<Fp>) {
+        let _: (GateType, Vec<Fp>) = (typ, coeffs);
+        let (w0, w1, w2, w3, w4, w5, w6) = wires;
+        let _: [CamlWire; 7] = [w0, w1, w2, w3, w4, w5, w6];
+    }
+
+    fn recursion_challenge(
+        CamlRecursionChallenge { chals, comm }: CamlRecursionChallenge<Vesta, Fp>,
+    ) -> (Vec<Fp>, CamlPolyComm<Vesta>) {
+        (chals, comm)
+    }
+
+    fn lookup_evaluations(
+        CamlLookupEvaluations {
+            sorted,
+            aggreg,
+            table,
+            runtime,
+        }: CamlLookupEvaluations<Fp>,
+    ) -> (Vec<Evals>, Evals, Evals, Option<Evals>) {
+        (sorted, aggreg, table, runtime)
+    }
+
+    fn proof_evaluations(
+        CamlProofEvaluations {
+            w,
+            z,
+            s,
+            coefficients,
+            lookup,
+            generic_selector,
+            poseidon_selector,
+        }: CamlProofEvaluations<Fp>,
+    ) {
+        let (w0, w1, w2, w3, w4, w5, w6, w7, w8, w9, w10, w11, w12, w13, w14) = w;
+        let _: [Evals; COLUMNS] = [
+            w0, w1, w2, w3, w4, w5, w6, w7, w8, w9, w10, w11, w12, w13, w14,
+        ];
+        let (c0, c1, c2, c3, c4, c5, c6, c7, c8, c9, c10, c11, c12, c13, c14) = coefficients;
+        let _: [Evals; COLUMNS] = [
+            c0, c1, c2, c3, c4, c5, c6, c7, c8, c9, c10, c11, c12, c13, c14,
+        ];
+        let (s0, s1, s2, s3, s4, s5) = s;
+        let _: [Evals; 9] = [
+            s0,
+            s1,
+            s2,
+            s3,
+            s4,
+            s5,
+            z,
+            generic_selector,
+            poseidon_selector,
+        ];
+        let _: Option<CamlLookupEvaluations<Fp>> = lookup;
+    }
+
+    fn lookup_commitments(
+        CamlLookupCommitments {
+            sorted,
+            aggreg,
+            runtime,
+        }: CamlLookupCommitments<Vesta>,
+    ) -> (
+        Vec<CamlPolyComm<Vesta>>,
+        CamlPolyComm<Vesta>,
+        Option<CamlPolyComm<Vesta>>,
+    ) {
+        (sorted, aggreg, runtime)
+    }
+
+    fn prover_commitments(
+        CamlProverCommitments {
+            w_comm,
+            z_comm,
+            t_comm,
+            lookup,
+        }: CamlProverCommitments<Vesta>,
+    ) {
+        let (w0, w1, w2, w3, w4, w5, w6, w7, w8, w9, w10, w11, w12, w13, w14) = w_comm;
+        let _: [CamlPolyComm<Vesta>; COLUMNS + 2] = [
+            w0, w1, w2, w3, w4, w5, w6, w7, w8, w9, w10, w11, w12, w13, w14, z_comm, t_comm,
+        ];
+        let _: Option<CamlLookupCommitments<Vesta>> = lookup;
+    }
+
+    fn prover_proof(
+        CamlProverProof {
+            commitments,
+            proof,
+            evals,
+            ft_eval1,
+            public,
+            prev_challenges,
+        }: CamlProverProof<Vesta, Fp>,
+    ) {
+        let _: CamlProverCommitments<Vesta> = commitments;
+        let _: CamlOpeningProof<Vesta, Fp> = proof;
+        let _: CamlProofEvaluations<Fp> = evals;
+        let _: (Fp, Vec<Fp>) = (ft_eval1, public);
+        let _: Vec<CamlRecursionChallenge<Vesta, Fp>> = prev_challenges;
+    }
+
+    fn random_oracles(
+        CamlRandomOracles {
+            joint_combiner,
+            beta,
+            gamma,
+            alpha_chal,
+            alpha,
+            zeta,
+            v,
+            u,
+            zeta_chal,
+            v_chal,
+            u_chal,
+        }: CamlRandomOracles<Fp>,
+    ) {
+        let _: Option<(CamlScalarChallenge<Fp>, Fp)> = joint_combiner;
+        let _: [Fp; 6] = [beta, gamma, alpha, zeta, v, u];
+        let _: [CamlScalarChallenge<Fp>; 4] = [alpha_chal, zeta_chal, v_chal, u_chal];
+    }
+
+    fn oracles(
+        CamlOracles {
+            o,
+            public_evals,
+            opening_prechallenges,
+            digest_before_evaluations,
+        }: CamlOracles<Fp>,
+    ) {
+        let _: CamlRandomOracles<Fp> = o;
+        let _: ((Fp, Fp), Vec<Fp>, Fp) = (
+            public_evals,
+            opening_prechallenges,
+            digest_before_evaluations,
+        );
+    }
+}
+
+#[test]
+// Test that the gates and the proofs are converted to the version and back without loss
+fn test_caml_v1_roundtrip() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    for gate in &gates {
+        let converted: CircuitGate<Fp> = CamlCircuitGate::<Fp>::from(gate).into();
+        assert_eq!(converted.typ, gate.typ);
+        assert_eq!(converted.wires, gate.wires);
+        assert_eq!(converted.coeffs, gate.coeffs);
+    }
+    let wire = Wire { row: 3, col: 5 };
+    assert_eq!(Wire::from(CamlWire::from(wire)), wire);
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+    let index = new_index_for_test(gates, public.len());
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], &index).unwrap();
+
+    let caml_proof = CamlProverProof::<Vesta, Fp>::from((proof, public.clone()));
+    let (proof, caml_public): (ProverProof<Vesta>, Vec<Fp>) = caml_proof.into();
+    assert_eq!(caml_public, public);
+    verify::<Vesta, BaseSponge, ScalarSponge>(
+        &group_map,
+        &index.verifier_index(),
+        &proof,
+        &caml_public,
+    )
+    .unwrap();
+}
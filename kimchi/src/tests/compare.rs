@@ -0,0 +1,329 @@
+use std::array;
+
+use super::framework::TestFramework;
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::CircuitGate,
+    polynomial::COLUMNS,
+    polynomials::{
+        compare::{
+            self, abs_output, less_than_output, sign_output, INTERVAL_ROWS, LESS_THAN_CONSTANT_ROWS,
+        },
+        generic::GenericGateSpec,
+    },
+    wires::Wire,
+};
+use ark_ff::Zero;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+const RNG_SEED: [u8; 32] = [
+    44, 109, 18, 201, 3, 87, 250, 61, 132, 9, 178, 26, 93, 240, 117, 5, 64, 199, 38, 152, 71, 220,
+    13, 186, 97, 30, 245, 123, 56, 167, 8, 211,
+];
+
+// A zero row, used by the 64-bit range checks of the gadgets
+fn zero_row() -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS]) {
+    let gates = vec![CircuitGate::<Fp>::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )];
+    (gates, array::from_fn(|_| vec![Fp::zero()]))
+}
+
+// Encodes a signed integer as a field element
+fn signed(value: i64) -> Fp {
+    if value < 0 {
+        -Fp::from(value.unsigned_abs())
+    } else {
+        Fp::from(value as u64)
+    }
+}
+
+// Checks that the witness satisfies the constraints of the gates
+fn check_witness(gates: Vec<CircuitGate<Fp>>, witness: &[Vec<Fp>; COLUMNS]) {
+    assert_eq!(witness[0].len(), gates.len());
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+    for row in 0..cs.gates.len() {
+        assert_eq!(
+            cs.gates[row].verify_witness::<Vesta>(row, witness, &cs, &witness[0][0..cs.public]),
+            Ok(())
+        );
+    }
+}
+
+// Creates the gates and witness of a less-than preceded by a zero row
+fn create_less_than(left: Fp, right: Fp, bits: u32) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS]) {
+    let (mut gates, mut witness) = zero_row();
+    CircuitGate::extend_less_than(&mut gates, bits, 0);
+    compare::extend_less_than_witness(&mut witness, left, right, bits);
+    (gates, witness)
+}
+
+// Creates the gates and witness of a sign and absolute value preceded by a zero row
+fn create_sign_abs(value: Fp, bits: u32) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS]) {
+    let (mut gates, mut witness) = zero_row();
+    CircuitGate::extend_sign_abs(&mut gates, bits, 0);
+    compare::extend_sign_abs_witness(&mut witness, value, bits);
+    (gates, witness)
+}
+
+// Checks the output and the constraints of a less-than
+fn test_less_than(left: Fp, right: Fp, bits: u32, expected: bool) {
+    let (gates, witness) = create_less_than(left, right, bits);
+    let (row, col) = less_than_output(1);
+    assert_eq!(witness[col][row], Fp::from(expected));
+    check_witness(gates, &witness);
+}
+
+// Checks the outputs and the constraints of a sign and absolute value
+fn test_sign_abs(value: i64, bits: u32) {
+    let (gates, witness) = create_sign_abs(signed(value), bits);
+    let (row, col) = sign_output(1);
+    assert_eq!(witness[col][row], Fp::from(value < 0));
+    let (row, col) = abs_output(1);
+    assert_eq!(witness[col][row], Fp::from(value.unsigned_abs()));
+    check_witness(gates, &witness);
+}
+
+#[test]
+// Test random comparisons of unsigned integers, for several lengths
+fn test_less_than_unsigned() {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    for bits in [1, 8, 32, 63, 64] {
+        let max = (2u128.pow(bits) - 1) as u64;
+        for _ in 0..4 {
+            let left = rng.gen_range(0..=max);
+            let right = rng.gen_range(0..=max);
+            test_less_than(left.into(), right.into(), bits, left < right);
+        }
+        test_less_than(0u64.into(), max.into(), bits, true);
+        test_less_than(max.into(), 0u64.into(), bits, false);
+        test_less_than(max.into(), max.into(), bits, false);
+    }
+}
+
+#[test]
+// Test comparisons of signed integers, including negative values
+fn test_less_than_signed() {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    for bits in [8, 32, 64] {
+        let max = (2i128.pow(bits - 1) - 1) as i64;
+        let min = -max - 1;
+        for _ in 0..4 {
+            let left = rng.gen_range(min..=max);
+            let right = rng.gen_range(min..=max);
+            test_less_than(signed(left), signed(right), bits, left < right);
+        }
+        test_less_than(signed(min), signed(max), bits, true);
+        test_less_than(signed(-1), signed(0), bits, true);
+        test_less_than(signed(0), signed(-1), bits, false);
+        test_less_than(signed(min), signed(min), bits, false);
+    }
+}
+
+#[test]
+// Test the sign and absolute value of signed integers, including the extreme values
+fn test_sign_abs_values() {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    for bits in [2, 16, 64] {
+        let max = (2i128.pow(bits - 1) - 1) as i64;
+        let min = -max - 1;
+        for value in [min, -1, 0, 1, max, rng.gen_range(min..=max)] {
+            test_sign_abs(value, bits);
+        }
+    }
+}
+
+#[test]
+// End-to-end test
+fn test_prove_and_verify() {
+    let (mut gates, mut witness) = zero_row();
+    CircuitGate::extend_less_than(&mut gates, 32, 0);
+    compare::extend_less_than_witness(&mut witness, signed(-5), signed(3), 32);
+    CircuitGate::extend_sign_abs(&mut gates, 32, 0);
+    compare::extend_sign_abs_witness(&mut witness, signed(-5), 32);
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+// Test that a wrong comparison bit is rejected
+fn test_bad_less_than() {
+    let (gates, mut witness) = create_less_than(3u64.into(), 5u64.into(), 16);
+    let (row, col) = less_than_output(1);
+    witness[col][row] = Fp::zero();
+
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+    assert!(cs.gates[row]
+        .verify_witness::<Vesta>(row, &witness, &cs, &witness[0][0..cs.public])
+        .is_err());
+
+    let res = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>();
+    assert!(res.is_err());
+}
+
+#[test]
+// Test that a wrong sign is rejected
+fn test_bad_sign() {
+    let (gates, mut witness) = create_sign_abs(signed(-7), 8);
+    let (row, col) = sign_output(1);
+    witness[col][row] = Fp::zero();
+
+    let res = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>();
+    assert!(res.is_err());
+}
+
+#[should_panic]
+#[test]
+// Test that a comparison of larger integers fails as expected
+fn test_large_input() {
+    let (_, mut witness) = zero_row();
+    compare::extend_less_than_witness(&mut witness, Fp::from(1u64 << 8), Fp::zero(), 8);
+}
+
+#[should_panic]
+#[test]
+// Test that comparisons of more than 64 bits fail as expected
+fn test_large_bits() {
+    CircuitGate::<Fp>::create_less_than(0, 65);
+}
+
+// Creates the gates and witness of a less-than-constant preceded by a zero row
+fn create_less_than_constant(
+    value: u64,
+    constant: u64,
+) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS], bool) {
+    let (mut gates, mut witness) = zero_row();
+    CircuitGate::extend_less_than_constant(&mut gates, constant, 0);
+    let lt = compare::extend_less_than_constant_witness(&mut witness, value.into(), constant);
+    assert_eq!(gates.len(), 1 + LESS_THAN_CONSTANT_ROWS);
+    (gates, witness, lt)
+}
+
+// Creates the gates and witness of an interval preceded by a zero row
+fn create_interval(
+    value: u64,
+    low: u64,
+    high: u64,
+) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS], bool) {
+    let (mut gates, mut witness) = zero_row();
+    CircuitGate::extend_interval(&mut gates, low, high, 0);
+    let inside = compare::extend_interval_witness(&mut witness, value.into(), low, high);
+    assert_eq!(gates.len(), 1 + INTERVAL_ROWS);
+    (gates, witness, inside)
+}
+
+// Checks whether the witness satisfies the constraints of the gates
+fn satisfies(gates: Vec<CircuitGate<Fp>>, witness: &[Vec<Fp>; COLUMNS]) -> bool {
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+    (0..cs.gates.len()).all(|row| {
+        cs.gates[row]
+            .verify_witness::<Vesta>(row, witness, &cs, &witness[0][0..cs.public])
+            .is_ok()
+    })
+}
+
+#[test]
+// Test the assertions of integers less than constants, around the constants and the bounds of 64 bits
+fn test_less_than_constant() {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    for constant in [1, 2, 1000, 1 << 32, u64::MAX, rng.gen_range(1..u64::MAX)] {
+        for value in [
+            0,
+            constant - 1,
+            constant,
+            constant.saturating_add(1),
+            u64::MAX,
+        ] {
+            let (gates, witness, lt) = create_less_than_constant(value, constant);
+            assert_eq!(lt, value < constant);
+            assert_eq!(satisfies(gates, &witness), lt);
+        }
+    }
+}
+
+#[test]
+// Test the assertions of integers in intervals, around the bounds of the intervals and of 64 bits
+fn test_interval() {
+    for (low, high) in [
+        (0, 0),
+        (5, 5),
+        (3, 1000),
+        (0, u64::MAX),
+        (1 << 40, u64::MAX),
+    ] {
+        for value in [
+            0,
+            low.saturating_sub(1),
+            low,
+            high,
+            high.saturating_add(1),
+            u64::MAX,
+        ] {
+            let (gates, witness, inside) = create_interval(value, low, high);
+            assert_eq!(inside, low <= value && value <= high);
+            assert_eq!(satisfies(gates, &witness), inside);
+        }
+    }
+}
+
+#[test]
+// End-to-end test of the constant bounds, and of a value out of the interval
+fn test_constant_bounds_prove_and_verify() {
+    let (mut gates, mut witness) = zero_row();
+    CircuitGate::extend_less_than_constant(&mut gates, 100, 0);
+    compare::extend_less_than_constant_witness(&mut witness, Fp::from(99u64), 100);
+    CircuitGate::extend_interval(&mut gates, 10, 20, 0);
+    compare::extend_interval_witness(&mut witness, Fp::from(10u64), 10, 20);
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+
+    let (gates, witness, _) = create_interval(21, 10, 20);
+    let res = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>();
+    assert!(res.is_err());
+}
+
+#[should_panic]
+#[test]
+// Test that the constant of a less-than-constant must be positive
+fn test_less_than_constant_zero() {
+    CircuitGate::<Fp>::create_less_than_constant(0, 0);
+}
+
+#[should_panic]
+#[test]
+// Test that the bounds of an interval must be in order
+fn test_interval_bounds_order() {
+    CircuitGate::<Fp>::create_interval(0, 2, 1);
+}
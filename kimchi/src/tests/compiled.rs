@@ -0,0 +1,119 @@
+use crate::{
+    circuits::{
+        compiled::{CompiledCircuit, CIRCUIT_FORMAT_VERSION, CIRCUIT_MAGIC},
+        lookup::{
+            runtime_tables::{RuntimeTableCfg, RuntimeTableSpec},
+            tables::LookupTable,
+        },
+        polynomials::generic::testing::{create_circuit, fill_in_witness},
+        wires::{Wire, COLUMNS},
+    },
+    domain_separation::DomainSeparator,
+    error::CircuitFormatError,
+    proof::ProverProof,
+    prover_index::testing::new_index_for_test_with_cs,
+    verifier::verify,
+};
+use ark_ff::Zero;
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use poly_commitment::commitment::CommitmentCurve;
+use std::array;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+#[test]
+// Test that a loaded circuit has the configuration of the stored one, and the same verifier index
+fn test_compiled_circuit_roundtrip() {
+    let mut circuit = CompiledCircuit::new(create_circuit::<Fp>(0, 2));
+    circuit.public = 1;
+    circuit.public_outputs = vec![Wire { row: 2, col: 2 }];
+    circuit.lookup_tables = vec![LookupTable {
+        id: 10,
+        data: vec![(0..4u64).map(Fp::from).collect()],
+    }];
+    circuit.runtime_tables = Some(vec![
+        RuntimeTableCfg::Indexed(RuntimeTableSpec { id: 11, len: 4 }),
+        RuntimeTableCfg::Custom {
+            id: 12,
+            first_column: vec![Fp::from(7u64), -Fp::from(1u64)],
+        },
+    ]);
+    circuit.domain_separator = Some(DomainSeparator::new(b"test").unwrap());
+    circuit.quotient_chunks = 8;
+
+    let bytes = circuit.to_bytes();
+    assert_eq!(&bytes[..4], CIRCUIT_MAGIC);
+    assert_eq!(bytes[4..8], CIRCUIT_FORMAT_VERSION.to_le_bytes());
+    let loaded = CompiledCircuit::<Fp>::from_bytes(&bytes).unwrap();
+    assert_eq!(loaded.to_bytes(), bytes);
+    assert_eq!(loaded.public_outputs, circuit.public_outputs);
+    assert_eq!(loaded.lookup_tables, circuit.lookup_tables);
+    assert_eq!(loaded.domain_separator, circuit.domain_separator);
+    for (gate, loaded_gate) in circuit.gates.iter().zip(&loaded.gates) {
+        assert_eq!(gate.typ, loaded_gate.typ);
+        assert_eq!(gate.wires, loaded_gate.wires);
+        assert_eq!(gate.coeffs, loaded_gate.coeffs);
+    }
+
+    let index = new_index_for_test_with_cs::<Vesta>(circuit.build().unwrap());
+    let loaded_index = new_index_for_test_with_cs::<Vesta>(loaded.build().unwrap());
+    assert_eq!(loaded_index.cs.public, 2);
+    assert_eq!(
+        index.verifier_index().digest::<BaseSponge>(),
+        loaded_index.verifier_index().digest::<BaseSponge>()
+    );
+}
+
+#[test]
+// Test that the proofs of a loaded circuit verify with the verifier index of the stored circuit
+fn test_compiled_circuit_prove() {
+    let public = vec![Fp::from(3u8); 5];
+    let mut circuit = CompiledCircuit::new(create_circuit::<Fp>(0, public.len()));
+    circuit.public = public.len();
+    let loaded = CompiledCircuit::<Fp>::from_bytes(&circuit.to_bytes()).unwrap();
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); loaded.gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+    let index = new_index_for_test_with_cs::<Vesta>(loaded.build().unwrap());
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], &index).unwrap();
+
+    let verifier_index =
+        new_index_for_test_with_cs::<Vesta>(circuit.build().unwrap()).verifier_index();
+    verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof, &public)
+        .unwrap();
+}
+
+#[test]
+// Test that the bytes of another format or version are rejected
+fn test_compiled_circuit_invalid_bytes() {
+    let bytes = CompiledCircuit::new(create_circuit::<Fp>(0, 0)).to_bytes();
+    assert_eq!(
+        CompiledCircuit::<Fp>::from_bytes(&bytes[..6]).unwrap_err(),
+        CircuitFormatError::NotACircuit
+    );
+    let mut other = bytes.clone();
+    other[0] ^= 1;
+    assert_eq!(
+        CompiledCircuit::<Fp>::from_bytes(&other).unwrap_err(),
+        CircuitFormatError::NotACircuit
+    );
+    let mut future = bytes.clone();
+    future[4..8].copy_from_slice(&(CIRCUIT_FORMAT_VERSION + 1).to_le_bytes());
+    assert_eq!(
+        CompiledCircuit::<Fp>::from_bytes(&future).unwrap_err(),
+        CircuitFormatError::UnsupportedVersion(CIRCUIT_FORMAT_VERSION + 1)
+    );
+    assert!(matches!(
+        CompiledCircuit::<Fp>::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(CircuitFormatError::Decoding(_))
+    ));
+}
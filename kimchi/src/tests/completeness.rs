@@ -0,0 +1,297 @@
+//! Automated search of counterexamples to the completeness of gadgets:
+//! inputs of the declared domain of a gadget for which its witness builder fails,
+//! or builds a witness which does not satisfy the circuit.
+//!
+//! The searches below run with a few samples by default.
+//! Set `KIMCHI_COMPLETENESS_SAMPLES` to run them with more, as the nightly workflow does.
+
+use super::{
+    foreign_field_add::{full_circuit, long_witness, secp256k1_modulus},
+    rot::{create_rot_gadget, create_rot_witness},
+};
+use crate::{
+    circuits::{
+        constraints::GateError,
+        gate::{CircuitGate, VerifyPrecomputations},
+        polynomial::COLUMNS,
+        polynomials::{
+            foreign_field_add::witness::FFOps,
+            rot::RotMode,
+            xor::{self},
+        },
+    },
+    prover_index::{testing::new_index_for_test, ProverIndex},
+};
+use ark_ff::Zero;
+use mina_curves::pasta::{Fp, Fq, Vesta};
+use num_bigint::{BigUint, RandBigInt};
+use o1_utils::FieldHelpers;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    any::Any,
+    env,
+    fmt::Debug,
+    panic::{self, AssertUnwindSafe},
+};
+
+const RNG_SEED: [u8; 32] = [
+    98, 13, 250, 7, 141, 66, 201, 35, 172, 19, 88, 240, 3, 115, 60, 154, 229, 41, 77, 186, 10, 133,
+    251, 92, 48, 170, 27, 209, 125, 64, 5, 143,
+];
+
+// Default number of inputs sampled by each search
+const DEFAULT_SAMPLES: usize = 16;
+
+// Number of inputs sampled by each search
+fn num_samples() -> usize {
+    env::var("KIMCHI_COMPLETENESS_SAMPLES")
+        .ok()
+        .and_then(|samples| samples.parse().ok())
+        .unwrap_or(DEFAULT_SAMPLES)
+}
+
+/// A counterexample to the completeness of a gadget
+#[derive(Debug)]
+pub(crate) enum Counterexample<I> {
+    /// The witness builder panicked
+    Panic { input: I, message: String },
+    /// The columns of the witness do not have a row for each gate of the circuit
+    Length {
+        input: I,
+        rows: Vec<usize>,
+        gates: usize,
+    },
+    /// A row of the witness does not satisfy the circuit
+    Row { input: I, err: GateError },
+}
+
+/// A gadget, described by the domain of its inputs, its circuit and its witness builder.
+/// The circuit of an input only depends on its shape (for example the length of the inputs),
+/// so that the circuit of a shape is only set up once.
+pub(crate) struct Gadget<I, S> {
+    /// The number of public inputs of the circuit
+    pub public: usize,
+    /// Samples an input from the domain of the gadget
+    pub sample: fn(&mut StdRng) -> I,
+    /// The shape of the circuit for an input
+    pub shape: fn(&I) -> S,
+    /// The circuit of a shape
+    pub circuit: fn(&S) -> Vec<CircuitGate<Fp>>,
+    /// The witness builder
+    pub witness: fn(&I) -> [Vec<Fp>; COLUMNS],
+}
+
+// Formats the payload of a panic
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+impl<I: Clone + Debug, S: PartialEq> Gadget<I, S> {
+    /// Samples `samples` inputs, and returns the counterexamples found for them
+    pub(crate) fn search(&self, rng: &mut StdRng, samples: usize) -> Vec<Counterexample<I>> {
+        // the number of gates and the index of each shape
+        let mut indexes: Vec<(S, usize, ProverIndex<Vesta>)> = vec![];
+        let mut counterexamples = vec![];
+
+        for _ in 0..samples {
+            let input = (self.sample)(rng);
+
+            let witness = match panic::catch_unwind(AssertUnwindSafe(|| (self.witness)(&input))) {
+                Ok(witness) => witness,
+                Err(payload) => {
+                    counterexamples.push(Counterexample::Panic {
+                        input,
+                        message: panic_message(payload),
+                    });
+                    continue;
+                }
+            };
+
+            let shape = (self.shape)(&input);
+            let position = match indexes.iter().position(|(other, _, _)| *other == shape) {
+                Some(position) => position,
+                None => {
+                    let gates = (self.circuit)(&shape);
+                    let num_gates = gates.len();
+                    indexes.push((shape, num_gates, new_index_for_test(gates, self.public)));
+                    indexes.len() - 1
+                }
+            };
+            let (_, gates, index) = &indexes[position];
+            let gates = *gates;
+
+            if witness.iter().any(|col| col.len() != gates) {
+                counterexamples.push(Counterexample::Length {
+                    input,
+                    rows: witness.iter().map(Vec::len).collect(),
+                    gates,
+                });
+                continue;
+            }
+
+            let public = &witness[0][..self.public];
+            let padded = index.pad_witness(&witness);
+            let precomputations = VerifyPrecomputations::create(&index.cs.gates);
+            for row in 0..gates {
+                if let Err(err) = index.verify_row(row, &padded, public, &precomputations) {
+                    counterexamples.push(Counterexample::Row {
+                        input: input.clone(),
+                        err,
+                    });
+                }
+            }
+        }
+
+        counterexamples
+    }
+}
+
+// The foreign field additions: a chain of operations on inputs in a foreign field
+fn ffadd_gadget() -> Gadget<(BigUint, Vec<FFOps>, Vec<BigUint>), (BigUint, Vec<FFOps>)> {
+    Gadget {
+        public: 1,
+        sample: |rng| {
+            let moduli = [
+                secp256k1_modulus(),
+                BigUint::from_bytes_be(&secp256k1::constants::CURVE_ORDER),
+                Fq::modulus_biguint(),
+            ];
+            let modulus = moduli[rng.gen_range(0..moduli.len())].clone();
+            let num = rng.gen_range(1..=3);
+            let opcodes = (0..num)
+                .map(|_| if rng.gen() { FFOps::Add } else { FFOps::Sub })
+                .collect();
+            // favor the edges of the domain
+            let inputs = (0..=num)
+                .map(|_| match rng.gen_range(0..4) {
+                    0 => BigUint::zero(),
+                    1 => &modulus - 1u64,
+                    _ => rng.gen_biguint_below(&modulus),
+                })
+                .collect();
+            (modulus, opcodes, inputs)
+        },
+        shape: |(modulus, opcodes, _)| (modulus.clone(), opcodes.clone()),
+        circuit: |(modulus, opcodes)| full_circuit(opcodes, modulus).1,
+        witness: |(modulus, opcodes, inputs)| long_witness(inputs, opcodes, modulus.clone()),
+    }
+}
+
+// The rotation of a 64-bit word
+fn rot_gadget() -> Gadget<(u64, u32, RotMode), (u32, RotMode)> {
+    Gadget {
+        public: 0,
+        sample: |rng| {
+            let word = match rng.gen_range(0..4) {
+                0 => 0,
+                1 => u64::MAX,
+                _ => rng.gen(),
+            };
+            let side = if rng.gen() {
+                RotMode::Left
+            } else {
+                RotMode::Right
+            };
+            (word, rng.gen_range(1..64), side)
+        },
+        shape: |(_, rot, side)| (*rot, *side),
+        circuit: |(rot, side)| create_rot_gadget::<Vesta>(*rot, *side),
+        witness: |(word, rot, side)| create_rot_witness::<Vesta>(*word, *rot, *side),
+    }
+}
+
+// The xor of two words of up to 254 bits
+fn xor_gadget() -> Gadget<(usize, Fp, Fp), usize> {
+    Gadget {
+        public: 0,
+        sample: |rng| {
+            let bits = rng.gen_range(1..=254);
+            let max = BigUint::from(2u64).pow(bits as u32);
+            let mut input = || match rng.gen_range(0..4) {
+                0 => Fp::zero(),
+                1 => Fp::from(&max - 1u64),
+                _ => Fp::from(rng.gen_biguint_below(&max)),
+            };
+            (bits, input(), input())
+        },
+        shape: |(bits, _, _)| *bits,
+        circuit: |bits| {
+            let mut gates = vec![];
+            CircuitGate::extend_xor_gadget(&mut gates, *bits);
+            gates
+        },
+        witness: |(bits, input1, input2)| xor::create_xor_witness(*input1, *input2, *bits),
+    }
+}
+
+// Asserts that the search of a gadget does not find any counterexample
+fn assert_complete<I: Clone + Debug, S: PartialEq>(gadget: Gadget<I, S>) {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    let counterexamples = gadget.search(rng, num_samples());
+    assert!(
+        counterexamples.is_empty(),
+        "found counterexamples: {counterexamples:#?}"
+    );
+}
+
+#[test]
+fn test_ffadd_completeness() {
+    assert_complete(ffadd_gadget());
+}
+
+#[test]
+fn test_rot_completeness() {
+    assert_complete(rot_gadget());
+}
+
+#[test]
+fn test_xor_completeness() {
+    assert_complete(xor_gadget());
+}
+
+#[test]
+// Test that the search reports wrong witnesses and failing witness builders
+fn test_counterexamples() {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+
+    // a witness builder that rotates to the wrong side
+    let wrong_side = Gadget {
+        witness: |(word, rot, side)| {
+            let side = if *side == RotMode::Left {
+                RotMode::Right
+            } else {
+                RotMode::Left
+            };
+            create_rot_witness::<Vesta>(*word, *rot, side)
+        },
+        // only the words whose bits repeat every 2 bits (such as 0) are rotated by 1 to the same
+        // word on both sides, which the random odd words sampled are almost never
+        sample: |rng| (rng.gen::<u64>() | 1, 1, RotMode::Left),
+        ..rot_gadget()
+    };
+    let counterexamples = wrong_side.search(rng, 4);
+    assert!(!counterexamples.is_empty());
+    assert!(counterexamples
+        .iter()
+        .all(|counterexample| matches!(counterexample, Counterexample::Row { .. })));
+
+    // a domain including inputs longer than the length of the gadget
+    let too_large = Gadget {
+        sample: |rng| {
+            let bits = rng.gen_range(1..=128);
+            (bits, Fp::two_pow(bits as u64), Fp::zero())
+        },
+        ..xor_gadget()
+    };
+    let counterexamples = too_large.search(rng, 4);
+    assert_eq!(counterexamples.len(), 4);
+    assert!(counterexamples
+        .iter()
+        .all(|counterexample| matches!(counterexample, Counterexample::Panic { .. })));
+}
@@ -0,0 +1,222 @@
+use super::framework::TestFramework;
+use crate::{
+    circuits::{
+        constraints::{ConstraintSystem, GateError, MAX_QUOTIENT_CHUNKS},
+        custom_gate::CustomGate,
+        expr::prologue::*,
+        gate::{CircuitGate, Connect, GateType},
+        polynomial::COLUMNS,
+        polynomials::generic::GenericGateSpec,
+        wires::Wire,
+    },
+    error::SetupError,
+    mock_prover::MockProverFailure,
+};
+use ark_ff::{Field, Zero};
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use std::array;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+// The input of the square gate must not be zero
+fn nonzero_input(row: usize, witness: &[Vec<Fp>; COLUMNS]) -> Result<(), String> {
+    if witness[0][row].is_zero() {
+        return Err(format!("the square gate of row {row} has a zero input"));
+    }
+    Ok(())
+}
+
+// A gate squaring its first cell into its second cell
+pub(crate) fn square_gate() -> CustomGate<Fp> {
+    CustomGate::new(
+        "square",
+        vec![witness_curr(1) - witness_curr(0).square()],
+        2,
+    )
+    .verifier(nonzero_input)
+}
+
+// A gate writing `w0 * w1 + c0` in the first cell of the next row
+pub(crate) fn mul_add_gate() -> CustomGate<Fp> {
+    CustomGate::new(
+        "mul_add",
+        vec![witness_next(0) - (witness_curr(0) * witness_curr(1) + coeff(0))],
+        2,
+    )
+}
+
+// A gate raising its first cell to the power 9 into its second cell
+fn pow9_gate() -> CustomGate<Fp> {
+    CustomGate::new("pow9", vec![witness_curr(1) - witness_curr(0).pow(9)], 9)
+}
+
+// The circuit computing `x^4 * x + 5` from the public input `x`,
+// with the square gate on the rows 1 and 2, and the mul_add gate on the row 3
+pub(crate) fn circuit() -> Vec<CircuitGate<Fp>> {
+    let mut gates = vec![
+        CircuitGate::create_generic_gadget(Wire::for_row(0), GenericGateSpec::Pub, None),
+        CircuitGate::new(GateType::Custom, Wire::for_row(1), vec![]),
+        CircuitGate::new(GateType::Custom, Wire::for_row(2), vec![]),
+        CircuitGate::new(GateType::Custom, Wire::for_row(3), vec![Fp::from(5u64)]),
+        CircuitGate::new(GateType::Zero, Wire::for_row(4), vec![]),
+    ];
+    gates.connect_cell_pair((0, 0), (1, 0));
+    gates.connect_cell_pair((1, 1), (2, 0));
+    gates.connect_cell_pair((2, 1), (3, 0));
+    gates.connect_cell_pair((3, 1), (1, 0));
+    gates
+}
+
+fn witness(x: u64) -> [Vec<Fp>; COLUMNS] {
+    let x = Fp::from(x);
+    let x4 = x.square().square();
+    let rows = [
+        vec![x],
+        vec![x, x.square()],
+        vec![x.square(), x4],
+        vec![x4, x],
+        vec![x4 * x + Fp::from(5u64)],
+    ];
+    array::from_fn(|col| {
+        rows.iter()
+            .map(|row| row.get(col).copied().unwrap_or_else(Fp::zero))
+            .collect()
+    })
+}
+
+fn framework(witness: [Vec<Fp>; COLUMNS]) -> TestFramework<Vesta> {
+    let public = vec![witness[0][0]];
+    TestFramework::<Vesta>::default()
+        .gates(circuit())
+        .witness(witness)
+        .public_inputs(public)
+        .custom_gate(square_gate(), &[1, 2])
+        .custom_gate(mul_add_gate(), &[3])
+}
+
+#[test]
+// Test that a circuit with custom gates is proven and verified
+fn test_custom_gates() {
+    framework(witness(3)).mock_prove().unwrap();
+    framework(witness(3))
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+// Test that a custom gate of degree above 8n, whose constraints are evaluated over the domain d16
+// by the prover, is proven and verified with more chunks of the quotient
+fn test_custom_gate_degree_above_8() {
+    let mut gates = vec![
+        CircuitGate::create_generic_gadget(Wire::for_row(0), GenericGateSpec::Pub, None),
+        CircuitGate::new(GateType::Custom, Wire::for_row(1), vec![]),
+    ];
+    gates.connect_cell_pair((0, 0), (1, 0));
+    let x = Fp::from(3u64);
+    let rows = [vec![x], vec![x, x.pow([9])]];
+    let witness: [Vec<Fp>; COLUMNS] = array::from_fn(|col| {
+        rows.iter()
+            .map(|row| row.get(col).copied().unwrap_or_else(Fp::zero))
+            .collect()
+    });
+    let framework = || {
+        TestFramework::<Vesta>::default()
+            .gates(gates.clone())
+            .witness(witness.clone())
+            .public_inputs(vec![x])
+            .quotient_chunks(MAX_QUOTIENT_CHUNKS)
+            .custom_gate(pow9_gate(), &[1])
+    };
+
+    framework().mock_prove().unwrap();
+    let runner = framework().setup();
+    assert_eq!(runner.prover_index().cs.max_constraint_degree(), 16);
+    runner
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+// Test that a witness failing the constraints of a custom gate is rejected
+fn test_custom_gate_wrong_witness() {
+    let mut wrong = witness(3);
+    wrong[0][4] += Fp::from(1u64);
+
+    assert_eq!(
+        framework(wrong.clone()).mock_prove(),
+        Err(vec![MockProverFailure::Constraint {
+            row: 3,
+            typ: GateType::Custom,
+            index: 1
+        }])
+    );
+    let runner = framework(wrong.clone()).setup();
+    assert!(matches!(
+        runner.prover_index().verify(&wrong, &[Fp::from(3u64)]),
+        Err(GateError::Custom { row: 3, err }) if err == "Invalid mul_add constraint: 1"
+    ));
+    assert!(runner
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .is_err());
+}
+
+#[test]
+// Test that the verification of the witness registered with a custom gate is run
+fn test_custom_gate_verifier() {
+    // the constraints hold for a zero input
+    let zero = witness(0);
+    framework(zero.clone()).mock_prove().unwrap();
+
+    let runner = framework(zero.clone()).setup();
+    assert!(matches!(
+        runner.prover_index().verify(&zero, &[Fp::zero()]),
+        Err(GateError::Custom { row: 1, err }) if err == "the square gate of row 1 has a zero input"
+    ));
+}
+
+#[test]
+// Test that the rows and the degrees of the custom gates are checked by the builder
+fn test_custom_gate_setup_errors() {
+    let build = |gates: Vec<(CustomGate<Fp>, Vec<usize>)>| {
+        gates
+            .into_iter()
+            .fold(
+                ConstraintSystem::create(circuit()).public(1),
+                |builder, (gate, rows)| builder.custom_gate(gate, &rows),
+            )
+            .build()
+    };
+    assert!(build(vec![(square_gate(), vec![1, 2]), (mul_add_gate(), vec![3])]).is_ok());
+
+    // a custom row without gate, and a gate on a row which is not custom
+    assert!(matches!(
+        build(vec![(square_gate(), vec![1, 2])]),
+        Err(SetupError::ConstraintSystem(_))
+    ));
+    assert!(matches!(
+        build(vec![
+            (square_gate(), vec![1, 2, 4]),
+            (mul_add_gate(), vec![3])
+        ]),
+        Err(SetupError::ConstraintSystem(_))
+    ));
+
+    // a constraint above the bound of its gate, and a bound above the degree of the quotient
+    let cube = CustomGate::new("cube", vec![witness_curr(1) - witness_curr(0).pow(3)], 2);
+    assert!(matches!(
+        build(vec![(square_gate(), vec![1, 2]), (cube, vec![3])]),
+        Err(SetupError::ConstraintSystem(_))
+    ));
+    let pow8 = CustomGate::new("pow8", vec![witness_curr(1) - witness_curr(0).pow(8)], 8);
+    assert!(matches!(
+        build(vec![(square_gate(), vec![1, 2]), (pow8, vec![3])]),
+        Err(SetupError::ConstraintSystem(_))
+    ));
+}
@@ -0,0 +1,127 @@
+use std::array;
+
+use super::{ecdsa::verify_rows, framework::TestFramework};
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::CircuitGate,
+    polynomial::COLUMNS,
+    polynomials::{
+        decompression::{self, DecompressionCell},
+        ecdsa::curve::ForeignCurve,
+    },
+};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{Field, PrimeField, SquareRootField, UniformRand};
+use mina_curves::pasta::{Fp, Fq, Pallas, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use num_bigint::BigUint;
+use o1_utils::{foreign_field::BigUintForeignFieldHelpers, FieldHelpers};
+use rand::{rngs::StdRng, SeedableRng};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+// Creates the gadget decompressing a point of Pallas, and its witness
+fn create_decompression(
+    x: Fp,
+    sign: bool,
+) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS], Option<Fp>) {
+    let (next_row, gates) = CircuitGate::create_decompression::<Pallas>(0);
+    assert_eq!(next_row, gates.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let y = decompression::extend_decompression_witness::<Pallas>(&mut witness, x, sign);
+    assert_eq!(witness[0].len(), next_row);
+    (gates, witness, y)
+}
+
+#[test]
+// Test that the decompression of a point of Pallas recovers its y-coordinate, and is proven
+fn test_decompression_valid() {
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let point = Pallas::prime_subgroup_generator()
+        .mul(Fq::rand(rng).into_repr())
+        .into_affine();
+    for (sign, y) in [
+        (point.y.to_biguint().bit(0), point.y),
+        (!point.y.to_biguint().bit(0), -point.y),
+    ] {
+        let (gates, witness, decompressed) = create_decompression(point.x, sign);
+        assert_eq!(decompressed, Some(y));
+        let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+        assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+        // the inputs and the output are at their positions
+        let (row, col) = decompression::decompression_cell(0, DecompressionCell::X);
+        assert_eq!(witness[col][row], point.x);
+        let (row, col) = decompression::decompression_cell(0, DecompressionCell::Sign);
+        assert_eq!(witness[col][row], Fp::from(u64::from(sign)));
+        let (row, col) = decompression::decompression_cell(0, DecompressionCell::Y);
+        assert_eq!(witness[col][row], y);
+
+        TestFramework::<Vesta>::default()
+            .gates(gates)
+            .witness(witness)
+            .setup()
+            .prove_and_verify::<BaseSponge, ScalarSponge>()
+            .unwrap();
+    }
+}
+
+#[test]
+// Test that the x-coordinate of no point of Pallas, and the other y-coordinate, do not satisfy the gadget
+fn test_decompression_invalid() {
+    let b = Fp::from(5u32);
+    let x = (1u32..)
+        .map(Fp::from)
+        .find(|x| (x.square() * x + b).sqrt().is_none())
+        .unwrap();
+    let (gates, witness, y) = create_decompression(x, false);
+    assert_eq!(y, None);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+    assert!(verify_rows(&cs, &witness).is_err());
+
+    // the y-coordinate of the other parity
+    let point = Pallas::prime_subgroup_generator();
+    let sign = point.y.to_biguint().bit(0);
+    let (_, mut witness, _) = create_decompression(point.x, sign);
+    let (row, col) = decompression::decompression_cell(0, DecompressionCell::Sign);
+    witness[col][row] = Fp::from(u64::from(!sign));
+    witness[col + 1][row] = witness[col][row];
+    assert!(verify_rows(&cs, &witness).is_err());
+}
+
+#[test]
+// Test the decompression of points of secp256k1, and that the x-coordinate of no point does not satisfy the gadget
+fn test_foreign_decompression() {
+    let curve = ForeignCurve::secp256k1();
+    let (next_row, gates) = CircuitGate::<Fp>::create_foreign_decompression(0, &curve);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    let (x, y) = curve.generator.clone();
+    for (sign, y) in [(y.bit(0), y.clone()), (!y.bit(0), &curve.modulus - &y)] {
+        let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+        let decompressed =
+            decompression::extend_foreign_decompression_witness(&mut witness, &curve, &x, sign);
+        assert_eq!(decompressed, Some(y.clone()));
+        assert_eq!(witness[0].len(), next_row);
+        assert_eq!(verify_rows(&cs, &witness), Ok(()));
+        let (row, col) = decompression::foreign_decompression_cell(0, DecompressionCell::Y, 0);
+        assert_eq!(witness[col][row], y.to_field_limbs::<Fp>()[0]);
+        let (row, col) = decompression::foreign_decompression_cell(0, DecompressionCell::Sign, 0);
+        assert_eq!(witness[col][row], Fp::from(u64::from(sign)));
+    }
+
+    let x = (1u32..)
+        .map(BigUint::from)
+        .find(|x| curve.y_coordinate(x).is_none())
+        .unwrap();
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let decompressed =
+        decompression::extend_foreign_decompression_witness(&mut witness, &curve, &x, true);
+    assert_eq!(decompressed, None);
+    assert!(verify_rows(&cs, &witness).is_err());
+}
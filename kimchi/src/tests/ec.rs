@@ -4,23 +4,18 @@ use crate::circuits::{
 };
 use ark_ec::{AffineCurve, ProjectiveCurve};
 use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
-use mina_curves::pasta::{Fp as F, Pallas as Other, Vesta, VestaParameters};
-use mina_poseidon::{
-    constants::PlonkSpongeConstantsKimchi,
-    sponge::{DefaultFqSponge, DefaultFrSponge},
-};
+use mina_curves::pasta::{Pallas, Vesta};
+use poly_commitment::commitment::CommitmentCurve;
 use rand::{rngs::StdRng, SeedableRng};
 use std::array;
 
-use super::framework::TestFramework;
-
-type SpongeParams = PlonkSpongeConstantsKimchi;
-type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
-type ScalarSponge = DefaultFrSponge<F, SpongeParams>;
+use super::framework::{TestCurve, TestFramework};
 
-// Tests add and double gates
-#[test]
-fn ec_test() {
+// Tests add and double gates over the points of the other curve of G
+fn ec<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
     let num_doubles = 100;
     let num_additions = 100;
     let num_infs = 100;
@@ -35,14 +30,16 @@ fn ec_test() {
         ));
     }
 
-    let mut witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![]);
+    let (zero, one) = (G::ScalarField::zero(), G::ScalarField::one());
+    let coordinates = |p: G::OtherCurve| p.to_coordinates().unwrap();
+    let mut witness: [Vec<G::ScalarField>; COLUMNS] = array::from_fn(|_| vec![]);
 
     let rng = &mut StdRng::from_seed([0; 32]);
 
     let ps = {
-        let p = Other::prime_subgroup_generator()
+        let p = G::OtherCurve::prime_subgroup_generator()
             .into_projective()
-            .mul(<Other as AffineCurve>::ScalarField::rand(rng).into_repr())
+            .mul(G::BaseField::rand(rng).into_repr())
             .into_affine();
         let mut res = vec![];
         let mut acc = p;
@@ -54,9 +51,9 @@ fn ec_test() {
     };
 
     let qs = {
-        let q = Other::prime_subgroup_generator()
+        let q = G::OtherCurve::prime_subgroup_generator()
             .into_projective()
-            .mul(<Other as AffineCurve>::ScalarField::rand(rng).into_repr())
+            .mul(G::BaseField::rand(rng).into_repr())
             .into_affine();
         let mut res = vec![];
         let mut acc = q;
@@ -68,87 +65,93 @@ fn ec_test() {
     };
 
     for &p in ps.iter().take(num_doubles) {
-        let p2 = p + p;
-        let (x1, y1) = (p.x, p.y);
+        let (x2, y2) = coordinates(p + p);
+        let (x1, y1) = coordinates(p);
         let x1_squared = x1.square();
         // 2 * s * y1 = 3 * x1^2
         let s = (x1_squared.double() + x1_squared) / y1.double();
 
-        witness[0].push(p.x);
-        witness[1].push(p.y);
-        witness[2].push(p.x);
-        witness[3].push(p.y);
-        witness[4].push(p2.x);
-        witness[5].push(p2.y);
-        witness[6].push(F::zero());
-        witness[7].push(F::one());
+        witness[0].push(x1);
+        witness[1].push(y1);
+        witness[2].push(x1);
+        witness[3].push(y1);
+        witness[4].push(x2);
+        witness[5].push(y2);
+        witness[6].push(zero);
+        witness[7].push(one);
         witness[8].push(s);
-        witness[9].push(F::zero());
-        witness[10].push(F::zero());
+        witness[9].push(zero);
+        witness[10].push(zero);
 
-        witness[11].push(F::zero());
-        witness[12].push(F::zero());
-        witness[13].push(F::zero());
-        witness[14].push(F::zero());
+        witness[11].push(zero);
+        witness[12].push(zero);
+        witness[13].push(zero);
+        witness[14].push(zero);
     }
 
     for i in 0..num_additions {
         let p = ps[i];
         let q = qs[i];
 
-        let pq = p + q;
-        let (x1, y1) = (p.x, p.y);
-        let (x2, y2) = (q.x, q.y);
+        let (x3, y3) = coordinates(p + q);
+        let (x1, y1) = coordinates(p);
+        let (x2, y2) = coordinates(q);
         // (x2 - x1) * s = y2 - y1
         let s = (y2 - y1) / (x2 - x1);
         witness[0].push(x1);
         witness[1].push(y1);
         witness[2].push(x2);
         witness[3].push(y2);
-        witness[4].push(pq.x);
-        witness[5].push(pq.y);
-        witness[6].push(F::zero());
-        witness[7].push(F::zero());
+        witness[4].push(x3);
+        witness[5].push(y3);
+        witness[6].push(zero);
+        witness[7].push(zero);
         witness[8].push(s);
-        witness[9].push(F::zero());
+        witness[9].push(zero);
         witness[10].push((x2 - x1).inverse().unwrap());
 
-        witness[11].push(F::zero());
-        witness[12].push(F::zero());
-        witness[13].push(F::zero());
-        witness[14].push(F::zero());
+        witness[11].push(zero);
+        witness[12].push(zero);
+        witness[13].push(zero);
+        witness[14].push(zero);
     }
 
     for &p in ps.iter().take(num_infs) {
-        let q = -p;
+        let (x2, y2) = coordinates(-p);
 
-        let p2 = p + p;
-        let (x1, y1) = (p.x, p.y);
+        let (x3, y3) = coordinates(p + p);
+        let (x1, y1) = coordinates(p);
         let x1_squared = x1.square();
         // 2 * s * y1 = -3 * x1^2
         let s = (x1_squared.double() + x1_squared) / y1.double();
-        witness[0].push(p.x);
-        witness[1].push(p.y);
-        witness[2].push(q.x);
-        witness[3].push(q.y);
-        witness[4].push(p2.x);
-        witness[5].push(p2.y);
-        witness[6].push(F::one());
-        witness[7].push(F::one());
+        witness[0].push(x1);
+        witness[1].push(y1);
+        witness[2].push(x2);
+        witness[3].push(y2);
+        witness[4].push(x3);
+        witness[5].push(y3);
+        witness[6].push(one);
+        witness[7].push(one);
         witness[8].push(s);
-        witness[9].push((q.y - p.y).inverse().unwrap());
-        witness[10].push(F::zero());
+        witness[9].push((y2 - y1).inverse().unwrap());
+        witness[10].push(zero);
 
-        witness[11].push(F::zero());
-        witness[12].push(F::zero());
-        witness[13].push(F::zero());
-        witness[14].push(F::zero());
+        witness[11].push(zero);
+        witness[12].push(zero);
+        witness[13].push(zero);
+        witness[14].push(zero);
     }
 
-    TestFramework::<Vesta>::default()
+    TestFramework::<G>::default()
         .gates(gates)
         .witness(witness)
         .setup()
-        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>()
         .unwrap();
 }
+
+#[test]
+fn ec_test() {
+    ec::<Vesta>();
+    ec::<Pallas>();
+}
@@ -0,0 +1,522 @@
+use std::array;
+
+use crate::{
+    circuits::{
+        argument::{ArgumentEnv, ArgumentWitness},
+        constraints::ConstraintSystem,
+        expr::Constants,
+        gate::{CircuitGate, CircuitGateError, GateType},
+        polynomial::COLUMNS,
+        polynomials::ecdsa::{
+            self, batch, bls12_381,
+            curve::{self, ForeignCurve, ForeignPoint},
+            ethereum::{self, EthereumSignature, ETHEREUM_PUBLIC_INPUTS},
+            glv::GlvEndomorphism,
+            webauthn::{self, WEBAUTHN_PUBLIC_INPUTS},
+            EcdsaInput, EcdsaSignature,
+        },
+    },
+    curve::KimchiCurve,
+};
+use ark_ff::{One, Zero};
+use mina_curves::pasta::{Fp, Vesta};
+use num_bigint::{BigUint, RandBigInt};
+use o1_utils::foreign_field::BigUintForeignFieldHelpers;
+use rand::{rngs::StdRng, SeedableRng};
+use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+use sha2::{Digest, Sha256};
+
+const SECRET_KEY: [u8; 32] = [
+    94, 183, 40, 13, 161, 58, 116, 34, 229, 91, 13, 218, 76, 2, 143, 87, 148, 193, 215, 8, 34, 129,
+    245, 66, 183, 102, 13, 19, 226, 231, 41, 140,
+];
+
+const MSG_HASH: [u8; 32] = [
+    185, 77, 39, 185, 147, 77, 62, 8, 165, 46, 82, 215, 218, 125, 171, 250, 196, 132, 239, 227,
+    122, 83, 128, 238, 144, 136, 247, 172, 226, 239, 205, 233,
+];
+
+// Signs a message hash with the secret key, and returns the signature and the public key
+fn sign(msg_hash: &[u8; 32]) -> (EcdsaSignature, ForeignPoint) {
+    let secp = Secp256k1::new();
+    let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+    let message = Message::from_slice(msg_hash).unwrap();
+    let signature = secp.sign_ecdsa(&message, &secret_key).serialize_compact();
+    let public_key = PublicKey::from_secret_key(&secp, &secret_key).serialize_uncompressed();
+    (
+        EcdsaSignature {
+            r: BigUint::from_bytes_be(&signature[..32]),
+            s: BigUint::from_bytes_be(&signature[32..]),
+        },
+        (
+            BigUint::from_bytes_be(&public_key[1..33]),
+            BigUint::from_bytes_be(&public_key[33..]),
+        ),
+    )
+}
+
+// Checks the constraints and the copy constraints of the rows of the witness,
+// with the generic gates which are not public inputs
+pub(crate) fn verify_rows(
+    cs: &ConstraintSystem<Fp>,
+    witness: &[Vec<Fp>; COLUMNS],
+) -> Result<(), CircuitGateError> {
+    let public = &witness[0][0..cs.public];
+    for (row, gate) in cs.gates.iter().enumerate().take(witness[0].len()) {
+        gate.verify_witness::<Vesta>(row, witness, cs, public)?;
+        if gate.typ == GateType::Generic && row >= cs.public {
+            let constants = Constants {
+                alpha: Fp::one(),
+                beta: Fp::one(),
+                gamma: Fp::one(),
+                joint_combiner: Some(Fp::one()),
+                endo_coefficient: cs.endo,
+                mds: &Vesta::sponge_params().mds,
+            };
+            let env = ArgumentEnv::create(
+                ArgumentWitness {
+                    curr: array::from_fn(|col| witness[col][row]),
+                    next: [Fp::zero(); COLUMNS],
+                },
+                gate.coeffs.clone(),
+                constants,
+            );
+            let evaluations = gate.evaluate_constraints(&env);
+            if let Some(i) = evaluations.iter().position(|eval| !eval.is_zero()) {
+                return Err(CircuitGateError::Constraint(GateType::Generic, i + 1));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+// Test the parameters of secp256k1 and the arithmetic of its points against the secp256k1 crate
+fn test_secp256k1_curve() {
+    let curve = ForeignCurve::secp256k1();
+    assert_eq!(
+        curve.modulus,
+        BigUint::from_bytes_be(&secp256k1::constants::FIELD_SIZE)
+    );
+    assert_eq!(
+        curve.order,
+        BigUint::from_bytes_be(&secp256k1::constants::CURVE_ORDER)
+    );
+    assert!(curve.is_on_curve(&curve.generator));
+    assert!(curve.is_on_curve(&curve.offset()));
+    assert_eq!(curve.scalar_mul(&curve.generator, &curve.order), None);
+
+    let (_, public_key) = sign(&MSG_HASH);
+    assert_eq!(
+        curve.scalar_mul(&curve.generator, &BigUint::from_bytes_be(&SECRET_KEY)),
+        Some(public_key)
+    );
+}
+
+#[test]
+// Test the verification of signatures out of the circuit
+fn test_verify_ecdsa() {
+    let curve = ForeignCurve::secp256k1();
+    let (signature, public_key) = sign(&MSG_HASH);
+    assert!(ecdsa::verify_ecdsa(
+        &curve,
+        &MSG_HASH,
+        &signature,
+        &public_key
+    ));
+
+    let mut other_hash = MSG_HASH;
+    other_hash[0] ^= 1;
+    assert!(!ecdsa::verify_ecdsa(
+        &curve,
+        &other_hash,
+        &signature,
+        &public_key
+    ));
+    let (other_signature, _) = sign(&other_hash);
+    assert!(!ecdsa::verify_ecdsa(
+        &curve,
+        &MSG_HASH,
+        &other_signature,
+        &public_key
+    ));
+    assert!(!ecdsa::verify_ecdsa(
+        &curve,
+        &MSG_HASH,
+        &signature,
+        &curve.generator
+    ));
+}
+
+#[test]
+// Test that the witness of a valid signature satisfies the gadget, and not the one of another message hash
+fn test_ecdsa_witness() {
+    let curve = ForeignCurve::secp256k1();
+    let (signature, public_key) = sign(&MSG_HASH);
+    let (next_row, gates) = CircuitGate::<Fp>::create_ecdsa(0, &curve);
+    assert_eq!(next_row, gates.len());
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    ecdsa::extend_ecdsa_witness(&mut witness, &curve, &MSG_HASH, &signature, &public_key);
+    assert_eq!(witness[0].len(), next_row);
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+    // the inputs are at their positions
+    let (row, col) = ecdsa::ecdsa_input_limb(0, EcdsaInput::S, 0);
+    assert_eq!(witness[col][row], signature.s.to_field_limbs::<Fp>()[0]);
+
+    let mut other_hash = MSG_HASH;
+    other_hash[31] ^= 1;
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    ecdsa::extend_ecdsa_witness(&mut witness, &curve, &other_hash, &signature, &public_key);
+    assert!(matches!(
+        verify_rows(&cs, &witness),
+        Err(CircuitGateError::CopyConstraint { .. })
+    ));
+}
+
+#[test]
+// Test the circuit of an Ethereum signed message, with the public key recovered from the signature
+fn test_ethereum_signed_message() {
+    let curve = ForeignCurve::secp256k1();
+    let message = b"kimchi signed message";
+    let msg_hash = ethereum::ethereum_message_hash(message);
+    let (signature, public_key) = sign(&msg_hash);
+    let address = ethereum::ethereum_address(&public_key);
+
+    // the recovery id of the signature is the one which recovers its public key
+    let signature = [27, 28]
+        .into_iter()
+        .map(|v| EthereumSignature {
+            r: signature.r.clone(),
+            s: signature.s.clone(),
+            v,
+        })
+        .find(|signature| {
+            ethereum::recover_public_key(&curve, &msg_hash, signature) == Some(public_key.clone())
+        })
+        .expect("a recovery id recovers the public key");
+
+    let gates = ethereum::ethereum_gates::<Fp>();
+    let cs = ConstraintSystem::create(gates)
+        .public(ETHEREUM_PUBLIC_INPUTS)
+        .build()
+        .unwrap();
+    let (mut witness, recovered) =
+        ethereum::ethereum_witness::<Fp>(&address, message, &signature).unwrap();
+    assert_eq!(recovered, public_key);
+    assert_eq!(witness[0].len(), cs.gates.len());
+    assert_eq!(
+        ethereum::ethereum_public_input(&address, message, &public_key),
+        Some(witness[0][0..ETHEREUM_PUBLIC_INPUTS].to_vec())
+    );
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+    // another address or message is rejected
+    let mut other_address = address;
+    other_address[0] ^= 1;
+    assert!(ethereum::ethereum_witness::<Fp>(&other_address, message, &signature).is_none());
+    assert!(ethereum::ethereum_public_input::<Fp>(&other_address, message, &public_key).is_none());
+    let other_public_input: Vec<Fp> =
+        ethereum::ethereum_public_input(&address, b"another message", &public_key).unwrap();
+    for (row, value) in other_public_input.into_iter().enumerate() {
+        witness[0][row] = value;
+    }
+    assert!(matches!(
+        verify_rows(&cs, &witness),
+        Err(CircuitGateError::CopyConstraint { .. })
+    ));
+}
+
+#[test]
+// Test the endomorphism of secp256k1, and the decomposition of scalars into scalars of half the bits
+fn test_secp256k1_glv() {
+    let curve = ForeignCurve::secp256k1();
+    let glv = GlvEndomorphism::secp256k1();
+    assert_eq!(
+        glv.apply(&curve, &curve.generator),
+        curve.scalar_mul(&curve.generator, &glv.lambda).unwrap()
+    );
+    assert_eq!(glv.scalar_bits(), 129);
+
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let mut scalars: Vec<BigUint> = (0..100)
+        .map(|_| rng.gen_biguint_below(&curve.order))
+        .collect();
+    scalars.extend([
+        BigUint::zero(),
+        BigUint::one(),
+        &curve.order - 1u32,
+        glv.lambda.clone(),
+    ]);
+    for k in scalars {
+        let (k1, k2) = glv.decompose(&k);
+        assert!(k1.bits() <= 129 && k2.bits() <= 129);
+        assert_eq!((k1 + k2 * &glv.lambda) % &curve.order, k);
+    }
+}
+
+#[test]
+// Test that the verification with the endomorphism is satisfied by a valid signature, in fewer rows
+fn test_ecdsa_glv_witness() {
+    let curve = ForeignCurve::secp256k1();
+    let glv = GlvEndomorphism::secp256k1();
+    let (signature, public_key) = sign(&MSG_HASH);
+    let (next_row, gates) = CircuitGate::<Fp>::create_ecdsa_glv(0, &curve, &glv);
+    assert_eq!(next_row, gates.len());
+    let (ecdsa_rows, _) = CircuitGate::<Fp>::create_ecdsa(0, &curve);
+    assert!(4 * next_row < 3 * ecdsa_rows);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    ecdsa::extend_ecdsa_glv_witness(
+        &mut witness,
+        &curve,
+        &glv,
+        &MSG_HASH,
+        &signature,
+        &public_key,
+    );
+    assert_eq!(witness[0].len(), next_row);
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+    // the inputs are at the positions of the gadget without the endomorphism
+    let (row, col) = ecdsa::ecdsa_input_limb(0, EcdsaInput::PublicKeyY, 1);
+    assert_eq!(witness[col][row], public_key.1.to_field_limbs::<Fp>()[1]);
+
+    let mut other_hash = MSG_HASH;
+    other_hash[31] ^= 1;
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    ecdsa::extend_ecdsa_glv_witness(
+        &mut witness,
+        &curve,
+        &glv,
+        &other_hash,
+        &signature,
+        &public_key,
+    );
+    assert!(verify_rows(&cs, &witness).is_err());
+}
+
+#[test]
+// Test the verification of a batch of signatures, and that the batch of an invalid signature is not satisfied
+fn test_ecdsa_batch_witness() {
+    let curve = ForeignCurve::secp256k1();
+    let hashes: Vec<[u8; 32]> = (0..3u8)
+        .map(|i| {
+            let mut msg_hash = MSG_HASH;
+            msg_hash[0] ^= i;
+            msg_hash
+        })
+        .collect();
+    let mut signatures: Vec<(&[u8], EcdsaSignature, ForeignPoint)> = hashes
+        .iter()
+        .map(|msg_hash| {
+            let (signature, public_key) = sign(msg_hash);
+            (msg_hash.as_slice(), signature, public_key)
+        })
+        .collect();
+
+    let (next_row, gates) = CircuitGate::<Fp>::create_ecdsa_batch::<Vesta>(0, &curve, 3);
+    assert_eq!(next_row, gates.len());
+    let (ecdsa_rows, _) = CircuitGate::<Fp>::create_ecdsa(0, &curve);
+    assert!(next_row < 3 * ecdsa_rows);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    assert!(batch::extend_ecdsa_batch_witness::<Vesta>(
+        &mut witness,
+        &curve,
+        &signatures
+    ));
+    assert_eq!(witness[0].len(), next_row);
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+    let (row, col) = batch::ecdsa_batch_input_limb(0, 2, EcdsaInput::S, 0);
+    assert_eq!(
+        witness[col][row],
+        signatures[2].1.s.to_field_limbs::<Fp>()[0]
+    );
+
+    // the signature of another hash
+    signatures[1].0 = MSG_HASH.as_slice();
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    assert!(!batch::extend_ecdsa_batch_witness::<Vesta>(
+        &mut witness,
+        &curve,
+        &signatures
+    ));
+    assert!(verify_rows(&cs, &witness).is_err());
+}
+
+// The key pair and the signature of the message "sample" with SHA-256 over P-256 of RFC 6979 (A.2.5)
+const P256_SECRET_KEY: &str = "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721";
+const P256_PUBLIC_KEY: [&str; 2] = [
+    "60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6",
+    "7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299",
+];
+const P256_SAMPLE_NONCE: &str = "a6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60";
+const P256_SAMPLE_SIGNATURE: [&str; 2] = [
+    "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716",
+    "f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8",
+];
+
+fn from_hex(hex: &str) -> BigUint {
+    BigUint::parse_bytes(hex.as_bytes(), 16).unwrap()
+}
+
+// Signs a message hash over P-256 with the secret key of RFC 6979 and a nonce
+fn sign_p256(msg_hash: &[u8; 32], nonce: &BigUint) -> EcdsaSignature {
+    let curve = ForeignCurve::p256();
+    let n = &curve.order;
+    let (x, _) = curve.scalar_mul(&curve.generator, nonce).unwrap();
+    let r = x % n;
+    let z = ecdsa::hash_to_scalar(&curve, msg_hash);
+    let s = curve::inverse(nonce, n) * (z + &r * from_hex(P256_SECRET_KEY)) % n;
+    EcdsaSignature { r, s }
+}
+
+// Encodes a signature in DER
+fn der_signature(signature: &EcdsaSignature) -> Vec<u8> {
+    let mut integers = vec![];
+    for value in [&signature.r, &signature.s] {
+        let mut bytes = value.to_bytes_be();
+        if bytes[0] & 0x80 != 0 {
+            bytes.insert(0, 0);
+        }
+        integers.extend([0x02, bytes.len() as u8]);
+        integers.extend(bytes);
+    }
+    let mut der = vec![0x30, integers.len() as u8];
+    der.extend(integers);
+    der
+}
+
+#[test]
+// Test the parameters of P-256 and the verification of signatures against the test vectors of RFC 6979
+fn test_p256_curve() {
+    let curve = ForeignCurve::p256();
+    assert!(curve.is_on_curve(&curve.generator));
+    assert!(curve.is_on_curve(&curve.offset()));
+    assert_eq!(curve.scalar_mul(&curve.generator, &curve.order), None);
+
+    let public_key = (from_hex(P256_PUBLIC_KEY[0]), from_hex(P256_PUBLIC_KEY[1]));
+    assert_eq!(
+        curve.scalar_mul(&curve.generator, &from_hex(P256_SECRET_KEY)),
+        Some(public_key.clone())
+    );
+    let msg_hash: [u8; 32] = Sha256::digest(b"sample").into();
+    let signature = EcdsaSignature {
+        r: from_hex(P256_SAMPLE_SIGNATURE[0]),
+        s: from_hex(P256_SAMPLE_SIGNATURE[1]),
+    };
+    assert_eq!(
+        sign_p256(&msg_hash, &from_hex(P256_SAMPLE_NONCE)),
+        signature
+    );
+    assert!(ecdsa::verify_ecdsa(
+        &curve,
+        &msg_hash,
+        &signature,
+        &public_key
+    ));
+    assert!(!ecdsa::verify_ecdsa(
+        &curve,
+        &Sha256::digest(b"test"),
+        &signature,
+        &public_key
+    ));
+
+    // the DER encoding of the signature
+    let der = der_signature(&signature);
+    assert_eq!(der.len(), 72);
+    assert_eq!(webauthn::parse_der_signature(&der), Some(signature));
+    assert_eq!(webauthn::parse_der_signature(&der[..71]), None);
+    let mut trailing = der;
+    trailing.push(0);
+    assert_eq!(webauthn::parse_der_signature(&trailing), None);
+}
+
+#[test]
+// Test the circuit of a WebAuthn assertion signed over P-256
+fn test_webauthn_assertion() {
+    let mut authenticator_data = Sha256::digest(b"example.com").to_vec();
+    authenticator_data.extend([0x05, 0, 0, 0, 1]);
+    let client_data_json =
+        br#"{"type":"webauthn.get","challenge":"a2ltY2hp","origin":"https://example.com"}"#;
+    let msg_hash = webauthn::webauthn_message_hash(&authenticator_data, client_data_json);
+    let der = der_signature(&sign_p256(&msg_hash, &BigUint::from(0x1234_5678u32)));
+    let signature = webauthn::parse_der_signature(&der).unwrap();
+    let public_key = (from_hex(P256_PUBLIC_KEY[0]), from_hex(P256_PUBLIC_KEY[1]));
+
+    let gates = webauthn::webauthn_gates::<Fp>();
+    let cs = ConstraintSystem::create(gates)
+        .public(WEBAUTHN_PUBLIC_INPUTS)
+        .build()
+        .unwrap();
+    let witness = webauthn::webauthn_witness::<Fp>(
+        &authenticator_data,
+        client_data_json,
+        &signature,
+        &public_key,
+    )
+    .unwrap();
+    assert_eq!(witness[0].len(), cs.gates.len());
+    assert_eq!(
+        webauthn::webauthn_public_input::<Fp>(&authenticator_data, client_data_json, &public_key),
+        witness[0][0..WEBAUTHN_PUBLIC_INPUTS].to_vec()
+    );
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+    // the signature of other client data is rejected
+    let other_client_data =
+        br#"{"type":"webauthn.get","challenge":"b3RoZXI","origin":"https://example.com"}"#;
+    assert!(webauthn::webauthn_witness::<Fp>(
+        &authenticator_data,
+        other_client_data,
+        &signature,
+        &public_key
+    )
+    .is_none());
+}
+
+#[test]
+// Test the parameters of the group G1 of BLS12-381, and the compressed encoding of its points
+fn test_bls12_381_g1() {
+    let curve = ForeignCurve::bls12_381_g1();
+    assert!(curve.is_on_curve(&curve.generator));
+    assert_eq!(curve.scalar_mul(&curve.generator, &curve.order), None);
+    assert!(!curve.is_supported::<Fp>());
+    assert!(ForeignCurve::secp256k1().is_supported::<Fp>());
+    assert!(ForeignCurve::p256().is_supported::<Fp>());
+
+    let compressed = bls12_381::compress_g1(&curve.generator);
+    assert_eq!(
+        hex::encode(compressed),
+        "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"
+    );
+    assert_eq!(
+        bls12_381::decompress_public_key(&compressed),
+        Some(curve.generator.clone())
+    );
+    let negated = curve.neg(&curve.generator);
+    let compressed = bls12_381::compress_g1(&negated);
+    assert_eq!(compressed[0] & 0x20, 0x20);
+    assert_eq!(bls12_381::decompress_public_key(&compressed), Some(negated));
+
+    // the point (0, 2) is on the curve, but not in the subgroup of the generator
+    let point = (BigUint::zero(), BigUint::from(2u32));
+    assert!(curve.is_on_curve(&point));
+    assert_eq!(
+        bls12_381::decompress_public_key(&bls12_381::compress_g1(&point)),
+        None
+    );
+
+    // the point at infinity, and the uncompressed encodings, are rejected
+    let mut infinity = [0u8; bls12_381::G1_COMPRESSED_BYTES];
+    infinity[0] = 0xc0;
+    assert_eq!(bls12_381::decompress_public_key(&infinity), None);
+    let mut uncompressed = bls12_381::compress_g1(&curve.generator);
+    uncompressed[0] &= 0x7f;
+    assert_eq!(bls12_381::decompress_public_key(&uncompressed), None);
+}
@@ -3,24 +3,23 @@ use crate::circuits::{
     polynomials::endosclmul,
     wires::*,
 };
-use crate::tests::framework::TestFramework;
+use crate::curve::KimchiCurve;
+use crate::tests::framework::{TestCurve, TestFramework};
 use ark_ec::{AffineCurve, ProjectiveCurve};
 use ark_ff::{BigInteger, BitIteratorLE, Field, One, PrimeField, UniformRand, Zero};
-use mina_curves::pasta::{Fp as F, Pallas as Other, Vesta, VestaParameters};
-use mina_poseidon::{
-    constants::PlonkSpongeConstantsKimchi,
-    sponge::{DefaultFqSponge, DefaultFrSponge, ScalarChallenge},
-};
-use poly_commitment::srs::endos;
+use mina_curves::pasta::{Pallas, Vesta};
+use mina_poseidon::sponge::ScalarChallenge;
+use o1_utils::FieldHelpers;
+use poly_commitment::commitment::CommitmentCurve;
 use rand::{rngs::StdRng, SeedableRng};
 use std::array;
 
-type SpongeParams = PlonkSpongeConstantsKimchi;
-type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
-type ScalarSponge = DefaultFrSponge<F, SpongeParams>;
-
-#[test]
-fn endomul_test() {
+// Proves the endoscaling of points of the other curve of G,
+// with the endomorphism coefficients of the other curve
+fn endomul<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
     let bits_per_chunk = 4;
     let num_bits = 128;
     let chunks = num_bits / bits_per_chunk;
@@ -47,32 +46,31 @@ fn endomul_test() {
         gates.push(CircuitGate::new(GateType::Zero, Wire::for_row(row), vec![]));
     }
 
-    let (endo_q, endo_r) = endos::<Other>();
+    let (endo_q, endo_r) = *G::OtherCurve::endos();
 
-    let mut witness: [Vec<F>; COLUMNS] =
-        array::from_fn(|_| vec![F::zero(); rows_per_scalar * num_scalars]);
+    let mut witness: [Vec<G::ScalarField>; COLUMNS] =
+        array::from_fn(|_| vec![G::ScalarField::zero(); rows_per_scalar * num_scalars]);
 
     let rng = &mut StdRng::from_seed([0; 32]);
 
     // let start = Instant::now();
     for i in 0..num_scalars {
-        let bits_lsb: Vec<_> = BitIteratorLE::new(F::rand(rng).into_repr())
+        let bits_lsb: Vec<_> = BitIteratorLE::new(G::ScalarField::rand(rng).into_repr())
             .take(num_bits)
             .collect();
-        let x = <Other as AffineCurve>::ScalarField::from_repr(
-            <F as PrimeField>::BigInt::from_bits_le(&bits_lsb[..]),
-        )
+        let x = G::BaseField::from_repr(<G::BaseField as PrimeField>::BigInt::from_bits_le(
+            &bits_lsb[..],
+        ))
         .unwrap();
 
         let x_scalar = ScalarChallenge(x).to_field(&endo_r);
 
-        let base = Other::prime_subgroup_generator();
-        // let g = Other::prime_subgroup_generator().into_projective();
+        let base = G::OtherCurve::prime_subgroup_generator();
+        let (base_x, base_y) = base.to_coordinates().unwrap();
         let acc0 = {
-            let t = Other::new(endo_q * base.x, base.y, false);
-            let p = t + base;
-            let acc = p + p;
-            (acc.x, acc.y)
+            let t = G::OtherCurve::of_coordinates(endo_q * base_x, base_y);
+            let p = t.into_projective() + base.into_projective();
+            (p + p).into_affine().to_coordinates().unwrap()
         };
 
         let bits_msb: Vec<_> = bits_lsb.iter().take(num_bits).copied().rev().collect();
@@ -81,39 +79,45 @@ fn endomul_test() {
             &mut witness,
             i * rows_per_scalar,
             endo_q,
-            (base.x, base.y),
+            (base_x, base_y),
             &bits_msb,
             acc0,
         );
 
         let expected = {
-            let t = Other::prime_subgroup_generator();
-            let mut acc = Other::new(acc0.0, acc0.1, false);
+            let one = G::ScalarField::one();
+            let mut acc = G::OtherCurve::of_coordinates(acc0.0, acc0.1).into_projective();
             for i in (0..(num_bits / 2)).rev() {
-                let b2i = F::from(bits_lsb[2 * i] as u64);
-                let b2i1 = F::from(bits_lsb[2 * i + 1] as u64);
-                let xq = (F::one() + ((endo_q - F::one()) * b2i1)) * t.x;
-                let yq = (b2i.double() - F::one()) * t.y;
-                acc = acc + (acc + Other::new(xq, yq, false));
+                let b2i = G::ScalarField::from(bits_lsb[2 * i] as u64);
+                let b2i1 = G::ScalarField::from(bits_lsb[2 * i + 1] as u64);
+                let xq = (one + ((endo_q - one) * b2i1)) * base_x;
+                let yq = (b2i.double() - one) * base_y;
+                let q = G::OtherCurve::of_coordinates(xq, yq).into_projective();
+                acc = acc + (acc + q);
             }
-            acc
+            acc.into_affine()
         };
         assert_eq!(
             expected,
-            Other::prime_subgroup_generator()
-                .into_projective()
+            base.into_projective()
                 .mul(x_scalar.into_repr())
                 .into_affine()
         );
 
-        assert_eq!((expected.x, expected.y), res.acc);
-        assert_eq!(x.into_repr(), res.n.into_repr());
+        assert_eq!(expected.to_coordinates(), Some(res.acc));
+        assert_eq!(x.to_biguint(), res.n.to_biguint());
     }
 
-    TestFramework::<Vesta>::default()
+    TestFramework::<G>::default()
         .gates(gates)
         .witness(witness)
         .setup()
-        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>()
         .unwrap();
 }
+
+#[test]
+fn endomul_test() {
+    endomul::<Vesta>();
+    endomul::<Pallas>();
+}
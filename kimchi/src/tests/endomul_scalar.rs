@@ -4,24 +4,20 @@ use crate::{
         polynomials::endomul_scalar,
         wires::*,
     },
-    tests::framework::TestFramework,
+    curve::KimchiCurve,
+    tests::framework::{TestCurve, TestFramework},
 };
 use ark_ff::{BigInteger, BitIteratorLE, PrimeField, UniformRand};
-use mina_curves::pasta::{Fp as F, Vesta, VestaParameters};
-use mina_poseidon::{
-    constants::PlonkSpongeConstantsKimchi,
-    sponge::{DefaultFqSponge, DefaultFrSponge, ScalarChallenge},
-};
-use poly_commitment::srs::endos;
+use mina_curves::pasta::{Pallas, Vesta};
+use mina_poseidon::sponge::ScalarChallenge;
 use rand::{rngs::StdRng, SeedableRng};
 use std::array;
 
-type SpongeParams = PlonkSpongeConstantsKimchi;
-type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
-type ScalarSponge = DefaultFrSponge<F, SpongeParams>;
-
-#[test]
-fn endomul_scalar_test() {
+// Proves the computation of the endoscalars of G from their scalar challenges
+fn endomul_scalar<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
     let bits_per_row = 2 * 8;
     let num_bits = 128;
     let rows_per_scalar = num_bits / bits_per_row;
@@ -43,19 +39,22 @@ fn endomul_scalar_test() {
         }
     }
 
-    let (_, endo_scalar_coeff) = endos::<Vesta>();
+    let (_, endo_scalar_coeff) = *G::endos();
 
-    let mut witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![]);
+    let mut witness: [Vec<G::ScalarField>; COLUMNS] = array::from_fn(|_| vec![]);
 
     let rng = &mut StdRng::from_seed([0; 32]);
 
     //let start = Instant::now();
     for _ in 0..num_scalars {
         let x = {
-            let bits_lsb: Vec<_> = BitIteratorLE::new(F::rand(rng).into_repr())
+            let bits_lsb: Vec<_> = BitIteratorLE::new(G::ScalarField::rand(rng).into_repr())
                 .take(num_bits)
                 .collect();
-            F::from_repr(<F as PrimeField>::BigInt::from_bits_le(&bits_lsb[..])).unwrap()
+            G::ScalarField::from_repr(<G::ScalarField as PrimeField>::BigInt::from_bits_le(
+                &bits_lsb[..],
+            ))
+            .unwrap()
         };
 
         assert_eq!(
@@ -64,10 +63,16 @@ fn endomul_scalar_test() {
         );
     }
 
-    TestFramework::<Vesta>::default()
+    TestFramework::<G>::default()
         .gates(gates)
         .witness(witness)
         .setup()
-        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>()
         .unwrap();
 }
+
+#[test]
+fn endomul_scalar_test() {
+    endomul_scalar::<Vesta>();
+    endomul_scalar::<Pallas>();
+}
@@ -0,0 +1,110 @@
+use std::array;
+
+use crate::{
+    circuits::{
+        gate::CircuitGate,
+        polynomial::COLUMNS,
+        polynomials::{generic::GenericGateSpec, not},
+        wires::Wire,
+    },
+    equivalence::{check_equivalence, CircuitVersion, EquivalenceError},
+    tests::xor::all_ones,
+};
+use ark_ff::Zero;
+use mina_curves::pasta::{Fp, Vesta};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+const RNG_SEED: [u8; 32] = [
+    47, 150, 3, 88, 201, 12, 64, 173, 9, 255, 31, 120, 77, 140, 5, 233, 18, 96, 201, 44, 160, 7,
+    91, 219, 62, 131, 250, 36, 108, 83, 14, 177,
+];
+
+// The gates of a public input holding the all-ones word
+fn create_all_ones() -> Vec<CircuitGate<Fp>> {
+    vec![CircuitGate::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )]
+}
+
+// The witness of a public input holding the all-ones word of `bits` bits
+fn all_ones_witness(bits: usize) -> [Vec<Fp>; COLUMNS] {
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); 1]);
+    witness[0][0] = all_ones::<Vesta>(bits);
+    witness
+}
+
+// The negation of a word of `bits` bits using Xor16 gates, which checks the length of the input
+fn not_xor(bits: usize) -> CircuitVersion<'static, Fp> {
+    let mut gates = create_all_ones();
+    CircuitGate::extend_not_gadget_checked_length(&mut gates, 0, bits);
+    CircuitVersion::new(gates, 1, move |inputs| {
+        let mut witness = all_ones_witness(bits);
+        not::extend_not_witness_checked_length(&mut witness, inputs[0], Some(bits));
+        witness
+    })
+    .unwrap()
+}
+
+// The negation of a word of `bits` bits using a generic gate, which assumes the length of the input
+fn not_gnrc(bits: usize) -> CircuitVersion<'static, Fp> {
+    let mut gates = create_all_ones();
+    CircuitGate::extend_not_gadget_unchecked_length(&mut gates, 1, 0);
+    CircuitVersion::new(gates, 1, move |inputs| {
+        let mut witness = all_ones_witness(bits);
+        // the witness of any input, even one longer than `bits`
+        let row = [witness[0][0], inputs[0], witness[0][0] - inputs[0]];
+        for (col, column) in witness.iter_mut().enumerate() {
+            column.push(row.get(col).copied().unwrap_or_else(Fp::zero));
+        }
+        witness
+    })
+    .unwrap()
+}
+
+#[test]
+// Test that the negations with Xor16 gates and with a generic gate are equivalent on inputs of 64 bits
+fn test_equivalence_not() {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    let result = check_equivalence::<Vesta, _>(&not_xor(64), &not_gnrc(64), 10, rng, |rng| {
+        vec![Fp::from(rng.gen::<u64>())]
+    });
+    assert_eq!(result, Ok(()));
+}
+
+#[test]
+// Test that only the negation with Xor16 gates checks the length of the inputs
+fn test_equivalence_not_length() {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    let result = check_equivalence::<Vesta, _>(&not_xor(64), &not_gnrc(64), 10, rng, |rng| {
+        vec![Fp::from(rng.gen::<u128>() | (1 << 100))]
+    });
+    assert_eq!(
+        result,
+        Err(EquivalenceError::Satisfiability {
+            sample: 0,
+            left: false,
+            right: true,
+        })
+    );
+}
+
+#[test]
+// Test that the versions must have the same public inputs
+fn test_equivalence_public() {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    let result = check_equivalence::<Vesta, _>(&not_xor(48), &not_gnrc(64), 10, rng, |rng| {
+        vec![Fp::from(rng.gen::<u32>())]
+    });
+    assert_eq!(result, Err(EquivalenceError::PublicValues(0)));
+
+    let mut gates = create_all_ones();
+    CircuitGate::extend_not_gadget_unchecked_length(&mut gates, 1, 0);
+    let private = CircuitVersion::new(gates, 0, |_| all_ones_witness(64)).unwrap();
+    let result = check_equivalence::<Vesta, _>(&not_gnrc(64), &private, 10, rng, |_| vec![]);
+    assert_eq!(
+        result,
+        Err(EquivalenceError::PublicLayout { left: 1, right: 0 })
+    );
+}
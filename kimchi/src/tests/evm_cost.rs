@@ -0,0 +1,66 @@
+use crate::{
+    circuits::{gate::CircuitGate, polynomials::generic::testing::create_circuit},
+    evm_cost::{GasSchedule, VerifierCostConfig},
+    prover_index::testing::new_index_for_test,
+};
+use mina_curves::pasta::{Fp, Vesta};
+
+#[test]
+// Test the configuration of a verifier index, and that public inputs and chunks increase the cost
+fn test_evm_cost_generic() {
+    let index = new_index_for_test::<Vesta>(create_circuit(0, 3), 3);
+    let config = VerifierCostConfig::from_index(&index.verifier_index());
+    assert_eq!(config.public, 3);
+    assert_eq!(config.lookup_sorted, None);
+    assert_eq!(config.field_bytes, 32);
+    assert_eq!(config.point_bytes, 64);
+
+    let schedule = GasSchedule::default();
+    let cost = config.estimate(&schedule);
+    assert_eq!(
+        cost.total_gas,
+        schedule.transaction + cost.calldata_gas + cost.msm_gas + cost.compute_gas
+    );
+    assert!(cost.msm_terms > config.max_poly_size);
+
+    let public = VerifierCostConfig {
+        public: 10,
+        ..config
+    }
+    .estimate(&schedule);
+    assert_eq!(
+        cost.calldata_bytes + 7 * config.field_bytes,
+        public.calldata_bytes
+    );
+    assert!(public.total_gas > cost.total_gas);
+
+    // a domain of two chunks
+    let chunked = VerifierCostConfig {
+        domain_size: 2 * config.max_poly_size,
+        ..config
+    }
+    .estimate(&schedule);
+    assert!(chunked.proof_points > cost.proof_points);
+    assert!(chunked.total_gas > cost.total_gas);
+}
+
+#[test]
+// Test that the lookup argument of xor gates increases the calldata and the gas
+fn test_evm_cost_lookup() {
+    let (_, gates) = CircuitGate::<Fp>::create_xor_gadget(0, 64);
+    let index = new_index_for_test::<Vesta>(gates, 0);
+    let config = VerifierCostConfig::from_index(&index.verifier_index());
+    assert!(config.lookup_sorted.is_some());
+    assert!(config.lookup_table_columns > 0);
+
+    let schedule = GasSchedule::default();
+    let cost = config.estimate(&schedule);
+    let without_lookup = VerifierCostConfig {
+        lookup_sorted: None,
+        lookup_table_columns: 0,
+        ..config
+    }
+    .estimate(&schedule);
+    assert!(cost.calldata_bytes > without_lookup.calldata_bytes);
+    assert!(cost.total_gas > without_lookup.total_gas);
+}
@@ -0,0 +1,126 @@
+use crate::{
+    circuits::{
+        polynomials::generic::testing::{create_circuit, fill_in_witness},
+        wires::COLUMNS,
+    },
+    fft::{self, CpuFft, FftBackend},
+    proof::ProverProof,
+    prover_index::{testing::new_index_for_test, ProverIndex},
+    verifier::verify,
+};
+use ark_ff::{UniformRand, Zero};
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Radix2EvaluationDomain as D, UVPolynomial,
+};
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use poly_commitment::commitment::CommitmentCurve;
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    array,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+// A backend computing the FFTs on the CPU and counting them, or declining them all,
+// which checks that it is given as many values as the size of the domain
+#[derive(Debug, Default)]
+struct CountingFft {
+    decline: bool,
+    ffts: AtomicUsize,
+    iffts: AtomicUsize,
+}
+
+impl FftBackend<Fp> for CountingFft {
+    fn fft_in_place(&self, domain: D<Fp>, coeffs: &mut Vec<Fp>) -> bool {
+        assert_eq!(coeffs.len(), domain.size());
+        self.ffts.fetch_add(1, Ordering::Relaxed);
+        !self.decline && CpuFft.fft_in_place(domain, coeffs)
+    }
+
+    fn ifft_in_place(&self, domain: D<Fp>, evals: &mut Vec<Fp>) -> bool {
+        assert_eq!(evals.len(), domain.size());
+        self.iffts.fetch_add(1, Ordering::Relaxed);
+        !self.decline && CpuFft.ifft_in_place(domain, evals)
+    }
+}
+
+fn prove(index: &ProverIndex<Vesta>, witness: &[Vec<Fp>; COLUMNS]) -> ProverProof<Vesta> {
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    ProverProof::create_recursive_with_rng::<BaseSponge, ScalarSponge, _>(
+        &group_map,
+        witness.clone(),
+        &[],
+        index,
+        vec![],
+        None,
+        &mut StdRng::seed_from_u64(0),
+    )
+    .unwrap()
+}
+
+#[test]
+// Test that the prover computes its FFTs with the backend of its index,
+// and gives the proof computed on the CPU whether the backend computes or declines the FFTs
+fn test_fft_backend() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+    let index = new_index_for_test::<Vesta>(gates, public.len());
+    let verifier_index = index.verifier_index();
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof = prove(&index, &witness);
+
+    for decline in [false, true] {
+        let backend = Arc::new(CountingFft {
+            decline,
+            ..CountingFft::default()
+        });
+        let backend_index = ProverIndex::create_with_fft_backend(
+            index.cs.clone(),
+            index.cs.endo,
+            index.srs.clone(),
+            backend.clone(),
+        );
+        let backend_proof = prove(&backend_index, &witness);
+        verify::<Vesta, BaseSponge, ScalarSponge>(
+            &group_map,
+            &verifier_index,
+            &backend_proof,
+            &public,
+        )
+        .unwrap();
+        assert_eq!(backend_proof.commitments.t_comm, proof.commitments.t_comm);
+        assert_eq!(backend_proof.ft_eval1, proof.ft_eval1);
+        assert_eq!(backend_proof.proof.delta, proof.proof.delta);
+
+        // the witness columns and the permutation and quotient polynomials are interpolated,
+        // and evaluated over d8
+        assert!(backend.iffts.load(Ordering::Relaxed) >= COLUMNS + 3);
+        assert!(backend.ffts.load(Ordering::Relaxed) >= COLUMNS + 1);
+    }
+}
+
+#[test]
+// Test that a polynomial of a smaller degree than the domain is given to the backend
+// with its coefficients resized to the size of the domain
+fn test_fft_backend_resized_coefficients() {
+    let rng = &mut StdRng::seed_from_u64(0);
+    let domain = D::<Fp>::new(16).unwrap();
+    let poly = DensePolynomial::from_coefficients_vec((0..5).map(|_| Fp::rand(rng)).collect());
+    let backend = CountingFft::default();
+    let evals = fft::evaluate_over_domain(&backend, &poly, domain);
+    assert_eq!(backend.ffts.load(Ordering::Relaxed), 1);
+    assert_eq!(evals, poly.evaluate_over_domain_by_ref(domain));
+}
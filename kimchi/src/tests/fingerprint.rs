@@ -0,0 +1,103 @@
+use super::custom_gate;
+use crate::{
+    circuits::{
+        constraints::ConstraintSystem, custom_gate::CustomGate, expr::prologue::*,
+        gate::CircuitGate, polynomials::generic::testing::create_circuit, wires::Wire,
+    },
+    prover_index::{
+        testing::{
+            new_index_for_test, new_index_for_test_with_cs, new_index_for_test_with_lookups,
+        },
+        ProverIndex,
+    },
+};
+use mina_curves::pasta::{Fp, Fq, Vesta, VestaParameters};
+use mina_poseidon::{constants::PlonkSpongeConstantsKimchi, sponge::DefaultFqSponge};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+
+fn fingerprint(index: &ProverIndex<Vesta>) -> Vec<u8> {
+    index.verifier_index().fingerprint::<BaseSponge>()
+}
+
+fn digest(index: &ProverIndex<Vesta>) -> Fq {
+    index.verifier_index().digest::<BaseSponge>()
+}
+
+#[test]
+// Test that the fingerprint identifies a circuit, whatever the instance of its index
+fn test_fingerprint() {
+    let gates = create_circuit::<Fp>(0, 3);
+    let index = new_index_for_test::<Vesta>(gates.clone(), 3);
+    assert_eq!(fingerprint(&index).len(), 64);
+    assert_eq!(
+        fingerprint(&index),
+        fingerprint(&new_index_for_test::<Vesta>(gates.clone(), 3))
+    );
+
+    // the coefficients of the gates
+    let mut other_gates = gates.clone();
+    other_gates[5].coeffs[4] += Fp::from(1u64);
+    assert_ne!(
+        fingerprint(&index),
+        fingerprint(&new_index_for_test::<Vesta>(other_gates, 3))
+    );
+
+    // the wiring of the gates
+    let mut other_gates = gates.clone();
+    other_gates[5].wires[0] = Wire::new(6, 0);
+    other_gates[6].wires[0] = Wire::new(5, 0);
+    assert_ne!(
+        fingerprint(&index),
+        fingerprint(&new_index_for_test::<Vesta>(other_gates, 3))
+    );
+}
+
+#[test]
+// Test that the fingerprint binds the configuration of the circuit which is not committed to,
+// unlike the digest of the verifier index
+fn test_fingerprint_configuration() {
+    let gates: Vec<CircuitGate<Fp>> = create_circuit(0, 3);
+    let index = new_index_for_test::<Vesta>(gates.clone(), 3);
+
+    // a public input less
+    let other = new_index_for_test::<Vesta>(gates.clone(), 2);
+    assert_eq!(digest(&index), digest(&other));
+    assert_ne!(fingerprint(&index), fingerprint(&other));
+
+    // previous challenges
+    let other = new_index_for_test_with_lookups::<Vesta>(gates, 3, 1, vec![], None, false);
+    assert_eq!(digest(&index), digest(&other));
+    assert_ne!(fingerprint(&index), fingerprint(&other));
+}
+
+#[test]
+// Test that the fingerprint binds the constraints of the custom gates,
+// of which only the selectors are committed to
+fn test_fingerprint_custom_constraints() {
+    let index = |mul_add: CustomGate<Fp>| {
+        let cs = ConstraintSystem::create(custom_gate::circuit())
+            .public(1)
+            .custom_gate(custom_gate::square_gate(), &[1, 2])
+            .custom_gate(mul_add, &[3])
+            .build()
+            .unwrap();
+        new_index_for_test_with_cs::<Vesta>(cs)
+    };
+    let base = index(custom_gate::mul_add_gate());
+    assert_eq!(
+        fingerprint(&base),
+        fingerprint(&index(custom_gate::mul_add_gate()))
+    );
+
+    // the coefficient is subtracted instead of added in the constraint of the mul_add gate
+    let mul_sub = CustomGate::new(
+        "mul_add",
+        vec![witness_next(0) - (witness_curr(0) * witness_curr(1) - coeff(0))],
+        2,
+    );
+    let other = index(mul_sub);
+    assert_eq!(digest(&base), digest(&other));
+    assert_ne!(fingerprint(&base), fingerprint(&other));
+}
@@ -0,0 +1,64 @@
+use std::array;
+
+use super::ecdsa::verify_rows;
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::CircuitGate,
+    polynomial::COLUMNS,
+    polynomials::{
+        ecdsa::curve::ForeignCurve,
+        foreign_curve_add::{self, CurveAddValue},
+    },
+};
+use ark_ff::One;
+use mina_curves::pasta::Fp;
+use num_bigint::BigUint;
+use o1_utils::foreign_field::BigUintForeignFieldHelpers;
+
+#[test]
+// Test that the witness of the addition of two points satisfies the gadget
+fn test_foreign_curve_add() {
+    let curve = ForeignCurve::secp256k1();
+    let left = curve.generator.clone();
+    let right = curve
+        .scalar_mul(&curve.generator, &BigUint::from(5u32))
+        .unwrap();
+    let (next_row, gates) = CircuitGate::<Fp>::create_foreign_curve_add(0, &curve);
+    assert_eq!(next_row, gates.len());
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let sum =
+        foreign_curve_add::extend_foreign_curve_add_witness(&mut witness, &curve, &left, &right);
+    assert_eq!(witness[0].len(), next_row);
+    assert_eq!(
+        sum,
+        curve
+            .scalar_mul(&curve.generator, &BigUint::from(6u32))
+            .unwrap()
+    );
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+    // the sum is at its position
+    let (row, col) = foreign_curve_add::foreign_curve_add_limb(0, CurveAddValue::SumY, 2);
+    assert_eq!(witness[col][row], sum.1.to_field_limbs::<Fp>()[2]);
+
+    // another sum breaks the constraints
+    let (row, col) = foreign_curve_add::foreign_curve_add_limb(0, CurveAddValue::SumX, 0);
+    witness[col][row] += Fp::one();
+    assert!(verify_rows(&cs, &witness).is_err());
+}
+
+#[test]
+#[should_panic]
+// Test that the witness of the addition of a point with itself is rejected
+fn test_foreign_curve_add_same_x() {
+    let curve = ForeignCurve::secp256k1();
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    foreign_curve_add::extend_foreign_curve_add_witness(
+        &mut witness,
+        &curve,
+        &curve.generator,
+        &curve.neg(&curve.generator),
+    );
+}
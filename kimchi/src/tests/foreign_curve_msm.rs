@@ -0,0 +1,113 @@
+use std::array;
+
+use super::ecdsa::verify_rows;
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::CircuitGate,
+    polynomial::COLUMNS,
+    polynomials::{
+        ecdsa::curve::{ForeignCurve, ForeignPoint},
+        foreign_curve_msm::{self, MsmValue},
+        foreign_curve_mul::{MulBase, MAX_WINDOW},
+    },
+};
+use ark_ff::One;
+use mina_curves::pasta::Fp;
+use num_bigint::BigUint;
+use o1_utils::foreign_field::BigUintForeignFieldHelpers;
+
+// The multiple of the generator of secp256k1
+fn multiple(curve: &ForeignCurve, scalar: u32) -> ForeignPoint {
+    curve
+        .scalar_mul(&curve.generator, &BigUint::from(scalar))
+        .unwrap()
+}
+
+#[test]
+// Test the multi-scalar multiplication of points of the witness and of a fixed point
+fn test_foreign_curve_msm() {
+    let curve = ForeignCurve::secp256k1();
+    let points = [
+        multiple(&curve, 3),
+        curve.generator.clone(),
+        multiple(&curve, 5),
+    ];
+    let bases = [
+        MulBase::Variable,
+        MulBase::Fixed(curve.generator.clone()),
+        MulBase::Variable,
+    ];
+    let scalars = [
+        &curve.order - BigUint::from(1u32),
+        BigUint::from(0x1234_5678u32),
+        BigUint::from(0u32),
+    ];
+    let (next_row, gates) = CircuitGate::<Fp>::create_foreign_curve_msm(0, &curve, &bases);
+    assert_eq!(next_row, gates.len());
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let result = foreign_curve_msm::extend_foreign_curve_msm_witness(
+        &mut witness,
+        &curve,
+        &bases,
+        &scalars,
+        &points,
+    );
+    assert_eq!(witness[0].len(), next_row);
+    // -3 G + 0x12345678 G + 0 (5 G)
+    assert_eq!(result, multiple(&curve, 0x1234_5678 - 3));
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+    // the elements are at their positions
+    let (row, col) = foreign_curve_msm::foreign_curve_msm_limb(0, &bases, MsmValue::PointX(2), 0);
+    assert_eq!(witness[col][row], points[2].0.to_field_limbs::<Fp>()[0]);
+    let (row, col) = foreign_curve_msm::foreign_curve_msm_limb(0, &bases, MsmValue::Scalar(1), 0);
+    assert_eq!(witness[col][row], Fp::from(0x1234_5678u32));
+
+    // another result breaks the constraints
+    let (row, col) = foreign_curve_msm::foreign_curve_msm_limb(0, &bases, MsmValue::ResultX, 2);
+    witness[col][row] += Fp::one();
+    assert!(verify_rows(&cs, &witness).is_err());
+}
+
+#[test]
+// Test that sharing the doublings lays out fewer rows than separate multiplications
+fn test_foreign_curve_msm_rows() {
+    let curve = ForeignCurve::secp256k1();
+    let msm = |bases: &[MulBase]| CircuitGate::<Fp>::create_foreign_curve_msm(0, &curve, bases).0;
+    let single = msm(&[MulBase::Variable]);
+    let double = msm(&[MulBase::Variable, MulBase::Variable]);
+    assert!(double < 2 * single);
+}
+
+#[test]
+// Test that the windowing of a single point has no joint table, and that the tables are bounded
+fn test_foreign_curve_msm_window() {
+    let curve = ForeignCurve::secp256k1();
+    let window = foreign_curve_msm::msm_window::<Fp>(&curve, &[MulBase::Variable]);
+    assert_eq!(window.points, 1);
+    for num_points in 1..=6 {
+        let bases = vec![MulBase::Fixed(curve.generator.clone()); num_points];
+        let window = foreign_curve_msm::msm_window::<Fp>(&curve, &bases);
+        assert!(window.points <= num_points && window.points * window.bits <= MAX_WINDOW);
+    }
+}
+
+#[test]
+#[should_panic]
+// Test that a result at infinity is rejected
+fn test_foreign_curve_msm_infinity() {
+    let curve = ForeignCurve::secp256k1();
+    let bases = [MulBase::Variable, MulBase::Variable];
+    let points = [curve.generator.clone(), curve.neg(&curve.generator)];
+    let scalars = [BigUint::from(2u32), BigUint::from(2u32)];
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    foreign_curve_msm::extend_foreign_curve_msm_witness(
+        &mut witness,
+        &curve,
+        &bases,
+        &scalars,
+        &points,
+    );
+}
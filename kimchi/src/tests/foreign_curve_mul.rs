@@ -0,0 +1,183 @@
+use std::array;
+
+use super::ecdsa::verify_rows;
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::CircuitGate,
+    polynomial::COLUMNS,
+    polynomials::{
+        ecdsa::{curve::ForeignCurve, layout::Layout},
+        foreign_curve_mul::{self, CurveMulValue, MulBase},
+    },
+};
+use ark_ff::One;
+use mina_curves::pasta::Fp;
+use num_bigint::BigUint;
+use o1_utils::foreign_field::BigUintForeignFieldHelpers;
+
+// Checks the witness of the multiplication of a multiple of the generator against the gadget
+fn check_foreign_curve_mul(window: usize, fixed: bool) {
+    let curve = ForeignCurve::secp256k1();
+    let point = curve
+        .scalar_mul(&curve.generator, &BigUint::from(7u32))
+        .unwrap();
+    let base = if fixed {
+        MulBase::Fixed(point.clone())
+    } else {
+        MulBase::Variable
+    };
+    let scalar = &curve.order - BigUint::from(0x1234_5678u32);
+    let (next_row, gates) = CircuitGate::<Fp>::create_foreign_curve_mul(0, &curve, window, &base);
+    assert_eq!(next_row, gates.len());
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let result = foreign_curve_mul::extend_foreign_curve_mul_witness(
+        &mut witness,
+        &curve,
+        window,
+        &base,
+        &scalar,
+        &point,
+    );
+    assert_eq!(witness[0].len(), next_row);
+    assert_eq!(
+        // k 7 G = -0x12345678 7 G
+        result,
+        curve.neg(
+            &curve
+                .scalar_mul(&curve.generator, &BigUint::from(7u32 * 0x1234_5678u32))
+                .unwrap()
+        )
+    );
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+    // the result is at its position
+    let (row, col) = foreign_curve_mul::foreign_curve_mul_limb(0, CurveMulValue::ResultX, 1);
+    assert_eq!(witness[col][row], result.0.to_field_limbs::<Fp>()[1]);
+
+    // another result breaks the constraints
+    let (row, col) = foreign_curve_mul::foreign_curve_mul_limb(0, CurveMulValue::ResultY, 0);
+    witness[col][row] += Fp::one();
+    assert!(verify_rows(&cs, &witness).is_err());
+}
+
+#[test]
+// Test the windowed multiplication of a point of the witness
+fn test_foreign_curve_mul_variable() {
+    check_foreign_curve_mul(3, false);
+}
+
+#[test]
+// Test the windowed multiplication of a fixed point, with a precomputed table
+fn test_foreign_curve_mul_fixed() {
+    check_foreign_curve_mul(4, true);
+}
+
+#[test]
+// Test that wider windows and precomputed tables lay out fewer rows
+fn test_foreign_curve_mul_rows() {
+    let curve = ForeignCurve::secp256k1();
+    let fixed = MulBase::Fixed(curve.generator.clone());
+    let rows =
+        |window, base| CircuitGate::<Fp>::create_foreign_curve_mul(0, &curve, window, base).0;
+    let naive = rows(1, &MulBase::Variable);
+    let windowed = rows(4, &MulBase::Variable);
+    assert!(windowed < naive);
+    assert!(rows(4, &fixed) < windowed);
+}
+
+#[test]
+// Test the multiplication of a fixed point with the tables in the coefficients of the gates,
+// which lays out fewer rows than the windowed multiplication with a precomputed table
+fn test_foreign_curve_fixed_mul() {
+    let curve = ForeignCurve::secp256k1();
+    let point = curve
+        .scalar_mul(&curve.generator, &BigUint::from(7u32))
+        .unwrap();
+    let scalar = &curve.order - BigUint::from(0x1234_5678u32);
+    let (next_row, gates) = CircuitGate::<Fp>::create_foreign_curve_fixed_mul(0, &curve, &point);
+    assert!(
+        next_row
+            < CircuitGate::<Fp>::create_foreign_curve_mul(
+                0,
+                &curve,
+                4,
+                &MulBase::Fixed(point.clone())
+            )
+            .0
+    );
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let result = foreign_curve_mul::extend_foreign_curve_fixed_mul_witness(
+        &mut witness,
+        &curve,
+        &point,
+        &scalar,
+    );
+    assert_eq!(witness[0].len(), next_row);
+    assert_eq!(result, curve.scalar_mul(&point, &scalar).unwrap());
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+    let (row, col) = foreign_curve_mul::foreign_curve_mul_limb(0, CurveMulValue::ResultX, 2);
+    assert_eq!(witness[col][row], result.0.to_field_limbs::<Fp>()[2]);
+    let (row, col) = foreign_curve_mul::foreign_curve_mul_limb(0, CurveMulValue::ResultY, 1);
+    witness[col][row] += Fp::one();
+    assert!(verify_rows(&cs, &witness).is_err());
+}
+
+#[test]
+// Test the fixed-base multiplication of a scalar of an odd number of bits, whose most significant window has one bit
+fn test_fixed_base_mul_odd_bits() {
+    let curve = ForeignCurve::p256();
+    let num_bits = 7;
+    let scalar = BigUint::from(0x5bu32);
+    let layout_mul = |with_witness| {
+        let mut layout = Layout::<Fp>::new(0, with_witness);
+        let value = layout.value(|| scalar.clone());
+        let k = layout.witness(value, &(BigUint::one() << num_bits));
+        let bits = layout.bits(&k, num_bits);
+        let result =
+            foreign_curve_mul::fixed_base_mul(&mut layout, &curve, &curve.generator, &bits);
+        let (gates, witness) = layout.finalize();
+        (gates, witness, result)
+    };
+    let (gates, _, _) = layout_mul(false);
+    let (_, witness, result) = layout_mul(true);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+    assert_eq!(verify_rows(&cs, &witness.unwrap()), Ok(()));
+
+    let expected = curve.scalar_mul(&curve.generator, &scalar).unwrap();
+    assert_eq!(result.x.value, Some(expected.0));
+    assert_eq!(result.y.value, Some(expected.1));
+}
+
+#[test]
+#[should_panic]
+// Test that the multiplication by zero is rejected
+fn test_foreign_curve_mul_zero() {
+    let curve = ForeignCurve::secp256k1();
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    foreign_curve_mul::extend_foreign_curve_mul_witness(
+        &mut witness,
+        &curve,
+        2,
+        &MulBase::Variable,
+        &BigUint::from(0u32),
+        &curve.generator,
+    );
+}
+
+#[test]
+#[should_panic]
+// Test that windows wider than the maximum are rejected
+fn test_foreign_curve_mul_window() {
+    let curve = ForeignCurve::secp256k1();
+    CircuitGate::<Fp>::create_foreign_curve_mul(
+        0,
+        &curve,
+        foreign_curve_mul::MAX_WINDOW + 1,
+        &MulBase::Variable,
+    );
+}
@@ -1,4 +1,7 @@
-use super::framework::TestFramework;
+use super::{
+    ecdsa::verify_rows,
+    framework::{TestCurve, TestFramework},
+};
 use crate::circuits::gate::CircuitGateResult;
 use crate::circuits::polynomials::generic::GenericGateSpec;
 use crate::prover_index::ProverIndex;
@@ -8,7 +11,13 @@ use crate::{
         gate::{CircuitGate, CircuitGateError, Connect, GateType},
         polynomial::COLUMNS,
         polynomials::{
-            foreign_field_add::witness::{self, FFOps},
+            foreign_field_add::{
+                bytes::{
+                    bytes_to_limbs_byte, bytes_to_limbs_limb, bytes_to_limbs_rows,
+                    extend_bytes_to_limbs_witness,
+                },
+                witness::{self, FFOps},
+            },
             range_check::{self, witness::extend_multi},
         },
         wires::Wire,
@@ -48,7 +57,7 @@ type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
 type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
 
 // The secp256k1 base field modulus
-fn secp256k1_modulus() -> BigUint {
+pub(crate) fn secp256k1_modulus() -> BigUint {
     BigUint::from_bytes_be(&secp256k1::constants::FIELD_SIZE)
 }
 
@@ -162,7 +171,7 @@ impl<F: PrimeField + SquareRootField> CircuitGate<F> {
             return Err("Gate is not a foreign field add gate".to_string());
         }
         match sign {
-            FFOps::Add => {
+            FFOps::Add | FFOps::Double => {
                 if self.coeffs[3] != F::one() {
                     return Err("Gate is not performing addition".to_string());
                 }
@@ -217,7 +226,7 @@ fn short_circuit<F: PrimeField + SquareRootField>(
 // Outputs tuple (next_row, circuit_gates) where
 //  next_row      - next row after this gate
 //  circuit_gates - vector of circuit gates comprising this gate
-fn full_circuit<F: PrimeField + SquareRootField>(
+pub(crate) fn full_circuit<F: PrimeField + SquareRootField>(
     opcodes: &[FFOps],
     foreign_field_modulus: &BigUint,
 ) -> (usize, Vec<CircuitGate<F>>) {
@@ -230,89 +239,86 @@ fn full_circuit<F: PrimeField + SquareRootField>(
     // -----
     // [n+3..n+6]    -> 1 Multi RangeCheck for first left input
     // {
-    //  [n+ 7+8i...n+10+8i]  -> 1 Multi RangeCheck for right input
-    //  [n+11+8i...n+14+8i]  -> 1 Multi RangeCheck for result
+    //  [...]        -> 1 Multi RangeCheck for right input (except for a doubling)
+    //  [...]        -> 1 Multi RangeCheck for result
     // } * num times
-    // [9n+7...9n+10] -> 1 Multi RangeCheck for bound
+    // [...]         -> 1 Multi RangeCheck for bound
     let (mut next_row, mut gates) = short_circuit(opcodes, foreign_field_modulus);
 
     let num = opcodes.len();
 
-    // RANGE CHECKS
-    // Add rangechecks for inputs, results, and final bound
-    CircuitGate::extend_multi_range_check(&mut gates, &mut next_row); // left input
-    for _ in 0..num {
-        for _ in 0..2 {
-            // right input and result
-            CircuitGate::extend_multi_range_check(&mut gates, &mut next_row);
-        }
-    }
-    // bound
-    CircuitGate::extend_multi_range_check(&mut gates, &mut next_row);
-
-    // WIRING
-    // Connect the num FFAdd gates with the range-check cells
-    for i in 0..num {
+    // RANGE CHECKS AND WIRING
+    // Add rangechecks for inputs, results, and final bound,
+    // and connect the num FFAdd gates with the range-check cells
+    let mut extend_range_check = |gates: &mut Vec<CircuitGate<F>>| {
+        let rc = next_row;
+        CircuitGate::extend_multi_range_check(gates, &mut next_row);
+        rc
+    };
+    let mut left_rc = extend_range_check(&mut gates); // left input
+    for (i, opcode) in opcodes.iter().enumerate() {
         let ffadd_row = i + 1;
-        let left_rc = num + 3 + 8 * i;
-        let right_rc = num + 7 + 8 * i;
-        let out_rc = num + 11 + 8 * i;
-        gates.connect_ffadd_range_checks(ffadd_row, Some(left_rc), Some(right_rc), out_rc);
+        // the right input of a doubling is not read, so that it needs no range check
+        let right_rc = (*opcode != FFOps::Double).then(|| extend_range_check(&mut gates));
+        let out_rc = extend_range_check(&mut gates);
+        gates.connect_ffadd_range_checks(ffadd_row, Some(left_rc), right_rc, out_rc);
+        left_rc = out_rc;
     }
     // Connect final bound gate to range-check cells
     let check_row = num + 1;
-    let bound_rc = 9 * num + 7;
+    let bound_rc = extend_range_check(&mut gates);
     gates.connect_ffadd_range_checks(check_row, None, None, bound_rc);
     (next_row, gates)
 }
 
-// Creates the witness with the public input for FFAdd containing the 1 value
-fn short_witness<F: PrimeField>(
-    inputs: &Vec<BigUint>,
-    opcodes: &[FFOps],
-    modulus: BigUint,
-) -> [Vec<F>; COLUMNS] {
+// Prepends the public input for FFAdd containing the 1 value to the witness of a chain
+fn public_witness<F: PrimeField>(add_witness: &[Vec<F>; COLUMNS]) -> [Vec<F>; COLUMNS] {
     let mut witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![F::zero(); 1]);
     witness[0][0] = F::one();
-    let add_witness = witness::create_chain::<F>(inputs, opcodes, modulus);
     for col in 0..COLUMNS {
         witness[col].extend(add_witness[col].iter());
     }
     witness
 }
 
+// Creates the witness with the public input for FFAdd containing the 1 value
+fn short_witness<F: PrimeField>(
+    inputs: &Vec<BigUint>,
+    opcodes: &[FFOps],
+    modulus: BigUint,
+) -> [Vec<F>; COLUMNS] {
+    public_witness(&witness::create_chain::<F>(inputs, opcodes, modulus))
+}
+
 // Creates a long witness including the chain of additions and rangechecks for all of the involved values
 // inputs: list of all inputs to the chain of additions/subtractions
 // opcode: true for addition, false for subtraction
 // modulus: modulus of the foreign field
-fn long_witness<F: PrimeField>(
+pub(crate) fn long_witness<F: PrimeField>(
     inputs: &Vec<BigUint>,
     opcodes: &[FFOps],
     modulus: BigUint,
 ) -> [Vec<F>; COLUMNS] {
-    let mut witness: [Vec<F>; COLUMNS] = short_witness(inputs, opcodes, modulus);
-
-    let num = inputs.len() - 1; // number of chained additions
+    let chain = witness::create_chain_witness::<F>(inputs, opcodes, modulus);
+    let mut witness: [Vec<F>; COLUMNS] = public_witness(&chain.witness);
+    let mut extend_range_check = |cells: [Wire; 3]| {
+        let [lo, mi, hi] = chain.limbs(cells);
+        range_check::witness::extend_multi(&mut witness, lo, mi, hi);
+    };
 
     // Create multi-range-check witness for first left input
-    let left = (witness[0][1], witness[1][1], witness[2][1]);
-    range_check::witness::extend_multi(&mut witness, left.0, left.1, left.2);
-
-    // Create multi-range-check witness for chained right inputs and results
-    for i in 1..num + 1 {
-        let right = (witness[3][i], witness[4][i], witness[5][i]);
-        let output = (witness[0][i + 1], witness[1][i + 1], witness[2][i + 1]);
-        range_check::witness::extend_multi(&mut witness, right.0, right.1, right.2);
-        range_check::witness::extend_multi(&mut witness, output.0, output.1, output.2);
+    extend_range_check(chain.ops[0].left);
+
+    // Create multi-range-check witness for chained right inputs (except for doublings) and results
+    for (op, opcode) in chain.ops.iter().zip(opcodes) {
+        if *opcode != FFOps::Double {
+            extend_range_check(op.right);
+        }
+        extend_range_check(op.result);
     }
 
     // Create multi-range-check witness for final bound
-    let bound = (
-        witness[0][num + 2],
-        witness[1][num + 2],
-        witness[2][num + 2],
-    );
-    range_check::witness::extend_multi(&mut witness, bound.0, bound.1, bound.2);
+    extend_range_check(chain.bound);
 
     witness
 }
@@ -1243,6 +1249,7 @@ fn test_random_chain() {
             let result = match op {
                 FFOps::Add => compute_sum(foreign_mod.clone(), &left[i], &inputs[i + 1]),
                 FFOps::Sub => compute_dif(foreign_mod.clone(), &left[i], &inputs[i + 1]),
+                FFOps::Double => unreachable!("the random operations have a right input"),
             };
             left.push(result.to_bytes_be());
             ForeignElement::<PallasField, 3>::from_biguint(result)
@@ -1251,8 +1258,136 @@ fn test_random_chain() {
     check_result(witness, results);
 }
 
-// Prove and verify used for end-to-end tests
-fn prove_and_verify(operation_count: usize) {
+#[test]
+// Test that a doubling reads its left input as its right input, without a right input of the chain
+// nor right input cells
+fn test_ffadd_double() {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    let foreign_mod = secp256k1_modulus();
+    let operations = [FFOps::Add, FFOps::Double, FFOps::Sub, FFOps::Double];
+    let inputs = (0..3)
+        .map(|_| {
+            BigUint::from_bytes_be(&random_input(rng, foreign_mod.clone(), true)) % &foreign_mod
+        })
+        .collect::<Vec<BigUint>>();
+
+    // the doubling has a doubling coefficient, and its right input cells are not wired
+    let (_next_row, gates) = short_circuit::<PallasField>(&operations, &foreign_mod);
+    assert!(gates[2].check_ffadd_sign(FFOps::Double).is_ok());
+    assert_eq!(gates[2].coeffs[4], PallasField::one());
+    assert_eq!(gates[1].coeffs[4], PallasField::zero());
+    for col in 3..6 {
+        assert_eq!(gates[2].wires[col], Wire { row: 2, col });
+    }
+
+    let witness = short_witness(&inputs, &operations, foreign_mod.clone());
+    let sum = (&inputs[0] + &inputs[1]) % &foreign_mod;
+    let double = (&sum + &sum) % &foreign_mod;
+    let dif = (&foreign_mod + &double - &inputs[2]) % &foreign_mod;
+    let results: Vec<ForeignElement<PallasField, 3>> = [&sum, &double, &dif, &(&dif + &dif)]
+        .into_iter()
+        .map(|result| ForeignElement::from_biguint(result % &foreign_mod))
+        .collect();
+    check_result(witness.clone(), results);
+
+    let cs = ConstraintSystem::create(gates.clone())
+        .public(1)
+        .build()
+        .unwrap();
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness.clone())
+        .public_inputs(vec![PallasField::one()])
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+
+    // the right input cells of the doubling are not read, but its left input is
+    let mut unread_witness = witness.clone();
+    unread_witness[3][2] += PallasField::one();
+    assert_eq!(verify_rows(&cs, &unread_witness), Ok(()));
+    let mut wrong_witness = witness;
+    wrong_witness[0][2] += PallasField::one();
+    assert!(verify_rows(&cs, &wrong_witness).is_err());
+}
+
+#[test]
+// Test that the doublings of a range checked chain save the range checks of their right inputs
+fn test_ffadd_double_range_checks() {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    let foreign_mod = secp256k1_modulus();
+    let input =
+        BigUint::from_bytes_be(&random_input(rng, foreign_mod.clone(), true)) % &foreign_mod;
+
+    // the doubling of the input twice, and its addition to itself twice
+    let doublings = [FFOps::Double, FFOps::Double];
+    let additions = [FFOps::Add, FFOps::Add];
+    let (double_rows, _) = full_circuit::<PallasField>(&doublings, &foreign_mod);
+    let (add_rows, _) = full_circuit::<PallasField>(&additions, &foreign_mod);
+    // a multi range check of 4 rows per doubling
+    assert_eq!(double_rows + 4 * doublings.len(), add_rows);
+
+    let double = (&input + &input) % &foreign_mod;
+    let add_inputs = vec![input.clone(), input.clone(), double.clone()];
+    let (double_witness, _) = test_ffadd(foreign_mod.clone(), vec![input], &doublings, true);
+    let (add_witness, _) = test_ffadd(foreign_mod.clone(), add_inputs, &additions, true);
+    assert_eq!(double_witness[0].len(), double_rows);
+    assert_eq!(add_witness[0].len(), add_rows);
+    let result = ForeignElement::from_biguint((&double + &double) % &foreign_mod);
+    check_result(
+        double_witness,
+        vec![ForeignElement::from_biguint(double), result],
+    );
+}
+
+#[test]
+// Test that the witness of a chain gives the cells of the result of each operation and of the bound
+fn test_chain_witness_cells() {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    let foreign_mod = secp256k1_modulus();
+    let operations = [FFOps::Add, FFOps::Double, FFOps::Sub];
+    let inputs = (0..3)
+        .map(|_| {
+            BigUint::from_bytes_be(&random_input(rng, foreign_mod.clone(), true)) % &foreign_mod
+        })
+        .collect::<Vec<BigUint>>();
+
+    let chain =
+        witness::create_chain_witness::<PallasField>(&inputs, &operations, foreign_mod.clone());
+    assert_eq!(
+        chain.witness,
+        witness::create_chain::<PallasField>(&inputs, &operations, foreign_mod.clone())
+    );
+
+    let sum = (&inputs[0] + &inputs[1]) % &foreign_mod;
+    let double = (&sum + &sum) % &foreign_mod;
+    let dif = (&foreign_mod + &double - &inputs[2]) % &foreign_mod;
+    let limbs =
+        |value: &BigUint| ForeignElement::<PallasField, 3>::from_biguint(value.clone()).limbs;
+    assert_eq!(chain.ops.len(), operations.len());
+    for (i, (op, result)) in chain.ops.iter().zip([&sum, &double, &dif]).enumerate() {
+        assert_eq!(op.row, i);
+        assert_eq!(op.result[0], Wire { row: i + 1, col: 0 });
+        assert_eq!(chain.limbs(op.result), limbs(result));
+    }
+    // the doubling reads its left input as its right input
+    assert_eq!(chain.limbs(chain.ops[1].right), limbs(&sum));
+    assert_eq!(chain.limbs(chain.ops[2].right), limbs(&inputs[2]));
+
+    // the bound of the result is on the row after the bound addition
+    assert_eq!(chain.bound_row, 3);
+    assert_eq!(chain.result(), chain.ops[2].result);
+    assert_eq!(chain.bound[0], Wire { row: 4, col: 0 });
+    let bound = dif + BigUint::binary_modulus() - &foreign_mod;
+    assert_eq!(chain.limbs(chain.bound), limbs(&bound));
+}
+
+// Prove and verify used for end-to-end tests, on the curve G
+fn prove_and_verify<G: TestCurve>(operation_count: usize)
+where
+    G::BaseField: PrimeField,
+{
     let rng = &mut StdRng::from_seed(RNG_SEED);
 
     // Create random operations
@@ -1277,25 +1412,27 @@ fn prove_and_verify(operation_count: usize) {
     // Create witness
     let witness = short_witness(&inputs, &operations, foreign_field_modulus);
 
-    TestFramework::<Vesta>::default()
+    TestFramework::<G>::default()
         .gates(gates)
         .witness(witness)
-        .public_inputs(vec![PallasField::one()])
+        .public_inputs(vec![G::ScalarField::one()])
         .setup()
-        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>()
         .unwrap();
 }
 
-// Prove and verify a randomly generated operation (only ffadd)
+// Prove and verify a randomly generated operation (only ffadd), over both curves
 #[test]
 fn prove_and_verify_1() {
-    prove_and_verify(1);
+    prove_and_verify::<Vesta>(1);
+    prove_and_verify::<Pallas>(1);
 }
 
-// Prove and verify a chain of 50 randomly generated operations (only ffadd)
+// Prove and verify a chain of 50 randomly generated operations (only ffadd), over both curves
 #[test]
 fn prove_and_verify_50() {
-    prove_and_verify(50);
+    prove_and_verify::<Vesta>(50);
+    prove_and_verify::<Pallas>(50);
 }
 
 // Extends a gate with the final bound range check
@@ -1487,19 +1624,16 @@ fn test_ffadd_finalization() {
         let left = modulus.clone() - BigUint::one();
         let right = modulus.clone() - BigUint::one();
         // create a chain of 1 addition
-        let add_witness = witness::create_chain::<Fp>(&vec![left, right], operation, modulus);
+        let chain = witness::create_chain_witness::<Fp>(&vec![left, right], operation, modulus);
         for col in 0..COLUMNS {
-            witness[col].extend(add_witness[col].iter());
+            witness[col].extend(chain.witness[col].iter());
         }
         // extend range checks for all of left, right, output, and bound
-        let left = (witness[0][1], witness[1][1], witness[2][1]);
-        range_check::witness::extend_multi(&mut witness, left.0, left.1, left.2);
-        let right = (witness[3][1], witness[4][1], witness[5][1]);
-        range_check::witness::extend_multi(&mut witness, right.0, right.1, right.2);
-        let output = (witness[0][2], witness[1][2], witness[2][2]);
-        range_check::witness::extend_multi(&mut witness, output.0, output.1, output.2);
-        let bound = (witness[0][3], witness[1][3], witness[2][3]);
-        range_check::witness::extend_multi(&mut witness, bound.0, bound.1, bound.2);
+        let op = chain.ops[0];
+        for cells in [op.left, op.right, op.result, chain.bound] {
+            let [lo, mi, hi] = chain.limbs(cells);
+            range_check::witness::extend_multi(&mut witness, lo, mi, hi);
+        }
         witness
     };
 
@@ -1575,3 +1709,148 @@ fn test_witness_invalid_foreign_field_modulus() {
         BigUint::max_foreign_field_modulus::<PallasField>() + BigUint::one(),
     );
 }
+
+// Creates the circuit and the witness of the byte limbs gadget of the bytes
+fn bytes_to_limbs_circuit(
+    bytes: &[u8],
+) -> (Vec<CircuitGate<PallasField>>, [Vec<PallasField>; COLUMNS]) {
+    let (next_row, gates) = CircuitGate::create_bytes_to_limbs(0, bytes.len());
+    assert_eq!(next_row, bytes_to_limbs_rows(bytes.len()));
+    let mut witness: [Vec<PallasField>; COLUMNS] = array::from_fn(|_| vec![]);
+    extend_bytes_to_limbs_witness(&mut witness, bytes);
+    assert_eq!(witness[0].len(), next_row);
+    (gates, witness)
+}
+
+#[test]
+// Test the limbs and the positions of the bytes of the byte limbs gadget, for all the lengths of the limbs
+fn test_bytes_to_limbs() {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    for num_bytes in [1, 10, 11, 12, 22, 23, 32, 33] {
+        let bytes: Vec<u8> = (0..num_bytes).map(|_| rng.gen()).collect();
+        let (gates, witness) = bytes_to_limbs_circuit(&bytes);
+        let cs = ConstraintSystem::create(gates).build().unwrap();
+        for row in 0..witness[0].len() {
+            assert_eq!(
+                cs.gates[row].verify_witness::<Vesta>(row, &witness, &cs, &[]),
+                Ok(())
+            );
+        }
+
+        let limbs = BigUint::from_bytes_be(&bytes).to_field_limbs::<PallasField>();
+        for (i, limb) in limbs.iter().enumerate() {
+            let (row, col) = bytes_to_limbs_limb(0, num_bytes, i);
+            assert_eq!(witness[col][row], *limb);
+        }
+        for (i, byte) in bytes.iter().enumerate() {
+            let (row, col) = bytes_to_limbs_byte(0, num_bytes, i);
+            assert_eq!(witness[col][row], PallasField::from(*byte));
+        }
+    }
+}
+
+#[test]
+// Test that a limb which is not the recomposition of its bytes breaks the copy constraints
+fn test_bytes_to_limbs_invalid_limb() {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    let bytes: Vec<u8> = (0..32).map(|_| rng.gen()).collect();
+    let (gates, mut witness) = bytes_to_limbs_circuit(&bytes);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    let (row, col) = bytes_to_limbs_limb(0, 32, 1);
+    witness[col][row] += PallasField::one();
+    let error = (0..witness[0].len())
+        .map(|row| cs.gates[row].verify_witness::<Vesta>(row, &witness, &cs, &[]))
+        .find(|result| result.is_err());
+    assert!(matches!(
+        error,
+        Some(Err(CircuitGateError::CopyConstraint { .. }))
+    ));
+}
+
+#[test]
+// Test the addition of an element recomposed from the bytes of a hash
+fn test_bytes_to_limbs_ffadd() {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    let foreign_mod = secp256k1_modulus();
+    let hash: Vec<u8> = (0..32).map(|_| rng.gen()).collect();
+    let left = BigUint::from_bytes_be(&hash) % &foreign_mod;
+    let hash = left.to_bytes_be();
+    let right = rng.gen_biguint_range(&BigUint::zero(), &foreign_mod);
+
+    // the limbs of the bytes are the left input of the addition
+    let (mut gates, mut witness) = bytes_to_limbs_circuit(&hash);
+    let ffadd_row = gates.len();
+    let (_, mut ffadd_gates) =
+        CircuitGate::create_single_ffadd(ffadd_row, FFOps::Add, &foreign_mod);
+    gates.append(&mut ffadd_gates);
+    for limb in 0..3 {
+        gates.connect_cell_pair(bytes_to_limbs_limb(0, hash.len(), limb), (ffadd_row, limb));
+    }
+    let ffadd_witness = witness::create_chain(&vec![left, right], &[FFOps::Add], foreign_mod);
+    for col in 0..COLUMNS {
+        witness[col].extend(ffadd_witness[col].iter());
+    }
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+// Test that the cells of a chain of additions are those of its witness,
+// and that its result and its bound are wired to their range checks from its cells
+fn test_ffadd_chain_cells() {
+    let foreign_mod = secp256k1_modulus();
+    let opcodes = [FFOps::Add, FFOps::Double, FFOps::Sub];
+    let inputs = vec![secp256k1_max(), BigUint::from(7u32), BigUint::from(5u32)];
+    let mut gates = vec![CircuitGate::<PallasField>::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )];
+    let cells = CircuitGate::extend_foreign_field_add(&mut gates, 0, &opcodes, &foreign_mod);
+    assert_eq!(cells.next_row, gates.len());
+    assert_eq!(cells.ops.len(), opcodes.len());
+    assert_eq!(cells.bound_overflow, Wire::new(1 + opcodes.len(), 6));
+
+    // the cells of the witness are relative to the first row of the chain
+    let chain = witness::create_chain_witness::<PallasField>(&inputs, &opcodes, foreign_mod);
+    let shift = |limbs: [Wire; 3]| limbs.map(|cell| Wire::new(1 + cell.row, cell.col));
+    for (op, chain_op) in cells.ops.iter().zip(&chain.ops) {
+        assert_eq!(op.row, 1 + chain_op.row);
+        assert_eq!(op.left, shift(chain_op.left));
+        assert_eq!(op.right, shift(chain_op.right));
+        assert_eq!(op.result, shift(chain_op.result));
+    }
+    assert_eq!(cells.result(), shift(chain.result()));
+    assert_eq!(cells.bound, shift(chain.bound));
+
+    // the result and the bound are range checked
+    let mut next_row = cells.next_row;
+    CircuitGate::extend_multi_range_checks(&mut gates, &mut next_row, 2);
+    let range_check_limbs = |i: usize| {
+        array::from_fn(|limb| {
+            let (row, col) = range_check::gadget::multi_range_check_limb(cells.next_row, i, limb);
+            Wire::new(row, col)
+        })
+    };
+    gates.connect_limbs(cells.result(), range_check_limbs(0));
+    gates.connect_limbs(cells.bound, range_check_limbs(1));
+
+    let mut witness = public_witness(&chain.witness);
+    for limbs in [chain.result(), chain.bound] {
+        let [lo, mi, hi] = chain.limbs(limbs);
+        extend_multi(&mut witness, lo, mi, hi);
+    }
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(vec![PallasField::one()])
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
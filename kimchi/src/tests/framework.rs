@@ -2,29 +2,59 @@
 
 use crate::{
     circuits::{
+        constraints::ConstraintSystem,
+        custom_gate::CustomGate,
         gate::CircuitGate,
         lookup::{
+            lookups::LookupArgument,
             runtime_tables::{RuntimeTable, RuntimeTableCfg},
             tables::LookupTable,
         },
         wires::COLUMNS,
     },
     curve::KimchiCurve,
+    mock_prover::{MockProver, MockProverFailure},
     plonk_sponge::FrSponge,
     proof::{ProverProof, RecursionChallenge},
-    prover_index::{testing::new_index_for_test_with_lookups, ProverIndex},
+    prover_index::{
+        testing::{new_index_for_test_with_cs, new_index_for_test_with_srs},
+        ProverIndex,
+    },
     verifier::verify,
     verifier_index::VerifierIndex,
 };
 use ark_ff::PrimeField;
 use groupmap::GroupMap;
-use mina_poseidon::sponge::FqSponge;
+use mina_curves::pasta::{Fp, Fq, Pallas, PallasParameters, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge, FqSponge},
+};
 use num_bigint::BigUint;
-use poly_commitment::commitment::CommitmentCurve;
-use std::{fmt::Write, mem, time::Instant};
+use poly_commitment::{commitment::CommitmentCurve, srs::SRS};
+use std::{fmt::Write, mem, ops::Range, sync::Arc, time::Instant};
 
 // aliases
 
+/// A curve of the tests, with the sponges of its proofs,
+/// so that a test generic over the curve runs over both Pasta curves
+pub(crate) trait TestCurve: KimchiCurve {
+    /// The sponge of the base field
+    type BaseSponge: Clone + FqSponge<Self::BaseField, Self, Self::ScalarField>;
+    /// The sponge of the scalar field
+    type ScalarSponge: FrSponge<Self::ScalarField>;
+}
+
+impl TestCurve for Vesta {
+    type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+    type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+}
+
+impl TestCurve for Pallas {
+    type BaseSponge = DefaultFqSponge<PallasParameters, PlonkSpongeConstantsKimchi>;
+    type ScalarSponge = DefaultFrSponge<Fq, PlonkSpongeConstantsKimchi>;
+}
+
 #[derive(Default, Clone)]
 pub(crate) struct TestFramework<G: KimchiCurve> {
     gates: Option<Vec<CircuitGate<G::ScalarField>>>,
@@ -33,9 +63,14 @@ pub(crate) struct TestFramework<G: KimchiCurve> {
     lookup_tables: Vec<LookupTable<G::ScalarField>>,
     runtime_tables_setup: Option<Vec<RuntimeTableCfg<G::ScalarField>>>,
     runtime_tables: Vec<RuntimeTable<G::ScalarField>>,
+    lookup_argument: LookupArgument,
+    lookup_table_rows: Option<Range<usize>>,
     recursion: Vec<RecursionChallenge<G>>,
     num_prev_challenges: usize,
     disable_gates_checks: bool,
+    srs: Option<Arc<SRS<G>>>,
+    custom_gates: Vec<(CustomGate<G::ScalarField>, Vec<usize>)>,
+    quotient_chunks: Option<usize>,
 
     prover_index: Option<ProverIndex<G>>,
     verifier_index: Option<VerifierIndex<G>>,
@@ -88,28 +123,95 @@ where
         self
     }
 
+    #[must_use]
+    pub(crate) fn lookup_argument(mut self, lookup_argument: LookupArgument) -> Self {
+        self.lookup_argument = lookup_argument;
+        self
+    }
+
+    /// Place the lookup tables in the given rows,
+    /// see [crate::circuits::constraints::Builder::lookup_table_rows]
+    #[must_use]
+    pub(crate) fn lookup_table_rows(mut self, rows: Range<usize>) -> Self {
+        self.lookup_table_rows = Some(rows);
+        self
+    }
+
     #[must_use]
     pub(crate) fn disable_gates_checks(mut self, disable_gates_checks: bool) -> Self {
         self.disable_gates_checks = disable_gates_checks;
         self
     }
 
-    /// creates the indexes
+    /// Use a prebuilt SRS instead of the one shared by the tests
+    /// (see [crate::prover_index::testing::srs_for_test])
     #[must_use]
-    pub(crate) fn setup(mut self) -> TestRunner<G> {
-        let start = Instant::now();
+    pub(crate) fn srs(mut self, srs: Arc<SRS<G>>) -> Self {
+        self.srs = Some(srs);
+        self
+    }
+
+    /// Use a custom gate on the given rows, see [crate::circuits::constraints::Builder::custom_gate]
+    #[must_use]
+    pub(crate) fn custom_gate(mut self, gate: CustomGate<G::ScalarField>, rows: &[usize]) -> Self {
+        self.custom_gates.push((gate, rows.to_vec()));
+        self
+    }
 
+    /// Commit to `quotient_chunks` chunks of the quotient, see [crate::circuits::constraints::Builder::quotient_chunks]
+    #[must_use]
+    pub(crate) fn quotient_chunks(mut self, quotient_chunks: usize) -> Self {
+        self.quotient_chunks = Some(quotient_chunks);
+        self
+    }
+
+    /// creates the constraint system
+    fn constraint_system(&mut self) -> ConstraintSystem<G::ScalarField> {
         let lookup_tables = std::mem::take(&mut self.lookup_tables);
         let runtime_tables_setup = mem::replace(&mut self.runtime_tables_setup, None);
 
-        let index = new_index_for_test_with_lookups::<G>(
-            self.gates.take().unwrap(),
-            self.public_inputs.len(),
-            self.num_prev_challenges,
-            lookup_tables,
-            runtime_tables_setup,
-            self.disable_gates_checks,
-        );
+        let builder = ConstraintSystem::create(self.gates.take().unwrap())
+            .lookup(lookup_tables)
+            .runtime(runtime_tables_setup)
+            .lookup_argument(self.lookup_argument)
+            .public(self.public_inputs.len())
+            .prev_challenges(self.num_prev_challenges)
+            .disable_gates_checks(self.disable_gates_checks);
+        let builder = match self.lookup_table_rows.take() {
+            Some(rows) => builder.lookup_table_rows(rows),
+            None => builder,
+        };
+        let builder = match self.quotient_chunks {
+            Some(quotient_chunks) => builder.quotient_chunks(quotient_chunks),
+            None => builder,
+        };
+        mem::take(&mut self.custom_gates)
+            .into_iter()
+            .fold(builder, |builder, (gate, rows)| {
+                builder.custom_gate(gate, &rows)
+            })
+            .build()
+            .unwrap()
+    }
+
+    /// Check the witness with the mock prover, without creating the indexes
+    /// (the circuit cannot use runtime tables)
+    pub(crate) fn mock_prove(mut self) -> Result<(), Vec<MockProverFailure>> {
+        let cs = self.constraint_system();
+        let witness = self.witness.unwrap();
+        MockProver::<G>::new(&cs, &witness, &self.public_inputs).verify()
+    }
+
+    /// creates the indexes
+    #[must_use]
+    pub(crate) fn setup(mut self) -> TestRunner<G> {
+        let start = Instant::now();
+
+        let cs = self.constraint_system();
+        let index = match self.srs.take() {
+            Some(srs) => new_index_for_test_with_srs::<G>(cs, srs),
+            None => new_index_for_test_with_cs::<G>(cs),
+        };
         println!(
             "- time to create prover index: {:?}s",
             start.elapsed().as_secs()
@@ -0,0 +1,197 @@
+//! Test vectors of the constraints of the gates, for the implementations of kimchi in other languages.
+//!
+//! For each gate with constraints, the file `src/tests/test_vectors/gates.json` gives random
+//! coefficients and rows `Curr` and `Next` of the witness over the Pallas base field,
+//! and the evaluations of the constraints of the gate on them (in the order of their numbers),
+//! as little-endian hexadecimal strings.
+//! The constraints of the Poseidon gate use the MDS matrix of the kimchi parameters of the field,
+//! and the ones of the endomorphism gates use the endomorphism coefficient of the file.
+//!
+//! The test checks the evaluations of the file, which is committed: it only writes it
+//! if the environment variable `REGENERATE_GATE_VECTORS` is set, and fails if it is missing.
+
+use crate::{
+    circuits::{
+        argument::{ArgumentEnv, ArgumentWitness},
+        expr::Constants,
+        gate::{CircuitGate, GateType},
+        wires::{Wire, COLUMNS},
+    },
+    curve::KimchiCurve,
+};
+use ark_ff::{One, UniformRand};
+use mina_curves::pasta::{Fp, Pallas, Vesta};
+use poly_commitment::srs::endos;
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use std::{
+    env,
+    fs::{self, File},
+    iter,
+    path::PathBuf,
+};
+
+// The gates which have constraints
+const GATES: [GateType; 17] = [
+    GateType::Generic,
+    GateType::Poseidon,
+    GateType::CompleteAdd,
+    GateType::VarBaseMul,
+    GateType::EndoMul,
+    GateType::EndoMulScalar,
+    GateType::CairoClaim,
+    GateType::CairoInstruction,
+    GateType::CairoFlags,
+    GateType::CairoTransition,
+    GateType::RangeCheck0,
+    GateType::RangeCheck1,
+    GateType::ForeignFieldAdd,
+    GateType::ForeignFieldMul,
+    GateType::Xor16,
+    GateType::Rot64,
+    GateType::AddU64,
+];
+
+// The number of vectors of each gate
+const NUM_VECTORS: usize = 2;
+
+// The number of coefficients of the vectors, enough for all the gates
+const NUM_COEFFS: usize = COLUMNS;
+
+const RNG_SEED: [u8; 32] = [
+    107, 105, 109, 99, 104, 105, 32, 103, 97, 116, 101, 32, 116, 101, 115, 116, 32, 118, 101, 99,
+    116, 111, 114, 115, 0, 0, 0, 0, 0, 0, 0, 0,
+];
+
+#[serde_as]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct GateVectors {
+    #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+    endo_coefficient: Fp,
+    test_vectors: Vec<GateVector>,
+}
+
+#[serde_as]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct GateVector {
+    gate: GateType,
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    coeffs: Vec<Fp>,
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    curr: Vec<Fp>,
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    next: Vec<Fp>,
+    #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+    constraints: Vec<Fp>,
+}
+
+// The gates of the vectors, in their order
+fn vector_gates() -> Vec<GateType> {
+    GATES
+        .iter()
+        .flat_map(|gate| iter::repeat(*gate).take(NUM_VECTORS))
+        .collect()
+}
+
+fn vectors_path() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("src/tests/test_vectors/gates.json");
+    path
+}
+
+// Evaluates the constraints of a gate on the rows of a witness
+fn evaluate(
+    gate: GateType,
+    coeffs: &[Fp],
+    curr: &[Fp],
+    next: &[Fp],
+    endo_coefficient: Fp,
+) -> Vec<Fp> {
+    let row = |cells: &[Fp]| -> [Fp; COLUMNS] { cells.try_into().expect("a row of the witness") };
+    let constants = Constants {
+        alpha: Fp::one(),
+        beta: Fp::one(),
+        gamma: Fp::one(),
+        joint_combiner: Some(Fp::one()),
+        endo_coefficient,
+        mds: &Vesta::sponge_params().mds,
+    };
+    let env = ArgumentEnv::create(
+        ArgumentWitness {
+            curr: row(curr),
+            next: row(next),
+        },
+        coeffs.to_vec(),
+        constants,
+    );
+    CircuitGate::new(gate, Wire::for_row(0), coeffs.to_vec()).evaluate_constraints(&env)
+}
+
+// Creates the vectors of the gates, from random coefficients and witness
+fn generate() -> GateVectors {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    let (endo_coefficient, _) = endos::<Pallas>();
+    let test_vectors = vector_gates()
+        .into_iter()
+        .map(|gate| {
+            let coeffs: Vec<Fp> = (0..NUM_COEFFS).map(|_| Fp::rand(rng)).collect();
+            let curr: Vec<Fp> = (0..COLUMNS).map(|_| Fp::rand(rng)).collect();
+            let next: Vec<Fp> = (0..COLUMNS).map(|_| Fp::rand(rng)).collect();
+            let constraints = evaluate(gate, &coeffs, &curr, &next, endo_coefficient);
+            GateVector {
+                gate,
+                coeffs,
+                curr,
+                next,
+                constraints,
+            }
+        })
+        .collect();
+    GateVectors {
+        endo_coefficient,
+        test_vectors,
+    }
+}
+
+#[test]
+// Test that the evaluations of the constraints of the gates are the ones of the test vectors
+fn test_gate_vectors() {
+    let path = vectors_path();
+    if env::var_os("REGENERATE_GATE_VECTORS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let file = File::create(&path).expect("couldn't create test vector file");
+        serde_json::to_writer_pretty(file, &generate()).expect("couldn't write test vector file");
+    }
+    assert!(
+        path.exists(),
+        "the test vector file {} is missing, set REGENERATE_GATE_VECTORS to write it",
+        path.display()
+    );
+
+    let file = File::open(&path).expect("couldn't open test vector file");
+    let vectors: GateVectors =
+        serde_json::from_reader(file).expect("couldn't deserialize test vector file");
+    let gates: Vec<GateType> = vectors.test_vectors.iter().map(|v| v.gate).collect();
+    assert_eq!(
+        gates,
+        vector_gates(),
+        "the test vectors cover all the gates with constraints"
+    );
+
+    for vector in vectors.test_vectors {
+        assert!(!vector.constraints.is_empty());
+        assert_eq!(
+            evaluate(
+                vector.gate,
+                &vector.coeffs,
+                &vector.curr,
+                &vector.next,
+                vectors.endo_coefficient
+            ),
+            vector.constraints,
+            "the evaluations of the constraints of the gate {:?}",
+            vector.gate
+        );
+    }
+}
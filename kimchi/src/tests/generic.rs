@@ -1,88 +1,241 @@
-use super::framework::TestFramework;
+use super::framework::{TestCurve, TestFramework};
 use crate::circuits::polynomials::generic::testing::{create_circuit, fill_in_witness};
 use crate::circuits::wires::COLUMNS;
-use ark_ff::Zero;
-use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use crate::{
+    circuits::constraints::{
+        ConstraintSystem, GateError, DEFAULT_QUOTIENT_CHUNKS, MAX_QUOTIENT_CHUNKS,
+    },
+    domain_separation::DomainSeparator,
+    proof::ProverProof,
+    prover_index::{
+        testing::{new_index_for_test, new_index_for_test_with_cs},
+        ProverIndex,
+    },
+    verifier::verify,
+};
+use ark_ff::{PrimeField, Zero};
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Fq, Pallas, Vesta, VestaParameters};
 use mina_poseidon::{
     constants::PlonkSpongeConstantsKimchi,
     sponge::{DefaultFqSponge, DefaultFrSponge},
 };
-use std::array;
+use poly_commitment::{commitment::CommitmentCurve, srs::SRS};
+use std::{array, sync::Arc};
 
 type SpongeParams = PlonkSpongeConstantsKimchi;
 type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
 type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
 
-#[test]
-fn test_generic_gate() {
-    let gates = create_circuit(0, 0);
+// Creates and verifies a proof of the generic gates with the public inputs, on the curve G
+fn prove_and_verify_generic_gate<G: TestCurve>(public: Vec<G::ScalarField>)
+where
+    G::BaseField: PrimeField,
+{
+    let gates = create_circuit(0, public.len());
 
     // create witness
-    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
-    fill_in_witness(0, &mut witness, &[]);
+    let mut witness: [Vec<G::ScalarField>; COLUMNS] =
+        array::from_fn(|_| vec![G::ScalarField::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
 
     // create and verify proof based on the witness
-    TestFramework::<Vesta>::default()
+    TestFramework::<G>::default()
         .gates(gates)
         .witness(witness)
+        .public_inputs(public)
         .setup()
-        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>()
         .unwrap();
 }
 
 #[test]
-fn test_generic_gate_pub() {
-    let public = vec![Fp::from(3u8); 5];
-    let gates = create_circuit(0, public.len());
+fn test_generic_gate() {
+    let gates = create_circuit(0, 0);
 
     // create witness
     let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
-    fill_in_witness(0, &mut witness, &public);
+    fill_in_witness(0, &mut witness, &[]);
 
     // create and verify proof based on the witness
     TestFramework::<Vesta>::default()
         .gates(gates)
         .witness(witness)
-        .public_inputs(public)
         .setup()
         .prove_and_verify::<BaseSponge, ScalarSponge>()
         .unwrap();
+
+    // and over the other curve
+    prove_and_verify_generic_gate::<Pallas>(vec![]);
+}
+
+#[test]
+fn test_generic_gate_pub() {
+    prove_and_verify_generic_gate::<Vesta>(vec![Fp::from(3u8); 5]);
+    prove_and_verify_generic_gate::<Pallas>(vec![Fq::from(3u8); 5]);
 }
 
 #[test]
 fn test_generic_gate_pub_all_zeros() {
-    let public = vec![Fp::from(0u8); 5];
+    prove_and_verify_generic_gate::<Vesta>(vec![Fp::from(0u8); 5]);
+    prove_and_verify_generic_gate::<Pallas>(vec![Fq::from(0u8); 5]);
+}
+
+#[test]
+fn test_generic_gate_pub_empty() {
+    prove_and_verify_generic_gate::<Vesta>(vec![]);
+    prove_and_verify_generic_gate::<Pallas>(vec![]);
+}
+
+#[test]
+fn test_generic_gate_domain_separator() {
+    let public = vec![Fp::from(3u8); 5];
     let gates = create_circuit(0, public.len());
 
     // create witness
     let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
     fill_in_witness(0, &mut witness, &public);
 
-    // create and verify proof based on the witness
-    TestFramework::<Vesta>::default()
-        .gates(gates)
-        .witness(witness)
-        .public_inputs(public)
-        .setup()
-        .prove_and_verify::<BaseSponge, ScalarSponge>()
+    let index_for = |domain_separator: Option<DomainSeparator>| {
+        let mut builder = ConstraintSystem::create(gates.clone()).public(public.len());
+        if let Some(domain_separator) = domain_separator {
+            builder = builder.domain_separator(domain_separator);
+        }
+        new_index_for_test_with_cs::<Vesta>(builder.build().unwrap())
+    };
+    let testnet = index_for(Some(DomainSeparator::new(b"testnet").unwrap()));
+    let mainnet = index_for(Some(DomainSeparator::new(b"mainnet").unwrap()));
+    let default = index_for(None);
+
+    // the domain separator changes the verifier index digest
+    let digest = |index: &ProverIndex<Vesta>| index.verifier_index().digest::<BaseSponge>();
+    assert_ne!(digest(&testnet), digest(&mainnet));
+    assert_ne!(digest(&testnet), digest(&default));
+
+    // a proof only verifies within its own domain
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof = ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], &testnet)
         .unwrap();
+    verify::<Vesta, BaseSponge, ScalarSponge>(
+        &group_map,
+        &testnet.verifier_index(),
+        &proof,
+        &public,
+    )
+    .unwrap();
+    for other in [&mainnet, &default] {
+        assert!(verify::<Vesta, BaseSponge, ScalarSponge>(
+            &group_map,
+            &other.verifier_index(),
+            &proof,
+            &public,
+        )
+        .is_err());
+    }
 }
 
 #[test]
-fn test_generic_gate_pub_empty() {
-    let public = vec![];
+fn test_generic_gate_quotient_chunks() {
+    let public = vec![Fp::from(3u8); 5];
     let gates = create_circuit(0, public.len());
 
     // create witness
     let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
     fill_in_witness(0, &mut witness, &public);
 
-    // create and verify proof based on the witness
-    TestFramework::<Vesta>::default()
+    let index_for = |quotient_chunks: usize| {
+        let cs = ConstraintSystem::create(gates.clone())
+            .public(public.len())
+            .quotient_chunks(quotient_chunks)
+            .build()
+            .unwrap();
+        new_index_for_test_with_cs::<Vesta>(cs)
+    };
+    let extended = index_for(MAX_QUOTIENT_CHUNKS);
+    let default = index_for(DEFAULT_QUOTIENT_CHUNKS);
+    assert_eq!(extended.cs.max_constraint_degree(), 16);
+    assert_eq!(
+        extended.verifier_index().quotient_chunks,
+        MAX_QUOTIENT_CHUNKS
+    );
+
+    // the proof commits to all the chunks of the quotient, and only verifies with the same number of chunks
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], &extended)
+            .unwrap();
+    assert_eq!(
+        proof.commitments.t_comm.unshifted.len(),
+        MAX_QUOTIENT_CHUNKS
+    );
+    verify::<Vesta, BaseSponge, ScalarSponge>(
+        &group_map,
+        &extended.verifier_index(),
+        &proof,
+        &public,
+    )
+    .unwrap();
+    assert!(verify::<Vesta, BaseSponge, ScalarSponge>(
+        &group_map,
+        &default.verifier_index(),
+        &proof,
+        &public,
+    )
+    .is_err());
+
+    // the number of chunks is bounded by the domain d16
+    for quotient_chunks in [DEFAULT_QUOTIENT_CHUNKS - 1, MAX_QUOTIENT_CHUNKS + 1] {
+        assert!(ConstraintSystem::create(gates.clone())
+            .quotient_chunks(quotient_chunks)
+            .build()
+            .is_err());
+    }
+}
+
+#[test]
+// Test that the parallel verification of the rows of a witness reports the first invalid row
+fn test_generic_gate_verify_first_invalid_row() {
+    let gates = create_circuit(0, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+    let index = new_index_for_test::<Vesta>(gates, 0);
+    index.verify(&witness, &[]).unwrap();
+
+    // break the additions of two rows
+    for row in [7, 2] {
+        witness[2][row] += Fp::from(1u8);
+    }
+    assert!(matches!(
+        index.verify(&witness, &[]),
+        Err(GateError::Custom { row: 2, .. })
+    ));
+}
+
+#[test]
+// Test that the indexes of the tests share the SRS of their domain, unless another SRS is given
+fn test_generic_gate_shared_srs() {
+    let gates = create_circuit(0, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+
+    let first = new_index_for_test::<Vesta>(gates.clone(), 0);
+    let second = new_index_for_test::<Vesta>(gates.clone(), 0);
+    assert!(Arc::ptr_eq(&first.srs, &second.srs));
+    assert!(first
+        .srs
+        .lagrange_bases
+        .contains_key(&first.cs.domain.d1.size()));
+
+    // the Lagrange basis of the domain is added to a prebuilt SRS
+    let srs = Arc::new(SRS::<Vesta>::create(first.cs.domain.d1.size()));
+    let test_runner = TestFramework::<Vesta>::default()
         .gates(gates)
         .witness(witness)
-        .public_inputs(public)
-        .setup()
+        .srs(srs.clone())
+        .setup();
+    let index = test_runner.prover_index();
+    assert!(*index.srs == *srs && !Arc::ptr_eq(&index.srs, &first.srs));
+    test_runner
         .prove_and_verify::<BaseSponge, ScalarSponge>()
         .unwrap();
 }
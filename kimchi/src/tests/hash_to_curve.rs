@@ -0,0 +1,157 @@
+use std::array;
+
+use super::ecdsa::verify_rows;
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::CircuitGate,
+    polynomial::COLUMNS,
+    polynomials::{
+        ecdsa::curve::ForeignCurve,
+        hash_to_curve::{self, HashToCurveValue, SswuMap},
+    },
+};
+use mina_curves::pasta::Fp;
+use num_bigint::BigUint;
+use o1_utils::foreign_field::BigUintForeignFieldHelpers;
+
+// The domain separation tag of the test vectors of the suite P256_XMD:SHA-256_SSWU_RO_ in RFC 9380
+const DST: &[u8] = b"QUUX-V01-CS02-with-P256_XMD:SHA-256_SSWU_RO_";
+
+// The domain separation tag of the test vectors of the suite secp256k1_XMD:SHA-256_SSWU_RO_ in RFC 9380
+const SECP256K1_DST: &[u8] = b"QUUX-V01-CS02-with-secp256k1_XMD:SHA-256_SSWU_RO_";
+
+// The test vectors of the messages "" and "abc": u0, u1, and the coordinates of the point
+const RFC_VECTORS: [(&[u8], [&str; 4]); 2] = [
+    (
+        b"",
+        [
+            "ad5342c66a6dd0ff080df1da0ea1c04b96e0330dd89406465eeba11582515009",
+            "8c0f1d43204bd6f6ea70ae8013070a1518b43873bcd850aafa0a9e220e2eea5a",
+            "2c15230b26dbc6fc9a37051158c95b79656e17a1a920b11394ca91c44247d3e4",
+            "8a7a74985cc5c776cdfe4b1f19884970453912e9d31528c060be9ab5c43e8415",
+        ],
+    ),
+    (
+        b"abc",
+        [
+            "afe47f2ea2b10465cc26ac403194dfb68b7f5ee865cda61e9f3e07a537220af1",
+            "379a27833b0bfe6f7bdca08e1e83c760bf9a338ab335542704edcd69ce9e46e0",
+            "0bb8b87485551aa43ed54f009230450b492fead5f1cc91658775dac4a3388a0f",
+            "5c41b3d0731a27a7b14bc0bf0ccded2d8751f83493404c84a88e71ffd424212e",
+        ],
+    ),
+];
+
+// The test vectors of the suite secp256k1_XMD:SHA-256_SSWU_RO_, as RFC_VECTORS
+const SECP256K1_RFC_VECTORS: [(&[u8], [&str; 4]); 2] = [
+    (
+        b"",
+        [
+            "6b0f9910dd2ba71c78f2ee9f04d73b5f4c5f7fc773a701abea1e573cab002fb3",
+            "1ae6c212e08fe1a5937f6202f929a2cc8ef4ee5b9782db68b0d5799fd8f09e16",
+            "c1cae290e291aee617ebaef1be6d73861479c48b841eaba9b7b5852ddfeb1346",
+            "64fa678e07ae116126f08b022a94af6de15985c996c3a91b64c406a960e51067",
+        ],
+    ),
+    (
+        b"abc",
+        [
+            "128aab5d3679a1f7601e3bdf94ced1f43e491f544767e18a4873f397b08a2b61",
+            "5897b65da3b595a813d0fdcc75c895dc531be76a03518b044daaa0f2e4689e00",
+            "3377e01eab42db296b512293120c6cee72b6ecf9f9205760bd9ff11fb3cb2c4b",
+            "7f95890f33efebd1044d382a01b1bee0900fb6116f94688d487c6c7b9c8371f6",
+        ],
+    ),
+];
+
+// The suites of the test vectors: the map, the domain separation tag and the vectors
+fn suites() -> [(
+    SswuMap,
+    &'static [u8],
+    [(&'static [u8], [&'static str; 4]); 2],
+); 2] {
+    [
+        (SswuMap::p256(), DST, RFC_VECTORS),
+        (SswuMap::secp256k1(), SECP256K1_DST, SECP256K1_RFC_VECTORS),
+    ]
+}
+
+fn from_hex(hex: &str) -> BigUint {
+    BigUint::parse_bytes(hex.as_bytes(), 16).unwrap()
+}
+
+#[test]
+// Test the hash to P-256 and to secp256k1 out of the circuit, with the test vectors of RFC 9380
+fn test_hash_to_curve_function() {
+    for (map, dst, vectors) in suites() {
+        for (msg, vector) in vectors {
+            let [u0, u1, x, y] = vector.map(from_hex);
+            assert_eq!(
+                hash_to_curve::hash_to_field(&map.curve, msg, dst),
+                [u0.clone(), u1.clone()]
+            );
+            for u in [&u0, &u1] {
+                assert!(map.curve.is_on_curve(&map.map_to_curve(u)));
+            }
+            assert_eq!(hash_to_curve::hash_to_curve(&map, msg, dst), Some((x, y)));
+        }
+    }
+
+    // the Q0 of the message ""
+    assert_eq!(
+        SswuMap::p256().map_to_curve(&from_hex(RFC_VECTORS[0].1[0])),
+        (
+            from_hex("ab640a12220d3ff283510ff3f4b1953d09fad35795140b1c5d64f313967934d5"),
+            from_hex("dccb558863804a881d4fff3455716c836cef230e5209594ddd33d85c565b19b1")
+        )
+    );
+    assert_eq!(
+        SswuMap::secp256k1().map_to_curve(&from_hex(SECP256K1_RFC_VECTORS[0].1[0])),
+        (
+            from_hex("74519ef88b32b425a095e4ebcc84d81b64e9e2c2675340a720bb1a1857b99f1e"),
+            from_hex("c174fa322ab7c192e11748beed45b508e9fdb1ce046dee9c2cd3a2a86b410936")
+        )
+    );
+}
+
+#[test]
+// Test that the secp256k1 map needs its isogeny, since the curve has a = 0
+#[should_panic]
+fn test_hash_to_curve_without_isogeny() {
+    let curve = ForeignCurve::secp256k1();
+    let z = &curve.modulus - 11u32;
+    SswuMap::new(curve, z);
+}
+
+#[test]
+// Test the witness of the hash-to-curve gadget, and that another point does not satisfy the gadget
+fn test_hash_to_curve_witness() {
+    for (map, dst, vectors) in suites() {
+        verify_hash_to_curve_witness(&map, dst, &vectors);
+    }
+}
+
+fn verify_hash_to_curve_witness(map: &SswuMap, dst: &[u8], vectors: &[(&[u8], [&str; 4])]) {
+    let (next_row, gates) = CircuitGate::<Fp>::create_hash_to_curve(0, map);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    for (msg, vector) in vectors {
+        let u = hash_to_curve::hash_to_field(&map.curve, msg, dst);
+        let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+        let point = hash_to_curve::extend_hash_to_curve_witness(&mut witness, map, &u);
+        assert_eq!(point, (from_hex(vector[2]), from_hex(vector[3])));
+        assert_eq!(witness[0].len(), next_row);
+        assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+        for (limb, value) in point.0.to_field_limbs::<Fp>().into_iter().enumerate() {
+            let (row, col) = hash_to_curve::hash_to_curve_limb(0, HashToCurveValue::ResultX, limb);
+            assert_eq!(witness[col][row], value);
+        }
+
+        // another y-coordinate
+        let (row, col) = hash_to_curve::hash_to_curve_limb(0, HashToCurveValue::ResultY, 0);
+        let mut wrong = witness.clone();
+        wrong[col][row] += Fp::from(1u32);
+        assert!(verify_rows(&cs, &wrong).is_err());
+    }
+}
@@ -0,0 +1,163 @@
+use std::{array, iter, sync::Arc};
+
+use super::ecdsa::verify_rows;
+use crate::{
+    circuits::{
+        constraints::{ConstraintSystem, IncrementalConstraintSystem},
+        gate::{CircuitGate, Connect},
+        polynomial::COLUMNS,
+        polynomials::generic::{
+            testing::{create_circuit, fill_in_witness},
+            GenericGateSpec,
+        },
+        wires::Wire,
+    },
+    error::SetupError,
+};
+use ark_ff::Zero;
+use mina_curves::pasta::Fp;
+
+const PUBLIC: usize = 2;
+
+// Creates `count` generic gates from the row `new_row`, each adding the two public inputs
+fn create_sums(new_row: usize, count: usize) -> Vec<CircuitGate<Fp>> {
+    let spec = || GenericGateSpec::Add {
+        left_coeff: None,
+        right_coeff: None,
+        output_coeff: None,
+    };
+    (new_row..new_row + count)
+        .map(|row| CircuitGate::create_generic_gadget(Wire::for_row(row), spec(), None))
+        .collect()
+}
+
+// The copies of the public inputs to the gates created by `create_sums`
+fn sum_copies(new_row: usize, count: usize) -> Vec<((usize, usize), (usize, usize))> {
+    (new_row..new_row + count)
+        .flat_map(|row| [((0, 0), (row, 0)), ((1, 0), (row, 1))])
+        .collect()
+}
+
+// Checks that a constraint system extended with gates is the one built from all its gates
+fn assert_rebuilt(incremental: &IncrementalConstraintSystem<Fp>, mut gates: Vec<CircuitGate<Fp>>) {
+    let extended = incremental.constraint_system();
+    assert_eq!(incremental.num_gates(), gates.len());
+    gates.connect_cell_pairs(&sum_copies(22, gates.len() - 22));
+    let cs = ConstraintSystem::create(gates)
+        .public(PUBLIC)
+        .build()
+        .unwrap();
+    assert_eq!(extended.domain.d1.size, cs.domain.d1.size);
+    assert_eq!(extended.gates.len(), cs.gates.len());
+    for (gate, cs_gate) in extended.gates.iter().zip(&cs.gates) {
+        assert_eq!(gate.typ, cs_gate.typ);
+        assert_eq!(gate.wires, cs_gate.wires);
+        assert_eq!(gate.coeffs, cs_gate.coeffs);
+    }
+    assert_eq!(extended.shift, cs.shift);
+    assert_eq!(extended.sid, cs.sid);
+}
+
+#[test]
+// Test that the gates appended to a constraint system keep the precomputations of its domain,
+// until the circuit outgrows the domain
+fn test_incremental_constraint_system() {
+    let gates = create_circuit::<Fp>(0, PUBLIC);
+    assert_eq!(gates.len(), 22);
+    let mut incremental =
+        IncrementalConstraintSystem::new(ConstraintSystem::create(gates.clone()).public(PUBLIC))
+            .unwrap();
+    let precomputations = incremental.constraint_system().precomputations().clone();
+    assert_eq!(incremental.constraint_system().domain.d1.size, 32);
+
+    // the 25 gates and the zero-knowledge rows fit in the domain
+    let sums = create_sums(22, 3);
+    incremental
+        .append(sums.clone(), &sum_copies(22, 3))
+        .unwrap();
+    let mut all_gates = gates;
+    all_gates.extend(sums);
+    assert_rebuilt(&incremental, all_gates.clone());
+    assert!(Arc::ptr_eq(
+        incremental.constraint_system().precomputations(),
+        &precomputations
+    ));
+
+    // the circuit outgrows the domain
+    let sums = create_sums(25, 10);
+    incremental
+        .append(sums.clone(), &sum_copies(25, 10))
+        .unwrap();
+    all_gates.extend(sums);
+    assert_rebuilt(&incremental, all_gates);
+    assert_eq!(incremental.constraint_system().domain.d1.size, 64);
+    assert!(!Arc::ptr_eq(
+        incremental.constraint_system().precomputations(),
+        &precomputations
+    ));
+
+    // the witness of the appended gates copies the public inputs
+    let public = vec![Fp::from(3u64), Fp::from(5u64)];
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); 22]);
+    fill_in_witness(0, &mut witness, &public);
+    for _ in 0..13 {
+        for (col, cell) in witness.iter_mut().enumerate() {
+            cell.push(match col {
+                0 => public[0],
+                1 => public[1],
+                2 => public[0] + public[1],
+                _ => Fp::zero(),
+            });
+        }
+    }
+    let cs = incremental.into_constraint_system();
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+}
+
+// The cells of the cycle of the wiring of a cell, from this cell
+fn cycle(cs: &ConstraintSystem<Fp>, cell: (usize, usize)) -> Vec<(usize, usize)> {
+    iter::successors(Some(cell), |(row, col)| {
+        let wire = cs.gates[*row].wires[*col];
+        ((wire.row, wire.col) != cell).then_some((wire.row, wire.col))
+    })
+    .collect()
+}
+
+#[test]
+// Test that appending a copy between cells which are already connected keeps them connected
+fn test_incremental_connected_copy() {
+    let gates = create_circuit::<Fp>(0, PUBLIC);
+    let mut incremental =
+        IncrementalConstraintSystem::new(ConstraintSystem::create(gates.clone()).public(PUBLIC))
+            .unwrap();
+    let sums = create_sums(22, 1);
+    incremental
+        .append(sums.clone(), &sum_copies(22, 1))
+        .unwrap();
+    assert!(cycle(incremental.constraint_system(), (0, 0)).contains(&(22, 0)));
+
+    // the copy of the first public input to the appended gate is appended again
+    incremental.append(vec![], &sum_copies(22, 1)[..1]).unwrap();
+    let mut all_gates = gates;
+    all_gates.extend(sums);
+    assert_rebuilt(&incremental, all_gates);
+    assert!(cycle(incremental.constraint_system(), (0, 0)).contains(&(22, 0)));
+}
+
+#[test]
+// Test that the gates which can not be appended leave the constraint system unchanged
+fn test_incremental_wrong_gates() {
+    let gates = create_circuit::<Fp>(0, PUBLIC);
+    let mut incremental =
+        IncrementalConstraintSystem::new(ConstraintSystem::create(gates.clone()).public(PUBLIC))
+            .unwrap();
+
+    // the appended gate is wired outside of the circuit
+    let mut sums = create_sums(22, 1);
+    sums[0].wires[2] = Wire::new(23, 0);
+    assert!(matches!(
+        incremental.append(sums, &[]),
+        Err(SetupError::Wiring(_))
+    ));
+    assert_rebuilt(&incremental, gates);
+}
@@ -1,10 +1,11 @@
 use std::array;
 
+use super::ecdsa::verify_rows;
 use crate::circuits::{
     constraints::ConstraintSystem,
     gate::CircuitGate,
-    polynomials::keccak::{self, ROT_TAB},
-    wires::Wire,
+    polynomial::COLUMNS,
+    polynomials::keccak::{self, RotTable, ROT_TAB, ROT_TAB_800, ROUND_CONSTANTS},
 };
 use ark_ec::AffineCurve;
 use mina_curves::pasta::{Fp, Pallas, Vesta};
@@ -13,31 +14,32 @@ use rand::Rng;
 //use super::framework::TestFramework;
 type PallasField = <Pallas as AffineCurve>::BaseField;
 
-fn create_test_constraint_system() -> ConstraintSystem<Fp> {
-    let (mut next_row, mut gates) = { CircuitGate::<Fp>::create_keccak(0) };
+fn create_test_constraint_system(rot_tab: &RotTable) -> ConstraintSystem<Fp> {
+    let (_next_row, gates) = { CircuitGate::<Fp>::create_keccak_with_table(0, rot_tab) };
 
     ConstraintSystem::create(gates).build().unwrap()
 }
 
+// Checks the witness of the rotation of a given state for all the gates of the table
+fn check_keccak_table(rot_tab: &RotTable, state: [[u64; 5]; 5]) -> [Vec<PallasField>; COLUMNS] {
+    let cs = create_test_constraint_system(rot_tab);
+    let witness = keccak::create_witness_keccak_rot(state, rot_tab);
+    for row in 0..cs.gates.len() {
+        assert_eq!(
+            cs.gates[row].verify_witness::<Vesta>(row, &witness, &cs, &witness[0][0..cs.public]),
+            Ok(())
+        );
+    }
+    witness
+}
+
 #[test]
 // Test that all of the offsets in the rotation table work fine
 fn test_keccak_table() {
-    let cs = create_test_constraint_system();
     let state = array::from_fn(|_| {
         array::from_fn(|_| rand::thread_rng().gen_range(0..2u128.pow(64)) as u64)
     });
-    let witness = keccak::create_witness_keccak_rot(state);
-    for row in 0..=48 {
-        assert_eq!(
-            cs.gates[row].verify_witness::<Vesta>(
-                row,
-                &witness,
-                &cs,
-                &witness[0][0..cs.public].to_vec()
-            ),
-            Ok(())
-        );
-    }
+    let witness = check_keccak_table(&ROT_TAB, state);
     let mut rot = 0;
     for (x, row) in ROT_TAB.iter().enumerate() {
         for (y, &bits) in row.iter().enumerate() {
@@ -52,3 +54,87 @@ fn test_keccak_table() {
         }
     }
 }
+
+#[test]
+// Test the rotations of Keccak-f[800], with 32-bit lanes spread over 64-bit words
+fn test_keccak_800_table() {
+    assert_eq!(ROT_TAB_800, keccak::rot_table(32));
+    assert_eq!(keccak::rot_table(64), ROT_TAB);
+    assert!(ROT_TAB_800.iter().flatten().all(|&rot| rot < 32));
+
+    let lanes: [[u32; 5]; 5] =
+        array::from_fn(|_| array::from_fn(|_| rand::thread_rng().gen::<u32>()));
+    let state = array::from_fn(|x| array::from_fn(|y| keccak::spread_lane(lanes[x][y].into(), 32)));
+    let witness = check_keccak_table(&ROT_TAB_800, state);
+    let mut rot = 0;
+    for (x, row) in ROT_TAB_800.iter().enumerate() {
+        for (y, &bits) in row.iter().enumerate() {
+            if bits == 0 {
+                continue;
+            }
+            // both halves of the rotated word contain the rotated lane
+            let rotated = u64::from(lanes[x][y].rotate_left(bits));
+            assert_eq!(
+                PallasField::from(rotated << 32 | rotated),
+                witness[1][1 + 2 * rot],
+            );
+            rot += 1;
+        }
+    }
+    assert_eq!(rot, 24);
+}
+
+// Creates the iota step of a round and its witness
+fn create_keccak_iota(lane: u64, round: usize) -> (ConstraintSystem<Fp>, [Vec<Fp>; COLUMNS]) {
+    let (next_row, gates) = CircuitGate::<Fp>::create_keccak_iota(0, round);
+    assert_eq!(next_row, gates.len());
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    keccak::extend_keccak_iota_witness(&mut witness, lane, round);
+    assert_eq!(witness[0].len(), next_row);
+    (cs, witness)
+}
+
+#[test]
+// Test that the iota step of each round XORs the lane with the round constant
+fn test_keccak_iota() {
+    let lane = rand::thread_rng().gen::<u64>();
+    for (round, round_constant) in ROUND_CONSTANTS.iter().enumerate() {
+        let (cs, witness) = create_keccak_iota(lane, round);
+        assert_eq!(verify_rows(&cs, &witness), Ok(()));
+        assert_eq!(witness[2][0], Fp::from(lane ^ round_constant));
+    }
+}
+
+#[test]
+// Test that the iota step of a round rejects the constant of another round
+fn test_keccak_iota_wrong_constant() {
+    let lane = rand::thread_rng().gen::<u64>();
+    let (cs, _) = create_keccak_iota(lane, 3);
+    let (_, witness) = create_keccak_iota(lane, 4);
+    assert!(verify_rows(&cs, &witness).is_err());
+}
+
+#[test]
+fn test_spread_lane() {
+    assert_eq!(keccak::spread_lane(0xab, 8), 0xabababababababab);
+    assert_eq!(keccak::spread_lane(0x12345678, 32), 0x1234567812345678);
+    assert_eq!(keccak::spread_lane(u64::MAX, 64), u64::MAX);
+}
+
+#[test]
+// Test the Keccak-256 hashes out of the circuit, with one and two permutations
+fn test_keccak256() {
+    assert_eq!(
+        hex::encode(keccak::keccak256(b"")),
+        "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+    );
+    assert_eq!(
+        hex::encode(keccak::keccak256(b"abc")),
+        "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"
+    );
+    assert_eq!(
+        hex::encode(keccak::keccak256(&[7; 200])),
+        "4143ef737e81b990c8b604140712d1b0667457ad90b65918a9fa319d00c5b361"
+    );
+}
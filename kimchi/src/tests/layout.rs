@@ -0,0 +1,156 @@
+//! Golden layouts of the gadgets and of the packing of generic gates.
+//!
+//! The layouts below are pinned: a change in the gates or the wiring of a gadget,
+//! or in the pairing of the packer for a given seed, changes the digest of the circuits using them,
+//! and must be reflected here.
+
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::{CircuitGate, Connect},
+    packing::Packing,
+    polynomials::generic::GenericGateSpec,
+    wires::{Wire, PERMUTS},
+};
+use mina_curves::pasta::Fp;
+
+// The layout of a circuit: the type of the gate of each row, followed by its wires to other cells
+fn layout(gates: &[CircuitGate<Fp>]) -> Vec<String> {
+    gates
+        .iter()
+        .enumerate()
+        .map(|(row, gate)| {
+            let mut row_layout = format!("{:?}", gate.typ);
+            for col in 0..PERMUTS {
+                let wire = gate.wires[col];
+                if wire != (Wire { row, col }) {
+                    row_layout += &format!(" {col}->{}.{}", wire.row, wire.col);
+                }
+            }
+            row_layout
+        })
+        .collect()
+}
+
+// A public input followed by a chain of 6 additions of single generic gates
+fn addition_chain() -> Vec<CircuitGate<Fp>> {
+    let mut gates = vec![CircuitGate::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )];
+    let mut acc = (0, 0);
+    for row in 1..=6 {
+        gates.push(CircuitGate::create_generic_gadget(
+            Wire::for_row(row),
+            GenericGateSpec::Add {
+                left_coeff: None,
+                right_coeff: None,
+                output_coeff: None,
+            },
+            None,
+        ));
+        gates.connect_cell_pair(acc, (row, 0));
+        acc = (row, 2);
+    }
+    gates
+}
+
+#[test]
+fn test_gadget_layouts() {
+    let mut gates = vec![];
+    CircuitGate::extend_xor_gadget(&mut gates, 32);
+    assert_eq!(
+        layout(&gates),
+        ["Xor16", "Xor16", "Generic 0->2.2 1->2.0 2->2.1"]
+    );
+
+    let mut gates = vec![];
+    CircuitGate::extend_range_check_bytes(&mut gates, 5);
+    assert_eq!(
+        layout(&gates),
+        [
+            "RangeCheckU8",
+            "RangeCheckU8 1->2.0 2->1.1 3->1.2",
+            "Generic 0->1.3"
+        ]
+    );
+
+    let mut gates = vec![];
+    CircuitGate::extend_range_check_bytes(&mut gates, 23);
+    assert_eq!(
+        layout(&gates),
+        [
+            "RangeCheckU16",
+            "RangeCheckU16",
+            "RangeCheckU8",
+            "RangeCheckU8 3->4.0",
+            "Generic 0->3.3"
+        ]
+    );
+
+    let mut gates = vec![];
+    CircuitGate::extend_vector_lookup(&mut gates, 5, 2);
+    assert_eq!(
+        layout(&gates),
+        [
+            "Generic 0->1.0",
+            "VectorLookup 0->2.0",
+            "VectorLookup 0->0.0"
+        ]
+    );
+}
+
+#[test]
+fn test_packing_layouts() {
+    let gates = addition_chain();
+    assert_eq!(
+        layout(&gates),
+        [
+            "Generic 0->1.0",
+            "Generic 0->0.0 2->2.0",
+            "Generic 0->1.2 2->3.0",
+            "Generic 0->2.2 2->4.0",
+            "Generic 0->3.2 2->5.0",
+            "Generic 0->4.2 2->6.0",
+            "Generic 0->5.2",
+        ]
+    );
+
+    // the default layout pairs the gates in order
+    let (packed, packing) = Packing::create(&gates, 1);
+    assert_eq!(
+        layout(&packed),
+        [
+            "Generic 0->1.0",
+            "Generic 0->0.0 2->1.3 3->1.2 5->2.0",
+            "Generic 0->1.5 2->2.3 3->2.2 5->3.0",
+            "Generic 0->2.5 2->3.3 3->3.2",
+        ]
+    );
+    assert_eq!(Packing::create_with_seed(&gates, 1, 0).1, packing);
+
+    // a seeded layout pairs the gates (2, 6), (1, 3) and (4, 5)
+    let (packed, packing) = Packing::create_with_seed(&gates, 1, 7);
+    assert_eq!(
+        layout(&packed),
+        [
+            "Generic 0->1.0",
+            "Generic 0->0.0 2->2.0 3->2.2 5->3.0",
+            "Generic 0->1.2 2->1.3 3->3.5",
+            "Generic 0->1.5 2->3.3 3->3.2 5->2.3",
+        ]
+    );
+    assert_eq!(
+        packing.cell(Wire { row: 6, col: 2 }),
+        Some(Wire { row: 2, col: 5 })
+    );
+
+    // the builder lays out the circuit with its seed
+    let (builder, builder_packing) = ConstraintSystem::create(gates)
+        .public(1)
+        .layout_seed(7)
+        .pack_generic_gates();
+    assert_eq!(builder_packing, packing);
+    let cs = builder.build().unwrap();
+    assert_eq!(layout(&cs.gates[..packed.len()]), layout(&packed));
+}
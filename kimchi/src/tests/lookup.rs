@@ -1,26 +1,41 @@
 use super::framework::{print_witness, TestFramework};
-use crate::circuits::{
-    gate::{CircuitGate, GateType},
-    lookup::{
-        runtime_tables::{RuntimeTable, RuntimeTableCfg, RuntimeTableSpec},
-        tables::LookupTable,
+use crate::{
+    circuits::{
+        constraints::ConstraintSystem,
+        gate::{CircuitGate, GateType},
+        lookup::{
+            gadget,
+            index::{LookupConstraintSystem, LookupError},
+            lookups::LookupArgument,
+            runtime_tables::{RuntimeTable, RuntimeTableCfg, RuntimeTableSpec},
+            tables::{self, LookupTable, GATE_LOOKUP_TABLES},
+        },
+        polynomial::COLUMNS,
+        polynomials::{permutation::ZK_ROWS, xor},
+        wires::Wire,
     },
-    polynomial::COLUMNS,
-    wires::Wire,
+    error::SetupError,
+    prover_index::testing::new_index_for_test_with_cs,
 };
 use ark_ff::Zero;
+use ark_poly::EvaluationDomain;
 use mina_curves::pasta::{Fp, Vesta, VestaParameters};
 use mina_poseidon::{
     constants::PlonkSpongeConstantsKimchi,
     sponge::{DefaultFqSponge, DefaultFrSponge},
 };
-use std::array;
+use std::{array, ops::Range};
 
 type SpongeParams = PlonkSpongeConstantsKimchi;
 type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
 type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
 
-fn setup_lookup_proof(use_values_from_table: bool, num_lookups: usize, table_sizes: Vec<usize>) {
+fn setup_lookup_proof(
+    use_values_from_table: bool,
+    num_lookups: usize,
+    table_sizes: Vec<usize>,
+    argument: LookupArgument,
+) {
     let lookup_table_values: Vec<Vec<_>> = table_sizes
         .iter()
         .map(|size| (0..*size).map(|_| rand::random()).collect())
@@ -98,6 +113,7 @@ fn setup_lookup_proof(use_values_from_table: bool, num_lookups: usize, table_siz
         .gates(gates)
         .witness(witness)
         .lookup_tables(lookup_tables)
+        .lookup_argument(argument)
         .setup()
         .prove_and_verify::<BaseSponge, ScalarSponge>()
         .unwrap();
@@ -105,27 +121,86 @@ fn setup_lookup_proof(use_values_from_table: bool, num_lookups: usize, table_siz
 
 #[test]
 fn lookup_gate_proving_works() {
-    setup_lookup_proof(true, 500, vec![256])
+    setup_lookup_proof(true, 500, vec![256], LookupArgument::Plookup)
 }
 
 #[test]
 #[should_panic]
 fn lookup_gate_rejects_bad_lookups() {
-    setup_lookup_proof(false, 500, vec![256])
+    setup_lookup_proof(false, 500, vec![256], LookupArgument::Plookup)
 }
 
 #[test]
 fn lookup_gate_proving_works_multiple_tables() {
-    setup_lookup_proof(true, 500, vec![100, 50, 50, 2, 2])
+    setup_lookup_proof(true, 500, vec![100, 50, 50, 2, 2], LookupArgument::Plookup)
 }
 
 #[test]
 #[should_panic]
 fn lookup_gate_rejects_bad_lookups_multiple_tables() {
-    setup_lookup_proof(false, 500, vec![100, 50, 50, 2, 2])
+    setup_lookup_proof(false, 500, vec![100, 50, 50, 2, 2], LookupArgument::Plookup)
 }
 
-fn runtime_table(num: usize, indexed: bool) {
+#[test]
+fn lookup_gate_proving_works_logup() {
+    setup_lookup_proof(true, 500, vec![256], LookupArgument::LogUp)
+}
+
+#[test]
+#[should_panic]
+fn lookup_gate_rejects_bad_lookups_logup() {
+    setup_lookup_proof(false, 500, vec![256], LookupArgument::LogUp)
+}
+
+#[test]
+fn lookup_gate_proving_works_multiple_tables_logup() {
+    setup_lookup_proof(true, 500, vec![100, 50, 50, 2, 2], LookupArgument::LogUp)
+}
+
+#[test]
+// Test that the built-in tables are proven with the logUp argument, with the same gates as with plookup
+fn test_logup_xor() {
+    // the xor gadget performs 4 joint lookups per row
+    let mut gates = vec![];
+    CircuitGate::<Fp>::extend_xor_gadget(&mut gates, 64);
+    let witness = xor::create_xor_witness(Fp::from(0xdead_beef_u64), Fp::from(u64::MAX), 64);
+
+    let prove = |argument| {
+        let runner = TestFramework::<Vesta>::default()
+            .gates(gates.clone())
+            .witness(witness.clone())
+            .lookup_argument(argument)
+            .setup();
+        let lookup_info = &runner
+            .prover_index()
+            .cs
+            .lookup_constraint_system
+            .as_ref()
+            .unwrap()
+            .configuration
+            .lookup_info;
+        assert_eq!(lookup_info.features.argument, argument);
+        // plookup sorts the 4 lookups of each row with the table, logUp only counts multiplicities
+        let num_sorted = if argument == LookupArgument::LogUp {
+            1
+        } else {
+            5
+        };
+        assert_eq!(lookup_info.num_sorted(), num_sorted);
+        runner
+            .prove_and_verify::<BaseSponge, ScalarSponge>()
+            .unwrap();
+    };
+    prove(LookupArgument::Plookup);
+    prove(LookupArgument::LogUp);
+}
+
+fn runtime_table(
+    num: usize,
+    indexed: bool,
+    argument: LookupArgument,
+    table_rows: Option<Range<usize>>,
+) {
     // runtime
     let mut runtime_tables_setup = vec![];
     for table_id in 0..num {
@@ -186,24 +261,535 @@ fn runtime_table(num: usize, indexed: bool) {
     print_witness(&witness, 0, 20);
 
     // run test
-    TestFramework::<Vesta>::default()
+    let framework = TestFramework::<Vesta>::default()
         .gates(gates)
         .witness(witness)
         .runtime_tables_setup(runtime_tables_setup)
-        .setup()
-        .runtime_tables(runtime_tables)
-        .prove_and_verify::<BaseSponge, ScalarSponge>()
-        .unwrap();
+        .lookup_argument(argument);
+    match table_rows {
+        Some(rows) => framework.lookup_table_rows(rows),
+        None => framework,
+    }
+    .setup()
+    .runtime_tables(runtime_tables)
+    .prove_and_verify::<BaseSponge, ScalarSponge>()
+    .unwrap();
 }
 
 #[test]
 fn test_indexed_runtime_table() {
-    runtime_table(5, true);
+    runtime_table(5, true, LookupArgument::Plookup, None);
 }
 
 #[test]
 fn test_custom_runtime_table() {
-    runtime_table(5, false);
+    runtime_table(5, false, LookupArgument::Plookup, None);
+}
+
+#[test]
+fn test_runtime_tables_logup() {
+    runtime_table(5, true, LookupArgument::LogUp, None);
+    runtime_table(5, false, LookupArgument::LogUp, None);
+}
+
+#[test]
+// Test that the runtime tables are proven in the rows reserved for the tables
+fn test_runtime_tables_in_reserved_rows() {
+    for argument in [LookupArgument::Plookup, LookupArgument::LogUp] {
+        runtime_table(5, true, argument, Some(30..60));
+        runtime_table(5, false, argument, Some(30..60));
+    }
 }
 
 // TODO: add a test with a runtime table with ID 0 (it should panic)
+
+const SQUARES_TABLE_ID: i32 = 3;
+const EVEN_TABLE_ID: i32 = 4;
+
+// A custom table of two columns, mapping integers to their squares
+fn squares_table() -> LookupTable<Fp> {
+    LookupTable {
+        id: SQUARES_TABLE_ID,
+        data: vec![
+            (0u64..16).map(Into::into).collect(),
+            (0u64..16).map(|i| (i * i).into()).collect(),
+        ],
+    }
+}
+
+// A custom table of a single column, with the even integers
+fn even_table() -> LookupTable<Fp> {
+    LookupTable {
+        id: EVEN_TABLE_ID,
+        data: vec![(0u64..8).map(|i| (2 * i).into()).collect()],
+    }
+}
+
+fn fixed_table_lookups(squares: &[(Fp, Fp)], even: &[(Fp, Fp)]) -> Result<(), String> {
+    let mut gates = vec![];
+    CircuitGate::extend_lookup(&mut gates, SQUARES_TABLE_ID, squares.len());
+    CircuitGate::extend_lookup(&mut gates, EVEN_TABLE_ID, even.len());
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    gadget::extend_lookup_witness(&mut witness, SQUARES_TABLE_ID, squares);
+    gadget::extend_lookup_witness(&mut witness, EVEN_TABLE_ID, even);
+    assert_eq!(witness[0].len(), gates.len());
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .lookup_tables(vec![squares_table(), even_table()])
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+}
+
+#[test]
+fn test_fixed_table_lookup_gadget() {
+    // 7 lookups need 3 rows, with the unused slots repeating the last lookup
+    let squares: Vec<(Fp, Fp)> = [1u64, 3, 5, 7, 11, 13, 15]
+        .into_iter()
+        .map(|i| (i.into(), (i * i).into()))
+        .collect();
+    let even = [(Fp::from(6u64), Fp::zero())];
+    assert_eq!(gadget::num_lookup_rows(squares.len()), 3);
+    fixed_table_lookups(&squares, &even).unwrap();
+}
+
+#[test]
+fn test_fixed_table_lookup_gadget_wrong_table() {
+    // (2, 4) is an entry of the squares table, but not of the table of even integers
+    let squares = [(Fp::from(2u64), Fp::from(4u64))];
+    assert!(fixed_table_lookups(&squares, &squares).is_err());
+
+    // an odd integer is not an entry of the table of even integers
+    let even = [(Fp::from(7u64), Fp::zero())];
+    assert!(fixed_table_lookups(&squares, &even).is_err());
+}
+
+// The lookups of a selection lookup gadget for each selector, between the squares and even tables
+fn select_table_lookups(
+    lookups: &[(bool, Vec<(Fp, Fp)>)],
+    forge: impl Fn(&mut [Vec<Fp>; COLUMNS]),
+) -> Result<(), String> {
+    let table_ids = [SQUARES_TABLE_ID, EVEN_TABLE_ID];
+    let mut gates = vec![];
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    for (select, lookups) in lookups {
+        let row = gates.len();
+        CircuitGate::extend_select_lookup(&mut gates, table_ids, lookups.len());
+        gadget::extend_select_lookup_witness(&mut witness, table_ids, *select, lookups);
+        let (sel_row, sel_col) = gadget::select_lookup_selector(row);
+        assert_eq!(witness[sel_col][sel_row], Fp::from(*select));
+        let (id_row, id_col) = gadget::dynamic_lookup_table_id(row + 1);
+        assert_eq!(
+            witness[id_col][id_row],
+            Fp::from(table_ids[usize::from(*select)] as u64)
+        );
+    }
+    assert_eq!(witness[0].len(), gates.len());
+    forge(&mut witness);
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .lookup_tables(vec![squares_table(), even_table()])
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+}
+
+#[test]
+// Test that each gadget looks up into the table chosen by its selector
+fn test_select_lookup_gadget() {
+    let squares: Vec<(Fp, Fp)> = [2u64, 3, 4, 5]
+        .into_iter()
+        .map(|i| (i.into(), (i * i).into()))
+        .collect();
+    let even = vec![(Fp::from(10u64), Fp::zero())];
+    let lookups = [(false, squares.clone()), (true, even), (false, squares)];
+    select_table_lookups(&lookups, |_| ()).unwrap();
+}
+
+#[test]
+// Test that the lookups of a gadget are not in the other table, and that its table ID is the selected one
+fn test_select_lookup_gadget_wrong_table() {
+    let squares = vec![(Fp::from(2u64), Fp::from(4u64))];
+    let even = vec![(Fp::from(4u64), Fp::zero())];
+
+    // the lookups of the squares table, with the selector of the even table
+    assert!(select_table_lookups(&[(true, squares.clone())], |_| ()).is_err());
+
+    // the lookups of the even table, with the selector of the squares table
+    // and the ID of the even table in the lookup row
+    let forge = |witness: &mut [Vec<Fp>; COLUMNS]| {
+        let (row, col) = gadget::dynamic_lookup_table_id(1);
+        witness[col][row] = Fp::from(EVEN_TABLE_ID as u64);
+    };
+    assert!(select_table_lookups(&[(false, even.clone())], forge).is_err());
+
+    // the selector is not a boolean, to select the ID 5 = 3 + 2 * (4 - 3) of another table
+    let forge = |witness: &mut [Vec<Fp>; COLUMNS]| {
+        let (row, _) = gadget::select_lookup_selector(0);
+        for col in witness.iter_mut().take(4) {
+            col[row] = Fp::from(2u64);
+        }
+        let table_id = Fp::from(2 * EVEN_TABLE_ID as u64 - SQUARES_TABLE_ID as u64);
+        witness[5][row] = table_id;
+        let (row, col) = gadget::dynamic_lookup_table_id(1);
+        witness[col][row] = table_id;
+    };
+    assert!(select_table_lookups(&[(true, even)], forge).is_err());
+}
+
+const ALU_TABLE_ID: i32 = 5;
+
+// The operations of the ALU table
+const ALU_ADD: u64 = 0;
+const ALU_XOR: u64 = 1;
+
+// A custom table of four columns, with the entries (op, in1, in2, out) of additions and xors of 2-bit integers
+fn alu_table() -> LookupTable<Fp> {
+    let entries: Vec<[u64; 4]> = [ALU_ADD, ALU_XOR]
+        .into_iter()
+        .flat_map(|op| {
+            (0u64..4).flat_map(move |in1| {
+                (0u64..4).map(move |in2| {
+                    let out = if op == ALU_ADD { in1 + in2 } else { in1 ^ in2 };
+                    [op, in1, in2, out]
+                })
+            })
+        })
+        .collect();
+    LookupTable {
+        id: ALU_TABLE_ID,
+        data: (0..4)
+            .map(|col| entries.iter().map(|entry| entry[col].into()).collect())
+            .collect(),
+    }
+}
+
+fn alu(op: u64, in1: u64, in2: u64, out: u64) -> Vec<Fp> {
+    vec![op.into(), in1.into(), in2.into(), out.into()]
+}
+
+fn vector_table_lookups(
+    alu: &[Vec<Fp>],
+    even: &[Vec<Fp>],
+    argument: LookupArgument,
+) -> Result<(), String> {
+    // the vector lookups share the circuit with the lookups of pairs
+    let squares = [(Fp::from(3u64), Fp::from(9u64))];
+    let mut gates = vec![];
+    CircuitGate::extend_vector_lookup(&mut gates, ALU_TABLE_ID, alu.len());
+    CircuitGate::extend_vector_lookup(&mut gates, EVEN_TABLE_ID, even.len());
+    CircuitGate::extend_lookup(&mut gates, SQUARES_TABLE_ID, squares.len());
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    gadget::extend_vector_lookup_witness(&mut witness, ALU_TABLE_ID, alu);
+    gadget::extend_vector_lookup_witness(&mut witness, EVEN_TABLE_ID, even);
+    gadget::extend_lookup_witness(&mut witness, SQUARES_TABLE_ID, &squares);
+    assert_eq!(witness[0].len(), gates.len());
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .lookup_tables(vec![alu_table(), squares_table(), even_table()])
+        .lookup_argument(argument)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+}
+
+#[test]
+fn test_vector_lookup_gadget() {
+    let alu_lookups = [
+        alu(ALU_ADD, 1, 2, 3),
+        alu(ALU_ADD, 3, 3, 6),
+        alu(ALU_XOR, 3, 1, 2),
+        alu(ALU_XOR, 0, 0, 0),
+    ];
+    // the tuples of a table with a single column are looked up as single values
+    let even = [vec![Fp::from(14u64)]];
+    for argument in [LookupArgument::Plookup, LookupArgument::LogUp] {
+        vector_table_lookups(&alu_lookups, &even, argument).unwrap();
+    }
+}
+
+#[test]
+fn test_vector_lookup_gadget_wrong_tuple() {
+    let even = [vec![Fp::from(2u64)]];
+
+    // the output of the addition is the one of the xor
+    let alu_lookups = [alu(ALU_ADD, 3, 1, 2)];
+    assert!(vector_table_lookups(&alu_lookups, &even, LookupArgument::Plookup).is_err());
+
+    // the tuple is a permutation of an entry
+    let alu_lookups = [alu(ALU_ADD, 1, 3, 4), alu(ALU_ADD, 4, 1, 3)];
+    assert!(vector_table_lookups(&alu_lookups, &even, LookupArgument::Plookup).is_err());
+
+    // a tuple of an entry of the ALU table in the table of even integers
+    let alu_lookups = [alu(ALU_ADD, 0, 0, 0)];
+    let even = [alu(ALU_ADD, 2, 2, 4)];
+    assert!(vector_table_lookups(&alu_lookups, &even, LookupArgument::Plookup).is_err());
+}
+
+#[test]
+fn test_custom_table_id_collision() {
+    // the range check gate uses the built-in range check table (with ID 1)
+    let (_, mut gates) = CircuitGate::<Fp>::create_range_check(0);
+    CircuitGate::extend_lookup(&mut gates, 1, 1);
+    let table = LookupTable {
+        id: 1,
+        data: vec![vec![Fp::from(1u64 << 20)]],
+    };
+    let res = ConstraintSystem::create(gates.clone())
+        .lookup(vec![table])
+        .build();
+    assert!(matches!(res, Err(SetupError::ConstraintSystem(e))
+        if e == LookupError::TableIDCollision(1).to_string()));
+
+    // two custom tables with the same ID
+    let res = ConstraintSystem::create(gates.clone())
+        .lookup(vec![
+            squares_table(),
+            LookupTable {
+                id: SQUARES_TABLE_ID,
+                ..even_table()
+            },
+        ])
+        .build();
+    assert!(matches!(res, Err(SetupError::ConstraintSystem(e))
+        if e == LookupError::TableIDCollision(SQUARES_TABLE_ID).to_string()));
+
+    // registering the same table twice is allowed
+    assert!(ConstraintSystem::create(gates.clone())
+        .lookup(vec![squares_table(), squares_table()])
+        .build()
+        .is_ok());
+
+    // tables without entries are rejected
+    let res = ConstraintSystem::create(gates)
+        .lookup(vec![LookupTable {
+            id: EVEN_TABLE_ID,
+            data: vec![vec![]],
+        }])
+        .build();
+    assert!(matches!(res, Err(SetupError::ConstraintSystem(e))
+        if e == LookupError::EmptyTable(EVEN_TABLE_ID).to_string()));
+}
+
+#[test]
+// Test that the domain makes room for the lookup tables, without padding the circuit
+fn test_domain_size_with_large_table() {
+    // a table filling the rows of a domain of size 64, except the dummy entry,
+    // the final product of the lookup aggregation and the zero-knowledge rows
+    let max_len = 64 - 2 - ZK_ROWS as usize;
+    let table = |len: usize| LookupTable {
+        id: SQUARES_TABLE_ID,
+        data: vec![
+            (0..len as u64).map(Into::into).collect(),
+            (0..len as u64).map(|i| (i * i).into()).collect(),
+        ],
+    };
+    let lookups = [(Fp::from(7u64), Fp::from(49u64))];
+
+    for (len, domain_size) in [(max_len, 64), (max_len + 1, 128)] {
+        let (_, gates) = CircuitGate::<Fp>::create_lookup(0, SQUARES_TABLE_ID, lookups.len());
+        let cs = ConstraintSystem::create(gates.clone())
+            .lookup(vec![table(len)])
+            .build()
+            .unwrap();
+        assert_eq!(cs.domain.d1.size(), domain_size);
+
+        let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+        gadget::extend_lookup_witness(&mut witness, SQUARES_TABLE_ID, &lookups);
+
+        TestFramework::<Vesta>::default()
+            .gates(gates)
+            .witness(witness)
+            .lookup_tables(vec![table(len)])
+            .setup()
+            .prove_and_verify::<BaseSponge, ScalarSponge>()
+            .unwrap();
+    }
+}
+
+#[test]
+// Test that a reserved domain is used as is, and is not grown when the circuit does not fit
+fn test_reserved_domain_size() {
+    let max_len = 64 - 2 - ZK_ROWS as usize;
+    let table = |len: usize| LookupTable {
+        id: EVEN_TABLE_ID,
+        data: vec![(0..len as u64).map(|i| (2 * i).into()).collect()],
+    };
+    let (_, gates) = CircuitGate::<Fp>::create_lookup(0, EVEN_TABLE_ID, 1);
+    let build = |len: usize, domain_size: usize| {
+        ConstraintSystem::create(gates.clone())
+            .lookup(vec![table(len)])
+            .domain_size(domain_size)
+            .build()
+    };
+
+    // a domain larger than needed, and the smallest domain fitting the table
+    assert_eq!(build(4, 256).unwrap().domain.d1.size(), 256);
+    assert_eq!(build(max_len, 64).unwrap().domain.d1.size(), 64);
+
+    // the table does not fit, and the domain size is not a power of two
+    assert!(matches!(
+        build(max_len + 1, 64),
+        Err(SetupError::ConstraintSystem(_))
+    ));
+    assert!(matches!(
+        build(4, 100),
+        Err(SetupError::ConstraintSystem(_))
+    ));
+
+    // the gates do not fit
+    let (_, gates) = CircuitGate::<Fp>::create_lookup(0, EVEN_TABLE_ID, 3 * 32);
+    assert!(gates.len() + ZK_ROWS as usize > 32);
+    let res = ConstraintSystem::create(gates)
+        .lookup(vec![table(4)])
+        .domain_size(32)
+        .build();
+    assert!(matches!(res, Err(SetupError::ConstraintSystem(_))));
+}
+
+#[test]
+// Test that the fixed lookup tables are proven in the rows reserved for them,
+// and that the tables must fit in the reserved rows
+fn test_lookup_table_rows() {
+    let squares = [(Fp::from(3u64), Fp::from(9u64))];
+    let even = [(Fp::from(14u64), Fp::zero())];
+    let mut gates = vec![];
+    CircuitGate::extend_lookup(&mut gates, SQUARES_TABLE_ID, squares.len());
+    CircuitGate::extend_lookup(&mut gates, EVEN_TABLE_ID, even.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    gadget::extend_lookup_witness(&mut witness, SQUARES_TABLE_ID, &squares);
+    gadget::extend_lookup_witness(&mut witness, EVEN_TABLE_ID, &even);
+    let build = |rows: Range<usize>| {
+        ConstraintSystem::create(gates.clone())
+            .lookup(vec![squares_table(), even_table()])
+            .lookup_table_rows(rows)
+            .build()
+    };
+
+    // the domain grows to fit the reserved rows
+    let cs = build(90..114).unwrap();
+    assert_eq!(cs.domain.d1.size(), 128);
+    let prove = |rows: Range<usize>, argument: LookupArgument| {
+        TestFramework::<Vesta>::default()
+            .gates(gates.clone())
+            .witness(witness.clone())
+            .lookup_tables(vec![squares_table(), even_table()])
+            .lookup_argument(argument)
+            .lookup_table_rows(rows)
+            .setup()
+            .prove_and_verify::<BaseSponge, ScalarSponge>()
+    };
+    for argument in [LookupArgument::Plookup, LookupArgument::LogUp] {
+        prove(90..114, argument).unwrap();
+        prove(3..40, argument).unwrap();
+    }
+
+    // the placement of the tables is part of the digest of the lookup constraint system
+    let digest = |cs: ConstraintSystem<Fp>| cs.lookup_constraint_system.unwrap().inputs_digest;
+    assert_ne!(digest(build(3..40).unwrap()), digest(build(4..40).unwrap()));
+
+    // the 24 entries of the tables do not fit in the reserved rows
+    let out_of_rows = LookupError::LookupTableOutOfRows {
+        length: 24,
+        rows: 90..113,
+    };
+    assert!(
+        matches!(build(90..113), Err(SetupError::ConstraintSystem(e))
+        if e == out_of_rows.to_string())
+    );
+    assert!(matches!(
+        build(40..30),
+        Err(SetupError::ConstraintSystem(_))
+    ));
+
+    // the reserved rows collide with the end of the reserved domain
+    let res = ConstraintSystem::create(gates.clone())
+        .lookup(vec![squares_table(), even_table()])
+        .lookup_table_rows(90..114)
+        .domain_size(64)
+        .build();
+    assert!(matches!(res, Err(SetupError::ConstraintSystem(_))));
+}
+
+#[test]
+// Test that a serialized lookup constraint system and its commitments are reused when setting up the same circuit,
+// and computed again for other lookup tables
+fn test_lookup_precomputations() {
+    let (_, gates) = CircuitGate::<Fp>::create_lookup(0, SQUARES_TABLE_ID, 1);
+    let build = |table: LookupTable<Fp>, lcs: Option<LookupConstraintSystem<Fp>>| {
+        let builder = ConstraintSystem::create(gates.clone()).lookup(vec![table]);
+        match lcs {
+            Some(lcs) => builder.lookup_precomputations(lcs),
+            None => builder,
+        }
+        .build()
+        .unwrap()
+    };
+
+    let mut index = new_index_for_test_with_cs::<Vesta>(build(squares_table(), None));
+    index.precompute_lookup_commitments();
+    let lcs = index.cs.lookup_constraint_system.clone().unwrap();
+    let lcs_bytes = rmp_serde::to_vec(&lcs).unwrap();
+    let commitments_bytes = rmp_serde::to_vec(&index.lookup_commitments).unwrap();
+    let cached_lcs: LookupConstraintSystem<Fp> = rmp_serde::from_slice(&lcs_bytes).unwrap();
+    assert_eq!(cached_lcs.inputs_digest, lcs.inputs_digest);
+
+    // the cached lookup constraint system is used as is for the same circuit
+    let mut tampered_lcs = cached_lcs.clone();
+    tampered_lcs.lookup_table.clear();
+    let cs = build(squares_table(), Some(tampered_lcs));
+    assert!(cs.lookup_constraint_system.unwrap().lookup_table.is_empty());
+
+    // and the verifier index of the new setup uses the cached commitments
+    let mut index = new_index_for_test_with_cs::<Vesta>(build(squares_table(), Some(cached_lcs)));
+    let lookup_index = rmp_serde::to_vec(&index.verifier_index().lookup_index).unwrap();
+    index.lookup_commitments = rmp_serde::from_slice(&commitments_bytes).unwrap();
+    assert_eq!(lookup_index, commitments_bytes);
+    assert_eq!(
+        rmp_serde::to_vec(&index.verifier_index().lookup_index).unwrap(),
+        commitments_bytes
+    );
+
+    // another content of the table is computed again
+    let mut other_table = squares_table();
+    other_table.data[1][0] = Fp::from(1u64);
+    let cached_lcs = rmp_serde::from_slice(&lcs_bytes).unwrap();
+    let cs = build(other_table.clone(), Some(cached_lcs));
+    let lcs = cs.lookup_constraint_system.unwrap();
+    assert_ne!(rmp_serde::to_vec(&lcs).unwrap(), lcs_bytes);
+    assert_eq!(
+        lcs.inputs_digest,
+        build(other_table, None)
+            .lookup_constraint_system
+            .unwrap()
+            .inputs_digest
+    );
+}
+
+#[test]
+// Test that the fixed lookup tables of the gates have their IDs and sizes, and their entries by row
+fn test_gate_lookup_tables() {
+    let gate_tables = tables::gate_lookup_tables::<Fp>();
+    for (table, gate_table) in gate_tables.iter().zip(GATE_LOOKUP_TABLES) {
+        assert_eq!(table.id, gate_table.id());
+        assert_eq!(table.len(), gate_table.table_size());
+        assert_eq!(table.entries().count(), table.len());
+        assert!(table.contains(&table.entry(table.len() - 1)));
+    }
+    let ids: Vec<i32> = gate_tables.iter().map(|table| table.id).collect();
+    assert_eq!(ids, vec![0, 1, 2, 3]);
+
+    // the entries of the XOR table are (a, b, a ^ b)
+    let xor = &gate_tables[0];
+    assert_eq!(xor.width(), 3);
+    for (a, b) in (0u8..16).flat_map(|a| (0u8..16).map(move |b| (a, b))) {
+        assert!(xor.contains(&[Fp::from(a), Fp::from(b), Fp::from(a ^ b)]));
+        assert!(!xor.contains(&[Fp::from(a), Fp::from(b), Fp::from(a ^ b ^ 1)]));
+    }
+    assert!(!xor.contains(&[Fp::from(1u8), Fp::from(1u8)]));
+}
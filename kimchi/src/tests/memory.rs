@@ -0,0 +1,145 @@
+use std::array;
+
+use super::framework::TestFramework;
+use crate::circuits::{
+    gate::CircuitGate,
+    lookup::runtime_tables::RuntimeTable,
+    polynomial::COLUMNS,
+    polynomials::memory::{
+        self, memory_address_cell, memory_rows, memory_runtime_tables_setup, memory_value_cell,
+        MemoryAccess, MemoryOp,
+    },
+};
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+const FIRST_TABLE_ID: i32 = 10;
+
+fn load(address: u64) -> MemoryAccess<Fp> {
+    MemoryAccess::Load {
+        address: address.into(),
+    }
+}
+
+fn store(address: u64, value: u64) -> MemoryAccess<Fp> {
+    MemoryAccess::Store {
+        address: address.into(),
+        value: value.into(),
+    }
+}
+
+// Creates a memory gadget of the kinds of operations, and its witness for the accesses
+fn create_memory(
+    ops: &[MemoryOp],
+    accesses: &[MemoryAccess<Fp>],
+) -> (
+    Vec<CircuitGate<Fp>>,
+    [Vec<Fp>; COLUMNS],
+    Vec<RuntimeTable<Fp>>,
+) {
+    let mut gates = vec![];
+    let next_row = CircuitGate::extend_memory(&mut gates, FIRST_TABLE_ID, ops);
+    assert_eq!(next_row, memory_rows(ops.len()));
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let runtime_tables = memory::extend_memory_witness(&mut witness, FIRST_TABLE_ID, accesses);
+    assert_eq!(witness[0].len(), gates.len());
+
+    (gates, witness, runtime_tables)
+}
+
+fn prove_and_verify(
+    gates: Vec<CircuitGate<Fp>>,
+    witness: [Vec<Fp>; COLUMNS],
+    runtime_tables: Vec<RuntimeTable<Fp>>,
+) -> Result<(), String> {
+    let num_accesses = runtime_tables[0].data.len();
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .runtime_tables_setup(memory_runtime_tables_setup(FIRST_TABLE_ID, num_accesses))
+        .setup()
+        .runtime_tables(runtime_tables)
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+}
+
+#[test]
+// Test that the loads read the last value stored at their address, or zero
+fn test_memory() {
+    let accesses = [
+        store(5, 10),
+        load(5),
+        load(7),
+        store(7, 3),
+        store(5, 11),
+        load(5),
+        load(7),
+        load(u64::MAX),
+    ];
+    let ops: Vec<_> = accesses.iter().map(MemoryAccess::op).collect();
+    let (gates, witness, runtime_tables) = create_memory(&ops, &accesses);
+    assert_eq!(gates.len(), 2 + 4 + 6 * 3 + 5 * 7);
+
+    let value = |t: usize| {
+        let (row, col) = memory_value_cell(0, accesses.len(), t);
+        witness[col][row]
+    };
+    let loaded: Vec<Fp> = [1, 2, 5, 6, 7].into_iter().map(value).collect();
+    assert_eq!(
+        loaded,
+        [10u64, 0, 11, 3, 0].map(Fp::from).to_vec(),
+        "the loads read the last stores"
+    );
+    let (row, col) = memory_address_cell(0, accesses.len(), 7);
+    assert_eq!(witness[col][row], Fp::from(u64::MAX));
+
+    prove_and_verify(gates, witness, runtime_tables).unwrap();
+
+    // a single access
+    let (gates, witness, runtime_tables) = create_memory(&[MemoryOp::Load], &[load(1)]);
+    assert_eq!(gates.len(), memory_rows(1));
+    prove_and_verify(gates, witness, runtime_tables).unwrap();
+}
+
+#[test]
+// Test that a load cannot write a new value
+fn test_memory_load_writes() {
+    // the second access is a load in the circuit, but writes 12 in the witness
+    let ops = [MemoryOp::Store, MemoryOp::Load, MemoryOp::Load];
+    let (gates, witness, runtime_tables) =
+        create_memory(&ops, &[store(5, 10), store(5, 12), load(5)]);
+    assert!(prove_and_verify(gates, witness, runtime_tables).is_err());
+}
+
+#[test]
+// Test that a load cannot read another value than the last one stored at its address
+fn test_memory_wrong_load() {
+    let accesses = [store(5, 10), load(5)];
+    let ops: Vec<_> = accesses.iter().map(MemoryAccess::op).collect();
+    let (gates, mut witness, mut runtime_tables) = create_memory(&ops, &accesses);
+
+    // the load reads and writes back 12 in its lookups (the old value is in the row before the new value),
+    // and in the runtime tables, but the sorted accesses still show the store of 10
+    let forged = Fp::from(12u64);
+    let (row, col) = memory_value_cell(0, accesses.len(), 1);
+    witness[col][row] = forged;
+    witness[col][row - 1] = forged;
+    runtime_tables[1].data[1] = forged;
+    runtime_tables[2].data[1] = forged;
+    assert!(prove_and_verify(gates, witness, runtime_tables).is_err());
+}
+
+#[test]
+#[should_panic]
+// Test that the addresses must fit in 64 bits
+fn test_memory_large_address() {
+    let address = Fp::from(u64::MAX) + Fp::from(1u64);
+    create_memory(&[MemoryOp::Load], &[MemoryAccess::Load { address }]);
+}
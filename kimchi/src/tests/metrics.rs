@@ -0,0 +1,83 @@
+use crate::{
+    circuits::{
+        polynomials::generic::testing::{create_circuit, fill_in_witness},
+        wires::COLUMNS,
+    },
+    metrics::ProverPhase,
+    proof::ProverProof,
+    prover_index::testing::new_index_for_test,
+    verifier::verify,
+};
+use ark_ff::Zero;
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use poly_commitment::commitment::CommitmentCurve;
+use rand::{rngs::StdRng, SeedableRng};
+use std::{array, time::Duration};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+#[test]
+// Test that the phases of the prover are timed in order, without changing the proof
+fn test_prover_metrics() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+    let index = new_index_for_test::<Vesta>(gates, public.len());
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+
+    let (proof, metrics) =
+        ProverProof::create_recursive_with_metrics::<BaseSponge, ScalarSponge, _>(
+            &group_map,
+            witness.clone(),
+            &[],
+            &index,
+            vec![],
+            None,
+            &mut StdRng::seed_from_u64(0),
+        )
+        .unwrap();
+    verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &index.verifier_index(), &proof, &public)
+        .unwrap();
+    let expected = ProverProof::create_recursive_with_rng::<BaseSponge, ScalarSponge, _>(
+        &group_map,
+        witness,
+        &[],
+        &index,
+        vec![],
+        None,
+        &mut StdRng::seed_from_u64(0),
+    )
+    .unwrap();
+    assert_eq!(proof.commitments.t_comm, expected.commitments.t_comm);
+    assert_eq!(proof.proof.delta, expected.proof.delta);
+
+    let phases: Vec<_> = metrics.phases.iter().map(|(phase, _)| *phase).collect();
+    assert_eq!(
+        phases,
+        [
+            ProverPhase::Witness,
+            ProverPhase::Lookup,
+            ProverPhase::Permutation,
+            ProverPhase::Quotient,
+            ProverPhase::Evaluations,
+            ProverPhase::Opening,
+        ]
+    );
+    assert_eq!(
+        metrics.total(),
+        metrics
+            .phases
+            .iter()
+            .map(|(phase, _)| metrics.duration(*phase))
+            .sum::<Duration>()
+    );
+    assert!(metrics.duration(ProverPhase::Quotient) > metrics.duration(ProverPhase::Lookup));
+}
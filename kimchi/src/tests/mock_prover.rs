@@ -0,0 +1,187 @@
+use std::array;
+
+use super::framework::TestFramework;
+use crate::{
+    circuits::{
+        constraints::ConstraintSystem,
+        gate::{CircuitGate, Connect, GateType},
+        lookup::runtime_tables::{RuntimeTable, RuntimeTableCfg, RuntimeTableSpec},
+        polynomial::COLUMNS,
+        polynomials::{generic::GenericGateSpec, xor},
+        wires::Wire,
+    },
+    mock_prover::{MockProver, MockProverFailure},
+};
+use ark_ff::{One, Zero};
+use mina_curves::pasta::{Fp, Vesta};
+
+// Creates a public input, and a gate adding a copy of it to another value
+fn create_add_public() -> Vec<CircuitGate<Fp>> {
+    let mut gates = vec![
+        CircuitGate::create_generic_gadget(Wire::for_row(0), GenericGateSpec::Pub, None),
+        CircuitGate::create_generic_gadget(
+            Wire::for_row(1),
+            GenericGateSpec::Add {
+                left_coeff: None,
+                right_coeff: None,
+                output_coeff: None,
+            },
+            None,
+        ),
+    ];
+    gates.connect_cell_pair((0, 0), (1, 0));
+    gates
+}
+
+// The witness of the addition of the public input to 4
+fn add_public_witness(public: u32) -> [Vec<Fp>; COLUMNS] {
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); 2]);
+    witness[0][0] = public.into();
+    witness[0][1] = public.into();
+    witness[1][1] = 4u32.into();
+    witness[2][1] = (public + 4).into();
+    witness
+}
+
+#[test]
+// Test that the mock prover accepts a valid xor, and reports the broken constraints of another output
+fn test_mock_prover_xor() {
+    let (_, gates) = CircuitGate::<Fp>::create_xor_gadget(0, 64);
+    let mut witness = xor::create_xor_witness(Fp::from(0x1234_5678u32), Fp::from(0xffffu32), 64);
+    TestFramework::<Vesta>::default()
+        .gates(gates.clone())
+        .witness(witness.clone())
+        .mock_prove()
+        .unwrap();
+
+    witness[2][0] += Fp::one();
+    let failures = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .mock_prove()
+        .unwrap_err();
+    assert!(!failures.is_empty());
+    for failure in failures {
+        assert!(matches!(
+            failure,
+            MockProverFailure::Constraint {
+                row: 0,
+                typ: GateType::Xor16,
+                ..
+            }
+        ));
+    }
+}
+
+#[test]
+// Test that the mock prover reports all the broken copies and the wrong public inputs
+fn test_mock_prover_copy() {
+    let gates = create_add_public();
+    let cs = ConstraintSystem::create(gates).public(1).build().unwrap();
+    let witness = add_public_witness(3);
+    let public = [Fp::from(3u32)];
+    assert_eq!(
+        MockProver::<Vesta>::new(&cs, &witness, &public).verify(),
+        Ok(())
+    );
+
+    // another value is added, breaking the copy in both directions
+    let mut other = witness.clone();
+    other[0][1] = 5u32.into();
+    other[2][1] = 9u32.into();
+    assert_eq!(
+        MockProver::<Vesta>::new(&cs, &other, &public).verify(),
+        Err(vec![
+            MockProverFailure::CopyConstraint {
+                src: Wire { row: 0, col: 0 },
+                dst: Wire { row: 1, col: 0 },
+            },
+            MockProverFailure::CopyConstraint {
+                src: Wire { row: 1, col: 0 },
+                dst: Wire { row: 0, col: 0 },
+            },
+        ])
+    );
+
+    // another public input
+    let public = [Fp::from(4u32)];
+    assert_eq!(
+        MockProver::<Vesta>::new(&cs, &witness, &public).verify(),
+        Err(vec![MockProverFailure::Constraint {
+            row: 0,
+            typ: GateType::Generic,
+            index: 1,
+        }])
+    );
+    assert_eq!(
+        MockProver::<Vesta>::new(&cs, &witness, &[]).verify(),
+        Err(vec![MockProverFailure::PublicInputLength {
+            expected: 1,
+            got: 0
+        }])
+    );
+}
+
+#[test]
+// Test that the mock prover checks the lookups into runtime tables
+fn test_mock_prover_runtime_table() {
+    let runtime_tables_setup = vec![RuntimeTableCfg::Indexed(RuntimeTableSpec { id: 1, len: 5 })];
+    let runtime_tables = [RuntimeTable {
+        id: 1,
+        data: [0u32, 2, 3, 4, 5].into_iter().map(Into::into).collect(),
+    }];
+    let gates = (0..10)
+        .map(|row| CircuitGate::new(GateType::Lookup, Wire::for_row(row), vec![]))
+        .collect();
+    let cs = ConstraintSystem::create(gates)
+        .runtime(Some(runtime_tables_setup))
+        .build()
+        .unwrap();
+
+    // the table 1 maps 1 to 2
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|col| match col {
+        0 | 1 | 3 | 5 => vec![Fp::one(); 10],
+        2 | 4 | 6 => vec![Fp::from(2u32); 10],
+        _ => vec![Fp::zero(); 10],
+    });
+    let prover = MockProver::<Vesta>::new(&cs, &witness, &[]);
+    assert_eq!(prover.runtime_tables(&runtime_tables).verify(), Ok(()));
+    assert_eq!(
+        MockProver::<Vesta>::new(&cs, &witness, &[]).verify(),
+        Err(vec![MockProverFailure::RuntimeTablesInconsistent])
+    );
+
+    witness[2][3] = 3u32.into();
+    let prover = MockProver::<Vesta>::new(&cs, &witness, &[]).runtime_tables(&runtime_tables);
+    assert_eq!(
+        prover.verify(),
+        Err(vec![MockProverFailure::Lookup { row: 3, index: 0 }])
+    );
+}
+
+#[test]
+// Test that the mock prover rejects witness columns of different lengths
+fn test_mock_prover_witness_length() {
+    let cs = ConstraintSystem::create(create_add_public())
+        .public(1)
+        .build()
+        .unwrap();
+    let mut witness = add_public_witness(3);
+    witness[5].push(Fp::zero());
+    assert_eq!(
+        MockProver::<Vesta>::new(&cs, &witness, &[Fp::from(3u32)]).verify(),
+        Err(vec![MockProverFailure::WitnessCsInconsistent])
+    );
+}
+
+#[test]
+#[should_panic]
+// Test that the failures of the mock prover can be asserted
+fn test_mock_prover_assert_satisfied() {
+    let cs = ConstraintSystem::create(create_add_public())
+        .public(1)
+        .build()
+        .unwrap();
+    let witness = add_public_witness(3);
+    MockProver::<Vesta>::new(&cs, &witness, &[Fp::from(5u32)]).assert_satisfied();
+}
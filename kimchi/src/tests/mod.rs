@@ -1,18 +1,70 @@
+mod add_u64;
 mod and;
+mod assertion;
+mod builder;
+#[cfg(feature = "ocaml_types")]
+mod caml_api;
+mod compare;
+mod compiled;
+mod completeness;
+mod custom_gate;
+mod decompression;
 mod ec;
+mod ecdsa;
 mod endomul;
 mod endomul_scalar;
+mod equivalence;
+mod evm_cost;
+mod fft;
+mod fingerprint;
+mod foreign_curve_add;
+mod foreign_curve_msm;
+mod foreign_curve_mul;
 mod foreign_field_add;
 mod foreign_field_mul;
 mod framework;
+mod gate_vectors;
 mod generic;
+mod hash_to_curve;
+mod incremental;
+mod keccak;
+mod layout;
 mod lookup;
+mod memory;
+mod metrics;
+mod mock_prover;
+mod mux;
 mod not;
+mod o1js;
+mod optimizer;
+mod packing;
+mod pedersen;
 mod poseidon;
+mod public_commitment;
+mod public_output;
 mod range_check;
 mod recursion;
+mod redact;
+mod reproducibility;
 mod rot;
+mod scheduler;
+mod schnorr;
+mod segments;
+mod select;
 mod serde;
+mod session;
+mod set_membership;
+mod shift;
+mod solver;
+mod stats;
+mod sub_circuit;
+mod template;
 mod turshi;
+mod validation;
 mod varbasemul;
+mod version;
+mod vrf;
+mod wiring;
+mod x25519;
 mod xor;
+mod zk;
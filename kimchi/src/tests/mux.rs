@@ -0,0 +1,180 @@
+use std::array;
+
+use super::{ecdsa::verify_rows, framework::TestFramework};
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::{CircuitGate, CircuitGateError, Connect, GateType},
+    lookup::tables::LookupTable,
+    polynomial::COLUMNS,
+    polynomials::{
+        generic::GenericGateSpec,
+        mux::{self, mux_index_table, MuxLayout},
+    },
+    wires::Wire,
+};
+use ark_ff::{One, Zero};
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+const INDEX_TABLE_ID: i32 = 5;
+
+// The values of the multiplexers of the tests
+fn values(num_values: usize) -> Vec<Fp> {
+    (0..num_values as u64).map(|i| Fp::from(100 + i)).collect()
+}
+
+// The table of the indices, only needed if the number of values is not a power of two
+fn index_tables(num_values: usize) -> Vec<LookupTable<Fp>> {
+    if num_values.is_power_of_two() {
+        vec![]
+    } else {
+        vec![mux_index_table(INDEX_TABLE_ID, num_values)]
+    }
+}
+
+// Creates a circuit selecting one of the public inputs `values` by the last public input,
+// and its witness selecting the value `index`
+fn create_mux(
+    index: usize,
+    values: &[Fp],
+) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS], Vec<Fp>, MuxLayout) {
+    let num_values = values.len();
+    let mut gates: Vec<_> = (0..=num_values)
+        .map(|row| {
+            CircuitGate::create_generic_gadget(Wire::for_row(row), GenericGateSpec::Pub, None)
+        })
+        .collect();
+    let layout = MuxLayout::new(gates.len(), num_values);
+    let next_row = CircuitGate::extend_mux(&mut gates, num_values, INDEX_TABLE_ID);
+    assert_eq!(next_row, layout.next_row);
+    for (row, cell) in layout.values.iter().enumerate() {
+        gates.connect_cell_pair((row, 0), *cell);
+    }
+    gates.connect_cell_pair((num_values, 0), layout.index);
+
+    let mut public = values.to_vec();
+    public.push(Fp::from(index as u64));
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|col| {
+        if col == 0 {
+            public.clone()
+        } else {
+            vec![Fp::zero(); public.len()]
+        }
+    });
+    let out = mux::extend_mux_witness(&mut witness, INDEX_TABLE_ID, index, values);
+    assert_eq!(out, values[index]);
+    let (row, col) = layout.output;
+    assert_eq!(witness[col][row], out);
+    assert_eq!(witness[0].len(), gates.len());
+    (gates, witness, public, layout)
+}
+
+fn constraint_system(gates: Vec<CircuitGate<Fp>>, public: usize) -> ConstraintSystem<Fp> {
+    let num_values = public - 1;
+    ConstraintSystem::create(gates)
+        .public(public)
+        .lookup(index_tables(num_values))
+        .build()
+        .unwrap()
+}
+
+fn prove_and_verify(
+    gates: Vec<CircuitGate<Fp>>,
+    witness: [Vec<Fp>; COLUMNS],
+    public: Vec<Fp>,
+) -> Result<(), String> {
+    let num_values = public.len() - 1;
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(public)
+        .lookup_tables(index_tables(num_values))
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+}
+
+#[test]
+// Test the number of rows of the multiplexers, with and without the lookup of the index
+fn test_mux_layout() {
+    for (num_values, bits, rows) in [(2, 1, 1), (3, 2, 5), (4, 2, 3), (5, 3, 7), (8, 3, 6)] {
+        let layout = MuxLayout::new(10, num_values);
+        assert_eq!(layout.bits(), bits);
+        assert_eq!(layout.next_row, 10 + rows);
+        let (next_row, gates) = CircuitGate::<Fp>::create_mux(10, num_values, INDEX_TABLE_ID);
+        assert_eq!(next_row, layout.next_row);
+        assert_eq!(gates.len(), rows);
+        let lookups = gates.iter().filter(|gate| gate.typ == GateType::Lookup);
+        assert_eq!(lookups.count(), usize::from(!num_values.is_power_of_two()));
+    }
+}
+
+#[test]
+// Test that every value of the multiplexers is selected by its index
+fn test_mux() {
+    for num_values in [2, 3, 4, 5, 8, 11] {
+        let values = values(num_values);
+        for index in 0..num_values {
+            let (gates, witness, public, _) = create_mux(index, &values);
+            let cs = constraint_system(gates, public.len());
+            assert_eq!(verify_rows(&cs, &witness), Ok(()));
+        }
+        let (gates, witness, public, _) = create_mux(num_values - 1, &values);
+        prove_and_verify(gates, witness, public).unwrap();
+    }
+}
+
+#[test]
+// Test that a value other than the one of the index is not selected
+fn test_mux_wrong_output() {
+    let values = values(5);
+    let (gates, mut witness, public, layout) = create_mux(2, &values);
+    let cs = constraint_system(gates.clone(), public.len());
+    let (row, col) = layout.output;
+    witness[col][row] = values[3];
+    assert_eq!(
+        verify_rows(&cs, &witness),
+        Err(CircuitGateError::Constraint(GateType::Select, 2))
+    );
+    assert!(prove_and_verify(gates, witness, public).is_err());
+}
+
+#[test]
+// Test that an index whose bits are valid, but which is not below the number of values,
+// is rejected by the lookup of the index
+fn test_mux_out_of_range_index() {
+    let values = values(5);
+    let (gates, mut witness, mut public, layout) = create_mux(4, &values);
+    // the 5 public values and the index, followed by the 3 levels of the tree on rows 6 to 8,
+    // the recomposition of the index on rows 9 and 10, and its lookup on rows 11 and 12
+    assert_eq!(layout.index, (10, 2));
+    assert_eq!(layout.next_row, 13);
+
+    // the index 5 sets the bit of the first level, which selects the odd values
+    public[5] = Fp::from(5u64);
+    witness[0][5] = public[5];
+    witness[0][6] = Fp::one();
+    witness[3][6] = witness[1][6];
+    witness[6][6] = witness[4][6];
+    // the second level selects the first of its values, from the first level
+    witness[2][7] = witness[3][6];
+    witness[1][7] = witness[6][6];
+    witness[3][7] = witness[2][7];
+    // the third level selects the last value, which does not change
+    witness[2][8] = witness[3][7];
+    // the recomposition and the lookup of the index
+    witness[1][10] = Fp::one();
+    witness[2][10] = public[5];
+    witness[1][12] = public[5];
+
+    // only the lookup of the index is not satisfied
+    let cs = constraint_system(gates.clone(), public.len());
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+    assert!(prove_and_verify(gates, witness, public).is_err());
+}
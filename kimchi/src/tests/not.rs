@@ -9,32 +9,17 @@ use crate::{
         wires::Wire,
     },
     curve::KimchiCurve,
-    plonk_sponge::FrSponge,
     prover_index::testing::new_index_for_test_with_lookups,
     tests::xor::{all_ones, check_xor},
 };
 
-use super::framework::TestFramework;
-use ark_ec::AffineCurve;
+use super::framework::{TestCurve, TestFramework};
 use ark_ff::{Field, One, PrimeField, Zero};
-use mina_curves::pasta::{Fp, Fq, Pallas, PallasParameters, Vesta, VestaParameters};
-use mina_poseidon::{
-    constants::PlonkSpongeConstantsKimchi,
-    sponge::{DefaultFqSponge, DefaultFrSponge},
-    FqSponge,
-};
+use mina_curves::pasta::{Pallas, Vesta};
 use num_bigint::BigUint;
 use o1_utils::{BigUintHelpers, BitwiseOps, FieldHelpers, RandomField};
 use rand::{rngs::StdRng, SeedableRng};
 
-type PallasField = <Pallas as AffineCurve>::BaseField;
-type VestaField = <Vesta as AffineCurve>::BaseField;
-type SpongeParams = PlonkSpongeConstantsKimchi;
-type VestaBaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
-type VestaScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
-type PallasBaseSponge = DefaultFqSponge<PallasParameters, SpongeParams>;
-type PallasScalarSponge = DefaultFrSponge<Fq, SpongeParams>;
-
 const NOT: bool = false;
 
 const RNG_SEED: [u8; 32] = [
@@ -76,7 +61,7 @@ fn create_not_witness_checked_length<F: PrimeField>(
 }
 
 // Constraint system for Not gadget using Xor16
-fn create_test_constraint_system_not_xor<G: KimchiCurve, EFqSponge, EFrSponge>(
+fn create_test_constraint_system_not_xor<G: TestCurve>(
     bits: usize,
 ) -> ConstraintSystem<G::ScalarField>
 where
@@ -97,7 +82,7 @@ where
 }
 
 // Constraint system for Not gadget using generic gates
-fn create_test_constraint_system_not_gnrc<G: KimchiCurve, EFqSponge, EFrSponge>(
+fn create_test_constraint_system_not_gnrc<G: TestCurve>(
     num_nots: usize,
 ) -> ConstraintSystem<G::ScalarField>
 where
@@ -115,7 +100,7 @@ where
 }
 
 // Creates the witness and circuit for NOT gadget using XOR
-fn setup_not_xor<G: KimchiCurve, EFqSponge, EFrSponge>(
+fn setup_not_xor<G: TestCurve>(
     input: Option<G::ScalarField>,
     bits: Option<usize>,
 ) -> (
@@ -124,8 +109,6 @@ fn setup_not_xor<G: KimchiCurve, EFqSponge, EFrSponge>(
 )
 where
     G::BaseField: PrimeField,
-    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
-    EFrSponge: FrSponge<G::ScalarField>,
 {
     let rng = &mut StdRng::from_seed(RNG_SEED);
 
@@ -135,7 +118,7 @@ where
     // Otherwise, use the length of the input
     let bits_real = max(input.to_biguint().bitlen(), bits.unwrap_or(0));
 
-    let cs = create_test_constraint_system_not_xor::<G, EFqSponge, EFrSponge>(bits_real);
+    let cs = create_test_constraint_system_not_xor::<G>(bits_real);
 
     let witness = create_not_witness_checked_length::<G::ScalarField>(input, bits);
 
@@ -145,16 +128,14 @@ where
 }
 
 // Tester for not gate
-fn test_not_xor<G: KimchiCurve, EFqSponge, EFrSponge>(
+fn test_not_xor<G: TestCurve>(
     input: Option<G::ScalarField>,
     bits: Option<usize>,
 ) -> [Vec<G::ScalarField>; COLUMNS]
 where
     G::BaseField: PrimeField,
-    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
-    EFrSponge: FrSponge<G::ScalarField>,
 {
-    let (witness, cs) = setup_not_xor::<G, EFqSponge, EFrSponge>(input, bits);
+    let (witness, cs) = setup_not_xor::<G>(input, bits);
 
     for row in 0..witness[0].len() {
         assert_eq!(
@@ -167,7 +148,7 @@ where
 }
 
 // Creates the witness and circuit for NOT gadget using generic
-fn setup_not_gnrc<G: KimchiCurve, EFqSponge, EFrSponge>(
+fn setup_not_gnrc<G: TestCurve>(
     inputs: Option<Vec<G::ScalarField>>,
     bits: usize,
     len: Option<usize>,
@@ -177,8 +158,6 @@ fn setup_not_gnrc<G: KimchiCurve, EFqSponge, EFrSponge>(
 )
 where
     G::BaseField: PrimeField,
-    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
-    EFrSponge: FrSponge<G::ScalarField>,
 {
     let rng = &mut StdRng::from_seed(RNG_SEED);
 
@@ -193,7 +172,7 @@ where
             .collect::<Vec<G::ScalarField>>()
     };
 
-    let cs = create_test_constraint_system_not_gnrc::<G, EFqSponge, EFrSponge>(inputs.len());
+    let cs = create_test_constraint_system_not_gnrc::<G>(inputs.len());
 
     let witness = create_not_witness_unchecked_length::<G::ScalarField>(&inputs, bits);
 
@@ -203,17 +182,15 @@ where
 }
 
 // Tester for not gate generic
-fn test_not_gnrc<G: KimchiCurve, EFqSponge, EFrSponge>(
+fn test_not_gnrc<G: TestCurve>(
     inputs: Option<Vec<G::ScalarField>>,
     bits: usize,
     len: Option<usize>,
 ) -> [Vec<G::ScalarField>; COLUMNS]
 where
     G::BaseField: PrimeField,
-    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
-    EFrSponge: FrSponge<G::ScalarField>,
 {
-    let (witness, cs) = setup_not_gnrc::<G, EFqSponge, EFrSponge>(inputs, bits, len);
+    let (witness, cs) = setup_not_gnrc::<G>(inputs, bits, len);
 
     // test public input and not generic gate
     for row in 0..witness[0].len() {
@@ -263,200 +240,267 @@ fn check_not_gnrc<G: KimchiCurve>(
     }
 }
 
-#[test]
-// End-to-end test of NOT using XOR gadget
-fn test_prove_and_verify_not_xor() {
+// End-to-end test of NOT using XOR gadget on the curve G
+fn prove_and_verify_not_xor<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
     let bits = 64;
     let rng = &mut StdRng::from_seed(RNG_SEED);
 
     // Create circuit
     let gates = {
-        let mut gates = vec![CircuitGate::<Fp>::create_generic_gadget(
+        let mut gates = vec![CircuitGate::<G::ScalarField>::create_generic_gadget(
             Wire::for_row(0),
             GenericGateSpec::Pub,
             None,
         )];
-        let _next_row = CircuitGate::<Fp>::extend_not_gadget_checked_length(&mut gates, 0, bits);
+        let _next_row =
+            CircuitGate::<G::ScalarField>::extend_not_gadget_checked_length(&mut gates, 0, bits);
         gates
     };
 
     // Create witness and random inputs
 
-    let witness =
-        create_not_witness_checked_length::<PallasField>(rng.gen_field_with_bits(bits), Some(bits));
+    let witness = create_not_witness_checked_length::<G::ScalarField>(
+        rng.gen_field_with_bits(bits),
+        Some(bits),
+    );
 
-    TestFramework::<Vesta>::default()
+    TestFramework::<G>::default()
         .gates(gates)
         .witness(witness)
         .public_inputs(vec![
-            PallasField::from(2u32).pow([bits as u64]) - PallasField::one(),
+            G::ScalarField::from(2u32).pow([bits as u64]) - G::ScalarField::one(),
         ])
         .setup()
-        .prove_and_verify::<VestaBaseSponge, VestaScalarSponge>()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>()
         .unwrap();
 }
 
 #[test]
-// End-to-end test of NOT using generic gadget
-fn test_prove_and_verify_five_not_gnrc() {
+// End-to-end test of NOT using XOR gadget over both curves
+fn test_prove_and_verify_not_xor() {
+    prove_and_verify_not_xor::<Vesta>();
+    prove_and_verify_not_xor::<Pallas>();
+}
+
+// End-to-end test of NOT using generic gadget on the curve G
+fn prove_and_verify_five_not_gnrc<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
     let bits = 64;
     let rng = &mut StdRng::from_seed(RNG_SEED);
 
     // Create circuit
     let gates = {
-        let mut gates = vec![CircuitGate::<Fp>::create_generic_gadget(
+        let mut gates = vec![CircuitGate::<G::ScalarField>::create_generic_gadget(
             Wire::for_row(0),
             GenericGateSpec::Pub,
             None,
         )];
-        let _next_row = CircuitGate::<Fp>::extend_not_gadget_unchecked_length(&mut gates, 5, 0);
+        let _next_row =
+            CircuitGate::<G::ScalarField>::extend_not_gadget_unchecked_length(&mut gates, 5, 0);
         gates
     };
 
     // Create witness and random inputs
-    let witness: [Vec<PallasField>; 15] = create_not_witness_unchecked_length::<PallasField>(
+    let witness: [Vec<G::ScalarField>; 15] = create_not_witness_unchecked_length::<G::ScalarField>(
         &(0..5)
             .map(|_| rng.gen_field_with_bits(bits))
-            .collect::<Vec<PallasField>>(),
+            .collect::<Vec<G::ScalarField>>(),
         bits,
     );
 
-    TestFramework::<Vesta>::default()
+    TestFramework::<G>::default()
         .gates(gates)
         .witness(witness)
         .public_inputs(vec![
-            PallasField::from(2u32).pow([bits as u64]) - PallasField::one(),
+            G::ScalarField::from(2u32).pow([bits as u64]) - G::ScalarField::one(),
         ])
         .setup()
-        .prove_and_verify::<VestaBaseSponge, VestaScalarSponge>()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>()
         .unwrap();
 }
 
 #[test]
+// End-to-end test of NOT using generic gadget over both curves
+fn test_prove_and_verify_five_not_gnrc() {
+    prove_and_verify_five_not_gnrc::<Vesta>();
+    prove_and_verify_five_not_gnrc::<Pallas>();
+}
+
 // Tests all possible 16 values for a crumb, for both full 4, 8, 12, and 16 bits, and smallest
-fn test_not_xor_all_crumb() {
+fn not_xor_all_crumb<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
     for i in 0..2u8.pow(4) {
-        let input = PallasField::from(i);
-        test_not_xor::<Vesta, VestaBaseSponge, VestaScalarSponge>(Some(input), None);
+        let input = G::ScalarField::from(i);
+        test_not_xor::<G>(Some(input), None);
         for c in (4..=16).step_by(4) {
             let bits = Some(c);
-            test_not_xor::<Vesta, VestaBaseSponge, VestaScalarSponge>(Some(input), bits);
+            test_not_xor::<G>(Some(input), bits);
         }
     }
 }
 
 #[test]
+// Tests all possible 16 values for a crumb over both curves
+fn test_not_xor_all_crumb() {
+    not_xor_all_crumb::<Vesta>();
+    not_xor_all_crumb::<Pallas>();
+}
+
 // Tests NOT for bitlengths of 4, 8, 16, 32, 64, 128, for both exact output width and varying
-fn test_not_xor_crumbs_random() {
+fn not_xor_crumbs_random<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
     for i in 2..=7 {
         let bits = 2u32.pow(i) as usize;
         let rng = &mut StdRng::from_seed(RNG_SEED);
         let input = rng.gen_field_with_bits(bits);
-        test_not_xor::<Vesta, VestaBaseSponge, VestaScalarSponge>(Some(input), Some(bits));
-        test_not_xor::<Vesta, VestaBaseSponge, VestaScalarSponge>(Some(input), None);
+        test_not_xor::<G>(Some(input), Some(bits));
+        test_not_xor::<G>(Some(input), None);
     }
 }
 
+#[test]
+// Tests NOT for bitlengths of 4 to 128 over both curves
+fn test_not_xor_crumbs_random() {
+    not_xor_crumbs_random::<Vesta>();
+    not_xor_crumbs_random::<Pallas>();
+}
+
 #[test]
 // Tests a NOT for a random-length big input
 fn test_not_xor_big_random() {
     let rng = &mut StdRng::from_seed(RNG_SEED);
     let input = rng.gen_field_with_bits(200);
-    test_not_xor::<Vesta, VestaBaseSponge, VestaScalarSponge>(Some(input), None);
+    test_not_xor::<Vesta>(Some(input), None);
     let input = rng.gen_field_with_bits(200);
-    test_not_xor::<Pallas, PallasBaseSponge, PallasScalarSponge>(Some(input), None);
+    test_not_xor::<Pallas>(Some(input), None);
 }
 
 #[test]
 // Tests two NOTs with the generic builder
 fn test_not_gnrc_double() {
-    test_not_gnrc::<Vesta, VestaBaseSponge, VestaScalarSponge>(None, 64, Some(2));
-    test_not_gnrc::<Pallas, PallasBaseSponge, PallasScalarSponge>(None, 64, Some(2));
+    test_not_gnrc::<Vesta>(None, 64, Some(2));
+    test_not_gnrc::<Pallas>(None, 64, Some(2));
 }
 
 #[test]
 // Tests one NOT with the generic builder
 fn test_not_gnrc_single() {
-    test_not_gnrc::<Vesta, VestaBaseSponge, VestaScalarSponge>(None, 64, Some(1));
-    test_not_gnrc::<Pallas, PallasBaseSponge, PallasScalarSponge>(None, 64, Some(1));
+    test_not_gnrc::<Vesta>(None, 64, Some(1));
+    test_not_gnrc::<Pallas>(None, 64, Some(1));
 }
 
-#[test]
 // Tests a chain of 5 NOTs with different lengths but padded to 254 bits with the generic builder
-fn test_not_gnrc_vector() {
+fn not_gnrc_vector<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
     let rng = &mut StdRng::from_seed(RNG_SEED);
     // up to 2^16, 2^32, 2^64, 2^128, 2^254
     let inputs = (0..5)
         .map(|i| rng.gen_field_with_bits(4 + i))
-        .collect::<Vec<PallasField>>();
-    test_not_gnrc::<Vesta, VestaBaseSponge, VestaScalarSponge>(Some(inputs), 254, None);
-    let inputs = (0..5)
-        .map(|i| rng.gen_field_with_bits(4 + i))
-        .collect::<Vec<VestaField>>();
-    test_not_gnrc::<Pallas, PallasBaseSponge, PallasScalarSponge>(Some(inputs), 254, None);
+        .collect::<Vec<G::ScalarField>>();
+    test_not_gnrc::<G>(Some(inputs), 254, None);
 }
 
 #[test]
+// Tests a chain of 5 NOTs padded to 254 bits over both curves
+fn test_not_gnrc_vector() {
+    not_gnrc_vector::<Vesta>();
+    not_gnrc_vector::<Pallas>();
+}
+
 // Test a bad NOT with gnrc builder
-fn test_bad_not_gnrc() {
-    let (mut witness, cs) =
-        setup_not_gnrc::<Vesta, VestaBaseSponge, VestaScalarSponge>(None, 64, Some(1));
+fn bad_not_gnrc<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let (mut witness, cs) = setup_not_gnrc::<G>(None, 64, Some(1));
     // modify public input row to make sure the copy constraint fails and the generic gate also fails
-    witness[0][0] += PallasField::one();
+    witness[0][0] += G::ScalarField::one();
     assert_eq!(
-        cs.gates[0].verify_witness::<Vesta>(0, &witness, &cs, &witness[0][0..cs.public]),
+        cs.gates[0].verify_witness::<G>(0, &witness, &cs, &witness[0][0..cs.public]),
         Err(CircuitGateError::CopyConstraint {
             typ: GateType::Generic,
             src: Wire { row: 0, col: 0 },
             dst: Wire { row: 1, col: 0 }
         })
     );
-    witness[0][1] += PallasField::one();
-    let index =
-        new_index_for_test_with_lookups(cs.gates, 1, 0, vec![xor::lookup_table()], None, false);
+    witness[0][1] += G::ScalarField::one();
+    let index = new_index_for_test_with_lookups::<G>(
+        cs.gates,
+        1,
+        0,
+        vec![xor::lookup_table()],
+        None,
+        false,
+    );
     assert_eq!(
-        index.cs.gates[1].verify::<Vesta>(1, &witness, &index, &[]),
+        index.cs.gates[1].verify::<G>(1, &witness, &index, &[]),
         Err(("generic: incorrect gate").to_string())
     );
 }
 
 #[test]
+// Test a bad NOT with gnrc builder over both curves
+fn test_bad_not_gnrc() {
+    bad_not_gnrc::<Vesta>();
+    bad_not_gnrc::<Pallas>();
+}
+
 // Test a bad NOT with XOR builder
-fn test_bad_not_xor() {
-    let (mut witness, cs) =
-        setup_not_xor::<Vesta, VestaBaseSponge, VestaScalarSponge>(None, Some(16));
+fn bad_not_xor<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let (mut witness, cs) = setup_not_xor::<G>(None, Some(16));
     // modify public input row to make sure the copy constraint fails and the XOR gate also fails
-    witness[0][0] += PallasField::one();
+    witness[0][0] += G::ScalarField::one();
     assert_eq!(
-        cs.gates[0].verify_witness::<Vesta>(0, &witness, &cs, &witness[0][0..cs.public]),
+        cs.gates[0].verify_witness::<G>(0, &witness, &cs, &witness[0][0..cs.public]),
         Err(CircuitGateError::CopyConstraint {
             typ: GateType::Generic,
             src: Wire { row: 0, col: 0 },
             dst: Wire { row: 1, col: 1 }
         })
     );
-    witness[1][1] += PallasField::one();
+    witness[1][1] += G::ScalarField::one();
     // decomposition of xor fails
     assert_eq!(
-        cs.gates[1].verify_witness::<Vesta>(1, &witness, &cs, &witness[0][0..cs.public]),
+        cs.gates[1].verify_witness::<G>(1, &witness, &cs, &witness[0][0..cs.public]),
         Err(CircuitGateError::Constraint(GateType::Xor16, 2))
     );
     // Make the second input zero with correct decomposition to make sure XOR table fails
-    witness[0][0] = PallasField::zero();
-    witness[1][1] = PallasField::zero();
-    witness[7][1] = PallasField::zero();
-    witness[8][1] = PallasField::zero();
-    witness[9][1] = PallasField::zero();
-    witness[10][1] = PallasField::zero();
+    witness[0][0] = G::ScalarField::zero();
+    witness[1][1] = G::ScalarField::zero();
+    witness[7][1] = G::ScalarField::zero();
+    witness[8][1] = G::ScalarField::zero();
+    witness[9][1] = G::ScalarField::zero();
+    witness[10][1] = G::ScalarField::zero();
 
     assert_eq!(
-        TestFramework::<Vesta>::default()
+        TestFramework::<G>::default()
             .gates(cs.gates)
             .witness(witness)
             .setup()
-            .prove_and_verify::<VestaBaseSponge, VestaScalarSponge>(),
+            .prove_and_verify::<G::BaseSponge, G::ScalarSponge>(),
         Err(String::from(
             "the lookup failed to find a match in the table"
         ))
     );
 }
+
+#[test]
+// Test a bad NOT with XOR builder over both curves
+fn test_bad_not_xor() {
+    bad_not_xor::<Vesta>();
+    bad_not_xor::<Pallas>();
+}
@@ -0,0 +1,155 @@
+use super::framework::TestFramework;
+use crate::{
+    circuits::{
+        compiled::CompiledCircuit,
+        gate::{Circuit, CircuitGate, GateType},
+        lookup::tables::LookupTable,
+        polynomials::generic::testing::{create_circuit, fill_in_witness},
+        wires::{Wire, COLUMNS},
+    },
+    error::O1jsFormatError,
+    snarky::o1js::{O1jsCircuit, O1jsWitness},
+};
+use ark_ff::{One, Zero};
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use o1_utils::FieldHelpers;
+use serde_json::{json, Value};
+use std::array;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+// The generic circuit of the tests with its public input, and its witness
+fn circuit_and_witness() -> (CompiledCircuit<Fp>, [Vec<Fp>; COLUMNS], Vec<Fp>) {
+    let public = vec![Fp::from(3u8), -Fp::one()];
+    let mut circuit = CompiledCircuit::new(create_circuit::<Fp>(0, public.len()));
+    circuit.public = public.len();
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); circuit.gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+    (circuit, witness, public)
+}
+
+#[test]
+// Test that an exported circuit and its witness have the JSON layout of o1js
+fn test_o1js_export() {
+    let (circuit, witness, public) = circuit_and_witness();
+    let exported = O1jsCircuit::from_compiled(&circuit).unwrap();
+    let json = serde_json::to_value(&exported).unwrap();
+
+    // the circuit is serialized as by the bindings of o1js
+    let gates = Circuit::new(circuit.public, &circuit.gates);
+    assert_eq!(json, serde_json::to_value(gates).unwrap());
+    assert_eq!(json["public_input_size"], json!(2));
+    let gate = &json["gates"][2];
+    assert_eq!(gate["typ"], json!("Generic"));
+    assert_eq!(gate["wires"][0], json!({"row": 2, "col": 0}));
+    assert_eq!(gate["wires"].as_array().unwrap().len(), 7);
+    let coeffs = &circuit.gates[2].coeffs;
+    assert_eq!(
+        gate["coeffs"],
+        json!(coeffs.iter().map(Fp::to_hex).collect::<Vec<_>>())
+    );
+
+    // the field elements of the witness are decimal strings
+    let json = serde_json::to_value(O1jsWitness::new(&witness, &public)).unwrap();
+    let minus_one = (-Fp::one()).to_biguint().to_string();
+    assert_eq!(json["public_input"], json!(["3", minus_one]));
+    assert_eq!(json["columns"].as_array().unwrap().len(), COLUMNS);
+    assert_eq!(json["columns"][0][1], json!(minus_one));
+    assert_eq!(json["columns"][1][2], json!("23"));
+}
+
+#[test]
+// Test that a circuit and a witness imported from o1js are proven
+fn test_o1js_import() {
+    let (circuit, witness, public) = circuit_and_witness();
+    let circuit_json =
+        serde_json::to_string(&O1jsCircuit::from_compiled(&circuit).unwrap()).unwrap();
+    let witness_json = serde_json::to_string(&O1jsWitness::new(&witness, &public)).unwrap();
+
+    let imported: O1jsCircuit<Fp> = serde_json::from_str(&circuit_json).unwrap();
+    let compiled = imported.compiled();
+    assert_eq!(compiled.public, public.len());
+    assert_eq!(compiled.to_bytes(), circuit.to_bytes());
+    let imported: O1jsWitness<Fp> = serde_json::from_str(&witness_json).unwrap();
+    let (imported_witness, imported_public) = imported.witness().unwrap();
+    assert_eq!(imported_witness, witness);
+    assert_eq!(imported_public, public);
+
+    TestFramework::<Vesta>::default()
+        .gates(compiled.gates)
+        .witness(imported_witness)
+        .public_inputs(imported_public)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+// Test that the gates and the configurations unknown by o1js are not exported
+fn test_o1js_unsupported_circuits() {
+    let (mut circuit, _, _) = circuit_and_witness();
+    let row = circuit.gates.len();
+    circuit.gates.push(CircuitGate::new(
+        GateType::Select,
+        Wire::for_row(row),
+        vec![],
+    ));
+    assert_eq!(
+        O1jsCircuit::from_compiled(&circuit).unwrap_err(),
+        O1jsFormatError::UnsupportedGate(row, GateType::Select)
+    );
+
+    let (mut circuit, _, _) = circuit_and_witness();
+    circuit.lookup_tables = vec![LookupTable {
+        id: 10,
+        data: vec![vec![Fp::zero()]],
+    }];
+    assert_eq!(
+        O1jsCircuit::from_compiled(&circuit).unwrap_err(),
+        O1jsFormatError::UnsupportedConfiguration("lookup tables")
+    );
+}
+
+#[test]
+// Test that the witnesses of the wrong shape, or with invalid field elements, are rejected
+fn test_o1js_invalid_witness() {
+    let (_, witness, public) = circuit_and_witness();
+    let import = |json: Value| serde_json::from_value::<O1jsWitness<Fp>>(json);
+    let exported = serde_json::to_value(O1jsWitness::new(&witness, &public)).unwrap();
+
+    let mut columns = exported.clone();
+    columns["columns"].as_array_mut().unwrap().pop();
+    assert_eq!(
+        import(columns).unwrap().witness().unwrap_err(),
+        O1jsFormatError::WitnessColumns(COLUMNS - 1)
+    );
+    let mut rows = exported.clone();
+    rows["columns"][4].as_array_mut().unwrap().pop();
+    assert_eq!(
+        import(rows).unwrap().witness().unwrap_err(),
+        O1jsFormatError::ColumnLength(4, witness[4].len() - 1, witness[0].len())
+    );
+    let mut wrong_public = exported.clone();
+    wrong_public["public_input"][1] = json!("4");
+    assert_eq!(
+        import(wrong_public).unwrap().witness().unwrap_err(),
+        O1jsFormatError::PublicInputMismatch(1)
+    );
+
+    // the values must be canonical decimal strings
+    for value in [
+        json!(Fp::modulus_biguint().to_string()),
+        json!("0x10"),
+        json!(16),
+    ] {
+        let mut invalid = exported.clone();
+        invalid["columns"][1][2] = value;
+        assert!(import(invalid).is_err());
+    }
+}
@@ -0,0 +1,161 @@
+use super::{ecdsa::verify_rows, framework::TestFramework};
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::{CircuitGate, Connect, GateType},
+    optimizer::Optimization,
+    polynomial::COLUMNS,
+    polynomials::{generic::GenericGateSpec, xor},
+    wires::Wire,
+};
+use ark_ff::Zero;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use std::array;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+const PUBLIC: u64 = 5;
+
+fn single_add(row: usize, right_coeff: Option<Fp>) -> CircuitGate<Fp> {
+    CircuitGate::create_generic_gadget(
+        Wire::for_row(row),
+        GenericGateSpec::Add {
+            left_coeff: None,
+            right_coeff,
+            output_coeff: None,
+        },
+        None,
+    )
+}
+
+// Appends a row of values to the witness, the other cells of the row are zero
+fn push_row(witness: &mut [Vec<Fp>; COLUMNS], values: &[u64]) {
+    for (col, column) in witness.iter_mut().enumerate() {
+        column.push(values.get(col).map_or(Fp::zero(), |value| (*value).into()));
+    }
+}
+
+// A machine-generated circuit with a public input, additions, a dead zero gate,
+// a copy gate and a wired zero gate between the additions, and a 16-bit xor gadget
+fn redundant_circuit() -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS]) {
+    let mut gates = vec![
+        CircuitGate::create_generic_gadget(Wire::for_row(0), GenericGateSpec::Pub, None),
+        single_add(1, None),
+        CircuitGate::new(GateType::Zero, Wire::for_row(2), vec![]),
+        single_add(3, Some(Fp::zero())),
+        CircuitGate::new(GateType::Zero, Wire::for_row(4), vec![]),
+        single_add(5, None),
+    ];
+    CircuitGate::extend_xor_gadget(&mut gates, 16);
+    let row = gates.len();
+    gates.push(single_add(row, None));
+    gates.connect_cell_pair((0, 0), (1, 0));
+    gates.connect_cell_pair((1, 2), (3, 0));
+    gates.connect_cell_pair((3, 2), (4, 0));
+    gates.connect_cell_pair((4, 0), (5, 0));
+    gates.connect_cell_pair((5, 2), (row, 0));
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let rows: [&[u64]; 6] = [&[PUBLIC], &[5, 1, 6], &[], &[6, 0, 6], &[6], &[6, 2, 8]];
+    for values in rows {
+        push_row(&mut witness, values);
+    }
+    xor::extend_xor_witness(&mut witness, Fp::from(0xa5a5u64), Fp::from(0x0ff0u64), 16);
+    push_row(&mut witness, &[8, 3, 11]);
+    assert_eq!(witness[0].len(), gates.len());
+
+    (gates, witness)
+}
+
+fn prove_and_verify(
+    gates: Vec<CircuitGate<Fp>>,
+    witness: [Vec<Fp>; COLUMNS],
+) -> Result<(), String> {
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(vec![PUBLIC.into()])
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+}
+
+#[test]
+// Test that the optimized circuit has fewer rows, and proves the optimized witness of the original circuit
+fn test_optimizer_removes_gates() {
+    let (gates, witness) = redundant_circuit();
+    assert_eq!(gates.len(), 9);
+    prove_and_verify(gates.clone(), witness.clone()).unwrap();
+
+    // the zero and copy gates are removed, the additions are packed, and the xor gadget is kept
+    let (optimized, optimization) = Optimization::create(&gates, 1);
+    assert_eq!(optimization.num_rows(), 5);
+    assert_eq!(optimization.removed_rows(), 4);
+    assert_eq!(optimized.len(), optimization.num_rows());
+    assert!(optimized.iter().map(|gate| gate.typ).eq([
+        GateType::Generic,
+        GateType::Generic,
+        GateType::Xor16,
+        GateType::Zero,
+        GateType::Generic
+    ]));
+    assert_eq!(optimization.cell(Wire { row: 3, col: 0 }), None);
+    assert_eq!(optimization.cell(Wire { row: 4, col: 0 }), None);
+    assert_eq!(
+        optimization.cell(Wire { row: 5, col: 0 }),
+        Some(Wire { row: 1, col: 3 })
+    );
+
+    let optimized_witness = optimization.optimize_witness(&witness);
+    let cs = ConstraintSystem::create(optimized.clone())
+        .public(1)
+        .build()
+        .unwrap();
+    assert_eq!(verify_rows(&cs, &optimized_witness), Ok(()));
+    prove_and_verify(optimized.clone(), optimized_witness.clone()).unwrap();
+
+    // the copies through the removed gates still hold in the optimized circuit
+    let mut wrong_witness = optimized_witness;
+    for (col, row) in [(3, 1), (5, 1), (0, 4), (2, 4)] {
+        wrong_witness[col][row] += Fp::from(1u64);
+    }
+    assert!(verify_rows(&cs, &wrong_witness).is_err());
+
+    // the builder optimizes the circuit in the same way
+    let (builder, builder_optimization) = ConstraintSystem::create(gates).public(1).optimize();
+    assert_eq!(builder_optimization, optimization);
+    let cs = builder.build().unwrap();
+    assert!(cs.gates[..optimized.len()]
+        .iter()
+        .zip(&optimized)
+        .all(|(gate, optimized)| gate.typ == optimized.typ && gate.wires == optimized.wires));
+}
+
+#[test]
+// Test that the zero gates constrained by the gate before them, and the public inputs, are kept
+fn test_optimizer_keeps_constrained_gates() {
+    let gates = vec![
+        CircuitGate::new(GateType::Zero, Wire::for_row(0), vec![]),
+        CircuitGate::new(GateType::ForeignFieldAdd, Wire::for_row(1), vec![]),
+        CircuitGate::new(GateType::Zero, Wire::for_row(2), vec![]),
+        CircuitGate::new(GateType::Zero, Wire::for_row(3), vec![]),
+    ];
+
+    // the zero gate after the foreign field addition is its next row
+    let (optimized, optimization) = Optimization::create(&gates, 1);
+    assert_eq!(optimization.num_rows(), 3);
+    assert!(optimized.iter().map(|gate| gate.typ).eq([
+        GateType::Zero,
+        GateType::ForeignFieldAdd,
+        GateType::Zero
+    ]));
+    assert_eq!(
+        optimization.cell(Wire { row: 2, col: 0 }),
+        Some(Wire { row: 2, col: 0 })
+    );
+    assert_eq!(optimization.cell(Wire { row: 3, col: 0 }), None);
+}
@@ -0,0 +1,194 @@
+use super::framework::TestFramework;
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::{CircuitGate, Connect, GateType},
+    packing::Packing,
+    polynomial::COLUMNS,
+    polynomials::{generic::GenericGateSpec, xor},
+    wires::Wire,
+};
+use ark_ff::Zero;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use std::array;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+const PUBLIC: u64 = 5;
+
+fn single_add(row: usize) -> CircuitGate<Fp> {
+    CircuitGate::create_generic_gadget(
+        Wire::for_row(row),
+        GenericGateSpec::Add {
+            left_coeff: None,
+            right_coeff: None,
+            output_coeff: None,
+        },
+        None,
+    )
+}
+
+// A circuit with a public input and a chain of additions acc + i = acc' of single generic gates,
+// around a 16-bit xor gadget (the chain is wired across the gadget)
+fn addition_chain(
+    adds_before: usize,
+    adds_after: usize,
+) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS]) {
+    let mut gates = vec![CircuitGate::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )];
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero()]);
+    witness[0][0] = PUBLIC.into();
+
+    let mut acc = (0, 0);
+    let mut sum = PUBLIC;
+    let mut extend_adds =
+        |gates: &mut Vec<CircuitGate<Fp>>, witness: &mut [Vec<Fp>; COLUMNS], num: usize| {
+            for i in 0..num {
+                let row = gates.len();
+                gates.push(single_add(row));
+                gates.connect_cell_pair(acc, (row, 0));
+                acc = (row, 2);
+
+                let x = i as u64 + 1;
+                for (col, value) in witness.iter_mut().enumerate() {
+                    value.push(match col {
+                        0 => sum.into(),
+                        1 => x.into(),
+                        2 => (sum + x).into(),
+                        _ => Fp::zero(),
+                    });
+                }
+                sum += x;
+            }
+        };
+
+    extend_adds(&mut gates, &mut witness, adds_before);
+    CircuitGate::extend_xor_gadget(&mut gates, 16);
+    xor::extend_xor_witness(&mut witness, Fp::from(0xa5a5u64), Fp::from(0x0ff0u64), 16);
+    extend_adds(&mut gates, &mut witness, adds_after);
+    assert_eq!(witness[0].len(), gates.len());
+
+    (gates, witness)
+}
+
+fn prove_and_verify(
+    gates: Vec<CircuitGate<Fp>>,
+    witness: [Vec<Fp>; COLUMNS],
+) -> Result<(), String> {
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(vec![PUBLIC.into()])
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+}
+
+#[test]
+// Test that the packed circuit has fewer rows, and proves the packed witness of the original circuit
+fn test_packing_generic_gates() {
+    let (gates, witness) = addition_chain(10, 5);
+    assert_eq!(gates.len(), 18);
+    prove_and_verify(gates.clone(), witness.clone()).unwrap();
+
+    // the public input and the xor gadget are kept in their own rows
+    let (packed, packing) = Packing::create(&gates, 1);
+    assert_eq!(packing.num_rows(), 1 + 5 + 2 + 3);
+    assert_eq!(packed.len(), packing.num_rows());
+    assert_eq!(
+        packing.cell(Wire { row: 0, col: 0 }),
+        Some(Wire { row: 0, col: 0 })
+    );
+    assert_eq!(
+        packing.cell(Wire { row: 2, col: 1 }),
+        Some(Wire { row: 1, col: 4 })
+    );
+    assert_eq!(packing.cell(Wire { row: 2, col: 3 }), None);
+    assert_eq!(
+        packing.cell(Wire { row: 11, col: 0 }),
+        Some(Wire { row: 6, col: 0 })
+    );
+    assert!(packed[6..8]
+        .iter()
+        .map(|gate| gate.typ)
+        .eq([GateType::Xor16, GateType::Zero]));
+
+    let packed_witness = packing.pack_witness(&witness);
+    prove_and_verify(packed.clone(), packed_witness.clone()).unwrap();
+
+    // the copies of the chain still hold in the packed circuit
+    let mut wrong_witness = packed_witness;
+    wrong_witness[3][1] += Fp::from(1u64);
+    wrong_witness[5][1] += Fp::from(1u64);
+    assert!(prove_and_verify(packed, wrong_witness).is_err());
+
+    // the builder packs the circuit in the same way
+    let (builder, builder_packing) = ConstraintSystem::create(gates)
+        .public(1)
+        .pack_generic_gates();
+    assert_eq!(builder_packing, packing);
+    let cs = builder.build().unwrap();
+    assert!(cs.gates[..packed.len()]
+        .iter()
+        .zip(&packed)
+        .all(|(gate, packed)| gate.typ == packed.typ && gate.wires == packed.wires));
+}
+
+#[test]
+// Test that the gates constrained by the gate before them, and the public inputs, are not packed
+fn test_packing_constrained_gates() {
+    let gates = vec![
+        single_add(0),
+        CircuitGate::new(GateType::ForeignFieldAdd, Wire::for_row(1), vec![]),
+        single_add(2),
+        single_add(3),
+    ];
+
+    // the last gate is packed with the first one, the gate after the foreign field addition is kept
+    let (packed, packing) = Packing::create(&gates, 0);
+    assert_eq!(packing.num_rows(), 3);
+    assert_eq!(
+        packing.cell(Wire { row: 2, col: 0 }),
+        Some(Wire { row: 2, col: 0 })
+    );
+    assert_eq!(
+        packing.cell(Wire { row: 3, col: 0 }),
+        Some(Wire { row: 0, col: 3 })
+    );
+    assert_eq!(
+        packed[0].coeffs[5..8],
+        [Fp::from(1u64), Fp::from(1u64), -Fp::from(1u64)]
+    );
+
+    // the first gate is a public input, so the last one has no gate to be packed with
+    let (packed, packing) = Packing::create(&gates, 1);
+    assert_eq!(packing.num_rows(), 4);
+    assert!(packed
+        .iter()
+        .zip(&gates)
+        .all(|(packed, gate)| packed.typ == gate.typ
+            && packed.wires == gate.wires
+            && packed.coeffs == gate.coeffs));
+}
+
+#[test]
+// Test that the seeded packings have the rows of the default one, and prove the packed witness
+fn test_packing_with_seed() {
+    let (gates, witness) = addition_chain(10, 5);
+    let (_, default_packing) = Packing::create(&gates, 1);
+    for seed in [1, 7, u64::MAX] {
+        let (packed, packing) = Packing::create_with_seed(&gates, 1, seed);
+        assert_eq!(packing.num_rows(), default_packing.num_rows());
+        assert_eq!(Packing::create_with_seed(&gates, 1, seed).1, packing);
+
+        let packed_witness = packing.pack_witness(&witness);
+        prove_and_verify(packed, packed_witness).unwrap();
+    }
+}
@@ -0,0 +1,134 @@
+use std::array;
+
+use super::{ecdsa::verify_rows, framework::TestFramework};
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::CircuitGate,
+    polynomial::COLUMNS,
+    polynomials::{
+        ecdsa::curve::{ForeignCurve, ForeignPoint},
+        pedersen::{self, PedersenGenerators, PedersenInput},
+    },
+};
+use ark_ff::{One, UniformRand};
+use mina_curves::pasta::{Fp, Pallas, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use num_bigint::BigUint;
+use o1_utils::foreign_field::BigUintForeignFieldHelpers;
+use poly_commitment::srs::SRS;
+use rand::{rngs::StdRng, SeedableRng};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+// Creates the gadget opening a commitment over Pallas, and its witness
+fn create_pedersen(
+    generators: &PedersenGenerators<Pallas>,
+    values: &[Fp],
+    blinding: Fp,
+    commitment: &Pallas,
+) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS], bool) {
+    let (next_row, gates) = CircuitGate::create_pedersen(0, generators);
+    assert_eq!(next_row, gates.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let valid =
+        pedersen::extend_pedersen_witness(&mut witness, generators, values, blinding, commitment);
+    assert_eq!(witness[0].len(), next_row);
+    (gates, witness, valid)
+}
+
+#[test]
+// Test that the opening of a commitment with a zero value satisfies the gadget, and is proven
+fn test_pedersen_valid() {
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let generators = PedersenGenerators::from_srs(&SRS::<Pallas>::create(2), 2);
+    let values = [Fp::from(0x1234_5678u32), Fp::from(0u32)];
+    let blinding = Fp::rand(rng);
+    let commitment = generators.commit(&values, blinding);
+
+    let (gates, witness, valid) = create_pedersen(&generators, &values, blinding, &commitment);
+    assert!(valid);
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+    // the inputs are at their positions
+    let (row, col) = pedersen::pedersen_input(0, 2, PedersenInput::Value(0));
+    assert_eq!(witness[col][row], values[0]);
+    let (row, col) = pedersen::pedersen_input(0, 2, PedersenInput::Blinding);
+    assert_eq!(witness[col][row], blinding);
+    let (row, col) = pedersen::pedersen_input(0, 2, PedersenInput::CommitmentY);
+    assert_eq!(witness[col][row], commitment.y);
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+// Test that the opening of a commitment to other values does not satisfy the gadget
+fn test_pedersen_wrong_opening() {
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let generators = PedersenGenerators::from_srs(&SRS::<Pallas>::create(1), 1);
+    let blinding = Fp::rand(rng);
+    let commitment = generators.commit(&[Fp::one()], blinding);
+
+    let (gates, witness, valid) =
+        create_pedersen(&generators, &[Fp::from(2u32)], blinding, &commitment);
+    assert!(!valid);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+    assert!(verify_rows(&cs, &witness).is_err());
+}
+
+// The multiple of the generator of secp256k1
+fn multiple(curve: &ForeignCurve, scalar: u32) -> ForeignPoint {
+    curve
+        .scalar_mul(&curve.generator, &BigUint::from(scalar))
+        .unwrap()
+}
+
+#[test]
+// Test the opening of a commitment over secp256k1, and that another blinding factor does not satisfy the gadget
+fn test_foreign_pedersen() {
+    let curve = ForeignCurve::secp256k1();
+    let generators = [multiple(&curve, 3), multiple(&curve, 7)];
+    let value = BigUint::from(0x1234_5678u32);
+    let blinding = &curve.order - BigUint::from(5u32);
+    // 3 v G - 35 G
+    let commitment = multiple(&curve, 3 * 0x1234_5678 - 35);
+
+    let (next_row, gates) = CircuitGate::<Fp>::create_foreign_pedersen(0, &curve, &generators);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let valid = pedersen::extend_foreign_pedersen_witness(
+        &mut witness,
+        &curve,
+        &generators,
+        &[value.clone()],
+        &blinding,
+        &commitment,
+    );
+    assert!(valid);
+    assert_eq!(witness[0].len(), next_row);
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+    let (row, col) = pedersen::foreign_pedersen_limb(0, 1, PedersenInput::CommitmentX, 0);
+    assert_eq!(witness[col][row], commitment.0.to_field_limbs::<Fp>()[0]);
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let valid = pedersen::extend_foreign_pedersen_witness(
+        &mut witness,
+        &curve,
+        &generators,
+        &[value],
+        &BigUint::from(5u32),
+        &commitment,
+    );
+    assert!(!valid);
+    assert!(verify_rows(&cs, &witness).is_err());
+}
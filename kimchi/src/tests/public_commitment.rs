@@ -0,0 +1,194 @@
+use super::framework::TestFramework;
+use crate::{
+    circuits::{
+        polynomials::generic::testing::{create_circuit, fill_in_witness},
+        wires::COLUMNS,
+    },
+    error::VerifyError,
+    proof::ProverProof,
+    public_commitment::{
+        batch_verify_with_public_commitment, public_input_digest, CommittedContext,
+        PublicInputOpening, PublicInputTree,
+    },
+};
+use ark_ff::{One, Zero};
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use poly_commitment::commitment::CommitmentCurve;
+use std::array;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+// The witness of the generic gates with the public input `leaf`
+fn create_witness(gates_len: usize, leaf: Fp) -> [Vec<Fp>; COLUMNS] {
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates_len]);
+    fill_in_witness(0, &mut witness, &[leaf]);
+    witness
+}
+
+// The digests of the public values of `n` proofs
+fn digests(n: u64) -> Vec<Fp> {
+    (0..n)
+        .map(|i| {
+            let values: Vec<Fp> = (0..10).map(|j| Fp::from(i * 10 + j)).collect();
+            public_input_digest::<Vesta>(&values)
+        })
+        .collect()
+}
+
+#[test]
+// Test that the openings of a tree padded to a power of two match its root, but not with another leaf
+fn test_public_input_tree() {
+    let tree = PublicInputTree::<Vesta>::new(digests(5));
+    for index in 0..5 {
+        let opening = tree.opening(index);
+        assert_eq!(opening.path.len(), 3);
+        assert_eq!(opening.root::<Vesta>(), tree.root());
+        assert!(opening.check::<Vesta>(tree.root(), tree.num_leaves()));
+
+        let mut other = opening.clone();
+        other.leaf += Fp::one();
+        assert_ne!(other.root::<Vesta>(), tree.root());
+        let mut moved = opening;
+        moved.index ^= 1;
+        assert_ne!(moved.root::<Vesta>(), tree.root());
+    }
+
+    // a single proof is its own root
+    let single = PublicInputTree::<Vesta>::new(digests(1));
+    assert_eq!(single.root(), digests(1)[0]);
+}
+
+#[test]
+// Test the batch verification of proofs with a public input commitment
+fn test_batch_verify_with_public_commitment() {
+    let leaves = digests(3);
+    let gates = create_circuit(0, 1);
+    let witness = |leaf: Fp| create_witness(gates.len(), leaf);
+
+    let test_runner = TestFramework::<Vesta>::default()
+        .gates(gates.clone())
+        .witness(witness(leaves[0]))
+        .public_inputs(vec![leaves[0]])
+        .setup();
+    let index = test_runner.prover_index();
+    let verifier_index = index.verifier_index();
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proofs: Vec<_> = leaves
+        .iter()
+        .map(|leaf| {
+            ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness(*leaf), &[], index)
+                .unwrap()
+        })
+        .collect();
+
+    let tree = PublicInputTree::<Vesta>::new(leaves);
+    let openings: Vec<_> = (0..3).map(|i| tree.opening(i)).collect();
+    let verify = |openings: [&_; 3], root: Fp| {
+        let contexts: Vec<_> = proofs
+            .iter()
+            .zip(openings)
+            .map(|(proof, opening)| CommittedContext {
+                verifier_index: &verifier_index,
+                proof,
+                opening,
+            })
+            .collect();
+        batch_verify_with_public_commitment::<Vesta, BaseSponge, ScalarSponge>(
+            &group_map,
+            root,
+            tree.num_leaves(),
+            &contexts,
+        )
+    };
+    let [first, second, third] = [&openings[0], &openings[1], &openings[2]];
+    verify([first, second, third], tree.root()).unwrap();
+
+    // the openings are checked against the root
+    assert!(matches!(
+        verify([first, second, third], Fp::one()),
+        Err(VerifyError::PublicInputOpening(0))
+    ));
+    let mut tampered = second.clone();
+    tampered.leaf = third.leaf;
+    assert!(matches!(
+        verify([first, &tampered, third], tree.root()),
+        Err(VerifyError::PublicInputOpening(1))
+    ));
+
+    // and the proofs against their opened public input
+    let result = verify([first, third, third], tree.root());
+    assert!(result.is_err() && !matches!(result, Err(VerifyError::PublicInputOpening(_))));
+}
+
+// Verifies the proof of the public input of an opening against the root of a tree
+fn verify_opening(
+    tree: &PublicInputTree<Vesta>,
+    opening: &PublicInputOpening<Fp>,
+) -> Result<(), VerifyError> {
+    let gates = create_circuit(0, 1);
+    let witness = create_witness(gates.len(), opening.leaf);
+    let test_runner = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness.clone())
+        .public_inputs(vec![opening.leaf])
+        .setup();
+    let index = test_runner.prover_index();
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], index).unwrap();
+    let verifier_index = index.verifier_index();
+    batch_verify_with_public_commitment::<Vesta, BaseSponge, ScalarSponge>(
+        &group_map,
+        tree.root(),
+        tree.num_leaves(),
+        &[CommittedContext {
+            verifier_index: &verifier_index,
+            proof: &proof,
+            opening,
+        }],
+    )
+}
+
+#[test]
+// Test that a node of the tree, or a padding leaf, cannot be opened as the public input of a proof,
+// although their paths give the root
+fn test_public_commitment_node_opening() {
+    let tree = PublicInputTree::<Vesta>::new(digests(3));
+    verify_opening(&tree, &tree.opening(1)).unwrap();
+
+    // the first node above the leaves, with the rest of the path of its first leaf
+    let node = PublicInputOpening {
+        index: 0,
+        leaf: tree.layer(1)[0],
+        path: tree.opening(0).path[1..].to_vec(),
+    };
+    assert_eq!(node.root::<Vesta>(), tree.root());
+    assert!(!node.check::<Vesta>(tree.root(), tree.num_leaves()));
+    assert!(matches!(
+        verify_opening(&tree, &node),
+        Err(VerifyError::PublicInputOpening(0))
+    ));
+
+    // the leaf padding the 3 leaves to 4
+    let padding = PublicInputOpening {
+        index: 3,
+        leaf: Fp::zero(),
+        path: vec![tree.layer(0)[2], tree.layer(1)[0]],
+    };
+    assert_eq!(padding.root::<Vesta>(), tree.root());
+    assert!(matches!(
+        verify_opening(&tree, &padding),
+        Err(VerifyError::PublicInputOpening(0))
+    ));
+
+    // leaves and nodes are hashed with distinct prefixes
+    let children = [tree.layer(0)[0], tree.layer(0)[1]];
+    assert_ne!(public_input_digest::<Vesta>(&children), tree.layer(1)[0]);
+}
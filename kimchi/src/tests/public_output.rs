@@ -0,0 +1,132 @@
+use std::{array, sync::Arc};
+
+use crate::{
+    circuits::{
+        constraints::ConstraintSystem,
+        gate::{CircuitGate, Connect},
+        polynomial::COLUMNS,
+        polynomials::generic::GenericGateSpec,
+        wires::Wire,
+    },
+    error::VerifyError,
+    proof::ProverProof,
+    prover_index::ProverIndex,
+    verifier::verify_with_outputs,
+};
+use ark_ff::{One, Zero};
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Pallas, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use poly_commitment::{
+    commitment::CommitmentCurve,
+    srs::{endos, SRS},
+};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+// The cell of the output `x^2 + x` of the circuit
+pub(crate) const OUTPUT: Wire = Wire { row: 3, col: 2 };
+
+// Creates the circuit computing `x^2 + x` from the public input `x`, followed by the row of its public output
+pub(crate) fn create_circuit() -> Vec<CircuitGate<Fp>> {
+    let mut gates = vec![
+        CircuitGate::create_generic_gadget(Wire::for_row(0), GenericGateSpec::Pub, None),
+        CircuitGate::create_generic_gadget(Wire::for_row(1), GenericGateSpec::Pub, None),
+        CircuitGate::create_generic_gadget(
+            Wire::for_row(2),
+            GenericGateSpec::Mul {
+                output_coeff: None,
+                mul_coeff: None,
+            },
+            None,
+        ),
+        CircuitGate::create_generic_gadget(
+            Wire::for_row(3),
+            GenericGateSpec::Add {
+                left_coeff: None,
+                right_coeff: None,
+                output_coeff: None,
+            },
+            None,
+        ),
+    ];
+    gates.connect_cell_pair((0, 0), (2, 0));
+    gates.connect_cell_pair((2, 0), (2, 1));
+    gates.connect_cell_pair((2, 1), (3, 1));
+    gates.connect_cell_pair((2, 2), (3, 0));
+    gates
+}
+
+// Creates the witness of the circuit for `x`, without its public output
+pub(crate) fn create_witness(x: Fp) -> [Vec<Fp>; COLUMNS] {
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); 4]);
+    witness[0][0] = x;
+    witness[0][2] = x;
+    witness[1][2] = x;
+    witness[2][2] = x * x;
+    witness[0][3] = x * x;
+    witness[1][3] = x;
+    witness[2][3] = x * x + x;
+    witness
+}
+
+#[test]
+// Test that the output cell is given to the verifier after the public inputs, and checked
+fn test_public_output() {
+    let x = Fp::from(3u64);
+    let cs = ConstraintSystem::create(create_circuit())
+        .public(1)
+        .public_outputs(&[OUTPUT])
+        .build()
+        .unwrap();
+    assert_eq!((cs.public, cs.public_outputs), (2, 1));
+    let witness = create_witness(x);
+    assert_eq!(cs.public_output_values(&witness), vec![Fp::from(12u64)]);
+
+    let mut srs = SRS::<Vesta>::create(cs.domain.d1.size());
+    srs.add_lagrange_basis(cs.domain.d1);
+    let (endo_q, _endo_r) = endos::<Pallas>();
+    let index = ProverIndex::<Vesta>::create(cs, endo_q, Arc::new(srs));
+    let verifier_index = index.verifier_index();
+    assert_eq!(verifier_index.public_outputs, 1);
+
+    // the row of the output is filled from the output cell
+    let mut filled = witness.clone();
+    index.cs.fill_public_outputs(&mut filled);
+    assert_eq!(filled[0][1], Fp::from(12u64));
+    index.verify(&filled, &[x, Fp::from(12u64)]).unwrap();
+    assert!(index.verify(&witness, &[x, Fp::zero()]).is_err());
+
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], &index).unwrap();
+    let verify = |output: &[Fp]| {
+        verify_with_outputs::<Vesta, BaseSponge, ScalarSponge>(
+            &group_map,
+            &verifier_index,
+            &proof,
+            &[x],
+            output,
+        )
+    };
+    verify(&[Fp::from(12u64)]).unwrap();
+    assert!(verify(&[Fp::from(12u64) + Fp::one()]).is_err());
+    assert!(matches!(
+        verify(&[]),
+        Err(VerifyError::IncorrectPublicOutputLength(1))
+    ));
+}
+
+#[test]
+#[should_panic]
+// Test that the row of a public output must be a public gate
+fn test_public_output_not_public() {
+    ConstraintSystem::create(create_circuit())
+        .public(2)
+        .public_outputs(&[OUTPUT]);
+}
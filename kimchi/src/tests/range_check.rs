@@ -5,7 +5,12 @@ use crate::{
         polynomial::COLUMNS,
         polynomials::{
             generic::GenericGateSpec,
-            range_check::{self},
+            range_check::{
+                self,
+                bits::{range_check_bits_input, range_check_bits_rows},
+                bytes::{pack_bytes, range_check_bytes_rows},
+                gadget,
+            },
         },
         wires::Wire,
     },
@@ -13,10 +18,9 @@ use crate::{
     prover_index::testing::new_index_for_test_with_lookups,
 };
 
-use ark_ec::AffineCurve;
-use ark_ff::{Field, One, Zero};
+use ark_ff::{Field, One, PrimeField, Zero};
 use ark_poly::EvaluationDomain;
-use mina_curves::pasta::{Fp, Pallas, Vesta, VestaParameters};
+use mina_curves::pasta::{Fp, Pallas, Vesta};
 use num_bigint::{BigUint, RandBigInt};
 use o1_utils::{
     foreign_field::{
@@ -30,34 +34,25 @@ use rand::{rngs::StdRng, SeedableRng};
 use std::array;
 use std::sync::Arc;
 
-use crate::{prover_index::ProverIndex, verifier::verify};
+use crate::{curve::KimchiCurve, prover_index::ProverIndex, verifier::verify};
 use groupmap::GroupMap;
-use mina_poseidon::{
-    constants::PlonkSpongeConstantsKimchi,
-    sponge::{DefaultFqSponge, DefaultFrSponge},
-};
-use poly_commitment::{
-    commitment::CommitmentCurve,
-    srs::{endos, SRS},
-};
-
-use super::framework::TestFramework;
+use poly_commitment::{commitment::CommitmentCurve, srs::SRS};
 
-type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
-type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
-
-type PallasField = <Pallas as AffineCurve>::BaseField;
+use super::framework::{TestCurve, TestFramework};
 
 const RNG_SEED: [u8; 32] = [
     22, 4, 34, 75, 29, 255, 0, 126, 237, 19, 86, 160, 1, 90, 131, 221, 186, 168, 40, 59, 0, 4, 9,
     0, 33, 210, 215, 172, 130, 24, 164, 12,
 ];
 
-fn create_test_prover_index(public_size: usize, compact: bool) -> ProverIndex<Vesta> {
+fn create_test_prover_index<G: TestCurve>(public_size: usize, compact: bool) -> ProverIndex<G>
+where
+    G::BaseField: PrimeField,
+{
     let (_next_row, gates) = if compact {
-        CircuitGate::<Fp>::create_compact_multi_range_check(0)
+        CircuitGate::<G::ScalarField>::create_compact_multi_range_check(0)
     } else {
-        CircuitGate::<Fp>::create_multi_range_check(0)
+        CircuitGate::<G::ScalarField>::create_multi_range_check(0)
     };
 
     new_index_for_test_with_lookups(
@@ -70,14 +65,17 @@ fn create_test_prover_index(public_size: usize, compact: bool) -> ProverIndex<Ve
     )
 }
 
-#[test]
-fn verify_range_check0_zero_valid_witness() {
-    let index = create_test_prover_index(0, false);
-    let witness: [Vec<PallasField>; COLUMNS] = array::from_fn(|_| vec![PallasField::from(0); 4]);
+fn verify_range_check0_zero_valid_witness<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
+    let witness: [Vec<G::ScalarField>; COLUMNS] =
+        array::from_fn(|_| vec![G::ScalarField::from(0u64); 4]);
 
     // gates[0] is RangeCheck0
     assert_eq!(
-        index.cs.gates[0].verify_witness::<Vesta>(
+        index.cs.gates[0].verify_witness::<G>(
             0,
             &witness,
             &index.cs,
@@ -88,7 +86,7 @@ fn verify_range_check0_zero_valid_witness() {
 
     // gates[1] is RangeCheck0
     assert_eq!(
-        index.cs.gates[1].verify_witness::<Vesta>(
+        index.cs.gates[1].verify_witness::<G>(
             1,
             &witness,
             &index.cs,
@@ -99,13 +97,22 @@ fn verify_range_check0_zero_valid_witness() {
 }
 
 #[test]
-fn verify_range_check0_one_invalid_witness() {
-    let index = create_test_prover_index(0, false);
-    let witness: [Vec<PallasField>; COLUMNS] = array::from_fn(|_| vec![PallasField::from(1); 4]);
+fn test_verify_range_check0_zero_valid_witness() {
+    verify_range_check0_zero_valid_witness::<Vesta>();
+    verify_range_check0_zero_valid_witness::<Pallas>();
+}
+
+fn verify_range_check0_one_invalid_witness<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
+    let witness: [Vec<G::ScalarField>; COLUMNS] =
+        array::from_fn(|_| vec![G::ScalarField::from(1u64); 4]);
 
     // gates[0] is RangeCheck0
     assert_eq!(
-        index.cs.gates[0].verify_witness::<Vesta>(
+        index.cs.gates[0].verify_witness::<G>(
             0,
             &witness,
             &index.cs,
@@ -116,7 +123,7 @@ fn verify_range_check0_one_invalid_witness() {
 
     // gates[1] is RangeCheck0
     assert_eq!(
-        index.cs.gates[1].verify_witness::<Vesta>(
+        index.cs.gates[1].verify_witness::<G>(
             1,
             &witness,
             &index.cs,
@@ -127,21 +134,35 @@ fn verify_range_check0_one_invalid_witness() {
 }
 
 #[test]
-fn verify_range_check0_valid_witness() {
-    let index = create_test_prover_index(0, false);
+fn test_verify_range_check0_one_invalid_witness() {
+    verify_range_check0_one_invalid_witness::<Vesta>();
+    verify_range_check0_one_invalid_witness::<Pallas>();
+}
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::from_hex("115655443433221211ffef000000000000000000000000000000000000000000")
-            .unwrap(),
-        PallasField::from_hex("eeddcdccbbabaa99898877000000000000000000000000000000000000000000")
-            .unwrap(),
-        PallasField::from_hex("7766565544343322121100000000000000000000000000000000000000000000")
-            .unwrap(),
+fn verify_range_check0_valid_witness<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
+
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::from_hex(
+            "115655443433221211ffef000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
+        G::ScalarField::from_hex(
+            "eeddcdccbbabaa99898877000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
+        G::ScalarField::from_hex(
+            "7766565544343322121100000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
     );
 
     // gates[0] is RangeCheck0
     assert_eq!(
-        index.cs.gates[0].verify_witness::<Vesta>(
+        index.cs.gates[0].verify_witness::<G>(
             0,
             &witness,
             &index.cs,
@@ -152,7 +173,7 @@ fn verify_range_check0_valid_witness() {
 
     // gates[1] is RangeCheck0
     assert_eq!(
-        index.cs.gates[1].verify_witness::<Vesta>(
+        index.cs.gates[1].verify_witness::<G>(
             1,
             &witness,
             &index.cs,
@@ -161,18 +182,24 @@ fn verify_range_check0_valid_witness() {
         Ok(())
     );
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::from_hex("23d406ac800d1af73040dd000000000000000000000000000000000000000000")
-            .unwrap(),
-        PallasField::from_hex("a8fe8555371eb021469863000000000000000000000000000000000000000000")
-            .unwrap(),
-        PallasField::from_hex("3edff808d8f533be9af500000000000000000000000000000000000000000000")
-            .unwrap(),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::from_hex(
+            "23d406ac800d1af73040dd000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
+        G::ScalarField::from_hex(
+            "a8fe8555371eb021469863000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
+        G::ScalarField::from_hex(
+            "3edff808d8f533be9af500000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
     );
 
     // gates[0] is RangeCheck0
     assert_eq!(
-        index.cs.gates[0].verify_witness::<Vesta>(
+        index.cs.gates[0].verify_witness::<G>(
             0,
             &witness,
             &index.cs,
@@ -183,7 +210,7 @@ fn verify_range_check0_valid_witness() {
 
     // gates[1] is RangeCheck0
     assert_eq!(
-        index.cs.gates[1].verify_witness::<Vesta>(
+        index.cs.gates[1].verify_witness::<G>(
             1,
             &witness,
             &index.cs,
@@ -194,24 +221,38 @@ fn verify_range_check0_valid_witness() {
 }
 
 #[test]
-fn verify_range_check0_invalid_witness() {
-    let index = create_test_prover_index(0, false);
+fn test_verify_range_check0_valid_witness() {
+    verify_range_check0_valid_witness::<Vesta>();
+    verify_range_check0_valid_witness::<Pallas>();
+}
 
-    let mut witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::from_hex("22f6b4e7ecb4488433ade7000000000000000000000000000000000000000000")
-            .unwrap(),
-        PallasField::from_hex("e20e9d80333f2fba463ffd000000000000000000000000000000000000000000")
-            .unwrap(),
-        PallasField::from_hex("25d28bfd6cdff91ca9bc00000000000000000000000000000000000000000000")
-            .unwrap(),
+fn verify_range_check0_invalid_witness<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
+
+    let mut witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::from_hex(
+            "22f6b4e7ecb4488433ade7000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
+        G::ScalarField::from_hex(
+            "e20e9d80333f2fba463ffd000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
+        G::ScalarField::from_hex(
+            "25d28bfd6cdff91ca9bc00000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
     );
 
     // Invalidate witness copy constraint
-    witness[1][0] += PallasField::one();
+    witness[1][0] += G::ScalarField::one();
 
     // gates[0] is RangeCheck0
     assert_eq!(
-        index.cs.gates[0].verify_witness::<Vesta>(
+        index.cs.gates[0].verify_witness::<G>(
             0,
             &witness,
             &index.cs,
@@ -225,11 +266,11 @@ fn verify_range_check0_invalid_witness() {
     );
 
     // Invalidate witness copy constraint
-    witness[2][1] += PallasField::one();
+    witness[2][1] += G::ScalarField::one();
 
     // gates[1] is RangeCheck0
     assert_eq!(
-        index.cs.gates[1].verify_witness::<Vesta>(
+        index.cs.gates[1].verify_witness::<G>(
             1,
             &witness,
             &index.cs,
@@ -242,21 +283,27 @@ fn verify_range_check0_invalid_witness() {
         })
     );
 
-    let mut witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::from_hex("22cab5e27101eeafd2cbe1000000000000000000000000000000000000000000")
-            .unwrap(),
-        PallasField::from_hex("1ab61d31f4e27fe41a318c000000000000000000000000000000000000000000")
-            .unwrap(),
-        PallasField::from_hex("449a45cd749f1e091a3000000000000000000000000000000000000000000000")
-            .unwrap(),
+    let mut witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::from_hex(
+            "22cab5e27101eeafd2cbe1000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
+        G::ScalarField::from_hex(
+            "1ab61d31f4e27fe41a318c000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
+        G::ScalarField::from_hex(
+            "449a45cd749f1e091a3000000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
     );
 
     // Invalidate witness
-    witness[8][0] = witness[0][0] + PallasField::one();
+    witness[8][0] = witness[0][0] + G::ScalarField::one();
 
     // gates[0] is RangeCheck0
     assert_eq!(
-        index.cs.gates[0].verify_witness::<Vesta>(
+        index.cs.gates[0].verify_witness::<G>(
             0,
             &witness,
             &index.cs,
@@ -266,11 +313,11 @@ fn verify_range_check0_invalid_witness() {
     );
 
     // Invalidate witness
-    witness[9][1] = witness[0][1] + PallasField::one();
+    witness[9][1] = witness[0][1] + G::ScalarField::one();
 
     // gates[1] is RangeCheck0
     assert_eq!(
-        index.cs.gates[1].verify_witness::<Vesta>(
+        index.cs.gates[1].verify_witness::<G>(
             1,
             &witness,
             &index.cs,
@@ -281,18 +328,26 @@ fn verify_range_check0_invalid_witness() {
 }
 
 #[test]
-fn verify_range_check0_valid_v0_in_range() {
-    let index = create_test_prover_index(0, false);
+fn test_verify_range_check0_invalid_witness() {
+    verify_range_check0_invalid_witness::<Vesta>();
+    verify_range_check0_invalid_witness::<Pallas>();
+}
+
+fn verify_range_check0_valid_v0_in_range<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::from(2u64).pow([88]) - PallasField::one(),
-        PallasField::zero(),
-        PallasField::zero(),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::from(2u64).pow([88]) - G::ScalarField::one(),
+        G::ScalarField::zero(),
+        G::ScalarField::zero(),
     );
 
     // gates[0] is RangeCheck0 and contains v0
     assert_eq!(
-        index.cs.gates[0].verify_witness::<Vesta>(
+        index.cs.gates[0].verify_witness::<G>(
             0,
             &witness,
             &index.cs,
@@ -301,15 +356,15 @@ fn verify_range_check0_valid_v0_in_range() {
         Ok(())
     );
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::from(2u64).pow([64]),
-        PallasField::zero(),
-        PallasField::zero(),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::from(2u64).pow([64]),
+        G::ScalarField::zero(),
+        G::ScalarField::zero(),
     );
 
     // gates[0] is RangeCheck0 and contains v0
     assert_eq!(
-        index.cs.gates[0].verify_witness::<Vesta>(
+        index.cs.gates[0].verify_witness::<G>(
             0,
             &witness,
             &index.cs,
@@ -318,15 +373,15 @@ fn verify_range_check0_valid_v0_in_range() {
         Ok(())
     );
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::from(42u64),
-        PallasField::zero(),
-        PallasField::zero(),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::from(42u64),
+        G::ScalarField::zero(),
+        G::ScalarField::zero(),
     );
 
     // gates[0] is RangeCheck0 and contains v0
     assert_eq!(
-        index.cs.gates[0].verify_witness::<Vesta>(
+        index.cs.gates[0].verify_witness::<G>(
             0,
             &witness,
             &index.cs,
@@ -335,15 +390,15 @@ fn verify_range_check0_valid_v0_in_range() {
         Ok(())
     );
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::one(),
-        PallasField::zero(),
-        PallasField::zero(),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::one(),
+        G::ScalarField::zero(),
+        G::ScalarField::zero(),
     );
 
     // gates[0] is RangeCheck0 and contains v0
     assert_eq!(
-        index.cs.gates[0].verify_witness::<Vesta>(
+        index.cs.gates[0].verify_witness::<G>(
             0,
             &witness,
             &index.cs,
@@ -354,18 +409,26 @@ fn verify_range_check0_valid_v0_in_range() {
 }
 
 #[test]
-fn verify_range_check0_valid_v1_in_range() {
-    let index = create_test_prover_index(0, false);
+fn test_verify_range_check0_valid_v0_in_range() {
+    verify_range_check0_valid_v0_in_range::<Vesta>();
+    verify_range_check0_valid_v0_in_range::<Pallas>();
+}
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::zero(),
-        PallasField::from(2u64).pow([88]) - PallasField::one(),
-        PallasField::zero(),
+fn verify_range_check0_valid_v1_in_range<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
+
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::zero(),
+        G::ScalarField::from(2u64).pow([88]) - G::ScalarField::one(),
+        G::ScalarField::zero(),
     );
 
     // gates[1] is RangeCheck0 and contains v1
     assert_eq!(
-        index.cs.gates[1].verify_witness::<Vesta>(
+        index.cs.gates[1].verify_witness::<G>(
             1,
             &witness,
             &index.cs,
@@ -374,15 +437,15 @@ fn verify_range_check0_valid_v1_in_range() {
         Ok(())
     );
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::zero(),
-        PallasField::from(2u64).pow([63]),
-        PallasField::zero(),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::zero(),
+        G::ScalarField::from(2u64).pow([63]),
+        G::ScalarField::zero(),
     );
 
     // gates[1] is RangeCheck0 and contains v1
     assert_eq!(
-        index.cs.gates[1].verify_witness::<Vesta>(
+        index.cs.gates[1].verify_witness::<G>(
             1,
             &witness,
             &index.cs,
@@ -391,15 +454,15 @@ fn verify_range_check0_valid_v1_in_range() {
         Ok(())
     );
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::zero(),
-        PallasField::from(48u64),
-        PallasField::zero(),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::zero(),
+        G::ScalarField::from(48u64),
+        G::ScalarField::zero(),
     );
 
     // gates[1] is RangeCheck0 and contains v1
     assert_eq!(
-        index.cs.gates[1].verify_witness::<Vesta>(
+        index.cs.gates[1].verify_witness::<G>(
             1,
             &witness,
             &index.cs,
@@ -408,15 +471,15 @@ fn verify_range_check0_valid_v1_in_range() {
         Ok(())
     );
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::zero(),
-        PallasField::one() + PallasField::one(),
-        PallasField::zero(),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::zero(),
+        G::ScalarField::one() + G::ScalarField::one(),
+        G::ScalarField::zero(),
     );
 
     // gates[1] is RangeCheck0 and contains v1
     assert_eq!(
-        index.cs.gates[1].verify_witness::<Vesta>(
+        index.cs.gates[1].verify_witness::<G>(
             1,
             &witness,
             &index.cs,
@@ -427,18 +490,26 @@ fn verify_range_check0_valid_v1_in_range() {
 }
 
 #[test]
-fn verify_range_check0_invalid_v0_not_in_range() {
-    let index = create_test_prover_index(0, false);
+fn test_verify_range_check0_valid_v1_in_range() {
+    verify_range_check0_valid_v1_in_range::<Vesta>();
+    verify_range_check0_valid_v1_in_range::<Pallas>();
+}
+
+fn verify_range_check0_invalid_v0_not_in_range<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::from(2u64).pow([88]), // out of range
-        PallasField::zero(),
-        PallasField::zero(),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::from(2u64).pow([88]), // out of range
+        G::ScalarField::zero(),
+        G::ScalarField::zero(),
     );
 
     // gates[0] is RangeCheck0 and contains v0
     assert_eq!(
-        index.cs.gates[0].verify_witness::<Vesta>(
+        index.cs.gates[0].verify_witness::<G>(
             0,
             &witness,
             &index.cs,
@@ -447,15 +518,15 @@ fn verify_range_check0_invalid_v0_not_in_range() {
         Err(CircuitGateError::Constraint(GateType::RangeCheck0, 9))
     );
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::from(2u64).pow([96]), // out of range
-        PallasField::zero(),
-        PallasField::zero(),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::from(2u64).pow([96]), // out of range
+        G::ScalarField::zero(),
+        G::ScalarField::zero(),
     );
 
     // gates[0] is RangeCheck0 and contains v0
     assert_eq!(
-        index.cs.gates[0].verify_witness::<Vesta>(
+        index.cs.gates[0].verify_witness::<G>(
             0,
             &witness,
             &index.cs,
@@ -466,18 +537,26 @@ fn verify_range_check0_invalid_v0_not_in_range() {
 }
 
 #[test]
-fn verify_range_check0_invalid_v1_not_in_range() {
-    let index = create_test_prover_index(0, false);
+fn test_verify_range_check0_invalid_v0_not_in_range() {
+    verify_range_check0_invalid_v0_not_in_range::<Vesta>();
+    verify_range_check0_invalid_v0_not_in_range::<Pallas>();
+}
+
+fn verify_range_check0_invalid_v1_not_in_range<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::zero(),
-        PallasField::from(2u64).pow([88]), // out of range
-        PallasField::zero(),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::zero(),
+        G::ScalarField::from(2u64).pow([88]), // out of range
+        G::ScalarField::zero(),
     );
 
     // gates[1] is RangeCheck0 and contains v1
     assert_eq!(
-        index.cs.gates[1].verify_witness::<Vesta>(
+        index.cs.gates[1].verify_witness::<G>(
             1,
             &witness,
             &index.cs,
@@ -486,15 +565,15 @@ fn verify_range_check0_invalid_v1_not_in_range() {
         Err(CircuitGateError::Constraint(GateType::RangeCheck0, 9))
     );
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::zero(),
-        PallasField::from(2u64).pow([96]), // out of range
-        PallasField::zero(),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::zero(),
+        G::ScalarField::from(2u64).pow([96]), // out of range
+        G::ScalarField::zero(),
     );
 
     // gates[1] is RangeCheck0 and contains v1
     assert_eq!(
-        index.cs.gates[1].verify_witness::<Vesta>(
+        index.cs.gates[1].verify_witness::<G>(
             1,
             &witness,
             &index.cs,
@@ -505,21 +584,29 @@ fn verify_range_check0_invalid_v1_not_in_range() {
 }
 
 #[test]
-fn verify_range_check0_test_copy_constraints() {
-    let index = create_test_prover_index(0, false);
+fn test_verify_range_check0_invalid_v1_not_in_range() {
+    verify_range_check0_invalid_v1_not_in_range::<Vesta>();
+    verify_range_check0_invalid_v1_not_in_range::<Pallas>();
+}
+
+fn verify_range_check0_test_copy_constraints<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
 
     for row in 0..=1 {
         for col in 1..=2 {
             // Copy constraints impact v0 and v1
-            let mut witness = range_check::witness::create_multi::<PallasField>(
-                PallasField::from(2u64).pow([88]) - PallasField::one(), // in range
-                PallasField::from(2u64).pow([88]) - PallasField::one(), // in range
-                PallasField::zero(),
+            let mut witness = range_check::witness::create_multi::<G::ScalarField>(
+                G::ScalarField::from(2u64).pow([88]) - G::ScalarField::one(), // in range
+                G::ScalarField::from(2u64).pow([88]) - G::ScalarField::one(), // in range
+                G::ScalarField::zero(),
             );
 
             // Positive test case (gates[row] is a RangeCheck0 circuit gate)
             assert_eq!(
-                index.cs.gates[row].verify_witness::<Vesta>(
+                index.cs.gates[row].verify_witness::<G>(
                     row,
                     &witness,
                     &index.cs,
@@ -529,10 +616,10 @@ fn verify_range_check0_test_copy_constraints() {
             );
 
             // Negative test cases by breaking a copy constraint
-            assert_ne!(witness[col][row], PallasField::zero());
-            witness[col][row] = PallasField::zero();
+            assert_ne!(witness[col][row], G::ScalarField::zero());
+            witness[col][row] = G::ScalarField::zero();
             assert_eq!(
-                index.cs.gates[row].verify_witness::<Vesta>(
+                index.cs.gates[row].verify_witness::<G>(
                     row,
                     &witness,
                     &index.cs,
@@ -552,19 +639,27 @@ fn verify_range_check0_test_copy_constraints() {
 }
 
 #[test]
-fn verify_range_check0_v0_test_lookups() {
-    let index = create_test_prover_index(0, false);
+fn test_verify_range_check0_test_copy_constraints() {
+    verify_range_check0_test_copy_constraints::<Vesta>();
+    verify_range_check0_test_copy_constraints::<Pallas>();
+}
+
+fn verify_range_check0_v0_test_lookups<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::from(2u64).pow([88]) - PallasField::one(), // in range
-        PallasField::zero(),
-        PallasField::zero(),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::from(2u64).pow([88]) - G::ScalarField::one(), // in range
+        G::ScalarField::zero(),
+        G::ScalarField::zero(),
     );
 
     // Positive test
     // gates[0] is RangeCheck0 and constrains some of v0
     assert_eq!(
-        index.cs.gates[0].verify_witness::<Vesta>(
+        index.cs.gates[0].verify_witness::<G>(
             0,
             &witness,
             &index.cs,
@@ -573,9 +668,7 @@ fn verify_range_check0_v0_test_lookups() {
         Ok(())
     );
 
-    let test_runner = TestFramework::<Vesta>::default()
-        .gates(index.cs.gates)
-        .setup();
+    let test_runner = TestFramework::<G>::default().gates(index.cs.gates).setup();
 
     for i in 3..=6 {
         // Test ith lookup
@@ -584,11 +677,11 @@ fn verify_range_check0_v0_test_lookups() {
         // Negative test
         // Make ith plookup limb out of range while keeping the
         // rest of the witness consistent
-        witness[i][0] += PallasField::from(2u64.pow(12));
-        witness[i - 1][0] -= PallasField::one();
+        witness[i][0] += G::ScalarField::from(2u64.pow(12));
+        witness[i - 1][0] -= G::ScalarField::one();
         if i == 3 {
             // Make sure copy constraint doesn't fail
-            witness[4][3] -= PallasField::one();
+            witness[4][3] -= G::ScalarField::one();
         }
 
         // Perform test that will catch invalid plookup constraints
@@ -596,7 +689,7 @@ fn verify_range_check0_v0_test_lookups() {
             test_runner
                 .clone()
                 .witness(witness)
-                .prove_and_verify::<BaseSponge, ScalarSponge>(),
+                .prove_and_verify::<G::BaseSponge, G::ScalarSponge>(),
             Err(String::from(
                 "the lookup failed to find a match in the table"
             ))
@@ -605,19 +698,27 @@ fn verify_range_check0_v0_test_lookups() {
 }
 
 #[test]
-fn verify_range_check0_v1_test_lookups() {
-    let index = create_test_prover_index(0, false);
+fn test_verify_range_check0_v0_test_lookups() {
+    verify_range_check0_v0_test_lookups::<Vesta>();
+    verify_range_check0_v0_test_lookups::<Pallas>();
+}
+
+fn verify_range_check0_v1_test_lookups<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::zero(),
-        PallasField::from(2u64).pow([88]) - PallasField::one(), // in range
-        PallasField::zero(),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::zero(),
+        G::ScalarField::from(2u64).pow([88]) - G::ScalarField::one(), // in range
+        G::ScalarField::zero(),
     );
 
     // Positive test
     // gates[1] is RangeCheck0 and constrains some of v1
     assert_eq!(
-        index.cs.gates[1].verify_witness::<Vesta>(
+        index.cs.gates[1].verify_witness::<G>(
             1,
             &witness,
             &index.cs,
@@ -626,9 +727,7 @@ fn verify_range_check0_v1_test_lookups() {
         Ok(())
     );
 
-    let test_runner = TestFramework::<Vesta>::default()
-        .gates(index.cs.gates)
-        .setup();
+    let test_runner = TestFramework::<G>::default().gates(index.cs.gates).setup();
 
     for i in 3..=6 {
         // Test ith lookup
@@ -637,11 +736,11 @@ fn verify_range_check0_v1_test_lookups() {
         // Negative test
         // Make ith plookup limb out of range while keeping the
         // rest of the witness consistent
-        witness[i][1] += PallasField::from(2u64.pow(12));
-        witness[i - 1][1] -= PallasField::one();
+        witness[i][1] += G::ScalarField::from(2u64.pow(12));
+        witness[i - 1][1] -= G::ScalarField::one();
         if i == 3 {
             // Make sure copy constraint doesn't fail
-            witness[6][3] -= PallasField::one();
+            witness[6][3] -= G::ScalarField::one();
         }
 
         // Perform test that will catch invalid plookup constraints
@@ -649,7 +748,7 @@ fn verify_range_check0_v1_test_lookups() {
             test_runner
                 .clone()
                 .witness(witness)
-                .prove_and_verify::<BaseSponge, ScalarSponge>(),
+                .prove_and_verify::<G::BaseSponge, G::ScalarSponge>(),
             Err(String::from(
                 "the lookup failed to find a match in the table"
             ))
@@ -658,13 +757,22 @@ fn verify_range_check0_v1_test_lookups() {
 }
 
 #[test]
-fn verify_range_check1_zero_valid_witness() {
-    let index = create_test_prover_index(0, false);
-    let witness: [Vec<PallasField>; COLUMNS] = array::from_fn(|_| vec![PallasField::from(0); 4]);
+fn test_verify_range_check0_v1_test_lookups() {
+    verify_range_check0_v1_test_lookups::<Vesta>();
+    verify_range_check0_v1_test_lookups::<Pallas>();
+}
+
+fn verify_range_check1_zero_valid_witness<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
+    let witness: [Vec<G::ScalarField>; COLUMNS] =
+        array::from_fn(|_| vec![G::ScalarField::from(0u64); 4]);
 
     // gates[2] is RangeCheck1
     assert_eq!(
-        index.cs.gates[2].verify_witness::<Vesta>(
+        index.cs.gates[2].verify_witness::<G>(
             2,
             &witness,
             &index.cs,
@@ -675,13 +783,22 @@ fn verify_range_check1_zero_valid_witness() {
 }
 
 #[test]
-fn verify_range_check1_one_invalid_witness() {
-    let index = create_test_prover_index(0, false);
-    let witness: [Vec<PallasField>; COLUMNS] = array::from_fn(|_| vec![PallasField::from(1); 4]);
+fn test_verify_range_check1_zero_valid_witness() {
+    verify_range_check1_zero_valid_witness::<Vesta>();
+    verify_range_check1_zero_valid_witness::<Pallas>();
+}
+
+fn verify_range_check1_one_invalid_witness<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
+    let witness: [Vec<G::ScalarField>; COLUMNS] =
+        array::from_fn(|_| vec![G::ScalarField::from(1u64); 4]);
 
     // gates[2] is RangeCheck1
     assert_eq!(
-        index.cs.gates[2].verify_witness::<Vesta>(
+        index.cs.gates[2].verify_witness::<G>(
             2,
             &witness,
             &index.cs,
@@ -692,21 +809,35 @@ fn verify_range_check1_one_invalid_witness() {
 }
 
 #[test]
-fn verify_range_check1_valid_witness() {
-    let index = create_test_prover_index(0, false);
+fn test_verify_range_check1_one_invalid_witness() {
+    verify_range_check1_one_invalid_witness::<Vesta>();
+    verify_range_check1_one_invalid_witness::<Pallas>();
+}
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::from_hex("22cab5e27101eeafd2cbe1000000000000000000000000000000000000000000")
-            .unwrap(),
-        PallasField::from_hex("1ab61d31f4e27fe41a318c000000000000000000000000000000000000000000")
-            .unwrap(),
-        PallasField::from_hex("449a45cd749f1e091a3000000000000000000000000000000000000000000000")
-            .unwrap(),
+fn verify_range_check1_valid_witness<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
+
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::from_hex(
+            "22cab5e27101eeafd2cbe1000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
+        G::ScalarField::from_hex(
+            "1ab61d31f4e27fe41a318c000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
+        G::ScalarField::from_hex(
+            "449a45cd749f1e091a3000000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
     );
 
     // gates[2] is RangeCheck1
     assert_eq!(
-        index.cs.gates[2].verify_witness::<Vesta>(
+        index.cs.gates[2].verify_witness::<G>(
             2,
             &witness,
             &index.cs,
@@ -715,18 +846,24 @@ fn verify_range_check1_valid_witness() {
         Ok(())
     );
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::from_hex("0d96f6fc210316c73bcc4d000000000000000000000000000000000000000000")
-            .unwrap(),
-        PallasField::from_hex("59c8e7b0ffb3cab6ce8d48000000000000000000000000000000000000000000")
-            .unwrap(),
-        PallasField::from_hex("686c10e73930b92f375800000000000000000000000000000000000000000000")
-            .unwrap(),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::from_hex(
+            "0d96f6fc210316c73bcc4d000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
+        G::ScalarField::from_hex(
+            "59c8e7b0ffb3cab6ce8d48000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
+        G::ScalarField::from_hex(
+            "686c10e73930b92f375800000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
     );
 
     // gates[2] is RangeCheck1
     assert_eq!(
-        index.cs.gates[2].verify_witness::<Vesta>(
+        index.cs.gates[2].verify_witness::<G>(
             2,
             &witness,
             &index.cs,
@@ -737,16 +874,30 @@ fn verify_range_check1_valid_witness() {
 }
 
 #[test]
-fn verify_range_check1_invalid_witness() {
-    let index = create_test_prover_index(0, false);
+fn test_verify_range_check1_valid_witness() {
+    verify_range_check1_valid_witness::<Vesta>();
+    verify_range_check1_valid_witness::<Pallas>();
+}
 
-    let mut witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::from_hex("2ce2d3ac942f98d59e7e11000000000000000000000000000000000000000000")
-            .unwrap(),
-        PallasField::from_hex("52dd43524b95399f5d458d000000000000000000000000000000000000000000")
-            .unwrap(),
-        PallasField::from_hex("60ca087b427918fa0e2600000000000000000000000000000000000000000000")
-            .unwrap(),
+fn verify_range_check1_invalid_witness<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
+
+    let mut witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::from_hex(
+            "2ce2d3ac942f98d59e7e11000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
+        G::ScalarField::from_hex(
+            "52dd43524b95399f5d458d000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
+        G::ScalarField::from_hex(
+            "60ca087b427918fa0e2600000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
     );
 
     // Corrupt witness
@@ -754,7 +905,7 @@ fn verify_range_check1_invalid_witness() {
 
     // gates[2] is RangeCheck1
     assert_eq!(
-        index.cs.gates[2].verify_witness::<Vesta>(
+        index.cs.gates[2].verify_witness::<G>(
             2,
             &witness,
             &index.cs,
@@ -763,13 +914,19 @@ fn verify_range_check1_invalid_witness() {
         Err(CircuitGateError::Constraint(GateType::RangeCheck1, 21))
     );
 
-    let mut witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::from_hex("1bd50c94d2dc83d32f01c0000000000000000000000000000000000000000000")
-            .unwrap(),
-        PallasField::from_hex("e983d7cd9e28e440930f86000000000000000000000000000000000000000000")
-            .unwrap(),
-        PallasField::from_hex("ea226054772cd009d2af00000000000000000000000000000000000000000000")
-            .unwrap(),
+    let mut witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::from_hex(
+            "1bd50c94d2dc83d32f01c0000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
+        G::ScalarField::from_hex(
+            "e983d7cd9e28e440930f86000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
+        G::ScalarField::from_hex(
+            "ea226054772cd009d2af00000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
     );
 
     // Corrupt witness
@@ -777,7 +934,7 @@ fn verify_range_check1_invalid_witness() {
 
     // gates[2] is RangeCheck1
     assert_eq!(
-        index.cs.gates[2].verify_witness::<Vesta>(
+        index.cs.gates[2].verify_witness::<G>(
             2,
             &witness,
             &index.cs,
@@ -788,18 +945,26 @@ fn verify_range_check1_invalid_witness() {
 }
 
 #[test]
-fn verify_range_check1_valid_v2_in_range() {
-    let index = create_test_prover_index(0, false);
+fn test_verify_range_check1_invalid_witness() {
+    verify_range_check1_invalid_witness::<Vesta>();
+    verify_range_check1_invalid_witness::<Pallas>();
+}
+
+fn verify_range_check1_valid_v2_in_range<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::zero(),
-        PallasField::zero(),
-        PallasField::from(2u64).pow([88]) - PallasField::one(),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::zero(),
+        G::ScalarField::zero(),
+        G::ScalarField::from(2u64).pow([88]) - G::ScalarField::one(),
     );
 
     // gates[2] is RangeCheck1 and constrains v2
     assert_eq!(
-        index.cs.gates[2].verify_witness::<Vesta>(
+        index.cs.gates[2].verify_witness::<G>(
             2,
             &witness,
             &index.cs,
@@ -808,15 +973,15 @@ fn verify_range_check1_valid_v2_in_range() {
         Ok(())
     );
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::zero(),
-        PallasField::zero(),
-        PallasField::from(2u64).pow([64]),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::zero(),
+        G::ScalarField::zero(),
+        G::ScalarField::from(2u64).pow([64]),
     );
 
     // gates[2] is RangeCheck1 and constrains v2
     assert_eq!(
-        index.cs.gates[2].verify_witness::<Vesta>(
+        index.cs.gates[2].verify_witness::<G>(
             2,
             &witness,
             &index.cs,
@@ -825,15 +990,15 @@ fn verify_range_check1_valid_v2_in_range() {
         Ok(())
     );
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::zero(),
-        PallasField::zero(),
-        PallasField::from(42u64),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::zero(),
+        G::ScalarField::zero(),
+        G::ScalarField::from(42u64),
     );
 
     // gates[2] is RangeCheck1 and constrains v2
     assert_eq!(
-        index.cs.gates[2].verify_witness::<Vesta>(
+        index.cs.gates[2].verify_witness::<G>(
             2,
             &witness,
             &index.cs,
@@ -842,15 +1007,15 @@ fn verify_range_check1_valid_v2_in_range() {
         Ok(())
     );
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::zero(),
-        PallasField::zero(),
-        PallasField::one(),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::zero(),
+        G::ScalarField::zero(),
+        G::ScalarField::one(),
     );
 
     // gates[2] is RangeCheck1 and constrains v2
     assert_eq!(
-        index.cs.gates[2].verify_witness::<Vesta>(
+        index.cs.gates[2].verify_witness::<G>(
             2,
             &witness,
             &index.cs,
@@ -861,18 +1026,26 @@ fn verify_range_check1_valid_v2_in_range() {
 }
 
 #[test]
-fn verify_range_check1_invalid_v2_not_in_range() {
-    let index = create_test_prover_index(0, false);
+fn test_verify_range_check1_valid_v2_in_range() {
+    verify_range_check1_valid_v2_in_range::<Vesta>();
+    verify_range_check1_valid_v2_in_range::<Pallas>();
+}
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::zero(),
-        PallasField::zero(),
-        PallasField::from(2u64).pow([88]), // out of range
+fn verify_range_check1_invalid_v2_not_in_range<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
+
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::zero(),
+        G::ScalarField::zero(),
+        G::ScalarField::from(2u64).pow([88]), // out of range
     );
 
     // gates[2] is RangeCheck1 and constrains v2
     assert_eq!(
-        index.cs.gates[2].verify_witness::<Vesta>(
+        index.cs.gates[2].verify_witness::<G>(
             2,
             &witness,
             &index.cs,
@@ -881,15 +1054,15 @@ fn verify_range_check1_invalid_v2_not_in_range() {
         Err(CircuitGateError::Constraint(GateType::RangeCheck1, 21))
     );
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::zero(),
-        PallasField::zero(),
-        PallasField::from(2u64).pow([96]), // out of range
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::zero(),
+        G::ScalarField::zero(),
+        G::ScalarField::from(2u64).pow([96]), // out of range
     );
 
     // gates[2] is RangeCheck1 and constrains v2
     assert_eq!(
-        index.cs.gates[2].verify_witness::<Vesta>(
+        index.cs.gates[2].verify_witness::<G>(
             2,
             &witness,
             &index.cs,
@@ -900,21 +1073,29 @@ fn verify_range_check1_invalid_v2_not_in_range() {
 }
 
 #[test]
-fn verify_range_check1_test_copy_constraints() {
-    let index = create_test_prover_index(0, false);
+fn test_verify_range_check1_invalid_v2_not_in_range() {
+    verify_range_check1_invalid_v2_not_in_range::<Vesta>();
+    verify_range_check1_invalid_v2_not_in_range::<Pallas>();
+}
+
+fn verify_range_check1_test_copy_constraints<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
 
     for row in 0..=1 {
         for col in 1..=2 {
             // Copy constraints impact v0 and v1
-            let mut witness = range_check::witness::create_multi::<PallasField>(
-                PallasField::from(2u64).pow([88]) - PallasField::one(), // in range
-                PallasField::from(2u64).pow([88]) - PallasField::one(), // in range
-                PallasField::zero(),
+            let mut witness = range_check::witness::create_multi::<G::ScalarField>(
+                G::ScalarField::from(2u64).pow([88]) - G::ScalarField::one(), // in range
+                G::ScalarField::from(2u64).pow([88]) - G::ScalarField::one(), // in range
+                G::ScalarField::zero(),
             );
 
             // Positive test case (gates[2] is a RangeCheck1 circuit gate)
             assert_eq!(
-                index.cs.gates[2].verify_witness::<Vesta>(
+                index.cs.gates[2].verify_witness::<G>(
                     2,
                     &witness,
                     &index.cs,
@@ -924,12 +1105,12 @@ fn verify_range_check1_test_copy_constraints() {
             );
 
             // Negative test case by breaking a copy constraint
-            assert_ne!(witness[col][row], PallasField::zero());
-            witness[col][row] = PallasField::zero();
+            assert_ne!(witness[col][row], G::ScalarField::zero());
+            witness[col][row] = G::ScalarField::zero();
 
             // RangeCheck1's current row doesn't have any copy constraints
             assert_eq!(
-                index.cs.gates[2].verify_witness::<Vesta>(
+                index.cs.gates[2].verify_witness::<G>(
                     2,
                     &witness,
                     &index.cs,
@@ -940,7 +1121,7 @@ fn verify_range_check1_test_copy_constraints() {
 
             // RangeCheck1's next row has copy constraints, but it's a Zero gate
             assert_eq!(
-                index.cs.gates[3].verify_witness::<Vesta>(
+                index.cs.gates[3].verify_witness::<G>(
                     3,
                     &witness,
                     &index.cs,
@@ -960,18 +1141,26 @@ fn verify_range_check1_test_copy_constraints() {
 }
 
 #[test]
-fn verify_range_check1_test_curr_row_lookups() {
-    let index = create_test_prover_index(0, false);
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::zero(),
-        PallasField::zero(),
-        PallasField::from(2u64).pow([88]) - PallasField::one(), // in range
+fn test_verify_range_check1_test_copy_constraints() {
+    verify_range_check1_test_copy_constraints::<Vesta>();
+    verify_range_check1_test_copy_constraints::<Pallas>();
+}
+
+fn verify_range_check1_test_curr_row_lookups<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::zero(),
+        G::ScalarField::zero(),
+        G::ScalarField::from(2u64).pow([88]) - G::ScalarField::one(), // in range
     );
 
     // Positive test
     // gates[2] is RangeCheck1 and constrains v2
     assert_eq!(
-        index.cs.gates[2].verify_witness::<Vesta>(
+        index.cs.gates[2].verify_witness::<G>(
             2,
             &witness,
             &index.cs,
@@ -980,9 +1169,7 @@ fn verify_range_check1_test_curr_row_lookups() {
         Ok(())
     );
 
-    let test_runner = TestFramework::<Vesta>::default()
-        .gates(index.cs.gates)
-        .setup();
+    let test_runner = TestFramework::<G>::default().gates(index.cs.gates).setup();
 
     for i in 3..=6 {
         // Test ith lookup (impacts v2)
@@ -991,15 +1178,15 @@ fn verify_range_check1_test_curr_row_lookups() {
         // Negative test
         // Make ith plookup limb out of range while keeping the
         // rest of the witness consistent
-        witness[i][2] += PallasField::from(2u64.pow(12));
-        witness[i - 1][2] -= PallasField::one();
+        witness[i][2] += G::ScalarField::from(2u64.pow(12));
+        witness[i - 1][2] -= G::ScalarField::one();
 
         // Perform test that will catch invalid plookup constraints
         assert_eq!(
             test_runner
                 .clone()
                 .witness(witness.clone())
-                .prove_and_verify::<BaseSponge, ScalarSponge>(),
+                .prove_and_verify::<G::BaseSponge, G::ScalarSponge>(),
             Err(String::from(
                 "the lookup failed to find a match in the table"
             ))
@@ -1008,19 +1195,27 @@ fn verify_range_check1_test_curr_row_lookups() {
 }
 
 #[test]
-fn verify_range_check1_test_next_row_lookups() {
-    let index = create_test_prover_index(0, false);
+fn test_verify_range_check1_test_curr_row_lookups() {
+    verify_range_check1_test_curr_row_lookups::<Vesta>();
+    verify_range_check1_test_curr_row_lookups::<Pallas>();
+}
 
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::from(2u64).pow([88]) - PallasField::one(), // in range
-        PallasField::from(2u64).pow([88]) - PallasField::one(), // in range
-        PallasField::zero(),
+fn verify_range_check1_test_next_row_lookups<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let index = create_test_prover_index::<G>(0, false);
+
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::from(2u64).pow([88]) - G::ScalarField::one(), // in range
+        G::ScalarField::from(2u64).pow([88]) - G::ScalarField::one(), // in range
+        G::ScalarField::zero(),
     );
 
     // Positive test case (gates[2] is RangeCheck1 and constrains
     // both v0's and v1's lookups that are deferred to 4th row)
     assert_eq!(
-        index.cs.gates[2].verify_witness::<Vesta>(
+        index.cs.gates[2].verify_witness::<G>(
             2,
             &witness,
             &index.cs,
@@ -1029,9 +1224,7 @@ fn verify_range_check1_test_next_row_lookups() {
         Ok(())
     );
 
-    let test_runner = TestFramework::<Vesta>::default()
-        .gates(index.cs.gates)
-        .setup();
+    let test_runner = TestFramework::<G>::default().gates(index.cs.gates).setup();
 
     for row in 0..=1 {
         for col in 1..=2 {
@@ -1039,21 +1232,21 @@ fn verify_range_check1_test_next_row_lookups() {
 
             // Negative test by making plookup limb out of range
             // while also assuring the rest of the witness is still valid
-            witness[col][row] += PallasField::from(2u64.pow(12));
+            witness[col][row] += G::ScalarField::from(2u64.pow(12));
             if col > 1 {
-                witness[col - 1][row] -= PallasField::one();
-                witness[col - 1 + 2 * row + 2][3] -= PallasField::one();
+                witness[col - 1][row] -= G::ScalarField::one();
+                witness[col - 1 + 2 * row + 2][3] -= G::ScalarField::one();
             } else {
-                witness[col - 1][row] += PallasField::two_to_limb();
+                witness[col - 1][row] += G::ScalarField::two_to_limb();
             }
-            witness[col - 1 + 2 * row + 3][3] += PallasField::from(2u64.pow(12));
+            witness[col - 1 + 2 * row + 3][3] += G::ScalarField::from(2u64.pow(12));
 
             // Perform test that will catch invalid plookup constraints
             assert_eq!(
                 test_runner
                     .clone()
                     .witness(witness.clone())
-                    .prove_and_verify::<BaseSponge, ScalarSponge>(),
+                    .prove_and_verify::<G::BaseSponge, G::ScalarSponge>(),
                 Err(String::from(
                     "the lookup failed to find a match in the table"
                 ))
@@ -1063,44 +1256,54 @@ fn verify_range_check1_test_next_row_lookups() {
 }
 
 #[test]
-fn verify_64_bit_range_check() {
+fn test_verify_range_check1_test_next_row_lookups() {
+    verify_range_check1_test_next_row_lookups::<Vesta>();
+    verify_range_check1_test_next_row_lookups::<Pallas>();
+}
+
+fn verify_64_bit_range_check<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
     // Test circuit layout
     //    Row Gate        Cells       Description
     //      0 GenericPub  0 <-,-, ... Used to get a cell with zero
     //      1 RangeCheck0 v0  0 0 ... Wire cells 1 and 2 to 1st cell 0 of GenericPub
     let mut gates = vec![];
-    gates.push(CircuitGate::<Fp>::create_generic_gadget(
+    gates.push(CircuitGate::<G::ScalarField>::create_generic_gadget(
         Wire::for_row(0),
         GenericGateSpec::Pub,
         None,
     ));
-    gates.append(&mut CircuitGate::<Fp>::create_range_check(1).1);
+    gates.append(&mut CircuitGate::<G::ScalarField>::create_range_check(1).1);
     gates[1].wires[1] = Wire { row: 1, col: 2 };
     gates[1].wires[2] = Wire { row: 0, col: 0 };
     gates[0].wires[0] = Wire { row: 1, col: 1 };
 
     // Create constraint system
-    let cs =
-        ConstraintSystem::<Fp>::create(gates /*, mina_poseidon::pasta::fp_kimchi::params()*/)
-            .build()
-            .unwrap();
+    let cs = ConstraintSystem::<G::ScalarField>::create(
+        gates, /*, mina_poseidon::pasta::fp_kimchi::params()*/
+    )
+    .build()
+    .unwrap();
 
     let index = {
-        let mut srs = SRS::<Vesta>::create(cs.domain.d1.size());
+        let mut srs = SRS::<G>::create(cs.domain.d1.size());
         srs.add_lagrange_basis(cs.domain.d1);
         let srs = Arc::new(srs);
 
-        let (endo_q, _endo_r) = endos::<Pallas>();
-        ProverIndex::<Vesta>::create(cs, endo_q, srs)
+        let (endo_q, _endo_r) = *G::OtherCurve::endos();
+        ProverIndex::<G>::create(cs, endo_q, srs)
     };
 
     // Witness layout (positive test case)
     //   Row 0 1 2 3 ... 14  Gate
     //   0   0 0 0 0 ... 0   GenericPub
     //   1   0 0 X X ... X   RangeCheck0
-    let mut witness: [Vec<PallasField>; COLUMNS] = array::from_fn(|_| vec![PallasField::zero()]);
-    range_check::witness::create::<PallasField>(
-        PallasField::from(2u64).pow([64]) - PallasField::one(), // in range
+    let mut witness: [Vec<G::ScalarField>; COLUMNS] =
+        array::from_fn(|_| vec![G::ScalarField::zero()]);
+    range_check::witness::create::<G::ScalarField>(
+        G::ScalarField::from(2u64).pow([64]) - G::ScalarField::one(), // in range
     )
     .iter_mut()
     .enumerate()
@@ -1108,7 +1311,7 @@ fn verify_64_bit_range_check() {
 
     // Positive test case
     assert_eq!(
-        index.cs.gates[1].verify_witness::<Vesta>(
+        index.cs.gates[1].verify_witness::<G>(
             1,
             &witness,
             &index.cs,
@@ -1121,9 +1324,10 @@ fn verify_64_bit_range_check() {
     //   Row 0 1 2 3 ... 14  Gate
     //   0   0 0 0 0 ... 0   GenericPub
     //   1   0 X X X ... X   RangeCheck0
-    let mut witness: [Vec<PallasField>; COLUMNS] = array::from_fn(|_| vec![PallasField::zero()]);
-    range_check::witness::create::<PallasField>(
-        PallasField::from(2u64).pow([64]), // out of range
+    let mut witness: [Vec<G::ScalarField>; COLUMNS] =
+        array::from_fn(|_| vec![G::ScalarField::zero()]);
+    range_check::witness::create::<G::ScalarField>(
+        G::ScalarField::from(2u64).pow([64]), // out of range
     )
     .iter_mut()
     .enumerate()
@@ -1131,7 +1335,7 @@ fn verify_64_bit_range_check() {
 
     // Negative test case
     assert_eq!(
-        index.cs.gates[1].verify_witness::<Vesta>(
+        index.cs.gates[1].verify_witness::<G>(
             1,
             &witness,
             &index.cs,
@@ -1146,24 +1350,33 @@ fn verify_64_bit_range_check() {
 }
 
 #[test]
-fn compact_multi_range_check() {
+fn test_verify_64_bit_range_check() {
+    verify_64_bit_range_check::<Vesta>();
+    verify_64_bit_range_check::<Pallas>();
+}
+
+fn compact_multi_range_check<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
     let rng = &mut StdRng::from_seed(RNG_SEED);
 
     // Create prover index
-    let index = create_test_prover_index(0, true);
+    let index = create_test_prover_index::<G>(0, true);
 
     for _ in 0..3 {
         // Generate some random limbs in compact format
-        let limbs: [PallasField; 3] =
+        let limbs: [G::ScalarField; 3] =
             array::from_fn(|_| rng.gen_biguint_below(&BigUint::two_to_limb())).to_fields();
         let limbs = limbs.to_compact_limbs();
 
         // Create witness
-        let mut witness = range_check::witness::create_multi_compact_limbs::<PallasField>(&limbs);
+        let mut witness =
+            range_check::witness::create_multi_compact_limbs::<G::ScalarField>(&limbs);
 
         // Positive test
         assert_eq!(
-            index.cs.gates[1].verify_witness::<Vesta>(
+            index.cs.gates[1].verify_witness::<G>(
                 1,
                 &witness,
                 &index.cs,
@@ -1173,11 +1386,11 @@ fn compact_multi_range_check() {
         );
 
         // Invalidate witness
-        witness[1][2] = PallasField::one();
+        witness[1][2] = G::ScalarField::one();
 
         // Negative test
         assert_eq!(
-            index.cs.gates[1].verify_witness::<Vesta>(
+            index.cs.gates[1].verify_witness::<G>(
                 1,
                 &witness,
                 &index.cs,
@@ -1189,35 +1402,53 @@ fn compact_multi_range_check() {
 }
 
 #[test]
-fn verify_range_check_valid_proof1() {
+fn test_compact_multi_range_check() {
+    compact_multi_range_check::<Vesta>();
+    compact_multi_range_check::<Pallas>();
+}
+
+fn verify_range_check_valid_proof1<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
     // Create prover index
-    let prover_index = create_test_prover_index(0, false);
+    let prover_index = create_test_prover_index::<G>(0, false);
 
     // Create witness
-    let witness = range_check::witness::create_multi::<PallasField>(
-        PallasField::from_hex("2bc0afaa2f6f50b1d1424b000000000000000000000000000000000000000000")
-            .unwrap(),
-        PallasField::from_hex("8b30889f3a39e297ac851a000000000000000000000000000000000000000000")
-            .unwrap(),
-        PallasField::from_hex("c1c85ec47635e8edac5600000000000000000000000000000000000000000000")
-            .unwrap(),
+    let witness = range_check::witness::create_multi::<G::ScalarField>(
+        G::ScalarField::from_hex(
+            "2bc0afaa2f6f50b1d1424b000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
+        G::ScalarField::from_hex(
+            "8b30889f3a39e297ac851a000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
+        G::ScalarField::from_hex(
+            "c1c85ec47635e8edac5600000000000000000000000000000000000000000000",
+        )
+        .unwrap(),
     );
 
     // Verify computed witness satisfies the circuit
     prover_index.verify(&witness, &[]).unwrap();
 
     // Generate proof
-    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let group_map = <G as CommitmentCurve>::Map::setup();
     let public_input = witness[0][0..prover_index.cs.public].to_vec();
-    let proof =
-        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], &prover_index)
-            .expect("failed to generate proof");
+    let proof = ProverProof::create::<G::BaseSponge, G::ScalarSponge>(
+        &group_map,
+        witness,
+        &[],
+        &prover_index,
+    )
+    .expect("failed to generate proof");
 
     // Get the verifier index
     let verifier_index = prover_index.verifier_index();
 
     // Verify proof
-    let res = verify::<Vesta, BaseSponge, ScalarSponge>(
+    let res = verify::<G, G::BaseSponge, G::ScalarSponge>(
         &group_map,
         &verifier_index,
         &proof,
@@ -1228,22 +1459,423 @@ fn verify_range_check_valid_proof1() {
 }
 
 #[test]
-fn verify_compact_multi_range_check_proof() {
+fn test_verify_range_check_valid_proof1() {
+    verify_range_check_valid_proof1::<Vesta>();
+    verify_range_check_valid_proof1::<Pallas>();
+}
+
+fn verify_compact_multi_range_check_proof<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
     let rng = &mut StdRng::from_seed(RNG_SEED);
 
-    let limbs: [PallasField; 3] =
+    let limbs: [G::ScalarField; 3] =
         array::from_fn(|_| rng.gen_biguint_below(&BigUint::two_to_limb())).to_fields();
     let limbs = limbs.to_compact_limbs();
 
     // Create witness
-    let witness = range_check::witness::create_multi_compact_limbs::<PallasField>(&limbs);
+    let witness = range_check::witness::create_multi_compact_limbs::<G::ScalarField>(&limbs);
+
+    let (_next_row, gates) = CircuitGate::<G::ScalarField>::create_compact_multi_range_check(0);
+
+    TestFramework::<G>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+fn test_verify_compact_multi_range_check_proof() {
+    verify_compact_multi_range_check_proof::<Vesta>();
+    verify_compact_multi_range_check_proof::<Pallas>();
+}
+
+// Creates the gates and witness of a range check of `bits` length of `value`, preceded by a zero row
+fn create_range_check_bits<F: PrimeField>(
+    value: F,
+    bits: u32,
+    witness_bits: u32,
+) -> (Vec<CircuitGate<F>>, [Vec<F>; COLUMNS]) {
+    let mut gates = vec![CircuitGate::<F>::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )];
+    let mut witness: [Vec<F>; COLUMNS] = array::from_fn(|_| vec![F::zero()]);
+    CircuitGate::extend_range_check_bits(&mut gates, bits, 0);
+    range_check::bits::extend_range_check_bits_witness(&mut witness, value, witness_bits);
+    (gates, witness)
+}
+
+fn verify_range_check_bits<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+
+    for bits in [
+        1, 2, 3, 12, 63, 64, 65, 66, 88, 128, 130, 131, 150, 176, 192, 200, 240, 254, 255,
+    ] {
+        // the largest value, and a random one
+        let max = if bits < range_check::bits::MAX_BITS {
+            G::ScalarField::from_biguint(&((BigUint::one() << bits) - BigUint::one())).unwrap()
+        } else {
+            -G::ScalarField::one()
+        };
+        let random = G::ScalarField::from_biguint(&rng.gen_biguint(bits.min(253) as u64)).unwrap();
+
+        for value in [G::ScalarField::zero(), random, max] {
+            let (gates, witness) = create_range_check_bits(value, bits, bits);
+            assert_eq!(gates.len(), 1 + range_check_bits_rows(bits));
+            assert_eq!(witness[0].len(), gates.len());
+
+            let (row, col) = range_check_bits_input(1, bits);
+            assert_eq!(witness[col][row], value);
+
+            let cs = ConstraintSystem::create(gates).build().unwrap();
+            for row in 0..cs.gates.len() {
+                assert_eq!(
+                    cs.gates[row].verify_witness::<G>(
+                        row,
+                        &witness,
+                        &cs,
+                        &witness[0][0..cs.public]
+                    ),
+                    Ok(())
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_verify_range_check_bits() {
+    verify_range_check_bits::<Vesta>();
+    verify_range_check_bits::<Pallas>();
+}
+
+fn verify_range_check_bits_proof<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let mut gates = vec![CircuitGate::<G::ScalarField>::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )];
+    let mut witness: [Vec<G::ScalarField>; COLUMNS] =
+        array::from_fn(|_| vec![G::ScalarField::zero()]);
+    for (value, bits) in [
+        (1u128, 1),
+        (2, 2),
+        (1000, 10),
+        (u64::MAX as u128, 64),
+        (u128::MAX, 150),
+        (u128::MAX, 176),
+    ] {
+        CircuitGate::extend_range_check_bits(&mut gates, bits, 0);
+        range_check::bits::extend_range_check_bits_witness(
+            &mut witness,
+            G::ScalarField::from(value),
+            bits,
+        );
+    }
+
+    TestFramework::<G>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+fn test_verify_range_check_bits_proof() {
+    verify_range_check_bits_proof::<Vesta>();
+    verify_range_check_bits_proof::<Pallas>();
+}
+
+fn invalid_range_check_bits<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    // the witness of 2^100 for 101 bits has the layout of 100 bits, with a larger bound
+    let (gates, witness) = create_range_check_bits(G::ScalarField::two_pow(100), 100, 101);
 
-    let (_next_row, gates) = CircuitGate::<Fp>::create_compact_multi_range_check(0);
+    let res = TestFramework::<G>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>();
+    assert!(res.is_err());
+
+    // a 64-bit check of 2^64
+    let (gates, mut witness) = create_range_check_bits(G::ScalarField::zero(), 64, 64);
+    for col in witness.iter_mut() {
+        col.truncate(1);
+    }
+    range_check::witness::extend_single(&mut witness, G::ScalarField::two_pow(64));
+
+    let res = TestFramework::<G>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>();
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_invalid_range_check_bits() {
+    invalid_range_check_bits::<Vesta>();
+    invalid_range_check_bits::<Pallas>();
+}
+
+#[test]
+fn test_range_check_bits_rows() {
+    // boolean decompositions of the short values
+    assert_eq!(range_check_bits_rows(1), 1);
+    assert_eq!(range_check_bits_rows(2), 2);
+    // 64-bit limbs
+    assert_eq!(range_check_bits_rows(64), 1);
+    assert_eq!(range_check_bits_rows(100), 4);
+    assert_eq!(range_check_bits_rows(255), 7);
+    // 88-bit limbs, instead of 6 rows with 64-bit limbs
+    assert_eq!(range_check_bits_rows(150), 5);
+    assert_eq!(range_check_bits_rows(176), 5);
+}
+
+#[should_panic]
+#[test]
+fn range_check_bits_value_too_large() {
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero()]);
+    range_check::bits::extend_range_check_bits_witness(&mut witness, Fp::two_pow(70), 70);
+}
+
+#[should_panic]
+#[test]
+fn range_check_bits_too_many_bits() {
+    CircuitGate::<Fp>::create_range_check_bits(0, 256);
+}
+
+// Creates the gates and witness of byte range checks of `values` of the given lengths
+fn create_range_check_bytes<F: PrimeField>(
+    values: &[(F, usize)],
+) -> (Vec<CircuitGate<F>>, [Vec<F>; COLUMNS]) {
+    let mut gates = vec![];
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    for (value, num_bytes) in values {
+        CircuitGate::extend_range_check_bytes(&mut gates, *num_bytes);
+        range_check::bytes::extend_range_check_bytes_witness(&mut witness, *value, *num_bytes);
+    }
+    (gates, witness)
+}
+
+#[test]
+fn verify_range_check_bytes_layout() {
+    for (num_bytes, rows) in [
+        (1, 2),
+        (4, 2),
+        (5, 3),
+        (7, 3),
+        (8, 2),
+        (12, 3),
+        (16, 3),
+        (31, 6),
+    ] {
+        assert_eq!(range_check_bytes_rows(num_bytes), rows);
+        let (gates, witness) = create_range_check_bytes(&[(Fp::from(1u64), num_bytes)]);
+        assert_eq!(gates.len(), rows);
+        assert_eq!(witness[0].len(), rows);
+        assert_eq!(witness[0][0], Fp::from(1u64));
+        assert_eq!(witness[0][rows - 1], Fp::zero());
+    }
 
-    TestFramework::<Vesta>::default()
+    let (gates, _) = create_range_check_bytes(&[(Fp::zero(), 23)]);
+    assert!(gates.iter().map(|gate| gate.typ).eq([
+        GateType::RangeCheckU16,
+        GateType::RangeCheckU16,
+        GateType::RangeCheckU8,
+        GateType::RangeCheckU8,
+        GateType::Generic
+    ]));
+    // the unused value of the last byte row is wired to the zero
+    assert_eq!(gates[3].wires[3], Wire { row: 4, col: 0 });
+}
+
+#[test]
+fn verify_pack_bytes() {
+    let bytes: Vec<u8> = (1..=40).collect();
+    let packed = pack_bytes::<Fp>(&bytes);
+    assert_eq!(packed.len(), 2);
+    assert_eq!(
+        packed[0],
+        Fp::from_bytes(&[&bytes[..31], &[0]].concat()).unwrap()
+    );
+    assert_eq!(
+        packed[1],
+        Fp::from_bytes(&[&bytes[31..], &[0; 23]].concat()).unwrap()
+    );
+}
+
+fn verify_range_check_bytes_proof<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    // the byte rows only, without the 16-bit table
+    let values: Vec<_> = (1..=7)
+        .map(|num_bytes: usize| {
+            (
+                G::ScalarField::two_pow(8 * num_bytes as u64) - G::ScalarField::one(),
+                num_bytes,
+            )
+        })
+        .chain(pack_bytes(&[0xab; 5]).into_iter().map(|value| (value, 5)))
+        .collect();
+    let (gates, witness) = create_range_check_bytes(&values);
+
+    TestFramework::<G>::default()
         .gates(gates)
         .witness(witness)
         .setup()
-        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>()
         .unwrap();
 }
+
+#[test]
+fn test_verify_range_check_bytes_proof() {
+    verify_range_check_bytes_proof::<Vesta>();
+    verify_range_check_bytes_proof::<Pallas>();
+}
+
+fn verify_range_check_bytes_u16_proof<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    // a packed array of 40 bytes, with the 16-bit rows and a partial last byte row
+    let bytes: Vec<u8> = (0..40u8)
+        .map(|i| i.wrapping_mul(157).wrapping_add(11))
+        .collect();
+    let values: Vec<_> = pack_bytes(&bytes).into_iter().zip([31, 9]).collect();
+    let (gates, witness) = create_range_check_bytes(&values);
+    assert_eq!(gates.len(), 6 + 3);
+
+    TestFramework::<G>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+fn test_verify_range_check_bytes_u16_proof() {
+    verify_range_check_bytes_u16_proof::<Vesta>();
+    verify_range_check_bytes_u16_proof::<Pallas>();
+}
+
+fn invalid_range_check_bytes<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    // a 1-byte check of 256
+    let (gates, mut witness) = create_range_check_bytes(&[(G::ScalarField::zero(), 1)]);
+    witness[0][0] = G::ScalarField::from(256u64);
+    let res = TestFramework::<G>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>();
+    assert!(res.is_err());
+
+    // a 2-byte check of 2^16, with the second value of 2^15
+    let (gates, mut witness) = create_range_check_bytes(&[(G::ScalarField::from(1u64 << 15), 2)]);
+    witness[0][0] = G::ScalarField::two_pow(16);
+    let res = TestFramework::<G>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>();
+    assert!(res.is_err());
+
+    // the unused values of a 3-byte check are wired to zero
+    let (gates, _) = create_range_check_bytes(&[(G::ScalarField::zero(), 3)]);
+    let (_, witness) = create_range_check_bytes(&[(G::ScalarField::two_pow(24), 4)]);
+    let res = TestFramework::<G>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>();
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_invalid_range_check_bytes() {
+    invalid_range_check_bytes::<Vesta>();
+    invalid_range_check_bytes::<Pallas>();
+}
+
+#[should_panic]
+#[test]
+fn range_check_bytes_value_too_large() {
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    range_check::bytes::extend_range_check_bytes_witness(&mut witness, Fp::two_pow(16), 2);
+}
+
+#[should_panic]
+#[test]
+fn range_check_bytes_too_many_bytes() {
+    CircuitGate::<Fp>::create_range_check_bytes(0, 32);
+}
+
+// Test that a batch of multi-range-checks has the witness of the gadgets of its values, one after the other
+fn verify_multi_range_check_batch<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    let values: Vec<BigUint> = (0..20)
+        .map(|_| rng.gen_biguint_below(&BigUint::binary_modulus()))
+        .chain([BigUint::zero(), BigUint::binary_modulus() - BigUint::one()])
+        .collect();
+
+    let (next_row, gates) =
+        CircuitGate::<G::ScalarField>::create_multi_range_checks(0, values.len());
+    assert_eq!(next_row, gates.len());
+    assert_eq!(gates.len(), values.len() * gadget::MULTI_RANGE_CHECK_ROWS);
+
+    let witness = range_check::witness::create_multi_batch::<G::ScalarField>(&values);
+    let mut sequential_witness: [Vec<G::ScalarField>; COLUMNS] = array::from_fn(|_| vec![]);
+    for value in &values {
+        range_check::witness::extend_multi_limbs(&mut sequential_witness, &value.to_field_limbs());
+    }
+    assert_eq!(witness, sequential_witness);
+
+    let limbs = values[3].to_field_limbs::<G::ScalarField>();
+    for (limb, expected) in limbs.iter().enumerate() {
+        let (row, col) = gadget::multi_range_check_limb(0, 3, limb);
+        assert_eq!(witness[col][row], *expected);
+    }
+
+    TestFramework::<G>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+fn test_verify_multi_range_check_batch() {
+    verify_multi_range_check_batch::<Vesta>();
+    verify_multi_range_check_batch::<Pallas>();
+}
+
+#[test]
+#[should_panic]
+// Test that the values of a batch of multi-range-checks must fit in 264 bits
+fn multi_range_check_batch_value_too_large() {
+    range_check::witness::create_multi_batch::<Fp>(&[BigUint::binary_modulus()]);
+}
@@ -0,0 +1,85 @@
+use super::framework::TestFramework;
+use crate::circuits::{
+    constraints::GateError,
+    polynomials::generic::testing::{create_circuit, fill_in_witness},
+    wires::{Wire, COLUMNS},
+};
+use ark_ff::Zero;
+use mina_curves::pasta::{Fp, Vesta};
+use std::array;
+
+// The number of public inputs of the test circuit
+const PUBLIC: usize = 3;
+
+// Creates the generic test circuit, with its valid witness
+fn create_test_runner() -> (super::framework::TestRunner<Vesta>, [Vec<Fp>; COLUMNS]) {
+    let public = vec![Fp::from(7u8); PUBLIC];
+    let gates = create_circuit(0, PUBLIC);
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let runner = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness.clone())
+        .public_inputs(public)
+        .setup();
+    (runner, witness)
+}
+
+#[test]
+fn test_redact_valid_witness() {
+    let (runner, witness) = create_test_runner();
+    let index = runner.prover_index();
+    assert!(index
+        .redact_witness(&witness, &witness[0][..PUBLIC])
+        .is_none());
+}
+
+#[test]
+fn test_redact_failing_witness() {
+    let (runner, mut witness) = create_test_runner();
+    let index = runner.prover_index();
+    let public = witness[0][..PUBLIC].to_vec();
+
+    // break the addition of the first generic gate after the public inputs
+    witness[2][PUBLIC] += Fp::from(1u8);
+    assert!(matches!(
+        index.verify(&witness, &public),
+        Err(GateError::Custom { row: PUBLIC, .. })
+    ));
+
+    let redacted = index.redact_witness(&witness, &public).unwrap();
+
+    // the failure is preserved, and it is the only one
+    assert_eq!(redacted.failures.len(), 1);
+    assert!(matches!(
+        redacted.failures[0],
+        GateError::Custom { row: PUBLIC, .. }
+    ));
+    assert!(matches!(
+        index.verify(&redacted.witness, &public),
+        Err(GateError::Custom { row: PUBLIC, .. })
+    ));
+
+    // the public inputs are kept
+    assert_eq!(redacted.witness[0][..PUBLIC], public[..]);
+
+    // only the output of the broken addition is needed for it to fail,
+    // and the constants are needed for the next rows not to fail
+    let consts =
+        (PUBLIC + 10..PUBLIC + 20).flat_map(|row| [Wire { row, col: 0 }, Wire { row, col: 3 }]);
+    let expected: Vec<_> = std::iter::once(Wire {
+        row: PUBLIC,
+        col: 2,
+    })
+    .chain(consts)
+    .collect();
+    assert_eq!(redacted.kept, expected);
+    for row in PUBLIC..witness[0].len() {
+        for col in 0..COLUMNS {
+            if !expected.contains(&Wire { row, col }) {
+                assert!(redacted.witness[col][row].is_zero());
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! Golden vectors for the platform-sensitive building blocks of a proof.
+//!
+//! The expected values below are pinned constants: they must be identical on every target
+//! (x86_64, aarch64, wasm32), so any platform-dependent arithmetic shows up as a failing test.
+//! The proof bytes themselves are covered by `test_deterministic_proof_with_rng`,
+//! as they are fully determined by these primitives once the prover's RNG is seeded.
+
+use crate::{curve::KimchiCurve, plonk_sponge::FrSponge};
+use ark_ff::{Field, One, Zero};
+use ark_serialize::CanonicalSerialize;
+use mina_curves::pasta::{Fp, Fq, Pallas, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{endo_coefficient, DefaultFqSponge, DefaultFrSponge},
+    FqSponge,
+};
+use o1_utils::FieldHelpers;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+/// Checks that `value` serializes to the little-endian hex string `expected`,
+/// both with [FieldHelpers] and with the arkworks canonical serialization,
+/// and that it deserializes back to itself.
+fn check_serialization<F: Field>(value: F, expected: &str) {
+    assert_eq!(value.to_hex(), expected);
+
+    let mut bytes = vec![];
+    value.serialize(&mut bytes).unwrap();
+    assert_eq!(hex::encode(&bytes), expected);
+
+    assert_eq!(F::from_hex(expected).unwrap(), value);
+}
+
+#[test]
+fn test_fp_serialization_vectors() {
+    let u64_max = Fp::from(u64::MAX);
+    for (value, expected) in [
+        (
+            Fp::zero(),
+            "0000000000000000000000000000000000000000000000000000000000000000",
+        ),
+        (
+            Fp::one(),
+            "0100000000000000000000000000000000000000000000000000000000000000",
+        ),
+        (
+            -Fp::one(),
+            "00000000ed302d991bf94c09fc98462200000000000000000000000000000040",
+        ),
+        (
+            Fp::from(1u128 << 64),
+            "0000000000000000010000000000000000000000000000000000000000000000",
+        ),
+        (
+            u64_max * u64_max,
+            "0100000000000000feffffffffffffff00000000000000000000000000000000",
+        ),
+        (
+            Fp::from(2u8).inverse().unwrap(),
+            "01000080769896cc8d7ca6047e4c231100000000000000000000000000000020",
+        ),
+    ] {
+        check_serialization(value, expected);
+    }
+}
+
+#[test]
+fn test_fq_serialization_vectors() {
+    let u64_max = Fq::from(u64::MAX);
+    for (value, expected) in [
+        (
+            Fq::zero(),
+            "0000000000000000000000000000000000000000000000000000000000000000",
+        ),
+        (
+            Fq::one(),
+            "0100000000000000000000000000000000000000000000000000000000000000",
+        ),
+        (
+            -Fq::one(),
+            "0000000021eb468cdda89409fc98462200000000000000000000000000000040",
+        ),
+        (
+            Fq::from(1u128 << 64),
+            "0000000000000000010000000000000000000000000000000000000000000000",
+        ),
+        (
+            u64_max * u64_max,
+            "0100000000000000feffffffffffffff00000000000000000000000000000000",
+        ),
+        (
+            Fq::from(2u8).inverse().unwrap(),
+            "01000080907523c66e54ca047e4c231100000000000000000000000000000020",
+        ),
+    ] {
+        check_serialization(value, expected);
+    }
+}
+
+#[test]
+fn test_fq_sponge_vectors() {
+    let input = [Fq::from(1u8), Fq::from(2u8), Fq::from(3u8)];
+
+    // the Fq-sponge is set up exactly as in the prover and the verifier (with the sponge params of the other curve)
+    let mut fq_sponge = BaseSponge::new(Pallas::sponge_params());
+    fq_sponge.absorb_fq(&input);
+    assert_eq!(
+        fq_sponge.challenge().to_hex(),
+        "bf275fc958a501b40f0341579c38632500000000000000000000000000000000"
+    );
+    assert_eq!(
+        fq_sponge.challenge().to_hex(),
+        "536dd30e20a03c39f5eee79c7816abfb00000000000000000000000000000000"
+    );
+
+    let mut fq_sponge = BaseSponge::new(Pallas::sponge_params());
+    fq_sponge.absorb_fq(&input);
+    assert_eq!(
+        fq_sponge.digest().to_hex(),
+        "bf275fc958a501b40f0341579c386325c29635646f92b5998dad966ad7851637"
+    );
+}
+
+#[test]
+fn test_fr_sponge_challenge_vectors() {
+    let mut fr_sponge = ScalarSponge::new(Vesta::sponge_params());
+    fr_sponge.absorb_multiple(&[Fp::from(4u8), Fp::from(5u8)]);
+    let challenge = fr_sponge.challenge();
+    assert_eq!(
+        challenge.0.to_hex(),
+        "59bcee964bd34235dce076c8dc3183d800000000000000000000000000000000"
+    );
+
+    // derivation of the full challenge from its 128-bit representation
+    let endo: Fp = endo_coefficient();
+    assert_eq!(
+        endo.to_hex(),
+        "47b501022fd27f7bd2c79fd1290d2705504e55a8232a55d38e4532b57c35332d"
+    );
+    assert_eq!(
+        challenge.to_field(&endo).to_hex(),
+        "f46292a7787886e72271cdb62d8d155dd494106fb53477ed231ec18ecf22433d"
+    );
+}
@@ -44,7 +44,10 @@ const RNG_SEED: [u8; 32] = [
     89, 29, 13, 250, 215, 172, 130, 24, 164, 162,
 ];
 
-fn create_rot_gadget<G: KimchiCurve>(rot: u32, side: RotMode) -> Vec<CircuitGate<G::ScalarField>>
+pub(crate) fn create_rot_gadget<G: KimchiCurve>(
+    rot: u32,
+    side: RotMode,
+) -> Vec<CircuitGate<G::ScalarField>>
 where
     G::BaseField: PrimeField,
 {
@@ -58,7 +61,7 @@ where
     gates
 }
 
-fn create_rot_witness<G: KimchiCurve>(
+pub(crate) fn create_rot_witness<G: KimchiCurve>(
     word: u64,
     rot: u32,
     side: RotMode,
@@ -361,3 +364,48 @@ fn test_rot_finalization() {
         .prove_and_verify::<BaseSponge, ScalarSponge>()
         .unwrap();
 }
+
+#[test]
+// Test that a rotation of a word copied from a public input, with the range check of the word, is proven
+fn test_rot_from_cell() {
+    let gates = {
+        // the public word and the zero row
+        let mut gates: Vec<_> = (0..2)
+            .map(|row| {
+                CircuitGate::<Fp>::create_generic_gadget(
+                    Wire::for_row(row),
+                    GenericGateSpec::Pub,
+                    None,
+                )
+            })
+            .collect();
+        CircuitGate::<Fp>::extend_rot_from_cell(&mut gates, (0, 0), 13, RotMode::Right, 1);
+        gates
+    };
+    assert_eq!(gates.len(), 5);
+    assert_eq!(gates[4].typ, GateType::RangeCheck0);
+
+    let word = 0xDC811727DAF22EC1u64;
+    let mut witness: [_; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); 2]);
+    witness[0][0] = word.into();
+    rot::extend_rot_from_cell(&mut witness, (0, 0), word.into(), 13, RotMode::Right);
+    assert_eq!(witness[0][4], Fp::from(word));
+    assert_eq!(witness[1][2], Fp::from(word.rotate_right(13)));
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness.clone())
+        .public_inputs(vec![witness[0][0], witness[0][1]])
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+#[should_panic]
+// Test that the claimed word must be the value of the input cell
+fn test_rot_from_cell_wrong_claim() {
+    let mut witness: [_; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); 2]);
+    witness[0][0] = Fp::from(5u64);
+    rot::extend_rot_from_cell(&mut witness, (0, 0), Fp::from(6u64), 1, RotMode::Left);
+}
@@ -0,0 +1,114 @@
+use super::ecdsa::verify_rows;
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::{CircuitGate, Connect},
+    optimizer::Optimization,
+    polynomials::{
+        generic::GenericGateSpec,
+        select::{select_cells, select_condition},
+    },
+    scheduler::Schedule,
+    wires::Wire,
+    witness::solve_witness,
+};
+use mina_curves::pasta::Fp;
+
+const SELECTIONS: usize = 7;
+
+fn single_add(row: usize) -> CircuitGate<Fp> {
+    CircuitGate::create_generic_gadget(
+        Wire::for_row(row),
+        GenericGateSpec::Add {
+            left_coeff: None,
+            right_coeff: None,
+            output_coeff: None,
+        },
+        None,
+    )
+}
+
+// A circuit with a public condition, and selections each followed by the addition of their output,
+// so that no addition is packed in the order of the gadgets.
+// Returns the gates, the first rows of the blocks, and the input cells of the witness.
+fn selections() -> (Vec<CircuitGate<Fp>>, Vec<usize>, Vec<((usize, usize), Fp)>) {
+    let mut gates = vec![CircuitGate::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )];
+    let mut blocks = vec![];
+    let mut inputs = vec![];
+    for i in 0..SELECTIONS {
+        let select_row = gates.len();
+        blocks.push(select_row);
+        CircuitGate::extend_select(&mut gates, 1);
+        let add_row = gates.len();
+        blocks.push(add_row);
+        gates.push(single_add(add_row));
+
+        let [x, y, out] = select_cells(select_row, 0);
+        gates.connect_cell_pair((0, 0), select_condition(select_row));
+        gates.connect_cell_pair(out, (add_row, 0));
+        let value = |value: usize| Fp::from(value as u64);
+        inputs.extend([
+            (x, value(i)),
+            (y, value(10 + i)),
+            ((add_row, 1), value(100)),
+        ]);
+    }
+    (gates, blocks, inputs)
+}
+
+#[test]
+// Test that the blocks are reordered to pack the additions, and fit the circuit in a smaller domain
+fn test_schedule_blocks() {
+    let (gates, blocks, inputs) = selections();
+    assert_eq!(gates.len(), 1 + 2 * SELECTIONS);
+    let (optimized, _) = Optimization::create(&gates, 1);
+    assert_eq!(optimized.len(), 15);
+
+    let (scheduled, schedule) = Schedule::create(&gates, 1, &blocks);
+    assert_eq!(scheduled.len(), gates.len());
+    // the additions follow the public input row, before the selections
+    let order: Vec<usize> = (0..SELECTIONS)
+        .map(|i| 2 * i + 1)
+        .chain((0..SELECTIONS).map(|i| 2 * i))
+        .collect();
+    assert_eq!(schedule.order(), &order[..]);
+    assert_eq!(schedule.optimized_rows(), 15 - SELECTIONS / 2);
+    assert_eq!(schedule.domain_size(), 16);
+    assert_eq!(schedule.cell(Wire::new(2, 1)), Wire::new(1, 1));
+    assert_eq!(schedule.cell(Wire::new(1, 1)), Wire::new(1 + SELECTIONS, 1));
+
+    // the witness of the original circuit is laid out in the scheduled and optimized circuit
+    let public = vec![Fp::from(1u64)];
+    let witness = solve_witness(&gates, &public, &inputs).unwrap();
+    let (builder, schedule) = ConstraintSystem::create(gates).public(1).schedule(&blocks);
+    let (builder, optimization) = builder.optimize();
+    let cs = builder.build().unwrap();
+    assert_eq!(cs.domain.d1.size, schedule.domain_size());
+    let optimized_witness = optimization.optimize_witness(&schedule.schedule_witness(&witness));
+    assert_eq!(verify_rows(&cs, &optimized_witness), Ok(()));
+}
+
+#[test]
+// Test that the order of the blocks is kept when it gives the fewest rows
+fn test_schedule_kept_order() {
+    let gates: Vec<_> = (0..6).map(single_add).collect();
+    let blocks = [1, 3, 4];
+    let (scheduled, schedule) = Schedule::create(&gates, 1, &blocks);
+    assert_eq!(schedule.order(), &[0, 1, 2]);
+    assert_eq!(schedule.optimized_rows(), 1 + 3);
+    assert_eq!(schedule.cell(Wire::new(4, 2)), Wire::new(4, 2));
+    for (gate, scheduled_gate) in gates.iter().zip(&scheduled) {
+        assert_eq!(gate.wires, scheduled_gate.wires);
+    }
+}
+
+#[test]
+#[should_panic]
+// Test that the public input rows are not scheduled
+fn test_schedule_public_rows() {
+    let gates: Vec<_> = (0..4).map(single_add).collect();
+    Schedule::create(&gates, 2, &[1, 3]);
+}
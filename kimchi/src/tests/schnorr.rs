@@ -0,0 +1,118 @@
+use std::array;
+
+use super::{ecdsa::verify_rows, framework::TestFramework};
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::CircuitGate,
+    polynomial::COLUMNS,
+    polynomials::schnorr::{self, SchnorrInput, SchnorrSignature},
+};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, PrimeField, UniformRand};
+use mina_curves::pasta::{Fp, Fq, Pallas, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+const DOMAIN: &str = "MinaSignatureMainnet";
+
+// Signs a message with a random key, as the Mina signer, and returns the public key and the signature
+fn sign(message: &[Fp]) -> (Pallas, SchnorrSignature<Pallas>) {
+    let rng = &mut StdRng::from_seed([0; 32]);
+    let secret = Fq::rand(rng);
+    let public_key = Pallas::prime_subgroup_generator()
+        .mul(secret.into_repr())
+        .into_affine();
+    let mut k = Fq::rand(rng);
+    let r = Pallas::prime_subgroup_generator()
+        .mul(k.into_repr())
+        .into_affine();
+    if r.y.into_repr().is_odd() {
+        k = -k;
+    }
+    let e = schnorr::schnorr_challenge(DOMAIN, message, &public_key, r.x);
+    let e = Fq::from_repr(e.into_repr()).unwrap();
+    let signature = SchnorrSignature {
+        rx: r.x,
+        s: k + e * secret,
+    };
+    (public_key, signature)
+}
+
+// Creates the gadget verifying a signature of a message, and its witness
+fn create_schnorr(
+    message: &[Fp],
+    public_key: &Pallas,
+    signature: &SchnorrSignature<Pallas>,
+) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS], bool) {
+    let (next_row, gates) = CircuitGate::create_schnorr::<Pallas>(0, DOMAIN, message.len());
+    assert_eq!(next_row, gates.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let valid =
+        schnorr::extend_schnorr_witness(&mut witness, DOMAIN, message, public_key, signature);
+    assert_eq!(witness[0].len(), next_row);
+    (gates, witness, valid)
+}
+
+#[test]
+// Test that a signature of an odd number of elements satisfies the gadget, and is proven
+fn test_schnorr_valid() {
+    let message: Vec<Fp> = (1..=4u64).map(Fp::from).collect();
+    let (public_key, signature) = sign(&message);
+    assert!(schnorr::verify_schnorr(
+        DOMAIN,
+        &message,
+        &public_key,
+        &signature
+    ));
+
+    let (gates, witness, valid) = create_schnorr(&message, &public_key, &signature);
+    assert!(valid);
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+    // the inputs are at their positions
+    let (row, col) = schnorr::schnorr_input(0, message.len(), SchnorrInput::Message(3));
+    assert_eq!(witness[col][row], message[3]);
+    let (row, col) = schnorr::schnorr_input(0, message.len(), SchnorrInput::Rx);
+    assert_eq!(witness[col][row], signature.rx);
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+// Test that a signature of another message does not satisfy the gadget
+fn test_schnorr_wrong_message() {
+    let message: Vec<Fp> = (1..=3u64).map(Fp::from).collect();
+    let (public_key, signature) = sign(&message);
+    let other: Vec<Fp> = (2..=4u64).map(Fp::from).collect();
+    assert!(!schnorr::verify_schnorr(
+        DOMAIN,
+        &other,
+        &public_key,
+        &signature
+    ));
+
+    let (gates, witness, valid) = create_schnorr(&other, &public_key, &signature);
+    assert!(!valid);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+    assert!(verify_rows(&cs, &witness).is_err());
+}
+
+#[test]
+#[should_panic]
+// Test that domain strings longer than 20 bytes are rejected
+fn test_schnorr_domain() {
+    CircuitGate::<Fp>::create_schnorr::<Pallas>(0, "MinaSignatureMainnet!", 1);
+}
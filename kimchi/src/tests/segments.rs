@@ -0,0 +1,156 @@
+use super::public_output;
+use crate::{
+    circuits::{
+        constraints::ConstraintSystem, polynomial::COLUMNS,
+        polynomials::generic::testing::create_circuit, witness::WitnessSegments,
+    },
+    error::ProverError,
+    proof::ProverProof,
+    prover_index::testing::{new_index_for_test, new_index_for_test_with_cs},
+    verifier::verify_with_outputs,
+};
+use ark_ff::Zero;
+use ark_poly::{EvaluationDomain, Evaluations};
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use poly_commitment::commitment::CommitmentCurve;
+use rand::{rngs::StdRng, SeedableRng};
+use std::{cell::Cell, ops::Range};
+
+type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+const RNG_SEED: [u8; 32] = [
+    115, 101, 103, 109, 101, 110, 116, 115, 0, 3, 14, 15, 92, 65, 35, 89, 79, 32, 38, 46, 26, 43,
+    38, 32, 79, 50, 88, 41, 97, 16, 93, 99,
+];
+
+// A witness computing its cells from their positions,
+// recording the largest segment computed
+struct Positions {
+    num_rows: usize,
+    largest_segment: Cell<usize>,
+}
+
+impl Positions {
+    fn new(num_rows: usize) -> Self {
+        Positions {
+            num_rows,
+            largest_segment: Cell::new(0),
+        }
+    }
+}
+
+impl WitnessSegments<Fp> for Positions {
+    fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    fn segment(&self, col: usize, rows: Range<usize>) -> Vec<Fp> {
+        self.largest_segment
+            .set(std::cmp::max(self.largest_segment.get(), rows.len()));
+        rows.map(|row| Fp::from((COLUMNS * row + col) as u64))
+            .collect()
+    }
+}
+
+#[test]
+// Test that the commitments of a witness computed in segments are the commitments of its columns,
+// and that the segments are not larger than requested
+fn test_commit_witness_segments() {
+    let index = new_index_for_test::<Vesta>(create_circuit(0, 0), 0);
+    let domain = index.cs.domain.d1;
+    let positions = Positions::new(20);
+    let witness = positions.witness();
+    let comms: Vec<_> = witness
+        .iter()
+        .map(|column| {
+            let mut evals = column.clone();
+            evals.resize(domain.size(), Fp::zero());
+            let evals = Evaluations::from_vec_and_domain(evals, domain);
+            index.srs.commit_evaluations_non_hiding(domain, &evals)
+        })
+        .collect();
+
+    for segment_rows in [1, 7, 20, 64] {
+        let positions = Positions::new(20);
+        let segment_comms = index
+            .commit_witness_segments(&positions, segment_rows)
+            .unwrap();
+        assert_eq!(&segment_comms[..], &comms[..]);
+        assert_eq!(
+            positions.largest_segment.get(),
+            std::cmp::min(segment_rows, 20)
+        );
+    }
+    assert_eq!(
+        index.commit_witness_segments(&witness, 6).unwrap()[..],
+        comms[..]
+    );
+}
+
+#[test]
+// Test that the witness leaves room for the zero-knowledge rows in the domain
+fn test_commit_witness_segments_no_room() {
+    let index = new_index_for_test::<Vesta>(create_circuit(0, 0), 0);
+    let num_rows = index.cs.domain.d1.size() - 2;
+    assert!(matches!(
+        index.commit_witness_segments(&Positions::new(num_rows), 8),
+        Err(ProverError::NoRoomForZkInWitness)
+    ));
+}
+
+#[test]
+// Test that the proof created from a witness given in segments is the one created from
+// the whole witness with the same randomness, with the row of its public output filled
+fn test_prove_witness_segments() {
+    let x = Fp::from(3u64);
+    let cs = ConstraintSystem::create(public_output::create_circuit())
+        .public(1)
+        .public_outputs(&[public_output::OUTPUT])
+        .build()
+        .unwrap();
+    let index = new_index_for_test_with_cs::<Vesta>(cs);
+    let witness = public_output::create_witness(x);
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+
+    let proof = ProverProof::create_recursive_with_rng::<BaseSponge, ScalarSponge, _>(
+        &group_map,
+        witness.clone(),
+        &[],
+        &index,
+        vec![],
+        None,
+        &mut StdRng::from_seed(RNG_SEED),
+    )
+    .unwrap();
+    let proof = rmp_serde::to_vec(&proof).unwrap();
+
+    for segment_rows in [1, 3, 4] {
+        let segment_proof =
+            ProverProof::create_recursive_with_segments::<BaseSponge, ScalarSponge, _>(
+                &group_map,
+                &witness,
+                segment_rows,
+                &[],
+                &index,
+                vec![],
+                None,
+                &mut StdRng::from_seed(RNG_SEED),
+            )
+            .unwrap();
+        verify_with_outputs::<Vesta, BaseSponge, ScalarSponge>(
+            &group_map,
+            &index.verifier_index(),
+            &segment_proof,
+            &[x],
+            &[x * x + x],
+        )
+        .unwrap();
+        assert_eq!(rmp_serde::to_vec(&segment_proof).unwrap(), proof);
+    }
+}
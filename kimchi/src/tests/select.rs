@@ -0,0 +1,167 @@
+use std::array;
+
+use super::{ecdsa::verify_rows, framework::TestFramework};
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::{CircuitGate, CircuitGateError, Connect, GateType},
+    polynomial::COLUMNS,
+    polynomials::{
+        generic::GenericGateSpec,
+        select::{self, select_cells, select_condition},
+    },
+    wires::Wire,
+};
+use ark_ff::{One, Zero};
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+// Creates a circuit selecting between the public inputs `x` and `y` with the public input `b`,
+// and its witness
+fn create_select(b: bool, x: Fp, y: Fp) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS], Vec<Fp>) {
+    let mut gates: Vec<_> = (0..3)
+        .map(|row| {
+            CircuitGate::create_generic_gadget(Wire::for_row(row), GenericGateSpec::Pub, None)
+        })
+        .collect();
+    let new_row = gates.len();
+    CircuitGate::extend_select(&mut gates, 1);
+    gates.connect_cell_pair((0, 0), select_condition(new_row));
+    let [x_cell, y_cell, _] = select_cells(new_row, 0);
+    gates.connect_cell_pair((1, 0), x_cell);
+    gates.connect_cell_pair((2, 0), y_cell);
+
+    let public = vec![Fp::from(b), x, y];
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|col| {
+        if col == 0 {
+            public.clone()
+        } else {
+            vec![Fp::zero(); public.len()]
+        }
+    });
+    let out = select::extend_select_witness(&mut witness, b, &[x], &[y]);
+    assert_eq!(out, vec![if b { x } else { y }]);
+    assert_eq!(witness[0].len(), gates.len());
+    (gates, witness, public)
+}
+
+fn prove_and_verify(
+    gates: Vec<CircuitGate<Fp>>,
+    witness: [Vec<Fp>; COLUMNS],
+    public: Vec<Fp>,
+) -> Result<(), String> {
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(public)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+}
+
+#[test]
+// Test that both branches of the selection are proven
+fn test_select() {
+    let (x, y) = (Fp::from(7u64), Fp::from(42u64));
+    for b in [true, false] {
+        let (gates, witness, public) = create_select(b, x, y);
+        assert_eq!(witness[3][3], if b { x } else { y });
+        let cs = ConstraintSystem::create(gates.clone())
+            .public(public.len())
+            .build()
+            .unwrap();
+        assert_eq!(verify_rows(&cs, &witness), Ok(()));
+        prove_and_verify(gates, witness, public).unwrap();
+    }
+}
+
+#[test]
+// Test that a condition which is not a bit, and a wrong output, fail their constraints
+fn test_select_bad_constraints() {
+    let (x, y) = (Fp::from(7u64), Fp::from(42u64));
+    let (gates, mut witness, public) = create_select(true, x, y);
+    let cs = ConstraintSystem::create(gates.clone())
+        .public(public.len())
+        .build()
+        .unwrap();
+    let row = 3;
+
+    // C1: the condition is not a bit, even if the output is the combination of the words
+    let two = Fp::from(2u64);
+    witness[0][row] = two;
+    witness[3][row] = y + two * (x - y);
+    assert_eq!(
+        cs.gates[row].verify_witness::<Vesta>(row, &witness, &cs, &[]),
+        Err(CircuitGateError::Constraint(GateType::Select, 1))
+    );
+    witness[0][row] = Fp::one();
+
+    // C2: the output is the other word
+    witness[3][row] = y;
+    assert_eq!(
+        cs.gates[row].verify_witness::<Vesta>(row, &witness, &cs, &[]),
+        Err(CircuitGateError::Constraint(GateType::Select, 2))
+    );
+    witness[3][row] = x;
+    assert_eq!(
+        cs.gates[row].verify_witness::<Vesta>(row, &witness, &cs, &[]),
+        Ok(())
+    );
+
+    // the condition is copied from the public input
+    let mut wrong_witness = witness;
+    wrong_witness[0][row] = Fp::zero();
+    wrong_witness[3][row] = y;
+    assert!(verify_rows(&cs, &wrong_witness).is_err());
+    assert!(prove_and_verify(gates, wrong_witness, public).is_err());
+}
+
+#[test]
+// Test the selection between the limbs of two foreign field elements, on two rows
+fn test_select_foreign() {
+    let x: [Fp; 3] = array::from_fn(|i| Fp::from(1000u64 + i as u64));
+    let y: [Fp; 3] = array::from_fn(|i| Fp::from(2000u64 + i as u64));
+    for b in [true, false] {
+        let mut gates = vec![CircuitGate::create_generic_gadget(
+            Wire::for_row(0),
+            GenericGateSpec::Pub,
+            None,
+        )];
+        let next_row = CircuitGate::extend_select_foreign(&mut gates);
+        assert_eq!(next_row, 3);
+        gates.connect_cell_pair((0, 0), select_condition(1));
+
+        let public = vec![Fp::from(b)];
+        let mut witness: [Vec<Fp>; COLUMNS] =
+            array::from_fn(|col| vec![if col == 0 { public[0] } else { Fp::zero() }]);
+        let out = select::extend_select_foreign_witness(&mut witness, b, x, y);
+        assert_eq!(out, if b { x } else { y });
+        for (i, limb) in out.iter().enumerate() {
+            let (row, col) = select_cells(1, i)[2];
+            assert_eq!(witness[col][row], *limb);
+        }
+
+        let cs = ConstraintSystem::create(gates.clone())
+            .public(1)
+            .build()
+            .unwrap();
+        assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+        // the condition of the second row is the one of the first row
+        let mut wrong_witness = witness.clone();
+        wrong_witness[0][2] = Fp::from(!b);
+        let (row, col) = select_cells(1, 2)[2];
+        wrong_witness[col][row] = if b { y[2] } else { x[2] };
+        assert!(matches!(
+            verify_rows(&cs, &wrong_witness),
+            Err(CircuitGateError::CopyConstraint { .. })
+        ));
+
+        prove_and_verify(gates, witness, public).unwrap();
+    }
+}
@@ -4,9 +4,10 @@ use crate::{
         polynomials::generic::testing::{create_circuit, fill_in_witness},
         wires::COLUMNS,
     },
+    linearization::LinearizationExport,
     proof::ProverProof,
     prover_index::testing::new_index_for_test,
-    verifier::verify,
+    verifier::{batch_verify_with_rng, verify, verify_with_rng, Context},
     verifier_index::VerifierIndex,
 };
 use ark_ec::short_weierstrass_jacobian::GroupAffine;
@@ -18,6 +19,7 @@ use mina_poseidon::{
     sponge::{DefaultFqSponge, DefaultFrSponge},
 };
 use poly_commitment::{commitment::CommitmentCurve, srs::SRS};
+use rand::{rngs::StdRng, SeedableRng};
 use std::array;
 use std::time::Instant;
 
@@ -92,4 +94,137 @@ mod tests {
         .unwrap();
         println!("- time to verify: {}ms", start.elapsed().as_millis());
     }
+
+    #[test]
+    fn test_linearization_export() {
+        let public = vec![Fp::from(3u8); 5];
+        let gates = create_circuit(0, public.len());
+        let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+        fill_in_witness(0, &mut witness, &public);
+
+        let index = new_index_for_test(gates, public.len());
+        let mut verifier_index = index.verifier_index();
+
+        // the export is machine-readable, and has the exponents of alpha of the arguments
+        let export = serde_json::to_string(&verifier_index.linearization_export()).unwrap();
+        let export: LinearizationExport = serde_json::from_str(&export).unwrap();
+        assert_eq!(
+            export.powers_of_alpha,
+            verifier_index.powers_of_alpha.exponents()
+        );
+
+        // it imports back to the exact linearization of the verifier index
+        let linearization = export.linearization::<Fp>().unwrap();
+        assert_eq!(
+            linearization.constant_term,
+            verifier_index.linearization.constant_term
+        );
+        assert_eq!(
+            linearization.index_terms,
+            verifier_index.linearization.index_terms
+        );
+
+        // and the imported linearization verifies the proofs
+        let group_map = <Vesta as CommitmentCurve>::Map::setup();
+        let proof =
+            ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], &index)
+                .unwrap();
+        verifier_index.linearization = linearization;
+        verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof, &public)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_deterministic_proof_with_rng() {
+        let public = vec![Fp::from(3u8); 5];
+        let gates = create_circuit(0, public.len());
+
+        // create witness
+        let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+        fill_in_witness(0, &mut witness, &public);
+
+        let index = new_index_for_test(gates, public.len());
+        let verifier_index = index.verifier_index();
+        let group_map = <Vesta as CommitmentCurve>::Map::setup();
+
+        // the same seed must produce the exact same proof
+        let prove = |seed: u64| {
+            ProverProof::create_recursive_with_rng::<BaseSponge, ScalarSponge, _>(
+                &group_map,
+                witness.clone(),
+                &[],
+                &index,
+                vec![],
+                None,
+                &mut StdRng::seed_from_u64(seed),
+            )
+            .unwrap()
+        };
+        let proof = prove(0);
+        let proof_bytes = rmp_serde::to_vec(&proof).unwrap();
+        assert_eq!(proof_bytes, rmp_serde::to_vec(&prove(0)).unwrap());
+        assert_ne!(proof_bytes, rmp_serde::to_vec(&prove(1)).unwrap());
+
+        // verify the proof with a seeded rng as well
+        let batch = vec![Context {
+            verifier_index: &verifier_index,
+            proof: &proof,
+            public_input: &public,
+        }];
+        batch_verify_with_rng::<Vesta, BaseSponge, ScalarSponge, _>(
+            &group_map,
+            &batch,
+            &mut StdRng::seed_from_u64(0),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_proof_with_external_rng() {
+        let public = vec![Fp::from(3u8); 5];
+        let gates = create_circuit(0, public.len());
+
+        // create witness
+        let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+        fill_in_witness(0, &mut witness, &public);
+
+        let index = new_index_for_test(gates, public.len());
+        let verifier_index = index.verifier_index();
+        let group_map = <Vesta as CommitmentCurve>::Map::setup();
+
+        // the proof is the recursive proof without previous challenges, for the same randomness
+        let mut rng = StdRng::seed_from_u64(42);
+        let proof = ProverProof::create_with_rng::<BaseSponge, ScalarSponge, _>(
+            &group_map,
+            witness.clone(),
+            &[],
+            &index,
+            &mut rng,
+        )
+        .unwrap();
+        let recursive = ProverProof::create_recursive_with_rng::<BaseSponge, ScalarSponge, _>(
+            &group_map,
+            witness,
+            &[],
+            &index,
+            vec![],
+            None,
+            &mut StdRng::seed_from_u64(42),
+        )
+        .unwrap();
+        assert_eq!(
+            rmp_serde::to_vec(&proof).unwrap(),
+            rmp_serde::to_vec(&recursive).unwrap()
+        );
+
+        // the same rng keeps drawing fresh randomness for the verification
+        verify_with_rng::<Vesta, BaseSponge, ScalarSponge, _>(
+            &group_map,
+            &verifier_index,
+            &proof,
+            &public,
+            &mut rng,
+        )
+        .unwrap();
+    }
 }
@@ -0,0 +1,110 @@
+use super::framework::TestFramework;
+use crate::circuits::polynomials::generic::testing::{create_circuit, fill_in_witness};
+use crate::circuits::wires::COLUMNS;
+use crate::error::VerifyError;
+use crate::proof::ProverProof;
+use crate::session::{check_session_chain, verify_session, Session};
+use crate::verifier::{verify, Context};
+use ark_ff::Zero;
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use poly_commitment::commitment::CommitmentCurve;
+use rand::prelude::*;
+use std::array;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+#[test]
+fn test_session() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let test_runner = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness.clone())
+        .public_inputs(public.clone())
+        .setup();
+    let index = test_runner.prover_index();
+    let verifier_index = index.verifier_index();
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let rng = &mut StdRng::from_seed([0u8; 32]);
+
+    // create a session of three proofs
+    let mut session = Session::<Vesta>::new();
+    let proofs: Vec<ProverProof<Vesta>> = (0..3)
+        .map(|_| {
+            session
+                .prove_with_rng::<BaseSponge, ScalarSponge, _>(
+                    &group_map,
+                    witness.clone(),
+                    &[],
+                    index,
+                    rng,
+                )
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(proofs[0].prev_proof_digest, None);
+    assert_eq!(
+        session.prev_proof_digest(),
+        Some(proofs[2].digest::<BaseSponge>())
+    );
+
+    let contexts = |proofs: &[&ProverProof<Vesta>]| -> Vec<_> {
+        proofs
+            .iter()
+            .map(|&proof| Context {
+                verifier_index: &verifier_index,
+                proof,
+                public_input: &public,
+            })
+            .collect()
+    };
+
+    // the session verifies in order
+    let ordered: Vec<_> = proofs.iter().collect();
+    verify_session::<Vesta, BaseSponge, ScalarSponge>(&group_map, None, &contexts(&ordered))
+        .unwrap();
+
+    // and from any of its proofs, for a resumed session
+    verify_session::<Vesta, BaseSponge, ScalarSponge>(
+        &group_map,
+        Some(proofs[0].digest::<BaseSponge>()),
+        &contexts(&ordered[1..]),
+    )
+    .unwrap();
+
+    // but not when reordered or with a missing proof
+    assert!(matches!(
+        check_session_chain::<Vesta, BaseSponge>(None, &[&proofs[1], &proofs[0], &proofs[2]]),
+        Err(VerifyError::SessionChainBroken(0))
+    ));
+    assert!(matches!(
+        check_session_chain::<Vesta, BaseSponge>(None, &[&proofs[0], &proofs[2]]),
+        Err(VerifyError::SessionChainBroken(1))
+    ));
+
+    // each proof still verifies alone
+    verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proofs[1], &public)
+        .unwrap();
+
+    // but not with a digest that it was not created with
+    let mut unbound = proofs[1].clone();
+    unbound.prev_proof_digest = Some(proofs[1].digest::<BaseSponge>());
+    assert!(verify::<Vesta, BaseSponge, ScalarSponge>(
+        &group_map,
+        &verifier_index,
+        &unbound,
+        &public
+    )
+    .is_err());
+}
@@ -0,0 +1,182 @@
+use std::array;
+
+use super::framework::TestFramework;
+use crate::circuits::{
+    gate::CircuitGate,
+    lookup::{gadget, tables::LookupTable},
+    polynomial::COLUMNS,
+    polynomials::{
+        generic::GenericGateSpec,
+        range_check,
+        set_membership::{
+            self, set_membership_input, set_membership_table, set_non_membership_input,
+            set_non_membership_table, SET_NON_MEMBERSHIP_ROWS,
+        },
+    },
+    wires::Wire,
+};
+use ark_ff::Zero;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+const MEMBERS_TABLE_ID: i32 = 7;
+const GAPS_TABLE_ID: i32 = 8;
+
+// A set of 64-bit integers, unsorted and with a repeated element
+fn set() -> Vec<Fp> {
+    [42u64, 3, 17, 1 << 40, 17, 255]
+        .into_iter()
+        .map(Into::into)
+        .collect()
+}
+
+fn tables() -> Vec<LookupTable<Fp>> {
+    vec![
+        set_membership_table(MEMBERS_TABLE_ID, set()),
+        set_non_membership_table(GAPS_TABLE_ID, set()),
+    ]
+}
+
+// Creates the gates of a zero row, a membership gadget of the members and a non-membership gadget for each non-member
+fn create_set_circuit(
+    members: &[Fp],
+    non_members: &[Fp],
+) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS]) {
+    let mut gates = vec![CircuitGate::<Fp>::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )];
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero()]);
+
+    CircuitGate::extend_set_membership(&mut gates, MEMBERS_TABLE_ID, members.len());
+    set_membership::extend_set_membership_witness(&mut witness, MEMBERS_TABLE_ID, members);
+    for value in non_members {
+        CircuitGate::extend_set_non_membership(&mut gates, GAPS_TABLE_ID, 0);
+        set_membership::extend_set_non_membership_witness(
+            &mut witness,
+            GAPS_TABLE_ID,
+            &set(),
+            *value,
+        );
+    }
+    assert_eq!(witness[0].len(), gates.len());
+
+    (gates, witness)
+}
+
+fn prove_and_verify(
+    gates: Vec<CircuitGate<Fp>>,
+    witness: [Vec<Fp>; COLUMNS],
+) -> Result<(), String> {
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(vec![Fp::zero()])
+        .lookup_tables(tables())
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+}
+
+#[test]
+// Test that the tables of the set have its elements, and the gaps between its distinct elements
+fn test_set_tables() {
+    let table = set_membership_table(MEMBERS_TABLE_ID, set());
+    assert_eq!(table.data, vec![set()]);
+
+    // the elements 0 and 1 are consecutive, and 0 has no gap before it
+    let table = set_non_membership_table(
+        GAPS_TABLE_ID,
+        vec![Fp::from(5u64), 0u64.into(), 1u64.into()],
+    );
+    assert_eq!(table.id, GAPS_TABLE_ID);
+    assert_eq!(
+        table.data,
+        vec![
+            vec![Fp::from(1u64), 5u64.into()],
+            vec![Fp::from(5u64), Fp::from(2u128.pow(64))],
+        ]
+    );
+
+    // a set with the largest 64-bit integer has no gap after it
+    let table = set_non_membership_table(GAPS_TABLE_ID, vec![Fp::from(u64::MAX)]);
+    assert_eq!(
+        table.data,
+        vec![vec![-Fp::from(1u64)], vec![Fp::from(u64::MAX)]]
+    );
+}
+
+#[test]
+// Test the proofs of members and non-members of the set, including the values around its bounds
+fn test_set_membership() {
+    let members: Vec<Fp> = [17u64, 3, 1 << 40, 255, 42]
+        .into_iter()
+        .map(Into::into)
+        .collect();
+    let non_members: Vec<Fp> = [0u64, 2, 18, 41, 256, (1 << 40) + 1, u64::MAX]
+        .into_iter()
+        .map(Into::into)
+        .collect();
+    let (gates, witness) = create_set_circuit(&members, &non_members);
+
+    let (row, col) = set_membership_input(1, 4);
+    assert_eq!(witness[col][row], members[4]);
+    let start_row = gates.len() - SET_NON_MEMBERSHIP_ROWS;
+    let (row, col) = set_non_membership_input(start_row);
+    assert_eq!(witness[col][row], Fp::from(u64::MAX));
+
+    prove_and_verify(gates, witness).unwrap();
+}
+
+#[test]
+// Test that the values which are not elements of the set fail the membership gadget
+fn test_set_membership_wrong_value() {
+    let (gates, witness) = create_set_circuit(&[Fp::from(3u64), Fp::from(18u64)], &[]);
+    assert!(prove_and_verify(gates, witness).is_err());
+}
+
+#[test]
+// Test that an element of the set fails the non-membership gadget, even at the bound of a gap
+fn test_set_non_membership_wrong_value() {
+    let (mut gates, mut witness) = create_set_circuit(&[Fp::from(3u64)], &[]);
+    CircuitGate::extend_set_non_membership(&mut gates, GAPS_TABLE_ID, 0);
+
+    // 17 is the lower bound of the gap (17, 42), so its distance to the bound is -1
+    let (value, lo, hi) = (Fp::from(17u64), Fp::from(17u64), Fp::from(42u64));
+    let (d_lo, d_hi) = (value - lo - Fp::from(1u64), hi - value - Fp::from(1u64));
+    gadget::extend_lookup_witness(&mut witness, GAPS_TABLE_ID, &[(lo, hi)]);
+    for (col, cell) in [value, lo, d_lo, hi, value, d_hi]
+        .into_iter()
+        .chain(std::iter::repeat(Fp::zero()))
+        .take(COLUMNS)
+        .enumerate()
+    {
+        witness[col].push(cell);
+    }
+    range_check::witness::extend_single(&mut witness, d_lo);
+    range_check::witness::extend_single(&mut witness, d_hi);
+    assert_eq!(witness[0].len(), gates.len());
+
+    assert!(prove_and_verify(gates, witness).is_err());
+}
+
+#[test]
+#[should_panic]
+// Test that the witness of the non-membership of an element of the set cannot be created
+fn test_set_non_membership_of_member() {
+    create_set_circuit(&[Fp::from(3u64)], &[Fp::from(42u64)]);
+}
+
+#[test]
+#[should_panic]
+// Test that the tables of the set cannot have the ID 0 of the dummy entry
+fn test_set_table_id_zero() {
+    set_membership_table(0, set());
+}
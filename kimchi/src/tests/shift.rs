@@ -0,0 +1,162 @@
+use std::array;
+
+use super::framework::TestFramework;
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::CircuitGate,
+    polynomial::COLUMNS,
+    polynomials::{
+        generic::GenericGateSpec,
+        rot::RotMode,
+        shift::{self, shift_output},
+    },
+    wires::Wire,
+};
+use ark_ff::{One, Zero};
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+const RNG_SEED: [u8; 32] = [
+    12, 31, 143, 75, 29, 255, 0, 126, 237, 193, 86, 160, 1, 90, 131, 221, 186, 168, 4, 95, 50, 48,
+    89, 29, 13, 250, 215, 172, 130, 24, 164, 162,
+];
+
+// Creates the gates of a shift preceded by a zero row
+fn create_shift_gadget(bits: u32, shift: u32, side: RotMode) -> Vec<CircuitGate<Fp>> {
+    // gate for the zero value
+    let mut gates = vec![CircuitGate::<Fp>::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )];
+    CircuitGate::<Fp>::extend_shift(&mut gates, bits, shift, side, 0);
+    gates
+}
+
+// Creates the witness of a shift preceded by a zero row
+fn create_shift_witness(word: u64, bits: u32, shift: u32, side: RotMode) -> [Vec<Fp>; COLUMNS] {
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero()]);
+    shift::extend_shift_witness(&mut witness, word, bits, shift, side);
+    witness
+}
+
+// Checks the output and the constraints of a shift
+fn test_shift(word: u64, bits: u32, shift: u32, side: RotMode) {
+    let gates = create_shift_gadget(bits, shift, side);
+    let witness = create_shift_witness(word, bits, shift, side);
+
+    let mask = if bits == 64 {
+        u64::MAX
+    } else {
+        (1 << bits) - 1
+    };
+    let expected = match side {
+        RotMode::Left => (word << shift) & mask,
+        RotMode::Right => word >> shift,
+    };
+    let (row, col) = shift_output(1, bits, side);
+    assert_eq!(witness[col][row], Fp::from(expected));
+    assert_eq!(witness[0].len(), gates.len());
+
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+    for row in 0..cs.gates.len() {
+        assert_eq!(
+            cs.gates[row].verify_witness::<Vesta>(row, &witness, &cs, &witness[0][0..cs.public]),
+            Ok(())
+        );
+    }
+}
+
+#[test]
+// Test random shifts of random words, for all the widths and sides
+fn test_shift_random() {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    for bits in [32, 64] {
+        for side in [RotMode::Left, RotMode::Right] {
+            let word = rng.gen_range(0..2u128.pow(bits)) as u64;
+            let shift = rng.gen_range(1..bits);
+            test_shift(word, bits, shift, side);
+        }
+    }
+}
+
+#[test]
+// Test the extreme shift offsets
+fn test_shift_bounds() {
+    for bits in [32, 64] {
+        let word = if bits == 64 {
+            u64::MAX
+        } else {
+            u32::MAX.into()
+        };
+        for side in [RotMode::Left, RotMode::Right] {
+            test_shift(word, bits, 1, side);
+            test_shift(word, bits, bits - 1, side);
+        }
+    }
+}
+
+#[test]
+// End-to-end test
+fn test_prove_and_verify() {
+    let rng = &mut StdRng::from_seed(RNG_SEED);
+    for bits in [32, 64] {
+        for side in [RotMode::Left, RotMode::Right] {
+            let word = rng.gen_range(0..2u128.pow(bits)) as u64;
+            let shift = rng.gen_range(1..bits);
+            TestFramework::<Vesta>::default()
+                .gates(create_shift_gadget(bits, shift, side))
+                .witness(create_shift_witness(word, bits, shift, side))
+                .setup()
+                .prove_and_verify::<BaseSponge, ScalarSponge>()
+                .unwrap();
+        }
+    }
+}
+
+#[test]
+// Test that the output of a 32-bit left shift is constrained
+fn test_bad_shift_32() {
+    let gates = create_shift_gadget(32, 5, RotMode::Left);
+    let mut witness = create_shift_witness(0xdeadbeef, 32, 5, RotMode::Left);
+    let (row, col) = shift_output(1, 32, RotMode::Left);
+    witness[col][row] += Fp::one();
+
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+    assert!(cs.gates[row]
+        .verify_witness::<Vesta>(row, &witness, &cs, &witness[0][0..cs.public])
+        .is_err());
+
+    // changing the shifted word consistently in the generic gate breaks the copy constraint
+    witness[col][row] -= Fp::one();
+    witness[0][row] += Fp::from(1u64 << 32);
+    witness[col][row] += Fp::one();
+    let res = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>();
+    assert!(res.is_err());
+}
+
+#[should_panic]
+#[test]
+// Test that a shift by the length of the word fails as expected
+fn test_large_shift() {
+    create_shift_witness(1, 32, 32, RotMode::Left);
+}
+
+#[should_panic]
+#[test]
+// Test that a 32-bit shift of a larger word fails as expected
+fn test_large_word() {
+    create_shift_witness(1 << 32, 32, 1, RotMode::Right);
+}
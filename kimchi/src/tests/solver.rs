@@ -0,0 +1,314 @@
+use std::array;
+
+use super::{
+    ecdsa::verify_rows,
+    foreign_field_add::secp256k1_modulus,
+    framework::TestFramework,
+    rot::{create_rot_gadget, create_rot_witness},
+};
+use crate::{
+    circuits::{
+        builder::CircuitBuilder,
+        constraints::ConstraintSystem,
+        gate::{CircuitGate, Connect, GateType},
+        polynomial::COLUMNS,
+        polynomials::{
+            add_u64::extend_add_u64_witness,
+            foreign_field_add::witness::{create_chain, FFOps},
+            generic::{
+                testing::{create_circuit, fill_in_witness},
+                GenericGateSpec,
+            },
+            range_check::bytes::extend_range_check_bytes_witness,
+            rot::RotMode,
+            select::{select_cells, select_condition},
+            xor,
+        },
+        wires::Wire,
+        witness::{solve_partial_witness, solve_witness},
+    },
+    error::SolveError,
+};
+use ark_ff::{One, Zero};
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use num_bigint::BigUint;
+use o1_utils::{foreign_field::ForeignFieldHelpers, FieldHelpers};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+// Creates a circuit selecting between the last two public inputs by the first one
+fn create_select() -> Vec<CircuitGate<Fp>> {
+    let mut gates: Vec<_> = (0..3)
+        .map(|row| {
+            CircuitGate::create_generic_gadget(Wire::for_row(row), GenericGateSpec::Pub, None)
+        })
+        .collect();
+    CircuitGate::extend_select(&mut gates, 1);
+    let [x, y, _] = select_cells(3, 0);
+    gates.connect_cell_pair((0, 0), select_condition(3));
+    gates.connect_cell_pair((1, 0), x);
+    gates.connect_cell_pair((2, 0), y);
+    gates
+}
+
+#[test]
+// Test that the outputs of the generic gates are solved from their inputs
+fn test_solve_generic() {
+    let public = vec![Fp::from(3u64), Fp::from(5u64)];
+    let gates = create_circuit::<Fp>(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    // the inputs of the additions and multiplications, the constants are solved as well
+    let inputs: Vec<_> = (public.len()..public.len() + 10)
+        .flat_map(|row| [0, 1, 3, 4].map(|col| ((row, col), witness[col][row])))
+        .collect();
+    assert_eq!(solve_witness(&gates, &public, &inputs), Ok(witness));
+}
+
+#[test]
+// Test the witness of a circuit laid out by the circuit builder, solved from its public inputs
+fn test_solve_builder() {
+    let layout = |with_witness: bool| {
+        let mut builder = CircuitBuilder::<Fp>::new(with_witness);
+        let value = |value: u64| with_witness.then(|| Fp::from(value));
+        let x = builder.public_input(value(3));
+        let y = builder.public_input(value(5));
+        let z = builder.public_input(value(13));
+        // x * y + x - 5 = z
+        let five = builder.constant(Fp::from(5u64));
+        let xy = builder.mul(x, y);
+        let sum = builder.add(xy, x);
+        let diff = builder.sub(sum, five);
+        builder.assert_equal(diff, z);
+        builder.build()
+    };
+    let (gates, witness) = layout(true);
+    let witness = witness.unwrap();
+    assert_eq!(layout(false).0.len(), gates.len());
+    let public = witness[0][0..3].to_vec();
+    assert_eq!(solve_witness(&gates, &public, &[]), Ok(witness.clone()));
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(public)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+// Test that the output of a selection is solved once the condition is known
+fn test_solve_select() {
+    let gates = create_select();
+    let cs = ConstraintSystem::create(gates.clone())
+        .public(3)
+        .build()
+        .unwrap();
+    let [_, _, out] = select_cells(3, 0);
+    for (b, selected) in [(1u64, 7u64), (0, 9)] {
+        let public = [b, 7, 9].map(Fp::from).to_vec();
+        let witness = solve_witness(&gates, &public, &[]).unwrap();
+        assert_eq!(witness[out.1][out.0], Fp::from(selected));
+        assert_eq!(verify_rows(&cs, &witness), Ok(()));
+    }
+
+    // the output is not the selected value
+    let public = [1u64, 7, 9].map(Fp::from).to_vec();
+    assert_eq!(
+        solve_witness(&gates, &public, &[(out, Fp::from(9u64))]),
+        Err(SolveError::Conflict(out.0, out.1))
+    );
+    assert_eq!(
+        solve_witness(&gates, &public, &[((4, 0), Fp::zero())]),
+        Err(SolveError::InputOutOfRange(4, 0))
+    );
+}
+
+#[test]
+// Test that the gates without solver are solved from the rows of their witness functions,
+// and that the gates after them are solved from their cells
+fn test_solve_partial_witness() {
+    let mut gates = vec![];
+    let xor_row = gates.len();
+    CircuitGate::<Fp>::extend_xor_gadget(&mut gates, 16);
+    let add_row = gates.len();
+    gates.push(CircuitGate::create_generic_gadget(
+        Wire::for_row(add_row),
+        GenericGateSpec::Add {
+            left_coeff: None,
+            right_coeff: None,
+            output_coeff: None,
+        },
+        None,
+    ));
+    // the xor of the inputs is added to the first input
+    gates.connect_cell_pair((xor_row, 2), (add_row, 0));
+    gates.connect_cell_pair((xor_row, 0), (add_row, 1));
+
+    let (in1, in2) = (Fp::from(0x1234u64), Fp::from(0xabcdu64));
+    let mut xor_witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    xor::extend_xor_witness(&mut xor_witness, in1, in2, 16);
+    let partial = |with_xor: bool| -> [Vec<Option<Fp>>; COLUMNS] {
+        array::from_fn(|col| {
+            let mut column: Vec<_> = xor_witness[col]
+                .iter()
+                .map(|cell| with_xor.then_some(*cell))
+                .collect();
+            column.push(None);
+            column
+        })
+    };
+
+    let witness = solve_partial_witness(&gates, partial(true)).unwrap();
+    assert_eq!(witness[2][add_row], Fp::from(0x1234u64 ^ 0xabcd) + in1);
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+    // the xor rows are not determined without their inputs
+    assert_eq!(
+        solve_partial_witness(&gates, partial(false)),
+        Err(SolveError::Unsolved(xor_row, GateType::Xor16))
+    );
+    let mut short = partial(true);
+    short[3].pop();
+    assert_eq!(
+        solve_partial_witness(&gates, short),
+        Err(SolveError::ColumnLength(3, gates.len() - 1, gates.len()))
+    );
+}
+
+#[test]
+// Test that the gates whose cells are not determined by the inputs are reported
+fn test_solve_unsolved() {
+    let public = vec![Fp::from(3u64)];
+    let gates = create_circuit::<Fp>(0, public.len());
+    assert_eq!(
+        solve_witness(&gates, &public, &[]),
+        Err(SolveError::Unsolved(1, GateType::Generic))
+    );
+    // the condition of a selection is needed
+    let x = ((1, 0), Fp::from(7u64));
+    let y = ((2, 0), Fp::from(9u64));
+    assert_eq!(
+        solve_witness(&create_select(), &[], &[x, y]),
+        Err(SolveError::Unsolved(0, GateType::Generic))
+    );
+}
+
+#[test]
+// Test that the sum, the carry and the limbs of a 64-bit addition are solved from the words
+fn test_solve_add_u64() {
+    let mut gates = vec![];
+    CircuitGate::<Fp>::extend_add_u64(&mut gates);
+    for (a, b) in [(0x1234_5678_9abc_def0u64, 0xfedc_ba98u64), (u64::MAX, 2)] {
+        let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+        extend_add_u64_witness(&mut witness, a, b);
+        let inputs = [((0, 0), Fp::from(a)), ((0, 1), Fp::from(b))];
+        assert_eq!(solve_witness(&gates, &[], &inputs), Ok(witness));
+    }
+}
+
+#[test]
+// Test that the xor rows are solved from any two of their words
+fn test_solve_xor() {
+    let mut gates = vec![];
+    CircuitGate::<Fp>::extend_xor_gadget(&mut gates, 64);
+    let (in1, in2) = (0x0123_4567_89ab_cdefu64, 0xf0e1_d2c3_b4a5_9687u64);
+    let witness = xor::create_xor_witness(Fp::from(in1), Fp::from(in2), 64);
+    let out = Fp::from(in1 ^ in2);
+    for inputs in [
+        [((0, 0), Fp::from(in1)), ((0, 1), Fp::from(in2))],
+        [((0, 0), Fp::from(in1)), ((0, 2), out)],
+        [((0, 1), Fp::from(in2)), ((0, 2), out)],
+    ] {
+        assert_eq!(solve_witness(&gates, &[], &inputs), Ok(witness.clone()));
+    }
+}
+
+#[test]
+// Test that the rotation and the range check of the shifted word are solved from the word
+fn test_solve_rot() {
+    let word = 0x8123_4567_89ab_cdefu64;
+    for (rot, side) in [(1, RotMode::Left), (36, RotMode::Left), (7, RotMode::Right)] {
+        let gates = create_rot_gadget::<Vesta>(rot, side);
+        let witness = create_rot_witness::<Vesta>(word, rot, side);
+        let public = vec![Fp::zero()];
+        let inputs = [((1, 0), Fp::from(word))];
+        assert_eq!(solve_witness(&gates, &public, &inputs), Ok(witness));
+    }
+}
+
+#[test]
+// Test that the byte range check rows are solved from the checked value
+fn test_solve_range_check_bytes() {
+    for num_bytes in [4, 13, 31] {
+        let value = Fp::from_biguint(&((BigUint::from(1u32) << (8 * num_bytes)) / 3u32)).unwrap();
+        let mut gates = vec![];
+        CircuitGate::<Fp>::extend_range_check_bytes(&mut gates, num_bytes);
+        let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+        extend_range_check_bytes_witness(&mut witness, value, num_bytes);
+        assert_eq!(solve_witness(&gates, &[], &[((0, 0), value)]), Ok(witness));
+    }
+}
+
+#[test]
+// Test that the results, the overflows and the carries of a chain of foreign field additions
+// are solved from its inputs, up to the final bound
+fn test_solve_ffadd() {
+    let modulus = secp256k1_modulus();
+    let opcodes = [FFOps::Add, FFOps::Sub, FFOps::Double];
+    let inputs = vec![
+        &modulus - BigUint::from(1u32),
+        BigUint::from(5u32),
+        &modulus - BigUint::from(2u32),
+    ];
+    let mut gates = vec![CircuitGate::<Fp>::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )];
+    let mut next_row = 1;
+    CircuitGate::extend_chain_ffadd(&mut gates, 0, &mut next_row, &opcodes, &modulus);
+
+    // the public input with the 1 value, followed by the chain
+    let chain = create_chain::<Fp>(&inputs, &opcodes, modulus);
+    let witness: [Vec<Fp>; COLUMNS] = array::from_fn(|col| {
+        let public = if col == 0 { Fp::one() } else { Fp::zero() };
+        std::iter::once(public).chain(chain[col].clone()).collect()
+    });
+
+    // the inputs of the chain, and the right input of the bound addition
+    let bound_row = 1 + opcodes.len();
+    let cells = [
+        (1, 0),
+        (1, 1),
+        (1, 2),
+        (1, 3),
+        (1, 4),
+        (1, 5),
+        (2, 3),
+        (2, 4),
+        (2, 5),
+    ];
+    let mut inputs: Vec<_> = cells
+        .into_iter()
+        .map(|(row, col)| ((row, col), witness[col][row]))
+        .collect();
+    inputs.extend([
+        ((bound_row, 3), Fp::zero()),
+        ((bound_row, 4), Fp::zero()),
+        ((bound_row, 5), Fp::two_to_limb()),
+    ]);
+    let solved = solve_witness(&gates, &[Fp::one()], &inputs).unwrap();
+    assert_eq!(solved, witness);
+    let cs = ConstraintSystem::create(gates).public(1).build().unwrap();
+    assert_eq!(verify_rows(&cs, &solved), Ok(()));
+}
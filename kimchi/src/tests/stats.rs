@@ -0,0 +1,73 @@
+use crate::{
+    circuits::{
+        constraints::ConstraintSystem,
+        gate::{CircuitGate, GateType},
+        polynomials::{generic::testing::create_circuit, permutation::ZK_ROWS, xor},
+    },
+    error::SetupError,
+};
+use mina_curves::pasta::Fp;
+use std::collections::BTreeMap;
+
+#[test]
+// Test the statistics of a generic circuit, whose public rows hold a single generic gate
+fn test_stats_generic() {
+    let cs = ConstraintSystem::create(create_circuit::<Fp>(0, 2))
+        .public(2)
+        .build()
+        .unwrap();
+    let stats = cs.stats();
+    assert_eq!(stats.domain_size, 32);
+    assert_eq!(stats.rows, 22);
+    assert_eq!(stats.zk_rows, ZK_ROWS as usize);
+    assert_eq!(stats.free_rows(), 32 - 22 - ZK_ROWS as usize);
+    assert_eq!(stats.public, 2);
+    assert_eq!(stats.gates, BTreeMap::from([(GateType::Generic, 22)]));
+    assert_eq!(stats.single_generic_gates, 2);
+    assert_eq!(stats.lookups, 0);
+    assert_eq!(stats.wired_cells, [0; 7]);
+    assert!(stats.to_string().contains("Generic: 22 rows"));
+}
+
+#[test]
+// Test the statistics of a XOR gadget, with the lookups of its rows and its wired zero row
+fn test_stats_xor() {
+    let mut gates = vec![];
+    CircuitGate::<Fp>::extend_xor_gadget(&mut gates, 64);
+    let cs = ConstraintSystem::create(gates)
+        .lookup(vec![xor::lookup_table()])
+        .build()
+        .unwrap();
+    let stats = cs.stats();
+    assert_eq!(stats.rows, 5);
+    assert_eq!(
+        stats.gates,
+        BTreeMap::from([(GateType::Generic, 1), (GateType::Xor16, 4)])
+    );
+    assert_eq!(stats.single_generic_gates, 1);
+    assert_eq!(stats.lookups, 16);
+    assert_eq!(stats.wired_cells, [1, 1, 1, 0, 0, 0, 0]);
+}
+
+#[test]
+// Test that the statistics of a circuit are computed without building it
+fn test_builder_stats() {
+    let builder = ConstraintSystem::create(create_circuit::<Fp>(0, 2)).public(2);
+    let stats = builder.stats().unwrap();
+    assert_eq!(stats, builder.build().unwrap().stats());
+
+    // the lookup table of the xor gadget is larger than its gates
+    let mut gates = vec![];
+    CircuitGate::<Fp>::extend_xor_gadget(&mut gates, 64);
+    let builder = ConstraintSystem::create(gates).lookup(vec![xor::lookup_table()]);
+    let stats = builder.stats().unwrap();
+    assert_eq!(stats.rows, 5);
+    assert!(stats.domain_size > 256);
+    assert_eq!(stats, builder.clone().build().unwrap().stats());
+
+    // the reserved domain is too small for the lookup table
+    assert!(matches!(
+        builder.domain_size(256).stats(),
+        Err(SetupError::ConstraintSystem(_))
+    ));
+}
@@ -0,0 +1,238 @@
+use std::array;
+
+use super::{ecdsa::verify_rows, framework::TestFramework};
+use crate::{
+    circuits::{
+        builder::CircuitBuilder,
+        constraints::ConstraintSystem,
+        gate::{CircuitGate, CircuitGateError, Connect},
+        polynomial::COLUMNS,
+        polynomials::{generic::GenericGateSpec, mux::MuxLayout},
+        sub_circuit::SubCircuit,
+        wires::{Wire, PERMUTS},
+    },
+    error::WiringError,
+};
+use ark_ff::Zero;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+// The sub-circuit of the sum of two values, in a generic gate
+fn add() -> SubCircuit<Fp> {
+    let spec = GenericGateSpec::Add {
+        left_coeff: None,
+        right_coeff: None,
+        output_coeff: None,
+    };
+    let gate = CircuitGate::create_generic_gadget(Wire::for_row(0), spec, None);
+    SubCircuit::new(vec![gate])
+        .unwrap()
+        .with_input("left", (0, 0))
+        .with_input("right", (0, 1))
+        .with_output("sum", (0, 2))
+}
+
+// The sub-circuit of the sum of three values, from two instances of the sum of two values
+fn add3() -> SubCircuit<Fp> {
+    let add = add();
+    let mut gates = vec![];
+    let first = add.extend(&mut gates);
+    let second = add.extend(&mut gates);
+    gates.connect_cell_pair(first.output("sum").unwrap(), second.input("left").unwrap());
+    SubCircuit::new(gates)
+        .unwrap()
+        .with_input("a", first.input("left").unwrap())
+        .with_input("b", first.input("right").unwrap())
+        .with_input("c", second.input("right").unwrap())
+        .with_output("sum", second.output("sum").unwrap())
+}
+
+// Extends the witness with the rows of the sum of three values
+fn add3_witness(witness: &mut [Vec<Fp>; COLUMNS], values: &[Fp]) {
+    let partial = values[0] + values[1];
+    for row in [
+        [values[0], values[1], partial],
+        [partial, values[2], partial + values[2]],
+    ] {
+        for (col, cell) in witness.iter_mut().enumerate() {
+            cell.push(row.get(col).copied().unwrap_or_else(Fp::zero));
+        }
+    }
+}
+
+#[test]
+// Test that an instance of a sub-circuit has the gates of its gadget created at its row
+fn test_sub_circuit_instances() {
+    const INDEX_TABLE_ID: i32 = 5;
+    let (_, gates) = CircuitGate::<Fp>::create_mux(0, 5, INDEX_TABLE_ID);
+    let layout = MuxLayout::new(0, 5);
+    let mut mux = SubCircuit::new(gates).unwrap();
+    for (i, cell) in layout.values.iter().enumerate() {
+        mux = mux.with_input(&format!("value{i}"), *cell);
+    }
+    let mux = mux
+        .with_input("index", layout.index)
+        .with_output("output", layout.output);
+    assert_eq!(mux.num_rows(), layout.next_row);
+    assert_eq!(mux.output("output"), Some(layout.output));
+    assert_eq!(mux.input("output"), None);
+
+    for row in [0, 3, 17] {
+        let (instance, gates) = mux.create(row);
+        let (next_row, mux_gates) = CircuitGate::<Fp>::create_mux(row, 5, INDEX_TABLE_ID);
+        assert_eq!(instance.start_row, row);
+        assert_eq!(instance.next_row, next_row);
+        for (gate, mux_gate) in gates.iter().zip(&mux_gates) {
+            assert_eq!(gate.typ, mux_gate.typ);
+            assert_eq!(gate.wires, mux_gate.wires);
+            assert_eq!(gate.coeffs, mux_gate.coeffs);
+        }
+        assert_eq!(gates.len(), mux_gates.len());
+
+        let layout = MuxLayout::new(row, 5);
+        assert_eq!(instance.input("value3"), Some(layout.values[3]));
+        assert_eq!(instance.input("index"), Some(layout.index));
+        assert_eq!(instance.output("output"), Some(layout.output));
+    }
+}
+
+#[test]
+// Test that the instances of a sub-circuit built from other sub-circuits are proven,
+// with their inputs copied from the public inputs
+fn test_sub_circuit_composition() {
+    let add3 = add3();
+    assert_eq!(add3.num_rows(), 2);
+    let mut gates: Vec<_> = (0..3)
+        .map(|row| {
+            CircuitGate::create_generic_gadget(Wire::for_row(row), GenericGateSpec::Pub, None)
+        })
+        .collect();
+    let first = add3.extend(&mut gates);
+    let second = add3.extend(&mut gates);
+    assert_eq!(second.start_row, 5);
+    for (row, name) in ["a", "b", "c"].into_iter().enumerate() {
+        gates.connect_cell_pair((row, 0), first.input(name).unwrap());
+        gates.connect_cell_pair(first.input(name).unwrap(), second.input(name).unwrap());
+    }
+
+    let public = vec![Fp::from(3u64), Fp::from(5u64), Fp::from(8u64)];
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|col| {
+        if col == 0 {
+            public.clone()
+        } else {
+            vec![Fp::zero(); public.len()]
+        }
+    });
+    add3_witness(&mut witness, &public);
+    add3_witness(&mut witness, &public);
+    let (row, col) = second.output("sum").unwrap();
+    assert_eq!(witness[col][row], Fp::from(16u64));
+
+    let cs = ConstraintSystem::create(gates.clone())
+        .public(public.len())
+        .build()
+        .unwrap();
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+    // the inputs of the second instance are copied from the public inputs
+    let mut wrong_witness = witness.clone();
+    wrong_witness[1][second.start_row + 1] = Fp::from(9u64);
+    wrong_witness[2][second.start_row + 1] = Fp::from(17u64);
+    assert!(matches!(
+        verify_rows(&cs, &wrong_witness),
+        Err(CircuitGateError::CopyConstraint { .. })
+    ));
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(public)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+// Lays out the sum of the first three public inputs with the circuit builder,
+// constrained to be the last public input
+fn builder_add3(values: [u64; 4]) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS]) {
+    let mut builder = CircuitBuilder::<Fp>::new(true);
+    let [a, b, c, sum] = values.map(|value| builder.public_input(Some(value.into())));
+    let outputs = builder.sub_circuit(
+        &add3(),
+        add3_witness,
+        &[(a, "a"), (b, "b"), (c, "c")],
+        &["sum"],
+    );
+    let expected = Fp::from(values[0] + values[1] + values[2]);
+    assert_eq!(builder.value(outputs[0]), Some(expected));
+    builder.assert_equal(outputs[0], sum);
+    let (gates, witness) = builder.build();
+    (gates, witness.unwrap())
+}
+
+#[test]
+// Test the instances of a sub-circuit laid out by the circuit builder, from its variables
+fn test_builder_sub_circuit() {
+    let (gates, witness) = builder_add3([2, 3, 4, 9]);
+    let public = witness[0][0..4].to_vec();
+    let cs = ConstraintSystem::create(gates.clone())
+        .public(4)
+        .build()
+        .unwrap();
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(public)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+
+    // the last public input is not the sum
+    let (gates, witness) = builder_add3([2, 3, 5, 9]);
+    let cs = ConstraintSystem::create(gates).public(4).build().unwrap();
+    assert!(matches!(
+        verify_rows(&cs, &witness),
+        Err(CircuitGateError::CopyConstraint { .. })
+    ));
+}
+
+#[test]
+// Test that the gates wired outside of a sub-circuit are rejected
+fn test_sub_circuit_wiring() {
+    let mut gates = vec![CircuitGate::<Fp>::create_generic_gadget(
+        Wire::for_row(0),
+        GenericGateSpec::Pub,
+        None,
+    )];
+    gates[0].wires[0] = Wire::new(1, 0);
+    assert_eq!(
+        SubCircuit::new(gates).unwrap_err(),
+        WiringError::RowOutOfRange {
+            cell: Wire::new(0, 0),
+            wire: Wire::new(1, 0),
+            rows: 1,
+        }
+    );
+}
+
+#[test]
+#[should_panic]
+// Test that the cells of the columns which are not wired can not be named
+fn test_sub_circuit_unwired_cell() {
+    add().with_output("carry", (0, PERMUTS));
+}
+
+#[test]
+#[should_panic]
+// Test that a name is used by a single cell
+fn test_sub_circuit_duplicate_name() {
+    add().with_output("left", (0, 3));
+}
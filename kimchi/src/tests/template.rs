@@ -0,0 +1,182 @@
+use crate::{
+    circuit,
+    circuits::{
+        gate::CircuitGate,
+        polynomials::{
+            rot::{self, RotMode},
+            xor,
+        },
+    },
+};
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use o1_utils::FieldHelpers;
+
+use super::framework::TestFramework;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+const ROT: u32 = 7;
+
+circuit! {
+    /// Rotates the XOR of two public 64-bit words
+    mod xor_rot {
+        public { zero, left, right }
+        private {}
+        gadget xor {
+            gates(gates, _layout) => CircuitGate::extend_xor_gadget(gates, 64),
+            witness(witness, public, _private) => {
+                xor::extend_xor_witness(witness, public.left, public.right, 64)
+            },
+        }
+        gadget rot {
+            gates(gates, layout) => CircuitGate::extend_rot(gates, ROT, RotMode::Left, layout.zero),
+            witness(witness, public, _private) => {
+                let word = public.left.to_biguint() ^ public.right.to_biguint();
+                rot::extend_rot(witness, u64::try_from(&word).unwrap(), ROT, RotMode::Left)
+            },
+        }
+        copy {
+            (left, 0, 0) == (xor, 0, 0),
+            (right, 0, 0) == (xor, 0, 1),
+            (xor, 0, 2) == (rot, 0, 0),
+        }
+    }
+}
+
+circuit! {
+    /// A circuit with a private input only
+    mod private_xor {
+        public {}
+        private { word }
+        gadget xor {
+            gates(gates, _layout) => CircuitGate::extend_xor_gadget(gates, 16),
+            witness(witness, _public, private) => {
+                xor::extend_xor_witness(witness, private.word, private.word, 16)
+            },
+        }
+    }
+}
+
+fn xor_rot_public(left: u64, right: u64) -> xor_rot::Public<Fp> {
+    xor_rot::Public {
+        zero: Fp::from(0u8),
+        left: Fp::from(left),
+        right: Fp::from(right),
+    }
+}
+
+#[test]
+fn test_template_layout() {
+    let (layout, gates) = xor_rot::gates::<Fp>();
+
+    // 3 public inputs, 4 Xor16 and a zero row, then a Rot64 and its range check
+    assert_eq!(
+        layout,
+        xor_rot::Layout {
+            zero: 0,
+            left: 1,
+            right: 2,
+            xor: 3,
+            rot: 8,
+            num_rows: 10,
+        }
+    );
+    assert_eq!(gates.len(), layout.num_rows);
+    assert_eq!(xor_rot::Public::<Fp>::LEN, 3);
+    assert_eq!(xor_rot::Public::<Fp>::NAMES, &["zero", "left", "right"]);
+    assert_eq!(
+        xor_rot_public(1, 2).to_vec(),
+        vec![Fp::from(0u8), Fp::from(1u8), Fp::from(2u8)]
+    );
+
+    // the copy constraints are applied
+    assert_eq!(gates[layout.left].wires[0].row, layout.xor);
+    assert_eq!(gates[layout.xor].wires[2].row, layout.rot);
+
+    let (layout, gates) = private_xor::gates::<Fp>();
+    assert_eq!(private_xor::Public::<Fp>::LEN, 0);
+    assert_eq!(layout.xor, 0);
+    assert_eq!(gates.len(), layout.num_rows);
+}
+
+#[test]
+fn test_template_costs() {
+    let (layout, _) = xor_rot::gates::<Fp>();
+    let costs = xor_rot::costs::<Fp>();
+    let names: Vec<_> = costs.sections.iter().map(|section| section.name).collect();
+    assert_eq!(names, ["public", "xor", "rot"]);
+
+    // 4 Xor16 rows of 4 lookups, and the Rot64 and RangeCheck0 rows of 4 lookups
+    let public = costs.section("public").unwrap();
+    let xor = costs.section("xor").unwrap();
+    let rot = costs.section("rot").unwrap();
+    assert_eq!((public.rows, public.lookups), (3, 0));
+    assert_eq!((xor.rows, xor.lookups), (5, 16));
+    assert_eq!((rot.rows, rot.lookups), (2, 8));
+    assert!(public.copied_cells >= 2 && xor.copied_cells >= 3 && rot.copied_cells >= 1);
+
+    let total = costs.total();
+    assert_eq!(total.rows, layout.num_rows);
+    assert_eq!(total.lookups, 24);
+    assert_eq!(
+        total.copied_cells,
+        public.copied_cells + xor.copied_cells + rot.copied_cells
+    );
+    let report = costs.to_string();
+    assert!(report.contains("\nxor: 5 rows, 16 lookups, "));
+    assert!(report.ends_with(&total.to_string()));
+    assert!(total
+        .to_string()
+        .starts_with("total: 10 rows, 24 lookups, "));
+}
+
+#[test]
+fn test_template_prove_and_verify() {
+    let public = xor_rot_public(0x0123456789abcdef, 0xfedcba9876543210);
+    let witness = xor_rot::witness(&public, &xor_rot::Private::default());
+    let (_, gates) = xor_rot::gates();
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(public.to_vec())
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+
+    let private = private_xor::Private {
+        word: Fp::from(0xbeefu64),
+    };
+    let witness = private_xor::witness(&private_xor::Public::default(), &private);
+    let (_, gates) = private_xor::gates();
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+fn test_template_wrong_public_input() {
+    let public = xor_rot_public(0x0123456789abcdef, 0xfedcba9876543210);
+    let witness = xor_rot::witness(&public, &xor_rot::Private::default());
+    let (_, gates) = xor_rot::gates();
+
+    // the copy constraints bind the public inputs to the gadgets
+    let wrong = xor_rot_public(0x0123456789abcdef, 0);
+    let res = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(wrong.to_vec())
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>();
+    assert!(res.is_err());
+}
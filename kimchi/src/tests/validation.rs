@@ -0,0 +1,118 @@
+use super::public_output;
+use crate::{
+    circuits::{
+        constraints::ConstraintSystem,
+        gate::CircuitGate,
+        polynomials::{
+            generic::testing::{create_circuit, fill_in_witness},
+            range_check,
+        },
+        wires::COLUMNS,
+    },
+    error::WitnessError,
+    prover_index::testing::{
+        new_index_for_test, new_index_for_test_with_cs, new_index_for_test_with_lookups,
+    },
+    validation::validate_witness,
+};
+use ark_ff::{Field, One, Zero};
+use ark_poly::EvaluationDomain;
+use mina_curves::pasta::{Fp, Vesta};
+use o1_utils::foreign_field::LIMB_BITS;
+use std::array;
+
+#[test]
+// Test the shape and public input checks on the generic test circuit
+fn test_validate_generic_witness() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+    let index = new_index_for_test::<Vesta>(gates, public.len());
+    assert_eq!(validate_witness(&index, &witness, &public), Ok(()));
+
+    // the columns of the witness have different lengths
+    let mut short = witness.clone();
+    let rows = short[0].len();
+    short[2].pop();
+    assert_eq!(
+        validate_witness(&index, &short, &public),
+        Err(WitnessError::ColumnLength(2, rows - 1, rows))
+    );
+
+    // the witness fills the zero-knowledge rows
+    let mut long = witness.clone();
+    let size = index.cs.domain.d1.size();
+    for col in &mut long {
+        col.resize(size, Fp::zero());
+    }
+    assert!(matches!(
+        validate_witness(&index, &long, &public),
+        Err(WitnessError::TooManyRows(rows, _)) if rows == size
+    ));
+
+    // the public input is not the one of the witness
+    assert_eq!(
+        validate_witness(&index, &witness, &public[1..]),
+        Err(WitnessError::PublicInputLength(5, 4))
+    );
+    let mut other = public;
+    other[3] = Fp::from(4u8);
+    assert_eq!(
+        validate_witness(&index, &witness, &other),
+        Err(WitnessError::PublicInputMismatch(3))
+    );
+}
+
+#[test]
+// Test that the public outputs are checked against their output cells,
+// as their rows of the witness are filled by the prover
+fn test_validate_public_output_witness() {
+    let cs = ConstraintSystem::create(public_output::create_circuit())
+        .public(1)
+        .public_outputs(&[public_output::OUTPUT])
+        .build()
+        .unwrap();
+    let index = new_index_for_test_with_cs::<Vesta>(cs);
+    let x = Fp::from(3u8);
+    let witness = public_output::create_witness(x);
+    assert_eq!(witness[0][1], Fp::zero());
+    assert_eq!(
+        validate_witness(&index, &witness, &[x, Fp::from(12u8)]),
+        Ok(())
+    );
+    assert_eq!(
+        validate_witness(&index, &witness, &[x, Fp::from(13u8)]),
+        Err(WitnessError::PublicInputMismatch(1))
+    );
+    assert_eq!(
+        validate_witness(&index, &witness, &[x + Fp::one(), Fp::from(12u8)]),
+        Err(WitnessError::PublicInputMismatch(0))
+    );
+}
+
+#[test]
+// Test that the values of the range checks of the witness are checked to fit in 88 bits
+fn test_validate_range_check_witness() {
+    let (_, gates) = CircuitGate::<Fp>::create_multi_range_check(0);
+    let index = new_index_for_test_with_lookups::<Vesta>(
+        gates,
+        0,
+        0,
+        vec![range_check::gadget::lookup_table()],
+        None,
+        false,
+    );
+    let max = Fp::from(2u8).pow([LIMB_BITS as u64]) - Fp::one();
+    let witness = range_check::witness::create_multi(max, Fp::zero(), max);
+    assert_eq!(validate_witness(&index, &witness, &[]), Ok(()));
+
+    for row in 0..3 {
+        let mut witness = witness.clone();
+        witness[0][row] = max + Fp::one();
+        assert_eq!(
+            validate_witness(&index, &witness, &[]),
+            Err(WitnessError::RangeCheck(row))
+        );
+    }
+}
@@ -3,26 +3,23 @@ use crate::circuits::{
     polynomials::varbasemul,
     wires::*,
 };
-use crate::tests::framework::TestFramework;
+use crate::tests::framework::{TestCurve, TestFramework};
 use ark_ec::{AffineCurve, ProjectiveCurve};
 use ark_ff::{BigInteger, BitIteratorLE, Field, One, PrimeField, UniformRand, Zero};
 use colored::Colorize;
-use mina_curves::pasta::{Fp as F, Pallas as Other, Vesta, VestaParameters};
-use mina_poseidon::{
-    constants::PlonkSpongeConstantsKimchi,
-    sponge::{DefaultFqSponge, DefaultFrSponge},
-};
+use mina_curves::pasta::{Pallas, Vesta};
+use o1_utils::FieldHelpers;
+use poly_commitment::commitment::CommitmentCurve;
 use rand::{rngs::StdRng, SeedableRng};
 use std::array;
 use std::time::Instant;
 
-type SpongeParams = PlonkSpongeConstantsKimchi;
-type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
-type ScalarSponge = DefaultFrSponge<F, SpongeParams>;
-
-#[test]
-fn varbase_mul_test() {
-    let num_bits = F::size_in_bits();
+// Proves the variable base scalar multiplications of points of the other curve of G
+fn varbase_mul<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
+    let num_bits = G::ScalarField::size_in_bits();
     let chunks = num_bits / 5;
 
     let num_scalars = 10;
@@ -46,42 +43,41 @@ fn varbase_mul_test() {
         ));
     }
 
-    let mut witness: [Vec<F>; COLUMNS] =
-        array::from_fn(|_| vec![F::zero(); rows_per_scalar * num_scalars]);
+    let mut witness: [Vec<G::ScalarField>; COLUMNS] =
+        array::from_fn(|_| vec![G::ScalarField::zero(); rows_per_scalar * num_scalars]);
 
     let rng = &mut StdRng::from_seed([0; 32]);
 
     let start = Instant::now();
     for i in 0..num_scalars {
-        let x = F::rand(rng);
+        let x = G::ScalarField::rand(rng);
         let bits_lsb: Vec<_> = BitIteratorLE::new(x.into_repr()).take(num_bits).collect();
-        let x_ = <Other as AffineCurve>::ScalarField::from_repr(
-            <F as PrimeField>::BigInt::from_bits_le(&bits_lsb[..]),
-        )
+        let x_ = G::BaseField::from_repr(<G::BaseField as PrimeField>::BigInt::from_bits_le(
+            &bits_lsb[..],
+        ))
         .unwrap();
 
-        let base = Other::prime_subgroup_generator();
-        let g = Other::prime_subgroup_generator().into_projective();
-        let acc = (g + g).into_affine();
-        let acc = (acc.x, acc.y);
+        let base = G::OtherCurve::prime_subgroup_generator();
+        let g = base.into_projective();
+        let acc = (g + g).into_affine().to_coordinates().unwrap();
 
         let bits_msb: Vec<_> = bits_lsb.iter().take(num_bits).copied().rev().collect();
 
         let res = varbasemul::witness(
             &mut witness,
             i * rows_per_scalar,
-            (base.x, base.y),
+            base.to_coordinates().unwrap(),
             &bits_msb,
             acc,
         );
 
-        let shift = <Other as AffineCurve>::ScalarField::from(2).pow([(bits_msb.len()) as u64]);
+        let shift = G::BaseField::from(2u64).pow([(bits_msb.len()) as u64]);
         let expected = g
-            .mul((<Other as AffineCurve>::ScalarField::one() + shift + x_.double()).into_repr())
+            .mul((G::BaseField::one() + shift + x_.double()).into_repr())
             .into_affine();
 
-        assert_eq!(x_.into_repr(), res.n.into_repr());
-        assert_eq!((expected.x, expected.y), res.acc);
+        assert_eq!(x_.to_biguint(), res.n.to_biguint());
+        assert_eq!(expected.to_coordinates(), Some(res.acc));
     }
     println!(
         "{}{:?}",
@@ -89,10 +85,16 @@ fn varbase_mul_test() {
         start.elapsed()
     );
 
-    TestFramework::<Vesta>::default()
+    TestFramework::<G>::default()
         .gates(gates)
         .witness(witness)
         .setup()
-        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>()
         .unwrap();
 }
+
+#[test]
+fn varbase_mul_test() {
+    varbase_mul::<Vesta>();
+    varbase_mul::<Pallas>();
+}
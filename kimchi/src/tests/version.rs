@@ -0,0 +1,77 @@
+use super::framework::TestFramework;
+use crate::circuits::polynomials::generic::testing::{create_circuit, fill_in_witness};
+use crate::circuits::wires::COLUMNS;
+use crate::error::VerifyError;
+use crate::proof::ProverProof;
+use crate::verifier::verify;
+use crate::verifier_index::VerifierIndex;
+use crate::version::{ArithmetizationVersion, ARITHMETIZATION_VERSION};
+use ark_ff::Zero;
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use poly_commitment::commitment::CommitmentCurve;
+use std::array;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+#[test]
+// Test that the verifier checks the arithmetization versions of the proof and of the verifier index
+fn test_arithmetization_version() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let test_runner = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness.clone())
+        .public_inputs(public.clone())
+        .setup();
+    let index = test_runner.prover_index();
+    let mut verifier_index = index.verifier_index();
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let mut proof =
+        ProverProof::create::<BaseSponge, ScalarSponge>(&group_map, witness, &[], index).unwrap();
+    assert_eq!(proof.arithmetization_version, ARITHMETIZATION_VERSION);
+    assert_eq!(
+        verifier_index.arithmetization_version,
+        ARITHMETIZATION_VERSION
+    );
+    assert!(ARITHMETIZATION_VERSION.is_supported());
+    verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof, &public)
+        .unwrap();
+
+    // the verifier indexes serialized without a version are of the first version
+    let mut serialized = serde_json::to_value(&verifier_index).unwrap();
+    serialized
+        .as_object_mut()
+        .unwrap()
+        .remove("arithmetization_version")
+        .unwrap();
+    let deserialized: VerifierIndex<Vesta> = serde_json::from_str(&serialized.to_string()).unwrap();
+    assert_eq!(
+        deserialized.arithmetization_version,
+        ArithmetizationVersion::V1
+    );
+
+    // a proof of another version
+    let next = ArithmetizationVersion(ARITHMETIZATION_VERSION.0 + 1);
+    proof.arithmetization_version = next;
+    assert!(matches!(
+        verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof, &public),
+        Err(VerifyError::ArithmetizationVersionMismatch(v, w)) if v == next && w == ARITHMETIZATION_VERSION
+    ));
+
+    // a version which is not supported by the build
+    verifier_index.arithmetization_version = next;
+    assert!(matches!(
+        verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, &proof, &public),
+        Err(VerifyError::UnsupportedArithmetizationVersion(v)) if v == next
+    ));
+}
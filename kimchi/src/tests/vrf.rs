@@ -0,0 +1,102 @@
+use std::array;
+
+use super::{ecdsa::verify_rows, framework::TestFramework};
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::CircuitGate,
+    polynomial::COLUMNS,
+    polynomials::vrf::{self, VrfInput},
+};
+use ark_ec::{AffineCurve, ProjectiveCurve};
+use ark_ff::{BigInteger, PrimeField, UniformRand};
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Fq, Pallas, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use poly_commitment::commitment::CommitmentCurve;
+use rand::{rngs::StdRng, SeedableRng};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+// Returns a random secret key and its public key
+fn keypair() -> (Fq, Pallas) {
+    let secret_key = Fq::rand(&mut StdRng::from_seed([0; 32]));
+    let public_key = Pallas::prime_subgroup_generator()
+        .mul(secret_key.into_repr())
+        .into_affine();
+    (secret_key, public_key)
+}
+
+// Creates the gadget verifying the evaluation of the VRF on a message, and its witness
+fn create_vrf(
+    message: &[Fp],
+    public_key: &Pallas,
+    secret_key: Fq,
+) -> (Vec<CircuitGate<Fp>>, [Vec<Fp>; COLUMNS], Option<Fp>) {
+    let (next_row, gates) = CircuitGate::create_vrf::<Pallas>(0, message.len());
+    assert_eq!(next_row, gates.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let output = vrf::extend_vrf_witness(&mut witness, message, public_key, secret_key);
+    assert_eq!(witness[0].len(), next_row);
+    (gates, witness, output)
+}
+
+#[test]
+// Test that the point of a message is the one of the group map of the polynomial commitments, with an even y
+fn test_vrf_message_point() {
+    let group_map = <Pallas as CommitmentCurve>::Map::setup();
+    for len in 1..=4u64 {
+        let message: Vec<Fp> = (0..len).map(Fp::from).collect();
+        let point = vrf::vrf_message_point::<Pallas>(&message);
+        let (x, y) = group_map.to_group(vrf::vrf_message_hash::<Pallas>(&message));
+        assert_eq!(point.x, x);
+        assert!(point.y == y || point.y == -y);
+        assert!(point.y.into_repr().is_even());
+        assert!(point.is_on_curve());
+    }
+}
+
+#[test]
+// Test that the evaluation of the VRF satisfies the gadget, and is proven
+fn test_vrf_valid() {
+    let message: Vec<Fp> = (1..=3u64).map(Fp::from).collect();
+    let (secret_key, public_key) = keypair();
+    let (gates, witness, output) = create_vrf(&message, &public_key, secret_key);
+    assert_eq!(
+        output,
+        Some(vrf::vrf_output::<Pallas>(&message, secret_key))
+    );
+    let cs = ConstraintSystem::create(gates.clone()).build().unwrap();
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+    // the inputs are at their positions
+    let (row, col) = vrf::vrf_input(0, message.len(), VrfInput::Message(2));
+    assert_eq!(witness[col][row], message[2]);
+    let (row, col) = vrf::vrf_input(0, message.len(), VrfInput::PublicKeyY);
+    assert_eq!(witness[col][row], public_key.y);
+    let (row, col) = vrf::vrf_input(0, message.len(), VrfInput::Output);
+    assert_eq!(Some(witness[col][row]), output);
+
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
+
+#[test]
+// Test that the evaluation with a secret key of another public key does not satisfy the gadget
+fn test_vrf_wrong_public_key() {
+    let message = vec![Fp::from(7u64)];
+    let (secret_key, public_key) = keypair();
+    let other = public_key.into_projective().double().into_affine();
+    let (gates, witness, output) = create_vrf(&message, &other, secret_key);
+    assert_eq!(output, None);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+    assert!(verify_rows(&cs, &witness).is_err());
+}
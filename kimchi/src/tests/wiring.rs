@@ -0,0 +1,147 @@
+use crate::{
+    circuits::{
+        constraints::ConstraintSystem,
+        gate::{check_wiring, CircuitGate, Connect},
+        polynomials::generic::testing::create_circuit,
+        wires::{Wire, PERMUTS},
+    },
+    error::{SetupError, WiringError},
+};
+use mina_curves::pasta::Fp;
+use std::collections::BTreeSet;
+
+// Builds the constraint system of a circuit, returning the error of its wiring if any
+fn build_wiring(gates: Vec<CircuitGate<Fp>>) -> Result<(), WiringError> {
+    match ConstraintSystem::create(gates).build() {
+        Ok(_) => Ok(()),
+        Err(SetupError::Wiring(err)) => Err(err),
+        Err(err) => panic!("unexpected setup error: {err}"),
+    }
+}
+
+// The cells of the cycle of a cell
+fn cycle(gates: &[CircuitGate<Fp>], cell: (usize, usize)) -> BTreeSet<(usize, usize)> {
+    let mut cells = BTreeSet::from([cell]);
+    let mut wire = gates[cell.0].wires[cell.1];
+    while (wire.row, wire.col) != cell {
+        cells.insert((wire.row, wire.col));
+        wire = gates[wire.row].wires[wire.col];
+    }
+    cells
+}
+
+#[test]
+// Test that the circuits wired by connecting pairs of cells are permutations
+fn test_wiring() {
+    let mut gates = create_circuit::<Fp>(0, 3);
+    assert_eq!(check_wiring(&gates), Ok(()));
+    gates.connect_cell_pair((0, 0), (4, 2));
+    gates.connect_cell_pair((4, 2), (7, 6));
+    gates.connect_cell_pair((1, 0), (2, 0));
+    assert_eq!(check_wiring(&gates), Ok(()));
+    assert_eq!(build_wiring(gates), Ok(()));
+}
+
+#[test]
+// Test that the wires pointing outside of the wired cells of the circuit are rejected
+fn test_wiring_out_of_range() {
+    let gates = create_circuit::<Fp>(0, 3);
+    let rows = gates.len();
+
+    let mut wrong_column = gates.clone();
+    wrong_column[2].wires[1] = Wire::new(5, PERMUTS);
+    let err = WiringError::ColumnOutOfRange {
+        cell: Wire::new(2, 1),
+        wire: Wire::new(5, PERMUTS),
+    };
+    assert_eq!(check_wiring(&wrong_column), Err(err));
+    assert_eq!(build_wiring(wrong_column), Err(err));
+
+    // the padding rows of the domain can not be wired
+    let mut wrong_row = gates;
+    wrong_row[rows - 1].wires[6] = Wire::new(rows, 0);
+    let err = WiringError::RowOutOfRange {
+        cell: Wire::new(rows - 1, 6),
+        wire: Wire::new(rows, 0),
+        rows,
+    };
+    assert_eq!(check_wiring(&wrong_row), Err(err));
+    assert_eq!(build_wiring(wrong_row), Err(err));
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "the cell {},6 is wired to the row {rows}, outside of the {rows} rows of the circuit",
+            rows - 1
+        )
+    );
+}
+
+#[test]
+// Test that the wires whose cycles do not close are rejected, with the cells wired to the same cell
+fn test_wiring_unclosed_cycle() {
+    let mut gates = create_circuit::<Fp>(0, 3);
+    // the cell (0, 0) is wired to itself, and the cell (3, 2) is wired to it as well
+    gates[3].wires[2] = Wire::new(0, 0);
+    let err = WiringError::SharedWire {
+        cells: [Wire::new(0, 0), Wire::new(3, 2)],
+        wire: Wire::new(0, 0),
+    };
+    assert_eq!(check_wiring(&gates), Err(err));
+    assert_eq!(build_wiring(gates.clone()), Err(err));
+
+    // closing the cycle fixes the wiring
+    gates[0].wires[0] = Wire::new(3, 2);
+    assert_eq!(build_wiring(gates), Ok(()));
+}
+
+#[test]
+// Test that the cycles of the pairs of cells connected in a batch are merged,
+// whatever the order of the pairs and the wires of their cells
+fn test_connect_cell_pairs() {
+    let mut gates = create_circuit::<Fp>(0, 3);
+    gates.connect_cell_pair((0, 0), (4, 2));
+    gates.connect_cell_pairs(&[((7, 6), (1, 0)), ((4, 2), (7, 6)), ((0, 0), (1, 0))]);
+    assert_eq!(check_wiring(&gates), Ok(()));
+    let cells = BTreeSet::from([(0, 0), (1, 0), (4, 2), (7, 6)]);
+    for cell in &cells {
+        assert_eq!(cycle(&gates, *cell), cells);
+    }
+    assert_eq!(cycle(&gates, (2, 0)), BTreeSet::from([(2, 0)]));
+
+    // connecting a pair of cells twice splits their cycle, but not in a batch
+    let mut split = create_circuit::<Fp>(0, 3);
+    split.connect_cell_pair((0, 0), (4, 2));
+    split.connect_cell_pair((0, 0), (4, 2));
+    assert_eq!(cycle(&split, (0, 0)), BTreeSet::from([(0, 0)]));
+    gates.connect_cell_pairs(&[((0, 0), (4, 2))]);
+    assert_eq!(cycle(&gates, (0, 0)), cells);
+}
+
+#[test]
+// Test that the copies declared to the builder are resolved into the wires of the circuit
+fn test_builder_copies() {
+    let copies = [((0, 0), (4, 2)), ((4, 2), (7, 6)), ((1, 0), (2, 0))];
+    let builder = ConstraintSystem::create(create_circuit::<Fp>(0, 3))
+        .public(3)
+        .copies(&copies[..2])
+        .copies(&copies[2..]);
+    assert_eq!(builder.stats().unwrap().wired_cells, [3, 0, 1, 0, 0, 0, 1]);
+    let cs = builder.build().unwrap();
+
+    let mut gates = create_circuit::<Fp>(0, 3);
+    gates.connect_cell_pairs(&copies);
+    for (gate, cs_gate) in gates.iter().zip(&cs.gates) {
+        assert_eq!(gate.wires, cs_gate.wires);
+    }
+    assert_eq!(
+        cycle(&cs.gates, (7, 6)),
+        BTreeSet::from([(0, 0), (4, 2), (7, 6)])
+    );
+}
+
+#[test]
+#[should_panic]
+// Test that the copies are declared between wired cells
+fn test_builder_copies_unwired_cell() {
+    let _ = ConstraintSystem::create(create_circuit::<Fp>(0, 3)).copies(&[((0, 0), (1, PERMUTS))]);
+}
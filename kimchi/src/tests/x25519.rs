@@ -0,0 +1,89 @@
+use std::array;
+
+use super::ecdsa::verify_rows;
+use crate::circuits::{
+    constraints::ConstraintSystem,
+    gate::CircuitGate,
+    polynomial::COLUMNS,
+    polynomials::x25519::{self, X25519Elem, X25519_BASE_POINT},
+};
+use mina_curves::pasta::Fp;
+use num_bigint::BigUint;
+use o1_utils::foreign_field::BigUintForeignFieldHelpers;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+// The first test vector of the X25519 function in RFC 7748
+const RFC_SCALAR: &str = "a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4";
+const RFC_U: &str = "e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c";
+const RFC_OUTPUT: &str = "c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552";
+
+fn from_hex(hex: &str) -> [u8; 32] {
+    hex::decode(hex).unwrap().try_into().unwrap()
+}
+
+fn base_point() -> [u8; 32] {
+    let mut u = [0u8; 32];
+    u[0] = X25519_BASE_POINT;
+    u
+}
+
+#[test]
+// Test the X25519 function out of the circuit, with the test vector of RFC 7748 and a Diffie-Hellman exchange
+fn test_x25519_function() {
+    assert_eq!(
+        x25519::x25519(&from_hex(RFC_SCALAR), &from_hex(RFC_U)),
+        from_hex(RFC_OUTPUT)
+    );
+
+    let rng = &mut StdRng::seed_from_u64(0);
+    let (alice, bob): ([u8; 32], [u8; 32]) = (rng.gen(), rng.gen());
+    let alice_public = x25519::x25519(&alice, &base_point());
+    let bob_public = x25519::x25519(&bob, &base_point());
+    assert_ne!(alice_public, bob_public);
+    assert_eq!(
+        x25519::x25519(&alice, &bob_public),
+        x25519::x25519(&bob, &alice_public)
+    );
+
+    // the point of order 2
+    assert_eq!(x25519::x25519(&alice, &[0u8; 32]), [0u8; 32]);
+}
+
+#[test]
+// Test the witness of the X25519 gadget, and that a point of small order has no witness
+fn test_x25519_witness() {
+    let (next_row, gates) = CircuitGate::<Fp>::create_x25519(0);
+    let cs = ConstraintSystem::create(gates).build().unwrap();
+
+    let rng = &mut StdRng::seed_from_u64(1);
+    let (secret, other): ([u8; 32], [u8; 32]) = (rng.gen(), rng.gen());
+    let public_key = x25519::x25519(&other, &base_point());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let shared = x25519::extend_x25519_witness(&mut witness, &secret, &public_key);
+    assert_eq!(
+        shared,
+        Some(x25519::x25519(
+            &other,
+            &x25519::x25519(&secret, &base_point())
+        ))
+    );
+    assert_eq!(witness[0].len(), next_row);
+    assert_eq!(verify_rows(&cs, &witness), Ok(()));
+
+    let shared_limbs = BigUint::from_bytes_le(&shared.unwrap()).to_field_limbs::<Fp>();
+    for (limb, value) in shared_limbs.into_iter().enumerate() {
+        let (row, col) = x25519::x25519_limb(0, X25519Elem::SharedSecret, limb);
+        assert_eq!(witness[col][row], value);
+    }
+
+    // another shared secret
+    let (row, col) = x25519::x25519_limb(0, X25519Elem::SharedSecret, 0);
+    let mut wrong = witness.clone();
+    wrong[col][row] += Fp::from(1u32);
+    assert!(verify_rows(&cs, &wrong).is_err());
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![]);
+    let shared = x25519::extend_x25519_witness(&mut witness, &secret, &[0u8; 32]);
+    assert_eq!(shared, None);
+    assert!(verify_rows(&cs, &witness).is_err());
+}
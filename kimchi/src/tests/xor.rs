@@ -29,7 +29,7 @@ use o1_utils::{BigUintHelpers, BitwiseOps, FieldHelpers, RandomField};
 use poly_commitment::srs::{endos, SRS};
 use rand::{rngs::StdRng, SeedableRng};
 
-use super::framework::TestFramework;
+use super::framework::{TestCurve, TestFramework};
 
 type PallasField = <Pallas as AffineCurve>::BaseField;
 type SpongeParams = PlonkSpongeConstantsKimchi;
@@ -158,15 +158,17 @@ where
     witness
 }
 
-#[test]
-// End-to-end test of XOR
-fn test_prove_and_verify_xor() {
+// End-to-end test of XOR on the curve G
+fn prove_and_verify_xor<G: TestCurve>()
+where
+    G::BaseField: PrimeField,
+{
     let rng = &mut StdRng::from_seed(RNG_SEED);
 
     let bits = 64;
     // Create
     let mut gates = vec![];
-    let _next_row = CircuitGate::<Fp>::extend_xor_gadget(&mut gates, bits);
+    let _next_row = CircuitGate::<G::ScalarField>::extend_xor_gadget(&mut gates, bits);
 
     let input1 = rng.gen_field_with_bits(bits);
     let input2 = rng.gen_field_with_bits(bits);
@@ -174,14 +176,21 @@ fn test_prove_and_verify_xor() {
     // Create witness and random inputs
     let witness = xor::create_xor_witness(input1, input2, bits);
 
-    TestFramework::<Vesta>::default()
+    TestFramework::<G>::default()
         .gates(gates)
         .witness(witness)
         .setup()
-        .prove_and_verify::<VestaBaseSponge, VestaScalarSponge>()
+        .prove_and_verify::<G::BaseSponge, G::ScalarSponge>()
         .unwrap();
 }
 
+#[test]
+// End-to-end test of XOR over both curves
+fn test_prove_and_verify_xor() {
+    prove_and_verify_xor::<Vesta>();
+    prove_and_verify_xor::<Pallas>();
+}
+
 #[test]
 // Test a XOR of 64bit whose output is all ones with alternating inputs
 fn test_xor64_alternating() {
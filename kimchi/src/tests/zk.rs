@@ -0,0 +1,272 @@
+use crate::{
+    blinding::Blinding,
+    circuits::{
+        constraints::ConstraintSystem,
+        gate::{CircuitGate, GateType},
+        lookup::{
+            lookups::LookupArgument,
+            runtime_tables::{RuntimeTable, RuntimeTableCfg, RuntimeTableSpec},
+        },
+        polynomials::generic::testing::{create_circuit, fill_in_witness},
+        wires::{Wire, COLUMNS},
+    },
+    proof::ProverProof,
+    prover_index::{
+        testing::{new_index_for_test, new_index_for_test_with_cs},
+        ProverIndex,
+    },
+    verifier::verify,
+};
+use ark_ff::{UniformRand, Zero};
+use ark_poly::{EvaluationDomain, Evaluations, Polynomial};
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use poly_commitment::commitment::CommitmentCurve;
+use rand::{rngs::StdRng, SeedableRng};
+use std::array;
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+// The number of proofs of the same witness compared by the tests
+const NUM_PROOFS: u64 = 4;
+
+// Creates the index of the generic test circuit, with its valid witness and public input
+fn setup() -> (ProverIndex<Vesta>, [Vec<Fp>; COLUMNS], Vec<Fp>) {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+    let index = new_index_for_test(gates, public.len());
+    (index, witness, public)
+}
+
+// The identifier of the secret runtime table of the lookup test circuit
+const SECRET_TABLE_ID: i32 = 1;
+
+// Creates the index of a circuit looking up secret indices in a secret runtime table,
+// with its valid witness and runtime table
+fn setup_lookup(
+    argument: LookupArgument,
+) -> (
+    ProverIndex<Vesta>,
+    [Vec<Fp>; COLUMNS],
+    Vec<RuntimeTable<Fp>>,
+) {
+    let rng = &mut StdRng::seed_from_u64(0);
+    let data: Vec<Fp> = (0..5).map(|_| Fp::rand(rng)).collect();
+    let num_rows = 20;
+    let gates: Vec<_> = (0..num_rows)
+        .map(|row| CircuitGate::new(GateType::Lookup, Wire::for_row(row), vec![]))
+        .collect();
+
+    // the first register is the table id, followed by 3 pairs of an index and a value
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); num_rows]);
+    witness[0] = vec![Fp::from(SECRET_TABLE_ID as u32); num_rows];
+    for i in 0..3 {
+        let indices: Vec<_> = (0..num_rows).map(|row| (row + i) % data.len()).collect();
+        witness[1 + 2 * i] = indices
+            .iter()
+            .map(|index| Fp::from(*index as u64))
+            .collect();
+        witness[2 + 2 * i] = indices.iter().map(|index| data[*index]).collect();
+    }
+
+    let cs = ConstraintSystem::create(gates)
+        .runtime(Some(vec![RuntimeTableCfg::Indexed(RuntimeTableSpec {
+            id: SECRET_TABLE_ID,
+            len: data.len(),
+        })]))
+        .lookup_argument(argument)
+        .build()
+        .unwrap();
+    let index = new_index_for_test_with_cs(cs);
+    let runtime_tables = vec![RuntimeTable {
+        id: SECRET_TABLE_ID,
+        data,
+    }];
+    (index, witness, runtime_tables)
+}
+
+// Creates a proof of the witness, with the randomness of the seed
+fn prove(
+    index: &ProverIndex<Vesta>,
+    witness: &[Vec<Fp>; COLUMNS],
+    runtime_tables: &[RuntimeTable<Fp>],
+    seed: u64,
+) -> ProverProof<Vesta> {
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    ProverProof::create_recursive_with_rng::<BaseSponge, ScalarSponge, _>(
+        &group_map,
+        witness.clone(),
+        runtime_tables,
+        index,
+        vec![],
+        None,
+        &mut StdRng::seed_from_u64(seed),
+    )
+    .unwrap()
+}
+
+#[test]
+// Test that the proofs of the same witness with different randomness share no commitment, evaluation or opening
+fn test_proofs_are_unlinkable() {
+    let (index, witness, public) = setup();
+    let verifier_index = index.verifier_index();
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proofs: Vec<_> = (0..NUM_PROOFS)
+        .map(|seed| prove(&index, &witness, &[], seed))
+        .collect();
+
+    for (i, proof) in proofs.iter().enumerate() {
+        verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, proof, &public)
+            .unwrap();
+
+        for other in &proofs[i + 1..] {
+            for col in 0..COLUMNS {
+                assert_ne!(proof.commitments.w_comm[col], other.commitments.w_comm[col]);
+                assert_ne!(proof.evals.w[col].zeta, other.evals.w[col].zeta);
+                assert_ne!(proof.evals.w[col].zeta_omega, other.evals.w[col].zeta_omega);
+            }
+            assert_ne!(proof.commitments.z_comm, other.commitments.z_comm);
+            assert_ne!(proof.commitments.t_comm, other.commitments.t_comm);
+            assert_ne!(proof.evals.z.zeta, other.evals.z.zeta);
+            assert_ne!(proof.ft_eval1, other.ft_eval1);
+            assert_ne!(proof.proof.delta, other.proof.delta);
+            assert_ne!(proof.proof.z1, other.proof.z1);
+            assert_ne!(proof.proof.z2, other.proof.z2);
+        }
+    }
+}
+
+#[test]
+// Test that a guess of the witness cannot be checked against the witness commitments of a proof,
+// and that the columns of the witness which are zero are not distinguishable from the other ones
+fn test_witness_commitments_are_hiding() {
+    let (index, witness, _) = setup();
+    let domain = index.cs.domain.d1;
+    let proof = prove(&index, &witness, &[], 0);
+
+    // the commitments of the exact witness, without the blinding of the prover
+    for col in 0..COLUMNS {
+        let mut evals = witness[col].clone();
+        evals.resize(domain.size(), Fp::zero());
+        let guess = index.srs.commit_evaluations_non_hiding(
+            domain,
+            &Evaluations::from_vec_and_domain(evals, domain),
+        );
+        assert_ne!(proof.commitments.w_comm[col], guess);
+    }
+
+    // the last column of the witness of the generic gates is zero
+    let zero_col = COLUMNS - 1;
+    assert!(witness[zero_col].iter().all(Fp::is_zero));
+    assert!(proof.commitments.w_comm[zero_col]
+        .unshifted
+        .iter()
+        .all(|comm| !comm.is_zero()));
+    assert!(proof.evals.w[zero_col]
+        .zeta
+        .iter()
+        .all(|eval| !eval.is_zero()));
+    assert!(proof.evals.w[zero_col]
+        .zeta_omega
+        .iter()
+        .all(|eval| !eval.is_zero()));
+
+    // the same witness with the same randomness gives the same commitments
+    let same_proof = prove(&index, &witness, &[], 0);
+    assert_eq!(proof.commitments.w_comm, same_proof.commitments.w_comm);
+}
+
+#[test]
+// Test that the lookup columns of the proofs of a secret runtime table are hidden by default,
+// and that they are deterministic and verified when their blinding is disabled
+fn test_lookup_blinding() {
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let configurations = [
+        Blinding::zero_knowledge(),
+        Blinding::none(),
+        Blinding {
+            permutation: true,
+            lookup: false,
+        },
+        Blinding {
+            permutation: false,
+            lookup: true,
+        },
+    ];
+    for argument in [LookupArgument::Plookup, LookupArgument::LogUp] {
+        let (mut index, witness, runtime_tables) = setup_lookup(argument);
+        let verifier_index = index.verifier_index();
+        let domain = index.cs.domain.d1;
+
+        // the non-hiding commitment of the runtime table column, with zero zero-knowledge rows
+        let offset = index
+            .cs
+            .lookup_constraint_system
+            .as_ref()
+            .unwrap()
+            .runtime_table_offset
+            .unwrap();
+        let mut evals = vec![Fp::zero(); domain.size()];
+        evals[offset..offset + runtime_tables[0].data.len()]
+            .copy_from_slice(&runtime_tables[0].data);
+        let runtime_poly = Evaluations::from_vec_and_domain(evals, domain).interpolate();
+        let runtime_comm = index.srs.commit_non_hiding(&runtime_poly, None);
+
+        for blinding in configurations {
+            index.blinding = blinding;
+            let proofs: Vec<_> = (0..2)
+                .map(|seed| prove(&index, &witness, &runtime_tables, seed))
+                .collect();
+            for proof in &proofs {
+                verify::<Vesta, BaseSponge, ScalarSponge>(&group_map, &verifier_index, proof, &[])
+                    .unwrap();
+            }
+
+            let lookups: Vec<_> = proofs
+                .iter()
+                .map(|proof| proof.commitments.lookup.as_ref().unwrap())
+                .collect();
+            let runtime = lookups[0].runtime.as_ref().unwrap();
+            assert_eq!(runtime == &runtime_comm, !blinding.lookup);
+            assert_eq!(lookups[0].runtime == lookups[1].runtime, !blinding.lookup);
+            if blinding.lookup {
+                assert_ne!(lookups[0].aggreg, lookups[1].aggreg);
+                assert_ne!(lookups[0].sorted, lookups[1].sorted);
+            }
+        }
+    }
+}
+
+#[test]
+// Test that the last evaluations of the permutation aggregation polynomial are random,
+// and zero when the blinding of the permutation is disabled
+fn test_permutation_blinding() {
+    let (mut index, mut witness, _) = setup();
+    let domain = index.cs.domain.d1;
+    let n = domain.size();
+    for col in &mut witness {
+        col.resize(n, Fp::zero());
+    }
+    let rng = &mut StdRng::seed_from_u64(0);
+    let (beta, gamma) = (Fp::rand(rng), Fp::rand(rng));
+
+    for blinding in [Blinding::zero_knowledge(), Blinding::none()] {
+        index.blinding = blinding;
+        let z: Vec<_> = (0..2)
+            .map(|_| index.perm_aggreg(&witness, &beta, &gamma, rng).unwrap())
+            .collect();
+        for row in n - 2..n {
+            let eval = z[0].evaluate(&domain.element(row));
+            assert_eq!(eval.is_zero(), !blinding.permutation);
+        }
+        assert_eq!(z[0] == z[1], !blinding.permutation);
+    }
+}
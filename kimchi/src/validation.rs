@@ -0,0 +1,85 @@
+//! This module implements the validation of a witness before proving, as [`validate_witness`].
+//!
+//! The validation only performs cheap checks, linear in the size of the witness and without
+//! evaluating the constraints of the circuit, so that a proving service can reject malformed
+//! requests before queuing the expensive proving job:
+//!
+//! - the columns of the witness all have the same length, which leaves room in the domain
+//!   for the zero-knowledge rows;
+//! - the public input has the size of the public input of the circuit, and matches the first column of the witness,
+//!   or the output cells for the public outputs (see
+//!   [`public_output_values`](crate::circuits::constraints::ConstraintSystem::public_output_values));
+//! - the values of the range check gates (in their first column) fit in 88 bits.
+//!
+//! A valid witness can still fail to satisfy the constraints:
+//! [`ProverIndex::verify`] (or the [`MockProver`](crate::mock_prover::MockProver)) checks them,
+//! and the prover fails to create a proof of an unsatisfying witness.
+
+use crate::{
+    circuits::{
+        gate::GateType,
+        polynomials::permutation::ZK_ROWS,
+        wires::{Wire, COLUMNS},
+    },
+    curve::KimchiCurve,
+    error::WitnessError,
+    prover_index::ProverIndex,
+};
+use ark_ff::{BigInteger, PrimeField};
+use ark_poly::EvaluationDomain;
+use o1_utils::foreign_field::LIMB_BITS;
+
+/// Validates the shape of a witness and of a public input against the index of a circuit,
+/// without proving (see the module)
+///
+/// # Errors
+///
+/// Will give error if a check of the witness fails, see [WitnessError].
+pub fn validate_witness<G: KimchiCurve>(
+    index: &ProverIndex<G>,
+    witness: &[Vec<G::ScalarField>; COLUMNS],
+    public: &[G::ScalarField],
+) -> Result<(), WitnessError> {
+    let cs = &index.cs;
+
+    // the shape of the witness
+    let rows = witness[0].len();
+    if let Some((col, cells)) = witness
+        .iter()
+        .enumerate()
+        .find(|(_, cells)| cells.len() != rows)
+    {
+        return Err(WitnessError::ColumnLength(col, cells.len(), rows));
+    }
+    let max_rows = cs.domain.d1.size().saturating_sub(ZK_ROWS as usize);
+    if rows > max_rows {
+        return Err(WitnessError::TooManyRows(rows, max_rows));
+    }
+
+    // the public input
+    if public.len() != cs.public {
+        return Err(WitnessError::PublicInputLength(cs.public, public.len()));
+    }
+    // the rows of the public outputs are filled by the prover from their output cells
+    let inputs = cs.public - cs.public_outputs;
+    if let Some(row) = (0..cs.public).find(|row| {
+        let Wire { row: cell_row, col } = if *row < inputs {
+            Wire::new(*row, 0)
+        } else {
+            cs.gates[*row].wires[0]
+        };
+        witness[col].get(cell_row) != Some(&public[*row])
+    }) {
+        return Err(WitnessError::PublicInputMismatch(row));
+    }
+
+    // the values of the range checks, the rows after the witness are padded with zeros
+    for (row, (gate, value)) in cs.gates.iter().zip(&witness[0]).enumerate() {
+        let range_checked = matches!(gate.typ, GateType::RangeCheck0 | GateType::RangeCheck1);
+        if range_checked && value.into_repr().num_bits() as usize > LIMB_BITS {
+            return Err(WitnessError::RangeCheck(row));
+        }
+    }
+
+    Ok(())
+}
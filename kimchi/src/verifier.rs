@@ -27,7 +27,7 @@ use mina_poseidon::{sponge::ScalarChallenge, FqSponge};
 use poly_commitment::commitment::{
     absorb_commitment, combined_inner_product, BatchEvaluationProof, Evaluation, PolyComm,
 };
-use rand::thread_rng;
+use rand::{thread_rng, CryptoRng, RngCore};
 
 /// The result of a proof verification.
 pub type Result<T> = std::result::Result<T, VerifyError>;
@@ -70,7 +70,7 @@ impl<'a, G: KimchiCurve> Context<'a, G> {
                 match t {
                     Zero => None,
                     Generic => Some(&self.verifier_index.generic_comm),
-                    Lookup => None,
+                    Lookup | VectorLookup | RangeCheckU8 | RangeCheckU16 => None,
                     CompleteAdd => Some(&self.verifier_index.complete_add_comm),
                     VarBaseMul => Some(&self.verifier_index.mul_comm),
                     EndoMul => Some(&self.verifier_index.emul_comm),
@@ -83,8 +83,13 @@ impl<'a, G: KimchiCurve> Context<'a, G> {
                     ForeignFieldMul => Some(self.verifier_index.foreign_field_mul_comm.as_ref()?),
                     Xor16 => Some(self.verifier_index.xor_comm.as_ref()?),
                     Rot64 => Some(self.verifier_index.rot_comm.as_ref()?),
+                    AddU64 => Some(self.verifier_index.add_u64_comm.as_ref()?),
+                    Select => Some(self.verifier_index.select_comm.as_ref()?),
+                    // the custom gates have their own selectors
+                    Custom => None,
                 }
             }
+            Custom(i) => self.verifier_index.custom_comm.get(i),
         }
     }
 }
@@ -126,6 +131,11 @@ where
         let verifier_index_digest = index.digest::<EFqSponge>();
         fq_sponge.absorb_fq(&[verifier_index_digest]);
 
+        //~ 1. If the proof is part of a session, absorb the digest of the previous proof.
+        if let Some(digest) = self.prev_proof_digest {
+            fq_sponge.absorb_fq(&[digest]);
+        }
+
         //~ 1. Absorb the commitments of the previous challenges with the Fq-sponge.
         for RecursionChallenge { comm, .. } in &self.prev_challenges {
             absorb_commitment(&mut fq_sponge, comm);
@@ -212,8 +222,9 @@ where
         //~ 1. Derive $\alpha$ from $\alpha'$ using the endomorphism (TODO: details).
         let alpha = alpha_chal.to_field(endo_r);
 
-        //~ 1. Enforce that the length of the $t$ commitment is of size `PERMUTS`.
-        if self.commitments.t_comm.unshifted.len() != PERMUTS {
+        //~ 1. Enforce that the length of the $t$ commitment is the number of quotient chunks of the index
+        //~    (`PERMUTS` by default).
+        if self.commitments.t_comm.unshifted.len() != index.quotient_chunks {
             return Err(VerifyError::IncorrectCommitmentLength("t"));
         }
 
@@ -438,7 +449,7 @@ where
                     .lookup_index
                     .as_ref()
                     .map(|li| {
-                        (0..li.lookup_info.max_per_row + 1)
+                        (0..li.lookup_info.num_sorted())
                             .map(Column::LookupSorted)
                             .chain([Column::LookupAggreg, Column::LookupTable].into_iter())
                             .chain(
@@ -573,6 +584,17 @@ where
     //~ Essentially, this steps verifies that $f(\zeta) = t(\zeta) * Z_H(\zeta)$.
     //~
 
+    if proof.arithmetization_version != verifier_index.arithmetization_version {
+        return Err(VerifyError::ArithmetizationVersionMismatch(
+            proof.arithmetization_version,
+            verifier_index.arithmetization_version,
+        ));
+    }
+    if !verifier_index.arithmetization_version.is_supported() {
+        return Err(VerifyError::UnsupportedArithmetizationVersion(
+            verifier_index.arithmetization_version,
+        ));
+    }
     if proof.prev_challenges.len() != verifier_index.prev_challenges {
         return Err(VerifyError::IncorrectPrevChallengesLength(
             verifier_index.prev_challenges,
@@ -756,7 +778,7 @@ where
             .as_ref()
             .map(|li| {
                 // add evaluations of sorted polynomials
-                (0..li.lookup_info.max_per_row + 1)
+                (0..li.lookup_info.num_sorted())
                     .map(Column::LookupSorted)
                     // add evaluations of the aggreg polynomial
                     .chain([Column::LookupAggreg].into_iter())
@@ -878,6 +900,63 @@ where
     batch_verify::<G, EFqSponge, EFrSponge>(group_map, &proofs)
 }
 
+/// Verify a proof [`ProverProof`] using a [`VerifierIndex`] and a `group_map`,
+/// using `rng` to sample the randomness of the verification of the opening proof.
+///
+/// # Errors
+///
+/// Will give error if `proof(s)` are not verified as valid.
+pub fn verify_with_rng<G, EFqSponge, EFrSponge, RNG>(
+    group_map: &G::Map,
+    verifier_index: &VerifierIndex<G>,
+    proof: &ProverProof<G>,
+    public_input: &[G::ScalarField],
+    rng: &mut RNG,
+) -> Result<()>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+    RNG: RngCore + CryptoRng,
+{
+    let proofs = vec![Context {
+        verifier_index,
+        proof,
+        public_input,
+    }];
+    batch_verify_with_rng::<G, EFqSponge, EFrSponge, RNG>(group_map, &proofs, rng)
+}
+
+/// Verify a proof [`ProverProof`] of a circuit with public outputs,
+/// the values of the outputs being checked along with the public inputs.
+///
+/// # Errors
+///
+/// Will give error if the number of public outputs is not the one of the verifier index,
+/// or if `proof(s)` are not verified as valid.
+pub fn verify_with_outputs<G, EFqSponge, EFrSponge>(
+    group_map: &G::Map,
+    verifier_index: &VerifierIndex<G>,
+    proof: &ProverProof<G>,
+    public_input: &[G::ScalarField],
+    public_output: &[G::ScalarField],
+) -> Result<()>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
+    if public_output.len() != verifier_index.public_outputs {
+        return Err(VerifyError::IncorrectPublicOutputLength(
+            verifier_index.public_outputs,
+        ));
+    }
+    let public_input = [public_input, public_output].concat();
+    verify::<G, EFqSponge, EFrSponge>(group_map, verifier_index, proof, &public_input)
+}
+
 /// This function verifies the batch of zk-proofs
 ///     proofs: vector of Plonk proofs
 ///     RETURN: verification status
@@ -894,6 +973,29 @@ where
     G::BaseField: PrimeField,
     EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
     EFrSponge: FrSponge<G::ScalarField>,
+{
+    batch_verify_with_rng::<G, EFqSponge, EFrSponge, _>(group_map, proofs, &mut thread_rng())
+}
+
+/// This function verifies the batch of zk-proofs,
+/// using `rng` to sample the randomness that combines the opening proofs of the batch.
+///     proofs: vector of Plonk proofs
+///     RETURN: verification status
+///
+/// # Errors
+///
+/// Will give error if `srs` of `proof` is invalid or `verify` process fails.
+pub fn batch_verify_with_rng<G, EFqSponge, EFrSponge, RNG>(
+    group_map: &G::Map,
+    proofs: &[Context<G>],
+    rng: &mut RNG,
+) -> Result<()>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+    RNG: RngCore + CryptoRng,
 {
     //~ #### Batch verification of proofs
     //~
@@ -937,7 +1039,7 @@ where
     }
 
     //~ 1. Use the [`PolyCom.verify`](#polynomial-commitments) to verify the partially evaluated proofs.
-    if srs.verify::<EFqSponge, _>(group_map, &mut batch, &mut thread_rng()) {
+    if srs.verify::<EFqSponge, _>(group_map, &mut batch, rng) {
         Ok(())
     } else {
         Err(VerifyError::OpenProof)
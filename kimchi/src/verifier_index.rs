@@ -4,18 +4,25 @@
 use crate::{
     alphas::Alphas,
     circuits::{
+        constraints::default_quotient_chunks,
         expr::{Linearization, PolishToken},
         lookup::{index::LookupSelectors, lookups::LookupInfo},
         polynomials::permutation::{zk_polynomial, zk_w3},
         wires::{COLUMNS, PERMUTS},
     },
+    compression::{self, Compression},
     curve::KimchiCurve,
+    domain_separation::DomainSeparator,
     error::VerifierIndexError,
+    linearization::LinearizationExport,
     prover_index::ProverIndex,
+    version::{ArithmetizationVersion, ARITHMETIZATION_VERSION},
 };
 use ark_ff::{One, PrimeField};
 use ark_poly::{univariate::DensePolynomial, Radix2EvaluationDomain as D};
+use blake2::{Blake2b512, Digest};
 use mina_poseidon::FqSponge;
+use o1_utils::FieldHelpers;
 use once_cell::sync::OnceCell;
 use poly_commitment::{
     commitment::{CommitmentCurve, PolyComm},
@@ -65,8 +72,11 @@ pub struct VerifierIndex<G: KimchiCurve> {
     /// polynomial commitment keys
     #[serde(skip)]
     pub srs: OnceCell<Arc<SRS<G>>>,
-    /// number of public inputs
+    /// number of public inputs, including the public outputs
     pub public: usize,
+    /// number of public outputs, the last public inputs
+    #[serde(default)]
+    pub public_outputs: usize,
     /// number of previous evaluation challenges, for recursive proving
     pub prev_challenges: usize,
 
@@ -124,6 +134,18 @@ pub struct VerifierIndex<G: KimchiCurve> {
     #[serde(bound = "Option<PolyComm<G>>: Serialize + DeserializeOwned")]
     pub rot_comm: Option<PolyComm<G>>,
 
+    /// 64-bit modular addition commitments
+    #[serde(default, bound = "Option<PolyComm<G>>: Serialize + DeserializeOwned")]
+    pub add_u64_comm: Option<PolyComm<G>>,
+
+    /// Conditional selection commitments
+    #[serde(default, bound = "Option<PolyComm<G>>: Serialize + DeserializeOwned")]
+    pub select_comm: Option<PolyComm<G>>,
+
+    /// Custom gates commitments, see [crate::circuits::custom_gate]
+    #[serde(default, bound = "PolyComm<G>: Serialize + DeserializeOwned")]
+    pub custom_comm: Vec<PolyComm<G>>,
+
     /// wire coordinate shifts
     #[serde_as(as = "[o1_utils::serialization::SerdeAs; PERMUTS]")]
     pub shift: [G::ScalarField; PERMUTS],
@@ -141,6 +163,23 @@ pub struct VerifierIndex<G: KimchiCurve> {
     #[serde(bound = "PolyComm<G>: Serialize + DeserializeOwned")]
     pub lookup_index: Option<LookupVerifierIndex<G>>,
 
+    /// optional application-specific domain separator
+    #[serde(default)]
+    pub domain_separator: Option<DomainSeparator>,
+
+    /// number of chunks of the quotient polynomial
+    #[serde(default = "default_quotient_chunks")]
+    pub quotient_chunks: usize,
+
+    /// version of the arithmetization
+    #[serde(default)]
+    pub arithmetization_version: ArithmetizationVersion,
+
+    /// digest of the constraints of the custom gates, empty without custom gates
+    /// (see [crate::circuits::constraints::ConstraintSystem::custom_constraints_digest])
+    #[serde(default)]
+    pub custom_constraints_digest: Vec<u8>,
+
     #[serde(skip)]
     pub linearization: Linearization<Vec<PolishToken<G::ScalarField>>>,
     /// The mapping between powers of alpha and constraints
@@ -150,6 +189,40 @@ pub struct VerifierIndex<G: KimchiCurve> {
 //~spec:endcode
 
 impl<G: KimchiCurve> ProverIndex<G> {
+    /// Produces the lookup part of the [`VerifierIndex`],
+    /// from the precomputed commitments of the index if any (see [ProverIndex::precompute_lookup_commitments]).
+    pub fn lookup_verifier_index(&self) -> Option<LookupVerifierIndex<G>> {
+        if let Some(lookup_commitments) = &self.lookup_commitments {
+            return Some(lookup_commitments.clone());
+        }
+
+        let domain = self.cs.domain.d1;
+        self.cs
+            .lookup_constraint_system
+            .as_ref()
+            .map(|cs| LookupVerifierIndex {
+                joint_lookup_used: cs.configuration.lookup_info.features.joint_lookup_used,
+                lookup_info: cs.configuration.lookup_info.clone(),
+                lookup_selectors: cs
+                    .lookup_selectors
+                    .as_ref()
+                    .map(|e| self.srs.commit_evaluations_non_hiding(domain, e)),
+                lookup_table: cs
+                    .lookup_table8
+                    .iter()
+                    .map(|e| self.srs.commit_evaluations_non_hiding(domain, e))
+                    .collect(),
+                table_ids: cs
+                    .table_ids8
+                    .as_ref()
+                    .map(|table_ids8| self.srs.commit_evaluations_non_hiding(domain, table_ids8)),
+                runtime_tables_selector: cs
+                    .runtime_selector
+                    .as_ref()
+                    .map(|e| self.srs.commit_evaluations_non_hiding(domain, e)),
+            })
+    }
+
     /// Produces the [`VerifierIndex`] from the prover's [`ProverIndex`].
     ///
     /// # Panics
@@ -170,31 +243,7 @@ impl<G: KimchiCurve> ProverIndex<G> {
 
         let domain = self.cs.domain.d1;
 
-        let lookup_index = {
-            self.cs
-                .lookup_constraint_system
-                .as_ref()
-                .map(|cs| LookupVerifierIndex {
-                    joint_lookup_used: cs.configuration.lookup_info.features.joint_lookup_used,
-                    lookup_info: cs.configuration.lookup_info.clone(),
-                    lookup_selectors: cs
-                        .lookup_selectors
-                        .as_ref()
-                        .map(|e| self.srs.commit_evaluations_non_hiding(domain, e)),
-                    lookup_table: cs
-                        .lookup_table8
-                        .iter()
-                        .map(|e| self.srs.commit_evaluations_non_hiding(domain, e))
-                        .collect(),
-                    table_ids: cs.table_ids8.as_ref().map(|table_ids8| {
-                        self.srs.commit_evaluations_non_hiding(domain, table_ids8)
-                    }),
-                    runtime_tables_selector: cs
-                        .runtime_selector
-                        .as_ref()
-                        .map(|e| self.srs.commit_evaluations_non_hiding(domain, e)),
-                })
-        };
+        let lookup_index = self.lookup_verifier_index();
 
         // TODO: Switch to commit_evaluations for all index polys
         VerifierIndex {
@@ -202,6 +251,7 @@ impl<G: KimchiCurve> ProverIndex<G> {
             max_poly_size: self.max_poly_size,
             powers_of_alpha: self.powers_of_alpha.clone(),
             public: self.cs.public,
+            public_outputs: self.cs.public_outputs,
             prev_challenges: self.cs.prev_challenges,
             srs: {
                 let cell = OnceCell::new();
@@ -282,6 +332,22 @@ impl<G: KimchiCurve> ProverIndex<G> {
                 .rot_selector8
                 .as_ref()
                 .map(|eval8| self.srs.commit_evaluations_non_hiding(domain, eval8)),
+            add_u64_comm: self
+                .column_evaluations
+                .add_u64_selector8
+                .as_ref()
+                .map(|eval8| self.srs.commit_evaluations_non_hiding(domain, eval8)),
+            select_comm: self
+                .column_evaluations
+                .select_selector8
+                .as_ref()
+                .map(|eval8| self.srs.commit_evaluations_non_hiding(domain, eval8)),
+            custom_comm: self
+                .column_evaluations
+                .custom_selectors8
+                .iter()
+                .map(|eval8| self.srs.commit_evaluations_non_hiding(domain, eval8))
+                .collect(),
 
             shift: self.cs.shift,
             zkpm: {
@@ -296,6 +362,10 @@ impl<G: KimchiCurve> ProverIndex<G> {
             },
             endo: self.cs.endo,
             lookup_index,
+            domain_separator: self.cs.domain_separator.clone(),
+            quotient_chunks: self.cs.quotient_chunks,
+            arithmetization_version: ARITHMETIZATION_VERSION,
+            custom_constraints_digest: self.cs.custom_constraints_digest(),
             linearization: self.linearization.clone(),
         }
     }
@@ -324,7 +394,13 @@ impl<G: KimchiCurve> VerifierIndex<G> {
         self.w.get_or_init(|| zk_w3(self.domain))
     }
 
+    /// Exports the linearization evaluated by the verifier, see [LinearizationExport]
+    pub fn linearization_export(&self) -> LinearizationExport {
+        LinearizationExport::new(&self.linearization, &self.powers_of_alpha)
+    }
+
     /// Deserializes a [`VerifierIndex`] from a file, given a pointer to an SRS and an optional offset in the file.
+    /// The index is decompressed if it was written with a compression (see [`VerifierIndex::to_file_with_compression`]).
     ///
     /// # Errors
     ///
@@ -346,8 +422,7 @@ impl<G: KimchiCurve> VerifierIndex<G> {
         }
 
         // deserialize
-        let mut verifier_index = Self::deserialize(&mut rmp_serde::Deserializer::new(reader))
-            .map_err(|e| e.to_string())?;
+        let mut verifier_index: Self = compression::read(reader).map_err(|e| e.to_string())?;
 
         // fill in the rest
         if let Some(srs) = srs {
@@ -372,6 +447,21 @@ impl<G: KimchiCurve> VerifierIndex<G> {
     ///
     /// Will panic if `path` is invalid or `file serialization` has issue.
     pub fn to_file(&self, path: &Path, append: Option<bool>) -> Result<(), String> {
+        self.to_file_with_compression(path, append, Compression::None)
+    }
+
+    /// Writes a [`VerifierIndex`] to a file as [`VerifierIndex::to_file`], compressed with a [`Compression`]:
+    /// a compressed index starts with a header, and its offset in the file is the offset of its header.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if it fails to open a file or writes to the file.
+    pub fn to_file_with_compression(
+        &self,
+        path: &Path,
+        append: Option<bool>,
+        compression: Compression,
+    ) -> Result<(), String> {
         let append = append.unwrap_or(true);
         let file = OpenOptions::new()
             .append(append)
@@ -380,8 +470,7 @@ impl<G: KimchiCurve> VerifierIndex<G> {
 
         let writer = BufWriter::new(file);
 
-        self.serialize(&mut rmp_serde::Serializer::new(writer))
-            .map_err(|e| e.to_string())
+        compression::write(writer, self, compression).map_err(|e| e.to_string())
     }
 
     /// Compute the digest of the [`VerifierIndex`], which can be used for the Fiat-Shamir
@@ -396,6 +485,7 @@ impl<G: KimchiCurve> VerifierIndex<G> {
             max_poly_size: _,
             srs: _,
             public: _,
+            public_outputs: _,
             prev_challenges: _,
 
             // Always present
@@ -415,10 +505,19 @@ impl<G: KimchiCurve> VerifierIndex<G> {
             foreign_field_mul_comm,
             xor_comm,
             rot_comm,
+            add_u64_comm,
+            select_comm,
+            custom_comm,
 
             // Lookup index; optional
             lookup_index,
 
+            // Domain separator; optional
+            domain_separator,
+
+            quotient_chunks: _,
+            arithmetization_version: _,
+            custom_constraints_digest: _,
             shift: _,
             zkpm: _,
             w: _,
@@ -428,6 +527,12 @@ impl<G: KimchiCurve> VerifierIndex<G> {
             powers_of_alpha: _,
         } = &self;
 
+        // Domain separator; optional (absorbed first to bind the whole transcript)
+
+        if let Some(domain_separator) = domain_separator {
+            domain_separator.absorb(&mut fq_sponge);
+        }
+
         // Always present
 
         for comm in sigma_comm.iter() {
@@ -469,6 +574,18 @@ impl<G: KimchiCurve> VerifierIndex<G> {
             fq_sponge.absorb_g(&rot_comm.unshifted);
         }
 
+        if let Some(add_u64_comm) = add_u64_comm {
+            fq_sponge.absorb_g(&add_u64_comm.unshifted);
+        }
+
+        if let Some(select_comm) = select_comm {
+            fq_sponge.absorb_g(&select_comm.unshifted);
+        }
+
+        for comm in custom_comm {
+            fq_sponge.absorb_g(&comm.unshifted);
+        }
+
         // Lookup index; optional
 
         if let Some(LookupVerifierIndex {
@@ -484,6 +601,10 @@ impl<G: KimchiCurve> VerifierIndex<G> {
                     lookup,
                     range_check,
                     ffmul,
+                    vector_lookup,
+                    range_check_u8,
+                    range_check_u16,
+                    add_u64,
                 },
         }) = lookup_index
         {
@@ -509,7 +630,72 @@ impl<G: KimchiCurve> VerifierIndex<G> {
             if let Some(ffmul) = ffmul {
                 fq_sponge.absorb_g(&ffmul.unshifted);
             }
+            if let Some(vector_lookup) = vector_lookup {
+                fq_sponge.absorb_g(&vector_lookup.unshifted);
+            }
+            if let Some(range_check_u8) = range_check_u8 {
+                fq_sponge.absorb_g(&range_check_u8.unshifted);
+            }
+            if let Some(range_check_u16) = range_check_u16 {
+                fq_sponge.absorb_g(&range_check_u16.unshifted);
+            }
+            if let Some(add_u64) = add_u64 {
+                fq_sponge.absorb_g(&add_u64.unshifted);
+            }
         }
         fq_sponge.digest_fq()
     }
+
+    /// Computes the fingerprint of the [`VerifierIndex`], a canonical hash identifying the circuit
+    /// and its verification key, for example in a registry of circuits or on chain.
+    ///
+    /// The fingerprint is the Blake2b hash of the version of the arithmetization,
+    /// the size of the domain, the numbers of public inputs and outputs, previous challenges
+    /// and chunks, the lookup configuration, the digest of the constraints of the custom gates,
+    /// and the digest of the commitments (see [`VerifierIndex::digest`]), which bind the gates,
+    /// their coefficients, the wiring and the lookup tables.
+    pub fn fingerprint<EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>>(
+        &self,
+    ) -> Vec<u8> {
+        let mut hasher = Blake2b512::new();
+
+        hasher.update(self.arithmetization_version.0.to_le_bytes());
+        for size in [
+            self.domain.size,
+            self.max_poly_size as u64,
+            self.public as u64,
+            self.public_outputs as u64,
+            self.prev_challenges as u64,
+            self.quotient_chunks as u64,
+        ] {
+            hasher.update(size.to_le_bytes());
+        }
+        hasher.update((self.custom_constraints_digest.len() as u64).to_le_bytes());
+        hasher.update(&self.custom_constraints_digest);
+
+        match &self.lookup_index {
+            None => hasher.update([0u8]),
+            Some(lookup_index) => {
+                let LookupInfo {
+                    max_per_row,
+                    max_joint_size,
+                    features,
+                } = &lookup_index.lookup_info;
+                hasher.update([1u8]);
+                hasher.update((*max_per_row as u64).to_le_bytes());
+                hasher.update(max_joint_size.to_le_bytes());
+                for pattern in features.patterns {
+                    hasher.update([pattern as u8]);
+                }
+                hasher.update([
+                    features.joint_lookup_used as u8,
+                    features.uses_runtime_tables as u8,
+                    features.argument as u8,
+                ]);
+            }
+        }
+
+        hasher.update(self.digest::<EFqSponge>().to_bytes());
+        hasher.finalize().to_vec()
+    }
 }
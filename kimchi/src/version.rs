@@ -0,0 +1,55 @@
+//! This module implements the versions of the arithmetization of kimchi, as [ArithmetizationVersion].
+//!
+//! The arithmetization is the set of the constraints of the proofs: the gates and their polynomials,
+//! the permutation and lookup arguments, and the linearization of the verifier.
+//! Its version is bumped only by a breaking change of the constraints, which would reject the proofs
+//! of the previous version (and not by a change of the API or of the prover),
+//! so that the proofs of a version always verify with the verifier indexes of the same version.
+//!
+//! The version is recorded in the verifier indexes and in the proofs, and the verifier rejects a proof
+//! whose version differs from the version of its verifier index, or which is not supported by the build.
+//! The proofs and verifier indexes serialized before the versions were recorded are of the version
+//! [ArithmetizationVersion::V1].
+//!
+//! To verify the history of a chain, the verifiers of the previous versions can be built with features:
+//! when a version `N` is replaced, its constraints are kept behind the feature `arithmetization_vN`,
+//! which adds the version to [SUPPORTED_ARITHMETIZATION_VERSIONS].
+//! Note: [ARITHMETIZATION_VERSION] is the first version, so that no feature is defined yet.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A version of the arithmetization (see the module)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ArithmetizationVersion(pub u32);
+
+impl ArithmetizationVersion {
+    /// The first version of the arithmetization
+    pub const V1: Self = ArithmetizationVersion(1);
+
+    /// Checks that the verifier of this build supports the version
+    pub fn is_supported(self) -> bool {
+        SUPPORTED_ARITHMETIZATION_VERSIONS.contains(&self)
+    }
+}
+
+/// The version of the artifacts serialized before the versions were recorded
+impl Default for ArithmetizationVersion {
+    fn default() -> Self {
+        Self::V1
+    }
+}
+
+impl fmt::Display for ArithmetizationVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+/// The current version of the arithmetization, of the proofs and verifier indexes created by this build
+pub const ARITHMETIZATION_VERSION: ArithmetizationVersion = ArithmetizationVersion::V1;
+
+/// The versions of the arithmetization supported by the verifier of this build:
+/// the current version, and the previous versions enabled by their features
+pub const SUPPORTED_ARITHMETIZATION_VERSIONS: &[ArithmetizationVersion] =
+    &[ARITHMETIZATION_VERSION];
@@ -7,10 +7,14 @@
 //! 3. Verify batch of batched opening proofs
 
 use crate::srs::endos;
-use crate::{error::CommitmentError, srs::SRS};
+use crate::{
+    error::CommitmentError,
+    msm::{self, CpuMsm, MsmBackend},
+    srs::SRS,
+};
 use ark_ec::{
-    models::short_weierstrass_jacobian::GroupAffine as SWJAffine, msm::VariableBaseMSM,
-    AffineCurve, ProjectiveCurve, SWModelParameters,
+    models::short_weierstrass_jacobian::GroupAffine as SWJAffine, AffineCurve, ProjectiveCurve,
+    SWModelParameters,
 };
 use ark_ff::{
     BigInteger, Field, FpParameters, One, PrimeField, SquareRootField, UniformRand, Zero,
@@ -225,6 +229,20 @@ impl<C: AffineCurve> PolyComm<C> {
     ///
     /// Panics if `com` and `elm` are not of the same size.
     pub fn multi_scalar_mul(com: &[&PolyComm<C>], elm: &[C::ScalarField]) -> Self {
+        Self::multi_scalar_mul_with(&CpuMsm, com, elm)
+    }
+
+    /// Performs a multi-scalar multiplication between scalars `elm` and commitments `com`,
+    /// as [PolyComm::multi_scalar_mul], with a backend (see [msm]).
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `com` and `elm` are not of the same size.
+    pub fn multi_scalar_mul_with(
+        backend: &dyn MsmBackend<C>,
+        com: &[&PolyComm<C>],
+        elm: &[C::ScalarField],
+    ) -> Self {
         assert_eq!(com.len(), elm.len());
 
         if com.is_empty() || elm.is_empty() {
@@ -244,7 +262,7 @@ impl<C: AffineCurve> PolyComm<C> {
                 .filter_map(|(com, scalar)| com.unshifted.get(chunk).map(|c| (c, scalar)))
                 .unzip();
 
-            let chunk_msm = VariableBaseMSM::multi_scalar_mul::<C>(&points, &scalars);
+            let chunk_msm = msm::multi_scalar_mul::<C>(backend, &points, &scalars);
             unshifted.push(chunk_msm.into_affine());
         }
 
@@ -259,7 +277,7 @@ impl<C: AffineCurve> PolyComm<C> {
             None
         } else {
             let (points, scalars): (Vec<_>, Vec<_>) = shifted_pairs.unzip();
-            Some(VariableBaseMSM::multi_scalar_mul(&points, &scalars).into_affine())
+            Some(msm::multi_scalar_mul(backend, &points, &scalars).into_affine())
         };
 
         Self::new(unshifted, shifted)
@@ -583,7 +601,7 @@ impl<G: CommitmentCurve> SRS<G> {
             unshifted.push(G::zero());
         } else {
             coeffs.chunks(self.g.len()).for_each(|coeffs_chunk| {
-                let chunk = VariableBaseMSM::multi_scalar_mul(&self.g, coeffs_chunk);
+                let chunk = self.multi_scalar_mul(&self.g, coeffs_chunk);
                 unshifted.push(chunk.into_affine());
             });
         }
@@ -601,7 +619,7 @@ impl<G: CommitmentCurve> SRS<G> {
                     None
                 } else {
                     // we shift the last chunk to the right as proof of the degree bound
-                    let shifted = VariableBaseMSM::multi_scalar_mul(
+                    let shifted = self.multi_scalar_mul(
                         &self.g[basis_len - (max % basis_len)..],
                         &coeffs[start..],
                     );
@@ -623,7 +641,8 @@ impl<G: CommitmentCurve> SRS<G> {
             .get(&domain.size())
             .unwrap_or_else(|| panic!("lagrange bases for size {} not found", domain.size()));
         let commit_evaluations = |evals: &Vec<G::ScalarField>, basis: &Vec<PolyComm<G>>| {
-            PolyComm::<G>::multi_scalar_mul(&basis.iter().collect::<Vec<_>>()[..], &evals[..])
+            let basis: Vec<_> = basis.iter().collect();
+            PolyComm::<G>::multi_scalar_mul_with(self.msm_backend(), &basis, evals)
         };
         match domain.size.cmp(&plnm.domain().size) {
             std::cmp::Ordering::Less => {
@@ -638,6 +657,33 @@ impl<G: CommitmentCurve> SRS<G> {
         }
     }
 
+    /// Commits (non-hiding) to the polynomial whose evaluations over `domain` are `evals`
+    /// from the row `start`, and zero elsewhere. The commitments of consecutive segments
+    /// of evaluations add up to the commitment of the evaluations (see
+    /// [SRS::commit_evaluations_non_hiding]), without holding all the evaluations in memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the Lagrange bases of the domain are not in the SRS,
+    /// or if the segment does not fit in the domain.
+    pub fn commit_evaluations_segment(
+        &self,
+        domain: D<G::ScalarField>,
+        start: usize,
+        evals: &[G::ScalarField],
+    ) -> PolyComm<G> {
+        let basis = self
+            .lagrange_bases
+            .get(&domain.size())
+            .unwrap_or_else(|| panic!("lagrange bases for size {} not found", domain.size()));
+        assert!(
+            start + evals.len() <= basis.len(),
+            "the segment of evaluations does not fit in the domain"
+        );
+        let basis: Vec<_> = basis[start..start + evals.len()].iter().collect();
+        PolyComm::<G>::multi_scalar_mul_with(self.msm_backend(), &basis, evals)
+    }
+
     pub fn commit_evaluations(
         &self,
         domain: D<G::ScalarField>,
@@ -843,7 +889,7 @@ impl<G: CommitmentCurve> SRS<G> {
 
         // verify the equation
         let scalars: Vec<_> = scalars.iter().map(|x| x.into_repr()).collect();
-        VariableBaseMSM::multi_scalar_mul(&points, &scalars) == G::Projective::zero()
+        self.multi_scalar_mul(&points, &scalars) == G::Projective::zero()
     }
 }
 
@@ -1,6 +1,6 @@
 use crate::srs::SRS;
 use crate::{commitment::*, srs::endos};
-use ark_ec::{msm::VariableBaseMSM, AffineCurve, ProjectiveCurve};
+use ark_ec::{AffineCurve, ProjectiveCurve};
 use ark_ff::{FftField, Field, One, PrimeField, UniformRand, Zero};
 use ark_poly::{univariate::DensePolynomial, UVPolynomial};
 use ark_poly::{EvaluationDomain, Evaluations};
@@ -253,25 +253,27 @@ impl<G: CommitmentCurve> SRS<G> {
             let rand_l = <G::ScalarField as UniformRand>::rand(rng);
             let rand_r = <G::ScalarField as UniformRand>::rand(rng);
 
-            let l = VariableBaseMSM::multi_scalar_mul(
-                &[&g[0..n], &[self.h, u]].concat(),
-                &[&a[n..], &[rand_l, inner_prod(a_hi, b_lo)]]
-                    .concat()
-                    .iter()
-                    .map(|x| x.into_repr())
-                    .collect::<Vec<_>>(),
-            )
-            .into_affine();
-
-            let r = VariableBaseMSM::multi_scalar_mul(
-                &[&g[n..], &[self.h, u]].concat(),
-                &[&a[0..n], &[rand_r, inner_prod(a_lo, b_hi)]]
-                    .concat()
-                    .iter()
-                    .map(|x| x.into_repr())
-                    .collect::<Vec<_>>(),
-            )
-            .into_affine();
+            let l = self
+                .multi_scalar_mul(
+                    &[&g[0..n], &[self.h, u]].concat(),
+                    &[&a[n..], &[rand_l, inner_prod(a_hi, b_lo)]]
+                        .concat()
+                        .iter()
+                        .map(|x| x.into_repr())
+                        .collect::<Vec<_>>(),
+                )
+                .into_affine();
+
+            let r = self
+                .multi_scalar_mul(
+                    &[&g[n..], &[self.h, u]].concat(),
+                    &[&a[0..n], &[rand_r, inner_prod(a_lo, b_hi)]]
+                        .concat()
+                        .iter()
+                        .map(|x| x.into_repr())
+                        .collect::<Vec<_>>(),
+                )
+                .into_affine();
 
             lr.push((l, r));
             blinders.push((rand_l, rand_r));
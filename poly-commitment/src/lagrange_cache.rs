@@ -0,0 +1,127 @@
+//! This module implements a cache of the commitments to the Lagrange bases of SRSs,
+//! so that the constraint systems of the same domain size set up with the same SRS
+//! (for example by different processes) do not compute them again.
+//!
+//! The bases are keyed by the digest of the SRS (see [SRS::digest]) and the size of the domain.
+//! They are kept in memory, and optionally in a directory, one file per basis:
+//! a file which cannot be read as the basis of its domain is computed again and overwritten.
+
+use crate::{commitment::CommitmentCurve, srs::SRS, PolyComm};
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+// The digest of an SRS and the size of a domain
+type Key = ([u8; 32], usize);
+
+/// A cache of the commitments to the Lagrange bases of SRSs,
+/// see the [module documentation](self)
+#[derive(Debug)]
+pub struct LagrangeBasisCache<G> {
+    dir: Option<PathBuf>,
+    bases: Mutex<HashMap<Key, Vec<PolyComm<G>>>>,
+}
+
+impl<G> Default for LagrangeBasisCache<G> {
+    fn default() -> Self {
+        LagrangeBasisCache {
+            dir: None,
+            bases: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<G: CommitmentCurve> LagrangeBasisCache<G>
+where
+    G::BaseField: PrimeField,
+{
+    /// Creates a cache keeping the bases in memory
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a cache keeping the bases in memory and in files of the directory `dir`
+    pub fn with_dir(dir: impl Into<PathBuf>) -> Self {
+        LagrangeBasisCache {
+            dir: Some(dir.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Adds the commitments to the Lagrange basis of a domain to an SRS
+    /// (see [SRS::add_lagrange_basis]), from the cache if it has them,
+    /// or computing them and adding them to the cache otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the computed basis cannot be written to the directory of the cache,
+    /// in which case it is still added to the SRS and kept in memory.
+    pub fn add_lagrange_basis(
+        &self,
+        srs: &mut SRS<G>,
+        domain: D<G::ScalarField>,
+    ) -> io::Result<()> {
+        let n = domain.size();
+        if srs.lagrange_bases.contains_key(&n) {
+            return Ok(());
+        }
+        let key = (srs.digest(), n);
+        if let Some(basis) = self.bases().get(&key) {
+            srs.lagrange_bases.insert(n, basis.clone());
+            return Ok(());
+        }
+
+        let path = self.path(&key);
+        let read = path.as_ref().and_then(|path| read_basis(path, n));
+        let written = match read {
+            Some(basis) => {
+                srs.lagrange_bases.insert(n, basis);
+                Ok(())
+            }
+            None => {
+                srs.add_lagrange_basis(domain);
+                match &path {
+                    Some(path) => write_basis(path, &srs.lagrange_bases[&n]),
+                    None => Ok(()),
+                }
+            }
+        };
+        self.bases().insert(key, srs.lagrange_bases[&n].clone());
+        written
+    }
+
+    // The cached bases, which stay consistent if a thread panics while holding them
+    fn bases(&self) -> MutexGuard<HashMap<Key, Vec<PolyComm<G>>>> {
+        self.bases.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // The file of a basis in the directory of the cache, if any
+    fn path(&self, (digest, n): &Key) -> Option<PathBuf> {
+        let digest: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("lagrange_{digest}_{n}.bin")))
+    }
+}
+
+// Reads the basis of a domain of size `n` from a file, if it holds one
+fn read_basis<G: CommitmentCurve>(path: &Path, n: usize) -> Option<Vec<PolyComm<G>>> {
+    let bytes = fs::read(path).ok()?;
+    let basis: Vec<PolyComm<G>> = rmp_serde::from_slice(&bytes).ok()?;
+    (basis.len() == n).then_some(basis)
+}
+
+// Writes a basis to a file, creating its directory if needed
+fn write_basis<G: CommitmentCurve>(path: &Path, basis: &[PolyComm<G>]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let bytes =
+        rmp_serde::to_vec(basis).map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+    fs::write(path, bytes)
+}
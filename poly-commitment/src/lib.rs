@@ -3,6 +3,8 @@ mod combine;
 pub mod commitment;
 pub mod error;
 pub mod evaluation_proof;
+pub mod lagrange_cache;
+pub mod msm;
 pub mod srs;
 
 #[cfg(test)]
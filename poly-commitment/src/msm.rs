@@ -0,0 +1,44 @@
+//! This module implements the backends computing the multi-scalar multiplications (MSM)
+//! of the commitments, which dominate the time spent committing to polynomials.
+//!
+//! An [SRS](crate::srs::SRS) computes its MSMs with its backend (see
+//! [SRS::set_msm_backend](crate::srs::SRS::set_msm_backend)), on the CPU by default.
+//! A backend can decline an MSM, for example if it is too small to pay the transfer
+//! of its bases and scalars to a device, or if the device is unavailable:
+//! the MSM is then computed on the CPU with the Pippenger algorithm of arkworks.
+
+use ark_ec::{msm::VariableBaseMSM, AffineCurve};
+use ark_ff::PrimeField;
+use std::fmt::Debug;
+
+/// The scalars of an MSM, in their canonical representation
+pub type Scalar<G> = <<G as AffineCurve>::ScalarField as PrimeField>::BigInt;
+
+/// A backend computing the MSMs of the commitments, see the [module documentation](self)
+pub trait MsmBackend<G: AffineCurve>: Debug + Send + Sync {
+    /// Computes the sum of the bases multiplied by the scalars, up to the shortest of the two,
+    /// or returns `None` if the backend declines the MSM
+    fn multi_scalar_mul(&self, bases: &[G], scalars: &[Scalar<G>]) -> Option<G::Projective>;
+}
+
+/// The backend computing the MSMs on the CPU, with the Pippenger algorithm of arkworks
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CpuMsm;
+
+impl<G: AffineCurve> MsmBackend<G> for CpuMsm {
+    fn multi_scalar_mul(&self, bases: &[G], scalars: &[Scalar<G>]) -> Option<G::Projective> {
+        Some(VariableBaseMSM::multi_scalar_mul(bases, scalars))
+    }
+}
+
+/// Computes the sum of the bases multiplied by the scalars, up to the shortest of the two,
+/// with a backend, or on the CPU if the backend declines the MSM
+pub fn multi_scalar_mul<G: AffineCurve>(
+    backend: &dyn MsmBackend<G>,
+    bases: &[G],
+    scalars: &[Scalar<G>],
+) -> G::Projective {
+    backend
+        .multi_scalar_mul(bases, scalars)
+        .unwrap_or_else(|| VariableBaseMSM::multi_scalar_mul(bases, scalars))
+}
@@ -1,10 +1,12 @@
 //! This module implements the Marlin structured reference string primitive
 
 use crate::commitment::CommitmentCurve;
+use crate::msm::{self, CpuMsm, MsmBackend, Scalar};
 use crate::PolyComm;
 use ark_ec::{AffineCurve, ProjectiveCurve};
 use ark_ff::{BigInteger, PrimeField, Zero};
 use ark_poly::{EvaluationDomain, Radix2EvaluationDomain as D};
+use ark_serialize::CanonicalSerialize;
 use blake2::{Blake2b512, Digest};
 use groupmap::GroupMap;
 use serde::{Deserialize, Serialize};
@@ -12,9 +14,10 @@ use serde_with::serde_as;
 use std::array;
 use std::cmp::min;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 #[serde_as]
-#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SRS<G: CommitmentCurve> {
     /// The vector of group elements for committing to polynomials in coefficient form
     #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
@@ -27,6 +30,10 @@ pub struct SRS<G: CommitmentCurve> {
     /// Commitments to Lagrange bases, per domain size
     #[serde(skip)]
     pub lagrange_bases: HashMap<usize, Vec<PolyComm<G>>>,
+
+    /// The backend computing the MSMs of the commitments, on the CPU if none
+    #[serde(skip)]
+    msm_backend: Option<Arc<dyn MsmBackend<G>>>,
 }
 
 impl<G> PartialEq for SRS<G>
@@ -38,6 +45,43 @@ where
     }
 }
 
+impl<G> Eq for SRS<G> where G: CommitmentCurve {}
+
+impl<G: CommitmentCurve> SRS<G> {
+    /// Sets the backend computing the MSMs of the commitments (see [msm]),
+    /// which does not change the commitments
+    pub fn set_msm_backend(&mut self, backend: Arc<dyn MsmBackend<G>>) {
+        self.msm_backend = Some(backend);
+    }
+
+    /// The backend computing the MSMs of the commitments, [CpuMsm] by default
+    pub fn msm_backend(&self) -> &dyn MsmBackend<G> {
+        self.msm_backend.as_deref().unwrap_or(&CpuMsm)
+    }
+
+    /// Computes the sum of the bases multiplied by the scalars with the backend of the SRS,
+    /// see [msm::multi_scalar_mul]
+    pub fn multi_scalar_mul(&self, bases: &[G], scalars: &[Scalar<G>]) -> G::Projective {
+        msm::multi_scalar_mul(self.msm_backend(), bases, scalars)
+    }
+
+    /// The digest of the group elements of the SRS, identifying it in the caches of its
+    /// Lagrange bases (see [LagrangeBasisCache](crate::lagrange_cache::LagrangeBasisCache))
+    pub fn digest(&self) -> [u8; 32] {
+        let mut hasher = Blake2b512::new();
+        let mut bytes = vec![];
+        for point in self.g.iter().chain([&self.h]) {
+            bytes.clear();
+            point
+                .serialize(&mut bytes)
+                .expect("the points are serialized in memory");
+            hasher.update(&bytes);
+        }
+        let digest = hasher.finalize();
+        array::from_fn(|i| digest[i])
+    }
+}
+
 pub fn endos<G: CommitmentCurve>() -> (G::BaseField, G::ScalarField)
 where
     G::BaseField: PrimeField,
@@ -57,6 +101,9 @@ where
     (endo_q, endo_r)
 }
 
+/// The domain separation string used to derive the blinding generator `h` of an [SRS].
+pub const SRS_MISC_PREFIX: &str = "srs_misc";
+
 fn point_of_random_bytes<G: CommitmentCurve>(map: &G::Map, random_bytes: &[u8]) -> G
 where
     G::BaseField: PrimeField,
@@ -234,7 +281,7 @@ where
         const MISC: usize = 1;
         let [h]: [G; MISC] = array::from_fn(|i| {
             let mut h = Blake2b512::new();
-            h.update("srs_misc".as_bytes());
+            h.update(SRS_MISC_PREFIX.as_bytes());
             h.update((i as u32).to_be_bytes());
             point_of_random_bytes(&m, &h.finalize())
         });
@@ -243,6 +290,7 @@ where
             g,
             h,
             lagrange_bases: HashMap::new(),
+            msm_backend: None,
         }
     }
 }
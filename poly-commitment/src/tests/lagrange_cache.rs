@@ -0,0 +1,66 @@
+use crate::{lagrange_cache::LagrangeBasisCache, srs::SRS};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use mina_curves::pasta::{Fp, Vesta};
+use std::fs;
+
+#[test]
+// Test that the cached Lagrange bases are the computed ones, for the SRS and the domain of the key
+fn test_lagrange_basis_cache() {
+    let domain = Radix2EvaluationDomain::<Fp>::new(16).unwrap();
+    let mut expected = SRS::<Vesta>::create(16);
+    expected.add_lagrange_basis(domain);
+    let cache = LagrangeBasisCache::new();
+
+    for _ in 0..2 {
+        let mut srs = SRS::<Vesta>::create(16);
+        cache.add_lagrange_basis(&mut srs, domain).unwrap();
+        assert_eq!(srs.lagrange_bases[&16], expected.lagrange_bases[&16]);
+    }
+
+    // the basis of another SRS is not taken from the cache
+    let mut other = SRS::<Vesta>::create(8);
+    cache.add_lagrange_basis(&mut other, domain).unwrap();
+    let mut other_expected = SRS::<Vesta>::create(8);
+    other_expected.add_lagrange_basis(domain);
+    assert_eq!(other.lagrange_bases[&16].len(), 16);
+    assert_eq!(
+        other.lagrange_bases[&16],
+        other_expected.lagrange_bases[&16]
+    );
+    assert_ne!(other.lagrange_bases[&16], expected.lagrange_bases[&16]);
+}
+
+#[test]
+// Test that the Lagrange bases stored in the directory of a cache are read by other caches,
+// and that the unreadable files are computed again
+fn test_lagrange_basis_cache_dir() {
+    let dir = std::env::temp_dir().join(format!("lagrange_cache_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let domain = Radix2EvaluationDomain::<Fp>::new(16).unwrap();
+    let mut expected = SRS::<Vesta>::create(16);
+    expected.add_lagrange_basis(domain);
+
+    let mut srs = SRS::<Vesta>::create(16);
+    LagrangeBasisCache::with_dir(&dir)
+        .add_lagrange_basis(&mut srs, domain)
+        .unwrap();
+    let files: Vec<_> = fs::read_dir(&dir).unwrap().collect();
+    assert_eq!(files.len(), 1);
+    let file = files[0].as_ref().unwrap().path();
+
+    let mut srs = SRS::<Vesta>::create(16);
+    LagrangeBasisCache::with_dir(&dir)
+        .add_lagrange_basis(&mut srs, domain)
+        .unwrap();
+    assert_eq!(srs.lagrange_bases[&16], expected.lagrange_bases[&16]);
+
+    fs::write(&file, b"not a basis").unwrap();
+    let mut srs = SRS::<Vesta>::create(16);
+    LagrangeBasisCache::with_dir(&dir)
+        .add_lagrange_basis(&mut srs, domain)
+        .unwrap();
+    assert_eq!(srs.lagrange_bases[&16], expected.lagrange_bases[&16]);
+    assert_ne!(fs::read(&file).unwrap(), b"not a basis");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,2 +1,4 @@
 mod batch_15_wires;
 mod commitment;
+mod lagrange_cache;
+mod msm;
@@ -0,0 +1,69 @@
+use crate::{
+    msm::{CpuMsm, MsmBackend, Scalar},
+    srs::SRS,
+};
+use ark_ec::AffineCurve;
+use ark_poly::{
+    univariate::DensePolynomial, EvaluationDomain, Evaluations, Radix2EvaluationDomain,
+    UVPolynomial,
+};
+use mina_curves::pasta::{Fp, Vesta};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+// A backend computing the MSMs of at least `min_len` bases on the CPU, counting them,
+// and declining the smaller MSMs
+#[derive(Debug, Default)]
+struct CountingMsm {
+    min_len: usize,
+    count: AtomicUsize,
+}
+
+impl MsmBackend<Vesta> for CountingMsm {
+    fn multi_scalar_mul(
+        &self,
+        bases: &[Vesta],
+        scalars: &[Scalar<Vesta>],
+    ) -> Option<<Vesta as AffineCurve>::Projective> {
+        if std::cmp::min(bases.len(), scalars.len()) < self.min_len {
+            return None;
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        CpuMsm.multi_scalar_mul(bases, scalars)
+    }
+}
+
+#[test]
+// Test that the commitments computed with a backend are the commitments computed on the CPU,
+// whether the backend computes or declines their MSMs
+fn test_msm_backend() {
+    let rng = &mut rand::thread_rng();
+    let domain = Radix2EvaluationDomain::<Fp>::new(16).unwrap();
+    let mut srs = SRS::<Vesta>::create(32);
+    srs.add_lagrange_basis(domain);
+    let plnm = DensePolynomial::<Fp>::rand(40, rng);
+    let evals =
+        Evaluations::from_vec_and_domain((0..16).map(|i| Fp::from(i as u64)).collect(), domain);
+    let commitment = srs.commit_non_hiding(&plnm, Some(45));
+    assert_eq!(commitment.unshifted.len(), 2);
+    let evals_commitment = srs.commit_evaluations_non_hiding(domain, &evals);
+
+    for (min_len, count) in [(0, 4), (100, 0)] {
+        let backend = Arc::new(CountingMsm {
+            min_len,
+            count: AtomicUsize::new(0),
+        });
+        let mut backend_srs = srs.clone();
+        backend_srs.set_msm_backend(backend.clone());
+        assert_eq!(backend_srs, srs);
+        assert_eq!(backend_srs.commit_non_hiding(&plnm, Some(45)), commitment);
+        assert_eq!(
+            backend_srs.commit_evaluations_non_hiding(domain, &evals),
+            evals_commitment
+        );
+        // the two chunks and the shifted chunk of the polynomial, and the evaluations
+        assert_eq!(backend.count.load(Ordering::Relaxed), count);
+    }
+}
@@ -0,0 +1,119 @@
+//! Constant-time helpers for field elements.
+//!
+//! Comparing field elements with `==` or branching on them leaks timing information,
+//! which matters when assembling witnesses from secrets such as keys and nonces.
+//! The helpers below only use the limbs of the canonical representation of the elements,
+//! combined with the constant-time primitives of the [subtle] crate, without branching on them.
+//!
+//! Note that the conversion from and to the canonical representation is done by the
+//! underlying arkworks field implementation, which does not give constant-time guarantees itself.
+
+use ark_ff::PrimeField;
+use subtle::{ConditionallySelectable, ConstantTimeEq, ConstantTimeGreater};
+
+pub use subtle::Choice;
+
+/// Constant-time equality, comparison and selection of field elements
+pub trait FieldConstantTime: Sized {
+    /// Returns whether `self` and `other` are equal, in constant time
+    fn ct_eq(&self, other: &Self) -> Choice;
+
+    /// Returns whether `self` is less than `other`, comparing their canonical representations in constant time
+    fn ct_lt(&self, other: &Self) -> Choice;
+
+    /// Returns whether `self` is zero, in constant time
+    fn ct_is_zero(&self) -> Choice;
+
+    /// Returns `a` if `choice` is not set and `b` if `choice` is set, in constant time
+    fn ct_select(a: &Self, b: &Self, choice: Choice) -> Self;
+}
+
+impl<F: PrimeField> FieldConstantTime for F {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let (left, right) = (self.into_repr(), other.into_repr());
+        left.as_ref().ct_eq(right.as_ref())
+    }
+
+    fn ct_lt(&self, other: &Self) -> Choice {
+        let (left, right) = (self.into_repr(), other.into_repr());
+        // compare the limbs from the most significant one, keeping the result of the first that differs
+        let mut lt = Choice::from(0);
+        let mut eq = Choice::from(1);
+        for (l, r) in left.as_ref().iter().zip(right.as_ref()).rev() {
+            lt |= eq & r.ct_gt(l);
+            eq &= l.ct_eq(r);
+        }
+        lt
+    }
+
+    fn ct_is_zero(&self) -> Choice {
+        self.ct_eq(&F::zero())
+    }
+
+    fn ct_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let (left, right) = (a.into_repr(), b.into_repr());
+        let mut repr = F::BigInt::default();
+        for ((limb, l), r) in repr
+            .as_mut()
+            .iter_mut()
+            .zip(left.as_ref())
+            .zip(right.as_ref())
+        {
+            *limb = u64::conditional_select(l, r, choice);
+        }
+        // the selected representation is the one of a field element
+        F::from_repr(repr).expect("selected representation is canonical")
+    }
+}
+
+/// Returns whether the slices of field elements `a` and `b` are equal, in constant time for slices of the same length
+pub fn ct_eq_slice<F: FieldConstantTime>(a: &[F], b: &[F]) -> Choice {
+    if a.len() != b.len() {
+        return Choice::from(0);
+    }
+    a.iter()
+        .zip(b)
+        .fold(Choice::from(1), |acc, (x, y)| acc & x.ct_eq(y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ark_ff::{One, Zero};
+    use mina_curves::pasta::Fp;
+
+    #[test]
+    fn test_ct_eq() {
+        let a = Fp::from(42u64);
+        assert!(bool::from(a.ct_eq(&Fp::from(42u64))));
+        assert!(!bool::from(a.ct_eq(&Fp::from(43u64))));
+        assert!(!bool::from(a.ct_eq(&-a)));
+        assert!(bool::from(Fp::zero().ct_is_zero()));
+        assert!(!bool::from(a.ct_is_zero()));
+
+        assert!(bool::from(ct_eq_slice(&[a, -a], &[a, -a])));
+        assert!(!bool::from(ct_eq_slice(&[a, -a], &[a, a])));
+        assert!(!bool::from(ct_eq_slice(&[a, -a], &[a])));
+    }
+
+    #[test]
+    fn test_ct_lt() {
+        let small = Fp::from(1u64 << 63);
+        let large = Fp::from(u128::MAX);
+        assert!(bool::from(small.ct_lt(&large)));
+        assert!(!bool::from(large.ct_lt(&small)));
+        assert!(!bool::from(small.ct_lt(&small)));
+        // -1 is the largest canonical representation
+        assert!(bool::from(large.ct_lt(&-Fp::one())));
+        assert!(bool::from(Fp::zero().ct_lt(&Fp::one())));
+    }
+
+    #[test]
+    fn test_ct_select() {
+        let a = Fp::from(7u64);
+        let b = -Fp::from(9u64);
+        assert_eq!(Fp::ct_select(&a, &b, Choice::from(0)), a);
+        assert_eq!(Fp::ct_select(&a, &b, Choice::from(1)), b);
+    }
+}
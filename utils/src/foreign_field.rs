@@ -83,13 +83,38 @@ impl<F: Field, const N: usize> ForeignElement<F, N> {
     /// foreign element obtained corresponds to the negated input. It first converts the
     /// input big element to a big integer modulo the foreign field modulus, and then
     /// computes the negation of the result.
+    /// Panics if the modulus does not fit in the `N` limbs.
     pub fn neg(&self, modulus: &BigUint) -> Self {
         let big = self.to_biguint();
         let ok = big % modulus;
-        let neg = modulus - ok;
+        // reducing once more maps the negation of zero to zero (instead of the modulus)
+        let neg = (modulus - ok) % modulus;
         Self::from_biguint(neg)
     }
 
+    /// Computes the sum of two foreign elements modulo the foreign field `modulus`.
+    /// The inputs do not need to be reduced, and the result always is.
+    /// Panics if the modulus does not fit in the `N` limbs.
+    pub fn add(&self, other: &Self, modulus: &BigUint) -> Self {
+        Self::from_biguint((self.to_biguint() + other.to_biguint()) % modulus)
+    }
+
+    /// Computes the difference of two foreign elements modulo the foreign field `modulus`.
+    /// The inputs do not need to be reduced, and the result always is.
+    /// Panics if the modulus does not fit in the `N` limbs.
+    pub fn sub(&self, other: &Self, modulus: &BigUint) -> Self {
+        // the subtrahend is reduced first, so that the difference never underflows
+        let other = other.to_biguint() % modulus;
+        Self::from_biguint((self.to_biguint() + modulus - other) % modulus)
+    }
+
+    /// Computes the product of two foreign elements modulo the foreign field `modulus`.
+    /// The inputs do not need to be reduced, and the result always is.
+    /// Panics if the modulus does not fit in the `N` limbs.
+    pub fn mul(&self, other: &Self, modulus: &BigUint) -> Self {
+        Self::from_biguint(self.to_biguint() * other.to_biguint() % modulus)
+    }
+
     /// Initializes a new foreign element from a set of bytes in big endian
     pub fn from_be(bytes: &[u8]) -> Self {
         Self::from_biguint(BigUint::from_bytes_be(bytes))
@@ -439,6 +464,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_modular_ops() {
+        let rng = &mut StdRng::from_seed(RNG_SEED);
+        let modulus = secp256k1_modulus();
+        let fe = |big: &BigUint| ForeignElement::<BaseField, 3>::from_biguint(big.clone());
+        for _ in 0..10 {
+            // the inputs are not necessarily reduced
+            let left = rng.gen_biguint(264);
+            let right = rng.gen_biguint(264);
+
+            let sum = fe(&left).add(&fe(&right), &modulus);
+            assert_eq!(sum.to_biguint(), (&left + &right) % &modulus);
+
+            let difference = fe(&left).sub(&fe(&right), &modulus);
+            assert_eq!(
+                (difference.to_biguint() + &right) % &modulus,
+                &left % &modulus
+            );
+            assert!(difference.to_biguint() < modulus);
+
+            let product = fe(&left).mul(&fe(&right), &modulus);
+            assert_eq!(product.to_biguint(), (&left * &right) % &modulus);
+
+            let negation = fe(&left).neg(&modulus);
+            assert_eq!(fe(&left).add(&negation, &modulus), ForeignElement::zero());
+            assert_eq!(
+                fe(&right).sub(&fe(&left), &modulus),
+                fe(&right).add(&negation, &modulus)
+            );
+        }
+
+        // edge cases
+        let zero = ForeignElement::<BaseField, 3>::zero();
+        let max = fe(&(&modulus - 1u32));
+        assert_eq!(zero.neg(&modulus), zero);
+        assert_eq!(zero.sub(&fe(&modulus), &modulus), zero);
+        assert_eq!(max.add(&fe(&BigUint::one()), &modulus), zero);
+        assert_eq!(zero.sub(&fe(&BigUint::one()), &modulus), max);
+        assert_eq!(max.mul(&max, &modulus), fe(&BigUint::one()));
+    }
+
     #[test]
     fn test_negate_modulus_safe1() {
         secp256k1_modulus().negate();
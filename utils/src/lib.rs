@@ -7,6 +7,7 @@ pub mod biguint_helpers;
 pub mod bitwise_operations;
 pub mod chunked_evaluations;
 pub mod chunked_polynomial;
+pub mod constant_time;
 pub mod dense_polynomial;
 pub mod evaluations;
 pub mod field_helpers;
@@ -18,6 +19,7 @@ pub mod serialization;
 pub use biguint_helpers::BigUintHelpers;
 pub use bitwise_operations::BitwiseOps;
 pub use chunked_evaluations::ChunkedEvaluations;
+pub use constant_time::FieldConstantTime;
 pub use dense_polynomial::ExtendedDensePolynomial;
 pub use evaluations::ExtendedEvaluations;
 pub use field_helpers::{BigUintFieldHelpers, FieldHelpers, RandomField, Two};